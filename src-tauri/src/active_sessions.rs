@@ -0,0 +1,266 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use sysinfo::{ProcessRefreshKind, ProcessesToUpdate, System, UpdateKind};
+
+use crate::app_config::AppType;
+use crate::conversation::ConversationMeta;
+
+/// 无法按工作目录对应时，最近修改于此时间内的会话视为进行中
+const RECENT_WRITE_WINDOW: Duration = Duration::from_secs(120);
+
+/// 正在运行的 CLI 进程
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveProcess {
+    pub pid: u32,
+    pub app_type: String,
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// 启动时间（秒）
+    pub started_at: i64,
+}
+
+/// 进行中的会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSession {
+    pub app_type: String,
+    pub file_path: String,
+    /// 对应的进程；仅凭最近写入判断时为空
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// 会话文件修改时间（秒）
+    pub modified_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveSessions {
+    pub processes: Vec<ActiveProcess>,
+    pub sessions: Vec<ActiveSession>,
+}
+
+impl ActiveSessions {
+    /// 会话文件是否属于进行中的会话
+    pub fn is_live(&self, file_path: &str) -> bool {
+        self.sessions.iter().any(|s| s.file_path == file_path)
+    }
+}
+
+fn file_stem(name: &str) -> String {
+    let name = name.to_lowercase();
+    name.strip_suffix(".exe")
+        .or_else(|| name.strip_suffix(".cmd"))
+        .unwrap_or(&name)
+        .to_string()
+}
+
+/// 按进程名与命令行识别 Claude Code / Codex CLI（含通过 node 启动的 npm 安装版本）
+fn classify(name: &str, cmd: &[String]) -> Option<AppType> {
+    match file_stem(name).as_str() {
+        "claude" => return Some(AppType::Claude),
+        "codex" => return Some(AppType::Codex),
+        "node" | "bun" => {}
+        _ => return None,
+    }
+    let script = cmd.get(1)?.replace('\\', "/").to_lowercase();
+    if script.contains("@anthropic-ai/claude-code") || script.ends_with("/bin/claude") {
+        Some(AppType::Claude)
+    } else if script.contains("@openai/codex") || script.ends_with("/bin/codex") {
+        Some(AppType::Codex)
+    } else {
+        None
+    }
+}
+
+/// 扫描正在运行的 Claude Code / Codex 进程
+pub fn running_processes() -> Vec<ActiveProcess> {
+    let mut system = System::new();
+    system.refresh_processes_specifics(
+        ProcessesToUpdate::All,
+        true,
+        ProcessRefreshKind::nothing()
+            .with_cmd(UpdateKind::Always)
+            .with_cwd(UpdateKind::Always),
+    );
+    let own_pid = std::process::id();
+    let mut processes: Vec<ActiveProcess> = system
+        .processes()
+        .values()
+        .filter(|p| p.pid().as_u32() != own_pid)
+        .filter_map(|process| {
+            let name = process.name().to_string_lossy().to_string();
+            let cmd: Vec<String> = process
+                .cmd()
+                .iter()
+                .map(|a| a.to_string_lossy().to_string())
+                .collect();
+            let app_type = classify(&name, &cmd)?;
+            Some(ActiveProcess {
+                pid: process.pid().as_u32(),
+                app_type: app_type.as_str().to_string(),
+                name,
+                cwd: process.cwd().map(|p| p.to_string_lossy().to_string()),
+                started_at: process.start_time() as i64,
+            })
+        })
+        .collect();
+    // npm 包装脚本会再启动一个同名子进程，仅保留最外层
+    let pids: HashSet<u32> = processes.iter().map(|p| p.pid).collect();
+    processes.retain(|p| {
+        system
+            .process(sysinfo::Pid::from_u32(p.pid))
+            .and_then(|proc| proc.parent())
+            .is_none_or(|parent| !pids.contains(&parent.as_u32()))
+    });
+    processes.sort_by_key(|p| std::cmp::Reverse(p.started_at));
+    processes
+}
+
+fn modified_secs(path: &Path) -> Option<i64> {
+    path.metadata()
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+}
+
+fn same_dir(a: &str, b: &str) -> bool {
+    let normalize = |p: &str| {
+        Path::new(p)
+            .canonicalize()
+            .unwrap_or_else(|_| PathBuf::from(p))
+    };
+    normalize(a) == normalize(b)
+}
+
+/// 检测进行中的会话：进程启动后写入、且工作目录与进程一致的会话文件视为该进程的会话；
+/// 无法读取进程工作目录时，退而将最近写入的会话视为进行中
+pub fn detect() -> ActiveSessions {
+    let processes = running_processes();
+    if processes.is_empty() {
+        return ActiveSessions::default();
+    }
+    let earliest = processes.iter().map(|p| p.started_at).min().unwrap_or(0);
+    let since = UNIX_EPOCH + Duration::from_secs(earliest.max(0) as u64);
+    let mut candidates: Vec<(String, PathBuf, i64, Option<String>)> =
+        crate::conversation::recent_session_files(since)
+            .into_iter()
+            .filter_map(|(app, path)| {
+                let modified = modified_secs(&path)?;
+                let cwd = crate::conversation::read_session_cwd(&path);
+                Some((app, path, modified, cwd))
+            })
+            .collect();
+    // 最近修改的优先分配
+    candidates.sort_by_key(|(_, _, modified, _)| std::cmp::Reverse(*modified));
+
+    let mut sessions = Vec::new();
+    let mut claimed: HashSet<PathBuf> = HashSet::new();
+    for process in &processes {
+        let Some(cwd) = process.cwd.as_deref() else {
+            continue;
+        };
+        let found = candidates
+            .iter()
+            .find(|(app, path, modified, session_cwd)| {
+                *app == process.app_type
+                    && *modified >= process.started_at
+                    && !claimed.contains(path)
+                    && session_cwd.as_deref().is_some_and(|c| same_dir(c, cwd))
+            });
+        if let Some((app, path, modified, session_cwd)) = found {
+            claimed.insert(path.clone());
+            sessions.push(ActiveSession {
+                app_type: app.clone(),
+                file_path: path.to_string_lossy().to_string(),
+                pid: Some(process.pid),
+                cwd: session_cwd.clone(),
+                modified_at: *modified,
+            });
+        }
+    }
+
+    let recent = SystemTime::now()
+        .checked_sub(RECENT_WRITE_WINDOW)
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+    for (app, path, modified, session_cwd) in &candidates {
+        let unmatched_process = processes
+            .iter()
+            .any(|p| p.app_type == *app && p.cwd.is_none());
+        if *modified >= recent && unmatched_process && !claimed.contains(path) {
+            claimed.insert(path.clone());
+            sessions.push(ActiveSession {
+                app_type: app.clone(),
+                file_path: path.to_string_lossy().to_string(),
+                pid: None,
+                cwd: session_cwd.clone(),
+                modified_at: *modified,
+            });
+        }
+    }
+
+    ActiveSessions {
+        processes,
+        sessions,
+    }
+}
+
+/// 删除前检查：会话仍在进行时拒绝，force 为 true 时跳过检查
+pub fn ensure_not_live(file_path: &str, force: bool) -> Result<(), String> {
+    if force {
+        return Ok(());
+    }
+    let active = detect();
+    let Some(session) = active.sessions.iter().find(|s| s.file_path == file_path) else {
+        return Ok(());
+    };
+    let owner = match session.pid {
+        Some(pid) => format!("（PID {}）", pid),
+        None => String::new(),
+    };
+    Err(format!(
+        "该会话正在被运行中的 {} 进程使用{}，删除可能导致会话异常；如确认删除请使用强制删除",
+        session.app_type, owner
+    ))
+}
+
+/// 切换前检查：应用有正在运行的 CLI 进程时拒绝（运行中的进程不会读取新配置），
+/// force 为 true 时跳过检查
+pub fn ensure_app_idle(app_type: &AppType, force: bool) -> Result<(), String> {
+    if force {
+        return Ok(());
+    }
+    let pids: Vec<String> = running_processes()
+        .iter()
+        .filter(|p| p.app_type == app_type.as_str())
+        .map(|p| p.pid.to_string())
+        .collect();
+    if pids.is_empty() {
+        return Ok(());
+    }
+    Err(format!(
+        "检测到 {} 个正在运行的 {} 进程（PID {}），切换后需重启这些进程才会生效；如确认切换请使用强制切换",
+        pids.len(),
+        crate::app_adapter::adapter_for(app_type).display_name(),
+        pids.join(", ")
+    ))
+}
+
+/// 在对话列表中标记进行中的会话
+pub fn mark_live<'a>(conversations: impl IntoIterator<Item = &'a mut ConversationMeta>) {
+    let active = detect();
+    if active.sessions.is_empty() {
+        return;
+    }
+    for conversation in conversations {
+        conversation.live = active.is_live(&conversation.file_path);
+    }
+}
@@ -0,0 +1,470 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value as YamlValue};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Claude Code 内置的模型别名；也可填写完整模型 ID
+const MODEL_ALIASES: &[&str] = &["sonnet", "opus", "haiku", "inherit"];
+
+/// 子代理作用域
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum AgentScope {
+    /// ~/.claude/agents
+    Global,
+    /// <项目>/.claude/agents
+    Project,
+}
+
+/// 子代理文件信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentInfo {
+    pub file_name: String,
+    pub path: String,
+    pub scope: AgentScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 未设置时继承全部工具
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 系统提示词（frontmatter 之后的正文）
+    pub body: String,
+    /// frontmatter 校验失败的原因；为 None 表示文件有效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 编辑 frontmatter 时可修改的字段；为 None 的字段保持不变
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentFrontmatterUpdate {
+    pub name: Option<String>,
+    pub description: Option<String>,
+    /// 传入空数组表示移除 tools（继承全部工具）
+    pub tools: Option<Vec<String>>,
+    /// 传入空字符串表示移除 model（使用默认模型）
+    pub model: Option<String>,
+}
+
+/// 子代理模板
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AgentTemplate {
+    pub id: String,
+    pub description: String,
+    pub tools: Vec<String>,
+    pub body: String,
+}
+
+/// 内置模板
+pub fn agent_templates() -> Vec<AgentTemplate> {
+    let template = |id: &str, description: &str, tools: &[&str], body: &str| AgentTemplate {
+        id: id.to_string(),
+        description: description.to_string(),
+        tools: tools.iter().map(|t| t.to_string()).collect(),
+        body: body.to_string(),
+    };
+    vec![
+        template(
+            "code-reviewer",
+            "Reviews recent code changes for bugs, readability and security issues. Use proactively after writing or modifying code.",
+            &["Read", "Grep", "Glob", "Bash"],
+            "You are a senior code reviewer.\n\nWhen invoked:\n1. Run `git diff` to see recent changes\n2. Focus on the modified files\n3. Report issues by priority: critical, warnings, suggestions\n\nInclude concrete examples of how to fix each issue.\n",
+        ),
+        template(
+            "debugger",
+            "Debugging specialist for errors, failing tests and unexpected behavior.",
+            &["Read", "Edit", "Bash", "Grep", "Glob"],
+            "You are an expert debugger specializing in root cause analysis.\n\nWhen invoked:\n1. Capture the error message and stack trace\n2. Identify reproduction steps\n3. Isolate the failure location\n4. Implement a minimal fix\n5. Verify the fix works\n",
+        ),
+        template(
+            "test-writer",
+            "Writes and runs tests for new or changed code.",
+            &["Read", "Write", "Edit", "Bash", "Grep", "Glob"],
+            "You write focused, maintainable tests.\n\nFollow the project's existing test layout and conventions. Run the tests you add and fix any failures before finishing.\n",
+        ),
+        template(
+            "docs-writer",
+            "Writes and updates documentation for code changes.",
+            &["Read", "Write", "Edit", "Grep", "Glob"],
+            "You are a technical writer.\n\nKeep documentation concise and accurate, match the existing tone, and update examples when behavior changes.\n",
+        ),
+        template("blank", "Describe when this agent should be used.", &[], "\n"),
+    ]
+}
+
+/// 子代理目录：全局为 ~/.claude/agents，项目为 <项目>/.claude/agents
+pub fn agents_dir(scope: AgentScope, project_path: Option<&str>) -> Result<PathBuf, String> {
+    scope_dir(scope, project_path, "agents")
+}
+
+/// Claude 配置下的子目录（agents、commands、skills）：全局为 ~/.claude/<sub>，项目为 <项目>/.claude/<sub>
+pub(crate) fn scope_dir(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    sub: &str,
+) -> Result<PathBuf, String> {
+    match scope {
+        AgentScope::Global => Ok(crate::config::get_claude_config_dir().join(sub)),
+        AgentScope::Project => {
+            let project = project_path
+                .map(str::trim)
+                .filter(|p| !p.is_empty())
+                .ok_or_else(|| "项目作用域需要指定项目目录".to_string())?;
+            let project = Path::new(project);
+            if !project.is_dir() {
+                return Err(format!("项目目录不存在: {}", project.display()));
+            }
+            Ok(project.join(".claude").join(sub))
+        }
+    }
+}
+
+/// 子代理名称：小写字母、数字与连字符
+fn validate_agent_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+        && !name.starts_with('-')
+        && !name.ends_with('-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "子代理名称只能包含小写字母、数字和连字符: {}",
+            name
+        ))
+    }
+}
+
+/// 拆分 frontmatter 与正文
+pub(crate) fn split_frontmatter(content: &str) -> Result<(&str, &str), String> {
+    let content = content.strip_prefix('\u{feff}').unwrap_or(content);
+    let rest = content
+        .strip_prefix("---\n")
+        .or_else(|| content.strip_prefix("---\r\n"))
+        .ok_or_else(|| "缺少 YAML frontmatter（文件须以 --- 开头）".to_string())?;
+    let mut offset = 0;
+    for line in rest.split_inclusive('\n') {
+        if line.trim_end() == "---" {
+            return Ok((&rest[..offset], &rest[offset + line.len()..]));
+        }
+        offset += line.len();
+    }
+    Err("frontmatter 缺少结束标记 ---".to_string())
+}
+
+pub(crate) fn parse_frontmatter(yaml: &str) -> Result<Mapping, String> {
+    if yaml.trim().is_empty() {
+        return Ok(Mapping::new());
+    }
+    match serde_yaml::from_str::<YamlValue>(yaml) {
+        Ok(YamlValue::Mapping(map)) => Ok(map),
+        Ok(_) => Err("frontmatter 必须是键值对象".to_string()),
+        Err(e) => Err(format!("frontmatter YAML 格式错误: {}", e)),
+    }
+}
+
+pub(crate) fn yaml_str<'a>(map: &'a Mapping, key: &str) -> Option<&'a str> {
+    map.get(key).and_then(|v| v.as_str())
+}
+
+/// tools 可写为逗号分隔的字符串或列表
+pub(crate) fn parse_tools(value: &YamlValue) -> Result<Vec<String>, String> {
+    let tools: Vec<String> = match value {
+        YamlValue::String(s) => s.split(',').map(|t| t.trim().to_string()).collect(),
+        YamlValue::Sequence(items) => items
+            .iter()
+            .map(|item| {
+                item.as_str()
+                    .map(|s| s.trim().to_string())
+                    .ok_or_else(|| "tools 列表只能包含字符串".to_string())
+            })
+            .collect::<Result<_, _>>()?,
+        _ => return Err("tools 必须是逗号分隔的字符串或列表".to_string()),
+    };
+    Ok(tools.into_iter().filter(|t| !t.is_empty()).collect())
+}
+
+/// 校验 frontmatter：name、description 必填；tools、model 格式正确
+fn validate_frontmatter(map: &Mapping) -> Result<(), String> {
+    let name = yaml_str(map, "name").ok_or_else(|| "frontmatter 缺少 name".to_string())?;
+    validate_agent_name(name)?;
+    if yaml_str(map, "description").is_none_or(|d| d.trim().is_empty()) {
+        return Err("frontmatter 缺少 description".to_string());
+    }
+    if let Some(tools) = map.get("tools") {
+        parse_tools(tools)?;
+    }
+    if let Some(model) = map.get("model") {
+        validate_model(model)?;
+    }
+    Ok(())
+}
+
+/// model 须为模型别名或完整模型 ID
+pub(crate) fn validate_model(model: &YamlValue) -> Result<(), String> {
+    let model = model
+        .as_str()
+        .ok_or_else(|| "model 必须为字符串".to_string())?;
+    if !MODEL_ALIASES.contains(&model) && !model.starts_with("claude-") {
+        return Err(format!(
+            "未知的 model: {}（可用 {} 或完整模型 ID）",
+            model,
+            MODEL_ALIASES.join(" / ")
+        ));
+    }
+    Ok(())
+}
+
+/// 校验子代理文件内容（frontmatter 必须有效）
+pub fn validate_agent_content(content: &str) -> Result<(), String> {
+    let (yaml, _) = split_frontmatter(content)?;
+    validate_frontmatter(&parse_frontmatter(yaml)?)
+}
+
+pub(crate) fn render_with_frontmatter(frontmatter: &Mapping, body: &str) -> Result<String, String> {
+    let yaml = serde_yaml::to_string(frontmatter)
+        .map_err(|e| format!("序列化 frontmatter 失败: {}", e))?;
+    let body = body.trim_start_matches(['\r', '\n']);
+    Ok(format!("---\n{}---\n\n{}", yaml, body))
+}
+
+fn agent_info(path: &Path, scope: AgentScope) -> AgentInfo {
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut info = AgentInfo {
+        file_name,
+        path: path.to_string_lossy().to_string(),
+        scope,
+        name: None,
+        description: None,
+        tools: None,
+        model: None,
+        body: String::new(),
+        error: None,
+    };
+    let content = match fs::read_to_string(path) {
+        Ok(content) => content,
+        Err(e) => {
+            info.error = Some(format!("读取子代理失败: {}", e));
+            return info;
+        }
+    };
+    let (yaml, body) = match split_frontmatter(&content) {
+        Ok(parts) => parts,
+        Err(e) => {
+            info.body = content.clone();
+            info.error = Some(e);
+            return info;
+        }
+    };
+    info.body = body.trim_start_matches(['\r', '\n']).to_string();
+    let map = match parse_frontmatter(yaml) {
+        Ok(map) => map,
+        Err(e) => {
+            info.error = Some(e);
+            return info;
+        }
+    };
+    info.name = yaml_str(&map, "name").map(|s| s.to_string());
+    info.description = yaml_str(&map, "description").map(|s| s.to_string());
+    info.tools = map.get("tools").and_then(|t| parse_tools(t).ok());
+    info.model = yaml_str(&map, "model").map(|s| s.to_string());
+    info.error = validate_frontmatter(&map).err();
+    info
+}
+
+fn agent_path(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+) -> Result<PathBuf, String> {
+    crate::global_rules::validate_rule_filename(file_name)?;
+    Ok(agents_dir(scope, project_path)?.join(file_name.trim()))
+}
+
+/// 列出子代理：全局，以及指定项目时的项目作用域
+pub fn list_agents(project_path: Option<&str>) -> Result<Vec<AgentInfo>, String> {
+    let mut scopes = vec![AgentScope::Global];
+    if project_path.is_some_and(|p| !p.trim().is_empty()) {
+        scopes.push(AgentScope::Project);
+    }
+    let mut agents = Vec::new();
+    for scope in scopes {
+        let dir = agents_dir(scope, project_path)?;
+        let Ok(entries) = fs::read_dir(&dir) else {
+            continue;
+        };
+        let mut paths: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().and_then(|e| e.to_str()) == Some("md"))
+            .collect();
+        paths.sort();
+        agents.extend(paths.iter().map(|p| agent_info(p, scope)));
+    }
+    Ok(agents)
+}
+
+/// 读取单个子代理
+pub fn read_agent(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+) -> Result<AgentInfo, String> {
+    let path = agent_path(scope, project_path, file_name)?;
+    if !path.exists() {
+        return Err(format!("子代理不存在: {}", file_name));
+    }
+    Ok(agent_info(&path, scope))
+}
+
+/// 以原始内容写入子代理（须通过 frontmatter 校验）
+pub fn write_agent(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+    content: &str,
+) -> Result<AgentInfo, String> {
+    validate_agent_content(content)?;
+    let path = agent_path(scope, project_path, file_name)?;
+    crate::config::write_text_file(&path, content)?;
+    Ok(agent_info(&path, scope))
+}
+
+/// 从模板创建子代理，文件名为 <name>.md
+pub fn create_agent(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    template_id: &str,
+    name: &str,
+    description: Option<&str>,
+) -> Result<AgentInfo, String> {
+    let name = name.trim();
+    validate_agent_name(name)?;
+    let template = agent_templates()
+        .into_iter()
+        .find(|t| t.id == template_id)
+        .ok_or_else(|| format!("子代理模板不存在: {}", template_id))?;
+    let file_name = format!("{}.md", name);
+    let path = agent_path(scope, project_path, &file_name)?;
+    if path.exists() {
+        return Err(format!("子代理已存在: {}", file_name));
+    }
+
+    let mut map = Mapping::new();
+    map.insert("name".into(), name.into());
+    let description = description
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .unwrap_or(&template.description);
+    map.insert("description".into(), description.into());
+    if !template.tools.is_empty() {
+        map.insert("tools".into(), template.tools.join(", ").into());
+    }
+    crate::config::write_text_file(&path, &render_with_frontmatter(&map, &template.body)?)?;
+    Ok(agent_info(&path, scope))
+}
+
+/// 修改 frontmatter 字段，保留正文与其他字段；name 变化且文件名与旧 name 一致时同步重命名文件
+pub fn update_agent_frontmatter(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+    update: &AgentFrontmatterUpdate,
+) -> Result<AgentInfo, String> {
+    let path = agent_path(scope, project_path, file_name)?;
+    let content = fs::read_to_string(&path).map_err(|e| format!("读取子代理失败: {}", e))?;
+    let (yaml, body) = split_frontmatter(&content)?;
+    let mut map = parse_frontmatter(yaml)?;
+    let old_name = yaml_str(&map, "name").map(|s| s.to_string());
+
+    if let Some(name) = &update.name {
+        map.insert("name".into(), name.trim().into());
+    }
+    if let Some(description) = &update.description {
+        map.insert("description".into(), description.trim().into());
+    }
+    if let Some(tools) = &update.tools {
+        let tools: Vec<&str> = tools
+            .iter()
+            .map(|t| t.trim())
+            .filter(|t| !t.is_empty())
+            .collect();
+        if tools.is_empty() {
+            map.remove("tools");
+        } else {
+            map.insert("tools".into(), tools.join(", ").into());
+        }
+    }
+    if let Some(model) = &update.model {
+        if model.trim().is_empty() {
+            map.remove("model");
+        } else {
+            map.insert("model".into(), model.trim().into());
+        }
+    }
+    validate_frontmatter(&map)?;
+
+    let rendered = render_with_frontmatter(&map, body)?;
+    let new_name = yaml_str(&map, "name").unwrap_or_default();
+    let renamed =
+        old_name.is_some_and(|old| old != new_name && file_name.trim() == format!("{}.md", old));
+    let target = if renamed {
+        let target = agent_path(scope, project_path, &format!("{}.md", new_name))?;
+        if target.exists() {
+            return Err(format!("子代理已存在: {}.md", new_name));
+        }
+        target
+    } else {
+        path.clone()
+    };
+    crate::config::write_text_file(&target, &rendered)?;
+    if renamed {
+        crate::config::delete_file(&path)?;
+    }
+    Ok(agent_info(&target, scope))
+}
+
+/// 删除子代理
+pub fn delete_agent(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+) -> Result<(), String> {
+    let path = agent_path(scope, project_path, file_name)?;
+    if !path.exists() {
+        return Err(format!("子代理不存在: {}", file_name));
+    }
+    crate::config::delete_file(&path).map_err(Into::into)
+}
+
+/// 在全局与项目作用域之间复制子代理
+pub fn copy_agent(
+    from: AgentScope,
+    to: AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+    overwrite: bool,
+) -> Result<AgentInfo, String> {
+    if from == to {
+        return Err("源作用域与目标作用域相同".to_string());
+    }
+    let source = agent_path(from, project_path, file_name)?;
+    let content = fs::read_to_string(&source).map_err(|e| format!("读取子代理失败: {}", e))?;
+    let target = agent_path(to, project_path, file_name)?;
+    if target.exists() && !overwrite {
+        return Err(format!("目标作用域已存在同名子代理: {}", file_name));
+    }
+    crate::config::write_text_file(&target, &content)?;
+    Ok(agent_info(&target, to))
+}
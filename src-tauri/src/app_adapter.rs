@@ -0,0 +1,773 @@
+use serde_json::{json, Map, Value};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use crate::app_config::AppType;
+use crate::config::{read_json_file, write_json_file};
+use crate::conversation::{ConversationMeta, ReadingBlock};
+
+/// 应用接入适配器：描述一个 CLI 工具的配置路径、live 配置读写、对话记录与全局规则。
+/// 新工具实现本 trait 并在 `adapter_for` 中注册后，即可复用供应商存储、切换、撤销、密钥库、
+/// MCP 同步与对话浏览；可选能力（规则、对话记录）提供默认的空实现
+pub trait AppAdapter: Sync {
+    /// 对应的应用类型
+    fn app_type(&self) -> AppType;
+
+    /// 显示名称（托盘、日志）
+    fn display_name(&self) -> &'static str;
+
+    /// 配置目录
+    fn config_dir(&self) -> PathBuf;
+
+    /// 切换供应商时会改写的 live 文件
+    fn live_paths(&self) -> Vec<PathBuf>;
+
+    /// MCP 服务器写入的文件
+    fn mcp_path(&self) -> PathBuf;
+
+    /// live 配置是否存在
+    fn live_exists(&self) -> bool {
+        self.live_paths().iter().any(|p| p.exists())
+    }
+
+    /// 读取 live 配置为供应商 settings_config 结构
+    fn read_live(&self) -> Result<Value, String>;
+
+    /// 将供应商 settings_config 写入 live 配置
+    fn write_live(&self, settings: &Value) -> Result<(), String>;
+
+    /// 写入 live 配置是否会覆盖其中的 MCP 服务器（为 true 时切换后需重新投影）
+    fn write_replaces_mcp(&self) -> bool {
+        false
+    }
+
+    /// 校验供应商 settings_config
+    fn validate(&self, settings: &Value) -> Result<(), String>;
+
+    /// 视为密钥的字段（JSON Pointer），可随配置内容变化
+    fn secret_pointers(&self, settings: &Value) -> Vec<String>;
+
+    /// 首个非空的 API Key
+    fn api_key(&self, settings: &Value) -> Option<String> {
+        self.secret_pointers(settings).iter().find_map(|p| {
+            settings
+                .pointer(p)
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        })
+    }
+
+    /// 请求端点（Base URL）
+    fn base_url(&self, settings: &Value) -> Option<String>;
+
+    /// 配置中引用的模型
+    fn models(&self, settings: &Value) -> Vec<String>;
+
+    /// 将启用的 MCP 服务器（Claude 格式）写入 live 配置
+    fn set_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), String>;
+
+    /// 全局规则文件（CLAUDE.md、GEMINI.md 等）；不支持时为 None
+    fn rules_path(&self) -> Option<PathBuf> {
+        None
+    }
+
+    /// 对话记录根目录（设置中可配置多个）；不支持时为空
+    fn conversation_roots(&self) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// 列出对话记录
+    fn list_conversations(&self) -> Result<Vec<ConversationMeta>, String> {
+        Ok(Vec::new())
+    }
+
+    /// 将会话文件内容拆分为消息条目（默认按 JSONL 每行一条，无法解析的行保留为 null 以维持行号）
+    fn conversation_items(&self, content: &str) -> Result<Vec<Value>, String> {
+        Ok(content
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap_or_default())
+            .collect())
+    }
+
+    /// 解析单条消息为 (角色, 消息块)；非对话条目返回 None
+    fn parse_conversation_item(&self, _item: &Value) -> Option<(String, Vec<ReadingBlock>)> {
+        None
+    }
+
+    /// 修改时间不早于 since 的会话文件（工作区检测用）
+    fn recent_session_files(&self, _since: SystemTime) -> Vec<PathBuf> {
+        Vec::new()
+    }
+
+    /// 会话文件是否属于本应用
+    fn owns_conversation(&self, path: &Path) -> bool {
+        // 根目录可能是符号链接，同时按解析后的真实路径比较
+        let real_path = std::fs::canonicalize(path).ok();
+        self.conversation_roots().iter().any(|dir| {
+            path.starts_with(dir)
+                || real_path.as_ref().is_some_and(|real| {
+                    std::fs::canonicalize(dir).is_ok_and(|real_dir| real.starts_with(real_dir))
+                })
+        })
+    }
+}
+
+/// 按出现顺序追加非空且未重复的模型名
+fn push_model(models: &mut Vec<String>, model: Option<&str>) {
+    if let Some(model) = model.map(str::trim).filter(|s| !s.is_empty()) {
+        if !models.iter().any(|m| m == model) {
+            models.push(model.to_string());
+        }
+    }
+}
+
+/// Claude Code：~/.claude/settings.json
+pub struct ClaudeAdapter;
+
+impl AppAdapter for ClaudeAdapter {
+    fn app_type(&self) -> AppType {
+        AppType::Claude
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Claude"
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        crate::config::get_claude_config_dir()
+    }
+
+    fn live_paths(&self) -> Vec<PathBuf> {
+        vec![crate::config::get_claude_settings_path()]
+    }
+
+    fn mcp_path(&self) -> PathBuf {
+        crate::claude_mcp::user_config_path()
+    }
+
+    fn read_live(&self) -> Result<Value, String> {
+        read_json_file(&crate::config::get_claude_settings_path()).map_err(Into::into)
+    }
+
+    fn write_live(&self, settings: &Value) -> Result<(), String> {
+        write_json_file(&crate::config::get_claude_settings_path(), settings).map_err(Into::into)
+    }
+
+    fn validate(&self, settings: &Value) -> Result<(), String> {
+        if !settings.is_object() {
+            return Err("Claude 配置必须是 JSON 对象".to_string());
+        }
+        Ok(())
+    }
+
+    fn secret_pointers(&self, _settings: &Value) -> Vec<String> {
+        vec![
+            "/env/ANTHROPIC_AUTH_TOKEN".to_string(),
+            "/env/ANTHROPIC_API_KEY".to_string(),
+        ]
+    }
+
+    fn base_url(&self, settings: &Value) -> Option<String> {
+        settings
+            .pointer("/env/ANTHROPIC_BASE_URL")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
+
+    fn models(&self, settings: &Value) -> Vec<String> {
+        let mut models = Vec::new();
+        if let Some(env) = settings.get("env").and_then(|v| v.as_object()) {
+            let mut keys: Vec<&String> = env
+                .keys()
+                .filter(|k| k.starts_with("ANTHROPIC_") && k.ends_with("_MODEL"))
+                .collect();
+            keys.sort();
+            for key in keys {
+                push_model(&mut models, env.get(key).and_then(|v| v.as_str()));
+            }
+        }
+        models
+    }
+
+    fn set_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), String> {
+        crate::claude_mcp::set_mcp_servers_map(servers)
+    }
+
+    fn rules_path(&self) -> Option<PathBuf> {
+        crate::global_rules::get_claude_rules_path().ok()
+    }
+
+    fn conversation_roots(&self) -> Vec<PathBuf> {
+        crate::conversation::claude_conversation_roots()
+    }
+
+    fn list_conversations(&self) -> Result<Vec<ConversationMeta>, String> {
+        crate::conversation::list_claude_conversations()
+    }
+
+    fn parse_conversation_item(&self, item: &Value) -> Option<(String, Vec<ReadingBlock>)> {
+        crate::conversation::parse_claude_line(item)
+    }
+
+    fn recent_session_files(&self, since: SystemTime) -> Vec<PathBuf> {
+        // projects/<project>/<session>.jsonl
+        crate::conversation::claude_conversation_roots()
+            .iter()
+            .flat_map(|dir| crate::conversation::collect_session_files(dir, 1, since, "jsonl"))
+            .collect()
+    }
+}
+
+/// Codex：~/.codex/auth.json + config.toml（settings_config 为 { auth, config: TOML 文本 }）
+pub struct CodexAdapter;
+
+impl CodexAdapter {
+    /// 解析 settings_config.config（TOML 文本）
+    fn config_table(settings: &Value) -> Option<toml::Table> {
+        let text = settings.get("config").and_then(|v| v.as_str())?;
+        toml::from_str::<toml::Table>(text).ok()
+    }
+}
+
+impl AppAdapter for CodexAdapter {
+    fn app_type(&self) -> AppType {
+        AppType::Codex
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Codex"
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        crate::codex_config::get_codex_config_dir()
+    }
+
+    fn live_paths(&self) -> Vec<PathBuf> {
+        vec![
+            crate::codex_config::get_codex_auth_path(),
+            crate::codex_config::get_codex_config_path(),
+        ]
+    }
+
+    fn mcp_path(&self) -> PathBuf {
+        crate::codex_config::get_codex_config_path()
+    }
+
+    /// 只要 auth.json 存在即视为已配置；config.toml 允许缺失
+    fn live_exists(&self) -> bool {
+        crate::codex_config::get_codex_auth_path().exists()
+    }
+
+    fn read_live(&self) -> Result<Value, String> {
+        let auth: Value = read_json_file(&crate::codex_config::get_codex_auth_path())?;
+        let config_path = crate::codex_config::get_codex_config_path();
+        let config = if config_path.exists() {
+            std::fs::read_to_string(&config_path)
+                .map_err(|e| format!("读取 config.toml 失败: {}: {}", config_path.display(), e))?
+        } else {
+            String::new()
+        };
+        Ok(json!({ "auth": auth, "config": config }))
+    }
+
+    /// 双文件原子写入，失败时回滚
+    fn write_live(&self, settings: &Value) -> Result<(), String> {
+        let auth = settings
+            .get("auth")
+            .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
+        let cfg_text = settings.get("config").and_then(|v| v.as_str());
+        crate::codex_config::write_codex_live_atomic(auth, cfg_text)
+    }
+
+    /// config.toml 整体来自供应商配置，写入后需重新投影 mcp_servers
+    fn write_replaces_mcp(&self) -> bool {
+        true
+    }
+
+    fn validate(&self, settings: &Value) -> Result<(), String> {
+        let settings = settings
+            .as_object()
+            .ok_or_else(|| "Codex 配置必须是 JSON 对象".to_string())?;
+        let auth = settings
+            .get("auth")
+            .ok_or_else(|| "Codex 配置缺少 auth 字段".to_string())?;
+        if !auth.is_object() {
+            return Err("Codex auth 配置必须是 JSON 对象".to_string());
+        }
+        if let Some(config_value) = settings.get("config") {
+            if !(config_value.is_string() || config_value.is_null()) {
+                return Err("Codex config 字段必须是字符串".to_string());
+            }
+            if let Some(cfg_text) = config_value.as_str() {
+                crate::codex_config::validate_config_toml(cfg_text)?;
+            }
+        }
+        Ok(())
+    }
+
+    fn secret_pointers(&self, _settings: &Value) -> Vec<String> {
+        vec!["/auth/OPENAI_API_KEY".to_string()]
+    }
+
+    /// 当前 model_provider 的 base_url（缺省取第一个 provider，再退回顶层 base_url）
+    fn base_url(&self, settings: &Value) -> Option<String> {
+        let table = Self::config_table(settings)?;
+        let providers = table.get("model_providers").and_then(|v| v.as_table());
+        let current = table
+            .get("model_provider")
+            .and_then(|v| v.as_str())
+            .and_then(|key| providers.and_then(|p| p.get(key)));
+        current
+            .or_else(|| providers.and_then(|p| p.values().next()))
+            .and_then(|p| p.get("base_url"))
+            .or_else(|| table.get("base_url"))
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
+
+    fn models(&self, settings: &Value) -> Vec<String> {
+        let mut models = Vec::new();
+        if let Some(table) = Self::config_table(settings) {
+            push_model(&mut models, table.get("model").and_then(|v| v.as_str()));
+        }
+        models
+    }
+
+    fn set_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), String> {
+        crate::mcp::set_codex_mcp_servers(servers)
+    }
+
+    fn conversation_roots(&self) -> Vec<PathBuf> {
+        crate::conversation::codex_conversation_roots()
+    }
+
+    fn list_conversations(&self) -> Result<Vec<ConversationMeta>, String> {
+        crate::conversation::list_codex_conversations()
+    }
+
+    fn parse_conversation_item(&self, item: &Value) -> Option<(String, Vec<ReadingBlock>)> {
+        crate::conversation::parse_codex_line(item)
+    }
+
+    fn recent_session_files(&self, since: SystemTime) -> Vec<PathBuf> {
+        // sessions/<year>/<month>/<day>/<session>.jsonl
+        crate::conversation::codex_conversation_roots()
+            .iter()
+            .flat_map(|dir| crate::conversation::collect_session_files(dir, 3, since, "jsonl"))
+            .collect()
+    }
+}
+
+/// Gemini CLI：~/.gemini/.env + settings.json（settings_config 为 { env, config }）
+pub struct GeminiAdapter;
+
+impl AppAdapter for GeminiAdapter {
+    fn app_type(&self) -> AppType {
+        AppType::Gemini
+    }
+
+    fn display_name(&self) -> &'static str {
+        "Gemini"
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        crate::gemini_config::get_gemini_config_dir()
+    }
+
+    fn live_paths(&self) -> Vec<PathBuf> {
+        vec![
+            crate::gemini_config::get_gemini_env_path(),
+            crate::gemini_config::get_gemini_settings_path(),
+        ]
+    }
+
+    fn mcp_path(&self) -> PathBuf {
+        crate::gemini_config::get_gemini_settings_path()
+    }
+
+    fn read_live(&self) -> Result<Value, String> {
+        crate::gemini_config::read_gemini_live()
+    }
+
+    fn write_live(&self, settings: &Value) -> Result<(), String> {
+        crate::gemini_config::write_gemini_live(settings)
+    }
+
+    fn validate(&self, settings: &Value) -> Result<(), String> {
+        let settings = settings
+            .as_object()
+            .ok_or_else(|| "Gemini 配置必须是 JSON 对象".to_string())?;
+        if settings.get("env").is_some_and(|v| !v.is_object()) {
+            return Err("Gemini env 配置必须是 JSON 对象".to_string());
+        }
+        if settings
+            .get("config")
+            .is_some_and(|v| !(v.is_object() || v.is_null()))
+        {
+            return Err("Gemini config 字段必须是 JSON 对象".to_string());
+        }
+        Ok(())
+    }
+
+    fn secret_pointers(&self, _settings: &Value) -> Vec<String> {
+        vec!["/env/GEMINI_API_KEY".to_string()]
+    }
+
+    fn base_url(&self, settings: &Value) -> Option<String> {
+        settings
+            .pointer("/env/GOOGLE_GEMINI_BASE_URL")
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    }
+
+    fn models(&self, settings: &Value) -> Vec<String> {
+        let mut models = Vec::new();
+        for pointer in ["/env/GEMINI_MODEL", "/config/model/name"] {
+            push_model(
+                &mut models,
+                settings.pointer(pointer).and_then(|v| v.as_str()),
+            );
+        }
+        models
+    }
+
+    fn set_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), String> {
+        crate::gemini_config::set_mcp_servers_map(servers)
+    }
+
+    fn rules_path(&self) -> Option<PathBuf> {
+        Some(crate::gemini_config::get_gemini_rules_path())
+    }
+
+    fn conversation_roots(&self) -> Vec<PathBuf> {
+        crate::conversation::gemini_conversation_roots()
+    }
+
+    fn list_conversations(&self) -> Result<Vec<ConversationMeta>, String> {
+        crate::conversation::list_gemini_conversations()
+    }
+
+    /// 会话文件为单个 JSON：{ sessionId, messages: [...] }
+    fn conversation_items(&self, content: &str) -> Result<Vec<Value>, String> {
+        let value: Value =
+            serde_json::from_str(content).map_err(|e| format!("解析 Gemini 会话失败: {}", e))?;
+        Ok(value
+            .get("messages")
+            .and_then(|m| m.as_array())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    fn parse_conversation_item(&self, item: &Value) -> Option<(String, Vec<ReadingBlock>)> {
+        crate::conversation::parse_gemini_message(item)
+    }
+
+    fn recent_session_files(&self, since: SystemTime) -> Vec<PathBuf> {
+        // tmp/<project_hash>/chats/session-*.json
+        crate::conversation::gemini_conversation_roots()
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flat_map(|entries| entries.flatten())
+            .flat_map(|entry| {
+                crate::conversation::collect_session_files(
+                    &entry.path().join("chats"),
+                    0,
+                    since,
+                    "json",
+                )
+            })
+            .collect()
+    }
+}
+
+/// MCP 服务器定义的目标格式
+enum McpStyle {
+    /// { type: local, command: [cmd, ...args], environment } / { type: remote, url, headers }
+    OpenCode,
+    /// { type: stdio, command, args, env } / { type: http|sse, url, headers }
+    Crush,
+}
+
+/// 以单个 JSON 配置文件承载供应商的工具（OpenCode、Crush 等）。
+/// 供应商 settings_config 即该文件中由 cc-switch 管理的顶层字段，切换时仅替换这些字段，其余保持不变
+pub struct JsonConfigAdapter {
+    app: AppType,
+    name: &'static str,
+    dir_name: &'static str,
+    file_name: &'static str,
+    /// 供应商定义所在字段（键为工具内的供应商 ID）
+    providers_key: &'static str,
+    /// 切换时整体替换的顶层字段
+    managed_keys: &'static [&'static str],
+    /// 相对单个供应商定义的 Base URL 字段
+    base_url_field: &'static str,
+    /// 相对单个供应商定义的 API Key 字段
+    api_key_field: &'static str,
+    /// 当前所选模型字段
+    model_pointers: &'static [&'static str],
+    mcp_key: &'static str,
+    mcp_style: McpStyle,
+    /// 配置目录下的全局规则文件
+    rules_file: Option<&'static str>,
+}
+
+/// OpenCode：~/.config/opencode/opencode.json
+pub static OPENCODE: JsonConfigAdapter = JsonConfigAdapter {
+    app: AppType::OpenCode,
+    name: "OpenCode",
+    dir_name: "opencode",
+    file_name: "opencode.json",
+    providers_key: "provider",
+    managed_keys: &["provider", "model", "small_model"],
+    base_url_field: "/options/baseURL",
+    api_key_field: "/options/apiKey",
+    model_pointers: &["/model", "/small_model"],
+    mcp_key: "mcp",
+    mcp_style: McpStyle::OpenCode,
+    rules_file: Some("AGENTS.md"),
+};
+
+/// Crush：~/.config/crush/crush.json
+pub static CRUSH: JsonConfigAdapter = JsonConfigAdapter {
+    app: AppType::Crush,
+    name: "Crush",
+    dir_name: "crush",
+    file_name: "crush.json",
+    providers_key: "providers",
+    managed_keys: &["providers", "models"],
+    base_url_field: "/base_url",
+    api_key_field: "/api_key",
+    model_pointers: &["/models/large/model", "/models/small/model"],
+    mcp_key: "mcp",
+    mcp_style: McpStyle::Crush,
+    rules_file: None,
+};
+
+/// 获取应用对应的适配器
+pub fn adapter_for(app: &AppType) -> &'static dyn AppAdapter {
+    match app {
+        AppType::Claude => &ClaudeAdapter,
+        AppType::Codex => &CodexAdapter,
+        AppType::Gemini => &GeminiAdapter,
+        AppType::OpenCode => &OPENCODE,
+        AppType::Crush => &CRUSH,
+    }
+}
+
+/// 全部适配器（顺序同 `AppType::all`）
+pub fn all_adapters() -> Vec<&'static dyn AppAdapter> {
+    AppType::all().iter().map(adapter_for).collect()
+}
+
+/// 按应用名查找适配器（未知名称返回 None，不回退到 Claude）
+pub fn adapter_by_name(name: &str) -> Option<&'static dyn AppAdapter> {
+    all_adapters()
+        .into_iter()
+        .find(|adapter| adapter.app_type().as_str() == name)
+}
+
+/// XDG 配置根目录（OpenCode / Crush 在各平台均遵循 ~/.config）
+fn xdg_config_home() -> PathBuf {
+    std::env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .filter(|p| p.is_absolute())
+        .unwrap_or_else(|| {
+            dirs::home_dir()
+                .expect("无法获取用户主目录")
+                .join(".config")
+        })
+}
+
+/// JSON Pointer 单段转义
+fn escape_pointer_token(token: &str) -> String {
+    token.replace('~', "~0").replace('/', "~1")
+}
+
+impl JsonConfigAdapter {
+    fn config_path(&self) -> PathBuf {
+        self.config_dir().join(self.file_name)
+    }
+
+    fn read_root(&self) -> Result<Map<String, Value>, String> {
+        let path = self.config_path();
+        if !path.exists() {
+            return Ok(Map::new());
+        }
+        let root: Value = read_json_file(&path)?;
+        match root {
+            Value::Object(obj) => Ok(obj),
+            _ => Err(format!("{} 根必须是 JSON 对象", path.display())),
+        }
+    }
+
+    fn provider_entries<'a>(&self, settings: &'a Value) -> Vec<(&'a String, &'a Value)> {
+        settings
+            .get(self.providers_key)
+            .and_then(|v| v.as_object())
+            .map(|obj| obj.iter().collect())
+            .unwrap_or_default()
+    }
+
+    fn convert_mcp_server(&self, spec: &Value) -> Value {
+        let kind = spec.get("type").and_then(|v| v.as_str()).unwrap_or("stdio");
+        let field = |key: &str| spec.get(key).cloned().unwrap_or(Value::Null);
+        let mut out = match (&self.mcp_style, kind) {
+            (McpStyle::OpenCode, "http" | "sse") => json!({
+                "type": "remote",
+                "url": field("url"),
+                "headers": field("headers"),
+                "enabled": true,
+            }),
+            (McpStyle::OpenCode, _) => {
+                let mut command = vec![field("command")];
+                if let Some(args) = spec.get("args").and_then(|v| v.as_array()) {
+                    command.extend(args.iter().cloned());
+                }
+                json!({
+                    "type": "local",
+                    "command": command,
+                    "environment": field("env"),
+                    "enabled": true,
+                })
+            }
+            (McpStyle::Crush, "http" | "sse") => json!({
+                "type": kind,
+                "url": field("url"),
+                "headers": field("headers"),
+            }),
+            (McpStyle::Crush, _) => json!({
+                "type": "stdio",
+                "command": field("command"),
+                "args": field("args"),
+                "env": field("env"),
+            }),
+        };
+        if let Some(obj) = out.as_object_mut() {
+            obj.retain(|_, v| !v.is_null());
+        }
+        out
+    }
+}
+
+impl AppAdapter for JsonConfigAdapter {
+    fn app_type(&self) -> AppType {
+        self.app.clone()
+    }
+
+    fn display_name(&self) -> &'static str {
+        self.name
+    }
+
+    fn config_dir(&self) -> PathBuf {
+        xdg_config_home().join(self.dir_name)
+    }
+
+    fn live_paths(&self) -> Vec<PathBuf> {
+        vec![self.config_path()]
+    }
+
+    fn mcp_path(&self) -> PathBuf {
+        self.config_path()
+    }
+
+    fn read_live(&self) -> Result<Value, String> {
+        let root = self.read_root()?;
+        let managed: Map<String, Value> = root
+            .into_iter()
+            .filter(|(k, _)| self.managed_keys.contains(&k.as_str()))
+            .collect();
+        Ok(Value::Object(managed))
+    }
+
+    fn write_live(&self, settings: &Value) -> Result<(), String> {
+        let incoming = settings
+            .as_object()
+            .ok_or_else(|| format!("{} 配置必须是 JSON 对象", self.name))?;
+        let path = self.config_path();
+        let _lock = crate::file_lock::lock_file(&path)?;
+        let mut root = self.read_root()?;
+        root.retain(|k, _| !self.managed_keys.contains(&k.as_str()));
+        for key in self.managed_keys {
+            if let Some(value) = incoming.get(*key).filter(|v| !v.is_null()) {
+                root.insert(key.to_string(), value.clone());
+            }
+        }
+        write_json_file(&path, &Value::Object(root)).map_err(Into::into)
+    }
+
+    fn validate(&self, settings: &Value) -> Result<(), String> {
+        let obj = settings
+            .as_object()
+            .ok_or_else(|| format!("{} 配置必须是 JSON 对象", self.name))?;
+        let providers = obj
+            .get(self.providers_key)
+            .and_then(|v| v.as_object())
+            .ok_or_else(|| format!("{} 配置缺少 {} 对象", self.name, self.providers_key))?;
+        if providers.is_empty() {
+            return Err(format!(
+                "{} 配置的 {} 不能为空",
+                self.name, self.providers_key
+            ));
+        }
+        for (id, entry) in providers {
+            if !entry.is_object() {
+                return Err(format!("{} 供应商 '{}' 必须是 JSON 对象", self.name, id));
+            }
+        }
+        Ok(())
+    }
+
+    fn secret_pointers(&self, settings: &Value) -> Vec<String> {
+        self.provider_entries(settings)
+            .into_iter()
+            .map(|(id, _)| {
+                format!(
+                    "/{}/{}{}",
+                    self.providers_key,
+                    escape_pointer_token(id),
+                    self.api_key_field
+                )
+            })
+            .collect()
+    }
+
+    fn base_url(&self, settings: &Value) -> Option<String> {
+        self.provider_entries(settings)
+            .into_iter()
+            .find_map(|(_, entry)| entry.pointer(self.base_url_field))
+            .and_then(|v| v.as_str())
+            .map(|s| s.trim().trim_end_matches('/').to_string())
+            .filter(|s| !s.is_empty())
+    }
+
+    fn models(&self, settings: &Value) -> Vec<String> {
+        let mut models = Vec::new();
+        for pointer in self.model_pointers {
+            push_model(
+                &mut models,
+                settings.pointer(pointer).and_then(|v| v.as_str()),
+            );
+        }
+        models
+    }
+
+    fn set_mcp_servers(&self, servers: &HashMap<String, Value>) -> Result<(), String> {
+        let path = self.config_path();
+        let _lock = crate::file_lock::lock_file(&path)?;
+        let mut root = self.read_root()?;
+        let servers = crate::claude_mcp::build_mcp_servers_object(servers)?;
+        let converted: Map<String, Value> = servers
+            .iter()
+            .map(|(id, spec)| (id.clone(), self.convert_mcp_server(spec)))
+            .collect();
+        root.insert(self.mcp_key.to_string(), Value::Object(converted));
+        write_json_file(&path, &Value::Object(root)).map_err(Into::into)
+    }
+
+    fn rules_path(&self) -> Option<PathBuf> {
+        self.rules_file.map(|file| self.config_dir().join(file))
+    }
+}
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 
-/// MCP 配置：单客户端维度（claude 或 codex 下的一组服务器）
+/// MCP 配置：单客户端维度（claude、codex、gemini、opencode 或 crush 下的一组服务器）
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct McpConfig {
     /// 以 id 为键的服务器定义（宽松 JSON 对象，包含 enabled/source 等 UI 辅助字段）
@@ -16,6 +16,12 @@ pub struct McpRoot {
     pub claude: McpConfig,
     #[serde(default)]
     pub codex: McpConfig,
+    #[serde(default)]
+    pub gemini: McpConfig,
+    #[serde(default)]
+    pub opencode: McpConfig,
+    #[serde(default)]
+    pub crush: McpConfig,
 }
 
 use crate::config::{copy_file, get_app_config_dir, get_app_config_path, write_json_file};
@@ -27,6 +33,9 @@ use crate::provider::ProviderManager;
 pub enum AppType {
     Claude,
     Codex,
+    Gemini,
+    OpenCode,
+    Crush,
 }
 
 impl AppType {
@@ -34,14 +43,31 @@ impl AppType {
         match self {
             AppType::Claude => "claude",
             AppType::Codex => "codex",
+            AppType::Gemini => "gemini",
+            AppType::OpenCode => "opencode",
+            AppType::Crush => "crush",
         }
     }
+
+    /// 全部应用类型（按托盘与界面展示顺序）
+    pub fn all() -> [AppType; 5] {
+        [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::OpenCode,
+            AppType::Crush,
+        ]
+    }
 }
 
 impl From<&str> for AppType {
     fn from(s: &str) -> Self {
         match s.to_lowercase().as_str() {
             "codex" => AppType::Codex,
+            "gemini" => AppType::Gemini,
+            "opencode" => AppType::OpenCode,
+            "crush" => AppType::Crush,
             _ => AppType::Claude, // 默认为 Claude
         }
     }
@@ -52,7 +78,7 @@ impl From<&str> for AppType {
 pub struct MultiAppConfig {
     #[serde(default = "default_version")]
     pub version: u32,
-    /// 应用管理器（claude/codex）
+    /// 应用管理器（claude/codex/gemini/opencode/crush）
     #[serde(flatten)]
     pub apps: HashMap<String, ProviderManager>,
     /// MCP 配置（按客户端分治）
@@ -67,8 +93,9 @@ fn default_version() -> u32 {
 impl Default for MultiAppConfig {
     fn default() -> Self {
         let mut apps = HashMap::new();
-        apps.insert("claude".to_string(), ProviderManager::default());
-        apps.insert("codex".to_string(), ProviderManager::default());
+        for app in AppType::all() {
+            apps.insert(app.as_str().to_string(), ProviderManager::default());
+        }
 
         Self {
             version: 2,
@@ -100,6 +127,9 @@ impl MultiAppConfig {
             let mut apps = HashMap::new();
             apps.insert("claude".to_string(), v1_config);
             apps.insert("codex".to_string(), ProviderManager::default());
+            apps.insert("gemini".to_string(), ProviderManager::default());
+            apps.insert("opencode".to_string(), ProviderManager::default());
+            apps.insert("crush".to_string(), ProviderManager::default());
 
             let config = Self {
                 version: 2,
@@ -133,8 +163,22 @@ impl MultiAppConfig {
         serde_json::from_str::<Self>(&content).map_err(|e| format!("解析配置文件失败: {}", e))
     }
 
+    /// 校验 config.json 文本可被 load 解析（v1 或 v2 格式）
+    pub fn validate_text(content: &str) -> Result<(), String> {
+        if serde_json::from_str::<ProviderManager>(content).is_ok() {
+            return Ok(());
+        }
+        serde_json::from_str::<Self>(content)
+            .map(|_| ())
+            .map_err(|e| format!("解析配置文件失败: {}", e))
+    }
+
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), String> {
+        // 启动自检发现 config.json 损坏时，内存中是默认配置，不能覆盖原文件
+        if crate::self_check::app_config_corrupt() {
+            return Err("config.json 已损坏，请先从备份恢复或重置后再保存".to_string());
+        }
         let config_path = get_app_config_path();
         // 先备份旧版（若存在）到 ~/.cc-switch/config.json.bak，再写入新内容
         if config_path.exists() {
@@ -171,6 +215,9 @@ impl MultiAppConfig {
         match app {
             AppType::Claude => &self.mcp.claude,
             AppType::Codex => &self.mcp.codex,
+            AppType::Gemini => &self.mcp.gemini,
+            AppType::OpenCode => &self.mcp.opencode,
+            AppType::Crush => &self.mcp.crush,
         }
     }
 
@@ -179,6 +226,9 @@ impl MultiAppConfig {
         match app {
             AppType::Claude => &mut self.mcp.claude,
             AppType::Codex => &mut self.mcp.codex,
+            AppType::Gemini => &mut self.mcp.gemini,
+            AppType::OpenCode => &mut self.mcp.opencode,
+            AppType::Crush => &mut self.mcp.crush,
         }
     }
 }
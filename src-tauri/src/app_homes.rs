@@ -0,0 +1,320 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::AppType;
+use crate::provider::{Provider, ProviderManager};
+
+/// 已登记的应用配置目录（如通过 CLAUDE_CONFIG_DIR 使用的 ~/.claude-work）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppHome {
+    /// 显示名称，同一应用内唯一
+    pub name: String,
+    /// 应用类型：claude 或 codex
+    pub app_type: String,
+    /// 配置目录（支持 ~ 前缀）
+    pub dir: String,
+}
+
+/// 配置目录状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppHomeStatus {
+    pub name: String,
+    pub dir: String,
+    pub exists: bool,
+    pub active: bool,
+}
+
+/// 某个配置目录中的规则文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HomeRuleFile {
+    pub app_type: String,
+    /// 所属配置目录名称；默认目录为 None
+    pub home: Option<String>,
+    pub name: String,
+    pub path: String,
+    pub content: String,
+}
+
+fn supports_homes(app: &AppType) -> bool {
+    matches!(app, AppType::Claude | AppType::Codex)
+}
+
+/// 未选择配置目录时使用的默认目录（设置中的覆盖目录或 ~/.claude、~/.codex）
+fn base_dir(app: &AppType) -> PathBuf {
+    let settings = crate::settings::get_settings();
+    let custom = match app {
+        AppType::Codex => settings.codex_config_dir,
+        _ => settings.claude_config_dir,
+    };
+    if let Some(custom) = custom {
+        return crate::settings::resolve_override_path(&custom);
+    }
+    let default_name = match app {
+        AppType::Codex => ".codex",
+        _ => ".claude",
+    };
+    crate::settings::get_wsl_home_dir()
+        .unwrap_or_else(|| dirs::home_dir().expect("无法获取用户主目录"))
+        .join(default_name)
+}
+
+/// 应用已登记的配置目录：(名称, 解析后的目录)
+pub fn homes_for(app: &AppType) -> Vec<(String, PathBuf)> {
+    if !supports_homes(app) {
+        return Vec::new();
+    }
+    crate::settings::get_settings()
+        .app_homes
+        .into_iter()
+        .filter(|h| h.app_type == app.as_str())
+        .map(|h| (h.name, crate::settings::resolve_override_path(&h.dir)))
+        .collect()
+}
+
+/// 默认目录与全部已登记目录：(名称, 目录)，默认目录名称为 None
+pub fn all_home_dirs(app: &AppType) -> Vec<(Option<String>, PathBuf)> {
+    let mut dirs = vec![(None, base_dir(app))];
+    dirs.extend(homes_for(app).into_iter().map(|(name, dir)| (Some(name), dir)));
+    dirs
+}
+
+/// 文件所在的已登记配置目录名称；位于默认目录或不属于任何目录时为 None
+pub fn home_label_for(app: &AppType, path: &Path) -> Option<String> {
+    let real_path = fs::canonicalize(path).ok();
+    homes_for(app)
+        .into_iter()
+        .filter(|(_, dir)| {
+            path.starts_with(dir)
+                || real_path.as_ref().is_some_and(|real| {
+                    fs::canonicalize(dir).is_ok_and(|real_dir| real.starts_with(real_dir))
+                })
+        })
+        // 目录相互嵌套时取最深的一个
+        .max_by_key(|(_, dir)| dir.components().count())
+        .map(|(name, _)| name)
+}
+
+/// 列出应用的配置目录及当前选中状态（首项为默认目录，名称为空字符串）
+pub fn list_homes(app: &AppType) -> Vec<AppHomeStatus> {
+    let active = crate::settings::get_active_home(app);
+    all_home_dirs(app)
+        .into_iter()
+        .map(|(name, dir)| AppHomeStatus {
+            active: name == active,
+            name: name.unwrap_or_default(),
+            exists: dir.is_dir(),
+            dir: dir.to_string_lossy().to_string(),
+        })
+        .collect()
+}
+
+/// 选择切换供应商时写入的配置目录；None 表示回到默认目录
+pub fn select_home(app: &AppType, name: Option<&str>) -> Result<(), String> {
+    if !supports_homes(app) {
+        return Err(format!("{} 不支持多个配置目录", app.as_str()));
+    }
+    let name = name.map(str::trim).filter(|n| !n.is_empty());
+    if let Some(name) = name {
+        if !homes_for(app).iter().any(|(n, _)| n == name) {
+            return Err(format!("配置目录不存在: {}", name));
+        }
+    }
+
+    let mut settings = crate::settings::get_settings();
+    let active = name.map(|n| n.to_string());
+    match app {
+        AppType::Codex => settings.active_codex_home = active,
+        _ => settings.active_claude_home = active,
+    }
+    crate::settings::update_settings(settings)
+}
+
+/// live 配置对应的已保存供应商：API Key 与 Base URL 都一致且只有一个供应商匹配时返回其 id
+/// （密钥库锁定、无法解析密钥的供应商不参与匹配）
+pub fn provider_for_live(
+    app: &AppType,
+    live: &Value,
+    providers: &HashMap<String, Provider>,
+) -> Option<String> {
+    let adapter = crate::app_adapter::adapter_for(app);
+    let key = adapter.api_key(live)?;
+    let base_url = adapter.base_url(live);
+    let mut matches = providers.values().filter(|provider| {
+        crate::secrets::resolve_provider(app, provider).is_ok_and(|resolved| {
+            adapter.api_key(&resolved.settings_config).as_deref() == Some(key.as_str())
+                && adapter.base_url(&resolved.settings_config) == base_url
+        })
+    });
+    let first = matches.next()?;
+    matches.next().is_none().then(|| first.id.clone())
+}
+
+/// 按 live 配置重新确定当前供应商；无法确定时清空，返回是否有变化
+pub fn update_current(app: &AppType, manager: &mut ProviderManager, live: Option<&Value>) -> bool {
+    let current = live
+        .and_then(|live| provider_for_live(app, live, &manager.providers))
+        .unwrap_or_default();
+    if manager.current == current {
+        return false;
+    }
+    log::info!(
+        "配置目录已切换，{} 当前供应商: '{}' -> '{}'",
+        app.as_str(),
+        manager.current,
+        current
+    );
+    manager.current = current;
+    true
+}
+
+/// 切换配置目录后按新目录的 live 配置重新确定当前供应商。
+/// 否则下次切换供应商时会把新目录的配置回填到旧目录对应的供应商中
+pub fn refresh_current_provider(app: &AppType, manager: &mut ProviderManager) -> bool {
+    let adapter = crate::app_adapter::adapter_for(app);
+    let live = if adapter.live_exists() {
+        adapter
+            .read_live()
+            .map_err(|e| log::warn!("读取 {} live 配置失败: {}", app.as_str(), e))
+            .ok()
+    } else {
+        None
+    };
+    update_current(app, manager, live.as_ref())
+}
+
+/// 列出全部配置目录中的规则文件（Claude 的 CLAUDE.md 与 Codex 的 rules/*.md）
+pub fn list_rules_across_homes() -> Result<Vec<HomeRuleFile>, String> {
+    let mut rules = Vec::new();
+
+    for (home, dir) in all_home_dirs(&AppType::Claude) {
+        let path = dir.join("CLAUDE.md");
+        if !path.is_file() {
+            continue;
+        }
+        let content =
+            fs::read_to_string(&path).map_err(|e| format!("读取 Claude 规则失败: {}", e))?;
+        rules.push(HomeRuleFile {
+            app_type: "claude".to_string(),
+            home,
+            name: "CLAUDE.md".to_string(),
+            path: path.to_string_lossy().to_string(),
+            content,
+        });
+    }
+
+    for (home, dir) in all_home_dirs(&AppType::Codex) {
+        let Ok(entries) = fs::read_dir(dir.join("rules")) else {
+            continue;
+        };
+        let mut files: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_file() && p.extension().and_then(|s| s.to_str()) == Some("md"))
+            .collect();
+        files.sort();
+        for path in files {
+            let name = path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .unwrap_or("")
+                .to_string();
+            let content = fs::read_to_string(&path)
+                .map_err(|e| format!("读取规则文件 {} 失败: {}", name, e))?;
+            rules.push(HomeRuleFile {
+                app_type: "codex".to_string(),
+                home: home.clone(),
+                name,
+                path: path.to_string_lossy().to_string(),
+                content,
+            });
+        }
+    }
+
+    Ok(rules)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn claude_provider(id: &str, key: &str, base_url: &str) -> Provider {
+        Provider::with_id(
+            id.to_string(),
+            id.to_string(),
+            json!({ "env": { "ANTHROPIC_AUTH_TOKEN": key, "ANTHROPIC_BASE_URL": base_url } }),
+            None,
+        )
+    }
+
+    fn manager(current: &str) -> ProviderManager {
+        let mut manager = ProviderManager::default();
+        for provider in [
+            claude_provider("work", "sk-work-0000000000", "https://work.example.com"),
+            claude_provider("personal", "sk-personal-000000", "https://api.example.com"),
+        ] {
+            manager.providers.insert(provider.id.clone(), provider);
+        }
+        manager.current = current.to_string();
+        manager
+    }
+
+    /// 模拟 switch_provider 的回填：把 live 配置写回当前供应商
+    fn backfill(manager: &mut ProviderManager, live: &Value) {
+        let current = manager.current.clone();
+        if let Some(provider) = manager.providers.get_mut(&current) {
+            provider.settings_config = live.clone();
+        }
+    }
+
+    #[test]
+    fn switch_profile_then_switch_provider_backfills_the_new_homes_provider() {
+        let mut manager = manager("work");
+        let work_before = manager.providers["work"].settings_config.clone();
+        // 切换到 personal 环境配置后，新目录中的 live 配置属于 personal
+        let mut live = manager.providers["personal"].settings_config.clone();
+        live["env"]["ANTHROPIC_MODEL"] = json!("edited-in-personal-home");
+
+        assert!(update_current(&AppType::Claude, &mut manager, Some(&live)));
+        assert_eq!(manager.current, "personal");
+
+        backfill(&mut manager, &live);
+        assert_eq!(manager.providers["work"].settings_config, work_before);
+        assert_eq!(manager.providers["personal"].settings_config, live);
+    }
+
+    #[test]
+    fn unknown_live_config_clears_current_so_nothing_is_backfilled() {
+        let mut manager = manager("work");
+        let work_before = manager.providers["work"].settings_config.clone();
+        let live = json!({ "env": { "ANTHROPIC_AUTH_TOKEN": "sk-other-00000000" } });
+
+        assert!(update_current(&AppType::Claude, &mut manager, Some(&live)));
+        assert!(manager.current.is_empty());
+
+        backfill(&mut manager, &live);
+        assert_eq!(manager.providers["work"].settings_config, work_before);
+    }
+
+    #[test]
+    fn missing_live_config_clears_current() {
+        let mut manager = manager("personal");
+        assert!(update_current(&AppType::Claude, &mut manager, None));
+        assert!(manager.current.is_empty());
+    }
+
+    #[test]
+    fn ambiguous_match_is_not_guessed() {
+        let mut providers = manager("").providers;
+        let duplicate = claude_provider("copy", "sk-work-0000000000", "https://work.example.com");
+        providers.insert(duplicate.id.clone(), duplicate);
+        let live = providers["work"].settings_config.clone();
+        assert_eq!(provider_for_live(&AppType::Claude, &live, &providers), None);
+    }
+}
@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::config::get_app_config_dir;
+
+/// 审计日志超过该大小时只保留较新的一半
+const MAX_LOG_BYTES: u64 = 4 * 1024 * 1024;
+/// 操作标签的有效期（毫秒）：标记后在此时间内写入对应文件才归入该操作
+const LABEL_TTL_MS: i64 = 60_000;
+
+/// 序列化追加 audit.jsonl
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+/// 即将被修改的文件 → (操作名, 标记时间)
+static PENDING_LABELS: Mutex<Option<HashMap<PathBuf, (String, i64)>>> = Mutex::new(None);
+
+/// 审计日志文件 ~/.cc-switch/audit.jsonl（每行一条记录，只追加）
+fn get_log_path() -> PathBuf {
+    get_app_config_dir().join("audit.jsonl")
+}
+
+/// 文件层面的动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditAction {
+    Write,
+    Delete,
+    Copy,
+}
+
+impl AuditAction {
+    fn as_str(&self) -> &'static str {
+        match self {
+            AuditAction::Write => "write",
+            AuditAction::Delete => "delete",
+            AuditAction::Copy => "copy",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOutcome {
+    Success,
+    Failure,
+}
+
+/// 单条审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditEntry {
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    /// 触发修改的操作（如 switch-provider、claude-settings）；未标记时与 action 相同
+    pub operation: String,
+    pub action: AuditAction,
+    /// 目标文件
+    pub target: String,
+    /// 复制时的来源文件
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bytes: Option<u64>,
+    pub outcome: AuditOutcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 审计日志筛选条件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditFilter {
+    #[serde(default)]
+    pub operation: Option<String>,
+    #[serde(default)]
+    pub action: Option<AuditAction>,
+    /// 目标文件路径包含该文本（不区分大小写）
+    #[serde(default)]
+    pub target: Option<String>,
+    #[serde(default)]
+    pub outcome: Option<AuditOutcome>,
+    /// 起始时间（毫秒，含）
+    #[serde(default)]
+    pub since: Option<i64>,
+    /// 结束时间（毫秒，含）
+    #[serde(default)]
+    pub until: Option<i64>,
+    #[serde(default)]
+    pub limit: Option<usize>,
+}
+
+impl AuditFilter {
+    fn matches(&self, entry: &AuditEntry) -> bool {
+        let target = self.target.as_deref().map(str::to_lowercase);
+        self.operation
+            .as_deref()
+            .is_none_or(|op| entry.operation == op)
+            && self.action.is_none_or(|a| entry.action == a)
+            && target.is_none_or(|t| entry.target.to_lowercase().contains(&t))
+            && self.outcome.is_none_or(|o| entry.outcome == o)
+            && self.since.is_none_or(|t| entry.timestamp >= t)
+            && self.until.is_none_or(|t| entry.timestamp <= t)
+    }
+}
+
+/// 标记即将被某个操作修改的文件，随后的写入 / 删除记录为该操作
+pub fn label(operation: &str, paths: &[PathBuf]) {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut guard = PENDING_LABELS.lock().unwrap_or_else(|e| e.into_inner());
+    let labels = guard.get_or_insert_with(HashMap::new);
+    labels.retain(|_, (_, at)| now - *at <= LABEL_TTL_MS);
+    for path in paths {
+        labels.insert(path.clone(), (operation.to_string(), now));
+    }
+}
+
+fn take_label(path: &Path, now: i64) -> Option<String> {
+    let mut guard = PENDING_LABELS.lock().unwrap_or_else(|e| e.into_inner());
+    let (operation, at) = guard.as_mut()?.remove(path)?;
+    (now - at <= LABEL_TTL_MS).then_some(operation)
+}
+
+/// 超过大小上限时保留较新的一半记录
+fn trim_if_needed(path: &Path) {
+    if fs::metadata(path).map(|m| m.len()).unwrap_or(0) <= MAX_LOG_BYTES {
+        return;
+    }
+    let Ok(content) = fs::read_to_string(path) else {
+        return;
+    };
+    let lines: Vec<&str> = content.lines().collect();
+    let kept = lines[lines.len() / 2..].join("\n") + "\n";
+    if let Err(e) = fs::write(path, kept) {
+        log::warn!("裁剪审计日志失败: {}", e);
+    }
+}
+
+/// 记录一次文件修改；写入失败只记录日志，不影响操作本身
+pub fn record(
+    action: AuditAction,
+    target: &Path,
+    source: Option<&Path>,
+    bytes: Option<u64>,
+    result: &Result<(), impl std::fmt::Display>,
+) {
+    let log_path = get_log_path();
+    // 审计日志自身不走 atomic_write，这里只防御性跳过
+    if target == log_path {
+        return;
+    }
+    let timestamp = chrono::Utc::now().timestamp_millis();
+    let entry = AuditEntry {
+        timestamp,
+        operation: take_label(target, timestamp).unwrap_or_else(|| action.as_str().to_string()),
+        action,
+        target: target.to_string_lossy().to_string(),
+        source: source.map(|p| p.to_string_lossy().to_string()),
+        bytes,
+        outcome: if result.is_ok() {
+            AuditOutcome::Success
+        } else {
+            AuditOutcome::Failure
+        },
+        error: result.as_ref().err().map(|e| e.to_string()),
+    };
+    let line = match serde_json::to_string(&entry) {
+        Ok(line) => line,
+        Err(e) => {
+            log::warn!("序列化审计记录失败: {}", e);
+            return;
+        }
+    };
+
+    let _guard = LOG_LOCK.lock();
+    if let Some(parent) = log_path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    trim_if_needed(&log_path);
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&log_path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = result {
+        log::warn!("写入审计日志失败: {}: {}", log_path.display(), e);
+    }
+}
+
+/// 读取审计日志（最新在前）
+pub fn get_log(filter: &AuditFilter) -> Vec<AuditEntry> {
+    let content = {
+        let _guard = LOG_LOCK.lock();
+        fs::read_to_string(get_log_path()).unwrap_or_default()
+    };
+    content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<AuditEntry>(line).ok())
+        .filter(|entry| filter.matches(entry))
+        .take(filter.limit.unwrap_or(usize::MAX))
+        .collect()
+}
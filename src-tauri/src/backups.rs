@@ -0,0 +1,775 @@
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::PathBuf;
+use std::sync::{Mutex, OnceLock};
+use std::time::Duration;
+
+use crate::config::{atomic_write, get_app_config_dir};
+
+/// 快照清单在归档中的条目名
+const MANIFEST_ENTRY: &str = "manifest.json";
+/// 未配置时默认保留的快照数量
+const DEFAULT_RETAIN: usize = 20;
+/// 未配置时默认保留的写入前快照数量（与手动 / 定时快照分开计数）
+const DEFAULT_PRE_WRITE_RETAIN: usize = 30;
+/// 定时快照调度器的检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(600);
+
+/// 快照目录 ~/.cc-switch/snapshots
+fn get_snapshots_dir() -> PathBuf {
+    get_app_config_dir().join("snapshots")
+}
+
+/// 快照清单中的文件条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotEntry {
+    /// 归档内路径，例如 "claude/settings.json"
+    pub archive_path: String,
+    /// 快照时的原始路径（仅供展示，恢复时按 archive_path 重新解析目标）
+    pub original_path: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SnapshotManifest {
+    version: u32,
+    created_at: i64,
+    reason: String,
+    entries: Vec<SnapshotEntry>,
+    /// 快照内容的摘要，内容与上一份快照相同时不再创建写入前快照
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_hash: Option<String>,
+    /// 写入前自动创建的快照，与手动 / 定时快照分开保留
+    #[serde(default)]
+    pre_write: bool,
+}
+
+/// 快照信息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotInfo {
+    pub id: String,
+    pub path: String,
+    pub created_at: i64,
+    /// 触发原因："manual" / "scheduled" / "pre-switch" / "pre-restore" 等
+    pub reason: String,
+    pub size: u64,
+    pub entries: Vec<SnapshotEntry>,
+    /// 是否为写入前自动创建的快照
+    pub pre_write: bool,
+}
+
+/// 快照恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SnapshotRestoreResult {
+    pub restored: Vec<String>,
+    /// 恢复前自动创建的快照 ID
+    pub pre_restore_snapshot: Option<String>,
+    /// 是否恢复了 cc-switch 自身的 config.json（需要重新加载内存状态）
+    pub app_config_restored: bool,
+}
+
+/// 需要纳入快照的文件：(归档路径, 实际路径)
+fn snapshot_targets() -> Result<Vec<(String, PathBuf)>, String> {
+    let mut targets = vec![
+        (
+            "claude/settings.json".to_string(),
+            crate::config::get_claude_settings_path(),
+        ),
+        (
+            "claude/CLAUDE.md".to_string(),
+            crate::global_rules::get_claude_rules_path()?,
+        ),
+        (
+            "codex/config.toml".to_string(),
+            crate::codex_config::get_codex_config_path(),
+        ),
+        (
+            "codex/auth.json".to_string(),
+            crate::codex_config::get_codex_auth_path(),
+        ),
+        (
+            "cc-switch/config.json".to_string(),
+            crate::config::get_app_config_path(),
+        ),
+    ];
+
+    let rules_dir = crate::global_rules::get_codex_rules_dir()?;
+    if let Ok(entries) = fs::read_dir(&rules_dir) {
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().and_then(|s| s.to_str()) == Some("md") {
+                if let Some(name) = path.file_name().and_then(|s| s.to_str()) {
+                    targets.push((format!("codex/rules/{}", name), path.clone()));
+                }
+            }
+        }
+    }
+
+    Ok(targets)
+}
+
+/// 将归档路径解析为恢复目标；不认识的条目返回 None，防止恢复到任意位置
+fn resolve_target(archive_path: &str) -> Result<Option<PathBuf>, String> {
+    let path = match archive_path {
+        "claude/settings.json" => crate::config::get_claude_settings_path(),
+        "claude/CLAUDE.md" => crate::global_rules::get_claude_rules_path()?,
+        "codex/config.toml" => crate::codex_config::get_codex_config_path(),
+        "codex/auth.json" => crate::codex_config::get_codex_auth_path(),
+        "cc-switch/config.json" => crate::config::get_app_config_path(),
+        other => match other.strip_prefix("codex/rules/") {
+            Some(name) if crate::global_rules::validate_rule_filename(name).is_ok() => {
+                crate::global_rules::get_codex_rules_dir()?.join(name)
+            }
+            _ => return Ok(None),
+        },
+    };
+    Ok(Some(path))
+}
+
+fn sanitize_reason(reason: &str) -> String {
+    let cleaned: String = reason
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    if cleaned.is_empty() {
+        "manual".to_string()
+    } else {
+        cleaned
+    }
+}
+
+/// 读取需要纳入快照的文件：(归档路径, 实际路径, 内容)
+fn read_targets() -> Result<Vec<(String, PathBuf, Vec<u8>)>, String> {
+    let mut files = Vec::new();
+    for (archive_path, source) in snapshot_targets()? {
+        if !source.is_file() {
+            continue;
+        }
+        let bytes =
+            fs::read(&source).map_err(|e| format!("读取文件失败: {}: {}", source.display(), e))?;
+        files.push((archive_path, source, bytes));
+    }
+    Ok(files)
+}
+
+fn content_hash(files: &[(String, PathBuf, Vec<u8>)]) -> String {
+    let mut hasher = Sha256::new();
+    for (archive_path, _, bytes) in files {
+        hasher.update(archive_path.as_bytes());
+        hasher.update((bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// 最近一份快照的内容摘要（首次使用时从快照目录读取）
+fn latest_hash() -> &'static Mutex<Option<String>> {
+    static LATEST: OnceLock<Mutex<Option<String>>> = OnceLock::new();
+    LATEST.get_or_init(|| {
+        let hash = list_snapshots()
+            .ok()
+            .and_then(|s| s.first().map(|s| PathBuf::from(&s.path)))
+            .and_then(|path| read_manifest(&path).ok())
+            .and_then(|m| m.content_hash);
+        Mutex::new(hash)
+    })
+}
+
+fn retain_for(pre_write: bool) -> usize {
+    let settings = crate::settings::get_settings();
+    if pre_write {
+        settings
+            .backup_pre_write_retain
+            .unwrap_or(DEFAULT_PRE_WRITE_RETAIN)
+    } else {
+        settings.backup_retain.unwrap_or(DEFAULT_RETAIN)
+    }
+}
+
+/// 创建快照：将当前配置文件打包为 ~/.cc-switch/snapshots/snapshot_<时间>_<原因>.zip
+pub fn create_snapshot(reason: &str) -> Result<SnapshotInfo, String> {
+    write_snapshot(reason, false, read_targets()?)
+}
+
+fn write_snapshot(
+    reason: &str,
+    pre_write: bool,
+    files: Vec<(String, PathBuf, Vec<u8>)>,
+) -> Result<SnapshotInfo, String> {
+    use zip::write::SimpleFileOptions;
+
+    let reason = sanitize_reason(reason);
+    let dir = get_snapshots_dir();
+    fs::create_dir_all(&dir).map_err(|e| format!("创建快照目录失败: {}", e))?;
+
+    let now = chrono::Local::now();
+    let mut id = format!("snapshot_{}_{}", now.format("%Y%m%d_%H%M%S"), reason);
+    let mut suffix = 1;
+    while dir.join(format!("{}.zip", id)).exists() {
+        suffix += 1;
+        id = format!(
+            "snapshot_{}_{}_{}",
+            now.format("%Y%m%d_%H%M%S"),
+            reason,
+            suffix
+        );
+    }
+    let path = dir.join(format!("{}.zip", id));
+
+    let file = fs::File::create(&path).map_err(|e| format!("创建快照失败: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+    let options = SimpleFileOptions::default();
+
+    let hash = content_hash(&files);
+    let mut manifest = SnapshotManifest {
+        version: 1,
+        created_at: now.timestamp(),
+        reason,
+        entries: Vec::new(),
+        content_hash: Some(hash.clone()),
+        pre_write,
+    };
+    for (archive_path, source, bytes) in files {
+        zip.start_file(archive_path.as_str(), options)
+            .map_err(|e| format!("写入快照失败: {}", e))?;
+        zip.write_all(&bytes)
+            .map_err(|e| format!("写入快照失败: {}", e))?;
+        manifest.entries.push(SnapshotEntry {
+            archive_path,
+            original_path: source.to_string_lossy().to_string(),
+        });
+    }
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("序列化快照清单失败: {}", e))?;
+    zip.start_file(MANIFEST_ENTRY, options)
+        .map_err(|e| format!("写入快照失败: {}", e))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("写入快照失败: {}", e))?;
+    zip.finish().map_err(|e| format!("写入快照失败: {}", e))?;
+
+    if let Ok(mut latest) = latest_hash().lock() {
+        *latest = Some(hash);
+    }
+    if let Err(e) = prune_pool(pre_write, retain_for(pre_write)) {
+        log::warn!("清理旧快照失败: {}", e);
+    }
+
+    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+    log::info!("已创建配置快照: {}", id);
+    Ok(SnapshotInfo {
+        id,
+        path: path.to_string_lossy().to_string(),
+        created_at: manifest.created_at,
+        reason: manifest.reason,
+        size,
+        entries: manifest.entries,
+        pre_write,
+    })
+}
+
+fn read_manifest(path: &std::path::Path) -> Result<SnapshotManifest, String> {
+    let file = fs::File::open(path).map_err(|e| format!("打开快照失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("解析快照失败: {}", e))?;
+    let mut entry = archive
+        .by_name(MANIFEST_ENTRY)
+        .map_err(|e| format!("快照缺少清单: {}", e))?;
+    let mut text = String::new();
+    entry
+        .read_to_string(&mut text)
+        .map_err(|e| format!("读取快照清单失败: {}", e))?;
+    serde_json::from_str(&text).map_err(|e| format!("解析快照清单失败: {}", e))
+}
+
+/// 列出所有快照（按时间倒序）
+pub fn list_snapshots() -> Result<Vec<SnapshotInfo>, String> {
+    let dir = get_snapshots_dir();
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut snapshots = Vec::new();
+    let entries = fs::read_dir(&dir).map_err(|e| format!("读取快照目录失败: {}", e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.extension().and_then(|s| s.to_str()) != Some("zip") {
+            continue;
+        }
+        let Some(id) = path
+            .file_stem()
+            .and_then(|s| s.to_str())
+            .map(|s| s.to_string())
+        else {
+            continue;
+        };
+        match read_manifest(&path) {
+            Ok(manifest) => snapshots.push(SnapshotInfo {
+                id,
+                path: path.to_string_lossy().to_string(),
+                created_at: manifest.created_at,
+                reason: manifest.reason,
+                size: entry.metadata().map(|m| m.len()).unwrap_or(0),
+                entries: manifest.entries,
+                pre_write: manifest.pre_write,
+            }),
+            Err(e) => log::warn!("跳过无效快照 {}: {}", path.display(), e),
+        }
+    }
+
+    snapshots.sort_by_key(|s| std::cmp::Reverse(s.created_at));
+    Ok(snapshots)
+}
+
+fn snapshot_path(id: &str) -> Result<PathBuf, String> {
+    if id.is_empty() || id.contains(['/', '\\']) || id.contains("..") {
+        return Err(format!("无效的快照 ID: {}", id));
+    }
+    let path = get_snapshots_dir().join(format!("{}.zip", id));
+    if !path.exists() {
+        return Err(format!("快照不存在: {}", id));
+    }
+    Ok(path)
+}
+
+/// 从快照恢复；可通过 only 仅恢复指定的归档条目。恢复前会先创建一次 "pre-restore" 快照
+pub fn restore_snapshot(
+    id: &str,
+    only: Option<&[String]>,
+) -> Result<SnapshotRestoreResult, String> {
+    let path = snapshot_path(id)?;
+
+    // 先完整读出需要恢复的条目，确认快照可用后再落盘
+    let file = fs::File::open(&path).map_err(|e| format!("打开快照失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("解析快照失败: {}", e))?;
+    let mut files: Vec<(String, PathBuf, Vec<u8>)> = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取快照失败: {}", e))?;
+        let name = entry.name().to_string();
+        if name == MANIFEST_ENTRY || only.is_some_and(|only| !only.contains(&name)) {
+            continue;
+        }
+        let Some(target) = resolve_target(&name)? else {
+            log::warn!("跳过未知的快照条目: {}", name);
+            continue;
+        };
+        let mut bytes = Vec::new();
+        entry
+            .read_to_end(&mut bytes)
+            .map_err(|e| format!("读取快照条目失败: {}: {}", name, e))?;
+        files.push((name, target, bytes));
+    }
+    if files.is_empty() {
+        return Err("快照中没有可恢复的文件".to_string());
+    }
+
+    let pre_restore_snapshot = match create_snapshot("pre-restore") {
+        Ok(info) => Some(info.id),
+        Err(e) => return Err(format!("恢复前创建快照失败: {}", e)),
+    };
+
+    let mut restored = Vec::new();
+    let mut app_config_restored = false;
+    for (name, target, bytes) in files {
+        atomic_write(&target, &bytes)?;
+        app_config_restored |= name == "cc-switch/config.json";
+        restored.push(name);
+    }
+
+    Ok(SnapshotRestoreResult {
+        restored,
+        pre_restore_snapshot,
+        app_config_restored,
+    })
+}
+
+/// 单个键的恢复动作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum KeyRestoreAction {
+    /// 写入快照中的值
+    Set,
+    /// 快照中不存在该键，已从当前配置中移除
+    Removed,
+    /// 与快照一致，无需修改
+    Unchanged,
+}
+
+/// 单个键的恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRestore {
+    /// 归档路径，例如 "claude/settings.json"
+    pub file: String,
+    pub key: String,
+    pub action: KeyRestoreAction,
+}
+
+/// 按键恢复结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRestoreResult {
+    pub keys: Vec<KeyRestore>,
+    pub pre_restore_snapshot: Option<String>,
+    /// 是否修改了 cc-switch 自身的 config.json（需要重新加载内存状态）
+    pub app_config_restored: bool,
+}
+
+/// 解析键路径：以 `.` 分隔，含 `.` 的键用双引号包裹，例如 `model_providers."my.relay".base_url`
+fn parse_key_path(key: &str) -> Result<Vec<String>, String> {
+    let mut segments = Vec::new();
+    let mut current = String::new();
+    let mut quoted = false;
+    for c in key.trim().chars() {
+        match c {
+            '"' => quoted = !quoted,
+            '.' if !quoted => segments.push(std::mem::take(&mut current)),
+            c => current.push(c),
+        }
+    }
+    segments.push(current);
+    if quoted || segments.iter().any(|s| s.is_empty()) {
+        return Err(format!("无效的键路径: {}", key));
+    }
+    Ok(segments)
+}
+
+fn json_get<'a>(root: &'a serde_json::Value, path: &[String]) -> Option<&'a serde_json::Value> {
+    path.iter()
+        .try_fold(root, |value, segment| value.get(segment.as_str()))
+}
+
+/// 设置或移除 JSON 中的键；设置时自动创建中间对象
+fn json_apply(
+    root: &mut serde_json::Value,
+    path: &[String],
+    value: Option<serde_json::Value>,
+) -> Result<(), String> {
+    let (last, parents) = path.split_last().ok_or("键路径为空")?;
+    let mut node = root;
+    for segment in parents {
+        let obj = node
+            .as_object_mut()
+            .ok_or_else(|| format!("{} 的上级不是对象", segment))?;
+        if value.is_none() && !obj.contains_key(segment) {
+            return Ok(());
+        }
+        node = obj
+            .entry(segment.clone())
+            .or_insert_with(|| serde_json::Value::Object(Default::default()));
+    }
+    let obj = node
+        .as_object_mut()
+        .ok_or_else(|| format!("{} 的上级不是对象", last))?;
+    match value {
+        Some(value) => {
+            obj.insert(last.clone(), value);
+        }
+        None => {
+            obj.remove(last);
+        }
+    }
+    Ok(())
+}
+
+fn toml_get<'a>(root: &'a toml::Table, path: &[String]) -> Option<&'a toml::Value> {
+    let (first, rest) = path.split_first()?;
+    rest.iter().try_fold(root.get(first)?, |value, segment| {
+        value.get(segment.as_str())
+    })
+}
+
+/// 将 toml::Value 转换为 toml_edit 条目，用于写入保留格式的文档
+fn toml_edit_item(value: &toml::Value) -> Result<toml_edit::Item, String> {
+    let mut wrapper = toml::Table::new();
+    wrapper.insert("value".to_string(), value.clone());
+    let text = toml::to_string(&wrapper).map_err(|e| format!("序列化 TOML 失败: {}", e))?;
+    let mut doc = text
+        .parse::<toml_edit::DocumentMut>()
+        .map_err(|e| format!("序列化 TOML 失败: {}", e))?;
+    doc.remove("value")
+        .ok_or_else(|| "序列化 TOML 失败".to_string())
+}
+
+/// 在保留格式的文档中设置或移除键；设置时自动创建中间表，
+/// 已存在的键原位替换并保留其注释，文件中的其他内容不受影响
+fn toml_apply(
+    root: &mut toml_edit::DocumentMut,
+    path: &[String],
+    value: Option<&toml::Value>,
+) -> Result<(), String> {
+    let (last, parents) = path.split_last().ok_or("键路径为空")?;
+    let mut table: &mut dyn toml_edit::TableLike = root.as_table_mut();
+    for segment in parents {
+        if value.is_none() && !table.contains_key(segment) {
+            return Ok(());
+        }
+        table = table
+            .entry(segment)
+            .or_insert_with(|| {
+                let mut created = toml_edit::Table::new();
+                created.set_implicit(true);
+                toml_edit::Item::Table(created)
+            })
+            .as_table_like_mut()
+            .ok_or_else(|| format!("{} 不是表", segment))?;
+    }
+    match value {
+        Some(value) => {
+            let mut item = toml_edit_item(value)?;
+            match table.get_mut(last) {
+                Some(existing) => {
+                    if let (Some(old), Some(new)) = (existing.as_value(), item.as_value_mut()) {
+                        *new.decor_mut() = old.decor().clone();
+                    }
+                    *existing = item;
+                }
+                None => {
+                    table.insert(last, item);
+                }
+            }
+        }
+        None => {
+            table.remove(last);
+        }
+    }
+    Ok(())
+}
+
+/// 读取快照中的单个条目；条目不存在时返回 None
+pub(crate) fn read_snapshot_entry(id: &str, archive_path: &str) -> Result<Option<String>, String> {
+    let file = fs::File::open(snapshot_path(id)?).map_err(|e| format!("打开快照失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("解析快照失败: {}", e))?;
+    let mut entry = match archive.by_name(archive_path) {
+        Ok(entry) => entry,
+        Err(zip::result::ZipError::FileNotFound) => return Ok(None),
+        Err(e) => return Err(format!("读取快照失败: {}", e)),
+    };
+    let mut text = String::new();
+    entry
+        .read_to_string(&mut text)
+        .map_err(|e| format!("读取快照条目失败: {}: {}", archive_path, e))?;
+    Ok(Some(text))
+}
+
+fn parse_json_doc(text: &str, label: &str) -> Result<serde_json::Value, String> {
+    if text.trim().is_empty() {
+        return Ok(serde_json::json!({}));
+    }
+    serde_json::from_str(text).map_err(|e| format!("解析{} JSON 失败: {}", label, e))
+}
+
+fn parse_toml_doc(text: &str, label: &str) -> Result<toml::Table, String> {
+    toml::from_str(text).map_err(|e| format!("解析{} TOML 失败: {}", label, e))
+}
+
+fn restore_action(changed: bool, present: bool) -> KeyRestoreAction {
+    match (changed, present) {
+        (false, _) => KeyRestoreAction::Unchanged,
+        (true, true) => KeyRestoreAction::Set,
+        (true, false) => KeyRestoreAction::Removed,
+    }
+}
+
+/// 恢复单个文件中的若干键；没有任何变化时不写入
+fn restore_keys_in_file(
+    id: &str,
+    archive_path: &str,
+    keys: &[&str],
+) -> Result<Vec<KeyRestore>, String> {
+    let target = resolve_target(archive_path)?
+        .ok_or_else(|| format!("不支持按键恢复的文件: {}", archive_path))?;
+    let paths = keys
+        .iter()
+        .map(|k| parse_key_path(k))
+        .collect::<Result<Vec<_>, _>>()?;
+    let backup_text = read_snapshot_entry(id, archive_path)?.unwrap_or_default();
+
+    // 读取与写回之间持有文件锁，避免与切换供应商等写入交错
+    let _lock = crate::file_lock::lock_file(&target)?;
+    let live_text = if target.exists() {
+        fs::read_to_string(&target)
+            .map_err(|e| format!("读取文件失败: {}: {}", target.display(), e))?
+    } else {
+        String::new()
+    };
+
+    let mut actions = Vec::with_capacity(paths.len());
+    let new_text = if archive_path.ends_with(".json") {
+        let backup = parse_json_doc(&backup_text, "快照中的")?;
+        let mut live = parse_json_doc(&live_text, "当前")?;
+        for path in &paths {
+            let wanted = json_get(&backup, path).cloned();
+            let changed = json_get(&live, path) != wanted.as_ref();
+            actions.push(restore_action(changed, wanted.is_some()));
+            if changed {
+                json_apply(&mut live, path, wanted)?;
+            }
+        }
+        serde_json::to_string_pretty(&live).map_err(|e| format!("序列化 JSON 失败: {}", e))?
+    } else if archive_path.ends_with(".toml") {
+        let backup = parse_toml_doc(&backup_text, "快照中的")?;
+        let live = parse_toml_doc(&live_text, "当前")?;
+        // 比较用解析后的值，写回则编辑原文，保留注释、顺序与格式
+        let mut doc = live_text
+            .parse::<toml_edit::DocumentMut>()
+            .map_err(|e| format!("解析当前 TOML 失败: {}", e))?;
+        for path in &paths {
+            let wanted = toml_get(&backup, path);
+            let changed = toml_get(&live, path) != wanted;
+            actions.push(restore_action(changed, wanted.is_some()));
+            if changed {
+                toml_apply(&mut doc, path, wanted)?;
+            }
+        }
+        doc.to_string()
+    } else {
+        return Err(format!("仅支持按键恢复 JSON / TOML 文件: {}", archive_path));
+    };
+
+    if actions.iter().any(|a| *a != KeyRestoreAction::Unchanged) {
+        atomic_write(&target, new_text.as_bytes())?;
+    }
+    Ok(keys
+        .iter()
+        .zip(actions)
+        .map(|(key, action)| KeyRestore {
+            file: archive_path.to_string(),
+            key: key.to_string(),
+            action,
+        })
+        .collect())
+}
+
+/// 从快照中按键恢复，文件中的其他内容保持不变。
+/// keys 形如 `claude/settings.json:env.ANTHROPIC_BASE_URL`、`codex/config.toml:model`；
+/// 快照中不存在的键会从当前配置中移除。恢复前会先创建一次 "pre-restore" 快照
+pub fn restore_config_keys(id: &str, keys: &[String]) -> Result<KeyRestoreResult, String> {
+    // 按文件分组，保持首次出现的顺序
+    let mut groups: Vec<(&str, Vec<&str>)> = Vec::new();
+    for key_ref in keys {
+        let (file, key) = key_ref
+            .split_once(':')
+            .filter(|(file, key)| !file.is_empty() && !key.is_empty())
+            .ok_or_else(|| format!("键格式应为 <文件>:<键路径>: {}", key_ref))?;
+        match groups.iter_mut().find(|(f, _)| *f == file) {
+            Some((_, keys)) => keys.push(key),
+            None => groups.push((file, vec![key])),
+        }
+    }
+    if groups.is_empty() {
+        return Err("未指定需要恢复的键".to_string());
+    }
+    snapshot_path(id)?;
+
+    let pre_restore_snapshot = match create_snapshot("pre-restore") {
+        Ok(info) => Some(info.id),
+        Err(e) => return Err(format!("恢复前创建快照失败: {}", e)),
+    };
+
+    let mut restored = Vec::new();
+    let mut app_config_restored = false;
+    for (file, keys) in groups {
+        let results = restore_keys_in_file(id, file, &keys)?;
+        app_config_restored |= file == "cc-switch/config.json"
+            && results
+                .iter()
+                .any(|r| r.action != KeyRestoreAction::Unchanged);
+        restored.extend(results);
+    }
+
+    Ok(KeyRestoreResult {
+        keys: restored,
+        pre_restore_snapshot,
+        app_config_restored,
+    })
+}
+
+/// 删除多余的旧快照：手动 / 定时快照与写入前快照各自仅保留最近 keep 份，返回删除数量
+pub fn prune_snapshots(keep: usize) -> Result<usize, String> {
+    Ok(prune_pool(false, keep)? + prune_pool(true, keep)?)
+}
+
+fn prune_pool(pre_write: bool, keep: usize) -> Result<usize, String> {
+    let snapshots = list_snapshots()?;
+    let mut removed = 0;
+    for snapshot in snapshots
+        .into_iter()
+        .filter(|s| s.pre_write == pre_write)
+        .skip(keep)
+    {
+        match fs::remove_file(&snapshot.path) {
+            Ok(()) => removed += 1,
+            Err(e) => log::warn!("删除快照失败 {}: {}", snapshot.path, e),
+        }
+    }
+    Ok(removed)
+}
+
+/// 在 cc-switch 发起写入前创建快照：内容与最近一份快照相同时跳过（失败仅记录日志，不阻断写入）
+pub fn snapshot_before_write(reason: &str) {
+    let result = read_targets().and_then(|files| {
+        let hash = content_hash(&files);
+        let unchanged = latest_hash()
+            .lock()
+            .map(|latest| latest.as_deref() == Some(hash.as_str()))
+            .unwrap_or(false);
+        if unchanged {
+            return Ok(());
+        }
+        write_snapshot(reason, true, files).map(|_| ())
+    });
+    if let Err(e) = result {
+        log::warn!("写入前创建快照失败: {}", e);
+    }
+}
+
+/// 启动定时快照：按设置中的间隔（小时）检查最近一次快照，到期则创建
+pub fn start_scheduler() {
+    tauri::async_runtime::spawn(async {
+        loop {
+            let interval_hours = crate::settings::get_settings().backup_interval_hours;
+            if let Some(hours) = interval_hours.filter(|h| *h > 0) {
+                // 只看手动 / 定时快照，写入前快照不推迟定时快照
+                let latest = list_snapshots()
+                    .ok()
+                    .and_then(|s| s.into_iter().find(|s| !s.pre_write).map(|s| s.created_at));
+                let now = chrono::Utc::now().timestamp();
+                let due = latest.is_none_or(|t| now - t >= (hours as i64) * 3600);
+                if due {
+                    match create_snapshot("scheduled") {
+                        Ok(info) => crate::notifications::notify(
+                            crate::notifications::NotificationRecord::new(
+                                crate::notifications::NotificationCategory::BackupCompleted,
+                                crate::i18n::t("notification.backupCompleted.title"),
+                                crate::i18n::tf(
+                                    "notification.backupCompleted.message",
+                                    &[("id", &info.id)],
+                                ),
+                            ),
+                        ),
+                        Err(e) => log::warn!("定时快照失败: {}", e),
+                    }
+                }
+            }
+            tokio::time::sleep(SCHEDULER_TICK).await;
+        }
+    });
+}
@@ -0,0 +1,204 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::{Path, PathBuf};
+
+/// macOS 钥匙串中 Claude Code 凭据的服务名
+#[cfg(target_os = "macos")]
+const KEYCHAIN_SERVICE: &str = "Claude Code-credentials";
+
+/// Claude Code 实际使用的认证方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeAuthMode {
+    /// 订阅账号（claude.ai OAuth 登录）
+    OAuth,
+    /// API Key（settings.json env、apiKeyHelper、环境变量或 /login 保存的 Key）
+    ApiKey,
+    None,
+}
+
+/// OAuth 凭据所在位置
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CredentialStore {
+    /// ~/.claude/.credentials.json
+    File,
+    /// macOS 钥匙串
+    Keychain,
+}
+
+/// Claude 登录状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeAuthStatus {
+    pub mode: ClaudeAuthMode,
+    /// 是否存在 OAuth 登录（即使被 API Key 覆盖）
+    pub oauth_logged_in: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_store: Option<CredentialStore>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub organization_name: Option<String>,
+    /// 订阅类型（pro / max 等），仅凭据文件中可读
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_type: Option<String>,
+    /// 访问令牌过期时间（毫秒时间戳）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    /// API Key 来源：settingsEnv / apiKeyHelper / environment / primaryApiKey
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub api_key_source: Option<String>,
+    /// 已登录 OAuth，但 API Key 优先生效
+    pub oauth_overridden: bool,
+}
+
+fn credentials_path() -> PathBuf {
+    crate::config::get_claude_config_dir().join(".credentials.json")
+}
+
+fn read_json(path: &Path) -> Option<Value> {
+    if !path.is_file() {
+        return None;
+    }
+    crate::config::read_json_file::<Value>(path).ok()
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+#[cfg(target_os = "macos")]
+fn keychain_has_credentials() -> bool {
+    // 不带 -w 仅查询条目属性，不会触发钥匙串授权弹窗
+    std::process::Command::new("security")
+        .args(["find-generic-password", "-s", KEYCHAIN_SERVICE])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|s| s.success())
+}
+
+#[cfg(not(target_os = "macos"))]
+fn keychain_has_credentials() -> bool {
+    false
+}
+
+/// 生效的 API Key 来源，按 Claude Code 的优先级
+fn api_key_source(user_config: Option<&Value>) -> Option<String> {
+    let settings = read_json(&crate::config::get_claude_settings_path());
+    let env_has = |key: &str| {
+        settings
+            .as_ref()
+            .and_then(|s| s.get("env"))
+            .and_then(|env| str_field(env, key))
+            .is_some()
+    };
+    if env_has("ANTHROPIC_AUTH_TOKEN") || env_has("ANTHROPIC_API_KEY") {
+        return Some("settingsEnv".to_string());
+    }
+    if settings
+        .as_ref()
+        .and_then(|s| str_field(s, "apiKeyHelper"))
+        .is_some()
+    {
+        return Some("apiKeyHelper".to_string());
+    }
+    let process_env = ["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"]
+        .iter()
+        .any(|k| std::env::var(k).is_ok_and(|v| !v.trim().is_empty()));
+    if process_env {
+        return Some("environment".to_string());
+    }
+    user_config
+        .and_then(|c| str_field(c, "primaryApiKey"))
+        .map(|_| "primaryApiKey".to_string())
+}
+
+/// 读取 Claude 登录状态：凭据文件 / 钥匙串、~/.claude.json 的 oauthAccount 与 API Key 配置
+pub fn status() -> ClaudeAuthStatus {
+    let user_config = read_json(&crate::claude_mcp::user_config_path());
+    let account = user_config
+        .as_ref()
+        .and_then(|c| c.get("oauthAccount"))
+        .filter(|a| a.is_object());
+    let oauth = read_json(&credentials_path())
+        .and_then(|c| c.get("claudeAiOauth").cloned())
+        .filter(|o| o.is_object());
+
+    let credential_store = if oauth.is_some() {
+        Some(CredentialStore::File)
+    } else if keychain_has_credentials() {
+        Some(CredentialStore::Keychain)
+    } else {
+        None
+    };
+    let oauth_logged_in = credential_store.is_some();
+    let key_source = api_key_source(user_config.as_ref());
+
+    let mode = match (&key_source, oauth_logged_in) {
+        (Some(_), _) => ClaudeAuthMode::ApiKey,
+        (None, true) => ClaudeAuthMode::OAuth,
+        (None, false) => ClaudeAuthMode::None,
+    };
+
+    ClaudeAuthStatus {
+        mode,
+        oauth_logged_in,
+        credential_store,
+        email: account.and_then(|a| str_field(a, "emailAddress")),
+        display_name: account.and_then(|a| str_field(a, "displayName")),
+        organization_name: account.and_then(|a| str_field(a, "organizationName")),
+        subscription_type: oauth.as_ref().and_then(|o| str_field(o, "subscriptionType")),
+        expires_at: oauth
+            .as_ref()
+            .and_then(|o| o.get("expiresAt"))
+            .and_then(|v| v.as_i64()),
+        oauth_overridden: oauth_logged_in && key_source.is_some(),
+        api_key_source: key_source,
+    }
+}
+
+/// 登录信息涉及的文件（用于撤销）
+pub fn auth_paths() -> Vec<PathBuf> {
+    vec![credentials_path(), crate::claude_mcp::user_config_path()]
+}
+
+/// 退出 OAuth 登录：删除凭据文件与钥匙串条目，并移除 ~/.claude.json 中的 oauthAccount。
+/// API Key 配置不受影响
+pub fn logout() -> Result<ClaudeAuthStatus, String> {
+    let credentials = credentials_path();
+    if credentials.exists() {
+        crate::config::delete_file(&credentials)?;
+    }
+
+    #[cfg(target_os = "macos")]
+    {
+        if keychain_has_credentials() {
+            let status = std::process::Command::new("security")
+                .args(["delete-generic-password", "-s", KEYCHAIN_SERVICE])
+                .stdout(std::process::Stdio::null())
+                .stderr(std::process::Stdio::null())
+                .status()
+                .map_err(|e| format!("调用 security 失败: {}", e))?;
+            if !status.success() {
+                return Err("删除钥匙串中的 Claude 凭据失败".to_string());
+            }
+        }
+    }
+
+    let user_config_path = crate::claude_mcp::user_config_path();
+    if let Some(Value::Object(mut obj)) = read_json(&user_config_path) {
+        if obj.remove("oauthAccount").is_some() {
+            crate::config::write_json_file(&user_config_path, &Value::Object(obj))?;
+        }
+    }
+
+    Ok(status())
+}
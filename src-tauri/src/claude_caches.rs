@@ -0,0 +1,156 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Claude Code 在配置目录下持续累积的缓存目录
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeCacheKind {
+    /// ~/.claude/todos：每个会话的待办列表
+    Todos,
+    /// ~/.claude/shell-snapshots：每次会话启动时的 shell 环境快照
+    ShellSnapshots,
+}
+
+pub const ALL_KINDS: [ClaudeCacheKind; 2] =
+    [ClaudeCacheKind::Todos, ClaudeCacheKind::ShellSnapshots];
+
+impl ClaudeCacheKind {
+    fn dir(self) -> PathBuf {
+        let base = crate::config::get_claude_config_dir();
+        match self {
+            ClaudeCacheKind::Todos => base.join("todos"),
+            ClaudeCacheKind::ShellSnapshots => base.join("shell-snapshots"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            ClaudeCacheKind::Todos => "Claude todos",
+            ClaudeCacheKind::ShellSnapshots => "Claude shell snapshots",
+        }
+    }
+}
+
+/// 缓存目录中的单个文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCacheFile {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+    /// 秒级时间戳
+    pub modified_at: i64,
+}
+
+/// 缓存目录汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCacheSummary {
+    pub kind: ClaudeCacheKind,
+    pub path: String,
+    pub files: usize,
+    pub bytes: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oldest_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub newest_at: Option<i64>,
+}
+
+/// 清理结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeCachePurge {
+    pub kind: ClaudeCacheKind,
+    pub removed: usize,
+    pub freed_bytes: u64,
+    /// 因可能属于运行中的会话而保留的文件数
+    pub skipped_live: usize,
+}
+
+fn secs(time: SystemTime) -> i64 {
+    time.duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0)
+}
+
+/// 列出缓存文件（最近修改的在前）；目录不存在时返回空列表
+pub fn list(kind: ClaudeCacheKind) -> Vec<ClaudeCacheFile> {
+    let Ok(entries) = fs::read_dir(kind.dir()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<ClaudeCacheFile> = entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            if !meta.is_file() {
+                return None;
+            }
+            Some(ClaudeCacheFile {
+                name: entry.file_name().to_string_lossy().to_string(),
+                path: entry.path().to_string_lossy().to_string(),
+                size: meta.len(),
+                modified_at: meta.modified().map(secs).unwrap_or(0),
+            })
+        })
+        .collect();
+    files.sort_by_key(|f| std::cmp::Reverse(f.modified_at));
+    files
+}
+
+pub fn summary(kind: ClaudeCacheKind) -> ClaudeCacheSummary {
+    let files = list(kind);
+    ClaudeCacheSummary {
+        kind,
+        path: kind.dir().to_string_lossy().to_string(),
+        files: files.len(),
+        bytes: files.iter().map(|f| f.size).sum(),
+        oldest_at: files.iter().map(|f| f.modified_at).min(),
+        newest_at: files.iter().map(|f| f.modified_at).max(),
+    }
+}
+
+pub fn summaries() -> Vec<ClaudeCacheSummary> {
+    ALL_KINDS.iter().map(|kind| summary(*kind)).collect()
+}
+
+/// 删除缓存文件：keep_days 为空时删除全部，否则保留最近 keep_days 天内修改的文件。
+/// 运行中的 Claude 进程启动后修改过的文件一律保留
+pub fn purge(kind: ClaudeCacheKind, keep_days: Option<u32>) -> Result<ClaudeCachePurge, String> {
+    let cutoff = keep_days
+        .map(|days| SystemTime::now() - Duration::from_secs(days as u64 * 24 * 3600))
+        .map(secs);
+    let live_since = crate::active_sessions::running_processes()
+        .iter()
+        .filter(|p| p.app_type == "claude")
+        .map(|p| p.started_at)
+        .min();
+
+    let mut result = ClaudeCachePurge {
+        kind,
+        removed: 0,
+        freed_bytes: 0,
+        skipped_live: 0,
+    };
+    for file in list(kind) {
+        if cutoff.is_some_and(|c| file.modified_at >= c) {
+            continue;
+        }
+        if live_since.is_some_and(|t| file.modified_at >= t) {
+            result.skipped_live += 1;
+            continue;
+        }
+        crate::config::delete_file(Path::new(&file.path))
+            .map_err(|e| format!("删除文件失败: {}", e))?;
+        result.removed += 1;
+        result.freed_bytes += file.size;
+    }
+    log::info!(
+        "已清理 {}: 删除 {} 个文件，释放 {} 字节",
+        kind.label(),
+        result.removed,
+        result.freed_bytes
+    );
+    Ok(result)
+}
@@ -0,0 +1,568 @@
+use serde::{Deserialize, Serialize};
+use serde_yaml::{Mapping, Value as YamlValue};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use crate::agents::{parse_frontmatter, split_frontmatter, yaml_str, AgentScope};
+use crate::global_rules::{RenamedRule, RuleConflictStrategy, RulesImportResult};
+
+/// 技能目录中的入口文件
+const SKILL_FILE: &str = "SKILL.md";
+/// 命令包中的命令目录前缀
+const PACK_COMMANDS_PREFIX: &str = "commands/";
+/// 命令包中的技能目录前缀
+const PACK_SKILLS_PREFIX: &str = "skills/";
+const MAX_SKILL_NAME_CHARS: usize = 64;
+const MAX_SKILL_DESCRIPTION_CHARS: usize = 1024;
+
+/// 斜杠命令（commands/**/*.md，子目录为命名空间）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SlashCommandInfo {
+    /// 调用名，如 /review 或 /frontend:component
+    pub command: String,
+    /// 相对 commands 目录的路径，如 frontend/component.md
+    pub relative_path: String,
+    pub path: String,
+    pub scope: AgentScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub argument_hint: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    pub content: String,
+    /// frontmatter 校验失败的原因；为 None 表示文件有效
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 技能（skills/<目录>/SKILL.md 及其附带文件）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SkillInfo {
+    pub dir_name: String,
+    pub path: String,
+    pub scope: AgentScope,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allowed_tools: Option<Vec<String>>,
+    /// SKILL.md 之外的附带文件（相对技能目录）
+    pub files: Vec<String>,
+    pub content: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+pub fn commands_dir(scope: AgentScope, project_path: Option<&str>) -> Result<PathBuf, String> {
+    crate::agents::scope_dir(scope, project_path, "commands")
+}
+
+pub fn skills_dir(scope: AgentScope, project_path: Option<&str>) -> Result<PathBuf, String> {
+    crate::agents::scope_dir(scope, project_path, "skills")
+}
+
+/// 校验相对路径：仅允许普通路径段，禁止 `..` 与绝对路径
+fn safe_relative_path(relative: &str) -> Result<PathBuf, String> {
+    let relative = relative.trim().replace('\\', "/");
+    let path = PathBuf::from(&relative);
+    let valid =
+        !relative.is_empty() && path.components().all(|c| matches!(c, Component::Normal(_)));
+    if !valid {
+        return Err(format!("无效的路径: {}", relative));
+    }
+    Ok(path)
+}
+
+fn command_relative_path(relative: &str) -> Result<PathBuf, String> {
+    let path = safe_relative_path(relative)?;
+    if path.extension().and_then(|e| e.to_str()) != Some("md") {
+        return Err(format!("命令文件必须为 .md 文件: {}", relative));
+    }
+    Ok(path)
+}
+
+/// 技能目录名：小写字母、数字与连字符
+fn validate_skill_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name.chars().count() <= MAX_SKILL_NAME_CHARS
+        && name
+            .chars()
+            .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "技能名称只能包含小写字母、数字和连字符（最长 {} 个字符）: {}",
+            MAX_SKILL_NAME_CHARS, name
+        ))
+    }
+}
+
+fn string_list(map: &Mapping, key: &str) -> Result<Option<Vec<String>>, String> {
+    map.get(key)
+        .map(|v| crate::agents::parse_tools(v).map_err(|e| e.replace("tools", key)))
+        .transpose()
+}
+
+/// 校验命令 frontmatter（可省略）：description、argument-hint 为字符串，
+/// allowed-tools 为字符串或列表，model 为有效模型
+fn validate_command_frontmatter(map: &Mapping) -> Result<(), String> {
+    for key in ["description", "argument-hint"] {
+        if map.get(key).is_some_and(|v| !v.is_string()) {
+            return Err(format!("{} 必须为字符串", key));
+        }
+    }
+    string_list(map, "allowed-tools")?;
+    if let Some(model) = map.get("model") {
+        crate::agents::validate_model(model)?;
+    }
+    if map
+        .get("disable-model-invocation")
+        .is_some_and(|v| !v.is_bool())
+    {
+        return Err("disable-model-invocation 必须为布尔值".to_string());
+    }
+    Ok(())
+}
+
+/// 命令内容的 frontmatter（无 frontmatter 时为空）
+fn command_frontmatter(content: &str) -> Result<Mapping, String> {
+    let trimmed = content.strip_prefix('\u{feff}').unwrap_or(content);
+    if !trimmed.starts_with("---") {
+        return Ok(Mapping::new());
+    }
+    let (yaml, _) = split_frontmatter(content)?;
+    parse_frontmatter(yaml)
+}
+
+/// 校验命令文件内容
+pub fn validate_command_content(content: &str) -> Result<(), String> {
+    validate_command_frontmatter(&command_frontmatter(content)?)
+}
+
+/// 校验 SKILL.md：frontmatter 必填，name、description 必填
+pub fn validate_skill_content(content: &str) -> Result<(), String> {
+    let (yaml, _) = split_frontmatter(content)?;
+    let map = parse_frontmatter(yaml)?;
+    let name = yaml_str(&map, "name").ok_or_else(|| "frontmatter 缺少 name".to_string())?;
+    validate_skill_name(name)?;
+    let description = yaml_str(&map, "description")
+        .map(str::trim)
+        .filter(|d| !d.is_empty())
+        .ok_or_else(|| "frontmatter 缺少 description".to_string())?;
+    if description.chars().count() > MAX_SKILL_DESCRIPTION_CHARS {
+        return Err(format!(
+            "description 超过 {} 个字符",
+            MAX_SKILL_DESCRIPTION_CHARS
+        ));
+    }
+    string_list(&map, "allowed-tools")?;
+    Ok(())
+}
+
+fn command_info(root: &Path, path: &Path, scope: AgentScope) -> SlashCommandInfo {
+    let relative = path
+        .strip_prefix(root)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let command = format!(
+        "/{}",
+        relative
+            .strip_suffix(".md")
+            .unwrap_or(&relative)
+            .replace('/', ":")
+    );
+    let mut info = SlashCommandInfo {
+        command,
+        relative_path: relative,
+        path: path.to_string_lossy().to_string(),
+        scope,
+        description: None,
+        argument_hint: None,
+        allowed_tools: None,
+        model: None,
+        content: String::new(),
+        error: None,
+    };
+    match fs::read_to_string(path) {
+        Ok(content) => info.content = content,
+        Err(e) => {
+            info.error = Some(format!("读取命令失败: {}", e));
+            return info;
+        }
+    }
+    let map = match command_frontmatter(&info.content) {
+        Ok(map) => map,
+        Err(e) => {
+            info.error = Some(e);
+            return info;
+        }
+    };
+    info.description = yaml_str(&map, "description").map(|s| s.to_string());
+    info.argument_hint = yaml_str(&map, "argument-hint").map(|s| s.to_string());
+    info.allowed_tools = string_list(&map, "allowed-tools").ok().flatten();
+    info.model = yaml_str(&map, "model").map(|s| s.to_string());
+    info.error = validate_command_frontmatter(&map).err();
+    info
+}
+
+fn collect_markdown(dir: &Path, out: &mut Vec<PathBuf>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_markdown(&path, out);
+        } else if path.extension().and_then(|e| e.to_str()) == Some("md") {
+            out.push(path);
+        }
+    }
+}
+
+fn scopes(project_path: Option<&str>) -> Vec<AgentScope> {
+    let mut scopes = vec![AgentScope::Global];
+    if project_path.is_some_and(|p| !p.trim().is_empty()) {
+        scopes.push(AgentScope::Project);
+    }
+    scopes
+}
+
+/// 列出斜杠命令：全局，以及指定项目时的项目作用域
+pub fn list_commands(project_path: Option<&str>) -> Result<Vec<SlashCommandInfo>, String> {
+    let mut commands = Vec::new();
+    for scope in scopes(project_path) {
+        let root = commands_dir(scope, project_path)?;
+        let mut paths = Vec::new();
+        collect_markdown(&root, &mut paths);
+        paths.sort();
+        commands.extend(paths.iter().map(|p| command_info(&root, p, scope)));
+    }
+    Ok(commands)
+}
+
+/// 读取斜杠命令
+pub fn read_command(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    relative_path: &str,
+) -> Result<SlashCommandInfo, String> {
+    let root = commands_dir(scope, project_path)?;
+    let path = root.join(command_relative_path(relative_path)?);
+    if !path.exists() {
+        return Err(format!("命令不存在: {}", relative_path));
+    }
+    Ok(command_info(&root, &path, scope))
+}
+
+/// 写入斜杠命令（新建或覆盖），内容须通过 frontmatter 校验
+pub fn write_command(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    relative_path: &str,
+    content: &str,
+) -> Result<SlashCommandInfo, String> {
+    validate_command_content(content)?;
+    let root = commands_dir(scope, project_path)?;
+    let path = root.join(command_relative_path(relative_path)?);
+    crate::config::write_text_file(&path, content)?;
+    Ok(command_info(&root, &path, scope))
+}
+
+/// 新建斜杠命令：生成含 description 的 frontmatter
+pub fn create_command(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    relative_path: &str,
+    description: &str,
+    body: &str,
+) -> Result<SlashCommandInfo, String> {
+    let root = commands_dir(scope, project_path)?;
+    if root.join(command_relative_path(relative_path)?).exists() {
+        return Err(format!("命令已存在: {}", relative_path));
+    }
+    let mut map = Mapping::new();
+    if !description.trim().is_empty() {
+        map.insert("description".into(), description.trim().into());
+    }
+    let content = if map.is_empty() {
+        body.to_string()
+    } else {
+        crate::agents::render_with_frontmatter(&map, body)?
+    };
+    write_command(scope, project_path, relative_path, &content)
+}
+
+/// 删除斜杠命令，并清理空的命名空间目录
+pub fn delete_command(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    relative_path: &str,
+) -> Result<(), String> {
+    let root = commands_dir(scope, project_path)?;
+    let path = root.join(command_relative_path(relative_path)?);
+    if !path.exists() {
+        return Err(format!("命令不存在: {}", relative_path));
+    }
+    crate::config::delete_file(&path)?;
+    let mut dir = path.parent();
+    while let Some(d) = dir.filter(|d| *d != root && d.starts_with(&root)) {
+        if fs::remove_dir(d).is_err() {
+            break;
+        }
+        dir = d.parent();
+    }
+    Ok(())
+}
+
+fn skill_info(dir: &Path, scope: AgentScope) -> SkillInfo {
+    let dir_name = dir
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let mut files = Vec::new();
+    collect_files(dir, dir, &mut files);
+    files.retain(|f| f != SKILL_FILE);
+    files.sort();
+    let mut info = SkillInfo {
+        dir_name,
+        path: dir.to_string_lossy().to_string(),
+        scope,
+        name: None,
+        description: None,
+        allowed_tools: None,
+        files,
+        content: String::new(),
+        error: None,
+    };
+    match fs::read_to_string(dir.join(SKILL_FILE)) {
+        Ok(content) => info.content = content,
+        Err(e) => {
+            info.error = Some(format!("读取 {} 失败: {}", SKILL_FILE, e));
+            return info;
+        }
+    }
+    if let Ok((yaml, _)) = split_frontmatter(&info.content) {
+        if let Ok(map) = parse_frontmatter(yaml) {
+            info.name = yaml_str(&map, "name").map(|s| s.to_string());
+            info.description = yaml_str(&map, "description").map(|s| s.to_string());
+            info.allowed_tools = string_list(&map, "allowed-tools").ok().flatten();
+        }
+    }
+    info.error = validate_skill_content(&info.content).err();
+    info
+}
+
+fn collect_files(root: &Path, dir: &Path, out: &mut Vec<String>) {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            collect_files(root, &path, out);
+        } else if let Ok(relative) = path.strip_prefix(root) {
+            out.push(relative.to_string_lossy().replace('\\', "/"));
+        }
+    }
+}
+
+/// 列出技能：全局，以及指定项目时的项目作用域
+pub fn list_skills(project_path: Option<&str>) -> Result<Vec<SkillInfo>, String> {
+    let mut skills = Vec::new();
+    for scope in scopes(project_path) {
+        let root = skills_dir(scope, project_path)?;
+        let Ok(entries) = fs::read_dir(&root) else {
+            continue;
+        };
+        let mut dirs: Vec<PathBuf> = entries
+            .flatten()
+            .map(|e| e.path())
+            .filter(|p| p.is_dir())
+            .collect();
+        dirs.sort();
+        skills.extend(dirs.iter().map(|d| skill_info(d, scope)));
+    }
+    Ok(skills)
+}
+
+fn skill_dir(scope: AgentScope, project_path: Option<&str>, name: &str) -> Result<PathBuf, String> {
+    let name = name.trim();
+    validate_skill_name(name)?;
+    Ok(skills_dir(scope, project_path)?.join(name))
+}
+
+/// 读取技能
+pub fn read_skill(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    name: &str,
+) -> Result<SkillInfo, String> {
+    let dir = skill_dir(scope, project_path, name)?;
+    if !dir.is_dir() {
+        return Err(format!("技能不存在: {}", name));
+    }
+    Ok(skill_info(&dir, scope))
+}
+
+/// 写入技能的 SKILL.md（新建或覆盖），须通过 frontmatter 校验
+pub fn write_skill(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    name: &str,
+    content: &str,
+) -> Result<SkillInfo, String> {
+    validate_skill_content(content)?;
+    let dir = skill_dir(scope, project_path, name)?;
+    crate::config::write_text_file(&dir.join(SKILL_FILE), content)?;
+    Ok(skill_info(&dir, scope))
+}
+
+/// 新建技能：生成含 name、description 的 SKILL.md
+pub fn create_skill(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    name: &str,
+    description: &str,
+    body: &str,
+) -> Result<SkillInfo, String> {
+    let dir = skill_dir(scope, project_path, name)?;
+    if dir.exists() {
+        return Err(format!("技能已存在: {}", name));
+    }
+    let mut map = Mapping::new();
+    map.insert("name".into(), YamlValue::from(name.trim()));
+    map.insert("description".into(), YamlValue::from(description.trim()));
+    let content = crate::agents::render_with_frontmatter(&map, body)?;
+    write_skill(scope, project_path, name, &content)
+}
+
+/// 删除技能目录
+pub fn delete_skill(
+    scope: AgentScope,
+    project_path: Option<&str>,
+    name: &str,
+) -> Result<(), String> {
+    let dir = skill_dir(scope, project_path, name)?;
+    if !dir.is_dir() {
+        return Err(format!("技能不存在: {}", name));
+    }
+    crate::config::delete_dir(&dir).map_err(|e| format!("删除技能失败: {}", e))
+}
+
+/// 导入共享命令包（zip）：commands/ 下的 .md 为斜杠命令，skills/<名称>/ 下为技能。
+/// 命令冲突按策略处理；技能目录冲突时仅 overwrite 会覆盖，其余策略跳过
+pub fn import_pack(
+    pack_path: &str,
+    scope: AgentScope,
+    project_path: Option<&str>,
+    strategy: RuleConflictStrategy,
+) -> Result<RulesImportResult, String> {
+    use std::collections::BTreeMap;
+    use std::io::Read;
+
+    let file = fs::File::open(pack_path).map_err(|e| format!("打开命令包失败: {}", e))?;
+    let mut archive = zip::ZipArchive::new(file).map_err(|e| format!("解析命令包失败: {}", e))?;
+
+    // 先完整读出并校验包内容，再落盘
+    let mut commands: Vec<(PathBuf, String)> = Vec::new();
+    let mut skills: BTreeMap<String, Vec<(PathBuf, Vec<u8>)>> = BTreeMap::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取命令包条目失败: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().replace('\\', "/");
+        let mut data = Vec::new();
+        entry
+            .read_to_end(&mut data)
+            .map_err(|e| format!("读取命令包条目 {} 失败: {}", name, e))?;
+
+        if let Some(relative) = name.strip_prefix(PACK_COMMANDS_PREFIX) {
+            let path = command_relative_path(relative)?;
+            let content = String::from_utf8(data)
+                .map_err(|_| format!("命令文件不是 UTF-8 文本: {}", name))?;
+            validate_command_content(&content).map_err(|e| format!("{}: {}", name, e))?;
+            commands.push((path, content));
+        } else if let Some(relative) = name.strip_prefix(PACK_SKILLS_PREFIX) {
+            let (skill, file) = relative
+                .split_once('/')
+                .ok_or_else(|| format!("技能文件须位于 skills/<名称>/ 下: {}", name))?;
+            validate_skill_name(skill)?;
+            skills
+                .entry(skill.to_string())
+                .or_default()
+                .push((safe_relative_path(file)?, data));
+        } else {
+            log::warn!("跳过命令包中的未知条目: {}", name);
+        }
+    }
+    for (skill, files) in &skills {
+        let manifest = files
+            .iter()
+            .find(|(p, _)| p == Path::new(SKILL_FILE))
+            .ok_or_else(|| format!("技能 {} 缺少 {}", skill, SKILL_FILE))?;
+        validate_skill_content(&String::from_utf8_lossy(&manifest.1))
+            .map_err(|e| format!("技能 {}: {}", skill, e))?;
+    }
+
+    let mut result = RulesImportResult::default();
+
+    let root = commands_dir(scope, project_path)?;
+    for (relative, content) in commands {
+        let label = relative.to_string_lossy().replace('\\', "/");
+        let existing = root.join(&relative);
+        let conflict = existing.exists()
+            && fs::read_to_string(&existing)
+                .map(|local| local != content)
+                .unwrap_or(true);
+        match (conflict, strategy) {
+            (true, RuleConflictStrategy::Skip) => result.skipped.push(label),
+            (true, RuleConflictStrategy::Rename) => {
+                let dir = existing.parent().unwrap_or(&root);
+                let file_name = relative
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let new_name = crate::global_rules::unique_file_name(dir, &file_name);
+                crate::config::write_text_file(&dir.join(&new_name), &content)?;
+                result.renamed.push(RenamedRule {
+                    from: label,
+                    to: new_name,
+                });
+            }
+            _ => {
+                crate::config::write_text_file(&existing, &content)?;
+                result.imported.push(label);
+            }
+        }
+    }
+
+    let root = skills_dir(scope, project_path)?;
+    for (skill, files) in skills {
+        let dir = root.join(&skill);
+        let label = format!("{}{}", PACK_SKILLS_PREFIX, skill);
+        if dir.exists() && strategy != RuleConflictStrategy::Overwrite {
+            result.skipped.push(label);
+            continue;
+        }
+        if dir.exists() {
+            crate::config::delete_dir(&dir).map_err(|e| format!("删除旧技能失败: {}", e))?;
+        }
+        for (relative, data) in files {
+            crate::config::atomic_write(&dir.join(relative), &data)?;
+        }
+        result.imported.push(label);
+    }
+
+    Ok(result)
+}
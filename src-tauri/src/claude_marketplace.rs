@@ -0,0 +1,252 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+use crate::claude_settings::{get_section, set_section, ClaudeSettingsSection};
+
+/// 插件市场（settings.json 的 extraKnownMarketplaces 与 plugins/known_marketplaces.json）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeMarketplace {
+    pub name: String,
+    /// 来源，如 { "source": "github", "repo": "owner/repo" }
+    pub source: Value,
+    /// 已由 Claude Code 下载到本地时的目录
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_updated: Option<String>,
+    /// 是否在 settings.json 中声明（仅在 known_marketplaces.json 中的由 /plugin 命令添加）
+    pub in_settings: bool,
+}
+
+/// 插件（名称形如 plugin@marketplace）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudePlugin {
+    /// plugin@marketplace
+    pub id: String,
+    pub name: String,
+    pub marketplace: String,
+    /// enabledPlugins 中的值；未声明时为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+    pub installed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub install_path: Option<String>,
+}
+
+fn plugins_dir() -> PathBuf {
+    crate::config::get_claude_config_dir().join("plugins")
+}
+
+/// 读取 plugins 目录下的 JSON 文件；不存在或无法解析时返回 None
+fn read_plugins_file(name: &str) -> Option<Value> {
+    let path = plugins_dir().join(name);
+    if !path.is_file() {
+        return None;
+    }
+    match crate::config::read_json_file::<Value>(&path) {
+        Ok(value) => Some(value),
+        Err(e) => {
+            log::warn!("读取 {} 失败: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+fn object_section(section: ClaudeSettingsSection) -> Result<Map<String, Value>, String> {
+    match get_section(section)? {
+        Value::Object(map) => Ok(map),
+        Value::Null => Ok(Map::new()),
+        _ => Err("settings.json 中该配置段必须是 JSON 对象".to_string()),
+    }
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value.get(key).and_then(|v| v.as_str()).map(|s| s.to_string())
+}
+
+/// 列出 settings.json 中声明的与 Claude Code 已知的插件市场
+pub fn list_marketplaces() -> Result<Vec<ClaudeMarketplace>, String> {
+    let mut found: BTreeMap<String, ClaudeMarketplace> = BTreeMap::new();
+
+    if let Some(Value::Object(known)) = read_plugins_file("known_marketplaces.json") {
+        for (name, entry) in known {
+            found.insert(
+                name.clone(),
+                ClaudeMarketplace {
+                    source: entry.get("source").cloned().unwrap_or(Value::Null),
+                    install_location: str_field(&entry, "installLocation"),
+                    last_updated: str_field(&entry, "lastUpdated"),
+                    in_settings: false,
+                    name,
+                },
+            );
+        }
+    }
+
+    for (name, entry) in object_section(ClaudeSettingsSection::ExtraKnownMarketplaces)? {
+        let source = entry.get("source").cloned().unwrap_or(Value::Null);
+        let marketplace = found
+            .entry(name.clone())
+            .or_insert_with(|| ClaudeMarketplace {
+                name,
+                source: Value::Null,
+                install_location: None,
+                last_updated: None,
+                in_settings: true,
+            });
+        // settings.json 中的声明优先
+        marketplace.source = source;
+        marketplace.in_settings = true;
+    }
+
+    Ok(found.into_values().collect())
+}
+
+/// installed_plugins.json：{ plugins: { id: {...} | [{...}] } }，兼容旧版无 plugins 包裹的格式
+fn installed_plugins() -> BTreeMap<String, Value> {
+    let Some(root) = read_plugins_file("installed_plugins.json") else {
+        return BTreeMap::new();
+    };
+    let plugins = root.get("plugins").cloned().unwrap_or(root);
+    let Value::Object(map) = plugins else {
+        return BTreeMap::new();
+    };
+    map.into_iter()
+        .filter_map(|(id, entry)| {
+            // 新版按安装范围存为数组，取第一项
+            let entry = match entry {
+                Value::Array(items) => items.into_iter().next()?,
+                other => other,
+            };
+            entry.is_object().then_some((id, entry))
+        })
+        .collect()
+}
+
+fn split_id(id: &str) -> (String, String) {
+    match id.rsplit_once('@') {
+        Some((name, marketplace)) => (name.to_string(), marketplace.to_string()),
+        None => (id.to_string(), String::new()),
+    }
+}
+
+/// 列出已安装或在 enabledPlugins 中声明的插件
+pub fn list_plugins() -> Result<Vec<ClaudePlugin>, String> {
+    let enabled = object_section(ClaudeSettingsSection::EnabledPlugins)?;
+    let installed = installed_plugins();
+
+    let mut ids: Vec<&String> = enabled.keys().chain(installed.keys()).collect();
+    ids.sort();
+    ids.dedup();
+
+    Ok(ids
+        .into_iter()
+        .map(|id| {
+            let (name, marketplace) = split_id(id);
+            let entry = installed.get(id);
+            ClaudePlugin {
+                id: id.clone(),
+                name,
+                marketplace,
+                enabled: enabled.get(id).and_then(|v| v.as_bool()),
+                installed: entry.is_some(),
+                version: entry.and_then(|e| str_field(e, "version")),
+                install_path: entry.and_then(|e| str_field(e, "installPath")),
+            }
+        })
+        .collect())
+}
+
+fn validate_plugin_id(id: &str) -> Result<(), String> {
+    let valid = !id.chars().any(char::is_whitespace)
+        && id.split_once('@').is_some_and(|(name, market)| {
+            !name.is_empty() && !market.is_empty() && !market.contains('@')
+        });
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("插件名称格式应为 plugin@marketplace: {}", id))
+    }
+}
+
+/// 启用 / 禁用插件；enabled 为 None 时从 enabledPlugins 中移除（恢复默认）
+pub fn set_plugin_enabled(id: &str, enabled: Option<bool>) -> Result<Vec<ClaudePlugin>, String> {
+    let id = id.trim();
+    validate_plugin_id(id)?;
+    let mut map = object_section(ClaudeSettingsSection::EnabledPlugins)?;
+    match enabled {
+        Some(enabled) => {
+            map.insert(id.to_string(), Value::Bool(enabled));
+        }
+        None => {
+            map.remove(id);
+        }
+    }
+    let value = if map.is_empty() {
+        Value::Null
+    } else {
+        Value::Object(map)
+    };
+    set_section(ClaudeSettingsSection::EnabledPlugins, value)?;
+    list_plugins()
+}
+
+/// 在 settings.json 中声明或更新插件市场；来源格式由 settings.json Schema 校验
+pub fn upsert_marketplace(name: &str, source: Value) -> Result<Vec<ClaudeMarketplace>, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("插件市场名称不能为空".to_string());
+    }
+    let mut map = object_section(ClaudeSettingsSection::ExtraKnownMarketplaces)?;
+    let mut entry = map
+        .remove(name)
+        .and_then(|v| v.as_object().cloned())
+        .unwrap_or_default();
+    entry.insert("source".to_string(), source);
+    map.insert(name.to_string(), Value::Object(entry));
+    set_section(
+        ClaudeSettingsSection::ExtraKnownMarketplaces,
+        Value::Object(map),
+    )?;
+    list_marketplaces()
+}
+
+/// 从 settings.json 中移除插件市场声明，可选一并移除 enabledPlugins 中属于该市场的插件。
+/// Claude Code 已下载的市场文件不受影响
+pub fn remove_marketplace(
+    name: &str,
+    remove_plugins: bool,
+) -> Result<Vec<ClaudeMarketplace>, String> {
+    let name = name.trim();
+    let mut map = object_section(ClaudeSettingsSection::ExtraKnownMarketplaces)?;
+    if map.remove(name).is_none() {
+        return Err(format!("settings.json 中未声明插件市场: {}", name));
+    }
+    let value = if map.is_empty() {
+        Value::Null
+    } else {
+        Value::Object(map)
+    };
+    set_section(ClaudeSettingsSection::ExtraKnownMarketplaces, value)?;
+
+    if remove_plugins {
+        let mut enabled = object_section(ClaudeSettingsSection::EnabledPlugins)?;
+        let before = enabled.len();
+        enabled.retain(|id, _| split_id(id).1 != name);
+        if enabled.len() != before {
+            let value = if enabled.is_empty() {
+                Value::Null
+            } else {
+                Value::Object(enabled)
+            };
+            set_section(ClaudeSettingsSection::EnabledPlugins, value)?;
+        }
+    }
+    list_marketplaces()
+}
@@ -14,7 +14,15 @@ pub struct McpStatus {
     pub server_count: usize,
 }
 
-fn user_config_path() -> PathBuf {
+pub(crate) fn user_config_path() -> PathBuf {
+    // 选中已登记的配置目录时，与 CLAUDE_CONFIG_DIR 相同，使用该目录下的 .claude.json
+    if let Some(home) = crate::settings::get_active_claude_home_dir() {
+        return home.join(".claude.json");
+    }
+    // WSL 模式下使用发行版用户主目录中的 .claude.json
+    if let Some(home) = crate::settings::get_wsl_home_dir() {
+        return home.join(".claude.json");
+    }
     // 用户级 MCP 配置文件：~/.claude.json
     dirs::home_dir()
         .expect("无法获取用户主目录")
@@ -39,7 +47,7 @@ fn write_json_value(path: &Path, value: &Value) -> Result<(), String> {
     }
     let json =
         serde_json::to_string_pretty(value).map_err(|e| format!("序列化 JSON 失败: {}", e))?;
-    atomic_write(path, json.as_bytes())
+    atomic_write(path, json.as_bytes()).map_err(Into::into)
 }
 
 pub fn get_mcp_status() -> Result<McpStatus, String> {
@@ -186,19 +194,10 @@ pub fn validate_command_in_path(cmd: &str) -> Result<bool, String> {
     Ok(false)
 }
 
-/// 将给定的启用 MCP 服务器映射写入到用户级 ~/.claude.json 的 mcpServers 字段
-/// 仅覆盖 mcpServers，其他字段保持不变
-pub fn set_mcp_servers_map(
+/// 构建 mcpServers 对象：移除 UI 辅助字段（enabled/source 等），仅保留实际 MCP 规范
+pub(crate) fn build_mcp_servers_object(
     servers: &std::collections::HashMap<String, Value>,
-) -> Result<(), String> {
-    let path = user_config_path();
-    let mut root = if path.exists() {
-        read_json_value(&path)?
-    } else {
-        serde_json::json!({})
-    };
-
-    // 构建 mcpServers 对象：移除 UI 辅助字段（enabled/source），仅保留实际 MCP 规范
+) -> Result<Map<String, Value>, String> {
     let mut out: Map<String, Value> = Map::new();
     for (id, spec) in servers.iter() {
         let mut obj = if let Some(map) = spec.as_object() {
@@ -226,6 +225,21 @@ pub fn set_mcp_servers_map(
 
         out.insert(id.clone(), Value::Object(obj));
     }
+    Ok(out)
+}
+
+/// 将给定的启用 MCP 服务器映射写入到用户级 ~/.claude.json 的 mcpServers 字段
+/// 仅覆盖 mcpServers，其他字段保持不变
+pub fn set_mcp_servers_map(
+    servers: &std::collections::HashMap<String, Value>,
+) -> Result<(), String> {
+    let path = user_config_path();
+    let mut root = if path.exists() {
+        read_json_value(&path)?
+    } else {
+        serde_json::json!({})
+    };
+    let out = build_mcp_servers_object(servers)?;
 
     {
         let obj = root
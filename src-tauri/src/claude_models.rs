@@ -0,0 +1,124 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+/// 生效的默认模型：env 中的 ANTHROPIC_MODEL 优先于 settings.json 顶层 model
+const MODEL_ENV: &str = "ANTHROPIC_MODEL";
+/// 后台任务使用的快速模型
+const SMALL_FAST_MODEL_ENV: &str = "ANTHROPIC_SMALL_FAST_MODEL";
+const OPUS_MODEL_ENV: &str = "ANTHROPIC_DEFAULT_OPUS_MODEL";
+const SONNET_MODEL_ENV: &str = "ANTHROPIC_DEFAULT_SONNET_MODEL";
+const HAIKU_MODEL_ENV: &str = "ANTHROPIC_DEFAULT_HAIKU_MODEL";
+
+/// 供应商配置中的 Claude 模型设置；字段为 None 表示未设置（使用 Claude Code 默认值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeModelSettings {
+    /// 默认模型，可为别名（opus / sonnet / haiku）或完整模型 id
+    pub model: Option<String>,
+    /// 快速 / 后备模型（ANTHROPIC_SMALL_FAST_MODEL）
+    pub small_fast_model: Option<String>,
+    /// 别名 opus 对应的模型
+    pub opus_model: Option<String>,
+    /// 别名 sonnet 对应的模型
+    pub sonnet_model: Option<String>,
+    /// 别名 haiku 对应的模型
+    pub haiku_model: Option<String>,
+    /// 最近一次从端点获取的模型列表
+    pub available: Vec<String>,
+}
+
+fn env_str(settings: &Value, key: &str) -> Option<String> {
+    settings
+        .pointer(&format!("/env/{}", key))
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+/// 从供应商配置（settings.json 结构）中读取模型设置
+pub fn read(settings: &Value, available: &[String]) -> ClaudeModelSettings {
+    ClaudeModelSettings {
+        model: env_str(settings, MODEL_ENV).or_else(|| {
+            settings
+                .get("model")
+                .and_then(|v| v.as_str())
+                .map(str::trim)
+                .filter(|s| !s.is_empty())
+                .map(str::to_string)
+        }),
+        small_fast_model: env_str(settings, SMALL_FAST_MODEL_ENV),
+        opus_model: env_str(settings, OPUS_MODEL_ENV),
+        sonnet_model: env_str(settings, SONNET_MODEL_ENV),
+        haiku_model: env_str(settings, HAIKU_MODEL_ENV),
+        available: available.to_vec(),
+    }
+}
+
+fn set_env(env: &mut Map<String, Value>, key: &str, value: Option<&str>) {
+    match value.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(value) => {
+            env.insert(key.to_string(), Value::String(value.to_string()));
+        }
+        None => {
+            env.remove(key);
+        }
+    }
+}
+
+/// 将模型设置写入供应商配置，其余字段保持不变。
+/// 默认模型写入已有的 ANTHROPIC_MODEL（其优先级更高），否则写入顶层 model
+pub fn apply(settings: &mut Value, models: &ClaudeModelSettings) -> Result<(), String> {
+    let obj = settings
+        .as_object_mut()
+        .ok_or_else(|| "供应商配置必须是 JSON 对象".to_string())?;
+    let mut env = match obj.remove("env") {
+        Some(Value::Object(env)) => env,
+        None | Some(Value::Null) => Map::new(),
+        Some(_) => return Err("env 必须是 JSON 对象".to_string()),
+    };
+
+    let model = models
+        .model
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty());
+    if env.contains_key(MODEL_ENV) {
+        set_env(&mut env, MODEL_ENV, model);
+        obj.remove("model");
+    } else {
+        match model {
+            Some(model) => {
+                obj.insert("model".to_string(), Value::String(model.to_string()));
+            }
+            None => {
+                obj.remove("model");
+            }
+        }
+    }
+    set_env(
+        &mut env,
+        SMALL_FAST_MODEL_ENV,
+        models.small_fast_model.as_deref(),
+    );
+    set_env(&mut env, OPUS_MODEL_ENV, models.opus_model.as_deref());
+    set_env(&mut env, SONNET_MODEL_ENV, models.sonnet_model.as_deref());
+    set_env(&mut env, HAIKU_MODEL_ENV, models.haiku_model.as_deref());
+
+    obj.insert("env".to_string(), Value::Object(env));
+    Ok(())
+}
+
+/// 在模型列表中挑选合适的默认模型：优先最新的 sonnet，其次任意 claude 模型，最后取第一个
+pub fn suggest_default(available: &[String]) -> Option<String> {
+    let latest = |pred: &dyn Fn(&str) -> bool| {
+        available
+            .iter()
+            .filter(|m| pred(&m.to_lowercase()))
+            .max()
+            .cloned()
+    };
+    latest(&|m| m.contains("sonnet"))
+        .or_else(|| latest(&|m| m.contains("claude")))
+        .or_else(|| available.first().cloned())
+}
@@ -79,7 +79,7 @@ pub fn write_claude_config() -> Result<bool, String> {
     if changed || !path.exists() {
         let serialized = serde_json::to_string_pretty(&obj)
             .map_err(|e| format!("序列化 Claude 配置失败: {}", e))?;
-        fs::write(&path, format!("{}\n", serialized))
+        crate::config::write_text_file(&path, &format!("{}\n", serialized))
             .map_err(|e| format!("写入 Claude 配置失败: {}", e))?;
         Ok(true)
     } else {
@@ -114,7 +114,7 @@ pub fn clear_claude_config() -> Result<bool, String> {
 
     let serialized = serde_json::to_string_pretty(&value)
         .map_err(|e| format!("序列化 Claude 配置失败: {}", e))?;
-    fs::write(&path, format!("{}\n", serialized))
+    crate::config::write_text_file(&path, &format!("{}\n", serialized))
         .map_err(|e| format!("写入 Claude 配置失败: {}", e))?;
     Ok(true)
 }
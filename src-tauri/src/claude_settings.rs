@@ -0,0 +1,258 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::config::get_claude_settings_path;
+use crate::error::AppError;
+
+/// 随应用打包的 settings.json JSON Schema
+const SETTINGS_SCHEMA: &str = include_str!("../resources/claude_settings.schema.json");
+
+/// 权限配置（permissions）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudePermissions {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub allow: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deny: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ask: Option<Vec<String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub additional_directories: Option<Vec<String>>,
+    /// default / acceptEdits / plan / bypassPermissions
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_mode: Option<String>,
+    /// 未建模的字段，原样保留
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 单条 hook 命令
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeHookCommand {
+    /// 目前仅支持 command
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub command: String,
+    /// 超时秒数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<f64>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 按工具名匹配的一组 hook
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeHookMatcher {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub matcher: Option<String>,
+    pub hooks: Vec<ClaudeHookCommand>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 状态栏配置（statusLine）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeStatusLine {
+    #[serde(rename = "type")]
+    pub kind: String,
+    pub command: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub padding: Option<u32>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// ~/.claude/settings.json 的类型化模型；未建模的顶层字段保存在 extra 中
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ClaudeSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env: Option<BTreeMap<String, String>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub permissions: Option<ClaudePermissions>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 事件名（PreToolUse、Stop 等）→ hook 列表
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hooks: Option<BTreeMap<String, Vec<ClaudeHookMatcher>>>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status_line: Option<ClaudeStatusLine>,
+    #[serde(flatten)]
+    pub extra: Map<String, Value>,
+}
+
+/// 可单独读写的配置段
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ClaudeSettingsSection {
+    Env,
+    Permissions,
+    Model,
+    Hooks,
+    StatusLine,
+    EnabledPlugins,
+    ExtraKnownMarketplaces,
+}
+
+impl ClaudeSettingsSection {
+    /// settings.json 中对应的顶层键
+    fn key(self) -> &'static str {
+        match self {
+            ClaudeSettingsSection::Env => "env",
+            ClaudeSettingsSection::Permissions => "permissions",
+            ClaudeSettingsSection::Model => "model",
+            ClaudeSettingsSection::Hooks => "hooks",
+            ClaudeSettingsSection::StatusLine => "statusLine",
+            ClaudeSettingsSection::EnabledPlugins => "enabledPlugins",
+            ClaudeSettingsSection::ExtraKnownMarketplaces => "extraKnownMarketplaces",
+        }
+    }
+}
+
+/// 单条 Schema 校验错误
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SettingsSchemaError {
+    /// JSON Pointer，如 /permissions/defaultMode；根对象为空字符串
+    pub path: String,
+    pub message: String,
+}
+
+/// 获取 settings.json 的 JSON Schema（供前端编辑器提示使用）
+pub fn settings_schema() -> Value {
+    serde_json::from_str(SETTINGS_SCHEMA).unwrap_or_else(|e| {
+        log::error!("解析内置 settings.json Schema 失败: {}", e);
+        Value::Object(Map::new())
+    })
+}
+
+/// 按 JSON Schema 校验 settings.json 内容，返回全部错误
+pub fn validate_settings_value(value: &Value) -> Vec<SettingsSchemaError> {
+    let schema = settings_schema();
+    let compiled = match jsonschema::JSONSchema::compile(&schema) {
+        Ok(compiled) => compiled,
+        Err(e) => {
+            log::error!("编译 settings.json Schema 失败: {}", e);
+            return Vec::new();
+        }
+    };
+    let Err(errors) = compiled.validate(value) else {
+        return Vec::new();
+    };
+    errors
+        .map(|e| SettingsSchemaError {
+            path: e.instance_path.to_string(),
+            message: e.to_string(),
+        })
+        .collect()
+}
+
+/// 校验通过返回 Ok，否则将全部错误合并为一条消息
+fn ensure_valid(value: &Value) -> Result<(), AppError> {
+    let errors = validate_settings_value(value);
+    if errors.is_empty() {
+        return Ok(());
+    }
+    let details: Vec<String> = errors
+        .iter()
+        .map(|e| {
+            let path = if e.path.is_empty() { "/" } else { &e.path };
+            format!("{}: {}", path, e.message)
+        })
+        .collect();
+    Err(AppError::InvalidInput(format!(
+        "settings.json 校验失败: {}",
+        details.join("; ")
+    )))
+}
+
+/// 读取 settings.json 原始 JSON；文件不存在时返回空对象
+fn read_raw() -> Result<Value, AppError> {
+    let path = get_claude_settings_path();
+    if !path.exists() {
+        return Ok(Value::Object(Map::new()));
+    }
+    let value: Value = crate::config::read_json_file(&path)?;
+    if !value.is_object() {
+        return Err(AppError::Parse {
+            path: path.display().to_string(),
+            format: "JSON",
+            message: "顶层必须是 JSON 对象".to_string(),
+        });
+    }
+    Ok(value)
+}
+
+fn write_raw(value: &Value) -> Result<(), AppError> {
+    ensure_valid(value)?;
+    crate::config::write_json_file(&get_claude_settings_path(), value)
+}
+
+/// 读取类型化的 settings.json
+pub fn read_settings() -> Result<ClaudeSettings, AppError> {
+    serde_json::from_value(read_raw()?).map_err(|e| AppError::json(&get_claude_settings_path(), e))
+}
+
+/// 整体保存类型化的 settings.json（校验后写入）
+pub fn write_settings(settings: &ClaudeSettings) -> Result<ClaudeSettings, AppError> {
+    let value = serde_json::to_value(settings).map_err(|e| AppError::Serialize {
+        format: "JSON",
+        message: e.to_string(),
+    })?;
+    write_raw(&value)?;
+    read_settings()
+}
+
+/// 读取单个配置段；未设置时返回 null
+pub fn get_section(section: ClaudeSettingsSection) -> Result<Value, AppError> {
+    Ok(read_raw()?
+        .get(section.key())
+        .cloned()
+        .unwrap_or(Value::Null))
+}
+
+/// 写入单个配置段，其余字段保持不变；value 为 null 时移除该段
+pub fn set_section(
+    section: ClaudeSettingsSection,
+    value: Value,
+) -> Result<ClaudeSettings, AppError> {
+    let mut root = read_raw()?;
+    let obj = root
+        .as_object_mut()
+        .ok_or_else(|| AppError::InvalidInput("settings.json 顶层必须是 JSON 对象".to_string()))?;
+    if value.is_null() {
+        obj.remove(section.key());
+    } else {
+        obj.insert(section.key().to_string(), value);
+    }
+    write_raw(&root)?;
+    read_settings()
+}
+
+/// 设置或移除（value 为 None）单个环境变量
+pub fn set_env_var(key: &str, value: Option<&str>) -> Result<ClaudeSettings, AppError> {
+    let mut env = match get_section(ClaudeSettingsSection::Env)? {
+        Value::Object(map) => map,
+        Value::Null => Map::new(),
+        _ => return Err(AppError::InvalidInput("env 必须是 JSON 对象".to_string())),
+    };
+    match value {
+        Some(value) => {
+            env.insert(key.trim().to_string(), Value::String(value.to_string()));
+        }
+        None => {
+            env.remove(key.trim());
+        }
+    }
+    let env = if env.is_empty() {
+        Value::Null
+    } else {
+        Value::Object(env)
+    };
+    set_section(ClaudeSettingsSection::Env, env)
+}
@@ -0,0 +1,210 @@
+//! 命令行子命令：在不启动 Tauri 窗口的情况下读取本地数据，供外部 TUI / 分页器 / 脚本使用。
+//!
+//! ```text
+//! cc-switch conversations cat <id> [--format unified|raw]
+//! cc-switch profile env [--cwd <dir>] [--shell sh|fish|powershell]
+//! cc-switch profile hook <bash|zsh|fish|powershell>
+//! cc-switch statusline
+//! ```
+//!
+//! `unified` 格式逐行输出规范化后的消息（JSONL），每行一个对象：
+//! `{"index", "role", "timestamp", "blocks": [{"type": "text" | "thinking" | "toolUse" | "toolResult", ...}]}`，
+//! 字段与应用内阅读模式一致，Claude 与 Codex 会话输出相同结构。`raw` 格式原样输出会话文件。
+
+use std::io::{self, Write};
+
+const USAGE: &str = "\
+用法:
+  cc-switch conversations cat <id> [--format unified|raw]
+
+参数:
+  <id>        对话 id（会话文件名）、sessionId、文件路径，或其唯一前缀
+  --format    unified（默认）：规范化消息流，每行一个 JSON 对象
+              raw：原样输出会话 JSONL 文件
+";
+
+const PROFILE_USAGE: &str = "\
+用法:
+  cc-switch profile env [--cwd <dir>] [--shell sh|fish|powershell]
+  cc-switch profile hook <bash|zsh|fish|powershell>
+
+子命令:
+  env     输出当前目录所在工作区绑定供应商的环境变量（供 eval）
+  hook    输出 shell 钩子，进入项目目录时自动注入环境变量
+          例如在 ~/.zshrc 中加入: eval \"$(cc-switch profile hook zsh)\"
+";
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum CatFormat {
+    Unified,
+    Raw,
+}
+
+/// 解析命令行参数；非 CLI 子命令时返回 None，由调用方继续启动 GUI
+pub fn run(args: &[String]) -> Option<i32> {
+    let (command, rest) = args.split_first()?;
+    let result = match command.as_str() {
+        "conversations" => run_conversations(rest),
+        "profile" => run_profile(rest),
+        "statusline" => run_statusline(),
+        _ => return None,
+    };
+    Some(match result {
+        Ok(()) => 0,
+        Err(e) => {
+            eprintln!("错误: {}", e);
+            1
+        }
+    })
+}
+
+fn run_conversations(args: &[String]) -> Result<(), String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("cat") => cat(&args[1..]),
+        Some("-h") | Some("--help") | None => {
+            print!("{}", USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("未知子命令: {}\n\n{}", other, USAGE)),
+    }
+}
+
+/// Claude Code 状态栏：从标准输入读取会话 JSON，输出当前供应商、模型与费用
+fn run_statusline() -> Result<(), String> {
+    println!("{}", crate::statusline::render_from_stdin()?);
+    Ok(())
+}
+
+fn run_profile(args: &[String]) -> Result<(), String> {
+    match args.first().map(|s| s.as_str()) {
+        Some("env") => profile_env(&args[1..]),
+        Some("hook") => {
+            let shell = args
+                .get(1)
+                .ok_or_else(|| format!("缺少 shell 名称\n\n{}", PROFILE_USAGE))?;
+            let exe = crate::project_profiles::current_exe()?;
+            print!("{}", crate::project_profiles::shell_hook(shell, &exe)?);
+            Ok(())
+        }
+        Some("-h") | Some("--help") | None => {
+            print!("{}", PROFILE_USAGE);
+            Ok(())
+        }
+        Some(other) => Err(format!("未知子命令: {}\n\n{}", other, PROFILE_USAGE)),
+    }
+}
+
+fn profile_env(args: &[String]) -> Result<(), String> {
+    use crate::project_profiles::{ShellKind, PROFILE_VARS_ENV};
+
+    let mut cwd: Option<String> = None;
+    let mut shell = ShellKind::Posix;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        match arg.as_str() {
+            "--cwd" => {
+                cwd = Some(
+                    iter.next()
+                        .ok_or_else(|| "--cwd 需要一个值".to_string())?
+                        .clone(),
+                )
+            }
+            "--shell" => {
+                shell = ShellKind::parse(
+                    iter.next()
+                        .ok_or_else(|| "--shell 需要一个值".to_string())?,
+                )?
+            }
+            "-h" | "--help" => {
+                print!("{}", PROFILE_USAGE);
+                return Ok(());
+            }
+            other => return Err(format!("无法识别的参数: {}\n\n{}", other, PROFILE_USAGE)),
+        }
+    }
+    let cwd = match cwd {
+        Some(cwd) => cwd,
+        None => std::env::current_dir()
+            .map_err(|e| format!("获取当前目录失败: {}", e))?
+            .to_string_lossy()
+            .to_string(),
+    };
+
+    let config = crate::app_config::MultiAppConfig::load()?;
+    let env = crate::project_profiles::project_env(&config, &cwd);
+    for warning in &env.warnings {
+        eprintln!("cc-switch: {}", warning);
+    }
+    let previous: Vec<String> = std::env::var(PROFILE_VARS_ENV)
+        .map(|v| {
+            v.split(',')
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    print!(
+        "{}",
+        crate::project_profiles::render_env(&env, &previous, shell)
+    );
+    Ok(())
+}
+
+fn parse_format(value: &str) -> Result<CatFormat, String> {
+    match value {
+        "unified" => Ok(CatFormat::Unified),
+        "raw" => Ok(CatFormat::Raw),
+        other => Err(format!("不支持的格式: {}（可选 unified、raw）", other)),
+    }
+}
+
+fn cat(args: &[String]) -> Result<(), String> {
+    let mut id: Option<&str> = None;
+    let mut format = CatFormat::Unified;
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        if let Some(value) = arg.strip_prefix("--format=") {
+            format = parse_format(value)?;
+        } else if arg == "--format" {
+            let value = iter
+                .next()
+                .ok_or_else(|| "--format 需要一个值".to_string())?;
+            format = parse_format(value)?;
+        } else if arg == "-h" || arg == "--help" {
+            print!("{}", USAGE);
+            return Ok(());
+        } else if id.is_none() && !arg.starts_with("--") {
+            id = Some(arg);
+        } else {
+            return Err(format!("无法识别的参数: {}\n\n{}", arg, USAGE));
+        }
+    }
+    let id = id.ok_or_else(|| format!("缺少对话 id\n\n{}", USAGE))?;
+    let meta = crate::conversation::find_conversation(id)?;
+
+    let stdout = io::stdout();
+    let mut out = io::BufWriter::new(stdout.lock());
+    let result = match format {
+        CatFormat::Raw => {
+            let content = crate::conversation::read_conversation_content(&meta.file_path)?;
+            out.write_all(content.as_bytes())
+        }
+        CatFormat::Unified => {
+            let messages = crate::conversation::read_conversation_messages(
+                &meta.file_path,
+                &crate::conversation::ReadingOptions::default(),
+            )?;
+            messages.iter().try_for_each(|message| {
+                serde_json::to_writer(&mut out, message)?;
+                out.write_all(b"\n")
+            })
+        }
+    }
+    .and_then(|_| out.flush());
+
+    match result {
+        // 下游分页器提前退出时静默结束
+        Err(e) if e.kind() == io::ErrorKind::BrokenPipe => Ok(()),
+        other => other.map_err(|e| format!("写入标准输出失败: {}", e)),
+    }
+}
@@ -0,0 +1,233 @@
+use pulldown_cmark::{CodeBlockKind, Event, Parser, Tag, TagEnd};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::conversation::{
+    read_conversation_messages, truncate_chars, ReadingBlock, ReadingOptions, RoleFilter,
+};
+use crate::export_naming::{export_naming_settings, reserve_export_path, CollisionPolicy};
+
+/// 代码块前文的最大字符数
+const CONTEXT_MAX_CHARS: usize = 300;
+
+/// 从助手消息中提取的代码块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodeBlock {
+    /// 所在消息的条目序号（与阅读模式的 index 一致）
+    pub message_index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub message_uuid: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// 代码块的语言标记（``` 后的第一个词）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub language: Option<String>,
+    /// 语言标记中附带的文件名（如 ```rust:src/main.rs 或 ```python title=run.py）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub file_name: Option<String>,
+    pub code: String,
+    /// 代码块之前最近的一段文字，通常说明了代码的用途
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+    /// 写入文件时的路径
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub written_to: Option<String>,
+}
+
+/// 拆分 ``` 后的信息串：(语言, 文件名)
+fn parse_info(info: &str) -> (Option<String>, Option<String>) {
+    let mut tokens = info.split_whitespace();
+    let Some(first) = tokens.next() else {
+        return (None, None);
+    };
+    let (language, mut file_name) = match first.split_once(':') {
+        Some((lang, file)) => (lang, Some(file)),
+        None => (first, None),
+    };
+    for token in tokens {
+        if file_name.is_some() {
+            break;
+        }
+        let candidate = token
+            .strip_prefix("title=")
+            .or_else(|| token.strip_prefix("file="))
+            .unwrap_or(token)
+            .trim_matches(|c| c == '"' || c == '\'');
+        if candidate.contains('.') {
+            file_name = Some(candidate);
+        }
+    }
+    let non_empty = |s: &str| Some(s.to_string()).filter(|s| !s.is_empty());
+    (non_empty(language), file_name.and_then(non_empty))
+}
+
+/// Markdown 中的一个围栏代码块
+struct Fenced {
+    language: Option<String>,
+    file_name: Option<String>,
+    code: String,
+    context: Option<String>,
+}
+
+/// 解析一段 Markdown 中的围栏代码块
+fn blocks_in_text(text: &str) -> Vec<Fenced> {
+    let mut out = Vec::new();
+    let mut context: Option<String> = None;
+    let mut paragraph = String::new();
+    let mut current: Option<Fenced> = None;
+
+    for event in Parser::new(text) {
+        match event {
+            Event::Start(Tag::CodeBlock(CodeBlockKind::Fenced(info))) => {
+                let (language, file_name) = parse_info(&info);
+                current = Some(Fenced {
+                    language,
+                    file_name,
+                    code: String::new(),
+                    context: context.clone(),
+                });
+            }
+            Event::End(TagEnd::CodeBlock) => {
+                if let Some(fenced) = current.take() {
+                    if !fenced.code.trim().is_empty() {
+                        out.push(fenced);
+                    }
+                }
+            }
+            Event::Text(t) | Event::Code(t) => match current.as_mut() {
+                Some(fenced) => fenced.code.push_str(&t),
+                None => paragraph.push_str(&t),
+            },
+            Event::SoftBreak | Event::HardBreak if current.is_none() => paragraph.push(' '),
+            Event::End(TagEnd::Paragraph | TagEnd::Heading(_) | TagEnd::Item) => {
+                let trimmed = paragraph.trim();
+                if !trimmed.is_empty() {
+                    context = Some(truncate_chars(trimmed, CONTEXT_MAX_CHARS));
+                }
+                paragraph.clear();
+            }
+            _ => {}
+        }
+    }
+    out
+}
+
+fn extension_for(language: Option<&str>) -> &'static str {
+    match language.map(|l| l.to_ascii_lowercase()).as_deref() {
+        Some("rust" | "rs") => "rs",
+        Some("python" | "py") => "py",
+        Some("javascript" | "js") => "js",
+        Some("typescript" | "ts") => "ts",
+        Some("tsx") => "tsx",
+        Some("jsx") => "jsx",
+        Some("bash" | "sh" | "shell" | "zsh") => "sh",
+        Some("powershell" | "ps1") => "ps1",
+        Some("json") => "json",
+        Some("yaml" | "yml") => "yaml",
+        Some("toml") => "toml",
+        Some("html") => "html",
+        Some("css") => "css",
+        Some("sql") => "sql",
+        Some("go" | "golang") => "go",
+        Some("java") => "java",
+        Some("kotlin" | "kt") => "kt",
+        Some("swift") => "swift",
+        Some("c") => "c",
+        Some("cpp" | "c++" | "cc") => "cpp",
+        Some("csharp" | "cs" | "c#") => "cs",
+        Some("ruby" | "rb") => "rb",
+        Some("php") => "php",
+        Some("markdown" | "md") => "md",
+        Some("dockerfile") => "dockerfile",
+        Some("diff" | "patch") => "diff",
+        _ => "txt",
+    }
+}
+
+/// 写入目标目录：有文件名时使用其末级文件名，否则为 <序号>.<扩展名>；
+/// 同名文件按设置中的冲突策略处理，返回 None 表示已跳过
+fn write_block(
+    dest: &Path,
+    ordinal: usize,
+    block: &CodeBlock,
+    policy: CollisionPolicy,
+) -> Result<Option<PathBuf>, String> {
+    let hinted = block
+        .file_name
+        .as_deref()
+        .and_then(|name| Path::new(name).file_name())
+        .map(|name| name.to_string_lossy().to_string());
+    let (stem, extension) = match hinted.as_deref().and_then(|n| n.rsplit_once('.')) {
+        Some((stem, ext)) if !stem.is_empty() => (stem.to_string(), ext.to_string()),
+        _ => (
+            format!("{:03}", ordinal),
+            extension_for(block.language.as_deref()).to_string(),
+        ),
+    };
+    let Some(target) = reserve_export_path(dest, Path::new(&stem), &extension, policy)? else {
+        return Ok(None);
+    };
+    crate::config::write_text_file(&target, &block.code)?;
+    Ok(Some(target))
+}
+
+/// 提取对话中助手消息里的围栏代码块（按出现顺序），附带语言、文件名与前文说明；
+/// 指定 dest_dir 时将每个代码块写入单独的文件
+pub fn extract_code_blocks(
+    file_path: &str,
+    language: Option<&str>,
+    dest_dir: Option<&str>,
+) -> Result<Vec<CodeBlock>, String> {
+    let messages = read_conversation_messages(
+        file_path,
+        &ReadingOptions {
+            role: RoleFilter::Assistant,
+            collapse_tools: true,
+            strip_thinking: true,
+        },
+    )?;
+
+    let mut blocks = Vec::new();
+    for message in &messages {
+        for block in &message.blocks {
+            let ReadingBlock::Text { text } = block else {
+                continue;
+            };
+            for fenced in blocks_in_text(text) {
+                if language.is_some_and(|wanted| {
+                    !fenced
+                        .language
+                        .as_deref()
+                        .is_some_and(|l| l.eq_ignore_ascii_case(wanted))
+                }) {
+                    continue;
+                }
+                blocks.push(CodeBlock {
+                    message_index: message.index,
+                    message_uuid: message.uuid.clone(),
+                    timestamp: message.timestamp.clone(),
+                    language: fenced.language,
+                    file_name: fenced.file_name,
+                    code: fenced.code,
+                    context: fenced.context,
+                    written_to: None,
+                });
+            }
+        }
+    }
+
+    if let Some(dest) = dest_dir {
+        let dest = Path::new(dest);
+        let (_, policy) = export_naming_settings();
+        let mut written = 0;
+        for (i, block) in blocks.iter_mut().enumerate() {
+            if let Some(target) = write_block(dest, i + 1, block, policy)? {
+                block.written_to = Some(target.to_string_lossy().to_string());
+                written += 1;
+            }
+        }
+        log::info!("已导出 {} 个代码块到 {}", written, dest.display());
+    }
+    Ok(blocks)
+}
@@ -0,0 +1,198 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+
+use crate::codex_config::get_codex_config_dir;
+
+/// Codex 持续累积的历史与日志
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CodexCacheTarget {
+    /// ~/.codex/history.jsonl：输入历史，每行一条
+    History,
+    /// ~/.codex/log：运行日志目录
+    Logs,
+}
+
+pub const ALL_TARGETS: [CodexCacheTarget; 2] = [CodexCacheTarget::History, CodexCacheTarget::Logs];
+
+impl CodexCacheTarget {
+    fn path(self) -> PathBuf {
+        match self {
+            CodexCacheTarget::History => get_codex_config_dir().join("history.jsonl"),
+            CodexCacheTarget::Logs => get_codex_config_dir().join("log"),
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            CodexCacheTarget::History => "Codex history",
+            CodexCacheTarget::Logs => "Codex logs",
+        }
+    }
+}
+
+/// 清理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum CodexCacheAction {
+    /// 只保留最后 keep 条记录（日志按行计算，逐个文件截断）
+    Truncate { keep: usize },
+    /// 全部清空
+    Clear,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexCacheSummary {
+    pub target: CodexCacheTarget,
+    pub path: String,
+    pub exists: bool,
+    pub bytes: u64,
+    /// history 为记录条数，日志为文件数
+    pub entries: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexCachePurge {
+    pub target: CodexCacheTarget,
+    /// 删除的记录条数（日志为行数）
+    pub removed_entries: usize,
+    pub freed_bytes: u64,
+}
+
+fn log_files() -> Vec<(PathBuf, u64)> {
+    let Ok(entries) = fs::read_dir(CodexCacheTarget::Logs.path()) else {
+        return Vec::new();
+    };
+    entries
+        .flatten()
+        .filter_map(|entry| {
+            let meta = entry.metadata().ok()?;
+            meta.is_file().then(|| (entry.path(), meta.len()))
+        })
+        .collect()
+}
+
+fn line_count(path: &std::path::Path) -> usize {
+    fs::read(path)
+        .map(|bytes| {
+            bytes
+                .split(|b| *b == b'\n')
+                .filter(|l| !l.is_empty())
+                .count()
+        })
+        .unwrap_or(0)
+}
+
+pub fn summary(target: CodexCacheTarget) -> CodexCacheSummary {
+    let path = target.path();
+    let (bytes, entries) = match target {
+        CodexCacheTarget::History => (
+            fs::metadata(&path).map(|m| m.len()).unwrap_or(0),
+            line_count(&path),
+        ),
+        CodexCacheTarget::Logs => {
+            let files = log_files();
+            (files.iter().map(|(_, size)| size).sum(), files.len())
+        }
+    };
+    CodexCacheSummary {
+        target,
+        exists: path.exists(),
+        path: path.to_string_lossy().to_string(),
+        bytes,
+        entries,
+    }
+}
+
+pub fn summaries() -> Vec<CodexCacheSummary> {
+    ALL_TARGETS.iter().map(|target| summary(*target)).collect()
+}
+
+/// 截断单个按行记录的文件，返回 (删除行数, 释放字节数)
+fn truncate_lines(path: &std::path::Path, keep: usize) -> Result<(usize, u64), String> {
+    let bytes = fs::read(path).map_err(|e| format!("读取文件失败: {}: {}", path.display(), e))?;
+    let lines: Vec<&[u8]> = bytes
+        .split(|b| *b == b'\n')
+        .filter(|l| !l.is_empty())
+        .collect();
+    if lines.len() <= keep {
+        return Ok((0, 0));
+    }
+    let mut kept = lines[lines.len() - keep..].join(&b'\n');
+    if !kept.is_empty() {
+        kept.push(b'\n');
+    }
+    crate::config::atomic_write(path, &kept)?;
+    Ok((
+        lines.len() - keep,
+        (bytes.len() as u64).saturating_sub(kept.len() as u64),
+    ))
+}
+
+/// 清理历史或日志。该操作不可撤销，confirm 必须为 true；
+/// Codex 运行时会持续追加 history.jsonl，此时拒绝改写
+pub fn purge(
+    target: CodexCacheTarget,
+    action: CodexCacheAction,
+    confirm: bool,
+) -> Result<CodexCachePurge, String> {
+    if !confirm {
+        return Err("清理操作不可撤销，请确认后再执行".to_string());
+    }
+    let mut result = CodexCachePurge {
+        target,
+        removed_entries: 0,
+        freed_bytes: 0,
+    };
+    match target {
+        CodexCacheTarget::History => {
+            let path = target.path();
+            if !path.exists() {
+                return Ok(result);
+            }
+            let running = crate::active_sessions::running_processes()
+                .iter()
+                .any(|p| p.app_type == "codex");
+            if running {
+                return Err("检测到正在运行的 Codex 进程，请退出后再清理输入历史".to_string());
+            }
+            let (removed, freed) = match action {
+                CodexCacheAction::Truncate { keep } => truncate_lines(&path, keep)?,
+                CodexCacheAction::Clear => {
+                    let size = fs::metadata(&path).map(|m| m.len()).unwrap_or(0);
+                    let lines = line_count(&path);
+                    crate::config::atomic_write(&path, b"")?;
+                    (lines, size)
+                }
+            };
+            result.removed_entries = removed;
+            result.freed_bytes = freed;
+        }
+        CodexCacheTarget::Logs => {
+            for (path, size) in log_files() {
+                match action {
+                    CodexCacheAction::Truncate { keep } => {
+                        let (removed, freed) = truncate_lines(&path, keep)?;
+                        result.removed_entries += removed;
+                        result.freed_bytes += freed;
+                    }
+                    CodexCacheAction::Clear => {
+                        result.removed_entries += line_count(&path);
+                        crate::config::delete_file(&path)?;
+                        result.freed_bytes += size;
+                    }
+                }
+            }
+        }
+    }
+    log::info!(
+        "已清理 {}: 删除 {} 条记录，释放 {} 字节",
+        target.label(),
+        result.removed_entries,
+        result.freed_bytes
+    );
+    Ok(result)
+}
@@ -64,6 +64,9 @@ pub fn write_codex_live_atomic(auth: &Value, config_text_opt: Option<&str>) -> R
             .map_err(|e| format!("创建 Codex 目录失败: {}: {}", parent.display(), e))?;
     }
 
+    // 以 config.toml 的锁保护 auth.json + config.toml 的整个读取/写入/回滚过程
+    let _lock = crate::file_lock::lock_file(&config_path)?;
+
     // 读取旧内容用于回滚
     let old_auth = if auth_path.exists() {
         Some(
@@ -108,7 +111,7 @@ pub fn write_codex_live_atomic(auth: &Value, config_text_opt: Option<&str>) -> R
         } else {
             let _ = delete_file(&auth_path);
         }
-        return Err(e);
+        return Err(e.into());
     }
 
     Ok(())
@@ -0,0 +1,330 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use toml::{Table as TomlTable, Value as TomlValue};
+use toml_edit::{DocumentMut, Item, Table as EditTable};
+
+use crate::codex_config::{get_codex_config_path, read_and_validate_codex_config_text};
+
+/// 顶层默认 profile 键
+const DEFAULT_PROFILE_KEY: &str = "profile";
+const PROFILES_KEY: &str = "profiles";
+
+/// 取值受限的 profile 字段及其可选值
+pub(crate) const ENUM_FIELDS: &[(&str, &[&str])] = &[
+    (
+        "approval_policy",
+        &["untrusted", "on-failure", "on-request", "never"],
+    ),
+    (
+        "sandbox_mode",
+        &["read-only", "workspace-write", "danger-full-access"],
+    ),
+    (
+        "model_reasoning_effort",
+        &["minimal", "low", "medium", "high"],
+    ),
+];
+
+/// Codex config.toml 中的 [profiles.<name>]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexProfile {
+    pub name: String,
+    /// 是否为顶层 `profile` 指定的默认 profile
+    pub is_default: bool,
+    /// profile 表内容（model、model_provider、approval_policy 等），以 JSON 对象表示
+    pub settings: Value,
+}
+
+fn validate_profile_name(name: &str) -> Result<(), String> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!(
+            "profile 名称只能包含字母、数字、连字符和下划线: {}",
+            name
+        ))
+    }
+}
+
+/// 将前端传入的 JSON 对象转换为 TOML 表；null 字段视为未设置
+fn settings_to_table(settings: &Value) -> Result<TomlTable, String> {
+    let obj = settings
+        .as_object()
+        .ok_or_else(|| "profile 设置必须是 JSON 对象".to_string())?;
+    let mut table = TomlTable::new();
+    for (key, value) in obj {
+        if value.is_null() {
+            continue;
+        }
+        let value = TomlValue::try_from(value)
+            .map_err(|e| format!("profile 字段 {} 无法转换为 TOML: {}", key, e))?;
+        table.insert(key.clone(), value);
+    }
+    for (key, allowed) in ENUM_FIELDS {
+        let Some(value) = table.get(*key) else {
+            continue;
+        };
+        match value.as_str() {
+            Some(v) if allowed.contains(&v) => {}
+            _ => return Err(format!("{} 的取值必须为 {} 之一", key, allowed.join(" / "))),
+        }
+    }
+    if table.get("model").is_some_and(|v| !v.is_str()) {
+        return Err("model 必须为字符串".to_string());
+    }
+    Ok(table)
+}
+
+fn table_to_settings(table: &TomlTable) -> Value {
+    serde_json::to_value(table).unwrap_or_else(|_| Value::Object(Default::default()))
+}
+
+pub(crate) fn read_root() -> Result<TomlTable, String> {
+    let text = read_and_validate_codex_config_text()?;
+    if text.trim().is_empty() {
+        return Ok(TomlTable::new());
+    }
+    toml::from_str(&text).map_err(|e| format!("解析 config.toml 失败: {}", e))
+}
+
+fn profiles_table(root: &TomlTable) -> Result<Option<&TomlTable>, String> {
+    match root.get(PROFILES_KEY) {
+        None => Ok(None),
+        Some(value) => value
+            .as_table()
+            .map(Some)
+            .ok_or_else(|| "[profiles] 必须是表".to_string()),
+    }
+}
+
+fn profiles_table_mut(doc: &mut EditTable) -> Result<&mut EditTable, String> {
+    doc.entry(PROFILES_KEY)
+        .or_insert_with(|| {
+            // 隐式表：只输出 [profiles.<name>]，不单独输出 [profiles]
+            let mut table = EditTable::new();
+            table.set_implicit(true);
+            Item::Table(table)
+        })
+        .as_table_mut()
+        .ok_or_else(|| "[profiles] 必须是表".to_string())
+}
+
+fn default_profile(root: &TomlTable) -> Option<&str> {
+    root.get(DEFAULT_PROFILE_KEY).and_then(|v| v.as_str())
+}
+
+fn edited_default_profile(doc: &EditTable) -> Option<&str> {
+    doc.get(DEFAULT_PROFILE_KEY).and_then(Item::as_str)
+}
+
+fn profile_from(root: &TomlTable, name: &str, table: &TomlTable) -> CodexProfile {
+    CodexProfile {
+        name: name.to_string(),
+        is_default: default_profile(root) == Some(name),
+        settings: table_to_settings(table),
+    }
+}
+
+/// 将 TOML 表转换为可写入文档的 toml_edit 表
+fn to_edit_table(table: &TomlTable) -> Result<EditTable, String> {
+    let text = toml::to_string(table).map_err(|e| format!("序列化 profile 失败: {}", e))?;
+    let parsed = text
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("序列化 profile 失败: {}", e))?;
+    // 逐项放入新表，使其在文档中的位置由插入位置决定
+    let mut result = EditTable::new();
+    for (key, item) in parsed.into_table() {
+        result.insert(&key, item);
+    }
+    Ok(result)
+}
+
+/// 写入单个键：已存在时原位替换，保留键前注释与行尾注释
+pub(crate) fn set_item(table: &mut EditTable, key: &str, mut item: Item) {
+    match table.get_mut(key) {
+        Some(existing) => {
+            if let (Some(old), Some(new)) = (existing.as_value(), item.as_value_mut()) {
+                *new.decor_mut() = old.decor().clone();
+            }
+            *existing = item;
+        }
+        None => {
+            table.insert(key, item);
+        }
+    }
+}
+
+/// 以 source 的内容更新表：已有键保留位置与注释，source 中没有的键被移除
+fn replace_entries(table: &mut EditTable, source: EditTable) {
+    table.retain(|key, _| source.contains_key(key));
+    for (key, item) in source {
+        set_item(table, &key, item);
+    }
+}
+
+/// 在锁保护下读取、修改并写回 config.toml；通过 toml_edit 编辑，未修改的内容
+/// （包括注释、键顺序与格式）原样保留
+pub(crate) fn modify<T>(
+    f: impl FnOnce(&mut DocumentMut) -> Result<T, String>,
+) -> Result<T, String> {
+    let path = get_codex_config_path();
+    let _lock = crate::file_lock::lock_file(&path)?;
+    let text = read_and_validate_codex_config_text()?;
+    let mut doc = text
+        .parse::<DocumentMut>()
+        .map_err(|e| format!("解析 config.toml 失败: {}", e))?;
+    let result = f(&mut doc)?;
+    crate::config::write_text_file(&path, &doc.to_string())?;
+    Ok(result)
+}
+
+/// 读取单个 profile
+fn get_profile(name: &str) -> Result<CodexProfile, String> {
+    let root = read_root()?;
+    profiles_table(&root)?
+        .and_then(|profiles| profiles.get(name))
+        .and_then(|value| value.as_table())
+        .map(|table| profile_from(&root, name, table))
+        .ok_or_else(|| format!("profile 不存在: {}", name))
+}
+
+/// 列出所有 profile
+pub fn list_profiles() -> Result<Vec<CodexProfile>, String> {
+    let root = read_root()?;
+    let Some(profiles) = profiles_table(&root)? else {
+        return Ok(Vec::new());
+    };
+    Ok(profiles
+        .iter()
+        .filter_map(|(name, value)| {
+            value
+                .as_table()
+                .map(|table| profile_from(&root, name, table))
+        })
+        .collect())
+}
+
+/// 新建 profile
+pub fn create_profile(name: &str, settings: &Value) -> Result<CodexProfile, String> {
+    let name = name.trim();
+    validate_profile_name(name)?;
+    let table = to_edit_table(&settings_to_table(settings)?)?;
+    modify(|doc| {
+        let profiles = profiles_table_mut(doc)?;
+        if profiles.contains_key(name) {
+            return Err(format!("profile 已存在: {}", name));
+        }
+        profiles.insert(name, Item::Table(table));
+        Ok(())
+    })?;
+    get_profile(name)
+}
+
+/// 修改 profile：以 settings 替换整个 profile 表；new_name 非空时同时重命名
+/// （若其为默认 profile，顶层 `profile` 随之更新）
+pub fn update_profile(
+    name: &str,
+    settings: &Value,
+    new_name: Option<&str>,
+) -> Result<CodexProfile, String> {
+    let table = to_edit_table(&settings_to_table(settings)?)?;
+    let target = new_name
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .unwrap_or(name)
+        .to_string();
+    validate_profile_name(&target)?;
+    modify(|doc| {
+        let profiles = profiles_table_mut(doc)?;
+        if !profiles.contains_key(name) {
+            return Err(format!("profile 不存在: {}", name));
+        }
+        if target != name && profiles.contains_key(&target) {
+            return Err(format!("profile 已存在: {}", target));
+        }
+        // 原表存在时原地更新，保留表头注释与在文档中的位置
+        let item = match profiles.remove(name) {
+            Some(Item::Table(mut existing)) => {
+                replace_entries(&mut existing, table);
+                Item::Table(existing)
+            }
+            _ => Item::Table(table),
+        };
+        if target == name {
+            profiles.insert(name, item);
+        } else {
+            profiles.insert(&target, item);
+            if edited_default_profile(doc) == Some(name) {
+                set_item(doc, DEFAULT_PROFILE_KEY, toml_edit::value(target.as_str()));
+            }
+        }
+        Ok(())
+    })?;
+    get_profile(&target)
+}
+
+/// 复制 profile
+pub fn duplicate_profile(name: &str, new_name: &str) -> Result<CodexProfile, String> {
+    let new_name = new_name.trim();
+    validate_profile_name(new_name)?;
+    modify(|doc| {
+        let profiles = profiles_table_mut(doc)?;
+        let mut table = profiles
+            .get(name)
+            .and_then(Item::as_table)
+            .cloned()
+            .ok_or_else(|| format!("profile 不存在: {}", name))?;
+        if profiles.contains_key(new_name) {
+            return Err(format!("profile 已存在: {}", new_name));
+        }
+        // 表头注释属于原 profile，不随之复制
+        table.decor_mut().clear();
+        profiles.insert(new_name, Item::Table(table));
+        Ok(())
+    })?;
+    get_profile(new_name)
+}
+
+/// 删除 profile；若其为默认 profile，同时移除顶层 `profile`
+pub fn delete_profile(name: &str) -> Result<(), String> {
+    modify(|doc| {
+        let profiles = profiles_table_mut(doc)?;
+        if profiles.remove(name).is_none() {
+            return Err(format!("profile 不存在: {}", name));
+        }
+        if profiles.is_empty() {
+            doc.remove(PROFILES_KEY);
+        }
+        if edited_default_profile(doc) == Some(name) {
+            doc.remove(DEFAULT_PROFILE_KEY);
+        }
+        Ok(())
+    })
+}
+
+/// 设置默认 profile；None 表示移除顶层 `profile`
+pub fn set_default_profile(name: Option<&str>) -> Result<(), String> {
+    modify(|doc| {
+        match name.map(str::trim).filter(|n| !n.is_empty()) {
+            Some(name) => {
+                let exists = doc
+                    .get(PROFILES_KEY)
+                    .and_then(Item::as_table_like)
+                    .is_some_and(|p| p.contains_key(name));
+                if !exists {
+                    return Err(format!("profile 不存在: {}", name));
+                }
+                set_item(doc, DEFAULT_PROFILE_KEY, toml_edit::value(name));
+            }
+            None => {
+                doc.remove(DEFAULT_PROFILE_KEY);
+            }
+        }
+        Ok(())
+    })
+}
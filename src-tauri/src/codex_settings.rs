@@ -0,0 +1,112 @@
+use serde::{Deserialize, Serialize};
+
+use crate::codex_profiles::{modify, read_root, set_item, ENUM_FIELDS};
+
+/// 可快速切换的 config.toml 顶层键
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CodexSettingKey {
+    Model,
+    ModelReasoningEffort,
+    ApprovalPolicy,
+    SandboxMode,
+}
+
+impl CodexSettingKey {
+    /// config.toml 中对应的顶层键
+    fn key(self) -> &'static str {
+        match self {
+            CodexSettingKey::Model => "model",
+            CodexSettingKey::ModelReasoningEffort => "model_reasoning_effort",
+            CodexSettingKey::ApprovalPolicy => "approval_policy",
+            CodexSettingKey::SandboxMode => "sandbox_mode",
+        }
+    }
+
+    /// 取值受限时的可选值；model 为自由输入
+    fn allowed(self) -> Option<&'static [&'static str]> {
+        ENUM_FIELDS
+            .iter()
+            .find(|(key, _)| *key == self.key())
+            .map(|(_, allowed)| *allowed)
+    }
+}
+
+/// config.toml 中的常用顶层设置；未设置的为 None（由 Codex 使用默认值）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexQuickSettings {
+    pub model: Option<String>,
+    pub model_reasoning_effort: Option<String>,
+    pub approval_policy: Option<String>,
+    pub sandbox_mode: Option<String>,
+}
+
+/// 下拉框选项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CodexSettingOptions {
+    pub key: CodexSettingKey,
+    pub values: Vec<String>,
+}
+
+/// 读取 config.toml 中的常用顶层设置
+pub fn read_quick_settings() -> Result<CodexQuickSettings, String> {
+    let root = read_root()?;
+    let get = |key: CodexSettingKey| {
+        root.get(key.key())
+            .and_then(|v| v.as_str())
+            .map(|s| s.to_string())
+    };
+    Ok(CodexQuickSettings {
+        model: get(CodexSettingKey::Model),
+        model_reasoning_effort: get(CodexSettingKey::ModelReasoningEffort),
+        approval_policy: get(CodexSettingKey::ApprovalPolicy),
+        sandbox_mode: get(CodexSettingKey::SandboxMode),
+    })
+}
+
+/// 各受限字段的可选值
+pub fn setting_options() -> Vec<CodexSettingOptions> {
+    [
+        CodexSettingKey::ModelReasoningEffort,
+        CodexSettingKey::ApprovalPolicy,
+        CodexSettingKey::SandboxMode,
+    ]
+    .into_iter()
+    .filter_map(|key| {
+        key.allowed().map(|allowed| CodexSettingOptions {
+            key,
+            values: allowed.iter().map(|v| v.to_string()).collect(),
+        })
+    })
+    .collect()
+}
+
+/// 设置单个顶层键，其余内容保持不变；value 为空时移除该键（恢复 Codex 默认值）
+pub fn set_quick_setting(
+    key: CodexSettingKey,
+    value: Option<&str>,
+) -> Result<CodexQuickSettings, String> {
+    let value = value.map(str::trim).filter(|v| !v.is_empty());
+    if let (Some(value), Some(allowed)) = (value, key.allowed()) {
+        if !allowed.contains(&value) {
+            return Err(format!(
+                "{} 的取值必须为 {} 之一",
+                key.key(),
+                allowed.join(" / ")
+            ));
+        }
+    }
+    // 只改动这一个键，其余内容（注释、顺序、格式）保持不变
+    modify(|doc| {
+        match value {
+            Some(value) => set_item(doc, key.key(), toml_edit::value(value)),
+            None => {
+                doc.remove(key.key());
+            }
+        }
+        Ok(())
+    })?;
+    read_quick_settings()
+}
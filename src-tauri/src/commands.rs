@@ -9,38 +9,19 @@ use crate::app_config::AppType;
 use crate::claude_mcp;
 use crate::claude_plugin;
 use crate::codex_config;
-use crate::config::{self, get_claude_settings_path, ConfigStatus};
+use crate::config::{get_claude_settings_path, ConfigStatus};
+use crate::error::AppError;
 use crate::provider::{Provider, ProviderMeta};
 use crate::speedtest;
 use crate::store::AppState;
 
 fn validate_provider_settings(app_type: &AppType, provider: &Provider) -> Result<(), String> {
-    match app_type {
-        AppType::Claude => {
-            if !provider.settings_config.is_object() {
-                return Err("Claude 配置必须是 JSON 对象".to_string());
-            }
-        }
-        AppType::Codex => {
-            let settings = provider
-                .settings_config
-                .as_object()
-                .ok_or_else(|| "Codex 配置必须是 JSON 对象".to_string())?;
-            let auth = settings
-                .get("auth")
-                .ok_or_else(|| "Codex 配置缺少 auth 字段".to_string())?;
-            if !auth.is_object() {
-                return Err("Codex auth 配置必须是 JSON 对象".to_string());
-            }
-            if let Some(config_value) = settings.get("config") {
-                if !(config_value.is_string() || config_value.is_null()) {
-                    return Err("Codex config 字段必须是字符串".to_string());
-                }
-                if let Some(cfg_text) = config_value.as_str() {
-                    codex_config::validate_config_toml(cfg_text)?;
-                }
-            }
-        }
+    crate::app_adapter::adapter_for(app_type).validate(&provider.settings_config)?;
+    if let Some(extras) = provider.meta.as_ref().and_then(|m| m.extras.as_ref()) {
+        crate::provider_extras::validate(app_type, extras)?;
+    }
+    if let Some(rotation) = provider.meta.as_ref().and_then(|m| m.key_rotation.as_ref()) {
+        crate::key_rotation::validate(rotation)?;
     }
     Ok(())
 }
@@ -52,20 +33,17 @@ pub async fn get_providers(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<HashMap<String, Provider>, String> {
+) -> Result<HashMap<String, Provider>, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let config = state.config.lock()?;
 
     let manager = config
         .get_manager(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     Ok(manager.get_all_providers().clone())
 }
@@ -77,20 +55,17 @@ pub async fn get_current_provider(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let config = state.config.lock()?;
 
     let manager = config
         .get_manager(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     Ok(manager.current.clone())
 }
@@ -102,57 +77,41 @@ pub async fn add_provider(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-    provider: Provider,
-) -> Result<bool, String> {
+    mut provider: Provider,
+) -> Result<bool, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
     validate_provider_settings(&app_type, &provider)?;
+    crate::secrets::protect_rotation_keys(&app_type, &mut provider)?;
+    crate::key_rotation::apply_active(&app_type, &mut provider)?;
+    // 已设置密钥库时，新供应商的 API Key 直接加密保存
+    crate::secrets::protect_new_keys(&app_type, &mut provider)?;
 
     // 读取当前是否是激活供应商（短锁）
     let is_current = {
-        let config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let config = state.config.lock()?;
         let manager = config
             .get_manager(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
         manager.current == provider.id
     };
 
     // 若目标为当前供应商，则先写 live，成功后再落盘配置
     if is_current {
-        match app_type {
-            AppType::Claude => {
-                let settings_path = crate::config::get_claude_settings_path();
-                crate::config::write_json_file(&settings_path, &provider.settings_config)?;
-            }
-            AppType::Codex => {
-                let auth = provider
-                    .settings_config
-                    .get("auth")
-                    .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-                let cfg_text = provider
-                    .settings_config
-                    .get("config")
-                    .and_then(|v| v.as_str());
-                crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
-            }
-        }
+        let live_settings = crate::proxy::live_settings_for(&app_type, &provider)?;
+        crate::undo::capture("save-provider", &crate::undo::live_config_paths(&app_type));
+        crate::app_adapter::adapter_for(&app_type).write_live(&live_settings)?;
     }
 
     // 更新内存并保存配置
     {
-        let mut config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let mut config = state.config.lock()?;
         let manager = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
         manager
             .providers
             .insert(provider.id.clone(), provider.clone());
@@ -169,63 +128,62 @@ pub async fn update_provider(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-    provider: Provider,
-) -> Result<bool, String> {
+    mut provider: Provider,
+) -> Result<bool, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
     validate_provider_settings(&app_type, &provider)?;
+    crate::secrets::protect_rotation_keys(&app_type, &mut provider)?;
+    crate::key_rotation::apply_active(&app_type, &mut provider)?;
+    crate::backups::snapshot_before_write("pre-update");
 
     // 读取校验 & 是否当前（短锁）
-    let (exists, is_current) = {
-        let config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+    let (previous_settings, is_current) = {
+        let config = state.config.lock()?;
         let manager = config
             .get_manager(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
         (
-            manager.providers.contains_key(&provider.id),
+            manager
+                .providers
+                .get(&provider.id)
+                .map(|p| p.settings_config.clone()),
             manager.current == provider.id,
         )
     };
-    if !exists {
-        return Err(format!("供应商不存在: {}", provider.id));
+    if previous_settings.is_none() {
+        return Err(AppError::InvalidInput(crate::error::provider_not_found(
+            &provider.id,
+        )));
+    }
+
+    // 已加密的 API Key 在编辑后保持加密
+    if let Some(previous) = previous_settings {
+        crate::secrets::keep_protected(
+            &app_type,
+            &provider.id,
+            &previous,
+            &mut provider.settings_config,
+        )?;
     }
+    crate::secrets::protect_new_keys(&app_type, &mut provider)?;
 
     // 若更新的是当前供应商，先写 live 成功再保存
     if is_current {
-        match app_type {
-            AppType::Claude => {
-                let settings_path = crate::config::get_claude_settings_path();
-                crate::config::write_json_file(&settings_path, &provider.settings_config)?;
-            }
-            AppType::Codex => {
-                let auth = provider
-                    .settings_config
-                    .get("auth")
-                    .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-                let cfg_text = provider
-                    .settings_config
-                    .get("config")
-                    .and_then(|v| v.as_str());
-                crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
-            }
-        }
+        let live_settings = crate::proxy::live_settings_for(&app_type, &provider)?;
+        crate::undo::capture("save-provider", &crate::undo::live_config_paths(&app_type));
+        crate::app_adapter::adapter_for(&app_type).write_live(&live_settings)?;
     }
 
     // 更新内存并保存（保留/合并已有的 meta.custom_endpoints，避免丢失在编辑流程中新增的自定义端点）
     {
-        let mut config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let mut config = state.config.lock()?;
         let manager = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
         // 若已存在旧供应商，合并其 meta（尤其是 custom_endpoints）到新对象
         let merged_provider = if let Some(existing) = manager.providers.get(&provider.id) {
@@ -247,6 +205,15 @@ pub async fn update_provider(
                     updated.meta = Some(crate::provider::ProviderMeta {
                         custom_endpoints: merged_map,
                         usage_script: new_meta.usage_script.clone(),
+                        notes: new_meta.notes.clone(),
+                        local_server: new_meta.local_server.clone(),
+                        quota_endpoint: new_meta.quota_endpoint.clone(),
+                        needs_key: old_meta.needs_key,
+                        group_id: old_meta.group_id.clone(),
+                        proxy: old_meta.proxy.clone(),
+                        models: old_meta.models.clone(),
+                        extras: new_meta.extras.clone(),
+                        key_rotation: new_meta.key_rotation.clone(),
                     });
                 }
                 // 旧 meta 不存在：使用入参（可能为 None）
@@ -254,6 +221,14 @@ pub async fn update_provider(
                     updated.meta = maybe_new;
                 }
             }
+            // 补充了 API Key 后解除切换限制
+            if let Some(meta) = updated.meta.as_mut() {
+                if meta.needs_key
+                    && crate::provider_share::has_api_key(&app_type, &updated.settings_config)
+                {
+                    meta.needs_key = false;
+                }
+            }
 
             updated
         } else {
@@ -278,31 +253,30 @@ pub async fn delete_provider(
     app: Option<String>,
     appType: Option<String>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let mut config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut config = state.config.lock()?;
 
     let manager = config
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     // 检查是否为当前供应商
     if manager.current == id {
-        return Err("不能删除当前正在使用的供应商".to_string());
+        return Err(AppError::InvalidInput(crate::i18n::t(
+            "error.providerInUse",
+        )));
     }
 
     // 获取供应商信息
     let provider = manager
         .providers
         .get(&id)
-        .ok_or_else(|| format!("供应商不存在: {}", id))?
+        .ok_or_else(|| crate::error::provider_not_found(&id))?
         .clone();
 
     // 删除配置文件
@@ -318,6 +292,8 @@ pub async fn delete_provider(
             delete_file(&by_name)?;
             delete_file(&by_id)?;
         }
+        // Gemini 与适配器应用的供应商只保存在 config.json 中，没有副本文件
+        AppType::Gemini | AppType::OpenCode | AppType::Crush => {}
     }
 
     // 从管理器删除
@@ -327,178 +303,469 @@ pub async fn delete_provider(
     drop(config); // 释放锁
     state.save()?;
 
+    if crate::secrets::has_secret_refs(&app_type, &provider.settings_config) {
+        if let Err(e) = crate::secrets::remove_provider_secrets(&app_type, &id) {
+            log::warn!("清理供应商加密密钥失败: {}", e);
+        }
+    }
+
     Ok(true)
 }
 
-/// 切换供应商
+/// 预览切换供应商时 live 配置的键级差异（不写入任何文件）
 #[tauri::command]
-pub async fn switch_provider(
+pub async fn preview_switch(
     state: State<'_, AppState>,
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
     id: String,
-) -> Result<bool, String> {
+) -> Result<crate::switch_preview::SwitchPreview, String> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let mut config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    crate::switch_preview::preview_switch(&config, &app_type, &id)
+}
+
+/// 校验供应商配置（Base URL、API Key、模型名称、配置可序列化性），返回结构化问题列表
+#[tauri::command]
+pub async fn validate_provider(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    provider: Provider,
+    checkNetwork: Option<bool>,
+) -> Result<crate::provider_validation::ProviderValidation, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    Ok(crate::provider_validation::validate_provider(
+        &app_type,
+        &provider,
+        checkNetwork.unwrap_or(true),
+    )
+    .await)
+}
+
+/// 测试供应商连接：使用其端点与密钥发起最小请求，返回延迟、状态码与错误响应体
+#[tauri::command]
+pub async fn test_provider_connection(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    id: String,
+    timeoutSecs: Option<u64>,
+) -> Result<crate::provider_connection::ConnectionTestResult, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
 
-    // 为避免长期可变借用，尽快获取必要数据并缩小借用范围
     let provider = {
-        let manager = config
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        config
+            .get_manager(&app_type)
+            .and_then(|m| m.providers.get(&id))
+            .cloned()
+            .ok_or_else(|| crate::error::provider_not_found(&id))?
+    };
+    let provider = crate::secrets::resolve_provider(&app_type, &provider)?;
+    crate::provider_connection::test_provider_connection(&app_type, &provider, timeoutSecs).await
+}
+
+/// 从端点获取供应商的模型列表并缓存到 meta.models
+#[tauri::command]
+pub async fn fetch_provider_models(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    id: String,
+    timeoutSecs: Option<u64>,
+) -> Result<Vec<String>, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let provider = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        config
+            .get_manager(&app_type)
+            .and_then(|m| m.providers.get(&id))
+            .cloned()
+            .ok_or_else(|| crate::error::provider_not_found(&id))?
+    };
+    let provider = crate::secrets::resolve_provider(&app_type, &provider)?;
+    let models =
+        crate::provider_connection::fetch_models(&app_type, &provider, timeoutSecs).await?;
+
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        if let Some(provider) = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+            .and_then(|m| m.providers.get_mut(&id))
+        {
+            provider.meta.get_or_insert_with(Default::default).models = models.clone();
+        }
+    }
+    state.save()?;
+    Ok(models)
+}
 
-        // 检查供应商是否存在
+/// 读取 Claude 供应商的默认模型、快速模型与 opus / sonnet / haiku 别名映射
+#[tauri::command]
+pub async fn get_claude_provider_models(
+    state: State<'_, AppState>,
+    providerId: String,
+) -> Result<crate::claude_models::ClaudeModelSettings, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    let provider = config
+        .get_manager(&AppType::Claude)
+        .and_then(|m| m.providers.get(&providerId))
+        .ok_or_else(|| crate::error::provider_not_found(&providerId))?;
+    let available = provider
+        .meta
+        .as_ref()
+        .map(|m| m.models.as_slice())
+        .unwrap_or_default();
+    Ok(crate::claude_models::read(&provider.settings_config, available))
+}
+
+/// 修改 Claude 供应商的模型设置；当前供应商同时写入 live settings.json，
+/// 以后切换到该供应商时随之生效
+#[tauri::command]
+pub async fn set_claude_provider_models(
+    state: State<'_, AppState>,
+    providerId: String,
+    models: crate::claude_models::ClaudeModelSettings,
+) -> Result<crate::claude_models::ClaudeModelSettings, String> {
+    let app_type = AppType::Claude;
+    let (mut provider, is_current) = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
         let provider = manager
             .providers
-            .get(&id)
-            .ok_or_else(|| format!("供应商不存在: {}", id))?
-            .clone();
-        provider
+            .get(&providerId)
+            .cloned()
+            .ok_or_else(|| crate::error::provider_not_found(&providerId))?;
+        (provider, manager.current == providerId)
     };
+    crate::claude_models::apply(&mut provider.settings_config, &models)?;
+    validate_provider_settings(&app_type, &provider)?;
 
-    // SSOT 切换：先回填 live 配置到当前供应商，然后从内存写入目标主配置
-    match app_type {
-        AppType::Codex => {
-            use serde_json::Value;
-
-            // 回填：读取 live（auth.json + config.toml）写回当前供应商 settings_config
-            if !{
-                let cur = config
-                    .get_manager_mut(&app_type)
-                    .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                cur.current.is_empty()
-            } {
-                let auth_path = codex_config::get_codex_auth_path();
-                let config_path = codex_config::get_codex_config_path();
-                if auth_path.exists() {
-                    let auth: Value = crate::config::read_json_file(&auth_path)?;
-                    let config_str = if config_path.exists() {
-                        std::fs::read_to_string(&config_path).map_err(|e| {
-                            format!("读取 config.toml 失败: {}: {}", config_path.display(), e)
-                        })?
-                    } else {
-                        String::new()
-                    };
-
-                    let live = serde_json::json!({
-                        "auth": auth,
-                        "config": config_str,
-                    });
+    // 当前供应商：先写 live 成功再保存
+    if is_current {
+        let live_settings = crate::proxy::live_settings_for(&app_type, &provider)?;
+        crate::undo::capture("save-provider", &crate::undo::live_config_paths(&app_type));
+        crate::app_adapter::adapter_for(&app_type).write_live(&live_settings)?;
+    }
 
-                    let cur_id2 = {
-                        let m = config
-                            .get_manager(&app_type)
-                            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                        m.current.clone()
-                    };
-                    let m = config
-                        .get_manager_mut(&app_type)
-                        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                    if let Some(cur) = m.providers.get_mut(&cur_id2) {
-                        cur.settings_config = live;
-                    }
-                }
-            }
+    let available = provider
+        .meta
+        .as_ref()
+        .map(|m| m.models.clone())
+        .unwrap_or_default();
+    let result = crate::claude_models::read(&provider.settings_config, &available);
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        if let Some(manager) = config.get_manager_mut(&app_type) {
+            manager.providers.insert(provider.id.clone(), provider);
+        }
+    }
+    state.save()?;
+    Ok(result)
+}
+
+/// 根据缓存的模型列表推荐默认模型（优先最新的 sonnet）
+#[tauri::command]
+pub async fn suggest_claude_default_model(
+    state: State<'_, AppState>,
+    providerId: String,
+) -> Result<Option<String>, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    let provider = config
+        .get_manager(&AppType::Claude)
+        .and_then(|m| m.providers.get(&providerId))
+        .ok_or_else(|| crate::error::provider_not_found(&providerId))?;
+    Ok(provider
+        .meta
+        .as_ref()
+        .and_then(|m| crate::claude_models::suggest_default(&m.models)))
+}
 
-            // 切换：从目标供应商 settings_config 写入主配置（Codex 双文件原子+回滚）
-            let auth = provider
-                .settings_config
-                .get("auth")
-                .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
-            let cfg_text = provider
-                .settings_config
-                .get("config")
-                .and_then(|v| v.as_str());
-            crate::codex_config::write_codex_live_atomic(auth, cfg_text)?;
+/// 换用供应商的下一个 Key；为当前供应商时先写 live 成功再保存
+pub(crate) fn rotate_provider_key(
+    state: &AppState,
+    app_type: &AppType,
+    provider_id: &str,
+) -> Result<crate::key_rotation::ActiveKey, String> {
+    let (mut provider, is_current) = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager(app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        let provider = manager
+            .providers
+            .get(provider_id)
+            .cloned()
+            .ok_or_else(|| crate::error::provider_not_found(&provider_id))?;
+        (provider, manager.current == provider_id)
+    };
+    if !crate::key_rotation::advance(app_type, &mut provider)? {
+        return Err(format!("供应商 {} 未配置多个 Key", provider.name));
+    }
+    let active = crate::key_rotation::active_key(&provider)
+        .ok_or_else(|| format!("供应商 {} 未配置多个 Key", provider.name))?;
+
+    if is_current {
+        let live_settings = crate::proxy::live_settings_for(app_type, &provider)?;
+        crate::undo::capture("rotate-key", &crate::undo::live_config_paths(app_type));
+        crate::app_adapter::adapter_for(app_type).write_live(&live_settings)?;
+    }
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        if let Some(manager) = config.get_manager_mut(app_type) {
+            manager.providers.insert(provider.id.clone(), provider);
         }
-        AppType::Claude => {
-            use crate::config::{read_json_file, write_json_file};
+    }
+    state.save()?;
 
-            let settings_path = get_claude_settings_path();
+    crate::events::publish(
+        crate::events::AppEvent::new(crate::key_rotation::EVENT_PROVIDER_KEY_ROTATED)
+            .with_provider(app_type.as_str(), provider_id)
+            .with_data(serde_json::json!({ "index": active.index, "total": active.total })),
+    );
+    Ok(active)
+}
 
-            // 回填：读取 live settings.json 写回当前供应商 settings_config
-            if settings_path.exists() {
-                let cur_id = {
-                    let m = config
-                        .get_manager(&app_type)
-                        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                    m.current.clone()
-                };
-                if !cur_id.is_empty() {
-                    if let Ok(live) = read_json_file::<serde_json::Value>(&settings_path) {
-                        let m = config
-                            .get_manager_mut(&app_type)
-                            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                        if let Some(cur) = m.providers.get_mut(&cur_id) {
-                            cur.settings_config = live;
-                        }
-                    }
-                }
+/// 手动换用供应商的下一个 API Key
+#[tauri::command]
+pub async fn cycle_provider_key(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    providerId: String,
+) -> Result<crate::key_rotation::ActiveKey, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    rotate_provider_key(&state, &app_type, &providerId)
+}
+
+/// 并发测试所有（或指定应用的）供应商的首字节时间与补全往返时间，返回排序后的报告
+#[tauri::command]
+pub async fn benchmark_providers(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    timeoutSecs: Option<u64>,
+) -> Result<Vec<crate::provider_connection::ProviderBenchmark>, String> {
+    let filter = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()));
+
+    let providers = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let mut providers = Vec::new();
+        for app_type in AppType::all() {
+            if filter
+                .as_ref()
+                .is_some_and(|f| f.as_str() != app_type.as_str())
+            {
+                continue;
             }
+            if let Some(manager) = config.get_manager(&app_type) {
+                // 未解锁时加密的 API Key 无法解析，对应供应商会以认证失败计入结果
+                providers.extend(manager.providers.values().map(|p| {
+                    let provider = crate::secrets::resolve_provider(&app_type, p)
+                        .unwrap_or_else(|_| p.clone());
+                    (app_type.clone(), provider)
+                }));
+            }
+        }
+        providers
+    };
+    crate::provider_connection::benchmark_providers(providers, timeoutSecs).await
+}
+
+/// 切换供应商
+#[tauri::command]
+pub async fn switch_provider(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    id: String,
+    initiator: Option<crate::switch_history::SwitchInitiator>,
+    force: Option<bool>,
+) -> Result<bool, AppError> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
 
-            // 切换：从目标供应商 settings_config 写入主配置
-            if let Some(parent) = settings_path.parent() {
-                std::fs::create_dir_all(parent).map_err(|e| format!("创建目录失败: {}", e))?;
+    // 对应 CLI 正在运行时提示其仍使用旧配置，由用户确认后强制切换
+    crate::active_sessions::ensure_app_idle(&app_type, force.unwrap_or(false))?;
+
+    // 目标为本地推理服务时，先确认服务已在运行（需在持锁前完成异步探测）
+    let (target_endpoint, target_local_server, previous) = {
+        let config = state.config.lock()?;
+        let manager = config.get_manager(&app_type);
+        let target = manager.and_then(|m| m.providers.get(&id));
+        if let Some(p) = target.filter(|_| manager.is_some_and(|m| !m.is_provider_enabled(&id))) {
+            return Err(AppError::InvalidInput(crate::i18n::tf(
+                "error.providerGroupDisabled",
+                &[("name", &p.name)],
+            )));
+        }
+        if let Some(p) = target.filter(|p| p.needs_key()) {
+            return Err(AppError::InvalidInput(crate::i18n::tf(
+                "error.providerNeedsKey",
+                &[("name", &p.name)],
+            )));
+        }
+        let local_server = target
+            .filter(|p| p.category.as_deref() == Some("local"))
+            .and_then(|p| p.meta.as_ref())
+            .and_then(|m| m.local_server.clone());
+        let previous = manager.and_then(|m| {
+            m.providers.get(&m.current).map(|p| {
+                let stop = p
+                    .meta
+                    .as_ref()
+                    .and_then(|m| m.local_server.as_ref())
+                    .map(|c| c.stop_on_switch_away)
+                    .unwrap_or(false);
+                (p.id.clone(), p.name.clone(), stop)
+            })
+        });
+        (target.and_then(|p| p.endpoint(&app_type)), local_server, previous)
+    };
+    if let Some(url) = target_endpoint.filter(|u| crate::local_server::is_local_endpoint(u)) {
+        match target_local_server.as_ref() {
+            Some(local) => {
+                let key = format!("{}:{}", app_type.as_str(), id);
+                crate::local_server::start_and_wait(&key, &url, local).await?;
+            }
+            None => {
+                crate::local_server::ensure_running(&url).await?;
             }
+        }
+    }
+
+    crate::backups::snapshot_before_write("pre-switch");
+    crate::undo::capture_switch(
+        &app_type,
+        previous
+            .as_ref()
+            .map(|(id, _, _)| id.as_str())
+            .unwrap_or(""),
+    );
+
+    let mut config = state.config.lock()?;
 
-            // 不做归档，直接写入
-            write_json_file(&settings_path, &provider.settings_config)?;
+    // 为避免长期可变借用，尽快获取必要数据并缩小借用范围
+    let provider = {
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+
+        // 检查供应商是否存在
+        let mut provider = manager
+            .providers
+            .get(&id)
+            .ok_or_else(|| crate::error::provider_not_found(&id))?
+            .clone();
+        // 轮询策略：每次切换到该供应商时换用下一个 Key
+        if manager.current != id && crate::key_rotation::on_switch(&app_type, &mut provider)? {
+            manager.providers.insert(id.clone(), provider.clone());
+        }
+        provider
+    };
 
-            // 写入后回读 live，并回填到目标供应商的 SSOT，保证一致
-            if settings_path.exists() {
-                if let Ok(live_after) = read_json_file::<serde_json::Value>(&settings_path) {
+    // SSOT 切换：先回填 live 配置到当前供应商，然后从内存写入目标主配置
+    let adapter = crate::app_adapter::adapter_for(&app_type);
+
+    // 回填：读取 live 配置写回当前供应商 settings_config
+    if adapter.live_exists() {
+        let cur_id = {
+            let m = config
+                .get_manager(&app_type)
+                .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+            m.current.clone()
+        };
+        if !cur_id.is_empty() {
+            match adapter.read_live() {
+                Ok(mut live) => {
                     let m = config
                         .get_manager_mut(&app_type)
-                        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-                    if let Some(target) = m.providers.get_mut(&id) {
-                        target.settings_config = live_after;
+                        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+                    if let Some(cur) = m.providers.get_mut(&cur_id) {
+                        crate::secrets::keep_protected(
+                            &app_type,
+                            &cur_id,
+                            &cur.settings_config,
+                            &mut live,
+                        )?;
+                        if let Some(proxy) = crate::proxy::effective_proxy(cur) {
+                            crate::proxy::strip_from_settings(&app_type, &mut live, &proxy)?;
+                        }
+                        crate::statusline::strip_from_settings(&app_type, &mut live);
+                        crate::provider_extras::strip_from_settings(&app_type, &mut live, cur)?;
+                        cur.settings_config = live;
                     }
                 }
+                Err(e) => log::warn!("回填当前供应商失败，跳过: {}", e),
             }
         }
     }
 
+    // 切换：不做归档，直接写入（加密的 API Key 在此解析为真实值，并叠加代理）
+    let live_settings = crate::proxy::live_settings_for(&app_type, &provider)?;
+    adapter.write_live(&live_settings)?;
+
     // 更新当前供应商（短借用范围）
     {
         let manager = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-        manager.current = id;
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        manager.current = id.clone();
     }
 
-    // 对 Codex：切换完成后，同步 MCP 到 config.toml，并将最新的 config.toml 回填到当前供应商 settings_config.config
-    if let AppType::Codex = app_type {
-        // 1) 依据 SSOT 将启用的 MCP 投影到 ~/.codex/config.toml
-        crate::mcp::sync_enabled_to_codex(&config)?;
-
-        // 2) 读取投影后的 live config.toml 文本
-        let cfg_text_after = crate::codex_config::read_and_validate_codex_config_text()?;
+    // 写入覆盖了 MCP 字段的应用（如 Codex config.toml）：依据 SSOT 重新投影启用的 MCP
+    if adapter.write_replaces_mcp() {
+        crate::mcp::sync_enabled_for(&config, &app_type)?;
+    }
 
-        // 3) 回填到当前（目标）供应商的 settings_config.config，确保编辑面板读取到最新 MCP
-        let cur_id = {
-            let m = config
-                .get_manager(&app_type)
-                .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-            m.current.clone()
-        };
+    // 写入后回读 live，并回填到目标供应商的 SSOT，保证编辑面板读取到最新内容
+    if let Ok(mut live_after) = adapter.read_live() {
         let m = config
             .get_manager_mut(&app_type)
-            .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
-        if let Some(p) = m.providers.get_mut(&cur_id) {
-            if let Some(obj) = p.settings_config.as_object_mut() {
-                obj.insert(
-                    "config".to_string(),
-                    serde_json::Value::String(cfg_text_after),
-                );
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        if let Some(target) = m.providers.get_mut(&id) {
+            crate::secrets::keep_protected(
+                &app_type,
+                &id,
+                &target.settings_config,
+                &mut live_after,
+            )?;
+            if let Some(proxy) = crate::proxy::effective_proxy(target) {
+                crate::proxy::strip_from_settings(&app_type, &mut live_after, &proxy)?;
             }
+            crate::statusline::strip_from_settings(&app_type, &mut live_after);
+            crate::provider_extras::strip_from_settings(&app_type, &mut live_after, target)?;
+            target.settings_config = live_after;
         }
     }
 
@@ -508,6 +775,43 @@ pub async fn switch_provider(
     drop(config); // 释放锁
     state.save()?;
 
+    crate::notification_sinks::dispatch(
+        crate::notification_sinks::NotificationPayload::new(
+            crate::notification_sinks::NotificationEvent::Switch,
+            "供应商已切换",
+            format!("{} 已切换到 {}", app_type.as_str(), provider.name),
+        )
+        .with_provider(app_type.as_str(), &provider.id, &provider.name),
+    );
+
+    crate::events::publish(
+        crate::events::AppEvent::new(crate::events::EVENT_PROVIDER_SWITCHED)
+            .with_provider(app_type.as_str(), &provider.id)
+            .with_data(serde_json::json!({ "providerName": provider.name })),
+    );
+
+    // 切离本地供应商时，按配置停止由 cc-switch 启动的服务
+    if let Some((prev_id, _, true)) = &previous {
+        if *prev_id != provider.id {
+            crate::local_server::stop_launched(&format!("{}:{}", app_type.as_str(), prev_id));
+        }
+    }
+
+    if previous.as_ref().is_none_or(|(prev_id, _, _)| *prev_id != provider.id) {
+        let (from_id, from_name) = previous
+            .map(|(id, name, _)| (Some(id), Some(name)))
+            .unwrap_or_default();
+        crate::switch_history::record(&crate::switch_history::SwitchRecord {
+            timestamp: chrono::Utc::now().timestamp_millis(),
+            app_type: app_type.as_str().to_string(),
+            from_id,
+            from_name,
+            to_id: provider.id.clone(),
+            to_name: provider.name.clone(),
+            initiator: initiator.unwrap_or_default(),
+        });
+    }
+
     Ok(true)
 }
 
@@ -518,7 +822,7 @@ pub async fn import_default_config(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -526,10 +830,7 @@ pub async fn import_default_config(
 
     // 仅当 providers 为空时才从 live 导入一条默认项
     {
-        let config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let config = state.config.lock()?;
 
         if let Some(manager) = config.get_manager(&app_type) {
             if !manager.get_all_providers().is_empty() {
@@ -540,28 +841,19 @@ pub async fn import_default_config(
 
     // 根据应用类型导入配置
     // 读取当前主配置为默认供应商（不再写入副本文件）
-    let settings_config = match app_type {
-        AppType::Codex => {
-            let auth_path = codex_config::get_codex_auth_path();
-            if !auth_path.exists() {
-                return Err("Codex 配置文件不存在".to_string());
-            }
-            let auth: serde_json::Value =
-                crate::config::read_json_file::<serde_json::Value>(&auth_path)?;
-            let config_str = match crate::codex_config::read_and_validate_codex_config_text() {
-                Ok(s) => s,
-                Err(e) => return Err(e),
-            };
-            serde_json::json!({ "auth": auth, "config": config_str })
-        }
-        AppType::Claude => {
-            let settings_path = get_claude_settings_path();
-            if !settings_path.exists() {
-                return Err("Claude Code 配置文件不存在".to_string());
-            }
-            crate::config::read_json_file::<serde_json::Value>(&settings_path)?
-        }
-    };
+    let adapter = crate::app_adapter::adapter_for(&app_type);
+    if !adapter.live_exists() {
+        return Err(AppError::InvalidInput(crate::i18n::tf(
+            "error.liveConfigMissing",
+            &[("app", &adapter.display_name())],
+        )));
+    }
+    let mut settings_config = adapter.read_live()?;
+    if let Some(proxy) = crate::proxy::global_proxy() {
+        crate::proxy::strip_from_settings(&app_type, &mut settings_config, &proxy)?;
+    }
+    crate::statusline::strip_from_settings(&app_type, &mut settings_config);
+    adapter.validate(&settings_config)?;
 
     // 创建默认供应商（仅首次初始化）
     let provider = Provider::with_id(
@@ -572,14 +864,11 @@ pub async fn import_default_config(
     );
 
     // 添加到管理器
-    let mut config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut config = state.config.lock()?;
 
     let manager = config
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     manager.providers.insert(provider.id.clone(), provider);
     // 设置当前供应商为默认项
@@ -594,7 +883,7 @@ pub async fn import_default_config(
 
 /// 获取 Claude Code 配置状态
 #[tauri::command]
-pub async fn get_claude_config_status() -> Result<ConfigStatus, String> {
+pub async fn get_claude_config_status() -> Result<ConfigStatus, AppError> {
     Ok(crate::config::get_claude_config_status())
 }
 
@@ -605,7 +894,7 @@ pub async fn get_config_status(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<ConfigStatus, String> {
+) -> Result<ConfigStatus, AppError> {
     let app = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
@@ -613,22 +902,20 @@ pub async fn get_config_status(
 
     match app {
         AppType::Claude => Ok(crate::config::get_claude_config_status()),
-        AppType::Codex => {
-            use crate::codex_config::{get_codex_auth_path, get_codex_config_dir};
-            let auth_path = get_codex_auth_path();
-
-            // 放宽：只要 auth.json 存在即可认为已配置；config.toml 允许为空
-            let exists = auth_path.exists();
-            let path = get_codex_config_dir().to_string_lossy().to_string();
-
-            Ok(ConfigStatus { exists, path })
+        // 其他应用：只要 live 配置存在即可认为已配置，返回配置目录
+        _ => {
+            let adapter = crate::app_adapter::adapter_for(&app);
+            Ok(ConfigStatus {
+                exists: adapter.live_exists(),
+                path: adapter.config_dir().to_string_lossy().to_string(),
+            })
         }
     }
 }
 
 /// 获取 Claude Code 配置文件路径
 #[tauri::command]
-pub async fn get_claude_code_config_path() -> Result<String, String> {
+pub async fn get_claude_code_config_path() -> Result<String, AppError> {
     Ok(get_claude_settings_path().to_string_lossy().to_string())
 }
 
@@ -638,16 +925,13 @@ pub async fn get_config_dir(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<String, String> {
+) -> Result<String, AppError> {
     let app = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let dir = match app {
-        AppType::Claude => config::get_claude_config_dir(),
-        AppType::Codex => codex_config::get_codex_config_dir(),
-    };
+    let dir = crate::app_adapter::adapter_for(&app).config_dir();
 
     Ok(dir.to_string_lossy().to_string())
 }
@@ -660,16 +944,13 @@ pub async fn open_config_folder(
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let config_dir = match app_type {
-        AppType::Claude => crate::config::get_claude_config_dir(),
-        AppType::Codex => crate::codex_config::get_codex_config_dir(),
-    };
+    let config_dir = crate::app_adapter::adapter_for(&app_type).config_dir();
 
     // 确保目录存在
     if !config_dir.exists() {
@@ -737,7 +1018,7 @@ pub async fn open_external(app: tauri::AppHandle, url: String) -> Result<bool, S
 
 /// 获取应用配置文件路径
 #[tauri::command]
-pub async fn get_app_config_path() -> Result<String, String> {
+pub async fn get_app_config_path() -> Result<String, AppError> {
     use crate::config::get_app_config_path;
 
     let config_path = get_app_config_path();
@@ -746,7 +1027,7 @@ pub async fn get_app_config_path() -> Result<String, String> {
 
 /// 打开应用配置文件夹
 #[tauri::command]
-pub async fn open_app_config_folder(handle: tauri::AppHandle) -> Result<bool, String> {
+pub async fn open_app_config_folder(handle: tauri::AppHandle) -> Result<bool, AppError> {
     use crate::config::get_app_config_dir;
 
     let config_dir = get_app_config_dir();
@@ -784,12 +1065,14 @@ pub async fn read_claude_mcp_config() -> Result<Option<String>, String> {
 /// 新增或更新一个 MCP 服务器条目
 #[tauri::command]
 pub async fn upsert_claude_mcp_server(id: String, spec: serde_json::Value) -> Result<bool, String> {
+    crate::undo::capture("mcp", &[crate::claude_mcp::user_config_path()]);
     claude_mcp::upsert_mcp_server(&id, spec)
 }
 
 /// 删除一个 MCP 服务器条目
 #[tauri::command]
 pub async fn delete_claude_mcp_server(id: String) -> Result<bool, String> {
+    crate::undo::capture("mcp", &[crate::claude_mcp::user_config_path()]);
     claude_mcp::delete_mcp_server(&id)
 }
 
@@ -827,19 +1110,16 @@ pub async fn query_provider_usage(
 
     // 1. 获取供应商配置并克隆所需数据
     let (api_key, base_url, usage_script_code, timeout) = {
-        let config = state
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
 
         let manager = config
             .get_manager(&app_type)
-            .ok_or("应用类型不存在")?;
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
         let provider = manager
             .providers
             .get(&provider_id)
-            .ok_or("供应商不存在")?;
+            .ok_or_else(|| crate::error::provider_not_found(&provider_id))?;
 
         // 2. 检查脚本配置
         let usage_script = provider
@@ -903,66 +1183,78 @@ pub async fn query_provider_usage(
     }
 }
 
-/// 从供应商配置中提取 API Key 和 Base URL
-fn extract_credentials(
-    provider: &crate::provider::Provider,
-    app_type: &AppType,
-) -> Result<(String, String), String> {
-    match app_type {
-        AppType::Claude => {
-            let env = provider
-                .settings_config
-                .get("env")
-                .and_then(|v| v.as_object())
-                .ok_or("配置格式错误: 缺少 env")?;
-
-            let api_key = env
-                .get("ANTHROPIC_AUTH_TOKEN")
-                .and_then(|v| v.as_str())
-                .ok_or("缺少 API Key")?
-                .to_string();
-
-            let base_url = env
-                .get("ANTHROPIC_BASE_URL")
-                .and_then(|v| v.as_str())
-                .ok_or("缺少 ANTHROPIC_BASE_URL 配置")?
-                .to_string();
-
-            Ok((api_key, base_url))
-        }
-        AppType::Codex => {
-            let auth = provider
-                .settings_config
-                .get("auth")
-                .and_then(|v| v.as_object())
-                .ok_or("配置格式错误: 缺少 auth")?;
-
-            let api_key = auth
-                .get("OPENAI_API_KEY")
-                .and_then(|v| v.as_str())
-                .ok_or("缺少 API Key")?
-                .to_string();
-
-            // 从 config TOML 中提取 base_url
-            let config_toml = provider
-                .settings_config
-                .get("config")
-                .and_then(|v| v.as_str())
-                .unwrap_or("");
-
-            let base_url = if config_toml.contains("base_url") {
-                let re = regex::Regex::new(r#"base_url\s*=\s*["']([^"']+)["']"#).unwrap();
-                re.captures(config_toml)
-                    .and_then(|caps| caps.get(1))
-                    .map(|m| m.as_str().to_string())
-                    .ok_or("config.toml 中 base_url 格式错误")?
-            } else {
-                return Err("config.toml 中缺少 base_url 配置".to_string());
-            };
+/// 查询供应商余额 / 额度；缓存未过期时直接返回缓存，refresh 为 true 时强制重新查询
+#[tauri::command]
+pub async fn get_provider_quota(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    id: String,
+    refresh: Option<bool>,
+) -> Result<crate::provider_quota::ProviderQuota, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let (endpoint, api_key, base_url) = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let provider = config
+            .get_manager(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?
+            .providers
+            .get(&id)
+            .ok_or_else(|| crate::error::provider_not_found(&id))?;
+        let endpoint = provider
+            .meta
+            .as_ref()
+            .and_then(|m| m.quota_endpoint.clone())
+            .ok_or("未配置额度查询接口")?;
+        let (api_key, base_url) = extract_credentials(provider, &app_type)?;
+        (endpoint, api_key, base_url)
+    };
 
-            Ok((api_key, base_url))
+    if !refresh.unwrap_or(false) {
+        if let Some(quota) =
+            crate::provider_quota::cached_quota(app_type.as_str(), &id, endpoint.cache_minutes)
+        {
+            return Ok(quota);
         }
     }
+    Ok(
+        crate::provider_quota::fetch_quota(app_type.as_str(), &id, &endpoint, &api_key, &base_url)
+            .await,
+    )
+}
+
+/// 获取所有已缓存的供应商额度（不发起请求）
+#[tauri::command]
+pub async fn get_cached_provider_quotas() -> Result<Vec<crate::provider_quota::ProviderQuota>, String>
+{
+    Ok(crate::provider_quota::list_cached_quotas())
+}
+
+/// 从供应商配置中提取 API Key 和 Base URL
+fn extract_credentials(
+    provider: &crate::provider::Provider,
+    app_type: &AppType,
+) -> Result<(String, String), String> {
+    let provider = &crate::secrets::resolve_provider(app_type, provider)?;
+    let adapter = crate::app_adapter::adapter_for(app_type);
+    let api_key = adapter
+        .api_key(&provider.settings_config)
+        .ok_or("缺少 API Key")?;
+    let base_url = adapter
+        .base_url(&provider.settings_config)
+        .filter(|s| !s.trim().is_empty());
+    let base_url = match (base_url, app_type) {
+        (Some(url), _) => url,
+        // Gemini 未配置自定义端点时使用官方 API
+        (None, AppType::Gemini) => "https://generativelanguage.googleapis.com".to_string(),
+        (None, _) => return Err(format!("{} 配置中缺少 Base URL", adapter.display_name())),
+    };
+    Ok((api_key, base_url))
 }
 
 // =====================
@@ -984,10 +1276,7 @@ pub async fn get_mcp_config(
     let config_path = crate::config::get_app_config_path()
         .to_string_lossy()
         .to_string();
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let (servers, normalized) = crate::mcp::get_servers_snapshot_for(&mut cfg, &app_ty);
     let need_save = normalized > 0;
@@ -1010,10 +1299,7 @@ pub async fn upsert_mcp_server_in_config(
     spec: serde_json::Value,
     sync_other_side: Option<bool>,
 ) -> Result<bool, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let mut sync_targets: Vec<crate::app_config::AppType> = Vec::new();
 
@@ -1034,6 +1320,12 @@ pub async fn upsert_mcp_server_in_config(
         let other_app = match app_ty.clone() {
             crate::app_config::AppType::Claude => crate::app_config::AppType::Codex,
             crate::app_config::AppType::Codex => crate::app_config::AppType::Claude,
+            // Gemini 与 Claude 共用 JSON 格式的 mcpServers 定义
+            crate::app_config::AppType::Gemini => crate::app_config::AppType::Claude,
+            // 适配器应用的 MCP 定义以 Claude 格式保存，写入时再转换
+            crate::app_config::AppType::OpenCode | crate::app_config::AppType::Crush => {
+                crate::app_config::AppType::Claude
+            }
         };
         crate::mcp::upsert_in_config_for(&mut cfg, &other_app, &id, spec)?;
 
@@ -1051,15 +1343,14 @@ pub async fn upsert_mcp_server_in_config(
     drop(cfg);
     state.save()?;
 
-    let cfg2 = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let undo_paths: Vec<_> = sync_targets
+        .iter()
+        .map(crate::undo::mcp_config_path)
+        .collect();
+    crate::undo::capture("mcp", &undo_paths);
+    let cfg2 = state.config.lock().map_err(crate::error::lock_failed)?;
     for app_ty_to_sync in sync_targets {
-        match app_ty_to_sync {
-            crate::app_config::AppType::Claude => crate::mcp::sync_enabled_to_claude(&cfg2)?,
-            crate::app_config::AppType::Codex => crate::mcp::sync_enabled_to_codex(&cfg2)?,
-        };
+        crate::mcp::sync_enabled_for(&cfg2, &app_ty_to_sync)?;
     }
     Ok(changed)
 }
@@ -1071,23 +1362,15 @@ pub async fn delete_mcp_server_in_config(
     app: Option<String>,
     id: String,
 ) -> Result<bool, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
     let existed = crate::mcp::delete_in_config_for(&mut cfg, &app_ty, &id)?;
     drop(cfg);
     state.save()?;
     // 若删除的是 Claude/Codex 客户端的条目，则同步一次，确保启用项从对应 live 配置中移除
-    let cfg2 = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
-    match app_ty {
-        crate::app_config::AppType::Claude => crate::mcp::sync_enabled_to_claude(&cfg2)?,
-        crate::app_config::AppType::Codex => crate::mcp::sync_enabled_to_codex(&cfg2)?,
-    }
+    crate::undo::capture("mcp", &[crate::undo::mcp_config_path(&app_ty)]);
+    let cfg2 = state.config.lock().map_err(crate::error::lock_failed)?;
+    crate::mcp::sync_enabled_for(&cfg2, &app_ty)?;
     Ok(existed)
 }
 
@@ -1099,11 +1382,9 @@ pub async fn set_mcp_enabled(
     id: String,
     enabled: bool,
 ) -> Result<bool, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
+    crate::undo::capture("mcp", &[crate::undo::mcp_config_path(&app_ty)]);
     let changed = crate::mcp::set_enabled_and_sync_for(&mut cfg, &app_ty, &id, enabled)?;
     drop(cfg);
     state.save()?;
@@ -1113,12 +1394,10 @@ pub async fn set_mcp_enabled(
 /// 手动同步：将启用的 MCP 投影到 ~/.claude.json（不更改 config.json）
 #[tauri::command]
 pub async fn sync_enabled_mcp_to_claude(state: State<'_, AppState>) -> Result<bool, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let normalized = crate::mcp::normalize_servers_for(&mut cfg, &AppType::Claude);
-    crate::mcp::sync_enabled_to_claude(&cfg)?;
+    crate::undo::capture("mcp", &[crate::undo::mcp_config_path(&AppType::Claude)]);
+    crate::mcp::sync_enabled_for(&cfg, &AppType::Claude)?;
     let need_save = normalized > 0;
     drop(cfg);
     if need_save {
@@ -1130,12 +1409,25 @@ pub async fn sync_enabled_mcp_to_claude(state: State<'_, AppState>) -> Result<bo
 /// 手动同步：将启用的 MCP 投影到 ~/.codex/config.toml（不更改 config.json）
 #[tauri::command]
 pub async fn sync_enabled_mcp_to_codex(state: State<'_, AppState>) -> Result<bool, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let normalized = crate::mcp::normalize_servers_for(&mut cfg, &AppType::Codex);
-    crate::mcp::sync_enabled_to_codex(&cfg)?;
+    crate::undo::capture("mcp", &[crate::undo::mcp_config_path(&AppType::Codex)]);
+    crate::mcp::sync_enabled_for(&cfg, &AppType::Codex)?;
+    let need_save = normalized > 0;
+    drop(cfg);
+    if need_save {
+        state.save()?;
+    }
+    Ok(true)
+}
+
+/// 手动同步：将启用的 MCP 投影到 ~/.gemini/settings.json（不更改 config.json）
+#[tauri::command]
+pub async fn sync_enabled_mcp_to_gemini(state: State<'_, AppState>) -> Result<bool, String> {
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
+    let normalized = crate::mcp::normalize_servers_for(&mut cfg, &AppType::Gemini);
+    crate::undo::capture("mcp", &[crate::undo::mcp_config_path(&AppType::Gemini)]);
+    crate::mcp::sync_enabled_for(&cfg, &AppType::Gemini)?;
     let need_save = normalized > 0;
     drop(cfg);
     if need_save {
@@ -1147,10 +1439,7 @@ pub async fn sync_enabled_mcp_to_codex(state: State<'_, AppState>) -> Result<boo
 /// 从 ~/.claude.json 导入 MCP 定义到 config.json，返回变更数量
 #[tauri::command]
 pub async fn import_mcp_from_claude(state: State<'_, AppState>) -> Result<usize, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let changed = crate::mcp::import_from_claude(&mut cfg)?;
     drop(cfg);
     if changed > 0 {
@@ -1162,10 +1451,7 @@ pub async fn import_mcp_from_claude(state: State<'_, AppState>) -> Result<usize,
 /// 从 ~/.codex/config.toml 导入 MCP 定义到 config.json（Codex 作用域），返回变更数量
 #[tauri::command]
 pub async fn import_mcp_from_codex(state: State<'_, AppState>) -> Result<usize, String> {
-    let mut cfg = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
     let changed = crate::mcp::import_from_codex(&mut cfg)?;
     drop(cfg);
     if changed > 0 {
@@ -1174,9 +1460,87 @@ pub async fn import_mcp_from_codex(state: State<'_, AppState>) -> Result<usize,
     Ok(changed)
 }
 
+/// 测试 config.json 中的 MCP 服务器：启动命令（或连接 URL）完成 initialize 握手并列出工具
+#[tauri::command]
+pub async fn test_mcp_server(
+    state: State<'_, AppState>,
+    app: Option<String>,
+    id: String,
+    timeoutSecs: Option<u64>,
+) -> Result<crate::mcp_health::McpHealthResult, String> {
+    let spec = {
+        let cfg = state.config.lock().map_err(crate::error::lock_failed)?;
+        let app_ty = crate::app_config::AppType::from(app.as_deref().unwrap_or("claude"));
+        cfg.mcp_for(&app_ty)
+            .servers
+            .get(&id)
+            .ok_or_else(|| format!("MCP 服务器不存在: {}", id))?
+            .get("server")
+            .cloned()
+            .ok_or_else(|| "MCP 服务器条目缺少 server 字段".to_string())?
+    };
+    Ok(crate::mcp_health::check_server(&spec, timeoutSecs).await)
+}
+
+/// 测试尚未保存的 MCP 服务器连接定义（编辑表单中使用）
+#[tauri::command]
+pub async fn test_mcp_server_spec(
+    spec: serde_json::Value,
+    timeoutSecs: Option<u64>,
+) -> Result<crate::mcp_health::McpHealthResult, String> {
+    Ok(crate::mcp_health::check_server(&spec, timeoutSecs).await)
+}
+
+/// 获取 MCP 目录（内置目录叠加远程缓存）
+#[tauri::command]
+pub async fn get_mcp_catalog() -> Result<crate::mcp_catalog::McpCatalog, String> {
+    Ok(crate::mcp_catalog::load_catalog())
+}
+
+/// 从远程地址刷新 MCP 目录；未传入地址时使用设置中的 mcpCatalogUrl
+#[tauri::command]
+pub async fn refresh_mcp_catalog(
+    url: Option<String>,
+) -> Result<crate::mcp_catalog::McpCatalog, String> {
+    let url = url
+        .or_else(|| crate::settings::get_settings().mcp_catalog_url)
+        .unwrap_or_default();
+    crate::mcp_catalog::refresh_catalog(&url).await
+}
+
+/// 从 MCP 目录安装服务器到指定应用（默认 Claude）；values 为占位符变量的取值，
+/// 未填写完整时以禁用状态写入
+#[tauri::command]
+pub async fn install_mcp_from_catalog(
+    state: State<'_, AppState>,
+    id: String,
+    apps: Option<Vec<String>>,
+    values: Option<HashMap<String, String>>,
+) -> Result<crate::mcp_catalog::McpInstallResult, String> {
+    let apps: Vec<AppType> = match apps {
+        Some(apps) if !apps.is_empty() => apps.iter().map(|a| AppType::from(a.as_str())).collect(),
+        _ => vec![AppType::Claude],
+    };
+    let mut cfg = state.config.lock().map_err(crate::error::lock_failed)?;
+    let result = crate::mcp_catalog::install(&mut cfg, &id, &apps, &values.unwrap_or_default())?;
+    drop(cfg);
+    state.save()?;
+
+    if result.enabled {
+        let undo_paths: Vec<_> = apps.iter().map(crate::undo::mcp_config_path).collect();
+        crate::undo::capture("mcp", &undo_paths);
+        let cfg = state.config.lock().map_err(crate::error::lock_failed)?;
+        for app in &apps {
+            crate::mcp::sync_enabled_for(&cfg, app)?;
+        }
+    }
+    Ok(result)
+}
+
 /// 读取当前生效（live）的配置内容，返回可直接作为 provider.settings_config 的对象
 /// - Codex: 返回 { auth: JSON, config: string }
 /// - Claude: 返回 settings.json 的 JSON 内容
+/// - Gemini: 返回 { env: .env 键值, config: settings.json（不含 mcpServers） }
 #[tauri::command]
 pub async fn read_live_provider_settings(
     app_type: Option<AppType>,
@@ -1188,37 +1552,198 @@ pub async fn read_live_provider_settings(
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    match app_type {
-        AppType::Codex => {
-            let auth_path = crate::codex_config::get_codex_auth_path();
-            if !auth_path.exists() {
-                return Err("Codex 配置文件不存在：缺少 auth.json".to_string());
-            }
-            let auth: serde_json::Value = crate::config::read_json_file(&auth_path)?;
-            let cfg_text = crate::codex_config::read_and_validate_codex_config_text()?;
-            Ok(serde_json::json!({ "auth": auth, "config": cfg_text }))
-        }
-        AppType::Claude => {
-            let path = crate::config::get_claude_settings_path();
-            if !path.exists() {
-                return Err("Claude Code 配置文件不存在".to_string());
-            }
-            let v: serde_json::Value = crate::config::read_json_file(&path)?;
-            Ok(v)
-        }
+    let adapter = crate::app_adapter::adapter_for(&app_type);
+    if !adapter.live_exists() {
+        return Err(format!("{} 配置文件不存在", adapter.display_name()));
     }
+    let live = adapter.read_live()?;
+    adapter.validate(&live)?;
+    Ok(live)
 }
 
 /// 获取设置
 #[tauri::command]
-pub async fn get_settings() -> Result<crate::settings::AppSettings, String> {
+pub async fn get_settings() -> Result<crate::settings::AppSettings, AppError> {
     Ok(crate::settings::get_settings())
 }
 
 /// 保存设置
 #[tauri::command]
-pub async fn save_settings(settings: crate::settings::AppSettings) -> Result<bool, String> {
+pub async fn save_settings(
+    handle: tauri::AppHandle,
+    state: State<'_, AppState>,
+    settings: crate::settings::AppSettings,
+) -> Result<bool, AppError> {
+    let old_proxy = crate::proxy::global_proxy();
     crate::settings::update_settings(settings)?;
+    {
+        let config = state.config.lock()?;
+        crate::proxy::reapply_global(
+            &config,
+            old_proxy.as_ref(),
+            crate::proxy::global_proxy().as_ref(),
+        );
+    }
+    crate::local_api::reload(&handle)?;
+    // 配置目录可能已更改，按新目录重新监听
+    if let Err(e) = crate::config_watcher::start(&handle) {
+        log::warn!("{}", e);
+    }
+    Ok(true)
+}
+
+/// 列出应用的配置目录（默认目录与已登记的多个目录）
+#[tauri::command]
+pub async fn list_app_homes(
+    appType: String,
+) -> Result<Vec<crate::app_homes::AppHomeStatus>, String> {
+    Ok(crate::app_homes::list_homes(&appType.as_str().into()))
+}
+
+/// 配置目录切换后，按新目录的 live 配置重新确定各应用的当前供应商
+fn refresh_current_after_home_change(state: &AppState, apps: &[AppType]) -> Result<(), String> {
+    let changed = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let mut changed = false;
+        for app_type in apps {
+            if let Some(manager) = config.get_manager_mut(app_type) {
+                changed |= crate::app_homes::refresh_current_provider(app_type, manager);
+            }
+        }
+        changed
+    };
+    if changed {
+        state.save()?;
+    }
+    Ok(())
+}
+
+/// 选择切换供应商时写入的配置目录；name 为空时回到默认目录
+#[tauri::command]
+pub async fn select_app_home(
+    state: State<'_, AppState>,
+    handle: tauri::AppHandle,
+    appType: String,
+    name: Option<String>,
+) -> Result<bool, String> {
+    let app_type: AppType = appType.as_str().into();
+    crate::app_homes::select_home(&app_type, name.as_deref())?;
+    refresh_current_after_home_change(&state, &[app_type])?;
+    // live 配置目录已更改，按新目录重新监听
+    if let Err(e) = crate::config_watcher::start(&handle) {
+        log::warn!("{}", e);
+    }
+    Ok(true)
+}
+
+/// 切换环境配置（成组替换 Claude / Codex 配置目录）；id 为空时回到默认目录
+#[tauri::command]
+pub async fn switch_env_profile(
+    state: State<'_, AppState>,
+    handle: tauri::AppHandle,
+    id: Option<String>,
+) -> Result<bool, String> {
+    crate::env_profiles::switch_profile(id.as_deref())?;
+    refresh_current_after_home_change(&state, &[AppType::Claude, AppType::Codex])?;
+    if let Err(e) = crate::config_watcher::start(&handle) {
+        log::warn!("{}", e);
+    }
+    Ok(true)
+}
+
+/// 列出已安装的 WSL 发行版（仅 Windows）
+#[tauri::command]
+pub async fn list_wsl_distros() -> Result<Vec<String>, String> {
+    crate::wsl::list_distros()
+}
+
+/// 查找 WSL 发行版中各用户的 .claude / .codex / .gemini 目录
+#[tauri::command]
+pub async fn discover_wsl_homes(distro: String) -> Result<Vec<crate::wsl::WslHome>, String> {
+    crate::wsl::discover_homes(&distro)
+}
+
+/// 测试远程主机连接，返回远程主目录
+#[tauri::command]
+pub async fn test_remote_connection(profileId: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::remote::test_connection(&profileId))
+        .await
+        .map_err(|e| format!("连接任务失败: {}", e))?
+}
+
+/// 列出远程主机上的 Claude / Codex 对话
+#[tauri::command]
+pub async fn list_remote_conversations(
+    profileId: String,
+    appType: String,
+) -> Result<Vec<crate::remote::RemoteConversation>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::remote::list_conversations(&profileId, &appType.as_str().into())
+    })
+    .await
+    .map_err(|e| format!("连接任务失败: {}", e))?
+}
+
+/// 读取远程对话文件内容
+#[tauri::command]
+pub async fn read_remote_conversation(
+    profileId: String,
+    filePath: String,
+) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::remote::read_conversation(&profileId, &filePath)
+    })
+    .await
+    .map_err(|e| format!("连接任务失败: {}", e))?
+}
+
+/// 读取远程全局规则（Claude CLAUDE.md / Codex AGENTS.md）
+#[tauri::command]
+pub async fn read_remote_rules(profileId: String, appType: String) -> Result<String, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::remote::read_rules(&profileId, &appType.as_str().into())
+    })
+    .await
+    .map_err(|e| format!("连接任务失败: {}", e))?
+}
+
+/// 写入远程全局规则
+#[tauri::command]
+pub async fn write_remote_rules(
+    profileId: String,
+    appType: String,
+    content: String,
+) -> Result<bool, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::remote::write_rules(&profileId, &appType.as_str().into(), &content)
+    })
+    .await
+    .map_err(|e| format!("连接任务失败: {}", e))??;
+    Ok(true)
+}
+
+/// 将本地供应商写入远程主机的 live 配置
+#[tauri::command]
+pub async fn switch_remote_provider(
+    state: State<'_, AppState>,
+    profileId: String,
+    appType: String,
+    providerId: String,
+) -> Result<bool, String> {
+    let app_type: AppType = appType.as_str().into();
+    let provider = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        config
+            .get_manager(&app_type)
+            .and_then(|m| m.providers.get(&providerId))
+            .cloned()
+            .ok_or_else(|| crate::error::provider_not_found(&providerId))?
+    };
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::remote::switch_provider(&profileId, &app_type, &provider)
+    })
+    .await
+    .map_err(|e| format!("连接任务失败: {}", e))??;
     Ok(true)
 }
 
@@ -1247,12 +1772,29 @@ pub async fn check_for_updates(handle: tauri::AppHandle) -> Result<bool, String>
 /// 判断是否为便携版（绿色版）运行
 #[tauri::command]
 pub async fn is_portable_mode() -> Result<bool, String> {
-    let exe_path = std::env::current_exe().map_err(|e| format!("获取可执行路径失败: {}", e))?;
-    if let Some(dir) = exe_path.parent() {
-        Ok(dir.join("portable.ini").is_file())
-    } else {
-        Ok(false)
-    }
+    Ok(crate::portable::is_enabled())
+}
+
+/// 获取便携模式状态（标记文件与数据目录）
+#[tauri::command]
+pub async fn get_portable_info() -> Result<crate::portable::PortableInfo, String> {
+    Ok(crate::portable::info())
+}
+
+/// 启用便携模式：数据目录默认为可执行文件旁的 data，可选复制现有配置；重启后生效
+#[tauri::command]
+pub async fn enable_portable_mode(
+    dataDir: Option<String>,
+    copyExisting: Option<bool>,
+) -> Result<crate::portable::PortableInfo, String> {
+    crate::portable::enable(dataDir.as_deref(), copyExisting.unwrap_or(true))
+}
+
+/// 关闭便携模式（数据目录保留）；重启后生效
+#[tauri::command]
+pub async fn disable_portable_mode() -> Result<bool, String> {
+    crate::portable::disable()?;
+    Ok(true)
 }
 
 /// Claude 插件：获取 ~/.claude/config.json 状态
@@ -1302,63 +1844,242 @@ pub async fn test_api_endpoints(
     speedtest::test_endpoints(filtered, timeout_secs).await
 }
 
-/// 获取自定义端点列表
+/// 探测本地推理服务（Ollama / LM Studio 等）是否运行，并发现可用模型
 #[tauri::command]
-pub async fn get_custom_endpoints(
-    state: State<'_, crate::store::AppState>,
-    app_type: Option<AppType>,
-    app: Option<String>,
-    appType: Option<String>,
-    provider_id: Option<String>,
-    providerId: Option<String>,
-) -> Result<Vec<crate::settings::CustomEndpoint>, String> {
-    let app_type = app_type
-        .or_else(|| app.as_deref().map(|s| s.into()))
-        .or_else(|| appType.as_deref().map(|s| s.into()))
-        .unwrap_or(AppType::Claude);
-    let provider_id = provider_id
-        .or(providerId)
-        .ok_or_else(|| "缺少 providerId".to_string())?;
-    let mut cfg_guard = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
-
-    let manager = cfg_guard
-        .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+pub async fn probe_local_server(
+    base_url: String,
+) -> Result<crate::local_server::LocalServerStatus, String> {
+    Ok(crate::local_server::probe_local_server(&base_url).await)
+}
 
-    let Some(provider) = manager.providers.get_mut(&provider_id) else {
-        return Ok(vec![]);
+/// 导出供应商对比矩阵（Markdown / CSV），可选测量端点延迟并写入文件
+#[tauri::command]
+pub async fn export_provider_matrix(
+    state: State<'_, AppState>,
+    format: crate::provider_matrix::MatrixFormat,
+    file_path: Option<String>,
+    measure_latency: Option<bool>,
+) -> Result<String, String> {
+    let mut rows = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::provider_matrix::collect_rows(&config)
     };
 
-    // 首选从 provider.meta 读取
-    let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
-    if !meta.custom_endpoints.is_empty() {
-        let mut result: Vec<_> = meta.custom_endpoints.values().cloned().collect();
-        result.sort_by(|a, b| b.added_at.cmp(&a.added_at));
-        return Ok(result);
+    if measure_latency.unwrap_or(false) {
+        let targets: Vec<crate::provider_trends::HealthCheckTarget> = rows
+            .iter()
+            .filter_map(|r| {
+                r.endpoint
+                    .clone()
+                    .map(|endpoint| crate::provider_trends::HealthCheckTarget {
+                        app_type: r.app_type.clone(),
+                        provider_id: r.id.clone(),
+                        endpoint,
+                    })
+            })
+            .collect();
+        // 只测量延迟，导出不应写入趋势数据或触发健康检查事件
+        let results = crate::provider_trends::probe_targets(targets).await?;
+        for row in rows.iter_mut() {
+            row.latency_ms = results
+                .iter()
+                .find(|r| r.app_type == row.app_type && r.provider_id == row.id)
+                .and_then(|r| r.latency_ms)
+                .map(u128::from);
+        }
     }
 
-    Ok(vec![])
+    // 失败率取近 30 天的健康检查记录
+    for row in rows.iter_mut() {
+        row.failure_rate = crate::provider_trends::get_provider_trends(
+            &row.app_type,
+            &row.id,
+            crate::provider_trends::TrendRange::Month,
+        )
+        .failure_rate;
+    }
+
+    let text = crate::provider_matrix::render(&rows, format);
+    if let Some(path) = file_path {
+        let target = crate::export_naming::reserve_export_file(std::path::Path::new(&path))?;
+        crate::config::write_text_file(&target, &text)?;
+    }
+    Ok(text)
 }
 
-/// 添加自定义端点
+/// 对所有（或指定应用的）供应商执行健康检查，并记录到趋势数据
 #[tauri::command]
-pub async fn add_custom_endpoint(
-    state: State<'_, crate::store::AppState>,
+pub async fn run_provider_health_checks(
+    state: State<'_, AppState>,
     app_type: Option<AppType>,
     app: Option<String>,
     appType: Option<String>,
-    provider_id: Option<String>,
-    providerId: Option<String>,
-    url: String,
-) -> Result<(), String> {
+) -> Result<Vec<crate::provider_trends::HealthCheckResult>, String> {
+    let filter = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()));
+    let targets = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::provider_trends::collect_targets(&config)
+    };
+    let targets = targets
+        .into_iter()
+        .filter(|t| filter.as_ref().is_none_or(|f| t.app_type == f.as_str()))
+        .collect();
+    crate::provider_trends::run_health_checks(targets).await
+}
+
+/// 获取供应商的历史趋势（延迟与失败率）
+#[tauri::command]
+pub async fn get_provider_trends(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    id: String,
+    range: Option<crate::provider_trends::TrendRange>,
+) -> Result<crate::provider_trends::ProviderTrends, String> {
     let app_type = app_type
         .or_else(|| app.as_deref().map(|s| s.into()))
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
-    let provider_id = provider_id
+    Ok(crate::provider_trends::get_provider_trends(
+        app_type.as_str(),
+        &id,
+        range.unwrap_or(crate::provider_trends::TrendRange::Week),
+    ))
+}
+
+/// 立即生成状态报告（存储、用量、供应商可靠性、规则预算与维护建议）
+#[tauri::command]
+pub async fn generate_setup_report(
+    state: State<'_, AppState>,
+) -> Result<crate::setup_report::SetupReportInfo, String> {
+    let config = state
+        .config
+        .lock()
+        .map_err(crate::error::lock_failed)?
+        .clone();
+    tauri::async_runtime::spawn_blocking(move || crate::setup_report::write_setup_report(&config))
+        .await
+        .map_err(|e| format!("生成状态报告失败: {}", e))?
+}
+
+/// Claude todos / shell-snapshots 缓存汇总（文件数、大小、时间范围）
+#[tauri::command]
+pub async fn list_claude_caches() -> Result<Vec<crate::claude_caches::ClaudeCacheSummary>, String> {
+    tauri::async_runtime::spawn_blocking(crate::claude_caches::summaries)
+        .await
+        .map_err(|e| format!("统计缓存失败: {}", e))
+}
+
+/// 列出某个缓存目录中的文件（最近修改的在前）
+#[tauri::command]
+pub async fn get_claude_cache_files(
+    kind: crate::claude_caches::ClaudeCacheKind,
+) -> Result<Vec<crate::claude_caches::ClaudeCacheFile>, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::claude_caches::list(kind))
+        .await
+        .map_err(|e| format!("列出缓存文件失败: {}", e))
+}
+
+/// 清理缓存；传入 keepDays 时保留最近 N 天内修改的文件
+#[tauri::command]
+pub async fn purge_claude_cache(
+    kind: crate::claude_caches::ClaudeCacheKind,
+    keepDays: Option<u32>,
+) -> Result<crate::claude_caches::ClaudeCachePurge, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::claude_caches::purge(kind, keepDays))
+        .await
+        .map_err(|e| format!("清理缓存失败: {}", e))?
+}
+
+/// Codex history.jsonl 与日志目录的大小
+#[tauri::command]
+pub async fn list_codex_caches() -> Result<Vec<crate::codex_caches::CodexCacheSummary>, String> {
+    tauri::async_runtime::spawn_blocking(crate::codex_caches::summaries)
+        .await
+        .map_err(|e| format!("统计缓存失败: {}", e))
+}
+
+/// 截断或清空 Codex 历史/日志；不可撤销，需传入 confirm: true
+#[tauri::command]
+pub async fn purge_codex_cache(
+    target: crate::codex_caches::CodexCacheTarget,
+    action: crate::codex_caches::CodexCacheAction,
+    confirm: Option<bool>,
+) -> Result<crate::codex_caches::CodexCachePurge, String> {
+    let confirm = confirm.unwrap_or(false);
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::codex_caches::purge(target, action, confirm)
+    })
+    .await
+    .map_err(|e| format!("清理缓存失败: {}", e))?
+}
+
+/// 当前时刻各应用命中的定时切换规则
+#[tauri::command]
+pub async fn get_active_switch_schedules(
+) -> Result<Vec<crate::switch_schedule::ScheduleMatch>, String> {
+    Ok(crate::switch_schedule::active_schedules(
+        &crate::settings::get_settings().switch_schedules,
+        &chrono::Local::now(),
+    ))
+}
+
+/// 获取自定义端点列表
+#[tauri::command]
+pub async fn get_custom_endpoints(
+    state: State<'_, crate::store::AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    provider_id: Option<String>,
+    providerId: Option<String>,
+) -> Result<Vec<crate::settings::CustomEndpoint>, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let provider_id = provider_id
+        .or(providerId)
+        .ok_or_else(|| "缺少 providerId".to_string())?;
+    let mut cfg_guard = state.config.lock().map_err(crate::error::lock_failed)?;
+
+    let manager = cfg_guard
+        .get_manager_mut(&app_type)
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+
+    let Some(provider) = manager.providers.get_mut(&provider_id) else {
+        return Ok(vec![]);
+    };
+
+    // 首选从 provider.meta 读取
+    let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
+    if !meta.custom_endpoints.is_empty() {
+        let mut result: Vec<_> = meta.custom_endpoints.values().cloned().collect();
+        result.sort_by(|a, b| b.added_at.cmp(&a.added_at));
+        return Ok(result);
+    }
+
+    Ok(vec![])
+}
+
+/// 添加自定义端点
+#[tauri::command]
+pub async fn add_custom_endpoint(
+    state: State<'_, crate::store::AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    provider_id: Option<String>,
+    providerId: Option<String>,
+    url: String,
+) -> Result<(), String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    let provider_id = provider_id
         .or(providerId)
         .ok_or_else(|| "缺少 providerId".to_string())?;
     let normalized = url.trim().trim_end_matches('/').to_string();
@@ -1366,16 +2087,13 @@ pub async fn add_custom_endpoint(
         return Err("URL 不能为空".to_string());
     }
 
-    let mut cfg_guard = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg_guard = state.config.lock().map_err(crate::error::lock_failed)?;
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     let Some(provider) = manager.providers.get_mut(&provider_id) else {
-        return Err("供应商不存在或未选择".to_string());
+        return Err(crate::error::provider_not_found(&provider_id));
     };
     let meta = provider.meta.get_or_insert_with(ProviderMeta::default);
 
@@ -1415,13 +2133,10 @@ pub async fn remove_custom_endpoint(
         .ok_or_else(|| "缺少 providerId".to_string())?;
     let normalized = url.trim().trim_end_matches('/').to_string();
 
-    let mut cfg_guard = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg_guard = state.config.lock().map_err(crate::error::lock_failed)?;
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     if let Some(provider) = manager.providers.get_mut(&provider_id) {
         if let Some(meta) = provider.meta.as_mut() {
@@ -1453,13 +2168,10 @@ pub async fn update_endpoint_last_used(
         .ok_or_else(|| "缺少 providerId".to_string())?;
     let normalized = url.trim().trim_end_matches('/').to_string();
 
-    let mut cfg_guard = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut cfg_guard = state.config.lock().map_err(crate::error::lock_failed)?;
     let manager = cfg_guard
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     if let Some(provider) = manager.providers.get_mut(&provider_id) {
         if let Some(meta) = provider.meta.as_mut() {
@@ -1479,7 +2191,9 @@ pub async fn update_endpoint_last_used(
 
 /// 获取 app_config_dir 覆盖配置 (从 Store)
 #[tauri::command]
-pub async fn get_app_config_dir_override(app: tauri::AppHandle) -> Result<Option<String>, String> {
+pub async fn get_app_config_dir_override(
+    app: tauri::AppHandle,
+) -> Result<Option<String>, AppError> {
     Ok(crate::app_store::get_app_config_dir_from_store(&app)
         .map(|p| p.to_string_lossy().to_string()))
 }
@@ -1489,7 +2203,7 @@ pub async fn get_app_config_dir_override(app: tauri::AppHandle) -> Result<Option
 pub async fn set_app_config_dir_override(
     app: tauri::AppHandle,
     path: Option<String>,
-) -> Result<bool, String> {
+) -> Result<bool, AppError> {
     crate::app_store::set_app_config_dir_to_store(&app, path.as_deref())?;
     Ok(true)
 }
@@ -1519,14 +2233,11 @@ pub async fn update_providers_sort_order(
         .or_else(|| appType.as_deref().map(|s| s.into()))
         .unwrap_or(AppType::Claude);
 
-    let mut config = state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
 
     let manager = config
         .get_manager_mut(&app_type)
-        .ok_or_else(|| format!("应用类型不存在: {:?}", app_type))?;
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
 
     // Update sort_index for each provider
     for update in updates {
@@ -1550,33 +2261,37 @@ pub async fn update_providers_sort_order(
 pub async fn list_conversations(
     appType: Option<String>,
 ) -> Result<Vec<crate::conversation::ConversationMeta>, String> {
-    match appType.as_deref() {
-        Some("claude") => crate::conversation::list_claude_conversations(),
-        Some("codex") => crate::conversation::list_codex_conversations(),
-        _ => {
-            // 返回所有对话
-            let mut all = Vec::new();
-            all.extend(crate::conversation::list_claude_conversations()?);
-            all.extend(crate::conversation::list_codex_conversations()?);
-            // 按修改时间倒序排序
-            all.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
-            Ok(all)
-        }
-    }
+    let mut conversations = crate::conversation::list_conversations(appType.as_deref())?;
+    crate::active_sessions::mark_live(&mut conversations);
+    Ok(conversations)
 }
 
-/// 搜索对话记录
+/// 搜索对话记录：匹配元数据与消息正文，按相关度排序并返回匹配片段
 #[tauri::command]
 pub async fn search_conversations(
     appType: Option<String>,
     keyword: String,
-) -> Result<Vec<crate::conversation::ConversationMeta>, String> {
-    crate::conversation::search_conversations(appType, &keyword)
+    options: Option<crate::conversation_search::SearchOptions>,
+) -> Result<Vec<crate::conversation_search::SearchHit>, String> {
+    let mut hits = crate::conversation_search::search(
+        appType.as_deref(),
+        &keyword,
+        &options.unwrap_or_default(),
+    )?;
+    crate::active_sessions::mark_live(hits.iter_mut().map(|h| &mut h.meta));
+    Ok(hits)
+}
+
+/// 检测正在运行的 Claude Code / Codex 进程及其进行中的会话
+#[tauri::command]
+pub async fn get_active_sessions() -> Result<crate::active_sessions::ActiveSessions, String> {
+    Ok(crate::active_sessions::detect())
 }
 
 /// 删除对话记录
 #[tauri::command]
-pub async fn delete_conversation(filePath: String) -> Result<(), String> {
+pub async fn delete_conversation(filePath: String, force: Option<bool>) -> Result<(), String> {
+    crate::active_sessions::ensure_not_live(&filePath, force.unwrap_or(false))?;
     crate::conversation::delete_conversation(&filePath)
 }
 
@@ -1586,30 +2301,368 @@ pub async fn read_conversation_content(filePath: String) -> Result<String, Strin
     crate::conversation::read_conversation_content(&filePath)
 }
 
+/// 以阅读模式读取对话消息（角色筛选、工具调用折叠、去除思考块）
+#[tauri::command]
+pub async fn read_conversation_messages(
+    filePath: String,
+    options: Option<crate::conversation::ReadingOptions>,
+) -> Result<Vec<crate::conversation::ReadingMessage>, String> {
+    crate::conversation::read_conversation_messages(&filePath, &options.unwrap_or_default())
+}
+
+/// 跟随正在进行的会话，新消息通过 conversation-tail 事件推送
+#[tauri::command]
+pub async fn tail_conversation(
+    app: tauri::AppHandle,
+    filePath: String,
+) -> Result<crate::conversation_tail::TailStart, String> {
+    crate::conversation_tail::start(&app, &filePath)
+}
+
+/// 停止跟随会话；不传 filePath 时停止全部
+#[tauri::command]
+pub async fn stop_conversation_tail(filePath: Option<String>) -> Result<(), String> {
+    crate::conversation_tail::stop(filePath.as_deref());
+    Ok(())
+}
+
+/// 项目概览：会话数、消息数、token 用量、首末活动时间与平均会话时长
+#[tauri::command]
+pub async fn get_project_stats(
+    project: String,
+    appType: Option<String>,
+) -> Result<crate::conversation_stats::ProjectStats, String> {
+    crate::conversation_stats::project_stats(&project, appType.as_deref())
+}
+
+/// 会话概览：用户 / 助手回合、按工具名汇总的调用次数与耗时、时间线与总时长
+#[tauri::command]
+pub async fn summarize_conversation(
+    filePath: String,
+) -> Result<crate::conversation_stats::ConversationSummary, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::conversation_stats::summarize_conversation(&filePath)
+    })
+    .await
+    .map_err(|e| format!("生成会话概览失败: {}", e))?
+}
+
+/// 批量导出对话到目录（文件名模板与冲突策略取自设置）；includeNotes 时附带备注
+#[tauri::command]
+pub async fn export_conversations(
+    filePaths: Vec<String>,
+    destDir: String,
+    format: Option<crate::conversation::ConversationExportFormat>,
+    includeNotes: Option<bool>,
+) -> Result<crate::export_naming::ExportOutcome, String> {
+    crate::conversation::export_conversations(
+        &filePaths,
+        &destDir,
+        format.unwrap_or_default(),
+        includeNotes.unwrap_or(false),
+    )
+}
+
+/// 将对话生成可直接部署到静态托管的分享网站（index.html + 每个对话一个页面）
+#[tauri::command]
+pub async fn generate_share_bundle(
+    filePaths: Vec<String>,
+    destDir: String,
+    title: Option<String>,
+) -> Result<crate::share_bundle::ShareBundle, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::share_bundle::build(&filePaths, &destDir, title.as_deref())
+    })
+    .await
+    .map_err(|e| format!("生成分享网站失败: {}", e))?
+}
+
+/// 导入 ChatGPT / claude.ai 网页版数据导出包（zip 或其中的 conversations.json）
+#[tauri::command]
+pub async fn import_web_export(
+    filePath: String,
+) -> Result<crate::web_import::WebImportResult, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::web_import::import_archive(&filePath))
+        .await
+        .map_err(|e| format!("导入网页版对话失败: {}", e))?
+}
+
+/// 只导出对话中选中的消息（按条目序号或 uuid），返回导出内容；指定 destPath 时同时写入文件
+#[tauri::command]
+pub async fn export_conversation_messages(
+    filePath: String,
+    messages: Vec<crate::conversation::MessageRef>,
+    format: Option<crate::conversation::ConversationExportFormat>,
+    destPath: Option<String>,
+    includeNotes: Option<bool>,
+) -> Result<String, String> {
+    crate::conversation::export_messages(
+        &filePath,
+        &messages,
+        format.unwrap_or(crate::conversation::ConversationExportFormat::Markdown),
+        destPath.as_deref(),
+        includeNotes.unwrap_or(false),
+    )
+}
+
+/// 裁剪对话到新文件（保留最后 N 条或删除选中范围），原对话保持不变
+#[tauri::command]
+pub async fn trim_conversation(
+    filePath: String,
+    spec: crate::conversation_edit::TrimSpec,
+) -> Result<crate::conversation_edit::DerivedConversation, String> {
+    crate::conversation_edit::trim(&filePath, &spec)
+}
+
+/// 合并两个对话到新文件（顺序拼接或按时间交错），原对话保持不变
+#[tauri::command]
+pub async fn merge_conversations(
+    first: String,
+    second: String,
+    strategy: Option<crate::conversation_edit::MergeStrategy>,
+) -> Result<crate::conversation_edit::DerivedConversation, String> {
+    crate::conversation_edit::merge(&first, &second, strategy.unwrap_or_default())
+}
+
+/// 检查对话文件完整性；repair 为 true 时将损坏的行去掉后写出修复副本
+#[tauri::command]
+pub async fn verify_conversation(
+    filePath: String,
+    repair: Option<bool>,
+) -> Result<crate::conversation_edit::IntegrityReport, String> {
+    crate::conversation_edit::verify(&filePath, repair.unwrap_or(false))
+}
+
+/// 批量为对话添加/移除标签，返回变更的对话数量
+#[tauri::command]
+pub async fn bulk_tag_conversations(
+    filePaths: Vec<String>,
+    add: Vec<String>,
+    remove: Vec<String>,
+) -> Result<usize, String> {
+    crate::conversation_tags::bulk_tag_conversations(&filePaths, &add, &remove)
+}
+
+/// 读取对话的备注（对话整体与各消息）
+#[tauri::command]
+pub async fn get_conversation_notes(
+    filePath: String,
+) -> Result<crate::conversation_notes::ConversationNotes, String> {
+    Ok(crate::conversation_notes::get_notes(&filePath))
+}
+
+/// 设置备注：指定 messageId（消息 uuid）时为该消息的备注，否则为对话整体的备注；note 为空时删除
+#[tauri::command]
+pub async fn set_conversation_note(
+    filePath: String,
+    messageId: Option<String>,
+    note: Option<String>,
+) -> Result<crate::conversation_notes::ConversationNotes, String> {
+    crate::conversation_notes::set_note(&filePath, messageId.as_deref(), note)
+}
+
+/// 生成对话标题 / 摘要使用的供应商：默认为该应用的当前供应商
+fn summary_provider(
+    state: &AppState,
+    app_type: Option<String>,
+    provider_id: Option<String>,
+) -> Result<(AppType, Provider), String> {
+    let app_type: AppType = app_type
+        .as_deref()
+        .map(|s| s.into())
+        .unwrap_or(AppType::Claude);
+    let provider = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        let id = provider_id.unwrap_or_else(|| manager.current.clone());
+        manager
+            .providers
+            .get(&id)
+            .cloned()
+            .ok_or_else(|| crate::error::provider_not_found(&id))?
+    };
+    let provider = crate::secrets::resolve_provider(&app_type, &provider)?;
+    Ok((app_type, provider))
+}
+
+/// 读取模型生成的对话标题与摘要
+#[tauri::command]
+pub async fn get_conversation_summary(
+    filePath: String,
+) -> Result<Option<crate::conversation_summaries::GeneratedSummary>, String> {
+    Ok(crate::conversation_summaries::get_summary(&filePath))
+}
+
+/// 列出全部已生成的对话标题与摘要（以对话文件路径为键）
+#[tauri::command]
+pub async fn list_conversation_summaries(
+) -> Result<HashMap<String, crate::conversation_summaries::GeneratedSummary>, String> {
+    Ok(crate::conversation_summaries::list_summaries())
+}
+
+/// 将对话开头与结尾的消息发送给供应商（默认当前 Claude 供应商），生成标题与摘要并保存
+#[tauri::command]
+pub async fn generate_conversation_summary(
+    state: State<'_, AppState>,
+    filePath: String,
+    appType: Option<String>,
+    providerId: Option<String>,
+) -> Result<crate::conversation_summaries::GeneratedSummary, String> {
+    let (app_type, provider) = summary_provider(&state, appType, providerId)?;
+    crate::conversation_summaries::summarize_conversation(&app_type, &provider, &filePath).await
+}
+
+/// 为尚未生成标题的对话批量生成；不传 filePaths 时处理全部对话，单次最多 limit 个（默认 20）
+#[tauri::command]
+pub async fn generate_missing_conversation_summaries(
+    state: State<'_, AppState>,
+    appType: Option<String>,
+    providerId: Option<String>,
+    filePaths: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<crate::conversation_summaries::SummaryBatchResult, String> {
+    let (app_type, provider) = summary_provider(&state, appType, providerId)?;
+    crate::conversation_summaries::summarize_untitled(&app_type, &provider, filePaths, limit).await
+}
+
+/// 删除已生成的对话标题与摘要
+#[tauri::command]
+pub async fn delete_conversation_summary(filePath: String) -> Result<bool, String> {
+    crate::conversation_summaries::delete_summary(&filePath)
+}
+
+/// 提取对话中助手消息里的代码块；可按语言筛选，指定 destDir 时写入文件
+#[tauri::command]
+pub async fn extract_code_blocks(
+    filePath: String,
+    language: Option<String>,
+    destDir: Option<String>,
+) -> Result<Vec<crate::code_blocks::CodeBlock>, String> {
+    tauri::async_runtime::spawn_blocking(move || {
+        crate::code_blocks::extract_code_blocks(&filePath, language.as_deref(), destDir.as_deref())
+    })
+    .await
+    .map_err(|e| format!("提取代码块失败: {}", e))?
+}
+
+/// 列出会话中修改过的文件及改动（Claude Edit/Write、Codex apply_patch 等）
+#[tauri::command]
+pub async fn get_session_file_edits(
+    filePath: String,
+) -> Result<crate::session_edits::SessionEdits, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::session_edits::session_edits(&filePath))
+        .await
+        .map_err(|e| format!("解析文件修改失败: {}", e))?
+}
+
+/// 为对话中的消息添加书签（按条目序号或 uuid）；已有书签时只更新标签
+#[tauri::command]
+pub async fn add_message_bookmark(
+    filePath: String,
+    message: crate::conversation::MessageRef,
+    label: Option<String>,
+) -> Result<crate::conversation_bookmarks::MessageBookmark, String> {
+    crate::conversation_bookmarks::add_bookmark(&filePath, &message, label)
+}
+
+/// 删除消息书签
+#[tauri::command]
+pub async fn remove_message_bookmark(id: String) -> Result<bool, String> {
+    crate::conversation_bookmarks::remove_bookmark(&id)
+}
+
+/// 列出消息书签（最新在前）；指定 filePath 时只列出该对话的书签
+#[tauri::command]
+pub async fn list_message_bookmarks(
+    filePath: Option<String>,
+) -> Result<Vec<crate::conversation_bookmarks::BookmarkEntry>, String> {
+    Ok(crate::conversation_bookmarks::list_bookmarks(filePath.as_deref()))
+}
+
+/// 列出智能标签
+#[tauri::command]
+pub async fn list_smart_tags() -> Result<Vec<crate::conversation_tags::SmartTag>, String> {
+    Ok(crate::conversation_tags::list_smart_tags())
+}
+
+/// 新增或更新智能标签
+#[tauri::command]
+pub async fn upsert_smart_tag(tag: crate::conversation_tags::SmartTag) -> Result<(), String> {
+    crate::conversation_tags::upsert_smart_tag(tag)
+}
+
+/// 删除智能标签
+#[tauri::command]
+pub async fn delete_smart_tag(name: String) -> Result<bool, String> {
+    crate::conversation_tags::delete_smart_tag(&name)
+}
+
 // ==================== 全局规则管理 ====================
 
 /// 读取 Claude 全局规则
 #[tauri::command]
-pub async fn read_claude_rules() -> Result<String, String> {
-    crate::global_rules::read_claude_rules()
+pub async fn read_claude_rules() -> Result<String, AppError> {
+    Ok(crate::global_rules::read_claude_rules()?)
 }
 
 /// 写入 Claude 全局规则
 #[tauri::command]
-pub async fn write_claude_rules(content: String) -> Result<(), String> {
-    crate::global_rules::write_claude_rules(&content)
+pub async fn write_claude_rules(content: String) -> Result<(), AppError> {
+    crate::backups::snapshot_before_write("pre-rules");
+    if let Ok(path) = crate::global_rules::get_claude_rules_path() {
+        crate::undo::capture("write-rules", &[path]);
+    }
+    Ok(crate::global_rules::write_claude_rules(&content)?)
+}
+
+/// 读取 Gemini 全局规则
+#[tauri::command]
+pub async fn read_gemini_rules() -> Result<String, AppError> {
+    Ok(crate::global_rules::read_gemini_rules()?)
+}
+
+/// 写入 Gemini 全局规则
+#[tauri::command]
+pub async fn write_gemini_rules(content: String) -> Result<(), AppError> {
+    crate::backups::snapshot_before_write("pre-rules");
+    crate::undo::capture(
+        "write-rules",
+        &[crate::gemini_config::get_gemini_rules_path()],
+    );
+    Ok(crate::global_rules::write_gemini_rules(&content)?)
 }
 
 /// 列出 Codex 规则文件
 #[tauri::command]
-pub async fn list_codex_rules() -> Result<Vec<crate::global_rules::CodexRuleFile>, String> {
-    crate::global_rules::list_codex_rules()
+pub async fn list_codex_rules() -> Result<Vec<crate::global_rules::CodexRuleFile>, AppError> {
+    Ok(crate::global_rules::list_codex_rules()?)
+}
+
+/// 列出全部配置目录中的规则文件（附所属目录名称）
+#[tauri::command]
+pub async fn list_rules_across_homes() -> Result<Vec<crate::app_homes::HomeRuleFile>, AppError> {
+    Ok(crate::app_homes::list_rules_across_homes()?)
 }
 
 /// 读取 Codex 规则文件
 #[tauri::command]
-pub async fn read_codex_rule(filename: String) -> Result<String, String> {
-    crate::global_rules::read_codex_rule(&filename)
+pub async fn read_codex_rule(filename: String) -> Result<String, AppError> {
+    Ok(crate::global_rules::read_codex_rule(&filename)?)
+}
+
+/// 记录 Codex 规则文件及 config.toml 的修改前内容（文件名无效时交由后续写入报错）
+fn capture_codex_rule(operation: &str, filename: &str) {
+    if crate::global_rules::validate_rule_filename(filename).is_err() {
+        return;
+    }
+    if let Ok(dir) = crate::global_rules::get_codex_rules_dir() {
+        crate::undo::capture(
+            operation,
+            &[dir.join(filename), crate::codex_config::get_codex_config_path()],
+        );
+    }
 }
 
 /// 写入 Codex 规则文件
@@ -1618,12 +2671,1575 @@ pub async fn write_codex_rule(
     filename: String,
     content: String,
     tags: Vec<String>,
-) -> Result<(), String> {
-    crate::global_rules::write_codex_rule(&filename, &content, tags)
+) -> Result<(), AppError> {
+    crate::backups::snapshot_before_write("pre-rules");
+    capture_codex_rule("write-rules", &filename);
+    Ok(crate::global_rules::write_codex_rule(
+        &filename, &content, tags,
+    )?)
 }
 
 /// 删除 Codex 规则文件
 #[tauri::command]
-pub async fn delete_codex_rule(filename: String) -> Result<(), String> {
-    crate::global_rules::delete_codex_rule(&filename)
-}
\ No newline at end of file
+pub async fn delete_codex_rule(filename: String) -> Result<(), AppError> {
+    crate::backups::snapshot_before_write("pre-rules");
+    capture_codex_rule("delete-rule", &filename);
+    Ok(crate::global_rules::delete_codex_rule(&filename)?)
+}
+/// 调整 Codex 规则在 config.toml 中的顺序
+#[tauri::command]
+pub async fn reorder_codex_rules(filenames: Vec<String>) -> Result<(), AppError> {
+    crate::backups::snapshot_before_write("pre-rules");
+    crate::undo::capture(
+        "reorder-rules",
+        &[crate::codex_config::get_codex_config_path()],
+    );
+    Ok(crate::global_rules::reorder_codex_rules(filenames)?)
+}
+
+/// 估算全局规则的 token 数（可临时指定预算，默认读取设置）
+#[tauri::command]
+pub async fn estimate_rules_tokens(
+    budget: Option<usize>,
+) -> Result<crate::global_rules::RulesTokenReport, AppError> {
+    Ok(crate::global_rules::estimate_rules_tokens(budget)?)
+}
+
+/// 向指定通知渠道发送一条测试通知
+#[tauri::command]
+pub async fn test_notification_sink(
+    sink: crate::notification_sinks::NotificationSink,
+) -> Result<(), String> {
+    let payload = crate::notification_sinks::NotificationPayload::new(
+        crate::notification_sinks::NotificationEvent::Switch,
+        "CC Switch 测试通知",
+        "这是一条测试通知",
+    );
+    crate::notification_sinks::send_to_sink(&sink, &payload).await
+}
+
+/// 手动创建配置快照
+#[tauri::command]
+pub async fn create_snapshot(
+    reason: Option<String>,
+) -> Result<crate::backups::SnapshotInfo, String> {
+    crate::backups::create_snapshot(reason.as_deref().unwrap_or("manual"))
+}
+
+/// 列出配置快照
+#[tauri::command]
+pub async fn list_snapshots() -> Result<Vec<crate::backups::SnapshotInfo>, String> {
+    crate::backups::list_snapshots()
+}
+
+/// 从快照恢复（only 为空时恢复全部条目）
+#[tauri::command]
+pub async fn restore_snapshot(
+    state: State<'_, AppState>,
+    id: String,
+    only: Option<Vec<String>>,
+) -> Result<crate::backups::SnapshotRestoreResult, String> {
+    let result = crate::backups::restore_snapshot(&id, only.as_deref())?;
+
+    // 恢复了 config.json 时重新加载内存中的配置，避免下次保存时被覆盖
+    if result.app_config_restored {
+        let reloaded = crate::app_config::MultiAppConfig::load()?;
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        *config = reloaded;
+    }
+
+    Ok(result)
+}
+
+/// 启动自检结果：cc-switch 的 config.json、Claude settings.json 与 Codex config.toml 能否解析
+#[tauri::command]
+pub async fn get_startup_check() -> Result<crate::self_check::StartupReport, String> {
+    Ok(crate::self_check::report())
+}
+
+/// 处理启动自检发现的损坏文件（从最新备份恢复或重置），返回重新检查后的结果
+#[tauri::command]
+pub async fn recover_config_file(
+    state: State<'_, AppState>,
+    target: crate::self_check::CheckTarget,
+    action: crate::self_check::RecoveryAction,
+) -> Result<crate::self_check::StartupReport, String> {
+    let report = crate::self_check::recover(target, action)?;
+
+    // 恢复或重置了 config.json 时重新加载内存中的配置
+    if target == crate::self_check::CheckTarget::AppConfig
+        && !crate::self_check::app_config_corrupt()
+    {
+        let mut reloaded = crate::app_config::MultiAppConfig::load()?;
+        for app_type in [
+            AppType::Claude,
+            AppType::Codex,
+            AppType::Gemini,
+            AppType::OpenCode,
+            AppType::Crush,
+        ] {
+            reloaded.ensure_app(&app_type);
+        }
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        *config = reloaded;
+    }
+
+    Ok(report)
+}
+
+/// 从快照中按键恢复配置（keys 形如 `claude/settings.json:env.ANTHROPIC_BASE_URL`）
+#[tauri::command]
+pub async fn restore_config_keys(
+    state: State<'_, AppState>,
+    id: String,
+    keys: Vec<String>,
+) -> Result<crate::backups::KeyRestoreResult, String> {
+    let result = crate::backups::restore_config_keys(&id, &keys)?;
+
+    if result.app_config_restored {
+        let reloaded = crate::app_config::MultiAppConfig::load()?;
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        *config = reloaded;
+    }
+
+    Ok(result)
+}
+
+/// 列出尚未处理的外部修改冲突
+#[tauri::command]
+pub async fn list_write_conflicts() -> Result<Vec<crate::write_guard::WriteConflict>, String> {
+    Ok(crate::write_guard::list_conflicts())
+}
+
+/// 处理外部修改冲突；选择 overwrite 或 merge 后由前端重试原操作
+#[tauri::command]
+pub async fn resolve_write_conflict(
+    path: String,
+    resolution: crate::write_guard::ConflictResolution,
+) -> Result<(), String> {
+    crate::write_guard::resolve(&path, resolution)
+}
+
+/// 获取配置版本库状态
+#[tauri::command]
+pub async fn get_config_history_status(
+) -> Result<crate::config_history::ConfigHistoryStatus, String> {
+    Ok(crate::config_history::status())
+}
+
+/// 启用或停用配置版本库；启用时初始化仓库并提交当前配置
+#[tauri::command]
+pub async fn set_config_history(
+    enabled: bool,
+    includeRules: Option<bool>,
+) -> Result<crate::config_history::ConfigHistoryStatus, String> {
+    if enabled {
+        crate::config_history::enable(includeRules.unwrap_or(false))
+    } else {
+        crate::config_history::disable()
+    }
+}
+
+/// 配置版本历史（最新在前），可按文件过滤
+#[tauri::command]
+pub async fn list_config_history(
+    limit: Option<usize>,
+    file: Option<String>,
+) -> Result<Vec<crate::config_history::ConfigCommit>, String> {
+    crate::config_history::log(limit, file.as_deref())
+}
+
+/// 查看某个版本的改动
+#[tauri::command]
+pub async fn get_config_history_diff(
+    commit: String,
+    file: Option<String>,
+) -> Result<String, String> {
+    crate::config_history::diff(&commit, file.as_deref())
+}
+
+/// 恢复到某个历史版本（files 为空时恢复该版本中的全部文件）
+#[tauri::command]
+pub async fn checkout_config_version(
+    state: State<'_, AppState>,
+    commit: String,
+    files: Option<Vec<String>>,
+) -> Result<crate::config_history::ConfigCheckoutResult, String> {
+    let result = crate::config_history::checkout(&commit, files.as_deref())?;
+
+    if result.app_config_restored {
+        let reloaded = crate::app_config::MultiAppConfig::load()?;
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        *config = reloaded;
+    }
+
+    Ok(result)
+}
+
+/// 清除配置版本库的全部历史，并以当前配置重新初始化
+#[tauri::command]
+pub async fn purge_config_history() -> Result<crate::config_history::ConfigHistoryStatus, String> {
+    crate::config_history::purge()
+}
+
+/// 清理旧快照，仅保留最近 keep 份
+#[tauri::command]
+pub async fn prune_snapshots(keep: usize) -> Result<usize, String> {
+    crate::backups::prune_snapshots(keep)
+}
+
+/// 按工作目录查找匹配的工作区
+#[tauri::command]
+pub async fn detect_workspace(cwd: String) -> Result<Option<crate::workspaces::Workspace>, String> {
+    Ok(crate::workspaces::match_workspace(&cwd))
+}
+
+/// 预览目录对应的项目环境变量（与 `cc-switch profile env` 输出一致）
+#[tauri::command]
+pub async fn get_project_env(
+    state: State<'_, AppState>,
+    cwd: String,
+) -> Result<crate::project_profiles::ProjectEnv, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    Ok(crate::project_profiles::project_env(&config, &cwd))
+}
+
+/// 生成进入项目目录时自动注入供应商环境变量的 shell 钩子
+#[tauri::command]
+pub async fn get_profile_shell_hook(shell: String) -> Result<String, String> {
+    let exe = crate::project_profiles::current_exe()?;
+    crate::project_profiles::shell_hook(&shell, &exe)
+}
+
+/// 生成 Claude ↔ Codex 工作流迁移计划（dry-run，列出将创建/修改的每个文件）
+#[tauri::command]
+pub async fn plan_workflow_migration(
+    state: State<'_, AppState>,
+    direction: crate::workflow_migration::MigrationDirection,
+    projectPath: String,
+    providerId: Option<String>,
+) -> Result<crate::workflow_migration::MigrationPlan, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    crate::workflow_migration::plan_migration(
+        &config,
+        direction,
+        &projectPath,
+        providerId.as_deref(),
+    )
+}
+
+/// 执行 Claude ↔ Codex 工作流迁移
+#[tauri::command]
+pub async fn apply_workflow_migration(
+    state: State<'_, AppState>,
+    direction: crate::workflow_migration::MigrationDirection,
+    projectPath: String,
+    providerId: Option<String>,
+) -> Result<crate::workflow_migration::MigrationPlan, String> {
+    let plan = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::workflow_migration::apply_migration(
+            &mut config,
+            direction,
+            &projectPath,
+            providerId.as_deref(),
+        )?
+    };
+    state.save()?;
+    Ok(plan)
+}
+
+/// 解析文本形式的事件规则（保存前校验）
+#[tauri::command]
+pub async fn parse_event_rule(text: String) -> Result<crate::event_rules::EventRule, String> {
+    crate::event_rules::parse_rule(&text)
+}
+
+/// 将 Markdown 渲染为净化后的 HTML（规则编辑器预览）
+#[tauri::command]
+pub async fn render_markdown(content: String) -> Result<String, String> {
+    Ok(crate::markdown::render_markdown(&content))
+}
+
+/// 检查全局规则文件的常见问题
+#[tauri::command]
+pub async fn lint_rules() -> Result<Vec<crate::global_rules::RuleDiagnostic>, AppError> {
+    Ok(crate::global_rules::lint_rules()?)
+}
+
+/// 导出规则包（zip）
+#[tauri::command]
+pub async fn export_rules(file_path: String) -> Result<(), AppError> {
+    Ok(crate::global_rules::export_rules(&file_path)?)
+}
+
+/// 导入规则包（zip），strategy 为 skip / overwrite / rename
+#[tauri::command]
+pub async fn import_rules(
+    file_path: String,
+    strategy: crate::global_rules::RuleConflictStrategy,
+) -> Result<crate::global_rules::RulesImportResult, AppError> {
+    crate::backups::snapshot_before_write("pre-rules");
+    Ok(crate::global_rules::import_rules(&file_path, strategy)?)
+}
+
+// =====================
+// Claude 子代理（~/.claude/agents 与 <项目>/.claude/agents）
+// =====================
+
+/// 修改子代理文件前记录撤销点
+fn capture_agent_undo(
+    scope: crate::agents::AgentScope,
+    project_path: Option<&str>,
+    file_name: &str,
+) {
+    if let Ok(dir) = crate::agents::agents_dir(scope, project_path) {
+        crate::undo::capture("write-agent", &[dir.join(file_name.trim())]);
+    }
+}
+
+/// 列出子代理：全局，以及传入项目目录时的项目作用域
+#[tauri::command]
+pub async fn list_agents(
+    projectPath: Option<String>,
+) -> Result<Vec<crate::agents::AgentInfo>, String> {
+    crate::agents::list_agents(projectPath.as_deref())
+}
+
+/// 获取子代理模板
+#[tauri::command]
+pub async fn list_agent_templates() -> Result<Vec<crate::agents::AgentTemplate>, String> {
+    Ok(crate::agents::agent_templates())
+}
+
+/// 读取子代理
+#[tauri::command]
+pub async fn read_agent(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    fileName: String,
+) -> Result<crate::agents::AgentInfo, String> {
+    crate::agents::read_agent(scope, projectPath.as_deref(), &fileName)
+}
+
+/// 从模板创建子代理
+#[tauri::command]
+pub async fn create_agent(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    templateId: String,
+    name: String,
+    description: Option<String>,
+) -> Result<crate::agents::AgentInfo, String> {
+    crate::agents::create_agent(
+        scope,
+        projectPath.as_deref(),
+        &templateId,
+        &name,
+        description.as_deref(),
+    )
+}
+
+/// 以原始内容保存子代理（校验 YAML frontmatter）
+#[tauri::command]
+pub async fn write_agent(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    fileName: String,
+    content: String,
+) -> Result<crate::agents::AgentInfo, String> {
+    capture_agent_undo(scope, projectPath.as_deref(), &fileName);
+    crate::agents::write_agent(scope, projectPath.as_deref(), &fileName, &content)
+}
+
+/// 修改子代理 frontmatter（name、description、tools、model）
+#[tauri::command]
+pub async fn update_agent_frontmatter(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    fileName: String,
+    update: crate::agents::AgentFrontmatterUpdate,
+) -> Result<crate::agents::AgentInfo, String> {
+    capture_agent_undo(scope, projectPath.as_deref(), &fileName);
+    crate::agents::update_agent_frontmatter(scope, projectPath.as_deref(), &fileName, &update)
+}
+
+/// 删除子代理
+#[tauri::command]
+pub async fn delete_agent(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    fileName: String,
+) -> Result<(), String> {
+    capture_agent_undo(scope, projectPath.as_deref(), &fileName);
+    crate::agents::delete_agent(scope, projectPath.as_deref(), &fileName)
+}
+
+/// 在全局与项目作用域之间复制子代理
+#[tauri::command]
+pub async fn copy_agent(
+    from: crate::agents::AgentScope,
+    to: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    fileName: String,
+    overwrite: Option<bool>,
+) -> Result<crate::agents::AgentInfo, String> {
+    capture_agent_undo(to, projectPath.as_deref(), &fileName);
+    crate::agents::copy_agent(
+        from,
+        to,
+        projectPath.as_deref(),
+        &fileName,
+        overwrite.unwrap_or(false),
+    )
+}
+
+/// 校验子代理内容的 YAML frontmatter
+#[tauri::command]
+pub async fn validate_agent(content: String) -> Result<(), String> {
+    crate::agents::validate_agent_content(&content)
+}
+
+// =====================
+// Claude 斜杠命令与技能（.claude/commands、.claude/skills）
+// =====================
+
+/// 修改斜杠命令前记录撤销点
+fn capture_command_undo(
+    scope: crate::agents::AgentScope,
+    project_path: Option<&str>,
+    relative_path: &str,
+) {
+    if let Ok(dir) = crate::claude_commands::commands_dir(scope, project_path) {
+        crate::undo::capture("write-command", &[dir.join(relative_path.trim())]);
+    }
+}
+
+/// 修改技能前记录撤销点（仅 SKILL.md）
+fn capture_skill_undo(scope: crate::agents::AgentScope, project_path: Option<&str>, name: &str) {
+    if let Ok(dir) = crate::claude_commands::skills_dir(scope, project_path) {
+        let path = dir.join(name.trim()).join("SKILL.md");
+        crate::undo::capture("write-skill", &[path]);
+    }
+}
+
+/// 列出斜杠命令：全局，以及传入项目目录时的项目作用域
+#[tauri::command]
+pub async fn list_slash_commands(
+    projectPath: Option<String>,
+) -> Result<Vec<crate::claude_commands::SlashCommandInfo>, String> {
+    crate::claude_commands::list_commands(projectPath.as_deref())
+}
+
+/// 读取斜杠命令（relativePath 相对 commands 目录，如 frontend/component.md）
+#[tauri::command]
+pub async fn read_slash_command(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    relativePath: String,
+) -> Result<crate::claude_commands::SlashCommandInfo, String> {
+    crate::claude_commands::read_command(scope, projectPath.as_deref(), &relativePath)
+}
+
+/// 新建斜杠命令
+#[tauri::command]
+pub async fn create_slash_command(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    relativePath: String,
+    description: Option<String>,
+    body: Option<String>,
+) -> Result<crate::claude_commands::SlashCommandInfo, String> {
+    crate::claude_commands::create_command(
+        scope,
+        projectPath.as_deref(),
+        &relativePath,
+        description.as_deref().unwrap_or_default(),
+        body.as_deref().unwrap_or_default(),
+    )
+}
+
+/// 以原始内容保存斜杠命令（校验 frontmatter）
+#[tauri::command]
+pub async fn write_slash_command(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    relativePath: String,
+    content: String,
+) -> Result<crate::claude_commands::SlashCommandInfo, String> {
+    capture_command_undo(scope, projectPath.as_deref(), &relativePath);
+    crate::claude_commands::write_command(scope, projectPath.as_deref(), &relativePath, &content)
+}
+
+/// 删除斜杠命令
+#[tauri::command]
+pub async fn delete_slash_command(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    relativePath: String,
+) -> Result<(), String> {
+    capture_command_undo(scope, projectPath.as_deref(), &relativePath);
+    crate::claude_commands::delete_command(scope, projectPath.as_deref(), &relativePath)
+}
+
+/// 校验斜杠命令内容的 frontmatter
+#[tauri::command]
+pub async fn validate_slash_command(content: String) -> Result<(), String> {
+    crate::claude_commands::validate_command_content(&content)
+}
+
+/// 列出技能：全局，以及传入项目目录时的项目作用域
+#[tauri::command]
+pub async fn list_skills(
+    projectPath: Option<String>,
+) -> Result<Vec<crate::claude_commands::SkillInfo>, String> {
+    crate::claude_commands::list_skills(projectPath.as_deref())
+}
+
+/// 读取技能
+#[tauri::command]
+pub async fn read_skill(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    name: String,
+) -> Result<crate::claude_commands::SkillInfo, String> {
+    crate::claude_commands::read_skill(scope, projectPath.as_deref(), &name)
+}
+
+/// 新建技能
+#[tauri::command]
+pub async fn create_skill(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    name: String,
+    description: String,
+    body: Option<String>,
+) -> Result<crate::claude_commands::SkillInfo, String> {
+    crate::claude_commands::create_skill(
+        scope,
+        projectPath.as_deref(),
+        &name,
+        &description,
+        body.as_deref().unwrap_or_default(),
+    )
+}
+
+/// 以原始内容保存技能的 SKILL.md（校验 frontmatter）
+#[tauri::command]
+pub async fn write_skill(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    name: String,
+    content: String,
+) -> Result<crate::claude_commands::SkillInfo, String> {
+    capture_skill_undo(scope, projectPath.as_deref(), &name);
+    crate::claude_commands::write_skill(scope, projectPath.as_deref(), &name, &content)
+}
+
+/// 删除技能目录
+#[tauri::command]
+pub async fn delete_skill(
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    name: String,
+) -> Result<(), String> {
+    capture_skill_undo(scope, projectPath.as_deref(), &name);
+    crate::claude_commands::delete_skill(scope, projectPath.as_deref(), &name)
+}
+
+/// 校验 SKILL.md 内容的 frontmatter
+#[tauri::command]
+pub async fn validate_skill(content: String) -> Result<(), String> {
+    crate::claude_commands::validate_skill_content(&content)
+}
+
+/// 导入共享命令包（zip，含 commands/ 与 skills/），strategy 为 skip / overwrite / rename
+#[tauri::command]
+pub async fn import_command_pack(
+    filePath: String,
+    scope: crate::agents::AgentScope,
+    projectPath: Option<String>,
+    strategy: crate::global_rules::RuleConflictStrategy,
+) -> Result<crate::global_rules::RulesImportResult, String> {
+    crate::backups::snapshot_before_write("pre-command-pack");
+    crate::claude_commands::import_pack(&filePath, scope, projectPath.as_deref(), strategy)
+}
+
+// =====================
+// Codex profiles（config.toml 中的 [profiles.*]）
+// =====================
+
+fn capture_codex_profile_undo() {
+    crate::undo::capture(
+        "codex-profile",
+        &[crate::codex_config::get_codex_config_path()],
+    );
+}
+
+/// 列出 Codex profiles
+#[tauri::command]
+pub async fn list_codex_profiles() -> Result<Vec<crate::codex_profiles::CodexProfile>, String> {
+    crate::codex_profiles::list_profiles()
+}
+
+/// 新建 Codex profile，settings 为 profile 表内容（JSON 对象）
+#[tauri::command]
+pub async fn create_codex_profile(
+    name: String,
+    settings: serde_json::Value,
+) -> Result<crate::codex_profiles::CodexProfile, String> {
+    capture_codex_profile_undo();
+    crate::codex_profiles::create_profile(&name, &settings)
+}
+
+/// 修改 Codex profile；传入 newName 时同时重命名
+#[tauri::command]
+pub async fn update_codex_profile(
+    name: String,
+    settings: serde_json::Value,
+    newName: Option<String>,
+) -> Result<crate::codex_profiles::CodexProfile, String> {
+    capture_codex_profile_undo();
+    crate::codex_profiles::update_profile(&name, &settings, newName.as_deref())
+}
+
+/// 复制 Codex profile
+#[tauri::command]
+pub async fn duplicate_codex_profile(
+    name: String,
+    newName: String,
+) -> Result<crate::codex_profiles::CodexProfile, String> {
+    capture_codex_profile_undo();
+    crate::codex_profiles::duplicate_profile(&name, &newName)
+}
+
+/// 删除 Codex profile
+#[tauri::command]
+pub async fn delete_codex_profile(name: String) -> Result<(), String> {
+    capture_codex_profile_undo();
+    crate::codex_profiles::delete_profile(&name)
+}
+
+/// 设置默认 Codex profile；name 为空时移除默认设置
+#[tauri::command]
+pub async fn set_default_codex_profile(name: Option<String>) -> Result<(), String> {
+    capture_codex_profile_undo();
+    crate::codex_profiles::set_default_profile(name.as_deref())
+}
+
+/// 读取 Codex config.toml 中的 model、model_reasoning_effort、approval_policy、sandbox_mode
+#[tauri::command]
+pub async fn get_codex_quick_settings(
+) -> Result<crate::codex_settings::CodexQuickSettings, String> {
+    crate::codex_settings::read_quick_settings()
+}
+
+/// 受限字段的可选值（供下拉框使用）
+#[tauri::command]
+pub async fn get_codex_setting_options(
+) -> Result<Vec<crate::codex_settings::CodexSettingOptions>, String> {
+    Ok(crate::codex_settings::setting_options())
+}
+
+/// 设置 Codex config.toml 的单个顶层键；value 为空时移除
+#[tauri::command]
+pub async fn set_codex_quick_setting(
+    key: crate::codex_settings::CodexSettingKey,
+    value: Option<String>,
+) -> Result<crate::codex_settings::CodexQuickSettings, String> {
+    crate::undo::capture(
+        "codex-settings",
+        &[crate::codex_config::get_codex_config_path()],
+    );
+    crate::codex_settings::set_quick_setting(key, value.as_deref())
+}
+
+// =====================
+// Claude settings.json 结构化编辑
+// =====================
+
+fn capture_claude_settings_undo() {
+    crate::undo::capture("claude-settings", &[get_claude_settings_path()]);
+}
+
+/// 读取类型化的 Claude settings.json
+#[tauri::command]
+pub async fn get_claude_settings() -> Result<crate::claude_settings::ClaudeSettings, AppError> {
+    crate::claude_settings::read_settings()
+}
+
+/// 整体保存 Claude settings.json（按 Schema 校验）
+#[tauri::command]
+pub async fn save_claude_settings(
+    settings: crate::claude_settings::ClaudeSettings,
+) -> Result<crate::claude_settings::ClaudeSettings, AppError> {
+    capture_claude_settings_undo();
+    crate::claude_settings::write_settings(&settings)
+}
+
+/// 读取 settings.json 的单个配置段（env / permissions / model / hooks / statusLine /
+/// enabledPlugins / extraKnownMarketplaces）
+#[tauri::command]
+pub async fn get_claude_settings_section(
+    section: crate::claude_settings::ClaudeSettingsSection,
+) -> Result<serde_json::Value, AppError> {
+    crate::claude_settings::get_section(section)
+}
+
+/// 写入 settings.json 的单个配置段；value 为 null 时移除
+#[tauri::command]
+pub async fn set_claude_settings_section(
+    section: crate::claude_settings::ClaudeSettingsSection,
+    value: serde_json::Value,
+) -> Result<crate::claude_settings::ClaudeSettings, AppError> {
+    capture_claude_settings_undo();
+    crate::claude_settings::set_section(section, value)
+}
+
+/// 设置 settings.json 中的单个环境变量；value 为空时移除
+#[tauri::command]
+pub async fn set_claude_env_var(
+    key: String,
+    value: Option<String>,
+) -> Result<crate::claude_settings::ClaudeSettings, AppError> {
+    capture_claude_settings_undo();
+    crate::claude_settings::set_env_var(&key, value.as_deref())
+}
+
+/// 列出 Claude Code 插件市场（settings.json 声明与已下载的）
+#[tauri::command]
+pub async fn list_claude_marketplaces(
+) -> Result<Vec<crate::claude_marketplace::ClaudeMarketplace>, String> {
+    crate::claude_marketplace::list_marketplaces()
+}
+
+/// 列出已安装或在 enabledPlugins 中声明的 Claude Code 插件
+#[tauri::command]
+pub async fn list_claude_plugins() -> Result<Vec<crate::claude_marketplace::ClaudePlugin>, String> {
+    crate::claude_marketplace::list_plugins()
+}
+
+/// 启用 / 禁用插件（id 形如 plugin@marketplace）；enabled 为空时移除声明
+#[tauri::command]
+pub async fn set_claude_plugin_enabled(
+    id: String,
+    enabled: Option<bool>,
+) -> Result<Vec<crate::claude_marketplace::ClaudePlugin>, String> {
+    capture_claude_settings_undo();
+    crate::claude_marketplace::set_plugin_enabled(&id, enabled)
+}
+
+/// 在 settings.json 中声明或更新插件市场
+#[tauri::command]
+pub async fn upsert_claude_marketplace(
+    name: String,
+    source: serde_json::Value,
+) -> Result<Vec<crate::claude_marketplace::ClaudeMarketplace>, String> {
+    capture_claude_settings_undo();
+    crate::claude_marketplace::upsert_marketplace(&name, source)
+}
+
+/// 移除 settings.json 中的插件市场声明；removePlugins 为 true 时一并移除其插件的启用状态
+#[tauri::command]
+pub async fn remove_claude_marketplace(
+    name: String,
+    removePlugins: Option<bool>,
+) -> Result<Vec<crate::claude_marketplace::ClaudeMarketplace>, String> {
+    capture_claude_settings_undo();
+    crate::claude_marketplace::remove_marketplace(&name, removePlugins.unwrap_or(false))
+}
+
+/// 获取 settings.json 的 JSON Schema
+#[tauri::command]
+pub async fn get_claude_settings_schema() -> Result<serde_json::Value, String> {
+    Ok(crate::claude_settings::settings_schema())
+}
+
+/// 按 Schema 校验 settings.json 内容，返回全部错误（为空表示有效）
+#[tauri::command]
+pub async fn validate_claude_settings(
+    content: String,
+) -> Result<Vec<crate::claude_settings::SettingsSchemaError>, String> {
+    let value: serde_json::Value =
+        serde_json::from_str(&content).map_err(|e| format!("JSON 语法错误: {}", e))?;
+    Ok(crate::claude_settings::validate_settings_value(&value))
+}
+
+/// 获取 Claude Code 状态栏的安装状态
+#[tauri::command]
+pub async fn get_statusline_status() -> Result<crate::statusline::StatuslineStatus, String> {
+    Ok(crate::statusline::status())
+}
+
+/// 安装（或更新格式）cc-switch 状态栏，写入 settings.json 的 statusLine
+#[tauri::command]
+pub async fn install_statusline(
+    format: Option<String>,
+) -> Result<crate::statusline::StatuslineStatus, String> {
+    capture_claude_settings_undo();
+    crate::statusline::install(format)
+}
+
+/// 卸载 cc-switch 状态栏
+#[tauri::command]
+pub async fn uninstall_statusline() -> Result<crate::statusline::StatuslineStatus, String> {
+    capture_claude_settings_undo();
+    crate::statusline::uninstall()
+}
+
+/// 校验候选配置文本（Claude settings.json、Codex config.toml 等），
+/// 返回带行列号的语法错误与语义警告，供编辑器在保存前内联展示
+#[tauri::command]
+pub async fn validate_config_text(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    text: String,
+) -> Result<crate::config_validation::ConfigTextValidation, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    Ok(crate::config_validation::validate_config_text(
+        &app_type, &text,
+    ))
+}
+
+/// 探测 claude / codex CLI 是否可用（暂存验证依赖 `--version` 检查）
+#[tauri::command]
+pub async fn probe_cli_versions() -> Result<Vec<crate::staged_apply::CliProbe>, String> {
+    tauri::async_runtime::spawn_blocking(crate::staged_apply::probe_clis)
+        .await
+        .map_err(|e| format!("探测任务失败: {}", e))
+}
+
+/// 读取 Claude 登录状态（OAuth 订阅账号或 API Key）
+#[tauri::command]
+pub async fn get_claude_auth_status() -> Result<crate::claude_auth::ClaudeAuthStatus, String> {
+    tauri::async_runtime::spawn_blocking(crate::claude_auth::status)
+        .await
+        .map_err(|e| format!("读取登录状态失败: {}", e))
+}
+
+/// 退出 Claude OAuth 登录（删除凭据与账号信息，API Key 配置不受影响）
+#[tauri::command]
+pub async fn logout_claude_oauth() -> Result<crate::claude_auth::ClaudeAuthStatus, String> {
+    crate::undo::capture("claude-logout", &crate::claude_auth::auth_paths());
+    crate::claude_auth::logout()
+}
+
+// =====================
+// 环境变量（Claude settings.json 的 env 与 shell 配置文件导出）
+// =====================
+
+/// 汇总各处的环境变量取值并标记冲突
+#[tauri::command]
+pub async fn get_env_overview() -> Result<crate::env_manager::EnvOverview, String> {
+    crate::env_manager::env_overview()
+}
+
+/// 设置 settings.json env 中的变量；value 为空时移除
+#[tauri::command]
+pub async fn set_settings_env_var(
+    name: String,
+    value: Option<String>,
+) -> Result<crate::env_manager::EnvOverview, String> {
+    capture_claude_settings_undo();
+    crate::env_manager::set_settings_env(&name, value.as_deref())?;
+    crate::env_manager::env_overview()
+}
+
+/// 在 shell 配置文件中设置变量导出；value 为空时注释掉已有导出
+#[tauri::command]
+pub async fn set_shell_env_export(
+    file: String,
+    name: String,
+    value: Option<String>,
+) -> Result<crate::env_manager::EnvOverview, String> {
+    crate::undo::capture("env", &[std::path::PathBuf::from(&file)]);
+    crate::env_manager::set_shell_export(&file, &name, value.as_deref())?;
+    crate::env_manager::env_overview()
+}
+
+/// 以指定值统一 settings.json 与 shell 配置文件中的变量；commentShell 为 true 时注释掉 shell 导出
+#[tauri::command]
+pub async fn reconcile_env_var(
+    name: String,
+    value: String,
+    commentShell: Option<bool>,
+) -> Result<crate::env_manager::EnvOverview, String> {
+    crate::undo::capture("env", &crate::env_manager::affected_paths());
+    crate::env_manager::reconcile(&name, &value, commentShell.unwrap_or(false))
+}
+
+// =====================
+// 代理
+// =====================
+
+/// 设置全局代理（为空时移除），并更新未单独配置代理的当前供应商的 live 配置
+#[tauri::command]
+pub async fn set_global_proxy(
+    state: State<'_, AppState>,
+    proxy: Option<crate::proxy::ProxyConfig>,
+) -> Result<bool, String> {
+    let proxy = proxy.filter(|p| !p.url.trim().is_empty());
+    if let Some(proxy) = proxy.as_ref() {
+        crate::proxy::validate_proxy(proxy)?;
+    }
+    let mut settings = crate::settings::get_settings();
+    let old_proxy = crate::proxy::global_proxy();
+    settings.proxy = proxy;
+    crate::settings::update_settings(settings)?;
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    crate::proxy::reapply_global(
+        &config,
+        old_proxy.as_ref(),
+        crate::proxy::global_proxy().as_ref(),
+    );
+    Ok(true)
+}
+
+/// 设置供应商专用代理：proxy 为空时改用全局代理，url 为空表示直连；
+/// 若为当前供应商则同时更新 live 配置
+#[tauri::command]
+pub async fn set_provider_proxy(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    id: String,
+    proxy: Option<crate::proxy::ProxyConfig>,
+) -> Result<bool, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    if let Some(proxy) = proxy.as_ref().filter(|p| !p.url.trim().is_empty()) {
+        crate::proxy::validate_proxy(proxy)?;
+    }
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        let is_current = manager.current == id;
+        let provider = manager
+            .providers
+            .get_mut(&id)
+            .ok_or_else(|| crate::error::provider_not_found(&id))?;
+        let old_proxy = crate::proxy::effective_proxy(provider);
+        provider.meta.get_or_insert_with(Default::default).proxy = proxy;
+        if is_current {
+            let new_proxy = crate::proxy::effective_proxy(provider);
+            crate::proxy::reapply_live(&app_type, old_proxy.as_ref(), new_proxy.as_ref())?;
+        }
+    }
+    state.save()?;
+    Ok(true)
+}
+
+/// 测试代理连通性：经代理请求目标地址（默认 https://api.anthropic.com）
+#[tauri::command]
+pub async fn test_proxy(
+    proxy: crate::proxy::ProxyConfig,
+    url: Option<String>,
+    timeoutSecs: Option<u64>,
+) -> Result<crate::proxy::ProxyTestResult, String> {
+    let timeout = crate::speedtest::sanitize_timeout(timeoutSecs);
+    crate::proxy::test_proxy(&proxy, url.as_deref(), timeout).await
+}
+
+/// 获取 API Key 加密存储状态
+#[tauri::command]
+pub async fn get_secrets_status() -> Result<crate::secrets::SecretsStatus, String> {
+    crate::secrets::status()
+}
+
+/// 输入主密码解锁加密的 API Key（首次调用时以该密码创建密钥库）
+#[tauri::command]
+pub async fn unlock_secrets(password: String) -> Result<crate::secrets::SecretsStatus, String> {
+    crate::secrets::unlock(&password)
+}
+
+/// 锁定密钥库（清除内存中的密钥）
+#[tauri::command]
+pub async fn lock_secrets() -> Result<bool, String> {
+    crate::secrets::lock();
+    Ok(true)
+}
+
+/// 修改主密码
+#[tauri::command]
+pub async fn change_secrets_password(
+    oldPassword: String,
+    newPassword: String,
+) -> Result<bool, String> {
+    crate::secrets::change_password(&oldPassword, &newPassword)?;
+    Ok(true)
+}
+
+/// 将所有供应商的明文 API Key 迁移到加密密钥库，配置中仅保留引用
+#[tauri::command]
+pub async fn migrate_keys_to_vault(
+    state: State<'_, AppState>,
+) -> Result<crate::secrets::SecretsMigration, String> {
+    crate::backups::snapshot_before_write("pre-encrypt");
+    let result = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::secrets::migrate_to_vault(&mut config)?
+    };
+    state.save()?;
+    Ok(result)
+}
+
+/// 将加密的 API Key 还原为明文保存
+#[tauri::command]
+pub async fn migrate_keys_to_plaintext(
+    state: State<'_, AppState>,
+) -> Result<crate::secrets::SecretsMigration, String> {
+    let result = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::secrets::migrate_to_plaintext(&mut config)?
+    };
+    state.save()?;
+    Ok(result)
+}
+
+/// 导出包含明文 API Key 的完整配置（用于迁移到其他设备）
+#[tauri::command]
+pub async fn export_config_with_secrets(
+    state: State<'_, AppState>,
+    filePath: String,
+) -> Result<bool, AppError> {
+    let exported = {
+        let config = state.config.lock()?;
+        crate::secrets::export_with_secrets(&config)?
+    };
+    let target = crate::export_naming::reserve_export_file(std::path::Path::new(&filePath))?;
+    crate::config::write_json_file(&target, &exported)?;
+    Ok(true)
+}
+
+/// 扫描环境变量与现有 live 配置，返回可导入的候选供应商
+#[tauri::command]
+pub async fn discover_providers(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::provider_discovery::ProviderCandidate>, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    Ok(crate::provider_discovery::discover_providers(&config))
+}
+
+/// 导入选中的候选供应商（重复项自动跳过），返回新增供应商的 { appType, id }
+#[tauri::command]
+pub async fn import_discovered_providers(
+    state: State<'_, AppState>,
+    candidates: Vec<crate::provider_discovery::ProviderCandidate>,
+) -> Result<Vec<serde_json::Value>, String> {
+    let added = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::provider_discovery::import_candidates(&mut config, candidates)
+    };
+    if !added.is_empty() {
+        state.save()?;
+    }
+    Ok(added
+        .into_iter()
+        .map(|(app_type, id)| serde_json::json!({ "appType": app_type, "id": id }))
+        .collect())
+}
+
+/// 扫描其他供应商切换工具（ccs、claude-code-router）的默认配置位置
+#[tauri::command]
+pub async fn scan_external_providers(
+    state: State<'_, AppState>,
+) -> Result<Vec<crate::provider_import::ExternalToolSource>, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    Ok(crate::provider_import::scan(&config))
+}
+
+/// 解析其他工具的配置文件为候选供应商；tool 为空时按内容推断格式。
+/// 确认后通过 import_discovered_providers 导入
+#[tauri::command]
+pub async fn parse_external_providers(
+    state: State<'_, AppState>,
+    path: String,
+    tool: Option<crate::provider_import::ExternalTool>,
+) -> Result<Vec<crate::provider_discovery::ProviderCandidate>, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    let path = crate::settings::resolve_override_path(path.trim());
+    crate::provider_import::parse_file(&config, &path, tool)
+}
+
+/// 首次运行向导：检测 ~/.claude 与 ~/.codex 的现有配置
+#[tauri::command]
+pub async fn detect_existing_setup(
+    state: State<'_, AppState>,
+) -> Result<crate::onboarding::ExistingSetupReport, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    Ok(crate::onboarding::detect_existing_setup(&config))
+}
+
+/// 首次运行向导：一键导入现有供应商与 MCP 服务器
+#[tauri::command]
+pub async fn import_existing_setup(
+    state: State<'_, AppState>,
+    includeEnv: Option<bool>,
+) -> Result<crate::onboarding::SetupImportResult, String> {
+    let result = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::onboarding::import_existing_setup(&mut config, includeEnv.unwrap_or(false))
+    };
+    if !result.providers.is_empty() || result.mcp_imported > 0 {
+        state.save()?;
+    }
+    Ok(result)
+}
+
+/// 导出供应商列表为可分享的 JSON；includeSecrets 为 false 时清空 API Key
+#[tauri::command]
+pub async fn export_providers(
+    state: State<'_, AppState>,
+    includeSecrets: bool,
+) -> Result<String, String> {
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    crate::provider_share::export_providers(&config, includeSecrets)
+}
+
+/// 导入分享的供应商列表；未包含 API Key 的供应商会被标记为需要补充 Key
+#[tauri::command]
+pub async fn import_providers(
+    state: State<'_, AppState>,
+    json: String,
+) -> Result<crate::provider_share::SharedImportResult, String> {
+    let result = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        crate::provider_share::import_providers(&mut config, &json)?
+    };
+    if !result.imported.is_empty() {
+        state.save()?;
+    }
+    Ok(result)
+}
+
+/// 列出供应商分组（按显示顺序，末尾附加未分组的供应商）
+#[tauri::command]
+pub async fn get_provider_groups(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<Vec<crate::provider_groups::ProviderGroupInfo>, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    let manager = config
+        .get_manager(&app_type)
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+    Ok(crate::provider_groups::list_groups(manager))
+}
+
+/// 新建供应商分组
+#[tauri::command]
+pub async fn create_provider_group(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    name: String,
+) -> Result<crate::provider::ProviderGroup, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let group = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        crate::provider_groups::create_group(manager, &name)?
+    };
+    state.save()?;
+    Ok(group)
+}
+
+/// 重命名供应商分组
+#[tauri::command]
+pub async fn rename_provider_group(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    groupId: String,
+    name: String,
+) -> Result<bool, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        crate::provider_groups::rename_group(manager, &groupId, &name)?;
+    }
+    state.save()?;
+    Ok(true)
+}
+
+/// 删除供应商分组（组内供应商变为未分组，不会被删除）
+#[tauri::command]
+pub async fn delete_provider_group(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    groupId: String,
+) -> Result<bool, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        crate::provider_groups::delete_group(manager, &groupId)?;
+    }
+    state.save()?;
+    Ok(true)
+}
+
+/// 按给定顺序重排供应商分组
+#[tauri::command]
+pub async fn reorder_provider_groups(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    groupIds: Vec<String>,
+) -> Result<bool, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        crate::provider_groups::reorder_groups(manager, &groupIds)?;
+    }
+    state.save()?;
+    Ok(true)
+}
+
+/// 将供应商移动到分组（groupId 为空时移出分组），返回实际移动的数量
+#[tauri::command]
+pub async fn move_providers_to_group(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    providerIds: Vec<String>,
+    groupId: Option<String>,
+) -> Result<usize, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let moved = {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        crate::provider_groups::move_providers(manager, &providerIds, groupId.as_deref())?
+    };
+    if moved > 0 {
+        state.save()?;
+    }
+    Ok(moved)
+}
+
+/// 批量启用 / 停用分组内的全部供应商
+#[tauri::command]
+pub async fn set_provider_group_disabled(
+    state: State<'_, AppState>,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    groupId: String,
+    disabled: bool,
+) -> Result<bool, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager_mut(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        crate::provider_groups::set_group_disabled(manager, &groupId, disabled)?;
+    }
+    state.save()?;
+    Ok(true)
+}
+
+/// 获取供应商切换历史（最新在前）
+#[tauri::command]
+pub async fn get_switch_history(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+    limit: Option<usize>,
+) -> Result<Vec<crate::switch_history::SwitchRecord>, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()));
+    Ok(crate::switch_history::get_history(
+        app_type.as_ref().map(|a| a.as_str()),
+        limit,
+    ))
+}
+
+/// 读取后台事件日志（最新在前）
+#[tauri::command]
+pub async fn list_notifications(
+    limit: Option<usize>,
+) -> Result<Vec<crate::notifications::NotificationRecord>, String> {
+    Ok(crate::notifications::list(limit))
+}
+
+/// 清空后台事件日志
+#[tauri::command]
+pub async fn clear_notifications() -> Result<bool, String> {
+    crate::notifications::clear()?;
+    Ok(true)
+}
+
+/// 读取审计日志（cc-switch 对文件的每次写入、复制与删除，最新在前）
+#[tauri::command]
+pub async fn get_audit_log(
+    filter: Option<crate::audit::AuditFilter>,
+) -> Result<Vec<crate::audit::AuditEntry>, String> {
+    Ok(crate::audit::get_log(&filter.unwrap_or_default()))
+}
+
+/// 读取最近的应用日志（最新在前）；minLevel 为 warn 时只返回 warn 与 error
+#[tauri::command]
+pub async fn get_recent_logs(
+    limit: Option<usize>,
+    minLevel: Option<crate::logging::LogLevel>,
+) -> Result<Vec<crate::logging::LogEntry>, String> {
+    tauri::async_runtime::spawn_blocking(move || crate::logging::recent(limit, minLevel))
+        .await
+        .map_err(|e| format!("读取日志失败: {}", e))
+}
+
+/// 打开日志目录 ~/.cc-switch/logs
+#[tauri::command]
+pub async fn open_log_folder(handle: tauri::AppHandle) -> Result<bool, String> {
+    let log_dir = crate::logging::log_dir();
+    if !log_dir.exists() {
+        std::fs::create_dir_all(&log_dir).map_err(|e| format!("创建目录失败: {}", e))?;
+    }
+    handle
+        .opener()
+        .open_path(log_dir.to_string_lossy().to_string(), None::<String>)
+        .map_err(|e| format!("打开文件夹失败: {}", e))?;
+    Ok(true)
+}
+
+/// 切回上一个供应商，返回切换到的供应商 id
+#[tauri::command]
+pub async fn switch_to_previous_provider(
+    handle: tauri::AppHandle,
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<String, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+
+    let previous = crate::switch_history::previous_provider(app_type.as_str())
+        .ok_or_else(|| "没有可切回的供应商".to_string())?;
+    crate::switch_provider_internal(
+        &handle,
+        app_type,
+        previous.clone(),
+        crate::switch_history::SwitchInitiator::SwitchBack,
+    )
+    .await?;
+    Ok(previous)
+}
+
+/// 设置在当前与上一个供应商之间切换的全局快捷键；为空时取消快捷键
+#[tauri::command]
+pub async fn set_toggle_shortcut(
+    handle: tauri::AppHandle,
+    shortcut: Option<String>,
+) -> Result<bool, String> {
+    let shortcut = shortcut
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty());
+    if let Some(shortcut) = shortcut.as_deref() {
+        crate::hotkey::parse_shortcut(shortcut)?;
+    }
+    crate::hotkey::register(&handle, shortcut.as_deref())?;
+    let mut settings = crate::settings::get_settings();
+    settings.toggle_shortcut = shortcut;
+    crate::settings::update_settings(settings)?;
+    Ok(true)
+}
+
+/// 记录主界面当前选中的应用，全局快捷键作用于该应用
+#[tauri::command]
+pub async fn set_focused_app(
+    app_type: Option<AppType>,
+    app: Option<String>,
+    appType: Option<String>,
+) -> Result<bool, String> {
+    let app_type = app_type
+        .or_else(|| app.as_deref().map(|s| s.into()))
+        .or_else(|| appType.as_deref().map(|s| s.into()))
+        .unwrap_or(AppType::Claude);
+    crate::hotkey::set_focused_app(app_type);
+    Ok(true)
+}
+
+/// 取出尚未处理的 cc-switch:// 添加供应商链接（冷启动时由前端调用）
+#[tauri::command]
+pub async fn take_pending_deep_link() -> Result<Option<crate::deep_link::DeepLinkProvider>, String>
+{
+    Ok(crate::deep_link::take_pending())
+}
+
+/// 解析用户粘贴的 cc-switch:// 链接为供应商草稿（不写入配置）
+#[tauri::command]
+pub async fn parse_provider_link(
+    state: State<'_, AppState>,
+    link: String,
+) -> Result<crate::deep_link::DeepLinkProvider, String> {
+    let (app_type, provider) = crate::deep_link::parse_provider_link(&link)?;
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    Ok(crate::deep_link::draft_for(&config, app_type, provider))
+}
+
+/// 获取本地自动化接口状态（含访问令牌）
+#[tauri::command]
+pub async fn get_local_api_status() -> Result<crate::local_api::LocalApiStatus, String> {
+    Ok(crate::local_api::status())
+}
+
+/// 启用或关闭本地自动化接口；首次启用时生成访问令牌
+#[tauri::command]
+pub async fn set_local_api(
+    handle: tauri::AppHandle,
+    enabled: bool,
+    port: Option<u16>,
+) -> Result<crate::local_api::LocalApiStatus, String> {
+    let mut settings = crate::settings::get_settings();
+    let mut local_api = settings.local_api.take().unwrap_or_default();
+    local_api.enabled = enabled;
+    if port.is_some() {
+        local_api.port = port;
+    }
+    if enabled && local_api.token().is_none() {
+        local_api.token = Some(crate::local_api::generate_token());
+    }
+    settings.local_api = Some(local_api);
+    crate::settings::update_settings(settings)?;
+    crate::local_api::reload(&handle)?;
+    Ok(crate::local_api::status())
+}
+
+/// 重新生成本地自动化接口的访问令牌（旧令牌立即失效）
+#[tauri::command]
+pub async fn regenerate_local_api_token(
+    handle: tauri::AppHandle,
+) -> Result<crate::local_api::LocalApiStatus, String> {
+    let mut settings = crate::settings::get_settings();
+    let mut local_api = settings.local_api.take().unwrap_or_default();
+    local_api.token = Some(crate::local_api::generate_token());
+    settings.local_api = Some(local_api);
+    crate::settings::update_settings(settings)?;
+    crate::local_api::reload(&handle)?;
+    Ok(crate::local_api::status())
+}
+
+/// 获取云同步状态（不含后端凭据）
+#[tauri::command]
+pub async fn get_sync_status() -> Result<crate::sync::SyncStatus, String> {
+    Ok(crate::sync::status())
+}
+
+/// 设置云同步后端；传 None 关闭同步
+#[tauri::command]
+pub async fn set_sync_backend(
+    backend: Option<crate::sync::SyncBackend>,
+) -> Result<crate::sync::SyncStatus, String> {
+    crate::sync::set_backend(backend)
+}
+
+/// 执行云同步；两端都有修改时返回 conflict，由用户选择方向后以 force 重试
+#[tauri::command]
+pub async fn run_sync(
+    state: State<'_, AppState>,
+    passphrase: String,
+    direction: Option<crate::sync::SyncDirection>,
+    force: Option<bool>,
+) -> Result<crate::sync::SyncOutcome, String> {
+    crate::sync::run(
+        &state,
+        &passphrase,
+        direction.unwrap_or_default(),
+        force.unwrap_or(false),
+    )
+    .await
+}
+
+/// 列出可撤销的配置修改（最新在前）
+#[tauri::command]
+pub async fn list_undo_changes() -> Result<Vec<crate::undo::UndoEntry>, String> {
+    Ok(crate::undo::list_changes())
+}
+
+/// 撤销最近一次配置修改；指定 file 时只恢复该文件，返回已恢复的记录
+#[tauri::command]
+pub async fn rollback_last_change(
+    state: State<'_, AppState>,
+    file: Option<String>,
+) -> Result<Vec<crate::undo::UndoEntry>, String> {
+    let restored = crate::undo::rollback_last(file.as_deref())?;
+
+    // 撤销切换供应商时同时恢复当前供应商；原供应商已删除时清空，避免下次切换回填到错误的供应商
+    if let Some(previous) = restored.iter().find_map(|e| e.previous_current.as_ref()) {
+        let app_type: AppType = previous.app_type.as_str().into();
+        {
+            let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+            let manager = config
+                .get_manager_mut(&app_type)
+                .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+            manager.current = if manager.providers.contains_key(&previous.provider_id) {
+                previous.provider_id.clone()
+            } else {
+                String::new()
+            };
+        }
+        state.save()?;
+    }
+    Ok(restored)
+}
@@ -4,6 +4,8 @@ use std::fs;
 use std::io::Write;
 use std::path::{Path, PathBuf};
 
+use crate::error::AppError;
+
 /// 获取 Claude Code 配置目录路径
 pub fn get_claude_config_dir() -> PathBuf {
     if let Some(custom) = crate::settings::get_claude_override_dir() {
@@ -33,6 +35,10 @@ pub fn get_claude_settings_path() -> PathBuf {
 
 /// 获取应用配置目录路径 (~/.cc-switch)
 pub fn get_app_config_dir() -> PathBuf {
+    // 便携模式优先：数据目录位于可执行文件旁
+    if let Some(dir) = crate::portable::data_dir() {
+        return dir;
+    }
     if let Some(custom) = crate::app_store::get_app_config_dir_override() {
         return custom;
     }
@@ -117,52 +123,71 @@ pub fn get_provider_config_path(provider_id: &str, provider_name: Option<&str>)
 }
 
 /// 读取 JSON 配置文件
-pub fn read_json_file<T: for<'a> Deserialize<'a>>(path: &Path) -> Result<T, String> {
+pub fn read_json_file<T: for<'a> Deserialize<'a>>(path: &Path) -> Result<T, AppError> {
     if !path.exists() {
-        return Err(format!("文件不存在: {}", path.display()));
+        return Err(AppError::FileNotFound {
+            path: path.display().to_string(),
+        });
     }
 
-    let content =
-        fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}: {}", path.display(), e))?;
+    let content = fs::read_to_string(path).map_err(|e| AppError::io(path, e))?;
 
-    serde_json::from_str(&content).map_err(|e| format!("解析 JSON 失败: {}: {}", path.display(), e))
+    serde_json::from_str(&content).map_err(|e| AppError::json(path, e))
 }
 
 /// 写入 JSON 配置文件
-pub fn write_json_file<T: Serialize>(path: &Path, data: &T) -> Result<(), String> {
+pub fn write_json_file<T: Serialize>(path: &Path, data: &T) -> Result<(), AppError> {
     // 确保目录存在
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
     }
 
-    let json =
-        serde_json::to_string_pretty(data).map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+    let json = serde_json::to_string_pretty(data).map_err(|e| AppError::Serialize {
+        format: "JSON",
+        message: e.to_string(),
+    })?;
 
     atomic_write(path, json.as_bytes())
 }
 
 /// 原子写入文本文件（用于 TOML/纯文本）
-pub fn write_text_file(path: &Path, data: &str) -> Result<(), String> {
+pub fn write_text_file(path: &Path, data: &str) -> Result<(), AppError> {
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
     }
     atomic_write(path, data.as_bytes())
 }
 
-/// 原子写入：写入临时文件后 rename 替换，避免半写状态
-pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
+/// 原子写入：写入临时文件后 rename 替换，避免半写状态。
+/// 受保护的配置文件在上次写入后被外部修改时拒绝写入（见 write_guard），结果记入审计日志
+pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), AppError> {
+    let result = write_replacing(path, data);
+    crate::audit::record(
+        crate::audit::AuditAction::Write,
+        path,
+        None,
+        Some(data.len() as u64),
+        &result,
+    );
+    result
+}
+
+fn write_replacing(path: &Path, data: &[u8]) -> Result<(), AppError> {
+    let data = crate::write_guard::before_write(path, data)?;
+    let data = data.as_ref();
+    crate::staged_apply::verify(path, data)?;
+
     if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+        fs::create_dir_all(parent).map_err(|e| AppError::io(parent, e))?;
     }
 
-    let parent = path.parent().ok_or_else(|| "无效的路径".to_string())?;
+    let parent = path
+        .parent()
+        .ok_or_else(|| AppError::InvalidInput(format!("无效的路径: {}", path.display())))?;
     let mut tmp = parent.to_path_buf();
     let file_name = path
         .file_name()
-        .ok_or_else(|| "无效的文件名".to_string())?
+        .ok_or_else(|| AppError::InvalidInput(format!("无效的文件名: {}", path.display())))?
         .to_string_lossy()
         .to_string();
     let ts = std::time::SystemTime::now()
@@ -172,12 +197,9 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
     tmp.push(format!("{}.tmp.{}", file_name, ts));
 
     {
-        let mut f = fs::File::create(&tmp)
-            .map_err(|e| format!("创建临时文件失败: {}: {}", tmp.display(), e))?;
-        f.write_all(data)
-            .map_err(|e| format!("写入临时文件失败: {}: {}", tmp.display(), e))?;
-        f.flush()
-            .map_err(|e| format!("刷新临时文件失败: {}: {}", tmp.display(), e))?;
+        let mut f = fs::File::create(&tmp).map_err(|e| AppError::io(&tmp, e))?;
+        f.write_all(data).map_err(|e| AppError::io(&tmp, e))?;
+        f.flush().map_err(|e| AppError::io(&tmp, e))?;
     }
 
     #[cfg(unix)]
@@ -195,40 +217,55 @@ pub fn atomic_write(path: &Path, data: &[u8]) -> Result<(), String> {
         if path.exists() {
             let _ = fs::remove_file(path);
         }
-        fs::rename(&tmp, path).map_err(|e| {
-            format!(
-                "原子替换失败: {} -> {}: {}",
-                tmp.display(),
-                path.display(),
-                e
-            )
-        })?;
+        fs::rename(&tmp, path).map_err(|e| AppError::io(path, e))?;
     }
 
     #[cfg(not(windows))]
     {
-        fs::rename(&tmp, path).map_err(|e| {
-            format!(
-                "原子替换失败: {} -> {}: {}",
-                tmp.display(),
-                path.display(),
-                e
-            )
-        })?;
+        fs::rename(&tmp, path).map_err(|e| AppError::io(path, e))?;
     }
+    crate::write_guard::after_write(path, data);
     Ok(())
 }
 
 /// 复制文件
-pub fn copy_file(from: &Path, to: &Path) -> Result<(), String> {
-    fs::copy(from, to).map_err(|e| format!("复制文件失败: {}", e))?;
-    Ok(())
+pub fn copy_file(from: &Path, to: &Path) -> Result<(), AppError> {
+    let result = fs::copy(from, to).map_err(|e| {
+        // 来源缺失时报告来源路径，其余情况报告目标路径
+        if from.exists() {
+            AppError::io(to, e)
+        } else {
+            AppError::io(from, e)
+        }
+    });
+    let bytes = result.as_ref().ok().copied();
+    let result = result.map(|_| ());
+    crate::audit::record(
+        crate::audit::AuditAction::Copy,
+        to,
+        Some(from),
+        bytes,
+        &result,
+    );
+    result
 }
 
 /// 删除文件
-pub fn delete_file(path: &Path) -> Result<(), String> {
+pub fn delete_file(path: &Path) -> Result<(), AppError> {
+    if path.exists() {
+        let result = fs::remove_file(path).map_err(|e| AppError::io(path, e));
+        crate::audit::record(crate::audit::AuditAction::Delete, path, None, None, &result);
+        result?;
+    }
+    Ok(())
+}
+
+/// 删除目录（含其中所有文件）并记录审计日志
+pub fn delete_dir(path: &Path) -> Result<(), AppError> {
     if path.exists() {
-        fs::remove_file(path).map_err(|e| format!("删除文件失败: {}", e))?;
+        let result = fs::remove_dir_all(path).map_err(|e| AppError::io(path, e));
+        crate::audit::record(crate::audit::AuditAction::Delete, path, None, None, &result);
+        result?;
     }
     Ok(())
 }
@@ -0,0 +1,742 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::Mutex;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{get_app_config_dir, get_app_config_path};
+
+/// 串行化 git 操作；持有期间（启用、恢复版本）触发的自动提交会并入随后的提交
+static GIT_LOCK: Mutex<()> = Mutex::new(());
+
+/// 规则文件在版本库中的镜像目录
+const RULES_DIR: &str = "rules";
+/// 提交说明最多列出的变更项
+const MAX_SUMMARY_ITEMS: usize = 3;
+/// 版本库中 config.json 的脱敏副本：明文 API Key 替换为占位符，密钥引用原样保留
+const CONFIG_ENTRY: &str = "config.redacted.json";
+/// 旧版本直接跟踪的 config.json（含明文 API Key），提交时从索引中移除
+const LEGACY_CONFIG_ENTRY: &str = "config.json";
+/// 脱敏后 API Key 的占位符
+const REDACTED: &str = "<redacted>";
+/// Codex 官方登录的令牌
+const CODEX_TOKENS_POINTER: &str = "/auth/tokens";
+
+/// 只跟踪配置文件，快照、撤销记录、日志等均忽略
+const GITIGNORE: &str = "# 由 CC Switch 生成：仅跟踪配置文件
+/*
+!/.gitignore
+!/config.redacted.json
+!/settings.json
+!/conversation_tags.json
+!/rules/
+";
+
+/// 配置版本库设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigHistorySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 同时跟踪 CLAUDE.md、AGENTS.md、GEMINI.md 与 Codex 规则文件（镜像到 rules/ 目录）
+    #[serde(default)]
+    pub include_rules: bool,
+}
+
+/// 配置版本库状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigHistoryStatus {
+    pub enabled: bool,
+    pub include_rules: bool,
+    /// 系统中是否可以找到 git
+    pub git_available: bool,
+    pub initialized: bool,
+    pub repo_path: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub head: Option<String>,
+}
+
+/// 一次提交
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCommit {
+    pub id: String,
+    pub short_id: String,
+    pub message: String,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    /// 本次提交修改的文件（相对版本库根目录）
+    pub files: Vec<String>,
+}
+
+/// 恢复历史版本的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigCheckoutResult {
+    pub restored: Vec<String>,
+    pub app_config_restored: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub commit: Option<String>,
+}
+
+fn settings() -> ConfigHistorySettings {
+    crate::settings::get_settings()
+        .config_history
+        .unwrap_or_default()
+}
+
+/// 版本库目录，即 ~/.cc-switch（或自定义的配置目录）
+pub fn repo_dir() -> PathBuf {
+    get_app_config_dir()
+}
+
+fn git(repo: &Path, args: &[&str]) -> Result<String, String> {
+    let mut cmd = Command::new("git");
+    cmd.arg("-C")
+        .arg(repo)
+        .args([
+            "-c",
+            "user.name=CC Switch",
+            "-c",
+            "user.email=cc-switch@localhost",
+            "-c",
+            "commit.gpgsign=false",
+            "-c",
+            "core.quotepath=false",
+        ])
+        .args(args);
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+        // CREATE_NO_WINDOW：后台提交时不弹出控制台窗口
+        cmd.creation_flags(0x0800_0000);
+    }
+    let output = cmd
+        .output()
+        .map_err(|e| format!("执行 git 失败（请确认已安装 git）: {}", e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "git {} 失败: {}",
+            args.first().copied().unwrap_or_default(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        ));
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).to_string())
+}
+
+fn git_available() -> bool {
+    Command::new("git")
+        .arg("--version")
+        .output()
+        .map(|o| o.status.success())
+        .unwrap_or(false)
+}
+
+fn initialized() -> bool {
+    repo_dir().join(".git").exists()
+}
+
+fn head() -> Option<String> {
+    git(&repo_dir(), &["rev-parse", "--short", "HEAD"])
+        .ok()
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+}
+
+/// 查询版本库状态
+pub fn status() -> ConfigHistoryStatus {
+    let settings = settings();
+    let initialized = initialized();
+    ConfigHistoryStatus {
+        enabled: settings.enabled,
+        include_rules: settings.include_rules,
+        git_available: git_available(),
+        initialized,
+        repo_path: repo_dir().to_string_lossy().to_string(),
+        head: if initialized { head() } else { None },
+    }
+}
+
+/// 被跟踪的文件：(版本库内路径, 实际路径)
+fn tracked_files(include_rules: bool) -> Vec<(String, PathBuf)> {
+    let dir = repo_dir();
+    let mut files = vec![
+        (CONFIG_ENTRY.to_string(), get_app_config_path()),
+        (
+            "settings.json".to_string(),
+            crate::settings::AppSettings::settings_path(),
+        ),
+        (
+            "conversation_tags.json".to_string(),
+            dir.join("conversation_tags.json"),
+        ),
+    ];
+    if !include_rules {
+        return files;
+    }
+    for app_type in AppType::all() {
+        let Some(path) = crate::app_adapter::adapter_for(&app_type).rules_path() else {
+            continue;
+        };
+        let Some(name) = path.file_name() else {
+            continue;
+        };
+        files.push((
+            format!(
+                "{}/{}/{}",
+                RULES_DIR,
+                app_type.as_str(),
+                name.to_string_lossy()
+            ),
+            path,
+        ));
+    }
+    match crate::global_rules::list_codex_rules() {
+        Ok(rules) => files.extend(rules.into_iter().map(|rule| {
+            (
+                format!("{}/codex-rules/{}", RULES_DIR, rule.name),
+                PathBuf::from(rule.path),
+            )
+        })),
+        Err(e) => log::warn!("读取 Codex 规则失败: {}", e),
+    }
+    files
+}
+
+/// 将不在版本库目录中的文件（规则、被覆盖目录时的 settings.json）复制进版本库
+fn mirror_files(include_rules: bool) -> Result<(), String> {
+    let dir = repo_dir();
+    let rules_dir = dir.join(RULES_DIR);
+    if rules_dir.exists() {
+        fs::remove_dir_all(&rules_dir)
+            .map_err(|e| format!("清理规则镜像失败: {}: {}", rules_dir.display(), e))?;
+    }
+    for (relative, live) in tracked_files(include_rules) {
+        let target = dir.join(&relative);
+        if target == live {
+            continue;
+        }
+        if relative == CONFIG_ENTRY && live.exists() {
+            mirror_config(&live, &target)?;
+        } else if live.exists() {
+            let content =
+                fs::read(&live).map_err(|e| format!("读取文件失败: {}: {}", live.display(), e))?;
+            crate::config::atomic_write(&target, &content)?;
+        } else {
+            crate::config::delete_file(&target)?;
+        }
+    }
+    Ok(())
+}
+
+/// 需要脱敏的字段：各应用的 API Key 字段，以及 Codex 官方登录令牌
+fn redacted_pointers(app_type: &AppType, settings: &Value) -> Vec<String> {
+    let mut pointers = crate::app_adapter::adapter_for(app_type).secret_pointers(settings);
+    if matches!(app_type, AppType::Codex) {
+        pointers.push(CODEX_TOKENS_POINTER.to_string());
+    }
+    pointers
+}
+
+/// 明文 Key（非空、非密钥引用、非占位符）
+fn is_plain_key(key: &str) -> bool {
+    !key.is_empty() && key != REDACTED && !key.starts_with(crate::secrets::SECRET_REF_PREFIX)
+}
+
+/// 需要脱敏的值：明文 Key，或 Codex 令牌等非字符串的凭据
+fn is_plain_secret(value: &Value) -> bool {
+    match value {
+        Value::Null => false,
+        Value::String(s) => is_plain_key(s),
+        _ => true,
+    }
+}
+
+/// 将配置中的明文 API Key 与轮换 Key 替换为占位符
+fn redact_config(config: &mut MultiAppConfig) {
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        for provider in manager.providers.values_mut() {
+            for pointer in redacted_pointers(&app_type, &provider.settings_config) {
+                if let Some(slot) = provider.settings_config.pointer_mut(&pointer) {
+                    if is_plain_secret(slot) {
+                        *slot = Value::String(REDACTED.to_string());
+                    }
+                }
+            }
+            if let Some(rotation) = provider.meta.as_mut().and_then(|m| m.key_rotation.as_mut()) {
+                for entry in rotation.keys.iter_mut() {
+                    if is_plain_key(&entry.key) {
+                        entry.key = REDACTED.to_string();
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// 恢复脱敏副本：占位符处沿用当前配置中同一供应商的值，找不到时清空
+fn unredact_config(restored: &mut MultiAppConfig, current: &MultiAppConfig) {
+    for app_type in AppType::all() {
+        let current_manager = current.get_manager(&app_type);
+        let Some(manager) = restored.get_manager_mut(&app_type) else {
+            continue;
+        };
+        for (id, provider) in manager.providers.iter_mut() {
+            let previous = current_manager.and_then(|m| m.providers.get(id));
+            for pointer in redacted_pointers(&app_type, &provider.settings_config) {
+                let Some(slot) = provider.settings_config.pointer_mut(&pointer) else {
+                    continue;
+                };
+                if slot.as_str() != Some(REDACTED) {
+                    continue;
+                }
+                *slot = previous
+                    .and_then(|p| p.settings_config.pointer(&pointer))
+                    .cloned()
+                    .unwrap_or_else(|| {
+                        if pointer == CODEX_TOKENS_POINTER {
+                            Value::Null
+                        } else {
+                            Value::String(String::new())
+                        }
+                    });
+            }
+
+            let previous_keys = previous
+                .and_then(|p| p.meta.as_ref())
+                .and_then(|m| m.key_rotation.as_ref())
+                .map(|r| r.keys.clone())
+                .unwrap_or_default();
+            if let Some(rotation) = provider.meta.as_mut().and_then(|m| m.key_rotation.as_mut()) {
+                for (index, entry) in rotation.keys.iter_mut().enumerate() {
+                    if entry.key == REDACTED {
+                        entry.key = previous_keys
+                            .get(index)
+                            .map(|k| k.key.clone())
+                            .unwrap_or_default();
+                    }
+                }
+                rotation.keys.retain(|k| !k.key.is_empty());
+            }
+        }
+    }
+}
+
+/// 将 config.json 的脱敏副本写入版本库；无法解析时跳过，不阻断提交
+fn mirror_config(live: &Path, target: &Path) -> Result<(), String> {
+    let content =
+        fs::read_to_string(live).map_err(|e| format!("读取文件失败: {}: {}", live.display(), e))?;
+    let mut config = match serde_json::from_str::<MultiAppConfig>(&content) {
+        Ok(config) => config,
+        Err(e) => {
+            log::warn!("解析 config.json 失败，跳过本次脱敏副本: {}", e);
+            return Ok(());
+        }
+    };
+    redact_config(&mut config);
+    crate::config::write_json_file(target, &config).map_err(Into::into)
+}
+
+fn ensure_repo() -> Result<(), String> {
+    let dir = repo_dir();
+    if !dir.join(".git").exists() {
+        fs::create_dir_all(&dir).map_err(|e| format!("创建目录失败: {}: {}", dir.display(), e))?;
+        git(&dir, &["init", "--quiet"])?;
+    }
+    crate::config::write_text_file(&dir.join(".gitignore"), GITIGNORE).map_err(Into::into)
+}
+
+/// 读取某个版本中的文件；不存在时返回 None
+fn show_file(revision: &str, relative: &str) -> Option<String> {
+    git(
+        &repo_dir(),
+        &["show", &format!("{}:{}", revision, relative)],
+    )
+    .ok()
+}
+
+fn provider_name(manager: &crate::provider::ProviderManager, id: &str) -> String {
+    manager
+        .providers
+        .get(id)
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| id.to_string())
+}
+
+/// 比较两个版本的 config.json，生成供应商与 MCP 的变更描述
+fn describe_config_change(old: &MultiAppConfig, new: &MultiAppConfig) -> Vec<String> {
+    let mut items = Vec::new();
+    for app_type in AppType::all() {
+        let label = crate::app_adapter::adapter_for(&app_type).display_name();
+        let empty = Default::default();
+        let before = old.get_manager(&app_type).unwrap_or(&empty);
+        let after = new.get_manager(&app_type).unwrap_or(&empty);
+
+        if before.current != after.current && !after.current.is_empty() {
+            items.push(format!(
+                "{}: 切换到 {}",
+                label,
+                provider_name(after, &after.current)
+            ));
+        }
+        let mut ids: Vec<&String> = after.providers.keys().collect();
+        ids.sort();
+        for id in ids {
+            match before.providers.get(id) {
+                None => items.push(format!(
+                    "{}: 新增供应商 {}",
+                    label,
+                    provider_name(after, id)
+                )),
+                Some(previous) => {
+                    let changed = serde_json::to_value(previous).ok()
+                        != serde_json::to_value(&after.providers[id]).ok();
+                    if changed {
+                        items.push(format!(
+                            "{}: 修改供应商 {}",
+                            label,
+                            provider_name(after, id)
+                        ));
+                    }
+                }
+            }
+        }
+        let mut removed: Vec<&String> = before
+            .providers
+            .keys()
+            .filter(|id| !after.providers.contains_key(*id))
+            .collect();
+        removed.sort();
+        for id in removed {
+            items.push(format!(
+                "{}: 删除供应商 {}",
+                label,
+                provider_name(before, id)
+            ));
+        }
+        if serde_json::to_value(old.mcp_for(&app_type)).ok()
+            != serde_json::to_value(new.mcp_for(&app_type)).ok()
+        {
+            items.push(format!("{}: 更新 MCP 配置", label));
+        }
+    }
+    items
+}
+
+/// 根据暂存区的变更生成提交说明
+fn describe_changes(changed: &[String]) -> String {
+    let mut items = Vec::new();
+    if changed.iter().any(|f| f == CONFIG_ENTRY) {
+        let old = show_file("HEAD", CONFIG_ENTRY)
+            .and_then(|s| serde_json::from_str::<MultiAppConfig>(&s).ok());
+        let new = fs::read_to_string(repo_dir().join(CONFIG_ENTRY))
+            .ok()
+            .and_then(|s| serde_json::from_str::<MultiAppConfig>(&s).ok());
+        match (old, new) {
+            (Some(old), Some(new)) => {
+                let described = describe_config_change(&old, &new);
+                if described.is_empty() {
+                    items.push("更新供应商配置".to_string());
+                }
+                items.extend(described);
+            }
+            _ => items.push("更新供应商配置".to_string()),
+        }
+    }
+    if changed.iter().any(|f| f == "settings.json") {
+        items.push("更新应用设置".to_string());
+    }
+    if changed.iter().any(|f| f == "conversation_tags.json") {
+        items.push("更新会话标签".to_string());
+    }
+    let rules: Vec<&str> = changed
+        .iter()
+        .filter_map(|f| f.strip_prefix(&format!("{}/", RULES_DIR)))
+        .collect();
+    if !rules.is_empty() {
+        items.push(format!("更新规则 {}", rules.join("、")));
+    }
+    if items.is_empty() {
+        return "更新配置".to_string();
+    }
+
+    let subject = if items.len() > MAX_SUMMARY_ITEMS {
+        format!(
+            "{} 等 {} 项修改",
+            items[..MAX_SUMMARY_ITEMS].join("；"),
+            items.len()
+        )
+    } else {
+        items.join("；")
+    };
+    if items.len() > 1 {
+        format!("{}\n\n- {}", subject, items.join("\n- "))
+    } else {
+        subject
+    }
+}
+
+/// 暂存并提交全部变更；message 为空时按变更内容生成。无变更时返回 None
+fn commit_all(include_rules: bool, message: Option<&str>) -> Result<Option<String>, String> {
+    let dir = repo_dir();
+    mirror_files(include_rules)?;
+    git(
+        &dir,
+        &[
+            "rm",
+            "--cached",
+            "--quiet",
+            "--ignore-unmatch",
+            LEGACY_CONFIG_ENTRY,
+        ],
+    )?;
+    git(&dir, &["add", "--all"])?;
+    let changed: Vec<String> = git(&dir, &["diff", "--cached", "--name-only"])?
+        .lines()
+        .map(str::to_string)
+        .filter(|l| !l.is_empty())
+        .collect();
+    if changed.is_empty() {
+        return Ok(None);
+    }
+    let message = match message {
+        Some(message) => message.to_string(),
+        None => describe_changes(&changed),
+    };
+    git(&dir, &["commit", "--quiet", "--no-verify", "-m", &message])?;
+    Ok(head())
+}
+
+/// 配置写入后自动提交（未启用时直接返回；失败仅记录日志，不阻断写入）
+pub fn auto_commit() {
+    let settings = settings();
+    if !settings.enabled || !initialized() {
+        return;
+    }
+    let Ok(_guard) = GIT_LOCK.try_lock() else {
+        return;
+    };
+    if let Err(e) = commit_all(settings.include_rules, None) {
+        log::warn!("自动提交配置版本失败: {}", e);
+    }
+}
+
+/// 启用版本库：初始化仓库并提交当前配置
+pub fn enable(include_rules: bool) -> Result<ConfigHistoryStatus, String> {
+    if !git_available() {
+        return Err("未找到 git，请先安装 git 并确保其位于 PATH 中".to_string());
+    }
+    {
+        let _guard = GIT_LOCK.lock().map_err(crate::error::lock_failed)?;
+        let first = !initialized();
+        ensure_repo()?;
+        let mut app_settings = crate::settings::get_settings();
+        app_settings.config_history = Some(ConfigHistorySettings {
+            enabled: true,
+            include_rules,
+        });
+        crate::settings::update_settings(app_settings)?;
+        let message = if first {
+            "初始化配置版本库"
+        } else {
+            "重新启用配置版本库"
+        };
+        commit_all(include_rules, Some(message))?;
+    }
+    Ok(status())
+}
+
+/// 停用自动提交（保留已有的版本库与历史）
+pub fn disable() -> Result<ConfigHistoryStatus, String> {
+    let mut app_settings = crate::settings::get_settings();
+    if let Some(history) = app_settings.config_history.as_mut() {
+        history.enabled = false;
+    }
+    crate::settings::update_settings(app_settings)?;
+    Ok(status())
+}
+
+/// 清除全部历史（旧版本库跟踪的 config.json 含明文 API Key）：删除 .git 后以当前配置重新初始化
+pub fn purge() -> Result<ConfigHistoryStatus, String> {
+    ensure_initialized()?;
+    {
+        let _guard = GIT_LOCK.lock().map_err(crate::error::lock_failed)?;
+        crate::config::delete_dir(&repo_dir().join(".git"))?;
+        ensure_repo()?;
+        commit_all(
+            settings().include_rules,
+            Some("清除历史并重新初始化配置版本库"),
+        )?;
+    }
+    Ok(status())
+}
+
+fn ensure_initialized() -> Result<(), String> {
+    if initialized() {
+        Ok(())
+    } else {
+        Err("配置版本库尚未启用".to_string())
+    }
+}
+
+fn validate_revision(revision: &str) -> Result<(), String> {
+    let valid = !revision.is_empty()
+        && !revision.starts_with('-')
+        && revision
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '~' | '^'));
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的版本号: {}", revision))
+    }
+}
+
+/// 提交历史（最新在前），可按文件过滤
+pub fn log(limit: Option<usize>, file: Option<&str>) -> Result<Vec<ConfigCommit>, String> {
+    ensure_initialized()?;
+    if head().is_none() {
+        return Ok(Vec::new());
+    }
+    let limit = format!("--max-count={}", limit.unwrap_or(100));
+    let mut args = vec![
+        "log",
+        limit.as_str(),
+        "--name-only",
+        "--format=%x1e%H%x1f%h%x1f%ct%x1f%B%x1f",
+    ];
+    if let Some(file) = file {
+        args.push("--");
+        args.push(file);
+    }
+    let output = git(&repo_dir(), &args)?;
+    let commits = output
+        .split('\x1e')
+        .filter_map(|record| {
+            let mut fields = record.split('\x1f');
+            let id = fields.next()?.trim().to_string();
+            let short_id = fields.next()?.to_string();
+            let timestamp = fields.next()?.parse::<i64>().ok()? * 1000;
+            let message = fields.next()?.trim().to_string();
+            let files = fields
+                .next()
+                .unwrap_or_default()
+                .lines()
+                .map(str::trim)
+                .filter(|l| !l.is_empty())
+                .map(str::to_string)
+                .collect();
+            Some(ConfigCommit {
+                id,
+                short_id,
+                message,
+                timestamp,
+                files,
+            })
+        })
+        .collect();
+    Ok(commits)
+}
+
+/// 某次提交的改动（统一 diff 格式），可按文件过滤
+pub fn diff(revision: &str, file: Option<&str>) -> Result<String, String> {
+    ensure_initialized()?;
+    validate_revision(revision)?;
+    let mut args = vec!["show", "--format=", "--no-color", revision];
+    if let Some(file) = file {
+        args.push("--");
+        args.push(file);
+    }
+    git(&repo_dir(), &args)
+}
+
+/// 恢复到某个历史版本：把该版本中的文件写回原位置（files 为空时恢复全部），
+/// 恢复前创建快照，恢复后自动提交一次以保留可追溯的历史
+pub fn checkout(revision: &str, files: Option<&[String]>) -> Result<ConfigCheckoutResult, String> {
+    ensure_initialized()?;
+    validate_revision(revision)?;
+    let listed = git(
+        &repo_dir(),
+        &["ls-tree", "-r", "--name-only", revision, "--"],
+    )?;
+    let available: Vec<&str> = listed
+        .lines()
+        .filter(|f| !f.is_empty() && *f != ".gitignore")
+        .collect();
+    let selected: Vec<&str> = match files {
+        Some(files) if !files.is_empty() => {
+            for file in files {
+                if !available.contains(&file.as_str()) {
+                    return Err(format!("该版本中不存在文件: {}", file));
+                }
+            }
+            files.iter().map(String::as_str).collect()
+        }
+        _ => available,
+    };
+
+    let _guard = GIT_LOCK.lock().map_err(crate::error::lock_failed)?;
+    crate::backups::snapshot_before_write("pre-git-checkout");
+    let include_rules = settings().include_rules;
+    let live_paths = tracked_files(true);
+    let codex_rules_dir = crate::global_rules::get_codex_rules_dir()?;
+    let mut result = ConfigCheckoutResult {
+        restored: Vec::new(),
+        app_config_restored: false,
+        commit: None,
+    };
+    for relative in selected {
+        let content = show_file(revision, relative)
+            .ok_or_else(|| format!("读取历史文件失败: {}", relative))?;
+        if relative == CONFIG_ENTRY {
+            let mut restored: MultiAppConfig =
+                serde_json::from_str(&content).map_err(|e| format!("解析历史配置失败: {}", e))?;
+            let current = MultiAppConfig::load()?;
+            unredact_config(&mut restored, &current);
+            crate::config::write_json_file(&get_app_config_path(), &restored)?;
+            result.app_config_restored = true;
+        } else if relative == "settings.json" {
+            let restored: crate::settings::AppSettings =
+                serde_json::from_str(&content).map_err(|e| format!("解析历史设置失败: {}", e))?;
+            crate::settings::update_settings(restored)?;
+        } else {
+            let live = live_paths
+                .iter()
+                .find(|(r, _)| r == relative)
+                .map(|(_, p)| p.clone())
+                .or_else(|| {
+                    // 旧版本库中未脱敏的 config.json
+                    (relative == LEGACY_CONFIG_ENTRY).then(get_app_config_path)
+                })
+                .or_else(|| {
+                    // 当前已删除的 Codex 规则文件
+                    relative
+                        .strip_prefix(&format!("{}/codex-rules/", RULES_DIR))
+                        .filter(|name| crate::global_rules::validate_rule_filename(name).is_ok())
+                        .map(|name| codex_rules_dir.join(name))
+                })
+                .ok_or_else(|| format!("无法确定文件的恢复位置: {}", relative))?;
+            crate::config::write_text_file(&live, &content)?;
+            if relative == LEGACY_CONFIG_ENTRY {
+                result.app_config_restored = true;
+            }
+        }
+        result.restored.push(relative.to_string());
+    }
+
+    let short = head_of(revision);
+    result.commit = commit_all(include_rules, Some(&format!("恢复到版本 {}", short)))?;
+    Ok(result)
+}
+
+fn head_of(revision: &str) -> String {
+    git(&repo_dir(), &["rev-parse", "--short", revision])
+        .map(|s| s.trim().to_string())
+        .unwrap_or_else(|_| revision.to_string())
+}
@@ -0,0 +1,412 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::app_config::AppType;
+use crate::provider_validation::ValidationSeverity;
+
+/// Claude settings.json 已知的顶层字段
+const CLAUDE_KNOWN_KEYS: &[&str] = &[
+    "$schema",
+    "alwaysThinkingEnabled",
+    "apiKeyHelper",
+    "awsAuthRefresh",
+    "awsCredentialExport",
+    "cleanupPeriodDays",
+    "disableAllHooks",
+    "disabledMcpjsonServers",
+    "enableAllProjectMcpServers",
+    "enabledMcpjsonServers",
+    "env",
+    "forceLoginMethod",
+    "hooks",
+    "includeCoAuthoredBy",
+    "model",
+    "outputStyle",
+    "permissions",
+    "spinnerTipsEnabled",
+    "statusLine",
+];
+
+/// Codex config.toml 已知的顶层字段
+const CODEX_KNOWN_KEYS: &[&str] = &[
+    "approval_policy",
+    "chatgpt_base_url",
+    "disable_response_storage",
+    "experimental_instructions_file",
+    "file_opener",
+    "hide_agent_reasoning",
+    "history",
+    "instructions",
+    "mcp",
+    "mcp_servers",
+    "model",
+    "model_context_window",
+    "model_max_output_tokens",
+    "model_provider",
+    "model_providers",
+    "model_reasoning_effort",
+    "model_reasoning_summary",
+    "model_supports_reasoning_summaries",
+    "model_verbosity",
+    "notify",
+    "preferred_auth_method",
+    "profile",
+    "profiles",
+    "project_doc_max_bytes",
+    "projects",
+    "rules",
+    "sandbox_mode",
+    "sandbox_workspace_write",
+    "shell_environment_policy",
+    "show_raw_agent_reasoning",
+    "tools",
+    "tui",
+];
+
+/// Codex config.toml 中值必须为字符串的字段
+const CODEX_STRING_KEYS: &[&str] = &["model", "model_provider", "profile", "instructions"];
+
+/// Codex config.toml 中值必须为表的字段
+const CODEX_TABLE_KEYS: &[&str] = &["model_providers", "profiles", "mcp_servers", "projects"];
+
+/// 配置文本的格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+}
+
+/// 单条诊断，行列号均从 1 开始；无法定位时为 None
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigDiagnostic {
+    pub severity: ValidationSeverity,
+    /// 机器可读的问题代码
+    pub code: String,
+    pub message: String,
+    /// 问题所在字段，如 permissions.defaultMode、model_providers.foo
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub column: Option<usize>,
+}
+
+/// 配置文本校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigTextValidation {
+    pub format: ConfigFormat,
+    /// 不存在 error 级别诊断
+    pub valid: bool,
+    pub diagnostics: Vec<ConfigDiagnostic>,
+}
+
+struct Diagnostics<'a> {
+    text: &'a str,
+    format: ConfigFormat,
+    items: Vec<ConfigDiagnostic>,
+}
+
+impl<'a> Diagnostics<'a> {
+    fn new(text: &'a str, format: ConfigFormat) -> Self {
+        Self {
+            text,
+            format,
+            items: Vec::new(),
+        }
+    }
+
+    fn push_at(
+        &mut self,
+        severity: ValidationSeverity,
+        code: &str,
+        message: String,
+        path: Option<&[&str]>,
+        position: Option<(usize, usize)>,
+    ) {
+        self.items.push(ConfigDiagnostic {
+            severity,
+            code: code.to_string(),
+            message,
+            path: path.map(|p| p.join(".")),
+            line: position.map(|(l, _)| l),
+            column: position.map(|(_, c)| c),
+        });
+    }
+
+    /// 添加诊断，并按字段路径在原文中定位行列
+    fn push(&mut self, severity: ValidationSeverity, code: &str, message: String, path: &[&str]) {
+        let position = locate_path(self.text, self.format, path);
+        self.push_at(severity, code, message, Some(path), position);
+    }
+
+    fn finish(self) -> ConfigTextValidation {
+        ConfigTextValidation {
+            format: self.format,
+            valid: !self
+                .items
+                .iter()
+                .any(|d| d.severity == ValidationSeverity::Error),
+            diagnostics: self.items,
+        }
+    }
+}
+
+/// 字节偏移转换为 (行, 列)，列按字符计数
+fn offset_to_position(text: &str, offset: usize) -> (usize, usize) {
+    let mut offset = offset.min(text.len());
+    while !text.is_char_boundary(offset) {
+        offset -= 1;
+    }
+    let before = &text[..offset];
+    let line = before.matches('\n').count() + 1;
+    let line_start = before.rfind('\n').map(|i| i + 1).unwrap_or(0);
+    (line, before[line_start..].chars().count() + 1)
+}
+
+/// 在原文中粗略定位字段：依次查找路径中每一段键名，后一段从前一段的位置之后开始查找
+fn locate_path(text: &str, format: ConfigFormat, path: &[&str]) -> Option<(usize, usize)> {
+    let mut from = 0;
+    let mut found = None;
+    for segment in path.iter().filter(|s| s.parse::<usize>().is_err()) {
+        let offset = match format {
+            ConfigFormat::Json => text[from..].find(&format!("\"{}\"", segment)),
+            ConfigFormat::Toml => find_toml_key(&text[from..], segment),
+        }?;
+        from += offset;
+        found = Some(from);
+        from += segment.len();
+    }
+    found.map(|offset| offset_to_position(text, offset))
+}
+
+/// 查找 TOML 键所在行：`key = ...`、`key.sub = ...` 或表头 `[a.key]`
+fn find_toml_key(text: &str, key: &str) -> Option<usize> {
+    let quoted = format!("\"{}\"", key);
+    let is_key = |segment: &str| segment.trim() == key || segment.trim() == quoted;
+    let mut offset = 0;
+    for line in text.split_inclusive('\n') {
+        let trimmed = line.trim_start();
+        let matched = match trimmed.strip_prefix('[') {
+            Some(header) => header
+                .trim_start_matches('[')
+                .split(']')
+                .next()
+                .is_some_and(|inner| inner.split('.').any(is_key)),
+            None => trimmed
+                .split_once('=')
+                .and_then(|(name, _)| name.split('.').next())
+                .is_some_and(is_key),
+        };
+        if matched {
+            return Some(offset + line.len() - trimmed.len());
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// 校验候选配置文本：语法错误给出行列号，语义问题（未知字段、类型错误）给出警告或错误。
+/// Codex 为 config.toml，其余应用为 JSON（Claude 额外按 settings.json Schema 校验）
+pub fn validate_config_text(app_type: &AppType, text: &str) -> ConfigTextValidation {
+    match app_type {
+        AppType::Codex => validate_codex_toml(text),
+        AppType::Claude => validate_json(text, check_claude_settings),
+        _ => validate_json(text, |_, _| {}),
+    }
+}
+
+fn validate_json(
+    text: &str,
+    check: impl FnOnce(&mut Diagnostics, &serde_json::Map<String, Value>),
+) -> ConfigTextValidation {
+    let mut diagnostics = Diagnostics::new(text, ConfigFormat::Json);
+    if text.trim().is_empty() {
+        diagnostics.push_at(
+            ValidationSeverity::Error,
+            "empty",
+            "配置内容为空".to_string(),
+            None,
+            None,
+        );
+        return diagnostics.finish();
+    }
+    match serde_json::from_str::<Value>(text) {
+        Ok(Value::Object(obj)) => check(&mut diagnostics, &obj),
+        Ok(_) => diagnostics.push_at(
+            ValidationSeverity::Error,
+            "not-object",
+            "顶层必须是 JSON 对象".to_string(),
+            None,
+            Some((1, 1)),
+        ),
+        Err(e) => diagnostics.push_at(
+            ValidationSeverity::Error,
+            "invalid-json",
+            format!("JSON 语法错误: {}", e),
+            None,
+            Some((e.line().max(1), e.column().max(1))),
+        ),
+    }
+    diagnostics.finish()
+}
+
+fn check_claude_settings(diagnostics: &mut Diagnostics, obj: &serde_json::Map<String, Value>) {
+    for key in obj.keys() {
+        if !CLAUDE_KNOWN_KEYS.contains(&key.as_str()) {
+            diagnostics.push(
+                ValidationSeverity::Warning,
+                "unknown-key",
+                format!("未知字段 {}，Claude Code 可能会忽略", key),
+                &[key],
+            );
+        }
+    }
+    let value = Value::Object(obj.clone());
+    for error in crate::claude_settings::validate_settings_value(&value) {
+        let segments: Vec<&str> = error.path.split('/').filter(|s| !s.is_empty()).collect();
+        diagnostics.push(
+            ValidationSeverity::Error,
+            "schema",
+            error.message,
+            &segments,
+        );
+    }
+}
+
+fn validate_codex_toml(text: &str) -> ConfigTextValidation {
+    let mut diagnostics = Diagnostics::new(text, ConfigFormat::Toml);
+    // config.toml 允许为空
+    if text.trim().is_empty() {
+        return diagnostics.finish();
+    }
+    let table = match toml::from_str::<toml::Table>(text) {
+        Ok(table) => table,
+        Err(e) => {
+            let position = e.span().map(|span| offset_to_position(text, span.start));
+            diagnostics.push_at(
+                ValidationSeverity::Error,
+                "invalid-toml",
+                format!("TOML 语法错误: {}", e.message()),
+                None,
+                position,
+            );
+            return diagnostics.finish();
+        }
+    };
+
+    for key in table.keys() {
+        if !CODEX_KNOWN_KEYS.contains(&key.as_str()) {
+            diagnostics.push(
+                ValidationSeverity::Warning,
+                "unknown-key",
+                format!("未知字段 {}，Codex 可能会忽略", key),
+                &[key],
+            );
+        }
+    }
+    for key in CODEX_STRING_KEYS {
+        if table.get(*key).is_some_and(|v| !v.is_str()) {
+            diagnostics.push(
+                ValidationSeverity::Error,
+                "wrong-type",
+                format!("{} 必须是字符串", key),
+                &[key],
+            );
+        }
+    }
+    for key in CODEX_TABLE_KEYS {
+        if table.get(*key).is_some_and(|v| !v.is_table()) {
+            diagnostics.push(
+                ValidationSeverity::Error,
+                "wrong-type",
+                format!("{} 必须是表", key),
+                &[key],
+            );
+        }
+    }
+    check_codex_enums(&mut diagnostics, &table, &[]);
+
+    if let Some(providers) = table.get("model_providers").and_then(|v| v.as_table()) {
+        for (name, provider) in providers {
+            match provider.as_table() {
+                Some(provider) if !provider.get("base_url").is_some_and(|v| v.is_str()) => {
+                    diagnostics.push(
+                        ValidationSeverity::Warning,
+                        "missing-base-url",
+                        format!("model_providers.{} 未设置 base_url", name),
+                        &["model_providers", name],
+                    )
+                }
+                Some(_) => {}
+                None => diagnostics.push(
+                    ValidationSeverity::Error,
+                    "wrong-type",
+                    format!("model_providers.{} 必须是表", name),
+                    &["model_providers", name],
+                ),
+            }
+        }
+    }
+    if let Some(name) = table.get("model_provider").and_then(|v| v.as_str()) {
+        let known = ["openai", "oss"].contains(&name)
+            || table
+                .get("model_providers")
+                .and_then(|v| v.as_table())
+                .is_some_and(|p| p.contains_key(name));
+        if !known {
+            diagnostics.push(
+                ValidationSeverity::Error,
+                "unknown-model-provider",
+                format!(
+                    "model_provider = \"{}\" 未在 [model_providers] 中定义",
+                    name
+                ),
+                &["model_provider"],
+            );
+        }
+    }
+
+    let profiles = table.get("profiles").and_then(|v| v.as_table());
+    if let Some(profiles) = profiles {
+        for (name, profile) in profiles {
+            if let Some(profile) = profile.as_table() {
+                check_codex_enums(&mut diagnostics, profile, &["profiles", name]);
+            }
+        }
+    }
+    if let Some(name) = table.get("profile").and_then(|v| v.as_str()) {
+        if !profiles.is_some_and(|p| p.contains_key(name)) {
+            diagnostics.push(
+                ValidationSeverity::Error,
+                "unknown-profile",
+                format!("profile = \"{}\" 未在 [profiles] 中定义", name),
+                &["profile"],
+            );
+        }
+    }
+    diagnostics.finish()
+}
+
+/// 校验取值受限的字段（approval_policy、sandbox_mode 等）
+fn check_codex_enums(diagnostics: &mut Diagnostics, table: &toml::Table, prefix: &[&str]) {
+    for (key, allowed) in crate::codex_profiles::ENUM_FIELDS {
+        let Some(value) = table.get(*key) else {
+            continue;
+        };
+        if value.as_str().is_some_and(|v| allowed.contains(&v)) {
+            continue;
+        }
+        let mut path = prefix.to_vec();
+        path.push(key);
+        diagnostics.push(
+            ValidationSeverity::Error,
+            "invalid-value",
+            format!("{} 的取值必须为 {} 之一", key, allowed.join(" / ")),
+            &path,
+        );
+    }
+}
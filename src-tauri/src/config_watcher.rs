@@ -0,0 +1,132 @@
+use notify_debouncer_mini::notify::{RecommendedWatcher, RecursiveMode};
+use notify_debouncer_mini::{new_debouncer, DebounceEventResult, Debouncer};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_config::AppType;
+
+/// live 配置被外部修改，前端据此刷新
+pub const EVENT_LIVE_CONFIG_CHANGED: &str = "live-config-changed";
+
+/// 合并短时间内的连续修改（编辑器保存、原子替换会产生多次事件）
+const DEBOUNCE: Duration = Duration::from_millis(500);
+
+/// 当前的监听器；替换或清空即停止旧的监听
+static WATCHER: Mutex<Option<Debouncer<RecommendedWatcher>>> = Mutex::new(None);
+
+/// 外部修改通知
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LiveConfigChange {
+    pub app_type: String,
+    pub path: String,
+    /// 文件是否仍然存在（被删除时为 false）
+    pub exists: bool,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+}
+
+/// 监听的文件：各应用的 live 配置与全局规则文件
+fn watched_files() -> Vec<(AppType, PathBuf)> {
+    let mut files = Vec::new();
+    for app_type in AppType::all() {
+        let adapter = crate::app_adapter::adapter_for(&app_type);
+        for path in adapter.live_paths().into_iter().chain(adapter.rules_path()) {
+            files.push((app_type.clone(), path));
+        }
+    }
+    files
+}
+
+/// 监听器报告的路径可能经过规范化（如 macOS 的 /private 前缀），按目录与文件名比较
+fn same_file(watched: &Path, reported: &Path) -> bool {
+    if watched == reported {
+        return true;
+    }
+    watched.file_name() == reported.file_name()
+        && match (watched.parent(), reported.parent()) {
+            (Some(a), Some(b)) => a.canonicalize().ok() == b.canonicalize().ok(),
+            _ => false,
+        }
+}
+
+fn handle_events(app: &AppHandle, files: &[(AppType, PathBuf)], result: DebounceEventResult) {
+    let events = match result {
+        Ok(events) => events,
+        Err(e) => {
+            log::warn!("监听配置文件失败: {}", e);
+            return;
+        }
+    };
+    let mut seen = HashSet::new();
+    for event in events {
+        let Some((app_type, path)) = files.iter().find(|(_, p)| same_file(p, &event.path)) else {
+            continue;
+        };
+        if !seen.insert(path.clone()) {
+            continue;
+        }
+        let exists = path.exists();
+        // cc-switch 自身写入的内容与记录一致，不视为外部修改
+        if exists && crate::write_guard::is_own_write(path) {
+            continue;
+        }
+        log::info!("检测到外部修改: {}", path.display());
+        let change = LiveConfigChange {
+            app_type: app_type.as_str().to_string(),
+            path: path.to_string_lossy().to_string(),
+            exists,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        };
+        if let Err(e) = app.emit(EVENT_LIVE_CONFIG_CHANGED, &change) {
+            log::warn!("转发配置变更到前端失败: {}", e);
+        }
+        crate::events::publish(
+            crate::events::AppEvent::new(EVENT_LIVE_CONFIG_CHANGED)
+                .with_data(json!({ "appType": change.app_type, "path": change.path })),
+        );
+        let key = if exists {
+            "notification.externalChange.modified"
+        } else {
+            "notification.externalChange.deleted"
+        };
+        let message = crate::i18n::tf(key, &[("path", &change.path)]);
+        crate::notifications::notify(crate::notifications::NotificationRecord::new(
+            crate::notifications::NotificationCategory::ExternalChange,
+            crate::i18n::t("notification.externalChange.title"),
+            message,
+        ));
+    }
+}
+
+/// 开始（或按最新的目录设置重新开始）监听。
+/// 监听所在目录而非文件本身，因为原子写入会以 rename 替换文件
+pub fn start(app: &AppHandle) -> Result<(), String> {
+    let files = watched_files();
+    let dirs: HashSet<PathBuf> = files
+        .iter()
+        .filter_map(|(_, path)| path.parent().map(|p| p.to_path_buf()))
+        .filter(|dir| dir.is_dir())
+        .collect();
+
+    let handler_app = app.clone();
+    let handler_files = files.clone();
+    let mut debouncer = new_debouncer(DEBOUNCE, move |result: DebounceEventResult| {
+        handle_events(&handler_app, &handler_files, result)
+    })
+    .map_err(|e| format!("创建文件监听器失败: {}", e))?;
+    for dir in &dirs {
+        if let Err(e) = debouncer.watcher().watch(dir, RecursiveMode::NonRecursive) {
+            log::warn!("监听目录失败 {}: {}", dir.display(), e);
+        }
+    }
+
+    let mut watcher = WATCHER.lock().map_err(crate::error::lock_failed)?;
+    *watcher = Some(debouncer);
+    Ok(())
+}
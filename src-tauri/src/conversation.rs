@@ -2,19 +2,34 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::app_config::AppType;
+use crate::conversation_tags::TagStore;
+
 /// 对话记录元数据
 #[derive(Debug, Clone, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct ConversationMeta {
     pub id: String,
-    pub app_type: String, // "claude" or "codex"
+    pub app_type: String, // "claude", "codex" or "gemini"
     pub file_path: String,
     pub file_size: u64,
     pub created_at: Option<i64>,
     pub modified_at: i64,
     pub message_count: usize,
     pub project_name: Option<String>, // Claude: 项目名称
-    pub session_id: Option<String>,   // Codex: 会话ID
+    pub session_id: Option<String>,   // Codex / Gemini: 会话ID
+    /// 手动标签与命中的智能标签
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 会话仍在运行中的 CLI 进程使用（仅列表接口填充）
+    #[serde(default)]
+    pub live: bool,
+    /// 无法解析的行数（JSONL 中的损坏行；Gemini 会话整体无法解析时为 1）
+    #[serde(default)]
+    pub parse_errors: usize,
+    /// 所属的已登记配置目录名称（默认目录为 None）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub home: Option<String>,
 }
 
 /// Claude 对话消息
@@ -41,7 +56,7 @@ pub struct CodexMessage {
 }
 
 /// 获取 Claude 对话记录目录
-fn get_claude_conversations_dir() -> PathBuf {
+pub(crate) fn get_claude_conversations_dir() -> PathBuf {
     if let Some(custom) = crate::settings::get_claude_override_dir() {
         return custom.join("projects");
     }
@@ -52,7 +67,7 @@ fn get_claude_conversations_dir() -> PathBuf {
 }
 
 /// 获取 Codex 对话记录目录
-fn get_codex_conversations_dir() -> PathBuf {
+pub(crate) fn get_codex_conversations_dir() -> PathBuf {
     if let Some(custom) = crate::settings::get_codex_override_dir() {
         return custom.join("sessions");
     }
@@ -62,17 +77,108 @@ fn get_codex_conversations_dir() -> PathBuf {
         .join("sessions")
 }
 
+/// 获取 Gemini 对话记录目录
+pub(crate) fn get_gemini_conversations_dir() -> PathBuf {
+    crate::gemini_config::get_gemini_conversations_dir()
+}
+
+/// 设置了自定义对话目录时只使用自定义目录，否则使用默认目录；
+/// 指向同一位置的目录（如符号链接）只保留第一个，避免会话重复列出
+fn conversation_roots(custom: Vec<PathBuf>, defaults: Vec<PathBuf>) -> Vec<PathBuf> {
+    let candidates = if custom.is_empty() { defaults } else { custom };
+    let mut seen = std::collections::HashSet::new();
+    candidates
+        .into_iter()
+        .filter(|dir| seen.insert(fs::canonicalize(dir).unwrap_or_else(|_| dir.clone())))
+        .collect()
+}
+
+/// 当前配置目录下的对话目录，以及其余已登记配置目录下的同名子目录
+fn home_conversation_dirs(app: &AppType, primary: PathBuf, sub_dir: &str) -> Vec<PathBuf> {
+    let mut dirs = vec![primary];
+    dirs.extend(
+        crate::app_homes::all_home_dirs(app)
+            .into_iter()
+            .map(|(_, dir)| dir.join(sub_dir)),
+    );
+    dirs
+}
+
+/// Claude 对话记录根目录（每个目录下为 <项目>/<会话>.jsonl）；
+/// 导入的网页版对话（见 web_import）同样按此格式保存，始终作为额外的根目录
+pub(crate) fn claude_conversation_roots() -> Vec<PathBuf> {
+    let mut roots = conversation_roots(
+        crate::settings::get_claude_conversation_dirs(),
+        home_conversation_dirs(&AppType::Claude, get_claude_conversations_dir(), "projects"),
+    );
+    roots.push(crate::web_import::imported_dir());
+    roots
+}
+
+/// Codex 对话记录根目录（每个目录下为 <年>/<月>/<日>/<会话>.jsonl）
+pub(crate) fn codex_conversation_roots() -> Vec<PathBuf> {
+    conversation_roots(
+        crate::settings::get_codex_conversation_dirs(),
+        home_conversation_dirs(&AppType::Codex, get_codex_conversations_dir(), "sessions"),
+    )
+}
+
+/// Gemini 对话记录根目录（每个目录下为 <项目哈希>/chats/*.json）
+pub(crate) fn gemini_conversation_roots() -> Vec<PathBuf> {
+    conversation_roots(
+        crate::settings::get_gemini_conversation_dirs(),
+        vec![get_gemini_conversations_dir()],
+    )
+}
+
+/// 容错读取会话文件：非 UTF-8 内容以替换字符代替，避免整个文件被跳过
+fn read_lossy(file_path: &Path) -> Result<String, String> {
+    let bytes = fs::read(file_path).map_err(|e| format!("读取文件失败: {}", e))?;
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+/// 统计 JSONL 内容：(可解析的行数, 无法解析的非空行数)
+fn count_jsonl_lines(content: &str) -> (usize, usize) {
+    content
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .fold((0, 0), |(valid, broken), line| {
+            if serde_json::from_str::<serde::de::IgnoredAny>(line).is_ok() {
+                (valid + 1, broken)
+            } else {
+                (valid, broken + 1)
+            }
+        })
+}
+
 /// 列出 Claude 对话记录
 pub fn list_claude_conversations() -> Result<Vec<ConversationMeta>, String> {
-    let projects_dir = get_claude_conversations_dir();
-    if !projects_dir.exists() {
-        return Ok(Vec::new());
+    let mut conversations = Vec::new();
+    let tag_store = TagStore::load();
+
+    for projects_dir in claude_conversation_roots() {
+        if projects_dir.exists() {
+            collect_claude_conversations(&projects_dir, &tag_store, &mut conversations)?;
+        }
+    }
+    for meta in &mut conversations {
+        meta.home = crate::app_homes::home_label_for(&AppType::Claude, Path::new(&meta.file_path));
     }
 
-    let mut conversations = Vec::new();
+    // 按修改时间倒序排序
+    conversations.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
 
+    Ok(conversations)
+}
+
+/// 收集单个 Claude 对话根目录下的会话
+fn collect_claude_conversations(
+    projects_dir: &Path,
+    tag_store: &TagStore,
+    conversations: &mut Vec<ConversationMeta>,
+) -> Result<(), String> {
     // 遍历项目目录
-    for entry in fs::read_dir(&projects_dir)
+    for entry in fs::read_dir(projects_dir)
         .map_err(|e| format!("读取 Claude 项目目录失败: {}", e))?
     {
         let entry = entry.map_err(|e| format!("读取目录项失败: {}", e))?;
@@ -93,7 +199,7 @@ pub fn list_claude_conversations() -> Result<Vec<ConversationMeta>, String> {
                 let file_path = file_entry.path();
 
                 if file_path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                    if let Ok(meta) = get_claude_conversation_meta(&file_path, &dir_name) {
+                    if let Ok(meta) = get_claude_conversation_meta(&file_path, &dir_name, tag_store) {
                         conversations.push(meta);
                     }
                 }
@@ -101,16 +207,14 @@ pub fn list_claude_conversations() -> Result<Vec<ConversationMeta>, String> {
         }
     }
 
-    // 按修改时间倒序排序
-    conversations.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
-
-    Ok(conversations)
+    Ok(())
 }
 
 /// 获取 Claude 对话元数据
 fn get_claude_conversation_meta(
     file_path: &Path,
     project_name: &str,
+    tag_store: &TagStore,
 ) -> Result<ConversationMeta, String> {
     let metadata = fs::metadata(file_path)
         .map_err(|e| format!("获取文件元数据失败: {}", e))?;
@@ -121,16 +225,14 @@ fn get_claude_conversation_meta(
         .to_string_lossy()
         .to_string();
 
-    // 读取文件统计消息数量
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("读取文件失败: {}", e))?;
-    let message_count = content.lines().count();
+    // 只统计可解析的行，损坏的行单独计数
+    let content = read_lossy(file_path)?;
+    let (message_count, parse_errors) = count_jsonl_lines(&content);
 
-    // 尝试读取第一条消息获取 sessionId
+    // 首行可能是摘要或损坏的行，取第一条能解析的消息的 sessionId
     let session_id = content
         .lines()
-        .next()
-        .and_then(|line| serde_json::from_str::<ClaudeMessage>(line).ok())
+        .find_map(|line| serde_json::from_str::<ClaudeMessage>(line).ok())
         .map(|msg| msg.session_id);
 
     let modified_at = metadata
@@ -146,7 +248,7 @@ fn get_claude_conversation_meta(
         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
         .map(|d| d.as_secs() as i64);
 
-    Ok(ConversationMeta {
+    let mut meta = ConversationMeta {
         id: file_name.clone(),
         app_type: "claude".to_string(),
         file_path: file_path.to_string_lossy().to_string(),
@@ -156,20 +258,44 @@ fn get_claude_conversation_meta(
         message_count,
         project_name: Some(project_name.to_string()),
         session_id,
-    })
+        tags: Vec::new(),
+        live: false,
+        parse_errors,
+        home: None,
+    };
+    meta.tags = tag_store.tags_for(&meta, &content);
+
+    Ok(meta)
 }
 
 /// 列出 Codex 对话记录
 pub fn list_codex_conversations() -> Result<Vec<ConversationMeta>, String> {
-    let sessions_dir = get_codex_conversations_dir();
-    if !sessions_dir.exists() {
-        return Ok(Vec::new());
+    let mut conversations = Vec::new();
+    let tag_store = TagStore::load();
+
+    for sessions_dir in codex_conversation_roots() {
+        if sessions_dir.exists() {
+            collect_codex_conversations(&sessions_dir, &tag_store, &mut conversations)?;
+        }
+    }
+    for meta in &mut conversations {
+        meta.home = crate::app_homes::home_label_for(&AppType::Codex, Path::new(&meta.file_path));
     }
 
-    let mut conversations = Vec::new();
+    // 按修改时间倒序排序
+    conversations.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
 
+    Ok(conversations)
+}
+
+/// 收集单个 Codex 对话根目录下的会话
+fn collect_codex_conversations(
+    sessions_dir: &Path,
+    tag_store: &TagStore,
+    conversations: &mut Vec<ConversationMeta>,
+) -> Result<(), String> {
     // 遍历年/月/日目录结构
-    for year_entry in fs::read_dir(&sessions_dir)
+    for year_entry in fs::read_dir(sessions_dir)
         .map_err(|e| format!("读取 Codex 会话目录失败: {}", e))?
     {
         let year_entry = year_entry.map_err(|e| format!("读取年份目录失败: {}", e))?;
@@ -207,7 +333,7 @@ pub fn list_codex_conversations() -> Result<Vec<ConversationMeta>, String> {
                     let file_path = file_entry.path();
 
                     if file_path.extension().and_then(|s| s.to_str()) == Some("jsonl") {
-                        if let Ok(meta) = get_codex_conversation_meta(&file_path) {
+                        if let Ok(meta) = get_codex_conversation_meta(&file_path, tag_store) {
                             conversations.push(meta);
                         }
                     }
@@ -216,14 +342,14 @@ pub fn list_codex_conversations() -> Result<Vec<ConversationMeta>, String> {
         }
     }
 
-    // 按修改时间倒序排序
-    conversations.sort_by(|a, b| b.modified_at.cmp(&a.modified_at));
-
-    Ok(conversations)
+    Ok(())
 }
 
 /// 获取 Codex 对话元数据
-fn get_codex_conversation_meta(file_path: &Path) -> Result<ConversationMeta, String> {
+fn get_codex_conversation_meta(
+    file_path: &Path,
+    tag_store: &TagStore,
+) -> Result<ConversationMeta, String> {
     let metadata = fs::metadata(file_path)
         .map_err(|e| format!("获取文件元数据失败: {}", e))?;
 
@@ -233,17 +359,16 @@ fn get_codex_conversation_meta(file_path: &Path) -> Result<ConversationMeta, Str
         .to_string_lossy()
         .to_string();
 
-    // 读取文件统计消息数量
-    let content = fs::read_to_string(file_path)
-        .map_err(|e| format!("读取文件失败: {}", e))?;
-    let message_count = content.lines().count();
+    // 只统计可解析的行，损坏的行单独计数
+    let content = read_lossy(file_path)?;
+    let (message_count, parse_errors) = count_jsonl_lines(&content);
 
-    // 尝试读取第一条消息获取 session_id
+    // 取第一条能解析的 session_meta 中的 session_id
     let session_id = content
         .lines()
-        .next()
-        .and_then(|line| serde_json::from_str::<CodexMessage>(line).ok())
-        .and_then(|msg| {
+        .take(50)
+        .filter_map(|line| serde_json::from_str::<CodexMessage>(line).ok())
+        .find_map(|msg| {
             if msg.msg_type == "session_meta" {
                 msg.payload
                     .get("id")
@@ -267,7 +392,7 @@ fn get_codex_conversation_meta(file_path: &Path) -> Result<ConversationMeta, Str
         .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
         .map(|d| d.as_secs() as i64);
 
-    Ok(ConversationMeta {
+    let mut meta = ConversationMeta {
         id: file_name.clone(),
         app_type: "codex".to_string(),
         file_path: file_path.to_string_lossy().to_string(),
@@ -277,7 +402,124 @@ fn get_codex_conversation_meta(file_path: &Path) -> Result<ConversationMeta, Str
         message_count,
         project_name: None,
         session_id,
-    })
+        tags: Vec::new(),
+        live: false,
+        parse_errors,
+        home: None,
+    };
+    meta.tags = tag_store.tags_for(&meta, &content);
+
+    Ok(meta)
+}
+
+/// 列出 Gemini 对话记录（tmp/<项目哈希>/chats/*.json）
+pub fn list_gemini_conversations() -> Result<Vec<ConversationMeta>, String> {
+    let mut conversations = Vec::new();
+    let tag_store = TagStore::load();
+
+    for tmp_dir in gemini_conversation_roots() {
+        if tmp_dir.exists() {
+            collect_gemini_conversations(&tmp_dir, &tag_store, &mut conversations)?;
+        }
+    }
+
+    // 按修改时间倒序排序
+    conversations.sort_by_key(|c| std::cmp::Reverse(c.modified_at));
+
+    Ok(conversations)
+}
+
+/// 收集单个 Gemini 对话根目录下的会话
+fn collect_gemini_conversations(
+    tmp_dir: &Path,
+    tag_store: &TagStore,
+    conversations: &mut Vec<ConversationMeta>,
+) -> Result<(), String> {
+    for project_entry in fs::read_dir(tmp_dir)
+        .map_err(|e| format!("读取 Gemini 会话目录失败: {}", e))?
+    {
+        let project_entry = project_entry.map_err(|e| format!("读取目录项失败: {}", e))?;
+        let chats_dir = project_entry.path().join("chats");
+        if !chats_dir.is_dir() {
+            continue;
+        }
+
+        for file_entry in fs::read_dir(&chats_dir)
+            .map_err(|e| format!("读取会话文件失败: {}", e))?
+        {
+            let file_entry = file_entry.map_err(|e| format!("读取文件项失败: {}", e))?;
+            let file_path = file_entry.path();
+
+            if file_path.extension().and_then(|s| s.to_str()) == Some("json") {
+                if let Ok(meta) = get_gemini_conversation_meta(&file_path, tag_store) {
+                    conversations.push(meta);
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// 获取 Gemini 对话元数据（会话文件为单个 JSON：{ sessionId, messages: [...] }）
+fn get_gemini_conversation_meta(
+    file_path: &Path,
+    tag_store: &TagStore,
+) -> Result<ConversationMeta, String> {
+    let metadata = fs::metadata(file_path)
+        .map_err(|e| format!("获取文件元数据失败: {}", e))?;
+
+    // 无法解析时仍列出该文件，便于用户发现并处理
+    let content = read_lossy(file_path)?;
+    let parsed = serde_json::from_str::<serde_json::Value>(&content);
+    let parse_errors = usize::from(parsed.is_err());
+    let value = parsed.unwrap_or_default();
+
+    let message_count = value
+        .get("messages")
+        .and_then(|m| m.as_array())
+        .map(|m| m.len())
+        .unwrap_or(0);
+    let session_id = value
+        .get("sessionId")
+        .and_then(|v| v.as_str())
+        .map(|s| s.to_string());
+
+    let modified_at = metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64)
+        .unwrap_or(0);
+
+    let created_at = metadata
+        .created()
+        .ok()
+        .and_then(|t| t.duration_since(std::time::UNIX_EPOCH).ok())
+        .map(|d| d.as_secs() as i64);
+
+    let mut meta = ConversationMeta {
+        id: file_path
+            .file_stem()
+            .unwrap()
+            .to_string_lossy()
+            .to_string(),
+        app_type: "gemini".to_string(),
+        file_path: file_path.to_string_lossy().to_string(),
+        file_size: metadata.len(),
+        created_at,
+        modified_at,
+        message_count,
+        project_name: None,
+        session_id,
+        tags: Vec::new(),
+        live: false,
+        parse_errors,
+        home: None,
+    };
+    meta.tags = tag_store.tags_for(&meta, &content);
+
+    Ok(meta)
 }
 
 /// 删除对话记录
@@ -288,7 +530,7 @@ pub fn delete_conversation(file_path: &str) -> Result<(), String> {
     }
 
     // 删除文件
-    fs::remove_file(path).map_err(|e| format!("删除文件失败: {}", e))?;
+    crate::config::delete_file(path).map_err(|e| format!("删除文件失败: {}", e))?;
 
     // 尝试清理空文件夹
     if let Some(parent) = path.parent() {
@@ -308,12 +550,12 @@ fn cleanup_empty_directories(dir: &Path) {
             if fs::remove_dir(dir).is_ok() {
                 // 删除成功后,继续检查父目录
                 if let Some(parent) = dir.parent() {
-                    // 只清理 Codex 的日期目录结构 (sessions/year/month/day)
-                    // 不要删除 sessions 根目录或 Claude 的 projects 目录
-                    if parent.ends_with("sessions")
-                        || parent.ends_with("projects")
-                        || parent.file_name().is_none()
-                    {
+                    // 只清理对话目录内部的结构（如 Codex 的 sessions/year/month/day），
+                    // 不要删除各应用的对话记录根目录
+                    let is_root = crate::app_adapter::all_adapters()
+                        .iter()
+                        .any(|a| a.conversation_roots().iter().any(|d| d == parent));
+                    if is_root || parent.file_name().is_none() {
                         return;
                     }
                     cleanup_empty_directories(parent);
@@ -323,62 +565,903 @@ fn cleanup_empty_directories(dir: &Path) {
     }
 }
 
-/// 搜索对话记录
-pub fn search_conversations(
-    app_type: Option<String>,
-    keyword: &str,
-) -> Result<Vec<ConversationMeta>, String> {
-    let mut all_conversations = Vec::new();
+/// 列出指定应用的对话记录；未指定或未知应用时列出全部，按修改时间倒序
+pub fn list_conversations(app_type: Option<&str>) -> Result<Vec<ConversationMeta>, String> {
+    if let Some(adapter) = app_type.and_then(crate::app_adapter::adapter_by_name) {
+        return adapter.list_conversations();
+    }
+    let mut all = Vec::new();
+    for adapter in crate::app_adapter::all_adapters() {
+        all.extend(adapter.list_conversations()?);
+    }
+    all.sort_by_key(|c| std::cmp::Reverse(c.modified_at));
+    Ok(all)
+}
+
+/// 读取对话内容
+pub fn read_conversation_content(file_path: &str) -> Result<String, String> {
+    let path = Path::new(file_path);
+    if !path.exists() {
+        return Err("文件不存在".to_string());
+    }
+
+    read_lossy(path)
+}
+
+
+// ==================== 阅读模式 ====================
+
+/// 阅读模式的角色筛选
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum RoleFilter {
+    #[default]
+    All,
+    User,
+    Assistant,
+}
+
+/// 阅读模式选项
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingOptions {
+    #[serde(default)]
+    pub role: RoleFilter,
+    /// 将 tool_use / tool_result 配对折叠为摘要
+    #[serde(default)]
+    pub collapse_tools: bool,
+    /// 去除 thinking / reasoning 块
+    #[serde(default)]
+    pub strip_thinking: bool,
+}
+
+/// 阅读模式中的消息块
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum ReadingBlock {
+    Text {
+        text: String,
+    },
+    Thinking {
+        text: String,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolUse {
+        id: String,
+        name: String,
+        input: serde_json::Value,
+    },
+    #[serde(rename_all = "camelCase")]
+    ToolResult {
+        tool_use_id: String,
+        content: String,
+        is_error: bool,
+    },
+    /// 折叠后的工具调用摘要
+    #[serde(rename_all = "camelCase")]
+    ToolSummary {
+        name: String,
+        summary: String,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        result_preview: Option<String>,
+        result_chars: usize,
+        is_error: bool,
+    },
+}
+
+/// 阅读模式中的消息
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ReadingMessage {
+    /// 在原始文件中的行号（从 0 开始；Gemini 为 messages 数组下标）
+    pub index: usize,
+    /// "user" / "assistant" / "tool"（仅包含工具结果）/ 其他原始角色
+    pub role: String,
+    pub timestamp: Option<String>,
+    pub blocks: Vec<ReadingBlock>,
+    /// 条目自身的 uuid / id（用于引用消息，如导出选中消息与消息备注）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    /// 该消息的备注（见 conversation_notes）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// 该消息的书签 id（见 conversation_bookmarks）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub bookmark_id: Option<String>,
+}
+
+/// 摘要输入时优先展示的字段
+const TOOL_SUMMARY_KEYS: [&str; 8] = [
+    "command",
+    "file_path",
+    "path",
+    "pattern",
+    "url",
+    "query",
+    "description",
+    "prompt",
+];
+const TOOL_SUMMARY_MAX_CHARS: usize = 160;
+const TOOL_RESULT_PREVIEW_MAX_CHARS: usize = 200;
+
+pub(crate) fn truncate_chars(text: &str, max: usize) -> String {
+    if text.chars().count() <= max {
+        text.to_string()
+    } else {
+        let mut out: String = text.chars().take(max).collect();
+        out.push('…');
+        out
+    }
+}
+
+/// 将字符串或内容块数组转为纯文本
+fn value_to_text(value: &serde_json::Value) -> String {
+    match value {
+        serde_json::Value::String(s) => s.clone(),
+        serde_json::Value::Array(items) => items
+            .iter()
+            .filter_map(|item| {
+                item.get("text")
+                    .and_then(|t| t.as_str())
+                    .map(|s| s.to_string())
+                    .or_else(|| item.as_str().map(|s| s.to_string()))
+            })
+            .collect::<Vec<_>>()
+            .join("\n"),
+        serde_json::Value::Null => String::new(),
+        other => other.to_string(),
+    }
+}
+
+fn summarize_tool_input(input: &serde_json::Value) -> String {
+    let picked = TOOL_SUMMARY_KEYS
+        .iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()));
+    let text = match picked {
+        Some(s) => s.to_string(),
+        None if input.is_null() => String::new(),
+        None => input.to_string(),
+    };
+    truncate_chars(text.lines().next().unwrap_or(""), TOOL_SUMMARY_MAX_CHARS)
+}
+
+/// 解析 Claude 消息内容块
+fn parse_claude_blocks(content: &serde_json::Value) -> Vec<ReadingBlock> {
+    let Some(items) = content.as_array() else {
+        let text = value_to_text(content);
+        return if text.is_empty() {
+            Vec::new()
+        } else {
+            vec![ReadingBlock::Text { text }]
+        };
+    };
+
+    items
+        .iter()
+        .filter_map(|item| {
+            let str_field = |key: &str| {
+                item.get(key)
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string()
+            };
+            match item.get("type").and_then(|t| t.as_str()) {
+                Some("text") => Some(ReadingBlock::Text {
+                    text: str_field("text"),
+                }),
+                Some("thinking") => Some(ReadingBlock::Thinking {
+                    text: str_field("thinking"),
+                }),
+                Some("tool_use") => Some(ReadingBlock::ToolUse {
+                    id: str_field("id"),
+                    name: str_field("name"),
+                    input: item.get("input").cloned().unwrap_or_default(),
+                }),
+                Some("tool_result") => Some(ReadingBlock::ToolResult {
+                    tool_use_id: str_field("tool_use_id"),
+                    content: item.get("content").map(value_to_text).unwrap_or_default(),
+                    is_error: item
+                        .get("is_error")
+                        .and_then(|v| v.as_bool())
+                        .unwrap_or(false),
+                }),
+                _ => None,
+            }
+        })
+        .collect()
+}
+
+pub(crate) fn parse_claude_line(value: &serde_json::Value) -> Option<(String, Vec<ReadingBlock>)> {
+    let msg_type = value.get("type").and_then(|t| t.as_str())?;
+    if msg_type != "user" && msg_type != "assistant" {
+        return None;
+    }
+    let message = value.get("message")?;
+    let role = message
+        .get("role")
+        .and_then(|r| r.as_str())
+        .unwrap_or(msg_type)
+        .to_string();
+    let blocks = parse_claude_blocks(message.get("content")?);
+    Some((role, blocks))
+}
+
+pub(crate) fn parse_codex_line(value: &serde_json::Value) -> Option<(String, Vec<ReadingBlock>)> {
+    // 新格式包裹在 response_item.payload 中，旧格式直接为条目本身
+    let item = match value.get("type").and_then(|t| t.as_str()) {
+        Some("response_item") => value.get("payload")?,
+        _ => value,
+    };
+    let str_field = |key: &str| {
+        item.get(key)
+            .and_then(|v| v.as_str())
+            .unwrap_or("")
+            .to_string()
+    };
+
+    match item.get("type").and_then(|t| t.as_str())? {
+        "message" => {
+            let text = value_to_text(item.get("content")?);
+            let blocks = if text.is_empty() {
+                Vec::new()
+            } else {
+                vec![ReadingBlock::Text { text }]
+            };
+            Some((str_field("role"), blocks))
+        }
+        "reasoning" => {
+            let text = item.get("summary").map(value_to_text).unwrap_or_default();
+            Some(("assistant".to_string(), vec![ReadingBlock::Thinking { text }]))
+        }
+        "function_call" | "custom_tool_call" => {
+            let raw = item
+                .get("arguments")
+                .or_else(|| item.get("input"))
+                .cloned()
+                .unwrap_or_default();
+            // arguments 为 JSON 字符串时尽量解析为对象
+            let input = raw
+                .as_str()
+                .and_then(|s| serde_json::from_str(s).ok())
+                .unwrap_or(raw);
+            Some((
+                "assistant".to_string(),
+                vec![ReadingBlock::ToolUse {
+                    id: str_field("call_id"),
+                    name: str_field("name"),
+                    input,
+                }],
+            ))
+        }
+        "function_call_output" | "custom_tool_call_output" => {
+            let output = item.get("output").cloned().unwrap_or_default();
+            // output 可能是 {"output": "...", "metadata": {...}} 形式的 JSON 字符串
+            let parsed = output
+                .as_str()
+                .and_then(|s| serde_json::from_str::<serde_json::Value>(s).ok())
+                .filter(|v| v.is_object());
+            let content = match &parsed {
+                Some(obj) => obj.get("output").map(value_to_text).unwrap_or_default(),
+                None => value_to_text(&output),
+            };
+            let is_error = parsed
+                .as_ref()
+                .and_then(|obj| obj.get("metadata"))
+                .and_then(|m| m.get("exit_code"))
+                .and_then(|c| c.as_i64())
+                .map(|c| c != 0)
+                .unwrap_or(false);
+            Some((
+                "tool".to_string(),
+                vec![ReadingBlock::ToolResult {
+                    tool_use_id: str_field("call_id"),
+                    content,
+                    is_error,
+                }],
+            ))
+        }
+        _ => None,
+    }
+}
+
+/// 解析 Gemini 会话中的一条消息：type 为 user / gemini，gemini 消息可带 thoughts 与 toolCalls
+pub(crate) fn parse_gemini_message(value: &serde_json::Value) -> Option<(String, Vec<ReadingBlock>)> {
+    let role = match value.get("type").and_then(|t| t.as_str())? {
+        "user" => "user",
+        "gemini" => "assistant",
+        // info / error 等为 CLI 提示信息，不属于对话
+        _ => return None,
+    };
 
-    // 根据 app_type 决定搜索范围
-    match app_type.as_deref() {
-        Some("claude") => {
-            all_conversations.extend(list_claude_conversations()?);
+    let mut blocks = Vec::new();
+    if let Some(thoughts) = value.get("thoughts").and_then(|t| t.as_array()) {
+        for thought in thoughts {
+            let text = thought
+                .get("description")
+                .map(value_to_text)
+                .unwrap_or_else(|| value_to_text(thought));
+            if !text.is_empty() {
+                blocks.push(ReadingBlock::Thinking { text });
+            }
         }
-        Some("codex") => {
-            all_conversations.extend(list_codex_conversations()?);
+    }
+    let text = value.get("content").map(value_to_text).unwrap_or_default();
+    if !text.is_empty() {
+        blocks.push(ReadingBlock::Text { text });
+    }
+    if let Some(calls) = value.get("toolCalls").and_then(|c| c.as_array()) {
+        for call in calls {
+            let id = call
+                .get("id")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string();
+            blocks.push(ReadingBlock::ToolUse {
+                id: id.clone(),
+                name: call
+                    .get("name")
+                    .and_then(|v| v.as_str())
+                    .unwrap_or("")
+                    .to_string(),
+                input: call.get("args").cloned().unwrap_or_default(),
+            });
+            if let Some(result) = call.get("result").filter(|r| !r.is_null()) {
+                blocks.push(ReadingBlock::ToolResult {
+                    tool_use_id: id,
+                    content: value_to_text(result),
+                    is_error: call.get("status").and_then(|s| s.as_str()) == Some("error"),
+                });
+            }
         }
-        _ => {
-            // 搜索所有
-            all_conversations.extend(list_claude_conversations()?);
-            all_conversations.extend(list_codex_conversations()?);
+    }
+    Some((role.to_string(), blocks))
+}
+
+/// 将 tool_use 与对应的 tool_result 合并为摘要块，并移除已配对的结果块
+fn collapse_tool_pairs(messages: &mut [ReadingMessage]) {
+    let mut results: std::collections::HashMap<String, (String, bool)> =
+        std::collections::HashMap::new();
+    for message in messages.iter() {
+        for block in &message.blocks {
+            if let ReadingBlock::ToolResult {
+                tool_use_id,
+                content,
+                is_error,
+            } = block
+            {
+                results.insert(tool_use_id.clone(), (content.clone(), *is_error));
+            }
         }
     }
 
-    // 如果没有关键词,返回所有
-    if keyword.is_empty() {
-        return Ok(all_conversations);
+    let mut paired: std::collections::HashSet<String> = std::collections::HashSet::new();
+    for message in messages.iter_mut() {
+        for block in message.blocks.iter_mut() {
+            if let ReadingBlock::ToolUse { id, name, input } = block {
+                let result = results.get(id.as_str());
+                if result.is_some() {
+                    paired.insert(id.clone());
+                }
+                *block = ReadingBlock::ToolSummary {
+                    name: name.clone(),
+                    summary: summarize_tool_input(input),
+                    result_preview: result.map(|(content, _)| {
+                        truncate_chars(content.trim(), TOOL_RESULT_PREVIEW_MAX_CHARS)
+                    }),
+                    result_chars: result.map(|(content, _)| content.chars().count()).unwrap_or(0),
+                    is_error: result.map(|(_, err)| *err).unwrap_or(false),
+                };
+            }
+        }
     }
 
-    // 过滤包含关键词的对话
-    let keyword_lower = keyword.to_lowercase();
-    let filtered: Vec<ConversationMeta> = all_conversations
+    for message in messages.iter_mut() {
+        message.blocks.retain(|block| match block {
+            ReadingBlock::ToolResult { tool_use_id, .. } => !paired.contains(tool_use_id),
+            _ => true,
+        });
+    }
+}
+
+/// 按会话文件所在目录确定应用；无法识别时按 Claude 格式解析
+pub(crate) fn adapter_for_conversation(
+    file_path: &Path,
+) -> &'static dyn crate::app_adapter::AppAdapter {
+    crate::app_adapter::all_adapters()
         .into_iter()
-        .filter(|conv| {
-            conv.id.to_lowercase().contains(&keyword_lower)
-                || conv
-                    .project_name
-                    .as_ref()
-                    .map(|p| p.to_lowercase().contains(&keyword_lower))
-                    .unwrap_or(false)
-                || conv
-                    .session_id
-                    .as_ref()
-                    .map(|s| s.to_lowercase().contains(&keyword_lower))
-                    .unwrap_or(false)
-        })
+        .find(|a| a.owns_conversation(file_path))
+        .unwrap_or_else(|| crate::app_adapter::adapter_for(&crate::app_config::AppType::Claude))
+}
+
+/// 将一条会话条目转换为阅读模式消息；非对话条目返回 None
+pub(crate) fn reading_message(
+    adapter: &dyn crate::app_adapter::AppAdapter,
+    index: usize,
+    value: &serde_json::Value,
+) -> Option<ReadingMessage> {
+    let (mut role, blocks) = adapter.parse_conversation_item(value)?;
+    // 仅含工具结果的 user 消息实际是工具输出
+    if !blocks.is_empty()
+        && blocks
+            .iter()
+            .all(|b| matches!(b, ReadingBlock::ToolResult { .. }))
+    {
+        role = "tool".to_string();
+    }
+    Some(ReadingMessage {
+        index,
+        role,
+        timestamp: value
+            .get("timestamp")
+            .and_then(|t| t.as_str())
+            .map(|s| s.to_string()),
+        blocks,
+        uuid: item_id(value).map(|s| s.to_string()),
+        note: None,
+        bookmark_id: None,
+    })
+}
+
+/// 以阅读模式读取对话：解析消息、按角色筛选、折叠工具调用、去除思考块
+pub fn read_conversation_messages(
+    file_path: &str,
+    options: &ReadingOptions,
+) -> Result<Vec<ReadingMessage>, String> {
+    let content = read_conversation_content(file_path)?;
+    let adapter = adapter_for_conversation(Path::new(file_path));
+    let items = adapter.conversation_items(&content)?;
+
+    let mut messages: Vec<ReadingMessage> = items
+        .iter()
+        .enumerate()
+        .filter_map(|(index, value)| reading_message(adapter, index, value))
         .collect();
 
-    Ok(filtered)
+    if options.collapse_tools {
+        collapse_tool_pairs(&mut messages);
+    }
+    if options.strip_thinking {
+        for message in messages.iter_mut() {
+            message
+                .blocks
+                .retain(|b| !matches!(b, ReadingBlock::Thinking { .. }));
+        }
+    }
+
+    messages.retain(|m| {
+        !m.blocks.is_empty()
+            && match options.role {
+                RoleFilter::All => true,
+                RoleFilter::User => m.role == "user",
+                RoleFilter::Assistant => m.role == "assistant",
+            }
+    });
+
+    let notes = crate::conversation_notes::get_notes(file_path);
+    for message in messages.iter_mut() {
+        message.note = message
+            .uuid
+            .as_ref()
+            .and_then(|uuid| notes.messages.get(uuid))
+            .cloned();
+    }
+    for bookmark in crate::conversation_bookmarks::list_bookmarks(Some(file_path)) {
+        if let Some(message) = messages
+            .iter_mut()
+            .find(|m| m.index == bookmark.bookmark.index)
+        {
+            message.bookmark_id = Some(bookmark.bookmark.id);
+        }
+    }
+    Ok(messages)
 }
 
-/// 读取对话内容
-pub fn read_conversation_content(file_path: &str) -> Result<String, String> {
-    let path = Path::new(file_path);
-    if !path.exists() {
-        return Err("文件不存在".to_string());
+// ==================== 会话工作目录 ====================
+
+/// 递归收集 dir 下修改时间不早于 since、扩展名为 extension 的会话文件（跳过隐藏目录，最多下探 depth 层）
+pub(crate) fn collect_session_files(
+    dir: &Path,
+    depth: usize,
+    since: std::time::SystemTime,
+    extension: &str,
+) -> Vec<PathBuf> {
+    let mut out = Vec::new();
+    let Ok(entries) = fs::read_dir(dir) else {
+        return out;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        if path.is_dir() {
+            let hidden = path
+                .file_name()
+                .map(|n| n.to_string_lossy().starts_with('.'))
+                .unwrap_or(false);
+            if depth > 0 && !hidden {
+                out.extend(collect_session_files(&path, depth - 1, since, extension));
+            }
+        } else if path.extension().and_then(|s| s.to_str()) == Some(extension) {
+            let modified = entry.metadata().and_then(|m| m.modified());
+            if modified.map(|t| t >= since).unwrap_or(false) {
+                out.push(path);
+            }
+        }
+    }
+    out
+}
+
+/// 收集修改时间不早于 since 的会话文件，返回 (应用类型, 路径)
+pub fn recent_session_files(since: std::time::SystemTime) -> Vec<(String, PathBuf)> {
+    crate::app_adapter::all_adapters()
+        .into_iter()
+        .flat_map(|adapter| {
+            let app = adapter.app_type().as_str().to_string();
+            adapter
+                .recent_session_files(since)
+                .into_iter()
+                .map(move |p| (app.clone(), p))
+        })
+        .collect()
+}
+
+/// 从会话文件开头读取工作目录（Claude 消息的 cwd 字段；Codex session_meta / turn_context 的 payload.cwd）
+pub fn read_session_cwd(file_path: &Path) -> Option<String> {
+    use std::io::BufRead;
+
+    let file = fs::File::open(file_path).ok()?;
+    std::io::BufReader::new(file)
+        .lines()
+        .take(50)
+        .map_while(Result::ok)
+        .filter_map(|line| serde_json::from_str::<serde_json::Value>(&line).ok())
+        .find_map(|value| {
+            value
+                .get("cwd")
+                .or_else(|| value.get("payload").and_then(|p| p.get("cwd")))
+                .and_then(|v| v.as_str())
+                .filter(|s| !s.is_empty())
+                .map(|s| s.to_string())
+        })
+}
+
+// ==================== 批量导出 ====================
+
+/// 批量导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum ConversationExportFormat {
+    /// 原始 JSONL
+    #[default]
+    Jsonl,
+    /// 阅读模式渲染的 Markdown
+    Markdown,
+    /// 阅读模式消息的 JSON 数组
+    Json,
+    /// OpenAI Chat Completions 消息数组 `[{role, content}]`
+    Openai,
+    /// Anthropic Messages API 消息数组（以 user 开头、角色严格交替）
+    Anthropic,
+}
+
+/// 转换为 API 消息数组：只保留 user / assistant 的文本块，去除工具调用、工具结果与 thinking；
+/// strict 时合并相邻的同角色消息并丢弃开头的 assistant 消息
+fn api_messages(messages: &[ReadingMessage], strict: bool) -> Vec<serde_json::Value> {
+    let mut turns: Vec<(&str, String)> = Vec::new();
+    for message in messages {
+        let role = match message.role.as_str() {
+            "user" => "user",
+            "assistant" => "assistant",
+            _ => continue,
+        };
+        let text = message
+            .blocks
+            .iter()
+            .filter_map(|block| match block {
+                ReadingBlock::Text { text } if !text.trim().is_empty() => Some(text.trim()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n\n");
+        if text.is_empty() {
+            continue;
+        }
+        match turns.last_mut() {
+            Some((last_role, content)) if strict && *last_role == role => {
+                content.push_str("\n\n");
+                content.push_str(&text);
+            }
+            None if strict && role == "assistant" => {}
+            _ => turns.push((role, text)),
+        }
+    }
+    turns
+        .into_iter()
+        .map(|(role, content)| serde_json::json!({ "role": role, "content": content }))
+        .collect()
+}
+
+/// 按格式将阅读模式消息序列化为 JSON
+fn messages_json(
+    messages: &[ReadingMessage],
+    format: ConversationExportFormat,
+) -> Result<String, String> {
+    let result = match format {
+        ConversationExportFormat::Openai => {
+            serde_json::to_string_pretty(&api_messages(messages, false))
+        }
+        ConversationExportFormat::Anthropic => {
+            serde_json::to_string_pretty(&api_messages(messages, true))
+        }
+        _ => serde_json::to_string_pretty(messages),
+    };
+    result.map_err(|e| format!("序列化消息失败: {}", e))
+}
+
+/// 取第一条用户消息的首行作为标题
+pub(crate) fn conversation_title(messages: &[ReadingMessage]) -> Option<String> {
+    messages
+        .iter()
+        .filter(|m| m.role == "user")
+        .flat_map(|m| m.blocks.iter())
+        .find_map(|block| match block {
+            ReadingBlock::Text { text } => text
+                .lines()
+                .map(|l| l.trim())
+                .find(|l| !l.is_empty())
+                .map(|l| l.chars().take(60).collect()),
+            _ => None,
+        })
+}
+
+/// 备注渲染为引用块
+fn push_note_markdown(out: &mut String, note: &str) {
+    out.push_str("> **Note:** ");
+    out.push_str(&note.replace('\n', "\n> "));
+    out.push_str("\n\n");
+}
+
+/// 渲染为 Markdown；note 为对话整体的备注，消息自带的备注（ReadingMessage::note）附在消息末尾
+pub(crate) fn render_conversation_markdown(
+    meta: &ConversationMeta,
+    title: Option<&str>,
+    note: Option<&str>,
+    messages: &[ReadingMessage],
+) -> String {
+    let mut out = format!("# {}\n\n", title.unwrap_or(&meta.id));
+    out.push_str(&format!("- app: {}\n- id: {}\n", meta.app_type, meta.id));
+    if let Some(project) = &meta.project_name {
+        out.push_str(&format!("- project: {}\n", project));
+    }
+    out.push('\n');
+    if let Some(note) = note {
+        push_note_markdown(&mut out, note);
     }
 
-    fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))
+    for message in messages {
+        out.push_str(&format!("## {}", message.role));
+        if let Some(ts) = &message.timestamp {
+            out.push_str(&format!(" · {}", ts));
+        }
+        out.push_str("\n\n");
+        for block in &message.blocks {
+            match block {
+                ReadingBlock::Text { text } => out.push_str(text),
+                ReadingBlock::Thinking { text } => {
+                    out.push_str("> ");
+                    out.push_str(&text.replace('\n', "\n> "));
+                }
+                ReadingBlock::ToolUse { name, input, .. } => {
+                    out.push_str(&format!("**{}**\n\n```json\n{}\n```", name, input))
+                }
+                ReadingBlock::ToolResult { content, .. } => {
+                    out.push_str(&format!("```\n{}\n```", content))
+                }
+                ReadingBlock::ToolSummary { name, summary, .. } => {
+                    out.push_str(&format!("**{}** {}", name, summary))
+                }
+            }
+            out.push_str("\n\n");
+        }
+        if let Some(note) = &message.note {
+            push_note_markdown(&mut out, note);
+        }
+    }
+    out
 }
 
+/// 读取用于导出的阅读模式消息与对话备注；不包含备注时清除消息上的备注
+fn export_reading_messages(
+    file_path: &str,
+    include_notes: bool,
+) -> Result<(Vec<ReadingMessage>, Option<String>), String> {
+    let mut messages = read_conversation_messages(
+        file_path,
+        &ReadingOptions {
+            collapse_tools: true,
+            ..Default::default()
+        },
+    )?;
+    if !include_notes {
+        for message in messages.iter_mut() {
+            message.note = None;
+        }
+        return Ok((messages, None));
+    }
+    let note = crate::conversation_notes::get_notes(file_path).note;
+    Ok((messages, note))
+}
+
+/// 批量导出对话到目录：文件名按设置中的模板生成，同名文件按冲突策略处理；
+/// include_notes 时 Markdown / JSON 导出附带备注
+pub fn export_conversations(
+    file_paths: &[String],
+    dest_dir: &str,
+    format: ConversationExportFormat,
+    include_notes: bool,
+) -> Result<crate::export_naming::ExportOutcome, String> {
+    use crate::export_naming::{
+        export_naming_settings, render_export_name, reserve_export_path, ExportNameContext,
+        ExportOutcome,
+    };
+
+    let dest = Path::new(dest_dir);
+    let (template, policy) = export_naming_settings();
+    let all = list_conversations(None)?;
+
+    let mut outcome = ExportOutcome::default();
+    for file_path in file_paths {
+        let meta = all
+            .iter()
+            .find(|m| &m.file_path == file_path)
+            .ok_or_else(|| format!("对话不存在: {}", file_path))?;
+        let (messages, note) = export_reading_messages(file_path, include_notes)?;
+        let title = conversation_title(&messages);
+        // Codex 会话没有项目目录，取其工作目录的末级目录名
+        let project = meta.project_name.clone().or_else(|| {
+            read_session_cwd(Path::new(file_path)).and_then(|cwd| {
+                Path::new(&cwd)
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+            })
+        });
+        let ctx = ExportNameContext {
+            project,
+            date: meta.created_at.or(Some(meta.modified_at)),
+            title: title.clone(),
+            id: meta.id.clone(),
+            app: meta.app_type.clone(),
+        };
+
+        let relative = render_export_name(&template, &ctx);
+        let extension = match format {
+            ConversationExportFormat::Jsonl => "jsonl",
+            ConversationExportFormat::Markdown => "md",
+            ConversationExportFormat::Json
+            | ConversationExportFormat::Openai
+            | ConversationExportFormat::Anthropic => "json",
+        };
+        let Some(target) = reserve_export_path(dest, &relative, extension, policy)? else {
+            outcome.skipped.push(file_path.clone());
+            continue;
+        };
+
+        let content = match format {
+            ConversationExportFormat::Jsonl => read_conversation_content(file_path)?,
+            ConversationExportFormat::Markdown => {
+                render_conversation_markdown(meta, title.as_deref(), note.as_deref(), &messages)
+            }
+            _ => messages_json(&messages, format)?,
+        };
+        crate::config::write_text_file(&target, &content)?;
+        outcome.written.push(target.to_string_lossy().to_string());
+    }
+    Ok(outcome)
+}
+
+/// 导出时引用的消息：条目序号（与阅读模式的 index 一致）或消息的 uuid / id
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(untagged)]
+pub enum MessageRef {
+    Index(usize),
+    Uuid(String),
+}
+
+/// 条目自身的 uuid / id（Claude 为 uuid，Gemini 为 id，Codex 新格式在 payload.id）
+fn item_id(value: &serde_json::Value) -> Option<&str> {
+    value
+        .get("uuid")
+        .or_else(|| value.get("id"))
+        .or_else(|| value.get("payload").and_then(|p| p.get("id")))
+        .and_then(|v| v.as_str())
+}
+
+/// 只导出对话中选中的消息（按会话中的顺序），返回导出内容；指定 dest_path 时同时写入文件
+pub fn export_messages(
+    file_path: &str,
+    refs: &[MessageRef],
+    format: ConversationExportFormat,
+    dest_path: Option<&str>,
+    include_notes: bool,
+) -> Result<String, String> {
+    if refs.is_empty() {
+        return Err("请至少选择一条消息".to_string());
+    }
+    let content = read_conversation_content(file_path)?;
+    let adapter = adapter_for_conversation(Path::new(file_path));
+    let items = adapter.conversation_items(&content)?;
+
+    let mut selected = std::collections::BTreeSet::new();
+    for message_ref in refs {
+        let index = match message_ref {
+            MessageRef::Index(index) => Some(*index).filter(|i| *i < items.len()),
+            MessageRef::Uuid(uuid) => items.iter().position(|v| item_id(v) == Some(uuid)),
+        };
+        let index = index.ok_or_else(|| match message_ref {
+            MessageRef::Index(index) => format!("消息不存在: #{}", index),
+            MessageRef::Uuid(uuid) => format!("消息不存在: {}", uuid),
+        })?;
+        selected.insert(index);
+    }
+
+    let output = match format {
+        ConversationExportFormat::Jsonl => selected
+            .iter()
+            .map(|&i| serde_json::to_string(&items[i]))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| format!("序列化消息失败: {}", e))?
+            .join("\n"),
+        _ => {
+            let (messages, note) = export_reading_messages(file_path, include_notes)?;
+            let title = conversation_title(&messages);
+            let chosen: Vec<ReadingMessage> = messages
+                .into_iter()
+                .filter(|m| selected.contains(&m.index))
+                .collect();
+            if chosen.is_empty() {
+                return Err("选中的条目中没有可导出的消息内容".to_string());
+            }
+            if format == ConversationExportFormat::Markdown {
+                let meta = find_conversation(file_path)?;
+                render_conversation_markdown(&meta, title.as_deref(), note.as_deref(), &chosen)
+            } else {
+                messages_json(&chosen, format)?
+            }
+        }
+    };
+
+    if let Some(dest) = dest_path {
+        let target = crate::export_naming::reserve_export_file(Path::new(dest))?;
+        crate::config::write_text_file(&target, &output)?;
+    }
+    Ok(output)
+}
+
+/// 按 id（文件名）、sessionId 或文件路径查找对话；id 支持唯一前缀
+pub fn find_conversation(key: &str) -> Result<ConversationMeta, String> {
+    let key = key.trim();
+    if key.is_empty() {
+        return Err("对话 id 不能为空".to_string());
+    }
+    let all = list_conversations(None)?;
+
+    if let Some(meta) = all
+        .iter()
+        .find(|m| m.id == key || m.session_id.as_deref() == Some(key) || m.file_path == key)
+    {
+        return Ok(meta.clone());
+    }
+    let matches: Vec<&ConversationMeta> = all
+        .iter()
+        .filter(|m| {
+            m.id.starts_with(key) || m.session_id.as_deref().is_some_and(|s| s.starts_with(key))
+        })
+        .collect();
+    match matches.as_slice() {
+        [meta] => Ok((*meta).clone()),
+        [] => Err(format!("对话不存在: {}", key)),
+        _ => Err(format!(
+            "id 前缀 {} 匹配到 {} 个对话，请提供更长的 id",
+            key,
+            matches.len()
+        )),
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+use crate::conversation::{
+    read_conversation_messages, truncate_chars, MessageRef, ReadingBlock, ReadingOptions,
+};
+
+/// 书签摘录的最大字符数
+const EXCERPT_MAX_CHARS: usize = 160;
+
+/// 消息书签存储文件 ~/.cc-switch/conversation_bookmarks.json
+fn get_bookmarks_path() -> PathBuf {
+    get_app_config_dir().join("conversation_bookmarks.json")
+}
+
+/// 对话中某条消息的书签
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MessageBookmark {
+    pub id: String,
+    pub file_path: String,
+    /// 消息在会话中的条目序号（与阅读模式的 index 一致）
+    pub index: usize,
+    /// 消息的 uuid / id；会话格式没有 id 时为空，只能按序号定位
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub uuid: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub role: String,
+    /// 消息开头的文本，便于在列表中辨认
+    pub excerpt: String,
+    /// 毫秒时间戳
+    pub created_at: i64,
+}
+
+/// 书签列表项：附带对话文件是否仍然存在
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookmarkEntry {
+    #[serde(flatten)]
+    pub bookmark: MessageBookmark,
+    pub exists: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct BookmarkStore {
+    #[serde(default)]
+    bookmarks: Vec<MessageBookmark>,
+}
+
+impl BookmarkStore {
+    fn load() -> Self {
+        let path = get_bookmarks_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取消息书签失败，将使用空书签: {}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(&get_bookmarks_path(), self).map_err(Into::into)
+    }
+}
+
+fn excerpt(blocks: &[ReadingBlock]) -> String {
+    let text = blocks
+        .iter()
+        .find_map(|block| match block {
+            ReadingBlock::Text { text } => Some(text.as_str()),
+            ReadingBlock::ToolSummary { summary, .. } => Some(summary.as_str()),
+            _ => None,
+        })
+        .unwrap_or("");
+    truncate_chars(
+        &text.split_whitespace().collect::<Vec<_>>().join(" "),
+        EXCERPT_MAX_CHARS,
+    )
+}
+
+/// 为消息添加书签；同一条消息已有书签时只更新标签
+pub fn add_bookmark(
+    file_path: &str,
+    message: &MessageRef,
+    label: Option<String>,
+) -> Result<MessageBookmark, String> {
+    let messages = read_conversation_messages(
+        file_path,
+        &ReadingOptions {
+            collapse_tools: true,
+            ..Default::default()
+        },
+    )?;
+    let target = messages
+        .iter()
+        .find(|m| match message {
+            MessageRef::Index(index) => m.index == *index,
+            MessageRef::Uuid(uuid) => m.uuid.as_deref() == Some(uuid.as_str()),
+        })
+        .ok_or_else(|| match message {
+            MessageRef::Index(index) => format!("消息不存在: #{}", index),
+            MessageRef::Uuid(uuid) => format!("消息不存在: {}", uuid),
+        })?;
+    let label = label
+        .map(|l| l.trim().to_string())
+        .filter(|l| !l.is_empty());
+
+    let mut store = BookmarkStore::load();
+    if let Some(existing) = store
+        .bookmarks
+        .iter_mut()
+        .find(|b| b.file_path == file_path && b.index == target.index)
+    {
+        existing.label = label;
+        let result = existing.clone();
+        store.save()?;
+        return Ok(result);
+    }
+
+    let mut created_at = chrono::Utc::now().timestamp_millis();
+    while store
+        .bookmarks
+        .iter()
+        .any(|b| b.id == format!("bm-{}", created_at))
+    {
+        created_at += 1;
+    }
+    let bookmark = MessageBookmark {
+        id: format!("bm-{}", created_at),
+        file_path: file_path.to_string(),
+        index: target.index,
+        uuid: target.uuid.clone(),
+        label,
+        role: target.role.clone(),
+        excerpt: excerpt(&target.blocks),
+        created_at,
+    };
+    store.bookmarks.push(bookmark.clone());
+    store.save()?;
+    Ok(bookmark)
+}
+
+/// 删除书签，返回是否存在
+pub fn remove_bookmark(id: &str) -> Result<bool, String> {
+    let mut store = BookmarkStore::load();
+    let before = store.bookmarks.len();
+    store.bookmarks.retain(|b| b.id != id);
+    if store.bookmarks.len() == before {
+        return Ok(false);
+    }
+    store.save()?;
+    Ok(true)
+}
+
+/// 列出书签（最新在前）；指定 file_path 时只列出该对话的书签
+pub fn list_bookmarks(file_path: Option<&str>) -> Vec<BookmarkEntry> {
+    let mut entries: Vec<BookmarkEntry> = BookmarkStore::load()
+        .bookmarks
+        .into_iter()
+        .filter(|b| file_path.is_none_or(|p| b.file_path == p))
+        .map(|bookmark| BookmarkEntry {
+            exists: Path::new(&bookmark.file_path).exists(),
+            bookmark,
+        })
+        .collect();
+    entries.sort_by_key(|e| std::cmp::Reverse(e.bookmark.created_at));
+    entries
+}
@@ -0,0 +1,581 @@
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+
+use crate::app_config::AppType;
+use crate::conversation::{ReadingBlock, ReadingMessage};
+use crate::provider_validation::ValidationSeverity;
+
+/// 裁剪方式
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "mode", rename_all = "camelCase")]
+pub enum TrimSpec {
+    /// 只保留最后 count 条用户 / 助手消息
+    KeepLast { count: usize },
+    /// 删除条目序号 start..=end（与阅读模式的 index 一致）
+    RemoveRange { start: usize, end: usize },
+}
+
+/// 生成的新会话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DerivedConversation {
+    pub file_path: String,
+    pub session_id: String,
+    /// 新会话中的条目数（不含 Codex 会话元数据）
+    pub message_count: usize,
+}
+
+/// 已解析的会话：原始条目与对应的阅读模式消息（非对话条目为 None）
+struct Transcript {
+    app_type: AppType,
+    path: PathBuf,
+    items: Vec<Value>,
+    messages: Vec<Option<ReadingMessage>>,
+}
+
+impl Transcript {
+    fn role(&self, index: usize) -> Option<&str> {
+        self.messages[index].as_ref().map(|m| m.role.as_str())
+    }
+
+    /// 用户 / 助手消息所在的条目序号
+    fn dialog_indexes(&self) -> Vec<usize> {
+        (0..self.items.len())
+            .filter(|&i| matches!(self.role(i), Some("user") | Some("assistant")))
+            .collect()
+    }
+}
+
+fn new_uuid() -> String {
+    let mut bytes = [0u8; 16];
+    OsRng.fill_bytes(&mut bytes);
+    // RFC 4122 v4
+    bytes[6] = (bytes[6] & 0x0f) | 0x40;
+    bytes[8] = (bytes[8] & 0x3f) | 0x80;
+    let hex: String = bytes.iter().map(|b| format!("{:02x}", b)).collect();
+    format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    )
+}
+
+fn is_codex_session_meta(item: &Value) -> bool {
+    item.get("type").and_then(|t| t.as_str()) == Some("session_meta")
+}
+
+fn jsonl_adapter(path: &Path) -> Result<&'static dyn crate::app_adapter::AppAdapter, String> {
+    let adapter = crate::conversation::adapter_for_conversation(path);
+    if !matches!(adapter.app_type(), AppType::Claude | AppType::Codex)
+        || path.extension().and_then(|e| e.to_str()) != Some("jsonl")
+    {
+        return Err("仅支持 Claude / Codex 的 JSONL 会话".to_string());
+    }
+    Ok(adapter)
+}
+
+fn load(file_path: &str) -> Result<Transcript, String> {
+    let path = Path::new(file_path);
+    let adapter = jsonl_adapter(path)?;
+    let app_type = adapter.app_type();
+    let content = crate::conversation::read_conversation_content(file_path)?;
+    let items = adapter.conversation_items(&content)?;
+    let messages = items
+        .iter()
+        .enumerate()
+        .map(|(index, value)| {
+            // Claude 子代理的侧链消息不属于主对话
+            if value.get("isSidechain").and_then(|v| v.as_bool()) == Some(true) {
+                return None;
+            }
+            crate::conversation::reading_message(adapter, index, value)
+                .filter(|m| !m.blocks.is_empty())
+        })
+        .collect();
+    Ok(Transcript {
+        app_type,
+        path: path.to_path_buf(),
+        items,
+        messages,
+    })
+}
+
+/// 按 keep 选出条目：去掉工具调用已被移除的工具结果；
+/// Codex 保留会话元数据与范围内的上下文条目，Claude 的摘要、快照等非对话条目引用旧 uuid，一律丢弃
+fn select(transcript: &Transcript, keep: &[bool]) -> Vec<Value> {
+    let kept_tool_ids: HashSet<&str> = transcript
+        .messages
+        .iter()
+        .zip(keep)
+        .filter(|(_, keep)| **keep)
+        .filter_map(|(m, _)| m.as_ref())
+        .flat_map(|m| m.blocks.iter())
+        .filter_map(|b| match b {
+            ReadingBlock::ToolUse { id, .. } => Some(id.as_str()),
+            _ => None,
+        })
+        .collect();
+
+    let mut out = Vec::new();
+    for (index, item) in transcript.items.iter().enumerate() {
+        if item.is_null() {
+            continue;
+        }
+        let Some(message) = &transcript.messages[index] else {
+            if matches!(transcript.app_type, AppType::Codex)
+                && (keep[index] || is_codex_session_meta(item))
+            {
+                out.push(item.clone());
+            }
+            continue;
+        };
+        if !keep[index] {
+            continue;
+        }
+        let orphaned = message.role == "tool"
+            && message.blocks.iter().all(|b| match b {
+                ReadingBlock::ToolResult { tool_use_id, .. } => {
+                    !kept_tool_ids.contains(tool_use_id.as_str())
+                }
+                _ => false,
+            });
+        if !orphaned {
+            out.push(item.clone());
+        }
+    }
+    out
+}
+
+/// 为 Claude 条目重新生成 uuid，并按顺序串成 parentUuid 链
+fn relink_claude(items: &mut [Value], session_id: &str) {
+    let mut parent: Option<String> = None;
+    for item in items.iter_mut() {
+        let Some(obj) = item.as_object_mut() else {
+            continue;
+        };
+        let uuid = new_uuid();
+        obj.insert("uuid".to_string(), Value::String(uuid.clone()));
+        obj.insert(
+            "parentUuid".to_string(),
+            parent.map(Value::String).unwrap_or(Value::Null),
+        );
+        obj.insert(
+            "sessionId".to_string(),
+            Value::String(session_id.to_string()),
+        );
+        obj.remove("logicalParentUuid");
+        parent = Some(uuid);
+    }
+}
+
+/// 新会话文件的路径：与原会话同目录，Claude 以会话 ID 命名，Codex 沿用 rollout-<时间>-<ID> 格式
+fn derived_path(app_type: &AppType, source: &Path, session_id: &str) -> Result<PathBuf, String> {
+    let dir = source
+        .parent()
+        .ok_or_else(|| "无法确定会话所在目录".to_string())?;
+    let name = match app_type {
+        AppType::Codex => format!(
+            "rollout-{}-{}.jsonl",
+            chrono::Local::now().format("%Y-%m-%dT%H-%M-%S"),
+            session_id
+        ),
+        _ => format!("{}.jsonl", session_id),
+    };
+    Ok(dir.join(name))
+}
+
+fn set_codex_session_id(items: &mut [Value], session_id: &str) {
+    for item in items.iter_mut().filter(|i| is_codex_session_meta(i)) {
+        if let Some(payload) = item.get_mut("payload").and_then(|p| p.as_object_mut()) {
+            payload.insert("id".to_string(), Value::String(session_id.to_string()));
+        }
+    }
+}
+
+/// 以新的会话 ID 写出条目，原会话文件保持不变
+fn write_derived(
+    transcript: &Transcript,
+    mut items: Vec<Value>,
+) -> Result<DerivedConversation, String> {
+    let session_id = new_uuid();
+    match transcript.app_type {
+        AppType::Codex => set_codex_session_id(&mut items, &session_id),
+        _ => relink_claude(&mut items, &session_id),
+    }
+    write_session(&transcript.app_type, &transcript.path, session_id, &items)
+}
+
+/// 写出新的会话文件（条目中的会话 ID 已由调用方更新）
+fn write_session(
+    app_type: &AppType,
+    source: &Path,
+    session_id: String,
+    items: &[Value],
+) -> Result<DerivedConversation, String> {
+    let lines = items
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| format!("序列化会话失败: {}", e))?;
+    let path = derived_path(app_type, source, &session_id)?;
+    crate::config::write_text_file(&path, &format!("{}\n", lines.join("\n")))?;
+
+    let message_count = items
+        .iter()
+        .filter(|item| !is_codex_session_meta(item))
+        .count();
+    Ok(DerivedConversation {
+        file_path: path.to_string_lossy().to_string(),
+        session_id,
+        message_count,
+    })
+}
+
+/// 裁剪会话到新文件：保留最后若干条消息或删除选中的范围，原会话保持不变。
+/// 保留最后 N 条时从其之前最近的一条用户输入开始，保证新会话以用户提问开头
+pub fn trim(file_path: &str, spec: &TrimSpec) -> Result<DerivedConversation, String> {
+    let transcript = load(file_path)?;
+    let len = transcript.items.len();
+    let keep: Vec<bool> = match *spec {
+        TrimSpec::KeepLast { count } => {
+            if count == 0 {
+                return Err("至少需要保留 1 条消息".to_string());
+            }
+            let dialog = transcript.dialog_indexes();
+            if count >= dialog.len() {
+                return Err(format!("会话只有 {} 条消息，无需裁剪", dialog.len()));
+            }
+            let cut = dialog[dialog.len() - count];
+            let cut = dialog
+                .iter()
+                .rev()
+                .find(|&&i| i <= cut && transcript.role(i) == Some("user"))
+                .copied()
+                .unwrap_or(cut);
+            (0..len).map(|i| i >= cut).collect()
+        }
+        TrimSpec::RemoveRange { start, end } => {
+            if start > end || end >= len {
+                return Err(format!("删除范围无效: {}..{}（共 {} 条）", start, end, len));
+            }
+            (0..len).map(|i| i < start || i > end).collect()
+        }
+    };
+
+    let items = select(&transcript, &keep);
+    if !items.iter().any(|item| !is_codex_session_meta(item)) {
+        return Err("裁剪后没有剩余消息".to_string());
+    }
+    write_derived(&transcript, items)
+}
+
+/// 合并方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum MergeStrategy {
+    /// 第一个会话在前，第二个会话接在其后
+    #[default]
+    Concat,
+    /// 按时间戳交错排列
+    Interleave,
+}
+
+fn timestamp_millis(item: &Value) -> Option<i64> {
+    let ts = item.get("timestamp")?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|t| t.timestamp_millis())
+}
+
+/// 按时间戳归并两个各自有序的条目序列；缺少时间戳时保持第一个序列优先
+fn interleave(first: Vec<Value>, second: Vec<Value>) -> Vec<Value> {
+    let mut out = Vec::with_capacity(first.len() + second.len());
+    let mut first = first.into_iter().peekable();
+    let mut second = second.into_iter().peekable();
+    loop {
+        let take_second = match (first.peek(), second.peek()) {
+            (Some(a), Some(b)) => matches!(
+                (timestamp_millis(a), timestamp_millis(b)),
+                (Some(ta), Some(tb)) if tb < ta
+            ),
+            (None, Some(_)) => true,
+            (_, None) => false,
+        };
+        let next = if take_second {
+            second.next()
+        } else {
+            first.next()
+        };
+        match next {
+            Some(item) => out.push(item),
+            None => break,
+        }
+    }
+    out
+}
+
+/// 合并两个同一应用的会话到新文件（存放在第一个会话所在目录），原会话保持不变。
+/// Claude 会话重新生成 uuid / parentUuid 链；Codex 会话沿用第一个会话的元数据
+pub fn merge(
+    first_path: &str,
+    second_path: &str,
+    strategy: MergeStrategy,
+) -> Result<DerivedConversation, String> {
+    if first_path == second_path {
+        return Err("不能将会话与自身合并".to_string());
+    }
+    let first = load(first_path)?;
+    let second = load(second_path)?;
+    if first.app_type.as_str() != second.app_type.as_str() {
+        return Err("只能合并同一应用的会话".to_string());
+    }
+
+    let mut first_items = select(&first, &vec![true; first.items.len()]);
+    let second_items: Vec<Value> = select(&second, &vec![true; second.items.len()])
+        .into_iter()
+        .filter(|item| !is_codex_session_meta(item))
+        .collect();
+    // Codex 的会话元数据必须位于首行，不参与排序
+    let mut items: Vec<Value> = first_items
+        .iter()
+        .position(is_codex_session_meta)
+        .map(|i| vec![first_items.remove(i)])
+        .unwrap_or_default();
+    match strategy {
+        MergeStrategy::Concat => {
+            items.extend(first_items);
+            items.extend(second_items);
+        }
+        MergeStrategy::Interleave => items.extend(interleave(first_items, second_items)),
+    }
+    write_derived(&first, items)
+}
+
+/// 会话完整性问题
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    /// 行号（从 1 开始）
+    pub line: usize,
+    pub severity: ValidationSeverity,
+    /// 机器可读的问题代码
+    pub code: String,
+    pub message: String,
+}
+
+/// 会话完整性检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityReport {
+    pub file_path: String,
+    /// 非空行数
+    pub total_lines: usize,
+    /// 可解析为 JSON 对象的行数
+    pub valid_lines: usize,
+    pub issues: Vec<IntegrityIssue>,
+    /// 修复后的副本（仅在请求修复且存在 error 级别问题时生成）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub repaired: Option<DerivedConversation>,
+}
+
+fn issue(
+    line: usize,
+    severity: ValidationSeverity,
+    code: &str,
+    message: impl Into<String>,
+) -> IntegrityIssue {
+    IntegrityIssue {
+        line,
+        severity,
+        code: code.to_string(),
+        message: message.into(),
+    }
+}
+
+/// 检查 Claude 的 uuid / parentUuid 链：消息缺少 uuid、uuid 重复、parentUuid 指向此前未出现的条目
+fn check_claude_chain(lines: &[(usize, Value)], issues: &mut Vec<IntegrityIssue>) {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    for (line, value) in lines {
+        let uuid = value.get("uuid").and_then(|v| v.as_str());
+        if is_claude_message(value) && uuid.is_none() {
+            issues.push(issue(
+                *line,
+                ValidationSeverity::Error,
+                "missingUuid",
+                "消息缺少 uuid",
+            ));
+        }
+        if let Some(parent) = value.get("parentUuid").and_then(|v| v.as_str()) {
+            if !seen.contains_key(parent) {
+                issues.push(issue(
+                    *line,
+                    ValidationSeverity::Error,
+                    "danglingParent",
+                    format!("parentUuid {} 未在此前的行中出现", parent),
+                ));
+            }
+        }
+        if let Some(uuid) = uuid {
+            if let Some(first) = seen.insert(uuid, *line) {
+                issues.push(issue(
+                    *line,
+                    ValidationSeverity::Error,
+                    "duplicateUuid",
+                    format!("uuid 与第 {} 行重复", first),
+                ));
+            }
+        }
+    }
+}
+
+/// 时间戳应单调不减
+fn check_timestamps(lines: &[(usize, Value)], issues: &mut Vec<IntegrityIssue>) {
+    let mut latest: Option<(i64, usize)> = None;
+    for (line, value) in lines {
+        let Some(ts) = timestamp_millis(value) else {
+            continue;
+        };
+        match latest {
+            Some((prev, prev_line)) if ts < prev => issues.push(issue(
+                *line,
+                ValidationSeverity::Warning,
+                "timestampOrder",
+                format!("时间戳早于第 {} 行", prev_line),
+            )),
+            _ => latest = Some((ts, *line)),
+        }
+    }
+}
+
+fn is_claude_message(value: &Value) -> bool {
+    matches!(
+        value.get("type").and_then(|t| t.as_str()),
+        Some("user") | Some("assistant")
+    )
+}
+
+/// 修复 Claude 条目：去掉重复的 uuid，为缺失的消息补上，悬空的 parentUuid 改为指向上一条保留的条目；
+/// 摘要、快照等不在链上的条目原样保留
+fn repair_claude(lines: Vec<Value>, session_id: &str) -> Vec<Value> {
+    let mut seen = HashSet::new();
+    let mut previous: Option<String> = None;
+    let mut out = Vec::new();
+    for mut value in lines {
+        let uuid = match value.get("uuid").and_then(|v| v.as_str()) {
+            Some(uuid) if !seen.insert(uuid.to_string()) => continue,
+            Some(uuid) => uuid.to_string(),
+            None if !is_claude_message(&value) => {
+                out.push(value);
+                continue;
+            }
+            None => {
+                let uuid = new_uuid();
+                seen.insert(uuid.clone());
+                uuid
+            }
+        };
+        let parent_ok = match value.get("parentUuid") {
+            Some(Value::String(parent)) => seen.contains(parent) && *parent != uuid,
+            _ => true,
+        };
+        if let Some(obj) = value.as_object_mut() {
+            obj.insert("uuid".to_string(), Value::String(uuid.clone()));
+            if !parent_ok {
+                obj.insert(
+                    "parentUuid".to_string(),
+                    previous.clone().map(Value::String).unwrap_or(Value::Null),
+                );
+            }
+            obj.insert(
+                "sessionId".to_string(),
+                Value::String(session_id.to_string()),
+            );
+        }
+        previous = Some(uuid);
+        out.push(value);
+    }
+    out
+}
+
+/// 检查会话文件：每行能否解析、Claude 的 parentUuid 链是否一致、时间戳是否单调；
+/// repair 为 true 且存在 error 级别问题时，去掉损坏的行并修正链接，写出修复后的副本（原文件不变）
+pub fn verify(file_path: &str, repair: bool) -> Result<IntegrityReport, String> {
+    let path = Path::new(file_path);
+    let app_type = jsonl_adapter(path)?.app_type();
+    let content = crate::conversation::read_conversation_content(file_path)?;
+
+    let mut issues = Vec::new();
+    let mut total_lines = 0;
+    let mut lines: Vec<(usize, Value)> = Vec::new();
+    for (index, raw) in content.lines().enumerate() {
+        if raw.trim().is_empty() {
+            continue;
+        }
+        total_lines += 1;
+        match serde_json::from_str::<Value>(raw) {
+            Ok(value) if value.is_object() => lines.push((index + 1, value)),
+            Ok(_) => issues.push(issue(
+                index + 1,
+                ValidationSeverity::Error,
+                "notObject",
+                "该行不是 JSON 对象",
+            )),
+            Err(e) => issues.push(issue(
+                index + 1,
+                ValidationSeverity::Error,
+                "parseError",
+                format!("无法解析为 JSON: {}", e),
+            )),
+        }
+    }
+
+    match app_type {
+        AppType::Codex => {
+            if !lines.first().is_some_and(|(_, v)| is_codex_session_meta(v)) {
+                issues.push(issue(
+                    lines.first().map(|(line, _)| *line).unwrap_or(1),
+                    ValidationSeverity::Warning,
+                    "missingSessionMeta",
+                    "首行不是 session_meta，Codex 可能无法恢复该会话",
+                ));
+            }
+        }
+        _ => check_claude_chain(&lines, &mut issues),
+    }
+    check_timestamps(&lines, &mut issues);
+    issues.sort_by_key(|i| i.line);
+
+    let valid_lines = lines.len();
+    let has_errors = issues
+        .iter()
+        .any(|i| i.severity == ValidationSeverity::Error);
+    let repaired = if repair && has_errors {
+        if lines.is_empty() {
+            return Err("会话中没有可保留的行，无法修复".to_string());
+        }
+        let session_id = new_uuid();
+        let mut items: Vec<Value> = lines.into_iter().map(|(_, v)| v).collect();
+        match app_type {
+            AppType::Codex => set_codex_session_id(&mut items, &session_id),
+            _ => items = repair_claude(items, &session_id),
+        }
+        Some(write_session(&app_type, path, session_id, &items)?)
+    } else {
+        None
+    };
+
+    Ok(IntegrityReport {
+        file_path: file_path.to_string(),
+        total_lines,
+        valid_lines,
+        issues,
+        repaired,
+    })
+}
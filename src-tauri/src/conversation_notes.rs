@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+
+/// 对话备注存储文件 ~/.cc-switch/conversation_notes.json
+fn get_notes_path() -> PathBuf {
+    get_app_config_dir().join("conversation_notes.json")
+}
+
+/// 单个对话的备注
+#[derive(Debug, Clone, Serialize, Deserialize, Default, PartialEq)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationNotes {
+    /// 对话整体的备注
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub note: Option<String>,
+    /// 消息备注：以消息 uuid / id 为键
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub messages: HashMap<String, String>,
+    /// 毫秒时间戳
+    #[serde(default)]
+    pub updated_at: i64,
+}
+
+impl ConversationNotes {
+    fn is_empty(&self) -> bool {
+        self.note.is_none() && self.messages.is_empty()
+    }
+}
+
+/// 对话备注存储
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct NoteStore {
+    /// 以对话文件路径为键
+    #[serde(default)]
+    pub notes: HashMap<String, ConversationNotes>,
+}
+
+impl NoteStore {
+    pub fn load() -> Self {
+        let path = get_notes_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取对话备注失败，将使用空备注: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        write_json_file(&get_notes_path(), self).map_err(Into::into)
+    }
+}
+
+/// 读取对话的备注；没有备注时返回空结构
+pub fn get_notes(file_path: &str) -> ConversationNotes {
+    NoteStore::load()
+        .notes
+        .remove(file_path)
+        .unwrap_or_default()
+}
+
+/// 空白备注视为删除
+fn normalize(note: Option<String>) -> Option<String> {
+    note.map(|n| n.trim().to_string()).filter(|n| !n.is_empty())
+}
+
+/// 修改备注：message_id 为空时设置对话整体的备注，否则设置该消息的备注；
+/// note 为空时删除。返回修改后的备注
+pub fn set_note(
+    file_path: &str,
+    message_id: Option<&str>,
+    note: Option<String>,
+) -> Result<ConversationNotes, String> {
+    let note = normalize(note);
+    let mut store = NoteStore::load();
+    let entry = store.notes.entry(file_path.to_string()).or_default();
+    let before = entry.clone();
+
+    match message_id {
+        None => entry.note = note,
+        Some(id) => match note {
+            Some(note) => {
+                entry.messages.insert(id.to_string(), note);
+            }
+            None => {
+                entry.messages.remove(id);
+            }
+        },
+    }
+
+    if *entry == before {
+        return Ok(before);
+    }
+    entry.updated_at = chrono::Utc::now().timestamp_millis();
+    let result = entry.clone();
+    store.notes.retain(|_, notes| !notes.is_empty());
+    store.save()?;
+    Ok(result)
+}
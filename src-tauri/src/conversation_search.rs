@@ -0,0 +1,422 @@
+use regex::{Regex, RegexBuilder};
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+use std::time::{Duration, Instant};
+
+use crate::conversation::{ConversationMeta, ReadingBlock, ReadingMessage, RoleFilter};
+
+/// 每个对话默认返回的匹配片段数
+const DEFAULT_MAX_MATCHES: usize = 3;
+
+/// 片段中匹配位置前后保留的字符数
+const EXCERPT_CONTEXT: usize = 40;
+
+/// 时间衰减的半衰期（天）
+const RECENCY_HALF_LIFE_DAYS: f64 = 30.0;
+
+/// 元数据（id、项目、会话 ID、标签）命中时的加分
+const META_MATCH_BONUS: f64 = 2.0;
+
+/// 正则表达式长度上限
+const MAX_PATTERN_LEN: usize = 512;
+
+/// 编译后正则的内存上限，拒绝重复嵌套等会膨胀的表达式
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// 单次搜索的时间上限
+const SEARCH_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// 搜索选项
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchOptions {
+    /// 将关键词整体作为正则表达式（不解析引号与 AND / OR）
+    #[serde(default)]
+    pub regex: bool,
+    /// 区分大小写（默认不区分）
+    #[serde(default)]
+    pub case_sensitive: bool,
+    /// 全词匹配：关键词两侧不能紧接字母、数字或下划线
+    #[serde(default)]
+    pub whole_word: bool,
+    /// 只搜索用户输入或助手输出；指定角色时不再匹配 id、项目等元数据
+    #[serde(default)]
+    pub role: RoleFilter,
+    /// 每个对话返回的匹配片段数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_matches: Option<usize>,
+}
+
+/// 解析后的查询：OR 连接的若干组，组内关键词需全部命中
+struct SearchQuery {
+    terms: Vec<Regex>,
+    /// 每组包含的关键词下标
+    groups: Vec<Vec<usize>>,
+}
+
+impl SearchQuery {
+    /// 是否有一组关键词全部命中
+    fn satisfied(&self, hit: impl Fn(usize) -> bool) -> bool {
+        self.groups
+            .iter()
+            .any(|group| group.iter().all(|&t| hit(t)))
+    }
+}
+
+fn is_word_char(c: char) -> bool {
+    c.is_alphanumeric() || c == '_'
+}
+
+/// 编译关键词；literal 为原始关键词（非正则模式），用于判断全词匹配时两侧是否需要词边界
+fn build_regex(
+    pattern: &str,
+    literal: Option<&str>,
+    options: &SearchOptions,
+) -> Result<Regex, String> {
+    let pattern = if options.whole_word {
+        // 以符号开头或结尾的关键词（如 `-v`）在该侧不加 \b，否则永远无法匹配
+        let (left, right) = match literal {
+            Some(text) => (
+                text.chars().next().is_some_and(is_word_char),
+                text.chars().last().is_some_and(is_word_char),
+            ),
+            None => (true, true),
+        };
+        format!(
+            "{}(?:{}){}",
+            if left { r"\b" } else { "" },
+            pattern,
+            if right { r"\b" } else { "" }
+        )
+    } else {
+        pattern.to_string()
+    };
+    RegexBuilder::new(&pattern)
+        .case_insensitive(!options.case_sensitive)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .map_err(|e| format!("无效的正则表达式: {}", e))
+}
+
+/// 拆分查询：双引号包裹的短语为一个关键词，其余按空白分隔
+fn tokenize(input: &str) -> Result<Vec<(String, bool)>, String> {
+    let mut tokens = Vec::new();
+    let mut chars = input.chars().peekable();
+    while let Some(&c) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+        } else if c == '"' {
+            chars.next();
+            let mut phrase = String::new();
+            loop {
+                match chars.next() {
+                    Some('"') => break,
+                    Some(ch) => phrase.push(ch),
+                    None => return Err("搜索语句中的引号未闭合".to_string()),
+                }
+            }
+            tokens.push((phrase, true));
+        } else {
+            let mut word = String::new();
+            while let Some(&ch) = chars.peek() {
+                if ch.is_whitespace() || ch == '"' {
+                    break;
+                }
+                word.push(ch);
+                chars.next();
+            }
+            tokens.push((word, false));
+        }
+    }
+    Ok(tokens)
+}
+
+/// 解析查询语言：空格或 AND 表示同时包含，OR 表示任一组命中，"..." 为短语；
+/// 正则模式下整个输入作为一个表达式
+fn parse_query(input: &str, options: &SearchOptions) -> Result<SearchQuery, String> {
+    if options.regex {
+        if input.chars().count() > MAX_PATTERN_LEN {
+            return Err(format!("正则表达式过长（上限 {} 个字符）", MAX_PATTERN_LEN));
+        }
+        return Ok(SearchQuery {
+            terms: vec![build_regex(input, None, options)?],
+            groups: vec![vec![0]],
+        });
+    }
+
+    let mut terms = Vec::new();
+    let mut groups = vec![Vec::new()];
+    for (token, quoted) in tokenize(input)? {
+        if !quoted && token == "OR" {
+            if groups.last().is_some_and(|g| g.is_empty()) {
+                return Err("OR 两侧都需要关键词".to_string());
+            }
+            groups.push(Vec::new());
+            continue;
+        }
+        if (!quoted && token == "AND") || token.is_empty() {
+            continue;
+        }
+        if let Some(group) = groups.last_mut() {
+            group.push(terms.len());
+        }
+        terms.push(build_regex(&regex::escape(&token), Some(&token), options)?);
+    }
+    if terms.is_empty() {
+        return Err("搜索条件中没有关键词".to_string());
+    }
+    if groups.iter().any(|g| g.is_empty()) {
+        return Err("OR 两侧都需要关键词".to_string());
+    }
+    Ok(SearchQuery { terms, groups })
+}
+
+/// 对话内容中的一处匹配
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchMatch {
+    /// 消息在会话文件中的条目序号（与阅读模式的 index 一致）
+    pub index: usize,
+    pub role: String,
+    /// 匹配位置附近的片段（换行替换为空格）
+    pub excerpt: String,
+    /// 高亮范围在片段中的偏移（UTF-16 码元，可直接用于 JS 字符串截取）
+    pub highlight_start: usize,
+    pub highlight_end: usize,
+}
+
+/// 搜索结果：对话元数据 + 相关度与匹配片段
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SearchHit {
+    #[serde(flatten)]
+    pub meta: ConversationMeta,
+    pub score: f64,
+    /// 内容中的匹配总数
+    pub match_count: usize,
+    /// 前若干处匹配
+    pub matches: Vec<SearchMatch>,
+}
+
+impl SearchHit {
+    fn plain(meta: ConversationMeta) -> Self {
+        Self {
+            meta,
+            score: 0.0,
+            match_count: 0,
+            matches: Vec::new(),
+        }
+    }
+}
+
+fn utf16_len(text: &str) -> usize {
+    text.encode_utf16().count()
+}
+
+/// 截取匹配位置前后的片段，返回片段与高亮范围
+fn excerpt(text: &str, start: usize, end: usize) -> (String, usize, usize) {
+    let before: Vec<(usize, char)> = text[..start].char_indices().collect();
+    let from = before
+        .len()
+        .checked_sub(EXCERPT_CONTEXT)
+        .map(|i| before[i].0)
+        .unwrap_or(0);
+    let to = text[end..]
+        .char_indices()
+        .nth(EXCERPT_CONTEXT)
+        .map(|(i, _)| end + i)
+        .unwrap_or(text.len());
+
+    let mut out = String::new();
+    if from > 0 {
+        out.push('…');
+    }
+    out.push_str(&text[from..start]);
+    let highlight_start = utf16_len(&out);
+    out.push_str(&text[start..end]);
+    let highlight_end = utf16_len(&out);
+    out.push_str(&text[end..to]);
+    if to < text.len() {
+        out.push('…');
+    }
+    // 换行与空格等长，不影响偏移
+    (
+        out.replace(['\n', '\r', '\t'], " "),
+        highlight_start,
+        highlight_end,
+    )
+}
+
+/// 在对话内容中查找匹配：按关键词分别计数，保留前 max_matches 处片段
+fn content_matches(
+    file_path: &str,
+    terms: &[Regex],
+    role: RoleFilter,
+    max_matches: usize,
+) -> Result<(Vec<usize>, Vec<SearchMatch>), String> {
+    let content = crate::conversation::read_conversation_content(file_path)?;
+    let adapter = crate::conversation::adapter_for_conversation(Path::new(file_path));
+    let items = adapter.conversation_items(&content)?;
+
+    let mut counts = vec![0; terms.len()];
+    let mut matches = Vec::new();
+    for (index, value) in items.iter().enumerate() {
+        let Some(message) = crate::conversation::reading_message(adapter, index, value) else {
+            continue;
+        };
+        let wanted = match role {
+            RoleFilter::All => message.role == "user" || message.role == "assistant",
+            RoleFilter::User => message.role == "user" && !is_injected_context(value, &message),
+            RoleFilter::Assistant => message.role == "assistant",
+        };
+        if !wanted {
+            continue;
+        }
+        let text = message
+            .blocks
+            .iter()
+            .filter_map(|b| match b {
+                ReadingBlock::Text { text } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n");
+        let mut found: Vec<(usize, usize)> = Vec::new();
+        for (term, pattern) in terms.iter().enumerate() {
+            for m in pattern.find_iter(&text).filter(|m| !m.is_empty()) {
+                counts[term] += 1;
+                found.push((m.start(), m.end()));
+            }
+        }
+        // 同一消息内按出现位置取片段
+        found.sort_unstable();
+        for (start, end) in found {
+            if matches.len() >= max_matches {
+                break;
+            }
+            let (excerpt, highlight_start, highlight_end) = excerpt(&text, start, end);
+            matches.push(SearchMatch {
+                index: message.index,
+                role: message.role.clone(),
+                excerpt,
+                highlight_start,
+                highlight_end,
+            });
+        }
+    }
+    Ok((counts, matches))
+}
+
+/// 以 user 身份写入、但并非用户输入的条目：Claude 的 isMeta 消息与命令输出，
+/// Codex 注入的环境信息与 AGENTS.md 指令
+fn is_injected_context(value: &serde_json::Value, message: &ReadingMessage) -> bool {
+    if value.get("isMeta").and_then(|v| v.as_bool()) == Some(true) {
+        return true;
+    }
+    const INJECTED_PREFIXES: [&str; 5] = [
+        "<environment_context>",
+        "<user_instructions>",
+        "<local-command-stdout>",
+        "<command-name>",
+        "# AGENTS.md instructions",
+    ];
+    message.blocks.iter().any(|b| match b {
+        ReadingBlock::Text { text } => INJECTED_PREFIXES
+            .iter()
+            .any(|p| text.trim_start().starts_with(p)),
+        _ => false,
+    })
+}
+
+fn meta_matches(meta: &ConversationMeta, pattern: &Regex) -> bool {
+    pattern.is_match(&meta.id)
+        || meta
+            .project_name
+            .as_deref()
+            .is_some_and(|p| pattern.is_match(p))
+        || meta
+            .session_id
+            .as_deref()
+            .is_some_and(|s| pattern.is_match(s))
+        || meta.tags.iter().any(|t| pattern.is_match(t))
+}
+
+/// 相关度：匹配次数取对数（避免长对话独占前列），再按最近修改时间衰减
+fn score(meta_hit: bool, count: usize, modified_at: i64) -> f64 {
+    let relevance = (1.0 + count as f64).ln() + if meta_hit { META_MATCH_BONUS } else { 0.0 };
+    let age_days = (chrono::Utc::now().timestamp() - modified_at).max(0) as f64 / 86_400.0;
+    let recency = 0.5_f64.powf(age_days / RECENCY_HALF_LIFE_DAYS);
+    relevance * (1.0 + recency)
+}
+
+/// 搜索对话：关键词同时匹配元数据与消息正文，按相关度排序并返回匹配片段；
+/// 空关键词返回全部（按修改时间），`tag:xxx` 按标签精确过滤
+pub fn search(
+    app_type: Option<&str>,
+    keyword: &str,
+    options: &SearchOptions,
+) -> Result<Vec<SearchHit>, String> {
+    let all_conversations = crate::conversation::list_conversations(app_type)?;
+
+    let keyword = keyword.trim();
+    if keyword.is_empty() {
+        return Ok(all_conversations
+            .into_iter()
+            .map(SearchHit::plain)
+            .collect());
+    }
+
+    if let Some(tag) = keyword.strip_prefix("tag:") {
+        let tag = tag.trim();
+        return Ok(all_conversations
+            .into_iter()
+            .filter(|conv| conv.tags.iter().any(|t| t == tag))
+            .map(SearchHit::plain)
+            .collect());
+    }
+
+    let query = parse_query(keyword, options)?;
+    let max_matches = options.max_matches.unwrap_or(DEFAULT_MAX_MATCHES);
+    let started = Instant::now();
+
+    let mut hits = Vec::new();
+    for meta in all_conversations {
+        // regex 引擎保证线性时间，但对话很多时整体仍可能很慢
+        if started.elapsed() > SEARCH_TIMEOUT {
+            return Err(format!(
+                "搜索超时（超过 {} 秒），请缩小应用范围或简化搜索条件",
+                SEARCH_TIMEOUT.as_secs()
+            ));
+        }
+        let meta_hits: Vec<bool> = query
+            .terms
+            .iter()
+            .map(|t| options.role == RoleFilter::All && meta_matches(&meta, t))
+            .collect();
+        let (counts, matches) =
+            match content_matches(&meta.file_path, &query.terms, options.role, max_matches) {
+                Ok(found) => found,
+                Err(e) => {
+                    log::warn!("搜索对话内容失败 {}: {}", meta.file_path, e);
+                    (vec![0; query.terms.len()], Vec::new())
+                }
+            };
+        if !query.satisfied(|t| meta_hits[t] || counts[t] > 0) {
+            continue;
+        }
+        let count = counts.iter().sum();
+        hits.push(SearchHit {
+            score: score(meta_hits.contains(&true), count, meta.modified_at),
+            meta,
+            match_count: count,
+            matches,
+        });
+    }
+    hits.sort_by(|a, b| {
+        b.score
+            .total_cmp(&a.score)
+            .then(b.meta.modified_at.cmp(&a.meta.modified_at))
+    });
+    Ok(hits)
+}
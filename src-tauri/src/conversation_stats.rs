@@ -0,0 +1,548 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{HashMap, HashSet};
+use std::path::Path;
+
+use crate::conversation::ConversationMeta;
+
+/// Token 用量合计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TokenTotals {
+    pub input: u64,
+    pub output: u64,
+    /// 写入缓存的输入（Claude）
+    pub cache_creation: u64,
+    /// 命中缓存的输入
+    pub cache_read: u64,
+    pub total: u64,
+    /// 按内置价格表估算的费用（美元）；价格未知的模型不计入
+    #[serde(default)]
+    pub cost_usd: f64,
+}
+
+impl TokenTotals {
+    pub fn add(&mut self, other: &TokenTotals) {
+        self.input += other.input;
+        self.output += other.output;
+        self.cache_creation += other.cache_creation;
+        self.cache_read += other.cache_read;
+        self.total += other.total;
+        self.cost_usd += other.cost_usd;
+    }
+
+    /// 按模型价格估算费用；input_includes_cache 为 true 时 input 已包含命中缓存的部分（Codex / Gemini）
+    fn priced(mut self, model: Option<&str>, input_includes_cache: bool) -> Self {
+        if let Some([input, output, cache_write, cache_read]) = model.and_then(model_price) {
+            let uncached = if input_includes_cache {
+                self.input.saturating_sub(self.cache_read)
+            } else {
+                self.input
+            };
+            self.cost_usd = (uncached as f64 * input
+                + self.output as f64 * output
+                + self.cache_creation as f64 * cache_write
+                + self.cache_read as f64 * cache_read)
+                / 1_000_000.0;
+        }
+        self
+    }
+}
+
+/// 每百万 token 的美元价格（输入、输出、缓存写入、缓存读取），按模型名前缀匹配，
+/// 更具体的前缀在前；仅用于估算
+const MODEL_PRICES: &[(&str, [f64; 4])] = &[
+    ("claude-opus-4-5", [5.0, 25.0, 6.25, 0.5]),
+    ("claude-opus-4", [15.0, 75.0, 18.75, 1.5]),
+    ("claude-3-opus", [15.0, 75.0, 18.75, 1.5]),
+    ("claude-sonnet-4", [3.0, 15.0, 3.75, 0.3]),
+    ("claude-3-7-sonnet", [3.0, 15.0, 3.75, 0.3]),
+    ("claude-3-5-sonnet", [3.0, 15.0, 3.75, 0.3]),
+    ("claude-haiku-4", [1.0, 5.0, 1.25, 0.1]),
+    ("claude-3-5-haiku", [0.8, 4.0, 1.0, 0.08]),
+    ("gpt-5-mini", [0.25, 2.0, 0.0, 0.025]),
+    ("gpt-5-nano", [0.05, 0.4, 0.0, 0.005]),
+    ("gpt-5", [1.25, 10.0, 0.0, 0.125]),
+    ("gpt-4.1-mini", [0.4, 1.6, 0.0, 0.1]),
+    ("gpt-4.1", [2.0, 8.0, 0.0, 0.5]),
+    ("o4-mini", [1.1, 4.4, 0.0, 0.275]),
+    ("o3", [2.0, 8.0, 0.0, 0.5]),
+    ("gemini-2.5-pro", [1.25, 10.0, 0.0, 0.31]),
+    ("gemini-2.5-flash-lite", [0.1, 0.4, 0.0, 0.025]),
+    ("gemini-2.5-flash", [0.3, 2.5, 0.0, 0.075]),
+];
+
+/// 查找模型价格；忽略大小写与 `anthropic/` 等供应商前缀
+fn model_price(model: &str) -> Option<[f64; 4]> {
+    let model = model.to_ascii_lowercase();
+    let name = model.rsplit('/').next().unwrap_or(&model);
+    MODEL_PRICES
+        .iter()
+        .find(|(prefix, _)| name.starts_with(prefix))
+        .map(|(_, price)| *price)
+}
+
+/// 项目概览统计
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectStats {
+    pub project: String,
+    pub sessions: usize,
+    /// 用户与助手消息数（不含工具输出与元数据条目）
+    pub messages: usize,
+    pub tokens: TokenTotals,
+    /// 最早 / 最近活动时间（秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub first_activity: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_activity: Option<i64>,
+    /// 平均会话时长（秒，首条到末条消息）
+    pub avg_session_duration: i64,
+    pub avg_messages_per_session: f64,
+}
+
+/// 单个会话的统计
+#[derive(Default)]
+struct SessionStats {
+    messages: usize,
+    tokens: TokenTotals,
+    first: Option<i64>,
+    last: Option<i64>,
+}
+
+fn u64_at(value: &Value, key: &str) -> u64 {
+    value.get(key).and_then(|v| v.as_u64()).unwrap_or(0)
+}
+
+fn timestamp_secs(value: &Value) -> Option<i64> {
+    let ts = value.get("timestamp")?.as_str()?;
+    chrono::DateTime::parse_from_rfc3339(ts)
+        .ok()
+        .map(|t| t.timestamp())
+}
+
+/// Claude：assistant 条目的 message.usage；同一消息拆成多行时 usage 重复，按 message.id 去重
+fn claude_usage(value: &Value, seen: &mut HashSet<String>) -> Option<TokenTotals> {
+    let message = value.get("message")?;
+    let usage = message.get("usage")?;
+    if let Some(id) = message.get("id").and_then(|v| v.as_str()) {
+        if !seen.insert(id.to_string()) {
+            return None;
+        }
+    }
+    let mut tokens = TokenTotals {
+        input: u64_at(usage, "input_tokens"),
+        output: u64_at(usage, "output_tokens"),
+        cache_creation: u64_at(usage, "cache_creation_input_tokens"),
+        cache_read: u64_at(usage, "cache_read_input_tokens"),
+        ..Default::default()
+    };
+    tokens.total = tokens.input + tokens.output + tokens.cache_creation + tokens.cache_read;
+    let model = message.get("model").and_then(|v| v.as_str());
+    Some(tokens.priced(model, false))
+}
+
+/// Codex：token_count 事件中的 total_token_usage 为会话累计值，取最后一次
+fn codex_usage(value: &Value) -> Option<TokenTotals> {
+    let payload = value.get("payload")?;
+    if payload.get("type").and_then(|v| v.as_str()) != Some("token_count") {
+        return None;
+    }
+    let usage = payload.get("info")?.get("total_token_usage")?;
+    let input = u64_at(usage, "input_tokens");
+    let output = u64_at(usage, "output_tokens");
+    Some(TokenTotals {
+        input,
+        output,
+        cache_creation: 0,
+        cache_read: u64_at(usage, "cached_input_tokens"),
+        total: usage
+            .get("total_tokens")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(input + output),
+        cost_usd: 0.0,
+    })
+}
+
+/// Codex：turn_context 事件中的当前模型
+fn codex_model(value: &Value) -> Option<&str> {
+    if value.get("type").and_then(|v| v.as_str()) != Some("turn_context") {
+        return None;
+    }
+    value.get("payload")?.get("model")?.as_str()
+}
+
+/// Gemini：gemini 消息上的 tokens 字段（input 含命中缓存的部分）
+fn gemini_usage(value: &Value) -> Option<TokenTotals> {
+    if value.get("type").and_then(|v| v.as_str()) != Some("gemini") {
+        return None;
+    }
+    let usage = value.get("tokens")?;
+    let input = u64_at(usage, "input");
+    let output = u64_at(usage, "output") + u64_at(usage, "thoughts");
+    let tokens = TokenTotals {
+        input,
+        output,
+        cache_creation: 0,
+        cache_read: u64_at(usage, "cached"),
+        total: usage
+            .get("total")
+            .and_then(|v| v.as_u64())
+            .unwrap_or(input + output),
+        cost_usd: 0.0,
+    };
+    let model = value.get("model").and_then(|v| v.as_str());
+    Some(tokens.priced(model, true))
+}
+
+fn session_stats(file_path: &str) -> Result<SessionStats, String> {
+    let content = crate::conversation::read_conversation_content(file_path)?;
+    let adapter = crate::conversation::adapter_for_conversation(Path::new(file_path));
+    let items = adapter.conversation_items(&content)?;
+
+    let mut stats = SessionStats::default();
+    let mut seen_messages = HashSet::new();
+    let mut codex_total = None;
+    let mut codex_model_name = None;
+    for (index, value) in items.iter().enumerate() {
+        if let Some(ts) = timestamp_secs(value) {
+            stats.first = Some(stats.first.map_or(ts, |f| f.min(ts)));
+            stats.last = Some(stats.last.map_or(ts, |l| l.max(ts)));
+        }
+        if let Some(message) = crate::conversation::reading_message(adapter, index, value) {
+            // 仅含工具结果的条目已被归为 tool
+            if !message.blocks.is_empty() && (message.role == "user" || message.role == "assistant")
+            {
+                stats.messages += 1;
+            }
+        }
+        if let Some(tokens) = claude_usage(value, &mut seen_messages) {
+            stats.tokens.add(&tokens);
+        }
+        if let Some(tokens) = codex_usage(value) {
+            codex_total = Some(tokens);
+        }
+        if let Some(model) = codex_model(value) {
+            codex_model_name = Some(model);
+        }
+        if let Some(tokens) = gemini_usage(value) {
+            stats.tokens.add(&tokens);
+        }
+    }
+    if let Some(tokens) = codex_total {
+        stats.tokens.add(&tokens.priced(codex_model_name, true));
+    }
+    Ok(stats)
+}
+
+/// 单个会话文件的 token 用量与估算费用
+pub fn session_tokens(file_path: &str) -> Result<TokenTotals, String> {
+    Ok(session_stats(file_path)?.tokens)
+}
+
+fn normalize_dir(path: &str) -> String {
+    let trimmed = path.trim_end_matches(['/', '\\']);
+    Path::new(trimmed)
+        .canonicalize()
+        .map(|p| p.to_string_lossy().to_string())
+        .unwrap_or_else(|_| trimmed.to_string())
+}
+
+/// 会话是否属于该项目：Claude 按项目目录名或工作目录匹配，Codex 按工作目录匹配
+fn belongs_to(meta: &ConversationMeta, project: &str, project_dir: &str) -> bool {
+    if meta.app_type == "claude" && meta.project_name.as_deref() == Some(project) {
+        return true;
+    }
+    crate::conversation::read_session_cwd(Path::new(&meta.file_path))
+        .is_some_and(|cwd| normalize_dir(&cwd) == project_dir)
+}
+
+/// 统计一个 Claude 项目（项目目录名或工作目录）或 Codex 工作目录下的全部会话
+pub fn project_stats(project: &str, app_type: Option<&str>) -> Result<ProjectStats, String> {
+    let project = project.trim();
+    if project.is_empty() {
+        return Err("项目不能为空".to_string());
+    }
+    let project_dir = normalize_dir(project);
+
+    let mut conversations = Vec::new();
+    if app_type.is_none_or(|a| a == "claude") {
+        conversations.extend(crate::conversation::list_claude_conversations()?);
+    }
+    if app_type.is_none_or(|a| a == "codex") {
+        conversations.extend(crate::conversation::list_codex_conversations()?);
+    }
+
+    let mut result = ProjectStats {
+        project: project.to_string(),
+        ..Default::default()
+    };
+    let mut total_duration = 0;
+    for meta in conversations
+        .iter()
+        .filter(|m| belongs_to(m, project, &project_dir))
+    {
+        let stats = match session_stats(&meta.file_path) {
+            Ok(stats) => stats,
+            Err(e) => {
+                log::warn!("统计会话失败 {}: {}", meta.file_path, e);
+                continue;
+            }
+        };
+        // 没有时间戳的会话以文件时间代替
+        let first = stats.first.or(meta.created_at).unwrap_or(meta.modified_at);
+        let last = stats.last.unwrap_or(meta.modified_at);
+
+        result.sessions += 1;
+        result.messages += stats.messages;
+        result.tokens.add(&stats.tokens);
+        result.first_activity = Some(result.first_activity.map_or(first, |f| f.min(first)));
+        result.last_activity = Some(result.last_activity.map_or(last, |l| l.max(last)));
+        total_duration += (last - first).max(0);
+    }
+
+    if result.sessions > 0 {
+        result.avg_session_duration = total_duration / result.sessions as i64;
+        result.avg_messages_per_session = result.messages as f64 / result.sessions as f64;
+    }
+    Ok(result)
+}
+
+/// 时间线事件的预览文字最大字符数
+const PREVIEW_MAX_CHARS: usize = 120;
+
+/// 时间线事件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum TimelineKind {
+    /// 用户输入（一轮对话的开始）
+    User,
+    /// 助手回合：两次用户输入之间连续的助手消息
+    Assistant,
+    /// 一次工具调用
+    Tool,
+}
+
+/// 会话时间线上的一个事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TimelineEvent {
+    pub kind: TimelineKind,
+    /// 事件开始处消息的条目序号（与阅读模式的 index 一致）
+    pub index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// 助手回合：首条到末条消息；工具调用：调用到返回结果（毫秒）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<i64>,
+    /// 工具名（仅工具调用）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tool: Option<String>,
+    /// 工具结果为错误
+    pub is_error: bool,
+    /// 消息开头的文本
+    pub preview: String,
+}
+
+/// 按工具名汇总的调用统计
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ToolUsage {
+    pub name: String,
+    pub count: usize,
+    pub errors: usize,
+    /// 有返回结果且带时间戳的调用的总耗时 / 平均耗时（毫秒）
+    pub total_duration_ms: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_duration_ms: Option<i64>,
+    #[serde(skip)]
+    timed: usize,
+}
+
+/// 会话概览：回合数、工具调用统计、时间线与总时长
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConversationSummary {
+    pub user_turns: usize,
+    pub assistant_turns: usize,
+    pub tool_calls: usize,
+    /// 按调用次数降序
+    pub tools: Vec<ToolUsage>,
+    pub timeline: Vec<TimelineEvent>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub started_at: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ended_at: Option<String>,
+    /// 首条到末条消息的时长（秒）
+    pub wall_clock_secs: i64,
+    pub tokens: TokenTotals,
+}
+
+fn timestamp_millis(ts: Option<&str>) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(ts?)
+        .ok()
+        .map(|t| t.timestamp_millis())
+}
+
+fn preview(text: &str) -> String {
+    crate::conversation::truncate_chars(
+        &text.split_whitespace().collect::<Vec<_>>().join(" "),
+        PREVIEW_MAX_CHARS,
+    )
+}
+
+/// 生成会话概览：用户输入开始新的一轮，其后连续的助手消息计为一个助手回合；
+/// 工具调用按 id 与其结果配对，以两者的时间戳计算耗时
+pub fn summarize_conversation(file_path: &str) -> Result<ConversationSummary, String> {
+    use crate::conversation::{read_conversation_messages, ReadingBlock, ReadingOptions};
+
+    let messages = read_conversation_messages(file_path, &ReadingOptions::default())?;
+    let mut summary = ConversationSummary {
+        tokens: session_stats(file_path)?.tokens,
+        ..Default::default()
+    };
+
+    let mut tools: HashMap<String, ToolUsage> = HashMap::new();
+    // 工具调用 id -> (时间线位置, 调用时间)
+    let mut pending: HashMap<String, (usize, Option<i64>)> = HashMap::new();
+    // 当前助手回合在时间线中的位置与开始时间
+    let mut assistant_turn: Option<(usize, Option<i64>)> = None;
+    let mut first: Option<(i64, &str)> = None;
+    let mut last: Option<(i64, &str)> = None;
+
+    for message in &messages {
+        let ts_str = message.timestamp.as_deref();
+        let ts = timestamp_millis(ts_str);
+        if let (Some(ts), Some(raw)) = (ts, ts_str) {
+            if first.is_none_or(|(f, _)| ts < f) {
+                first = Some((ts, raw));
+            }
+            if last.is_none_or(|(l, _)| ts > l) {
+                last = Some((ts, raw));
+            }
+        }
+        let text = message.blocks.iter().find_map(|block| match block {
+            ReadingBlock::Text { text } => Some(text.as_str()),
+            _ => None,
+        });
+
+        match message.role.as_str() {
+            "user" if text.is_some() => {
+                assistant_turn = None;
+                summary.user_turns += 1;
+                summary.timeline.push(TimelineEvent {
+                    kind: TimelineKind::User,
+                    index: message.index,
+                    timestamp: message.timestamp.clone(),
+                    duration_ms: None,
+                    tool: None,
+                    is_error: false,
+                    preview: preview(text.unwrap_or("")),
+                });
+            }
+            "assistant" => match assistant_turn {
+                Some((position, start)) => {
+                    let event = &mut summary.timeline[position];
+                    if let (Some(start), Some(ts)) = (start, ts) {
+                        event.duration_ms = Some((ts - start).max(0));
+                    }
+                    if event.preview.is_empty() {
+                        event.preview = preview(text.unwrap_or(""));
+                    }
+                }
+                None => {
+                    summary.assistant_turns += 1;
+                    assistant_turn = Some((summary.timeline.len(), ts));
+                    summary.timeline.push(TimelineEvent {
+                        kind: TimelineKind::Assistant,
+                        index: message.index,
+                        timestamp: message.timestamp.clone(),
+                        duration_ms: None,
+                        tool: None,
+                        is_error: false,
+                        preview: preview(text.unwrap_or("")),
+                    });
+                }
+            },
+            _ => {}
+        }
+
+        for block in &message.blocks {
+            match block {
+                ReadingBlock::ToolUse { id, name, .. } => {
+                    summary.tool_calls += 1;
+                    tools
+                        .entry(name.clone())
+                        .or_insert_with(|| ToolUsage {
+                            name: name.clone(),
+                            count: 0,
+                            errors: 0,
+                            total_duration_ms: 0,
+                            avg_duration_ms: None,
+                            timed: 0,
+                        })
+                        .count += 1;
+                    pending.insert(id.clone(), (summary.timeline.len(), ts));
+                    summary.timeline.push(TimelineEvent {
+                        kind: TimelineKind::Tool,
+                        index: message.index,
+                        timestamp: message.timestamp.clone(),
+                        duration_ms: None,
+                        tool: Some(name.clone()),
+                        is_error: false,
+                        preview: String::new(),
+                    });
+                }
+                ReadingBlock::ToolResult {
+                    tool_use_id,
+                    is_error,
+                    ..
+                } => {
+                    let Some((position, start)) = pending.remove(tool_use_id) else {
+                        continue;
+                    };
+                    let event = &mut summary.timeline[position];
+                    let duration = start.zip(ts).map(|(start, ts)| (ts - start).max(0));
+                    event.duration_ms = duration;
+                    event.is_error = *is_error;
+                    let Some(usage) = event.tool.as_ref().and_then(|n| tools.get_mut(n)) else {
+                        continue;
+                    };
+                    if *is_error {
+                        usage.errors += 1;
+                    }
+                    if let Some(duration) = duration {
+                        usage.total_duration_ms += duration;
+                        usage.timed += 1;
+                    }
+                }
+                _ => {}
+            }
+        }
+        // 工具结果之后助手回合仍在继续，时长延伸到最后一条工具输出
+        if message.role == "tool" {
+            if let (Some((position, Some(start))), Some(ts)) = (assistant_turn, ts) {
+                summary.timeline[position].duration_ms = Some((ts - start).max(0));
+            }
+        }
+    }
+
+    let mut tools: Vec<ToolUsage> = tools
+        .into_values()
+        .map(|mut usage| {
+            if usage.timed > 0 {
+                usage.avg_duration_ms = Some(usage.total_duration_ms / usage.timed as i64);
+            }
+            usage
+        })
+        .collect();
+    tools.sort_by(|a, b| b.count.cmp(&a.count).then_with(|| a.name.cmp(&b.name)));
+    summary.tools = tools;
+
+    if let (Some((start, started_at)), Some((end, ended_at))) = (first, last) {
+        summary.started_at = Some(started_at.to_string());
+        summary.ended_at = Some(ended_at.to_string());
+        summary.wall_clock_secs = (end - start) / 1000;
+    }
+    Ok(summary)
+}
@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+use crate::conversation::{
+    read_conversation_messages, truncate_chars, ReadingBlock, ReadingMessage, ReadingOptions,
+};
+use crate::provider::Provider;
+
+/// 发送给模型的开头 / 结尾消息数
+const EDGE_MESSAGES: usize = 4;
+/// 每条消息最多保留的字符数
+const MESSAGE_MAX_CHARS: usize = 1500;
+/// 生成的标题最大字符数
+const TITLE_MAX_CHARS: usize = 80;
+const MAX_OUTPUT_TOKENS: u32 = 400;
+/// 生成请求的超时（秒）
+const REQUEST_TIMEOUT_SECS: u64 = 30;
+/// 批量生成时默认处理的对话数上限
+const DEFAULT_BATCH_LIMIT: usize = 20;
+
+/// 生成的标题与摘要存储文件 ~/.cc-switch/conversation_summaries.json
+fn get_summaries_path() -> PathBuf {
+    get_app_config_dir().join("conversation_summaries.json")
+}
+
+/// 模型为对话生成的标题与摘要
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct GeneratedSummary {
+    pub title: String,
+    pub summary: String,
+    pub app_type: String,
+    pub provider_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 毫秒时间戳
+    pub generated_at: i64,
+}
+
+/// 批量生成中失败的对话
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryFailure {
+    pub file_path: String,
+    pub error: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SummaryBatchResult {
+    /// 以对话文件路径为键
+    pub generated: HashMap<String, GeneratedSummary>,
+    pub failed: Vec<SummaryFailure>,
+    /// 超出数量上限、留待下次处理的对话数
+    pub remaining: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+struct SummaryStore {
+    /// 以对话文件路径为键
+    #[serde(default)]
+    summaries: HashMap<String, GeneratedSummary>,
+}
+
+impl SummaryStore {
+    fn load() -> Self {
+        let path = get_summaries_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取对话摘要失败，将使用空摘要: {}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(&get_summaries_path(), self).map_err(Into::into)
+    }
+}
+
+/// 读取已生成的标题与摘要
+pub fn get_summary(file_path: &str) -> Option<GeneratedSummary> {
+    SummaryStore::load().summaries.remove(file_path)
+}
+
+/// 全部已生成的标题与摘要，以对话文件路径为键
+pub fn list_summaries() -> HashMap<String, GeneratedSummary> {
+    SummaryStore::load().summaries
+}
+
+/// 删除已生成的标题与摘要，返回是否存在
+pub fn delete_summary(file_path: &str) -> Result<bool, String> {
+    let mut store = SummaryStore::load();
+    if store.summaries.remove(file_path).is_none() {
+        return Ok(false);
+    }
+    store.save()?;
+    Ok(true)
+}
+
+fn message_text(message: &ReadingMessage) -> Option<String> {
+    let text = message
+        .blocks
+        .iter()
+        .filter_map(|block| match block {
+            ReadingBlock::Text { text } => Some(text.trim()),
+            _ => None,
+        })
+        .filter(|t| !t.is_empty())
+        .collect::<Vec<_>>()
+        .join("\n");
+    Some(text).filter(|t| !t.is_empty())
+}
+
+/// 只取开头与结尾的若干条用户 / 助手文本，避免发送整个会话
+fn build_prompt(file_path: &str) -> Result<String, String> {
+    let messages = read_conversation_messages(
+        file_path,
+        &ReadingOptions {
+            collapse_tools: true,
+            strip_thinking: true,
+            ..Default::default()
+        },
+    )?;
+    let texts: Vec<(&str, String)> = messages
+        .iter()
+        .filter(|m| m.role == "user" || m.role == "assistant")
+        .filter_map(|m| message_text(m).map(|text| (m.role.as_str(), text)))
+        .collect();
+    if texts.is_empty() {
+        return Err("对话中没有文本消息".to_string());
+    }
+
+    let omitted = texts.len().saturating_sub(EDGE_MESSAGES * 2);
+    let (head, tail) = if omitted == 0 {
+        (&texts[..], &texts[..0])
+    } else {
+        (
+            &texts[..EDGE_MESSAGES],
+            &texts[texts.len() - EDGE_MESSAGES..],
+        )
+    };
+    let render = |(role, text): &(&str, String)| {
+        format!(
+            "[{}]\n{}\n\n",
+            role,
+            truncate_chars(text, MESSAGE_MAX_CHARS)
+        )
+    };
+    let mut transcript: String = head.iter().map(render).collect();
+    if omitted > 0 {
+        transcript.push_str(&format!("[... 省略 {} 条消息 ...]\n\n", omitted));
+    }
+    transcript.extend(tail.iter().map(render));
+
+    Ok(format!(
+        "Below are the first and last messages of a conversation between a user and an AI coding assistant.\n\
+         Write a short title (at most 10 words) and a 2-3 sentence abstract describing what was worked on and the outcome.\n\
+         Use the same language as the conversation. Respond with JSON only: {{\"title\": \"...\", \"summary\": \"...\"}}\n\n\
+         {}",
+        transcript
+    ))
+}
+
+/// 解析模型输出：优先取其中的 JSON 对象，否则首行为标题、其余为摘要
+fn parse_output(output: &str) -> Option<(String, String)> {
+    let json = output
+        .find('{')
+        .zip(output.rfind('}'))
+        .filter(|(start, end)| start < end)
+        .and_then(|(start, end)| {
+            serde_json::from_str::<serde_json::Value>(&output[start..=end]).ok()
+        });
+    let (title, summary) = match json {
+        Some(value) => (
+            value.get("title")?.as_str()?.to_string(),
+            value
+                .get("summary")
+                .and_then(|v| v.as_str())
+                .unwrap_or("")
+                .to_string(),
+        ),
+        None => {
+            let mut lines = output.trim().lines();
+            let title = lines.next()?.to_string();
+            (title, lines.collect::<Vec<_>>().join("\n"))
+        }
+    };
+    let title = title
+        .trim()
+        .trim_start_matches('#')
+        .trim()
+        .trim_matches(|c| c == '"' || c == '*')
+        .trim()
+        .to_string();
+    if title.is_empty() {
+        return None;
+    }
+    Some((
+        truncate_chars(&title, TITLE_MAX_CHARS),
+        summary.trim().to_string(),
+    ))
+}
+
+async fn generate(
+    app_type: &AppType,
+    provider: &Provider,
+    file_path: &str,
+) -> Result<GeneratedSummary, String> {
+    let prompt = build_prompt(file_path)?;
+    let output = crate::provider_connection::generate_text(
+        app_type,
+        provider,
+        &prompt,
+        MAX_OUTPUT_TOKENS,
+        Some(REQUEST_TIMEOUT_SECS),
+    )
+    .await?;
+    let (title, summary) =
+        parse_output(&output).ok_or_else(|| "模型未返回可用的标题".to_string())?;
+    Ok(GeneratedSummary {
+        title,
+        summary,
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id.clone(),
+        model: provider.models(app_type).into_iter().next(),
+        generated_at: chrono::Utc::now().timestamp_millis(),
+    })
+}
+
+/// 使用供应商为对话生成标题与摘要并保存（覆盖已有结果）
+pub async fn summarize_conversation(
+    app_type: &AppType,
+    provider: &Provider,
+    file_path: &str,
+) -> Result<GeneratedSummary, String> {
+    if !Path::new(file_path).exists() {
+        return Err(format!("对话文件不存在: {}", file_path));
+    }
+    let generated = generate(app_type, provider, file_path).await?;
+    let mut store = SummaryStore::load();
+    store
+        .summaries
+        .insert(file_path.to_string(), generated.clone());
+    store.save()?;
+    Ok(generated)
+}
+
+/// 为尚未生成标题的对话批量生成（按最近修改排序，逐个请求），每处理一个即保存；
+/// 未指定 file_paths 时处理全部对话，单次最多 limit 个
+pub async fn summarize_untitled(
+    app_type: &AppType,
+    provider: &Provider,
+    file_paths: Option<Vec<String>>,
+    limit: Option<usize>,
+) -> Result<SummaryBatchResult, String> {
+    let store = SummaryStore::load();
+    let candidates: Vec<String> = match file_paths {
+        Some(paths) => paths,
+        None => {
+            let mut conversations = crate::conversation::list_conversations(None)?;
+            conversations.sort_by_key(|m| std::cmp::Reverse(m.modified_at));
+            conversations.into_iter().map(|m| m.file_path).collect()
+        }
+    };
+    let pending: Vec<String> = candidates
+        .into_iter()
+        .filter(|path| !store.summaries.contains_key(path))
+        .collect();
+    let limit = limit.unwrap_or(DEFAULT_BATCH_LIMIT).max(1);
+
+    let mut result = SummaryBatchResult {
+        remaining: pending.len().saturating_sub(limit),
+        ..Default::default()
+    };
+    for file_path in pending.into_iter().take(limit) {
+        match generate(app_type, provider, &file_path).await {
+            Ok(generated) => {
+                // 每次重新读取，避免覆盖期间其他操作写入的结果
+                let mut store = SummaryStore::load();
+                store.summaries.insert(file_path.clone(), generated.clone());
+                store.save()?;
+                result.generated.insert(file_path, generated);
+            }
+            Err(error) => {
+                log::warn!("生成对话摘要失败 {}: {}", file_path, error);
+                result.failed.push(SummaryFailure { file_path, error });
+            }
+        }
+    }
+    log::info!(
+        "已生成 {} 个对话摘要，失败 {} 个，剩余 {} 个",
+        result.generated.len(),
+        result.failed.len(),
+        result.remaining
+    );
+    Ok(result)
+}
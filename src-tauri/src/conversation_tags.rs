@@ -0,0 +1,206 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeSet, HashMap};
+use std::path::PathBuf;
+
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+use crate::conversation::ConversationMeta;
+
+/// 对话标签存储文件 ~/.cc-switch/conversation_tags.json
+fn get_tags_path() -> PathBuf {
+    get_app_config_dir().join("conversation_tags.json")
+}
+
+/// 智能标签的筛选条件（所有已设置的条件需同时满足）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartTagFilter {
+    /// 限定应用类型："claude" / "codex"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    /// 项目名包含（忽略大小写）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub project: Option<String>,
+    /// 对话内容包含（忽略大小写）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub keyword: Option<String>,
+    /// 最少消息数
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_messages: Option<usize>,
+    /// 最少 token 数（按文件大小估算）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub min_tokens: Option<u64>,
+    /// 是否包含工具错误
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub has_errors: Option<bool>,
+}
+
+/// 智能标签：由保存的筛选条件定义，在列出对话时自动打上
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SmartTag {
+    pub name: String,
+    pub filter: SmartTagFilter,
+}
+
+/// 对话标签存储
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct TagStore {
+    /// 手动标签：以对话文件路径为键
+    #[serde(default)]
+    pub tags: HashMap<String, Vec<String>>,
+    #[serde(default)]
+    pub smart_tags: Vec<SmartTag>,
+}
+
+impl TagStore {
+    pub fn load() -> Self {
+        let path = get_tags_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取对话标签失败，将使用空标签: {}", e);
+            Self::default()
+        })
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        write_json_file(&get_tags_path(), self).map_err(Into::into)
+    }
+
+    /// 计算对话的全部标签（手动标签 + 命中的智能标签），content 为对话原始内容
+    pub fn tags_for(&self, meta: &ConversationMeta, content: &str) -> Vec<String> {
+        let mut out: BTreeSet<String> = self
+            .tags
+            .get(&meta.file_path)
+            .map(|tags| tags.iter().cloned().collect())
+            .unwrap_or_default();
+        for smart in &self.smart_tags {
+            if matches_filter(&smart.filter, meta, content) {
+                out.insert(smart.name.clone());
+            }
+        }
+        out.into_iter().collect()
+    }
+}
+
+/// 按字节数粗略估算 token 数（约 4 字节/token）
+pub fn estimate_tokens_from_bytes(bytes: u64) -> u64 {
+    bytes.div_ceil(4)
+}
+
+/// 判断对话内容中是否出现工具错误
+fn content_has_errors(content: &str) -> bool {
+    content.contains("\"is_error\":true") || content.contains("\"type\":\"error\"")
+}
+
+fn matches_filter(filter: &SmartTagFilter, meta: &ConversationMeta, content: &str) -> bool {
+    if let Some(app_type) = filter.app_type.as_deref() {
+        if meta.app_type != app_type {
+            return false;
+        }
+    }
+    if let Some(project) = filter.project.as_deref() {
+        let project = project.to_lowercase();
+        let hit = meta
+            .project_name
+            .as_ref()
+            .map(|p| p.to_lowercase().contains(&project))
+            .unwrap_or(false);
+        if !hit {
+            return false;
+        }
+    }
+    if let Some(min) = filter.min_messages {
+        if meta.message_count < min {
+            return false;
+        }
+    }
+    if let Some(min) = filter.min_tokens {
+        if estimate_tokens_from_bytes(meta.file_size) < min {
+            return false;
+        }
+    }
+    if let Some(expected) = filter.has_errors {
+        if content_has_errors(content) != expected {
+            return false;
+        }
+    }
+    if let Some(keyword) = filter.keyword.as_deref() {
+        if !content.to_lowercase().contains(&keyword.to_lowercase()) {
+            return false;
+        }
+    }
+    true
+}
+
+/// 批量为对话添加/移除手动标签，返回发生变更的对话数量
+pub fn bulk_tag_conversations(
+    file_paths: &[String],
+    add: &[String],
+    remove: &[String],
+) -> Result<usize, String> {
+    let mut store = TagStore::load();
+    let mut changed = 0usize;
+
+    for path in file_paths {
+        let entry = store.tags.entry(path.clone()).or_default();
+        let before = entry.clone();
+
+        entry.retain(|t| !remove.contains(t));
+        for tag in add {
+            let tag = tag.trim();
+            if !tag.is_empty() && !entry.iter().any(|t| t == tag) {
+                entry.push(tag.to_string());
+            }
+        }
+
+        if *entry != before {
+            changed += 1;
+        }
+    }
+
+    store.tags.retain(|_, tags| !tags.is_empty());
+    if changed > 0 {
+        store.save()?;
+    }
+    Ok(changed)
+}
+
+/// 列出智能标签
+pub fn list_smart_tags() -> Vec<SmartTag> {
+    TagStore::load().smart_tags
+}
+
+/// 新增或更新智能标签（按名称匹配）
+pub fn upsert_smart_tag(tag: SmartTag) -> Result<(), String> {
+    let name = tag.name.trim();
+    if name.is_empty() {
+        return Err("智能标签名称不能为空".to_string());
+    }
+    let tag = SmartTag {
+        name: name.to_string(),
+        filter: tag.filter,
+    };
+
+    let mut store = TagStore::load();
+    if let Some(existing) = store.smart_tags.iter_mut().find(|t| t.name == tag.name) {
+        *existing = tag;
+    } else {
+        store.smart_tags.push(tag);
+    }
+    store.save()
+}
+
+/// 删除智能标签
+pub fn delete_smart_tag(name: &str) -> Result<bool, String> {
+    let mut store = TagStore::load();
+    let before = store.smart_tags.len();
+    store.smart_tags.retain(|t| t.name != name);
+    if store.smart_tags.len() == before {
+        return Ok(false);
+    }
+    store.save()?;
+    Ok(true)
+}
@@ -0,0 +1,216 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::{Read, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Emitter};
+
+use crate::app_config::AppType;
+use crate::conversation::ReadingMessage;
+
+/// 跟随中的会话追加了新消息
+pub const EVENT_CONVERSATION_TAIL: &str = "conversation-tail";
+
+/// 检查文件增长的间隔
+const POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// 正在跟随的会话文件 → 后台任务
+static TAILS: Mutex<Option<HashMap<String, tauri::async_runtime::JoinHandle<()>>>> =
+    Mutex::new(None);
+
+/// 开始跟随时的位置，前端先按阅读模式加载 start_line 之前的消息，再接收后续事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailStart {
+    pub file_path: String,
+    /// 已有的完整行数；之后的消息 index 从此开始
+    pub start_line: usize,
+}
+
+/// 新增消息事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TailEvent {
+    pub file_path: String,
+    pub messages: Vec<ReadingMessage>,
+    /// 文件已被删除或移动，跟随结束
+    pub ended: bool,
+}
+
+/// 跟随状态：读取位置、已读完整行数与未换行的残余内容
+struct TailCursor {
+    path: PathBuf,
+    offset: u64,
+    line: usize,
+    partial: Vec<u8>,
+}
+
+impl TailCursor {
+    /// 读取自上次位置以来新增的完整行
+    fn read_new_lines(&mut self) -> Result<Vec<String>, String> {
+        let mut file = std::fs::File::open(&self.path)
+            .map_err(|e| format!("打开会话文件失败: {}: {}", self.path.display(), e))?;
+        let len = file
+            .metadata()
+            .map_err(|e| format!("读取会话文件失败: {}", e))?
+            .len();
+        if len < self.offset {
+            // 文件被截断或重写：从头开始
+            self.offset = 0;
+            self.line = 0;
+            self.partial.clear();
+        }
+        if len == self.offset {
+            return Ok(Vec::new());
+        }
+        file.seek(SeekFrom::Start(self.offset))
+            .map_err(|e| format!("读取会话文件失败: {}", e))?;
+        let mut buf = Vec::new();
+        file.read_to_end(&mut buf)
+            .map_err(|e| format!("读取会话文件失败: {}", e))?;
+        self.offset += buf.len() as u64;
+        self.partial.extend_from_slice(&buf);
+
+        let Some(last_newline) = self.partial.iter().rposition(|b| *b == b'\n') else {
+            return Ok(Vec::new());
+        };
+        let complete: Vec<u8> = self.partial.drain(..=last_newline).collect();
+        Ok(String::from_utf8_lossy(&complete)
+            .lines()
+            .map(str::to_string)
+            .collect())
+    }
+}
+
+fn is_jsonl_session(path: &Path) -> bool {
+    let adapter = crate::conversation::adapter_for_conversation(path);
+    matches!(adapter.app_type(), AppType::Claude | AppType::Codex)
+        && path.extension().and_then(|e| e.to_str()) == Some("jsonl")
+}
+
+/// 统计文件中完整行的数量与对应的字节位置
+fn initial_cursor(path: &Path) -> Result<TailCursor, String> {
+    let content = std::fs::read(path).map_err(|e| format!("读取会话文件失败: {}", e))?;
+    let end = content
+        .iter()
+        .rposition(|b| *b == b'\n')
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let line = content[..end].iter().filter(|b| **b == b'\n').count();
+    Ok(TailCursor {
+        path: path.to_path_buf(),
+        offset: end as u64,
+        line,
+        partial: Vec::new(),
+    })
+}
+
+fn emit(app: &AppHandle, event: &TailEvent) {
+    if let Err(e) = app.emit(EVENT_CONVERSATION_TAIL, event) {
+        log::warn!("转发会话新消息失败: {}", e);
+    }
+}
+
+async fn follow(app: AppHandle, file_path: String, mut cursor: TailCursor) {
+    let adapter = crate::conversation::adapter_for_conversation(&cursor.path);
+    loop {
+        tokio::time::sleep(POLL_INTERVAL).await;
+        if !cursor.path.exists() {
+            emit(
+                &app,
+                &TailEvent {
+                    file_path: file_path.clone(),
+                    messages: Vec::new(),
+                    ended: true,
+                },
+            );
+            break;
+        }
+        let lines = match cursor.read_new_lines() {
+            Ok(lines) => lines,
+            Err(e) => {
+                log::warn!("{}", e);
+                continue;
+            }
+        };
+        if lines.is_empty() {
+            continue;
+        }
+        let mut messages = Vec::new();
+        for line in lines {
+            let index = cursor.line;
+            cursor.line += 1;
+            let value = serde_json::from_str(&line).unwrap_or_default();
+            if let Some(message) = crate::conversation::reading_message(adapter, index, &value) {
+                if !message.blocks.is_empty() {
+                    messages.push(message);
+                }
+            }
+        }
+        if !messages.is_empty() {
+            emit(
+                &app,
+                &TailEvent {
+                    file_path: file_path.clone(),
+                    messages,
+                    ended: false,
+                },
+            );
+        }
+    }
+    if let Ok(mut tails) = TAILS.lock() {
+        if let Some(tails) = tails.as_mut() {
+            tails.remove(&file_path);
+        }
+    }
+}
+
+/// 开始跟随会话文件（Claude / Codex 的 JSONL），新消息通过 conversation-tail 事件推送；
+/// 对同一文件重复调用会从当前末尾重新开始
+pub fn start(app: &AppHandle, file_path: &str) -> Result<TailStart, String> {
+    let path = Path::new(file_path);
+    if !path.is_file() {
+        return Err("文件不存在".to_string());
+    }
+    if !is_jsonl_session(path) {
+        return Err("仅支持跟随 Claude / Codex 的 JSONL 会话".to_string());
+    }
+    let cursor = initial_cursor(path)?;
+    let start_line = cursor.line;
+
+    let handle = tauri::async_runtime::spawn(follow(app.clone(), file_path.to_string(), cursor));
+    let mut tails = TAILS.lock().map_err(crate::error::lock_failed)?;
+    if let Some(previous) = tails
+        .get_or_insert_with(HashMap::new)
+        .insert(file_path.to_string(), handle)
+    {
+        previous.abort();
+    }
+    Ok(TailStart {
+        file_path: file_path.to_string(),
+        start_line,
+    })
+}
+
+/// 停止跟随；file_path 为空时停止全部
+pub fn stop(file_path: Option<&str>) {
+    let Ok(mut tails) = TAILS.lock() else {
+        return;
+    };
+    let Some(tails) = tails.as_mut() else {
+        return;
+    };
+    match file_path {
+        Some(file_path) => {
+            if let Some(handle) = tails.remove(file_path) {
+                handle.abort();
+            }
+        }
+        None => {
+            for (_, handle) in tails.drain() {
+                handle.abort();
+            }
+        }
+    }
+}
@@ -0,0 +1,208 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::sync::Mutex;
+use tauri::{AppHandle, Emitter, Manager};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+use crate::store::AppState;
+
+/// 注册的 URL Scheme
+pub const SCHEME: &str = "cc-switch";
+/// 收到添加供应商链接，前端弹出预填表单
+pub const EVENT_DEEP_LINK_PROVIDER: &str = "deep-link-provider";
+/// 链接无效
+pub const EVENT_DEEP_LINK_ERROR: &str = "deep-link-error";
+
+const MAX_NAME_LEN: usize = 100;
+
+/// 前端就绪前收到的链接（冷启动时由前端主动拉取）
+static PENDING: Mutex<Option<DeepLinkProvider>> = Mutex::new(None);
+
+/// 由链接解析出的供应商草稿；仅用于预填表单，用户确认后由前端调用 add_provider 写入
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DeepLinkProvider {
+    pub app_type: String,
+    /// 草稿供应商（id 为空）
+    pub provider: Provider,
+    pub has_api_key: bool,
+    /// 与已有供应商重复时为其 id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+fn query_value(url: &reqwest::Url, key: &str) -> Option<String> {
+    url.query_pairs()
+        .find(|(k, _)| k == key)
+        .map(|(_, v)| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn validate_http_url(field: &str, value: &str) -> Result<(), String> {
+    let url =
+        reqwest::Url::parse(value).map_err(|e| format!("{} 无效: {} ({})", field, value, e))?;
+    if !matches!(url.scheme(), "http" | "https") || url.host_str().is_none() {
+        return Err(format!("{} 必须是 http(s) 地址: {}", field, value));
+    }
+    Ok(())
+}
+
+/// 按应用类型生成 settings_config
+fn settings_for(
+    app_type: &AppType,
+    name: &str,
+    base_url: &str,
+    api_key: Option<&str>,
+    model: Option<&str>,
+) -> Result<Value, String> {
+    let mut env = Map::new();
+    let mut put = |key: &str, value: Option<&str>| {
+        if let Some(value) = value {
+            env.insert(key.to_string(), Value::String(value.to_string()));
+        }
+    };
+    match app_type {
+        AppType::Claude => {
+            put("ANTHROPIC_BASE_URL", Some(base_url));
+            put("ANTHROPIC_AUTH_TOKEN", api_key);
+            put("ANTHROPIC_MODEL", model);
+            Ok(json!({ "env": env }))
+        }
+        AppType::Gemini => {
+            put("GOOGLE_GEMINI_BASE_URL", Some(base_url));
+            put("GEMINI_API_KEY", api_key);
+            put("GEMINI_MODEL", model);
+            Ok(json!({ "env": env }))
+        }
+        AppType::Codex => {
+            let config = crate::provider_discovery::codex_config_for(
+                name,
+                base_url,
+                model.unwrap_or("gpt-5-codex"),
+            );
+            Ok(json!({
+                "auth": { "OPENAI_API_KEY": api_key.unwrap_or("") },
+                "config": config,
+            }))
+        }
+        AppType::OpenCode | AppType::Crush => Err(format!(
+            "{} 暂不支持通过链接添加供应商",
+            crate::app_adapter::adapter_for(app_type).display_name()
+        )),
+    }
+}
+
+/// 解析添加供应商链接：
+/// `cc-switch://provider/add?app=claude&name=<名称>&baseUrl=<地址>[&apiKey=..][&model=..][&websiteUrl=..]`
+pub fn parse_provider_link(link: &str) -> Result<(AppType, Provider), String> {
+    let url = reqwest::Url::parse(link.trim()).map_err(|e| format!("链接无效: {}", e))?;
+    if url.scheme() != SCHEME {
+        return Err(format!("不支持的链接协议: {}", url.scheme()));
+    }
+    if url.host_str() != Some("provider") || url.path().trim_end_matches('/') != "/add" {
+        return Err("不支持的链接类型，仅支持 cc-switch://provider/add".to_string());
+    }
+
+    let app_type = match query_value(&url, "app").as_deref() {
+        None => AppType::Claude,
+        Some(app) => AppType::all()
+            .into_iter()
+            .find(|a| a.as_str() == app)
+            .ok_or_else(|| format!("未知的应用类型: {}", app))?,
+    };
+    let name = query_value(&url, "name").ok_or_else(|| "链接缺少 name 参数".to_string())?;
+    if name.chars().count() > MAX_NAME_LEN {
+        return Err(format!("供应商名称过长（最多 {} 个字符）", MAX_NAME_LEN));
+    }
+    let base_url =
+        query_value(&url, "baseUrl").ok_or_else(|| "链接缺少 baseUrl 参数".to_string())?;
+    validate_http_url("baseUrl", &base_url)?;
+    let website_url = query_value(&url, "websiteUrl");
+    if let Some(website_url) = website_url.as_deref() {
+        validate_http_url("websiteUrl", website_url)?;
+    }
+    let api_key = query_value(&url, "apiKey");
+    let model = query_value(&url, "model");
+
+    let settings = settings_for(
+        &app_type,
+        &name,
+        &base_url,
+        api_key.as_deref(),
+        model.as_deref(),
+    )?;
+    let provider = Provider::with_id(String::new(), name, settings, website_url);
+    Ok((app_type, provider))
+}
+
+/// 生成草稿，并标记与已有供应商（端点与 API Key 相同）的重复
+pub fn draft_for(
+    config: &MultiAppConfig,
+    app_type: AppType,
+    provider: Provider,
+) -> DeepLinkProvider {
+    let has_api_key = crate::app_adapter::adapter_for(&app_type)
+        .api_key(&provider.settings_config)
+        .is_some();
+    let identity = crate::provider_discovery::identity(&app_type, &provider);
+    let duplicate_of = config.get_manager(&app_type).and_then(|manager| {
+        manager
+            .providers
+            .values()
+            .find(|p| crate::provider_discovery::identity(&app_type, p) == identity)
+            .map(|p| p.id.clone())
+    });
+    DeepLinkProvider {
+        app_type: app_type.as_str().to_string(),
+        provider,
+        has_api_key,
+        duplicate_of,
+    }
+}
+
+fn build_draft(app: &AppHandle, link: &str) -> Result<DeepLinkProvider, String> {
+    let (app_type, provider) = parse_provider_link(link)?;
+    let state = app.state::<AppState>();
+    let config = state.config.lock().map_err(crate::error::lock_failed)?;
+    Ok(draft_for(&config, app_type, provider))
+}
+
+fn show_main_window(app: &AppHandle) {
+    if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.show();
+        let _ = window.set_focus();
+    }
+}
+
+/// 处理收到的链接：解析为草稿后暂存并通知前端弹出确认表单，不写入任何配置
+pub fn handle_urls(app: &AppHandle, urls: Vec<String>) {
+    for link in urls {
+        match build_draft(app, &link) {
+            Ok(draft) => {
+                log::info!(
+                    "收到添加供应商链接: {} ({})",
+                    draft.provider.name,
+                    draft.app_type
+                );
+                if let Ok(mut pending) = PENDING.lock() {
+                    *pending = Some(draft.clone());
+                }
+                if let Err(e) = app.emit(EVENT_DEEP_LINK_PROVIDER, &draft) {
+                    log::warn!("转发链接到前端失败: {}", e);
+                }
+            }
+            Err(e) => {
+                log::warn!("处理链接失败: {}", e);
+                let _ = app.emit(EVENT_DEEP_LINK_ERROR, &e);
+            }
+        }
+        show_main_window(app);
+    }
+}
+
+/// 取出尚未处理的链接草稿（前端启动或处理完事件后调用，取出后清空）
+pub fn take_pending() -> Option<DeepLinkProvider> {
+    PENDING.lock().ok().and_then(|mut pending| pending.take())
+}
@@ -0,0 +1,340 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::claude_settings::ClaudeSettingsSection;
+
+/// 重点管理的环境变量：始终出现在概览中，即使未在任何位置设置
+const MANAGED_VARS: &[&str] = &[
+    "ANTHROPIC_BASE_URL",
+    "ANTHROPIC_AUTH_TOKEN",
+    "ANTHROPIC_API_KEY",
+    "ANTHROPIC_MODEL",
+    "ANTHROPIC_SMALL_FAST_MODEL",
+    "OPENAI_API_KEY",
+    "OPENAI_BASE_URL",
+    "HTTP_PROXY",
+    "HTTPS_PROXY",
+    "ALL_PROXY",
+    "NO_PROXY",
+];
+
+/// 扫描的 shell 配置文件（相对用户主目录）
+const SHELL_PROFILES: &[&str] = &[
+    ".zshrc",
+    ".zprofile",
+    ".zshenv",
+    ".bashrc",
+    ".bash_profile",
+    ".profile",
+    ".config/fish/config.fish",
+];
+
+/// shell 配置文件中的一条导出
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShellExport {
+    pub file: String,
+    /// 行号（从 1 开始）
+    pub line: usize,
+    pub value: String,
+}
+
+/// 单个环境变量在各处的取值
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVarStatus {
+    pub name: String,
+    /// ~/.claude/settings.json 的 env 中的值
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub settings_value: Option<String>,
+    pub shell_exports: Vec<ShellExport>,
+    /// 当前进程继承到的值（从桌面启动时通常不含 shell 导出）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_value: Option<String>,
+    /// settings.json 与 shell 导出之间（或多处 shell 导出之间）取值不一致
+    pub conflict: bool,
+}
+
+/// 环境变量概览
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvOverview {
+    /// 已扫描且存在的 shell 配置文件
+    pub shell_files: Vec<String>,
+    pub variables: Vec<EnvVarStatus>,
+}
+
+fn validate_var_name(name: &str) -> Result<(), String> {
+    let mut chars = name.chars();
+    let valid = chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(format!("无效的环境变量名: {}", name))
+    }
+}
+
+/// 是否纳入概览：重点变量，或 ANTHROPIC_/OPENAI_/CLAUDE_ 前缀及代理变量
+fn is_relevant(name: &str) -> bool {
+    MANAGED_VARS.contains(&name)
+        || ["ANTHROPIC_", "OPENAI_", "CLAUDE_CODE_"]
+            .iter()
+            .any(|p| name.starts_with(p))
+        || name.to_ascii_uppercase().ends_with("_PROXY")
+}
+
+fn candidate_profiles() -> Vec<PathBuf> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    SHELL_PROFILES.iter().map(|p| home.join(p)).collect()
+}
+
+/// 存在的 shell 配置文件
+pub fn shell_profile_paths() -> Vec<PathBuf> {
+    candidate_profiles()
+        .into_iter()
+        .filter(|p| p.is_file())
+        .collect()
+}
+
+fn is_fish(path: &Path) -> bool {
+    path.extension().and_then(|e| e.to_str()) == Some("fish")
+}
+
+/// 去掉引号；双引号内处理 \" \\ \$ 转义，单引号内按字面量（fish 支持 \' 与 \\）
+fn unquote(raw: &str, fish: bool) -> String {
+    let raw = raw.trim();
+    if let Some(inner) = raw.strip_prefix('\'').and_then(|s| s.strip_suffix('\'')) {
+        if fish {
+            return inner.replace("\\'", "'").replace("\\\\", "\\");
+        }
+        return inner.replace("'\\''", "'");
+    }
+    if let Some(inner) = raw.strip_prefix('"').and_then(|s| s.strip_suffix('"')) {
+        let mut out = String::new();
+        let mut chars = inner.chars();
+        while let Some(c) = chars.next() {
+            match (c, chars.clone().next()) {
+                ('\\', Some(next @ ('"' | '\\' | '$' | '`'))) => {
+                    out.push(next);
+                    chars.next();
+                }
+                _ => out.push(c),
+            }
+        }
+        return out;
+    }
+    // 未加引号时去掉行尾注释
+    raw.split(" #").next().unwrap_or(raw).trim().to_string()
+}
+
+/// 解析一行导出，返回 (变量名, 值)
+fn parse_export_line(line: &str, fish: bool) -> Option<(String, String)> {
+    let line = line.trim();
+    if line.starts_with('#') {
+        return None;
+    }
+    if fish {
+        let rest = line.strip_prefix("set ")?;
+        let mut parts = rest.split_whitespace().peekable();
+        while parts.peek().is_some_and(|p| p.starts_with('-')) {
+            parts.next();
+        }
+        let name = parts.next()?;
+        let value_start = rest.find(name)? + name.len();
+        validate_var_name(name).ok()?;
+        return Some((name.to_string(), unquote(&rest[value_start..], true)));
+    }
+    let rest = line.strip_prefix("export ").unwrap_or(line);
+    let (name, value) = rest.split_once('=')?;
+    let name = name.trim();
+    validate_var_name(name).ok()?;
+    Some((name.to_string(), unquote(value, false)))
+}
+
+/// 读取 shell 配置文件中的全部导出：(变量名, 导出)
+fn scan_shell_exports() -> Vec<(String, ShellExport)> {
+    let mut found = Vec::new();
+    for path in shell_profile_paths() {
+        let Ok(text) = fs::read_to_string(&path) else {
+            continue;
+        };
+        let fish = is_fish(&path);
+        for (index, line) in text.lines().enumerate() {
+            if let Some((name, value)) = parse_export_line(line, fish) {
+                found.push((
+                    name,
+                    ShellExport {
+                        file: path.to_string_lossy().to_string(),
+                        line: index + 1,
+                        value,
+                    },
+                ));
+            }
+        }
+    }
+    found
+}
+
+fn settings_env() -> Result<serde_json::Map<String, Value>, String> {
+    match crate::claude_settings::get_section(ClaudeSettingsSection::Env)? {
+        Value::Object(map) => Ok(map),
+        _ => Ok(serde_json::Map::new()),
+    }
+}
+
+/// 汇总 Claude settings.json 的 env 与 shell 配置文件中的导出，并标记冲突
+pub fn env_overview() -> Result<EnvOverview, String> {
+    let settings = settings_env()?;
+    let exports = scan_shell_exports();
+
+    let mut names: Vec<String> = MANAGED_VARS.iter().map(|s| s.to_string()).collect();
+    let extra = settings
+        .keys()
+        .chain(exports.iter().map(|(name, _)| name))
+        .filter(|name| is_relevant(name));
+    for name in extra {
+        if !names.contains(name) {
+            names.push(name.clone());
+        }
+    }
+
+    let variables = names
+        .into_iter()
+        .map(|name| {
+            let settings_value = settings
+                .get(&name)
+                .and_then(|v| v.as_str())
+                .map(|s| s.to_string());
+            let shell_exports: Vec<ShellExport> = exports
+                .iter()
+                .filter(|(n, _)| *n == name)
+                .map(|(_, e)| e.clone())
+                .collect();
+            let mut values: Vec<&str> = shell_exports.iter().map(|e| e.value.as_str()).collect();
+            values.extend(settings_value.as_deref());
+            let conflict = values.iter().any(|v| *v != values[0]);
+            EnvVarStatus {
+                process_value: std::env::var(&name).ok(),
+                name,
+                settings_value,
+                shell_exports,
+                conflict,
+            }
+        })
+        .collect();
+
+    Ok(EnvOverview {
+        shell_files: shell_profile_paths()
+            .iter()
+            .map(|p| p.to_string_lossy().to_string())
+            .collect(),
+        variables,
+    })
+}
+
+/// 生成导出语句（单引号包裹，值中的单引号转义）
+fn render_export(name: &str, value: &str, fish: bool) -> String {
+    if fish {
+        format!(
+            "set -gx {} '{}'",
+            name,
+            value.replace('\\', "\\\\").replace('\'', "\\'")
+        )
+    } else {
+        format!("export {}='{}'", name, value.replace('\'', "'\\''"))
+    }
+}
+
+/// 仅允许修改已知的 shell 配置文件
+fn resolve_profile(file: &str) -> Result<PathBuf, String> {
+    let path = PathBuf::from(file);
+    candidate_profiles()
+        .into_iter()
+        .find(|p| *p == path)
+        .ok_or_else(|| format!("不支持的 shell 配置文件: {}", file))
+}
+
+/// 修改 shell 配置文件中某变量的导出：value 为 Some 时就地更新（不存在则追加），
+/// 为 None 时注释掉已有导出；返回是否修改了文件
+fn rewrite_shell_export(path: &Path, name: &str, value: Option<&str>) -> Result<bool, String> {
+    let text = if path.exists() {
+        fs::read_to_string(path).map_err(|e| format!("读取 {} 失败: {}", path.display(), e))?
+    } else {
+        String::new()
+    };
+    let fish = is_fish(path);
+    let mut found = false;
+    let mut lines: Vec<String> = text
+        .lines()
+        .map(|line| {
+            if parse_export_line(line, fish).is_none_or(|(n, _)| n != name) {
+                return line.to_string();
+            }
+            found = true;
+            let indent = &line[..line.len() - line.trim_start().len()];
+            match value {
+                Some(value) => format!("{}{}", indent, render_export(name, value, fish)),
+                None => format!("{}# {} # cc-switch", indent, line.trim_start()),
+            }
+        })
+        .collect();
+    if !found {
+        let Some(value) = value else {
+            return Ok(false);
+        };
+        lines.push(render_export(name, value, fish));
+    }
+    let mut new_text = lines.join("\n");
+    new_text.push('\n');
+    if new_text == text {
+        return Ok(false);
+    }
+    crate::config::write_text_file(path, &new_text)?;
+    Ok(true)
+}
+
+/// 在指定 shell 配置文件中设置（value 为 None 时注释掉）变量导出
+pub fn set_shell_export(file: &str, name: &str, value: Option<&str>) -> Result<(), String> {
+    let name = name.trim();
+    validate_var_name(name)?;
+    let path = resolve_profile(file)?;
+    rewrite_shell_export(&path, name, value)?;
+    Ok(())
+}
+
+/// 设置或移除 Claude settings.json env 中的变量
+pub fn set_settings_env(name: &str, value: Option<&str>) -> Result<(), String> {
+    let name = name.trim();
+    validate_var_name(name)?;
+    crate::claude_settings::set_env_var(name, value)?;
+    Ok(())
+}
+
+/// 一键消除冲突：以 value 为准写入 settings.json 的 env（OPENAI_* 仅作用于 Codex，不写入），
+/// 并将 shell 配置文件中的同名导出更新为该值（comment_shell 为 true 时改为注释掉）
+pub fn reconcile(name: &str, value: &str, comment_shell: bool) -> Result<EnvOverview, String> {
+    let name = name.trim();
+    validate_var_name(name)?;
+    if !name.starts_with("OPENAI_") {
+        crate::claude_settings::set_env_var(name, Some(value))?;
+    }
+    let shell_value = if comment_shell { None } else { Some(value) };
+    for path in shell_profile_paths() {
+        rewrite_shell_export(&path, name, shell_value)?;
+    }
+    env_overview()
+}
+
+/// 可能被 reconcile 修改的文件（用于记录撤销点）
+pub fn affected_paths() -> Vec<PathBuf> {
+    let mut paths = shell_profile_paths();
+    paths.push(crate::config::get_claude_settings_path());
+    paths
+}
@@ -0,0 +1,92 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::events::AppEvent;
+
+/// 环境配置切换完成
+pub const EVENT_ENV_PROFILE_SWITCHED: &str = "env-profile-switched";
+
+/// 环境配置：一组 Claude / Codex 配置目录（如 "work" 与 "personal"），
+/// 切换时整体替换规则、MCP 服务器与认证信息所在的目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvProfile {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    /// Claude 配置目录名称（在 appHomes 中登记），为空时使用默认目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_home: Option<String>,
+    /// Codex 配置目录名称（在 appHomes 中登记），为空时使用默认目录
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_home: Option<String>,
+}
+
+impl EnvProfile {
+    pub fn home_for(&self, app_type: &AppType) -> Option<&str> {
+        match app_type {
+            AppType::Claude => self.claude_home.as_deref(),
+            AppType::Codex => self.codex_home.as_deref(),
+            _ => None,
+        }
+    }
+}
+
+/// 检查环境配置引用的目录均已登记且存在
+fn validate_profile(profile: &EnvProfile) -> Result<(), String> {
+    for app_type in [AppType::Claude, AppType::Codex] {
+        let Some(home) = profile.home_for(&app_type) else {
+            continue;
+        };
+        let (_, dir) = crate::app_homes::homes_for(&app_type)
+            .into_iter()
+            .find(|(name, _)| name == home)
+            .ok_or_else(|| format!("{} 配置目录未登记: {}", app_type.as_str(), home))?;
+        if !dir.is_dir() {
+            return Err(format!(
+                "{} 配置目录不存在: {}",
+                app_type.as_str(),
+                dir.display()
+            ));
+        }
+    }
+    Ok(())
+}
+
+/// 切换到指定环境配置；id 为空时 Claude 与 Codex 都回到默认目录。
+/// 先校验全部目录，再一次性写入设置，任一目录无效时不做任何修改
+pub fn switch_profile(id: Option<&str>) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings();
+    let profile = match id.map(str::trim).filter(|s| !s.is_empty()) {
+        Some(id) => Some(
+            settings
+                .env_profiles
+                .iter()
+                .find(|p| p.id == id)
+                .cloned()
+                .ok_or_else(|| format!("环境配置不存在: {}", id))?,
+        ),
+        None => None,
+    };
+    if let Some(profile) = &profile {
+        validate_profile(profile)?;
+    }
+
+    settings.active_claude_home = profile.as_ref().and_then(|p| p.claude_home.clone());
+    settings.active_codex_home = profile.as_ref().and_then(|p| p.codex_home.clone());
+    settings.active_env_profile = profile.as_ref().map(|p| p.id.clone());
+    crate::settings::update_settings(settings)?;
+
+    log::info!(
+        "已切换环境配置: {}",
+        profile.as_ref().map(|p| p.id.as_str()).unwrap_or("default")
+    );
+    crate::events::publish(AppEvent::new(EVENT_ENV_PROFILE_SWITCHED).with_data(
+        serde_json::json!({
+            "profileId": profile.as_ref().map(|p| p.id.clone()),
+            "claudeHome": profile.as_ref().and_then(|p| p.claude_home.clone()),
+            "codexHome": profile.as_ref().and_then(|p| p.codex_home.clone()),
+        }),
+    ));
+    Ok(())
+}
@@ -0,0 +1,147 @@
+use serde::ser::SerializeStruct;
+use serde::{Serialize, Serializer};
+use std::fmt::{Debug, Display};
+use std::path::Path;
+
+use crate::i18n::tf;
+
+/// 后端错误：带错误码，序列化给前端为 `{ code, message, path? }`，
+/// 前端按 code 区分文件缺失、权限不足、解析失败等情况并本地化提示；
+/// message 按当前语言从文案目录生成（见 i18n）
+#[derive(Debug, thiserror::Error)]
+pub enum AppError {
+    #[error("{}", tf("error.fileNotFound", &[("path", .path)]))]
+    FileNotFound { path: String },
+    #[error("{}", tf("error.permissionDenied", &[("path", .path)]))]
+    PermissionDenied { path: String },
+    #[error("{}", tf("error.io", &[("path", .path), ("message", .message)]))]
+    Io { path: String, message: String },
+    #[error(
+        "{}",
+        tf("error.parse", &[("format", .format), ("path", .path), ("message", .message)])
+    )]
+    Parse {
+        path: String,
+        format: &'static str,
+        message: String,
+    },
+    #[error("{}", tf("error.serialize", &[("format", .format), ("message", .message)]))]
+    Serialize {
+        format: &'static str,
+        message: String,
+    },
+    #[error("{}", tf("error.lock", &[("message", .0)]))]
+    Lock(String),
+    #[error("{0}")]
+    InvalidInput(String),
+    /// 尚未细分的错误（由 String 错误转换而来）
+    #[error("{0}")]
+    Message(String),
+}
+
+impl AppError {
+    /// 按 io::ErrorKind 区分文件缺失与权限不足
+    pub fn io(path: &Path, err: std::io::Error) -> Self {
+        let path = path.display().to_string();
+        match err.kind() {
+            std::io::ErrorKind::NotFound => AppError::FileNotFound { path },
+            std::io::ErrorKind::PermissionDenied => AppError::PermissionDenied { path },
+            _ => AppError::Io {
+                path,
+                message: err.to_string(),
+            },
+        }
+    }
+
+    pub fn json(path: &Path, err: serde_json::Error) -> Self {
+        AppError::Parse {
+            path: path.display().to_string(),
+            format: "JSON",
+            message: err.to_string(),
+        }
+    }
+
+    pub fn toml(path: &Path, err: toml::de::Error) -> Self {
+        AppError::Parse {
+            path: path.display().to_string(),
+            format: "TOML",
+            message: err.to_string(),
+        }
+    }
+
+    /// 前端使用的错误码
+    pub fn code(&self) -> &'static str {
+        match self {
+            AppError::FileNotFound { .. } => "fileNotFound",
+            AppError::PermissionDenied { .. } => "permissionDenied",
+            AppError::Io { .. } => "io",
+            AppError::Parse { .. } => "parse",
+            AppError::Serialize { .. } => "serialize",
+            AppError::Lock(_) => "lock",
+            AppError::InvalidInput(_) => "invalidInput",
+            AppError::Message(_) => "unknown",
+        }
+    }
+
+    fn path(&self) -> Option<&str> {
+        match self {
+            AppError::FileNotFound { path }
+            | AppError::PermissionDenied { path }
+            | AppError::Io { path, .. }
+            | AppError::Parse { path, .. } => Some(path),
+            _ => None,
+        }
+    }
+}
+
+impl Serialize for AppError {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let path = self.path();
+        let mut state =
+            serializer.serialize_struct("AppError", if path.is_some() { 3 } else { 2 })?;
+        state.serialize_field("code", self.code())?;
+        state.serialize_field("message", &self.to_string())?;
+        if let Some(path) = path {
+            state.serialize_field("path", path)?;
+        }
+        state.end()
+    }
+}
+
+/// 兼容现有返回 String 错误的模块：可直接用 `?` 传入返回 AppError 的函数
+impl From<String> for AppError {
+    fn from(message: String) -> Self {
+        AppError::Message(message)
+    }
+}
+
+/// 反向兼容：返回 String 错误的调用方可直接对 AppError 使用 `?`
+impl From<AppError> for String {
+    fn from(err: AppError) -> Self {
+        err.to_string()
+    }
+}
+
+impl<T> From<std::sync::PoisonError<T>> for AppError {
+    fn from(err: std::sync::PoisonError<T>) -> Self {
+        AppError::Lock(err.to_string())
+    }
+}
+
+/// 获取锁失败的文案（按当前语言），供返回 String 错误的模块使用
+pub fn lock_failed(err: impl Display) -> String {
+    tf("error.lock", &[("message", &err)])
+}
+
+/// 应用类型不存在的文案
+pub fn app_type_not_found(app_type: impl Debug) -> String {
+    tf(
+        "error.appTypeNotFound",
+        &[("app", &format!("{:?}", app_type))],
+    )
+}
+
+/// 供应商不存在的文案
+pub fn provider_not_found(id: impl Display) -> String {
+    tf("error.providerNotFound", &[("id", &id)])
+}
@@ -0,0 +1,227 @@
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::app_config::AppType;
+use crate::events::AppEvent;
+use crate::notification_sinks::{NotificationEvent, NotificationPayload};
+
+/// 规则动作触发的事件最多再级联的层数，防止规则之间互相触发形成死循环
+const MAX_RULE_DEPTH: u8 = 2;
+
+tokio::task_local! {
+    /// 当前任务所处的规则级联深度（仅在规则动作任务内设置）
+    static RULE_DEPTH: u8;
+}
+
+/// 规则动作
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum RuleAction {
+    /// 切换到指定供应商（未指定应用时沿用事件中的应用）
+    #[serde(rename_all = "camelCase")]
+    SwitchProvider {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        app_type: Option<String>,
+        provider_id: String,
+    },
+    /// 通过已配置的通知渠道发送通知
+    Notify {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        message: Option<String>,
+    },
+}
+
+/// 事件规则："when <event> and <condition> then <action>"
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct EventRule {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_rule_enabled")]
+    pub enabled: bool,
+    /// 触发事件类型
+    pub when: String,
+    /// 条件（全部满足）：provider / app 或事件附加数据中的键
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub conditions: BTreeMap<String, String>,
+    pub actions: Vec<RuleAction>,
+    /// 规则的文本形式（由 parse_rule 生成时保留）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+}
+
+fn default_rule_enabled() -> bool {
+    true
+}
+
+fn parse_key_value(token: &str) -> Result<(String, String), String> {
+    match token.split_once('=') {
+        Some((key, value)) if !key.is_empty() && !value.is_empty() => {
+            Ok((key.to_lowercase(), value.to_string()))
+        }
+        _ => Err(format!("条件格式应为 key=value: {}", token)),
+    }
+}
+
+fn parse_action(tokens: &[&str]) -> Result<RuleAction, String> {
+    let (verb, rest) = tokens
+        .split_first()
+        .ok_or_else(|| "then 之后缺少动作".to_string())?;
+    match verb.to_lowercase().as_str() {
+        "switch" => {
+            let mut app_type = None;
+            let mut provider_id = None;
+            for token in rest.iter().filter(|t| !t.eq_ignore_ascii_case("to")) {
+                let (key, value) = parse_key_value(token)?;
+                match key.as_str() {
+                    "provider" => provider_id = Some(value),
+                    "app" => app_type = Some(value),
+                    _ => return Err(format!("switch 动作不支持参数: {}", key)),
+                }
+            }
+            Ok(RuleAction::SwitchProvider {
+                app_type,
+                provider_id: provider_id
+                    .ok_or_else(|| "switch 动作缺少 provider=<id>".to_string())?,
+            })
+        }
+        "notify" => Ok(RuleAction::Notify {
+            message: (!rest.is_empty()).then(|| rest.join(" ")),
+        }),
+        other => Err(format!("未知动作: {}", other)),
+    }
+}
+
+/// 解析文本规则，例如
+/// `when provider-health-failed and provider=relay1 then switch to provider=official and notify`
+pub fn parse_rule(text: &str) -> Result<EventRule, String> {
+    let tokens: Vec<&str> = text.split_whitespace().collect();
+    let is = |token: &&str, word: &str| token.eq_ignore_ascii_case(word);
+
+    if !tokens.first().is_some_and(|t| is(t, "when")) {
+        return Err("规则必须以 when 开头".to_string());
+    }
+    let then_index = tokens
+        .iter()
+        .position(|t| is(t, "then"))
+        .ok_or_else(|| "规则缺少 then".to_string())?;
+    let event = tokens
+        .get(1)
+        .filter(|_| then_index > 1)
+        .ok_or_else(|| "when 之后缺少事件类型".to_string())?;
+
+    let mut conditions = BTreeMap::new();
+    for token in &tokens[2..then_index] {
+        if is(token, "and") || is(token, "for") {
+            continue;
+        }
+        let (key, value) = parse_key_value(token)?;
+        conditions.insert(key, value);
+    }
+
+    let actions = tokens[then_index + 1..]
+        .split(|t| is(t, "and"))
+        .map(parse_action)
+        .collect::<Result<Vec<_>, _>>()?;
+
+    Ok(EventRule {
+        id: format!("rule-{}", chrono::Utc::now().timestamp_millis()),
+        name: String::new(),
+        enabled: true,
+        when: event.to_string(),
+        conditions,
+        actions,
+        source: Some(text.trim().to_string()),
+    })
+}
+
+fn condition_matches(event: &AppEvent, key: &str, expected: &str) -> bool {
+    let actual = match key {
+        "provider" => event.provider_id.clone(),
+        "app" => event.app_type.clone(),
+        _ => event.data.get(key).map(|v| match v.as_str() {
+            Some(s) => s.to_string(),
+            None => v.to_string(),
+        }),
+    };
+    actual.as_deref() == Some(expected)
+}
+
+fn rule_matches(rule: &EventRule, event: &AppEvent) -> bool {
+    rule.enabled
+        && rule.when == event.kind
+        && rule
+            .conditions
+            .iter()
+            .all(|(key, value)| condition_matches(event, key, value))
+}
+
+async fn run_action(rule: &EventRule, action: &RuleAction, event: &AppEvent) -> Result<(), String> {
+    match action {
+        RuleAction::SwitchProvider {
+            app_type,
+            provider_id,
+        } => {
+            let app = crate::events::handle().ok_or_else(|| "应用尚未初始化".to_string())?;
+            let app_type: AppType = app_type
+                .as_deref()
+                .or(event.app_type.as_deref())
+                .unwrap_or("claude")
+                .into();
+            crate::switch_provider_internal(
+                &app,
+                app_type,
+                provider_id.clone(),
+                crate::switch_history::SwitchInitiator::Rule,
+            )
+            .await
+        }
+        RuleAction::Notify { message } => {
+            let name = if rule.name.is_empty() {
+                &rule.id
+            } else {
+                &rule.name
+            };
+            let message = message
+                .clone()
+                .unwrap_or_else(|| format!("规则「{}」由事件 {} 触发", name, event.kind));
+            let mut payload =
+                NotificationPayload::new(NotificationEvent::Rule, "事件规则触发", message);
+            payload.app_type = event.app_type.clone();
+            payload.provider_id = event.provider_id.clone();
+            crate::notification_sinks::dispatch(payload);
+            Ok(())
+        }
+    }
+}
+
+/// 评估事件：对命中的规则按顺序异步执行其动作
+pub fn evaluate(event: &AppEvent) {
+    let depth = RULE_DEPTH.try_with(|d| *d).unwrap_or(0);
+    if depth >= MAX_RULE_DEPTH {
+        log::warn!("事件 {} 的规则级联层数已达上限，跳过规则评估", event.kind);
+        return;
+    }
+
+    let rules: Vec<EventRule> = crate::settings::get_settings()
+        .event_rules
+        .into_iter()
+        .filter(|r| rule_matches(r, event))
+        .collect();
+    if rules.is_empty() {
+        return;
+    }
+
+    let event = event.clone();
+    tauri::async_runtime::spawn(RULE_DEPTH.scope(depth + 1, async move {
+        for rule in &rules {
+            log::info!("事件 {} 命中规则: {}", event.kind, rule.id);
+            for action in &rule.actions {
+                if let Err(e) = run_action(rule, action, &event).await {
+                    log::warn!("执行规则 {} 的动作失败: {}", rule.id, e);
+                }
+            }
+        }
+    }));
+}
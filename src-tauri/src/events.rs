@@ -0,0 +1,72 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::OnceLock;
+use tauri::{AppHandle, Emitter};
+
+/// 供应商切换完成
+pub const EVENT_PROVIDER_SWITCHED: &str = "provider-switched";
+
+/// 后端内部事件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppEvent {
+    /// 事件类型，例如 "provider-switched"、"provider-health-failed"
+    pub kind: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    /// 附加数据（规则条件可按键匹配）
+    #[serde(default, skip_serializing_if = "Value::is_null")]
+    pub data: Value,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+}
+
+impl AppEvent {
+    pub fn new(kind: &str) -> Self {
+        Self {
+            kind: kind.to_string(),
+            app_type: None,
+            provider_id: None,
+            data: Value::Null,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn with_provider(mut self, app_type: &str, provider_id: &str) -> Self {
+        self.app_type = Some(app_type.to_string());
+        self.provider_id = Some(provider_id.to_string());
+        self
+    }
+
+    pub fn with_data(mut self, data: Value) -> Self {
+        self.data = data;
+        self
+    }
+}
+
+fn app_handle() -> &'static OnceLock<AppHandle> {
+    static HANDLE: OnceLock<AppHandle> = OnceLock::new();
+    &HANDLE
+}
+
+/// 在应用启动时注册 AppHandle，供事件转发与规则动作使用
+pub fn init(handle: AppHandle) {
+    let _ = app_handle().set(handle);
+}
+
+pub fn handle() -> Option<AppHandle> {
+    app_handle().get().cloned()
+}
+
+/// 发布事件：转发到前端（"app-event"），并交由事件规则引擎评估
+pub fn publish(event: AppEvent) {
+    log::debug!("发布事件: {}", event.kind);
+    if let Some(app) = handle() {
+        if let Err(e) = app.emit("app-event", &event) {
+            log::warn!("转发事件到前端失败: {}", e);
+        }
+    }
+    crate::event_rules::evaluate(&event);
+}
@@ -0,0 +1,178 @@
+use serde::{Deserialize, Serialize};
+use std::fs::OpenOptions;
+use std::io::ErrorKind;
+use std::path::{Path, PathBuf};
+
+/// 默认导出文件名模板
+pub const DEFAULT_EXPORT_NAME_TEMPLATE: &str = "{project}/{date}_{title}_{id}";
+
+/// 目标文件已存在时的处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum CollisionPolicy {
+    /// 保留已有文件，跳过本次导出
+    Skip,
+    /// 覆盖已有文件
+    Overwrite,
+    /// 追加序号（name-2.ext、name-3.ext …）
+    #[default]
+    Suffix,
+}
+
+/// 渲染文件名模板所需的字段
+#[derive(Debug, Clone, Default)]
+pub struct ExportNameContext {
+    pub project: Option<String>,
+    /// 秒级时间戳，渲染为 YYYY-MM-DD
+    pub date: Option<i64>,
+    pub title: Option<String>,
+    pub id: String,
+    pub app: String,
+}
+
+/// 批量导出结果
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct ExportOutcome {
+    pub written: Vec<String>,
+    pub skipped: Vec<String>,
+}
+
+/// 去除路径分隔符与文件系统不允许的字符，避免占位符内容逃逸出导出目录
+fn sanitize_component(raw: &str, max_chars: usize) -> String {
+    let cleaned: String = raw
+        .chars()
+        .map(|c| match c {
+            '/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+            c if c.is_control() => '_',
+            c => c,
+        })
+        .take(max_chars)
+        .collect();
+    let trimmed = cleaned.trim().trim_matches('.').trim();
+    if trimmed.is_empty() {
+        "untitled".to_string()
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// 按模板渲染相对路径（不含扩展名）。支持 {project} {date} {title} {id} {app}，
+/// 模板中的 `/` 用于划分子目录
+pub fn render_export_name(template: &str, ctx: &ExportNameContext) -> PathBuf {
+    let date = ctx
+        .date
+        .and_then(|ts| chrono::DateTime::from_timestamp(ts, 0))
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d")
+                .to_string()
+        })
+        .unwrap_or_else(|| "unknown-date".to_string());
+    let fields = [
+        ("{project}", ctx.project.as_deref().unwrap_or("no-project")),
+        ("{date}", date.as_str()),
+        ("{title}", ctx.title.as_deref().unwrap_or("untitled")),
+        ("{id}", ctx.id.as_str()),
+        ("{app}", ctx.app.as_str()),
+    ];
+
+    let mut path = PathBuf::new();
+    for segment in template.split(['/', '\\']).filter(|s| !s.trim().is_empty()) {
+        let mut rendered = segment.to_string();
+        for (placeholder, value) in fields {
+            if rendered.contains(placeholder) {
+                rendered = rendered.replace(placeholder, &sanitize_component(value, 80));
+            }
+        }
+        path.push(sanitize_component(&rendered, 200));
+    }
+    if path.as_os_str().is_empty() {
+        path.push(sanitize_component(&ctx.id, 200));
+    }
+    path
+}
+
+/// 原子地占用一个尚不存在的文件，返回是否占用成功
+fn try_reserve(path: &Path) -> Result<bool, String> {
+    match OpenOptions::new().write(true).create_new(true).open(path) {
+        Ok(_) => Ok(true),
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => Ok(false),
+        Err(e) => Err(format!("创建导出文件失败: {}: {}", path.display(), e)),
+    }
+}
+
+/// 按冲突策略确定最终写入路径；返回 None 表示按策略跳过。
+/// Skip / Suffix 通过 create_new 预先占用文件，多个导出并发进行时也不会互相覆盖
+pub fn reserve_export_path(
+    dir: &Path,
+    relative: &Path,
+    extension: &str,
+    policy: CollisionPolicy,
+) -> Result<Option<PathBuf>, String> {
+    let base = dir.join(relative);
+    if let Some(parent) = base.parent() {
+        std::fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+    }
+    let stem = base
+        .file_name()
+        .map(|s| s.to_string_lossy().to_string())
+        .unwrap_or_default();
+    let candidate = |n: usize| {
+        let mut name = if n <= 1 {
+            stem.clone()
+        } else {
+            format!("{}-{}", stem, n)
+        };
+        if !extension.is_empty() {
+            name.push('.');
+            name.push_str(extension);
+        }
+        base.with_file_name(name)
+    };
+
+    match policy {
+        CollisionPolicy::Overwrite => Ok(Some(candidate(1))),
+        CollisionPolicy::Skip => {
+            let path = candidate(1);
+            Ok(try_reserve(&path)?.then_some(path))
+        }
+        CollisionPolicy::Suffix => {
+            for n in 1..10_000 {
+                let path = candidate(n);
+                if try_reserve(&path)? {
+                    return Ok(Some(path));
+                }
+            }
+            Err(format!(
+                "无法为导出文件分配不重复的名称: {}",
+                base.display()
+            ))
+        }
+    }
+}
+
+/// 读取设置中的文件名模板与冲突策略
+pub fn export_naming_settings() -> (String, CollisionPolicy) {
+    let settings = crate::settings::get_settings();
+    let template = settings
+        .export_name_template
+        .filter(|t| !t.trim().is_empty())
+        .unwrap_or_else(|| DEFAULT_EXPORT_NAME_TEMPLATE.to_string());
+    (template, settings.export_collision_policy)
+}
+
+/// 单文件导出（由用户指定完整路径）按设置中的冲突策略确定写入路径；
+/// 策略为 Skip 且文件已存在时返回错误
+pub fn reserve_export_file(path: &Path) -> Result<PathBuf, String> {
+    let (_, policy) = export_naming_settings();
+    let dir = path.parent().unwrap_or_else(|| Path::new(""));
+    let stem = path.file_stem().unwrap_or(path.as_os_str());
+    let extension = path
+        .extension()
+        .map(|e| e.to_string_lossy().to_string())
+        .unwrap_or_default();
+    reserve_export_path(dir, Path::new(stem), &extension, policy)?
+        .ok_or_else(|| format!("文件已存在，已按冲突策略跳过: {}", path.display()))
+}
@@ -0,0 +1,292 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::events::AppEvent;
+use crate::notification_sinks::{NotificationEvent, NotificationPayload};
+use crate::notifications::{NotificationCategory, NotificationRecord};
+use crate::provider_trends::{run_health_checks, HealthCheckTarget};
+use crate::store::AppState;
+use crate::switch_history::SwitchInitiator;
+
+/// 自动故障转移完成
+pub const EVENT_PROVIDER_FAILOVER: &str = "provider-failover";
+
+const DEFAULT_FAILURE_THRESHOLD: u32 = 3;
+const DEFAULT_CHECK_INTERVAL_SECS: u64 = 60;
+const MIN_CHECK_INTERVAL_SECS: u64 = 10;
+
+/// 自动故障转移设置
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct FailoverSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// Claude 供应商优先级（供应商 id，按顺序尝试）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claude_priority: Vec<String>,
+    /// Codex 供应商优先级
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codex_priority: Vec<String>,
+    /// Gemini 供应商优先级
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gemini_priority: Vec<String>,
+    /// OpenCode 供应商优先级
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub opencode_priority: Vec<String>,
+    /// Crush 供应商优先级
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub crush_priority: Vec<String>,
+    /// 连续失败多少次后触发切换（默认 3）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<u32>,
+    /// 健康检查间隔（秒，默认 60）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub check_interval_secs: Option<u64>,
+}
+
+impl FailoverSettings {
+    fn priority_for(&self, app_type: &AppType) -> &[String] {
+        match app_type {
+            AppType::Claude => &self.claude_priority,
+            AppType::Codex => &self.codex_priority,
+            AppType::Gemini => &self.gemini_priority,
+            AppType::OpenCode => &self.opencode_priority,
+            AppType::Crush => &self.crush_priority,
+        }
+    }
+}
+
+/// 当前供应商与优先级列表中的候选（按尝试顺序）
+struct FailoverState {
+    current: HealthCheckTarget,
+    current_name: String,
+    candidates: Vec<(HealthCheckTarget, String)>,
+}
+
+fn failover_state(
+    app: &tauri::AppHandle,
+    app_type: &AppType,
+    priority: &[String],
+) -> Option<FailoverState> {
+    let state = app.state::<AppState>();
+    let config = state.config.lock().ok()?;
+    let manager = config.get_manager(app_type)?;
+    let current = manager.providers.get(&manager.current)?;
+    let target = |id: &str| {
+        let provider = manager
+            .providers
+            .get(id)
+            .filter(|_| manager.is_provider_enabled(id))?;
+        let endpoint = provider.endpoint(app_type)?;
+        Some((
+            HealthCheckTarget {
+                app_type: app_type.as_str().to_string(),
+                provider_id: provider.id.clone(),
+                endpoint,
+            },
+            provider.name.clone(),
+        ))
+    };
+    let (current_target, current_name) = target(&current.id)?;
+
+    // 从当前供应商在列表中的下一位开始轮转；当前供应商不在列表中时从头开始
+    let start = priority
+        .iter()
+        .position(|id| id == &current.id)
+        .map(|i| i + 1)
+        .unwrap_or(0);
+    let candidates = priority
+        .iter()
+        .cycle()
+        .skip(start)
+        .take(priority.len())
+        .filter(|id| *id != &current.id)
+        .filter_map(|id| target(id))
+        .collect();
+
+    Some(FailoverState {
+        current: current_target,
+        current_name,
+        candidates,
+    })
+}
+
+fn notify_failover(app_type: &AppType, from: &str, to: &str, to_id: &str) {
+    let title = crate::i18n::t("notification.failover.title");
+    let message = crate::i18n::tf(
+        "notification.failover.message",
+        &[("app", &app_type.as_str()), ("from", &from), ("to", &to)],
+    );
+    crate::notifications::notify(
+        NotificationRecord::new(NotificationCategory::Failover, &title, message.clone())
+            .with_provider(app_type.as_str(), to_id),
+    );
+    crate::notification_sinks::dispatch(
+        NotificationPayload::new(NotificationEvent::Failover, title, message).with_provider(
+            app_type.as_str(),
+            to_id,
+            to,
+        ),
+    );
+}
+
+/// 检查单个应用的当前供应商，必要时执行故障转移
+async fn check_app(
+    app: &tauri::AppHandle,
+    app_type: &AppType,
+    settings: &FailoverSettings,
+    failures: &mut HashMap<String, u32>,
+) {
+    let priority = settings.priority_for(app_type);
+    if priority.is_empty() {
+        return;
+    }
+    let Some(state) = failover_state(app, app_type, priority) else {
+        return;
+    };
+
+    let healthy = match run_health_checks(vec![state.current.clone()]).await {
+        Ok(results) => results.first().is_some_and(|r| r.ok),
+        Err(e) => {
+            log::warn!("故障转移健康检查失败: {}", e);
+            return;
+        }
+    };
+    let key = app_type.as_str().to_string();
+    if healthy {
+        failures.remove(&key);
+        return;
+    }
+    let count = failures.entry(key.clone()).or_insert(0);
+    *count += 1;
+    let threshold = settings
+        .failure_threshold
+        .unwrap_or(DEFAULT_FAILURE_THRESHOLD)
+        .max(1);
+    if *count < threshold {
+        return;
+    }
+
+    for (candidate, name) in state.candidates {
+        let ok = run_health_checks(vec![candidate.clone()])
+            .await
+            .is_ok_and(|results| results.first().is_some_and(|r| r.ok));
+        if !ok {
+            continue;
+        }
+        match crate::switch_provider_internal(
+            app,
+            app_type.clone(),
+            candidate.provider_id.clone(),
+            SwitchInitiator::Failover,
+        )
+        .await
+        {
+            Ok(()) => {
+                log::info!(
+                    "故障转移: {} 从 {} 切换到 {}",
+                    key,
+                    state.current.provider_id,
+                    candidate.provider_id
+                );
+                failures.remove(&key);
+                notify_failover(app_type, &state.current_name, &name, &candidate.provider_id);
+                crate::events::publish(
+                    AppEvent::new(EVENT_PROVIDER_FAILOVER)
+                        .with_provider(&key, &candidate.provider_id)
+                        .with_data(serde_json::json!({
+                            "fromProviderId": state.current.provider_id,
+                            "failures": threshold,
+                        })),
+                );
+                return;
+            }
+            Err(e) => log::warn!("故障转移切换到 {} 失败: {}", candidate.provider_id, e),
+        }
+    }
+    log::warn!("故障转移: {} 的优先级列表中没有可用的供应商", key);
+}
+
+/// 当前供应商配置了限流换 Key 时，请求模型列表检测是否被限流（429），是则换用下一个 Key
+async fn rotate_on_rate_limit(app: &tauri::AppHandle, app_type: &AppType) -> bool {
+    let provider = {
+        let state = app.state::<AppState>();
+        let Ok(config) = state.config.lock() else {
+            return false;
+        };
+        config
+            .get_manager(app_type)
+            .and_then(|m| m.providers.get(&m.current))
+            .filter(|p| crate::key_rotation::rotates_on_rate_limit(p))
+            .cloned()
+    };
+    let Some(provider) = provider else {
+        return false;
+    };
+    // 加密的 Key 需解析后才能发出真实请求；密钥库未解锁时跳过本轮
+    let resolved = match crate::secrets::resolve_provider(app_type, &provider) {
+        Ok(resolved) => resolved,
+        Err(e) => {
+            log::debug!("限流检测跳过 {}: {}", provider.name, e);
+            return false;
+        }
+    };
+    // 只请求模型列表，不发送补全，避免每个检测周期都产生计费
+    let rate_limited = crate::provider_connection::probe_models_list(app_type, &resolved, None)
+        .await
+        .is_ok_and(|r| r.status == Some(429));
+    if !rate_limited {
+        return false;
+    }
+    let state = app.state::<AppState>();
+    match crate::commands::rotate_provider_key(state.inner(), app_type, &provider.id) {
+        Ok(active) => {
+            log::info!(
+                "{} 的供应商 {} 被限流，已换用第 {}/{} 个 Key",
+                app_type.as_str(),
+                provider.name,
+                active.index + 1,
+                active.total
+            );
+            true
+        }
+        Err(e) => {
+            log::warn!("限流换 Key 失败: {}", e);
+            false
+        }
+    }
+}
+
+/// 启动故障转移监视器：按间隔检查当前供应商，连续失败达到阈值后切换到优先级列表中的下一个可用供应商；
+/// 配置了限流换 Key 的当前供应商被限流时换用下一个 Key
+pub fn start_failover_monitor(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut failures: HashMap<String, u32> = HashMap::new();
+        loop {
+            let settings = crate::settings::get_settings().failover;
+            let interval = settings
+                .check_interval_secs
+                .unwrap_or(DEFAULT_CHECK_INTERVAL_SECS)
+                .max(MIN_CHECK_INTERVAL_SECS);
+            tokio::time::sleep(Duration::from_secs(interval)).await;
+
+            let settings = crate::settings::get_settings().failover;
+            if !settings.enabled {
+                failures.clear();
+            }
+            for app_type in AppType::all() {
+                // 限流换 Key 不依赖故障转移开关；换 Key 后本轮不再判定供应商故障
+                if rotate_on_rate_limit(&app, &app_type).await {
+                    failures.remove(app_type.as_str());
+                    continue;
+                }
+                if settings.enabled {
+                    check_app(&app, &app_type, &settings, &mut failures).await;
+                }
+            }
+        }
+    });
+}
@@ -0,0 +1,74 @@
+use fs2::FileExt;
+use std::fs::{self, File, OpenOptions};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+/// 等待文件锁的超时
+const LOCK_TIMEOUT: Duration = Duration::from_secs(5);
+/// 重试获取锁的间隔
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(50);
+
+/// 建议性文件锁守卫，离开作用域时自动释放
+pub struct FileLockGuard {
+    file: File,
+}
+
+impl Drop for FileLockGuard {
+    fn drop(&mut self) {
+        let _ = FileExt::unlock(&self.file);
+    }
+}
+
+/// 目标文件对应的锁文件：`<dir>/.<file_name>.lock`
+///
+/// 不直接锁目标文件本身，因为原子写入会通过 rename 替换文件，锁会随旧 inode 一起失效。
+fn lock_path_for(target: &Path) -> Result<PathBuf, String> {
+    let file_name = target
+        .file_name()
+        .ok_or_else(|| format!("无效的文件路径: {}", target.display()))?
+        .to_string_lossy();
+    let parent = target
+        .parent()
+        .ok_or_else(|| format!("无效的文件路径: {}", target.display()))?;
+    Ok(parent.join(format!(".{}.lock", file_name)))
+}
+
+/// 获取目标文件的独占锁（带重试），超时返回明确错误
+///
+/// 用于包裹“读取-修改-写入”流程，避免多个 cc-switch 实例或其他遵守同一约定的工具并发写入。
+/// 同一进程内不可对同一文件嵌套加锁。
+pub fn lock_file(target: &Path) -> Result<FileLockGuard, String> {
+    let lock_path = lock_path_for(target)?;
+    if let Some(parent) = lock_path.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| format!("创建目录失败: {}: {}", parent.display(), e))?;
+    }
+
+    let file = OpenOptions::new()
+        .create(true)
+        .truncate(false)
+        .read(true)
+        .write(true)
+        .open(&lock_path)
+        .map_err(|e| format!("打开锁文件失败: {}: {}", lock_path.display(), e))?;
+
+    let started = Instant::now();
+    loop {
+        match file.try_lock_exclusive() {
+            Ok(()) => return Ok(FileLockGuard { file }),
+            Err(e) if e.kind() == fs2::lock_contended_error().kind() => {
+                if started.elapsed() >= LOCK_TIMEOUT {
+                    return Err(format!(
+                        "等待文件锁超时（{} 秒），文件可能正被其他进程写入: {}",
+                        LOCK_TIMEOUT.as_secs(),
+                        target.display()
+                    ));
+                }
+                std::thread::sleep(LOCK_RETRY_INTERVAL);
+            }
+            Err(e) => {
+                return Err(format!("获取文件锁失败: {}: {}", target.display(), e));
+            }
+        }
+    }
+}
@@ -0,0 +1,188 @@
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+
+use crate::config::{atomic_write, delete_file, read_json_file, write_json_file, write_text_file};
+
+/// settings.json 中由 MCP 管理维护的字段，切换供应商时保留 live 中的值
+const MCP_SERVERS_KEY: &str = "mcpServers";
+
+/// 获取 Gemini CLI 配置目录路径
+pub fn get_gemini_config_dir() -> PathBuf {
+    if let Some(custom) = crate::settings::get_gemini_override_dir() {
+        return custom;
+    }
+
+    dirs::home_dir()
+        .expect("无法获取用户主目录")
+        .join(".gemini")
+}
+
+/// 获取 Gemini settings.json 路径
+pub fn get_gemini_settings_path() -> PathBuf {
+    get_gemini_config_dir().join("settings.json")
+}
+
+/// 获取 Gemini .env 路径（API Key 与端点等环境变量）
+pub fn get_gemini_env_path() -> PathBuf {
+    get_gemini_config_dir().join(".env")
+}
+
+/// 获取 Gemini 全局规则文件 GEMINI.md 路径
+pub fn get_gemini_rules_path() -> PathBuf {
+    get_gemini_config_dir().join("GEMINI.md")
+}
+
+/// 获取 Gemini 对话记录目录（tmp/<项目哈希>/chats/*.json）
+pub fn get_gemini_conversations_dir() -> PathBuf {
+    get_gemini_config_dir().join("tmp")
+}
+
+/// 解析 .env 文本（KEY=VALUE，忽略注释与空行，支持 export 前缀与引号）
+pub fn parse_env_text(text: &str) -> Map<String, Value> {
+    let mut env = Map::new();
+    for line in text.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let line = line.strip_prefix("export ").unwrap_or(line);
+        let Some((key, value)) = line.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        let value = value
+            .strip_prefix('"')
+            .and_then(|v| v.strip_suffix('"'))
+            .or_else(|| value.strip_prefix('\'').and_then(|v| v.strip_suffix('\'')))
+            .unwrap_or(value);
+        env.insert(key.trim().to_string(), Value::String(value.to_string()));
+    }
+    env
+}
+
+/// 渲染 .env 文本；包含空白或 # 的值加双引号
+pub fn render_env_text(env: &Map<String, Value>) -> String {
+    let mut out = String::new();
+    for (key, value) in env {
+        let value = match value {
+            Value::String(s) => s.clone(),
+            Value::Null => continue,
+            other => other.to_string(),
+        };
+        if value.chars().any(|c| c.is_whitespace() || c == '#') {
+            out.push_str(&format!("{}=\"{}\"\n", key, value.replace('"', "\\\"")));
+        } else {
+            out.push_str(&format!("{}={}\n", key, value));
+        }
+    }
+    out
+}
+
+/// 读取 live 配置为供应商 settings_config 结构：{ "env": {...}, "config": {...} }
+/// （config 不含 mcpServers）
+pub fn read_gemini_live() -> Result<Value, String> {
+    let env_path = get_gemini_env_path();
+    let env = if env_path.exists() {
+        let text = fs::read_to_string(&env_path)
+            .map_err(|e| format!("读取 Gemini .env 失败: {}: {}", env_path.display(), e))?;
+        parse_env_text(&text)
+    } else {
+        Map::new()
+    };
+
+    let settings_path = get_gemini_settings_path();
+    let mut config: Value = if settings_path.exists() {
+        read_json_file(&settings_path)?
+    } else {
+        Value::Object(Map::new())
+    };
+    if let Some(obj) = config.as_object_mut() {
+        obj.remove(MCP_SERVERS_KEY);
+    }
+
+    Ok(serde_json::json!({ "env": env, "config": config }))
+}
+
+/// live 配置是否存在（.env 或 settings.json 任一存在即可）
+pub fn gemini_live_exists() -> bool {
+    get_gemini_env_path().exists() || get_gemini_settings_path().exists()
+}
+
+/// 写入 Gemini live 配置：env 写入 .env，config 写入 settings.json（保留 live 中的 mcpServers）；
+/// 第二步失败时回滚 .env
+pub fn write_gemini_live(settings: &Value) -> Result<(), String> {
+    let env = settings
+        .get("env")
+        .and_then(|v| v.as_object())
+        .cloned()
+        .unwrap_or_default();
+    let config = settings.get("config").filter(|v| !v.is_null());
+
+    let env_path = get_gemini_env_path();
+    let settings_path = get_gemini_settings_path();
+
+    // 以 settings.json 的锁保护 .env + settings.json 的整个读取/写入/回滚过程
+    let _lock = crate::file_lock::lock_file(&settings_path)?;
+
+    let old_env = if env_path.exists() {
+        Some(
+            fs::read(&env_path)
+                .map_err(|e| format!("读取旧 Gemini .env 失败: {}: {}", env_path.display(), e))?,
+        )
+    } else {
+        None
+    };
+
+    let new_settings = match config {
+        Some(config) => {
+            let mut obj = config
+                .as_object()
+                .cloned()
+                .ok_or_else(|| "Gemini config 字段必须是 JSON 对象".to_string())?;
+            obj.remove(MCP_SERVERS_KEY);
+            if settings_path.exists() {
+                let live: Value = read_json_file(&settings_path)?;
+                if let Some(servers) = live.get(MCP_SERVERS_KEY) {
+                    obj.insert(MCP_SERVERS_KEY.to_string(), servers.clone());
+                }
+            }
+            Some(Value::Object(obj))
+        }
+        None => None,
+    };
+
+    // 第一步：写 .env
+    write_text_file(&env_path, &render_env_text(&env))?;
+
+    // 第二步：写 settings.json（失败则回滚 .env）
+    if let Some(new_settings) = new_settings {
+        if let Err(e) = write_json_file(&settings_path, &new_settings) {
+            if let Some(bytes) = old_env {
+                let _ = atomic_write(&env_path, &bytes);
+            } else {
+                let _ = delete_file(&env_path);
+            }
+            return Err(e.into());
+        }
+    }
+
+    Ok(())
+}
+
+/// 将启用的 MCP 服务器写入 ~/.gemini/settings.json 的 mcpServers 字段，其他字段保持不变
+pub fn set_mcp_servers_map(servers: &HashMap<String, Value>) -> Result<(), String> {
+    let path = get_gemini_settings_path();
+    let _lock = crate::file_lock::lock_file(&path)?;
+    let mut root: Value = if path.exists() {
+        read_json_file(&path)?
+    } else {
+        Value::Object(Map::new())
+    };
+    let out = crate::claude_mcp::build_mcp_servers_object(servers)?;
+    root.as_object_mut()
+        .ok_or_else(|| "~/.gemini/settings.json 根必须是对象".to_string())?
+        .insert(MCP_SERVERS_KEY.to_string(), Value::Object(out));
+    write_json_file(&path, &root).map_err(Into::into)
+}
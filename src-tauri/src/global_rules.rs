@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 
+use crate::app_config::AppType;
+
 /// Claude 全局规则文件路径
 pub fn get_claude_rules_path() -> Result<PathBuf, String> {
     let claude_dir = if let Some(custom) = crate::settings::get_claude_override_dir() {
@@ -28,6 +30,8 @@ pub struct CodexRuleFile {
     pub path: String,
     pub tags: Vec<String>,
     pub content: String,
+    /// 在 [rules].global 中的位置（未登记时为 None）
+    pub order: Option<usize>,
 }
 
 /// 读取 Claude 全局规则
@@ -41,15 +45,42 @@ pub fn read_claude_rules() -> Result<String, String> {
 
 /// 写入 Claude 全局规则
 pub fn write_claude_rules(content: &str) -> Result<(), String> {
-    let path = get_claude_rules_path()?;
-    
-    // 确保目录存在
-    if let Some(parent) = path.parent() {
-        fs::create_dir_all(parent)
-            .map_err(|e| format!("创建 Claude 目录失败: {}", e))?;
+    write_app_rules(&AppType::Claude, content)
+}
+
+/// 读取应用的全局规则文件（由适配器提供路径）；文件不存在时返回空字符串
+pub fn read_app_rules(app: &AppType) -> Result<String, String> {
+    let adapter = crate::app_adapter::adapter_for(app);
+    let path = adapter
+        .rules_path()
+        .ok_or_else(|| format!("{} 不支持全局规则文件", adapter.display_name()))?;
+    if !path.exists() {
+        return Ok(String::new());
     }
-    
-    fs::write(&path, content).map_err(|e| format!("写入 Claude 规则失败: {}", e))
+    fs::read_to_string(&path)
+        .map_err(|e| format!("读取 {} 规则失败: {}", adapter.display_name(), e))
+}
+
+/// 写入应用的全局规则文件
+pub fn write_app_rules(app: &AppType, content: &str) -> Result<(), String> {
+    let adapter = crate::app_adapter::adapter_for(app);
+    let path = adapter
+        .rules_path()
+        .ok_or_else(|| format!("{} 不支持全局规则文件", adapter.display_name()))?;
+    crate::config::write_text_file(&path, content)
+        .map_err(|e| format!("写入 {} 规则失败: {}", adapter.display_name(), e))?;
+    crate::config_history::auto_commit();
+    Ok(())
+}
+
+/// 读取 Gemini 全局规则（~/.gemini/GEMINI.md）
+pub fn read_gemini_rules() -> Result<String, String> {
+    read_app_rules(&AppType::Gemini)
+}
+
+/// 写入 Gemini 全局规则
+pub fn write_gemini_rules(content: &str) -> Result<(), String> {
+    write_app_rules(&AppType::Gemini, content)
 }
 
 /// 列出 Codex 规则文件
@@ -83,24 +114,36 @@ pub fn list_codex_rules() -> Result<Vec<CodexRuleFile>, String> {
                 path: path.to_string_lossy().to_string(),
                 tags: Vec::new(), // 标签从 config.toml 中读取
                 content,
+                order: None,
             });
         }
     }
     
-    // 从 config.toml 中读取标签信息
+    // 从 config.toml 中读取标签与顺序信息
     if let Ok(config_rules) = read_codex_rules_config() {
         for rule in &mut rules {
-            if let Some(config_rule) = config_rules.iter().find(|r| {
-                Path::new(&r.path)
-                    .file_name()
-                    .and_then(|s| s.to_str())
-                    == Some(&rule.name)
-            }) {
+            if let Some((index, config_rule)) =
+                config_rules.iter().enumerate().find(|(_, r)| {
+                    Path::new(&r.path)
+                        .file_name()
+                        .and_then(|s| s.to_str())
+                        == Some(&rule.name)
+                })
+            {
                 rule.tags = config_rule.tags.clone();
+                rule.order = Some(index);
             }
         }
     }
-    
+
+    // 按 [rules].global 中的顺序返回，未登记的规则按文件名排在最后
+    rules.sort_by(|a, b| match (a.order, b.order) {
+        (Some(x), Some(y)) => x.cmp(&y),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => a.name.cmp(&b.name),
+    });
+
     Ok(rules)
 }
 
@@ -125,10 +168,12 @@ pub fn write_codex_rule(filename: &str, content: &str, tags: Vec<String>) -> Res
         .map_err(|e| format!("创建 Codex 规则目录失败: {}", e))?;
     
     let path = rules_dir.join(filename);
-    fs::write(&path, content).map_err(|e| format!("写入规则文件失败: {}", e))?;
+    crate::config::write_text_file(&path, content)
+        .map_err(|e| format!("写入规则文件失败: {}", e))?;
     
     // 更新 config.toml
     update_codex_rules_config(filename, tags)?;
+    crate::config_history::auto_commit();
     
     Ok(())
 }
@@ -142,10 +187,11 @@ pub fn delete_codex_rule(filename: &str) -> Result<(), String> {
         return Err(format!("规则文件不存在: {}", filename));
     }
     
-    fs::remove_file(&path).map_err(|e| format!("删除规则文件失败: {}", e))?;
+    crate::config::delete_file(&path).map_err(|e| format!("删除规则文件失败: {}", e))?;
     
     // 从 config.toml 中移除
     remove_from_codex_rules_config(filename)?;
+    crate::config_history::auto_commit();
     
     Ok(())
 }
@@ -209,6 +255,7 @@ pub fn read_codex_rules_config() -> Result<Vec<CodexRuleConfig>, String> {
 /// 更新 Codex config.toml 中的规则配置
 fn update_codex_rules_config(filename: &str, tags: Vec<String>) -> Result<(), String> {
     let config_path = crate::codex_config::get_codex_config_path();
+    let _lock = crate::file_lock::lock_file(&config_path)?;
     let config_text = crate::codex_config::read_codex_config_text()?;
     
     let mut root: toml::Table = if config_text.trim().is_empty() {
@@ -266,15 +313,98 @@ fn update_codex_rules_config(filename: &str, tags: Vec<String>) -> Result<(), St
     let new_config = toml::to_string_pretty(&root)
         .map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
     
-    fs::write(&config_path, new_config)
-        .map_err(|e| format!("写入 config.toml 失败: {}", e))?;
+    crate::config::write_text_file(&config_path, &new_config)?;
     
     Ok(())
 }
 
+/// 按给定文件名顺序重写 [rules].global；未列出的条目保持原相对顺序追加在末尾
+pub fn reorder_codex_rules(filenames: Vec<String>) -> Result<(), String> {
+    let config_path = crate::codex_config::get_codex_config_path();
+    let _lock = crate::file_lock::lock_file(&config_path)?;
+    let config_text = crate::codex_config::read_codex_config_text()?;
+
+    if config_text.trim().is_empty() {
+        return Err("config.toml 中没有 [rules] 段".to_string());
+    }
+
+    // 用 toml_edit 调整顺序，保留用户的注释与格式
+    let mut doc: toml_edit::DocumentMut = config_text
+        .parse()
+        .map_err(|e| format!("解析 config.toml 失败: {}", e))?;
+    let global = doc
+        .get_mut("rules")
+        .and_then(|v| v.as_table_like_mut())
+        .and_then(|t| t.get_mut("global"))
+        .ok_or_else(|| "[rules] 段中没有 global 数组".to_string())?;
+
+    let file_name = |path: Option<&str>| -> Option<String> {
+        path.and_then(|p| Path::new(p).file_name().and_then(|f| f.to_str()))
+            .map(|s| s.to_string())
+    };
+    match global {
+        // global = [{ path = "..." }, ...]
+        toml_edit::Item::Value(toml_edit::Value::Array(array)) => {
+            let entries: Vec<toml_edit::Value> = array.iter().cloned().collect();
+            let reordered = reorder_rule_entries(entries, &filenames, |item| {
+                file_name(
+                    item.as_inline_table()
+                        .and_then(|t| t.get("path"))
+                        .and_then(|v| v.as_str()),
+                )
+            })?;
+            array.clear();
+            for item in reordered {
+                array.push_formatted(item);
+            }
+        }
+        // [[rules.global]]：表按文档位置输出，按新顺序重新分配原有位置
+        toml_edit::Item::ArrayOfTables(tables) => {
+            let mut positions: Vec<Option<isize>> = tables.iter().map(|t| t.position()).collect();
+            positions.sort();
+            let entries: Vec<toml_edit::Table> = tables.iter().cloned().collect();
+            let reordered = reorder_rule_entries(entries, &filenames, |table| {
+                file_name(table.get("path").and_then(|v| v.as_str()))
+            })?;
+            tables.clear();
+            for (mut table, position) in reordered.into_iter().zip(positions) {
+                if let Some(position) = position {
+                    table.set_position(position);
+                }
+                tables.push(table);
+            }
+        }
+        _ => return Err("[rules] 段中没有 global 数组".to_string()),
+    }
+
+    let new_config = doc.to_string();
+    crate::config::write_text_file(&config_path, &new_config)?;
+
+    Ok(())
+}
+
+/// 按 filenames 的顺序排列规则条目，未列出的条目保持原顺序排在最后
+fn reorder_rule_entries<T>(
+    mut remaining: Vec<T>,
+    filenames: &[String],
+    entry_name: impl Fn(&T) -> Option<String>,
+) -> Result<Vec<T>, String> {
+    let mut reordered = Vec::with_capacity(remaining.len());
+    for filename in filenames {
+        let index = remaining
+            .iter()
+            .position(|item| entry_name(item).as_deref() == Some(filename.as_str()))
+            .ok_or_else(|| format!("规则未在 config.toml 中登记: {}", filename))?;
+        reordered.push(remaining.remove(index));
+    }
+    reordered.extend(remaining);
+    Ok(reordered)
+}
+
 /// 从 Codex config.toml 中移除规则配置
 fn remove_from_codex_rules_config(filename: &str) -> Result<(), String> {
     let config_path = crate::codex_config::get_codex_config_path();
+    let _lock = crate::file_lock::lock_file(&config_path)?;
     let config_text = crate::codex_config::read_codex_config_text()?;
     
     if config_text.trim().is_empty() {
@@ -301,9 +431,620 @@ fn remove_from_codex_rules_config(filename: &str) -> Result<(), String> {
     let new_config = toml::to_string_pretty(&root)
         .map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
     
-    fs::write(&config_path, new_config)
-        .map_err(|e| format!("写入 config.toml 失败: {}", e))?;
+    crate::config::write_text_file(&config_path, &new_config)?;
     
     Ok(())
 }
 
+
+// ==================== 规则包导入/导出 ====================
+
+/// 规则包中 Claude 规则的条目名
+const BUNDLE_CLAUDE_ENTRY: &str = "CLAUDE.md";
+/// 规则包中 Codex 规则的目录前缀
+const BUNDLE_CODEX_PREFIX: &str = "codex/rules/";
+/// 规则包清单条目名
+const BUNDLE_MANIFEST_ENTRY: &str = "manifest.json";
+
+/// 规则包清单（记录 Codex 规则标签等无法由文件本身表达的信息）
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesBundleManifest {
+    pub version: u32,
+    pub exported_at: i64,
+    #[serde(default)]
+    pub codex_rules: Vec<RulesBundleEntry>,
+}
+
+/// 规则包中的 Codex 规则条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesBundleEntry {
+    pub name: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// 导入规则包时的冲突处理策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleConflictStrategy {
+    /// 保留本地文件，跳过导入项
+    Skip,
+    /// 使用导入项覆盖本地文件
+    Overwrite,
+    /// 以新文件名导入，保留本地文件
+    Rename,
+}
+
+/// 规则重命名记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RenamedRule {
+    pub from: String,
+    pub to: String,
+}
+
+/// 规则包导入结果
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesImportResult {
+    pub imported: Vec<String>,
+    pub skipped: Vec<String>,
+    pub renamed: Vec<RenamedRule>,
+}
+
+/// 校验规则文件名：仅允许单层 .md 文件名，避免路径穿越
+pub(crate) fn validate_rule_filename(name: &str) -> Result<(), String> {
+    let trimmed = name.trim();
+    if trimmed.is_empty()
+        || trimmed.contains('/')
+        || trimmed.contains('\\')
+        || trimmed == "."
+        || trimmed == ".."
+    {
+        return Err(format!("无效的规则文件名: {}", name));
+    }
+    if !trimmed.ends_with(".md") {
+        return Err(format!("规则文件必须为 .md 文件: {}", name));
+    }
+    Ok(())
+}
+
+/// 在目录中为文件名生成不冲突的新名称（foo.md -> foo-2.md）
+pub(crate) fn unique_file_name(dir: &Path, file_name: &str) -> String {
+    let path = Path::new(file_name);
+    let stem = path
+        .file_stem()
+        .and_then(|s| s.to_str())
+        .unwrap_or("rule")
+        .to_string();
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| format!(".{}", s))
+        .unwrap_or_default();
+    for i in 2..1000 {
+        let candidate = format!("{}-{}{}", stem, i, ext);
+        if !dir.join(&candidate).exists() {
+            return candidate;
+        }
+    }
+    format!("{}-imported{}", stem, ext)
+}
+
+/// 导出规则包（zip）：包含 CLAUDE.md、Codex rules/*.md 及其标签清单
+pub fn export_rules(dest_path: &str) -> Result<(), String> {
+    use std::io::Write;
+    use zip::write::SimpleFileOptions;
+
+    let options = SimpleFileOptions::default();
+    let dest = crate::export_naming::reserve_export_file(Path::new(dest_path))?;
+    let file = fs::File::create(&dest).map_err(|e| format!("创建规则包失败: {}", e))?;
+    let mut zip = zip::ZipWriter::new(file);
+
+    let claude_rules = read_claude_rules()?;
+    if !claude_rules.is_empty() {
+        zip.start_file(BUNDLE_CLAUDE_ENTRY, options)
+            .map_err(|e| format!("写入规则包失败: {}", e))?;
+        zip.write_all(claude_rules.as_bytes())
+            .map_err(|e| format!("写入规则包失败: {}", e))?;
+    }
+
+    let mut manifest = RulesBundleManifest {
+        version: 1,
+        exported_at: chrono::Utc::now().timestamp(),
+        codex_rules: Vec::new(),
+    };
+    for rule in list_codex_rules()? {
+        zip.start_file(format!("{}{}", BUNDLE_CODEX_PREFIX, rule.name), options)
+            .map_err(|e| format!("写入规则包失败: {}", e))?;
+        zip.write_all(rule.content.as_bytes())
+            .map_err(|e| format!("写入规则包失败: {}", e))?;
+        manifest.codex_rules.push(RulesBundleEntry {
+            name: rule.name,
+            tags: rule.tags,
+        });
+    }
+
+    let manifest_json = serde_json::to_string_pretty(&manifest)
+        .map_err(|e| format!("序列化规则包清单失败: {}", e))?;
+    zip.start_file(BUNDLE_MANIFEST_ENTRY, options)
+        .map_err(|e| format!("写入规则包失败: {}", e))?;
+    zip.write_all(manifest_json.as_bytes())
+        .map_err(|e| format!("写入规则包失败: {}", e))?;
+
+    zip.finish().map_err(|e| format!("写入规则包失败: {}", e))?;
+    Ok(())
+}
+
+/// 导入规则包（zip），按冲突策略处理与本地同名的规则
+pub fn import_rules(
+    bundle_path: &str,
+    strategy: RuleConflictStrategy,
+) -> Result<RulesImportResult, String> {
+    use std::io::Read;
+
+    let file = fs::File::open(bundle_path).map_err(|e| format!("打开规则包失败: {}", e))?;
+    let mut archive =
+        zip::ZipArchive::new(file).map_err(|e| format!("解析规则包失败: {}", e))?;
+
+    // 先完整读出包内容，校验通过后再落盘
+    let mut manifest = RulesBundleManifest::default();
+    let mut claude_rules: Option<String> = None;
+    let mut codex_rules: Vec<(String, String)> = Vec::new();
+    for i in 0..archive.len() {
+        let mut entry = archive
+            .by_index(i)
+            .map_err(|e| format!("读取规则包条目失败: {}", e))?;
+        if entry.is_dir() {
+            continue;
+        }
+        let name = entry.name().to_string();
+        let mut content = String::new();
+        entry
+            .read_to_string(&mut content)
+            .map_err(|e| format!("读取规则包条目 {} 失败: {}", name, e))?;
+
+        if name == BUNDLE_MANIFEST_ENTRY {
+            manifest = serde_json::from_str(&content)
+                .map_err(|e| format!("解析规则包清单失败: {}", e))?;
+        } else if name == BUNDLE_CLAUDE_ENTRY {
+            claude_rules = Some(content);
+        } else if let Some(rule_name) = name.strip_prefix(BUNDLE_CODEX_PREFIX) {
+            validate_rule_filename(rule_name)?;
+            codex_rules.push((rule_name.to_string(), content));
+        } else {
+            log::warn!("跳过规则包中的未知条目: {}", name);
+        }
+    }
+
+    let mut result = RulesImportResult::default();
+
+    if let Some(content) = claude_rules {
+        let path = get_claude_rules_path()?;
+        let conflict = path.exists() && read_claude_rules()? != content;
+        match (conflict, strategy) {
+            (true, RuleConflictStrategy::Skip) => {
+                result.skipped.push(BUNDLE_CLAUDE_ENTRY.to_string());
+            }
+            (true, RuleConflictStrategy::Rename) => {
+                // CLAUDE.md 只有一份，改名导入后由用户自行合并
+                let dir = path
+                    .parent()
+                    .ok_or_else(|| "无效的 Claude 规则路径".to_string())?;
+                let new_name = unique_file_name(dir, BUNDLE_CLAUDE_ENTRY);
+                crate::config::write_text_file(&dir.join(&new_name), &content)?;
+                result.renamed.push(RenamedRule {
+                    from: BUNDLE_CLAUDE_ENTRY.to_string(),
+                    to: new_name,
+                });
+            }
+            _ => {
+                write_claude_rules(&content)?;
+                result.imported.push(BUNDLE_CLAUDE_ENTRY.to_string());
+            }
+        }
+    }
+
+    let rules_dir = get_codex_rules_dir()?;
+    for (name, content) in codex_rules {
+        let tags = manifest
+            .codex_rules
+            .iter()
+            .find(|r| r.name == name)
+            .map(|r| r.tags.clone())
+            .unwrap_or_default();
+
+        let existing = rules_dir.join(&name);
+        let conflict = existing.exists()
+            && fs::read_to_string(&existing)
+                .map(|local| local != content)
+                .unwrap_or(true);
+        match (conflict, strategy) {
+            (true, RuleConflictStrategy::Skip) => {
+                result.skipped.push(name);
+            }
+            (true, RuleConflictStrategy::Rename) => {
+                let new_name = unique_file_name(&rules_dir, &name);
+                write_codex_rule(&new_name, &content, tags)?;
+                result.renamed.push(RenamedRule {
+                    from: name,
+                    to: new_name,
+                });
+            }
+            _ => {
+                write_codex_rule(&name, &content, tags)?;
+                result.imported.push(name);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+// ==================== 规则 token 预算估算 ====================
+
+/// 未配置预算时使用的默认 token 预算
+const DEFAULT_RULES_TOKEN_BUDGET: usize = 8000;
+
+/// 单个规则文件的 token 估算
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleTokenEstimate {
+    pub name: String,
+    pub path: String,
+    pub chars: usize,
+    pub tokens: usize,
+}
+
+/// 规则 token 估算报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RulesTokenReport {
+    pub files: Vec<RuleTokenEstimate>,
+    pub total_tokens: usize,
+    pub budget: usize,
+    pub over_budget: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub warning: Option<String>,
+}
+
+fn is_cjk(c: char) -> bool {
+    matches!(c as u32,
+        0x3040..=0x30FF   // 日文假名
+        | 0x3400..=0x4DBF // CJK 扩展 A
+        | 0x4E00..=0x9FFF // CJK 统一表意文字
+        | 0xAC00..=0xD7AF // 韩文音节
+        | 0xF900..=0xFAFF // CJK 兼容表意文字
+        | 0xFF00..=0xFFEF // 全角字符
+    )
+}
+
+/// 近似估算文本 token 数：CJK 字符约 1 token/字，其余约 4 字符/token
+pub fn estimate_tokens(text: &str) -> usize {
+    let mut cjk = 0usize;
+    let mut other = 0usize;
+    for c in text.chars() {
+        if is_cjk(c) {
+            cjk += 1;
+        } else {
+            other += 1;
+        }
+    }
+    cjk + other.div_ceil(4)
+}
+
+/// 估算 CLAUDE.md 与已启用 Codex 规则（已登记在 [rules].global 中）的 token 数
+pub fn estimate_rules_tokens(budget: Option<usize>) -> Result<RulesTokenReport, String> {
+    let budget = budget
+        .or_else(|| crate::settings::get_settings().rules_token_budget)
+        .unwrap_or(DEFAULT_RULES_TOKEN_BUDGET);
+
+    let mut files = Vec::new();
+
+    let claude_path = get_claude_rules_path()?;
+    let claude_rules = read_claude_rules()?;
+    if !claude_rules.is_empty() {
+        files.push(RuleTokenEstimate {
+            name: "CLAUDE.md".to_string(),
+            path: claude_path.to_string_lossy().to_string(),
+            chars: claude_rules.chars().count(),
+            tokens: estimate_tokens(&claude_rules),
+        });
+    }
+
+    for rule in list_codex_rules()?.into_iter().filter(|r| r.order.is_some()) {
+        files.push(RuleTokenEstimate {
+            chars: rule.content.chars().count(),
+            tokens: estimate_tokens(&rule.content),
+            name: rule.name,
+            path: rule.path,
+        });
+    }
+
+    let total_tokens: usize = files.iter().map(|f| f.tokens).sum();
+    let over_budget = total_tokens > budget;
+    let warning = over_budget.then(|| {
+        format!(
+            "规则合计约 {} tokens，超出预算 {} tokens",
+            total_tokens, budget
+        )
+    });
+
+    Ok(RulesTokenReport {
+        files,
+        total_tokens,
+        budget,
+        over_budget,
+        warning,
+    })
+}
+
+// ==================== 规则文件检查 ====================
+
+/// 单行最大字符数，超过视为过长
+const MAX_RULE_LINE_CHARS: usize = 400;
+
+/// 否定指令关键词（需先于肯定关键词匹配，如 "must not" 先于 "must"）
+const NEGATIVE_DIRECTIVES: [&str; 9] = [
+    "must not", "do not", "don't", "never", "avoid", "不要", "禁止", "不得", "切勿",
+];
+/// 肯定指令关键词
+const POSITIVE_DIRECTIVES: [&str; 5] = ["always", "must", "总是", "必须", "务必"];
+
+/// 诊断级别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLintSeverity {
+    Error,
+    Warning,
+    Info,
+}
+
+/// 规则检查诊断项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleDiagnostic {
+    /// 文件名（CLAUDE.md、GEMINI.md 或 Codex 规则文件名）
+    pub file: String,
+    pub path: String,
+    /// 行号（从 1 开始）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line: Option<usize>,
+    pub severity: RuleLintSeverity,
+    /// 诊断代码："bom" / "duplicate-heading" / "long-line" / "missing-path" / "conflicting-directive"
+    pub code: String,
+    pub message: String,
+}
+
+/// 规则中的一条指令（肯定或否定）
+struct Directive {
+    file_index: usize,
+    line: usize,
+    negative: bool,
+    subject: String,
+    text: String,
+}
+
+/// 遍历非代码块行，回调参数为 (行号, 行内容)
+fn for_each_prose_line(content: &str, mut f: impl FnMut(usize, &str)) {
+    let mut in_fence = false;
+    for (index, line) in content.lines().enumerate() {
+        let trimmed = line.trim_start();
+        if trimmed.starts_with("```") || trimmed.starts_with("~~~") {
+            in_fence = !in_fence;
+            continue;
+        }
+        if !in_fence {
+            f(index + 1, line);
+        }
+    }
+}
+
+/// 解析指令行：去掉列表标记后识别肯定/否定关键词，返回 (是否否定, 归一化主题)
+fn parse_directive(line: &str) -> Option<(bool, String)> {
+    let text = line
+        .trim()
+        .trim_start_matches(['-', '*', '+', '>'])
+        .trim_start_matches(|c: char| c.is_ascii_digit() || c == '.' || c == ')')
+        .trim()
+        .to_lowercase();
+    if text.is_empty() || text.starts_with('#') {
+        return None;
+    }
+
+    let (negative, keyword) = NEGATIVE_DIRECTIVES
+        .iter()
+        .find(|k| text.contains(*k))
+        .map(|k| (true, *k))
+        .or_else(|| {
+            POSITIVE_DIRECTIVES
+                .iter()
+                .find(|k| text.contains(*k))
+                .map(|k| (false, *k))
+        })?;
+
+    let subject: String = text
+        .replacen(keyword, " ", 1)
+        .chars()
+        .map(|c| if c.is_alphanumeric() || is_cjk(c) { c } else { ' ' })
+        .collect::<String>()
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ");
+    if subject.chars().count() < 3 {
+        return None;
+    }
+    Some((negative, subject))
+}
+
+/// 提取行内代码与 Markdown 链接中疑似本地路径的引用
+fn extract_path_refs(line: &str) -> Vec<String> {
+    let mut refs = Vec::new();
+
+    // 行内代码：`...`
+    let mut parts = line.split('`');
+    parts.next();
+    while let (Some(code), Some(_)) = (parts.next(), parts.next()) {
+        refs.push(code.trim().to_string());
+    }
+
+    // Markdown 链接：[text](target)
+    let mut rest = line;
+    while let Some(start) = rest.find("](") {
+        let after = &rest[start + 2..];
+        let Some(end) = after.find(')') else {
+            break;
+        };
+        let target = after[..end].split('#').next().unwrap_or("").trim();
+        if !target.is_empty() && !target.contains("://") {
+            refs.push(target.to_string());
+        }
+        rest = &after[end..];
+    }
+
+    refs.into_iter()
+        .filter(|r| {
+            !r.is_empty()
+                && !r.contains(char::is_whitespace)
+                && ["/", "~/", "./", "../"].iter().any(|p| r.starts_with(p))
+        })
+        .collect()
+}
+
+/// 将引用路径解析为绝对路径（~ 展开为主目录，相对路径基于规则文件所在目录）
+fn resolve_ref_path(reference: &str, base_dir: Option<&Path>) -> Option<PathBuf> {
+    if let Some(rest) = reference.strip_prefix("~/") {
+        return dirs::home_dir().map(|home| home.join(rest));
+    }
+    let path = Path::new(reference);
+    if path.is_absolute() {
+        return Some(path.to_path_buf());
+    }
+    base_dir.map(|dir| dir.join(path))
+}
+
+/// 检查规则文件的常见问题：BOM、重复标题、过长行、引用不存在的路径、跨文件冲突指令
+pub fn lint_rules() -> Result<Vec<RuleDiagnostic>, String> {
+    // (文件名, 路径, 内容)
+    let mut files: Vec<(String, PathBuf, String)> = Vec::new();
+    // 各应用的单文件全局规则（CLAUDE.md、GEMINI.md、AGENTS.md 等）
+    for adapter in crate::app_adapter::all_adapters() {
+        let Some(path) = adapter.rules_path().filter(|p| p.exists()) else {
+            continue;
+        };
+        let name = path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        let content = read_app_rules(&adapter.app_type())?;
+        files.push((name, path, content));
+    }
+    for rule in list_codex_rules()? {
+        files.push((rule.name, PathBuf::from(rule.path), rule.content));
+    }
+
+    let mut diagnostics = Vec::new();
+    let mut directives: Vec<Directive> = Vec::new();
+
+    for (file_index, (name, path, content)) in files.iter().enumerate() {
+        let diag = |line: Option<usize>, severity, code: &str, message: String| RuleDiagnostic {
+            file: name.clone(),
+            path: path.to_string_lossy().to_string(),
+            line,
+            severity,
+            code: code.to_string(),
+            message,
+        };
+
+        for (index, line) in content.lines().enumerate() {
+            if line.contains('\u{FEFF}') {
+                let message = if index == 0 && line.starts_with('\u{FEFF}') {
+                    "文件以 UTF-8 BOM 开头，部分工具可能无法正确解析".to_string()
+                } else {
+                    "行内包含 BOM 字符（U+FEFF）".to_string()
+                };
+                diagnostics.push(diag(Some(index + 1), RuleLintSeverity::Warning, "bom", message));
+            }
+            let chars = line.chars().count();
+            if chars > MAX_RULE_LINE_CHARS {
+                diagnostics.push(diag(
+                    Some(index + 1),
+                    RuleLintSeverity::Info,
+                    "long-line",
+                    format!("行过长（{} 字符，建议不超过 {}）", chars, MAX_RULE_LINE_CHARS),
+                ));
+            }
+        }
+
+        let base_dir = path.parent();
+        let mut headings: Vec<(String, usize)> = Vec::new();
+        for_each_prose_line(content, |line_no, line| {
+            let trimmed = line.trim_start_matches('\u{FEFF}').trim();
+            if trimmed.starts_with('#') {
+                let title = trimmed.trim_start_matches('#').trim().to_lowercase();
+                if !title.is_empty() {
+                    if let Some((_, first)) = headings.iter().find(|(t, _)| *t == title) {
+                        diagnostics.push(diag(
+                            Some(line_no),
+                            RuleLintSeverity::Warning,
+                            "duplicate-heading",
+                            format!("标题重复：与第 {} 行相同", first),
+                        ));
+                    } else {
+                        headings.push((title, line_no));
+                    }
+                }
+            } else if let Some((negative, subject)) = parse_directive(trimmed) {
+                directives.push(Directive {
+                    file_index,
+                    line: line_no,
+                    negative,
+                    subject,
+                    text: trimmed.to_string(),
+                });
+            }
+
+            for reference in extract_path_refs(line) {
+                if let Some(resolved) = resolve_ref_path(&reference, base_dir) {
+                    if !resolved.exists() {
+                        diagnostics.push(diag(
+                            Some(line_no),
+                            RuleLintSeverity::Warning,
+                            "missing-path",
+                            format!("引用的路径不存在: {}", reference),
+                        ));
+                    }
+                }
+            }
+        });
+    }
+
+    // 跨文件冲突：同一主题在不同文件中分别为肯定与否定指令
+    for (i, a) in directives.iter().enumerate() {
+        for b in directives.iter().skip(i + 1) {
+            if a.file_index == b.file_index || a.negative == b.negative || a.subject != b.subject {
+                continue;
+            }
+            for (this, other) in [(a, b), (b, a)] {
+                let (name, path, _) = &files[this.file_index];
+                let (other_name, _, _) = &files[other.file_index];
+                diagnostics.push(RuleDiagnostic {
+                    file: name.clone(),
+                    path: path.to_string_lossy().to_string(),
+                    line: Some(this.line),
+                    severity: RuleLintSeverity::Warning,
+                    code: "conflicting-directive".to_string(),
+                    message: format!(
+                        "与 {} 第 {} 行的指令冲突: \"{}\"",
+                        other_name, other.line, other.text
+                    ),
+                });
+            }
+        }
+    }
+
+    diagnostics.sort_by(|a, b| a.file.cmp(&b.file).then(a.line.cmp(&b.line)));
+    Ok(diagnostics)
+}
@@ -0,0 +1,122 @@
+use std::sync::Mutex;
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutEvent, ShortcutState};
+use tauri_plugin_notification::NotificationExt;
+
+use crate::app_config::AppType;
+use crate::notification_sinks::{NotificationEvent, NotificationPayload};
+use crate::store::AppState;
+
+/// 主界面当前选中的应用，快捷键作用于该应用
+static FOCUSED_APP: Mutex<Option<AppType>> = Mutex::new(None);
+
+/// 记录主界面当前选中的应用
+pub fn set_focused_app(app_type: AppType) {
+    if let Ok(mut guard) = FOCUSED_APP.lock() {
+        *guard = Some(app_type);
+    }
+}
+
+fn focused_app() -> AppType {
+    FOCUSED_APP
+        .lock()
+        .ok()
+        .and_then(|guard| guard.clone())
+        .unwrap_or(AppType::Claude)
+}
+
+/// 校验快捷键写法，例如 `CmdOrCtrl+Shift+P`
+pub fn parse_shortcut(shortcut: &str) -> Result<Shortcut, String> {
+    shortcut
+        .trim()
+        .parse::<Shortcut>()
+        .map_err(|e| format!("无效的快捷键: {} ({})", shortcut, e))
+}
+
+/// 全局快捷键插件，按下时在当前与上一个供应商之间切换
+pub fn plugin() -> tauri::plugin::TauriPlugin<tauri::Wry> {
+    tauri_plugin_global_shortcut::Builder::new()
+        .with_handler(|app, _shortcut, event: ShortcutEvent| {
+            if event.state() != ShortcutState::Pressed {
+                return;
+            }
+            let app = app.clone();
+            tauri::async_runtime::spawn(async move {
+                let app_type = focused_app();
+                if let Err(e) = toggle_previous(&app, app_type).await {
+                    log::warn!("快捷键切换供应商失败: {}", e);
+                    show_notification(&app, "切换供应商失败", &e);
+                }
+            });
+        })
+        .build()
+}
+
+/// 按设置注册快捷键（先注销已注册的快捷键）；shortcut 为空时仅注销
+pub fn register(app: &AppHandle, shortcut: Option<&str>) -> Result<(), String> {
+    let manager = app.global_shortcut();
+    manager
+        .unregister_all()
+        .map_err(|e| format!("注销快捷键失败: {}", e))?;
+    let Some(shortcut) = shortcut.map(str::trim).filter(|s| !s.is_empty()) else {
+        return Ok(());
+    };
+    manager
+        .register(parse_shortcut(shortcut)?)
+        .map_err(|e| format!("注册快捷键 {} 失败: {}", shortcut, e))
+}
+
+/// 启动时按设置注册快捷键
+pub fn register_from_settings(app: &AppHandle) {
+    let shortcut = crate::settings::get_settings().toggle_shortcut;
+    if let Err(e) = register(app, shortcut.as_deref()) {
+        log::warn!("{}", e);
+    }
+}
+
+fn show_notification(app: &AppHandle, title: &str, body: &str) {
+    if let Err(e) = app.notification().builder().title(title).body(body).show() {
+        log::warn!("发送系统通知失败: {}", e);
+    }
+}
+
+fn notify_toggled(app: &AppHandle, app_type: &str, id: &str, name: &str) {
+    let title = "供应商已切换";
+    let message = format!("{} 已通过快捷键切换到 {}", app_type, name);
+    show_notification(app, title, &message);
+    crate::notification_sinks::dispatch(
+        NotificationPayload::new(NotificationEvent::Switch, title, message)
+            .with_provider(app_type, id, name),
+    );
+}
+
+/// 切换到上一个供应商并发送系统通知
+async fn toggle_previous(app: &AppHandle, app_type: AppType) -> Result<(), String> {
+    let previous = crate::switch_history::previous_provider(app_type.as_str())
+        .ok_or_else(|| format!("{} 没有可切回的供应商", app_type.as_str()))?;
+    let name = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        if manager.current == previous {
+            return Err("上一个供应商即为当前供应商".to_string());
+        }
+        manager
+            .providers
+            .get(&previous)
+            .map(|p| p.name.clone())
+            .ok_or_else(|| crate::error::provider_not_found(&previous))?
+    };
+    let app_str = app_type.as_str().to_string();
+    crate::switch_provider_internal(
+        app,
+        app_type,
+        previous.clone(),
+        crate::switch_history::SwitchInitiator::Hotkey,
+    )
+    .await?;
+    notify_toggled(app, &app_str, &previous, &name);
+    Ok(())
+}
@@ -0,0 +1,75 @@
+use std::collections::HashMap;
+use std::fmt::Display;
+use std::sync::OnceLock;
+
+/// 各语言的后端文案目录（消息键 → 文案，`{name}` 为占位符）
+const ZH_CATALOG: &str = include_str!("../resources/locales/zh.json");
+const EN_CATALOG: &str = include_str!("../resources/locales/en.json");
+
+/// 后端文案语言；与前端共用设置中的 language
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Locale {
+    Zh,
+    En,
+}
+
+impl Locale {
+    fn from_tag(tag: &str) -> Option<Self> {
+        let tag = tag.trim().to_lowercase();
+        if tag.starts_with("zh") {
+            Some(Locale::Zh)
+        } else if tag.starts_with("en") {
+            Some(Locale::En)
+        } else {
+            None
+        }
+    }
+
+    fn catalog(self) -> &'static HashMap<String, String> {
+        static ZH: OnceLock<HashMap<String, String>> = OnceLock::new();
+        static EN: OnceLock<HashMap<String, String>> = OnceLock::new();
+        let (cell, source) = match self {
+            Locale::Zh => (&ZH, ZH_CATALOG),
+            Locale::En => (&EN, EN_CATALOG),
+        };
+        cell.get_or_init(|| {
+            serde_json::from_str(source).unwrap_or_else(|e| {
+                log::warn!("解析文案目录失败: {}", e);
+                HashMap::new()
+            })
+        })
+    }
+}
+
+/// 当前语言：设置中的 language 优先，其次系统语言（LC_ALL / LANG），默认中文
+pub fn current() -> Locale {
+    crate::settings::get_language()
+        .as_deref()
+        .and_then(Locale::from_tag)
+        .or_else(|| {
+            ["LC_ALL", "LC_MESSAGES", "LANG"]
+                .iter()
+                .filter_map(|k| std::env::var(k).ok())
+                .find_map(|v| Locale::from_tag(&v))
+        })
+        .unwrap_or(Locale::Zh)
+}
+
+/// 按当前语言取文案；缺失时回退到中文目录，再回退到消息键本身
+pub fn t(key: &str) -> String {
+    tf(key, &[])
+}
+
+/// 取文案并替换 `{name}` 占位符
+pub fn tf(key: &str, args: &[(&str, &dyn Display)]) -> String {
+    let template = current()
+        .catalog()
+        .get(key)
+        .or_else(|| Locale::Zh.catalog().get(key))
+        .map(String::as_str)
+        .unwrap_or(key);
+    args.iter()
+        .fold(template.to_string(), |text, (name, value)| {
+            text.replace(&format!("{{{}}}", name), &value.to_string())
+        })
+}
@@ -1,7 +1,7 @@
 use chrono::Utc;
 use serde_json::{json, Value};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 // 默认仅保留最近 10 份备份，避免目录无限膨胀
 const MAX_BACKUPS: usize = 10;
@@ -87,13 +87,14 @@ pub async fn export_config_to_file(file_path: String) -> Result<Value, String> {
     let config_content = fs::read_to_string(&config_path)
         .map_err(|e| format!("Failed to read configuration: {}", e))?;
 
-    // 写入到指定文件
-    fs::write(&file_path, &config_content).map_err(|e| format!("Failed to write file: {}", e))?;
+    // 写入到指定文件（按导出冲突策略处理同名文件）
+    let target = crate::export_naming::reserve_export_file(Path::new(&file_path))?;
+    crate::config::write_text_file(&target, &config_content)?;
 
     Ok(json!({
         "success": true,
         "message": "Configuration exported successfully",
-        "filePath": file_path
+        "filePath": target.to_string_lossy()
     }))
 }
 
@@ -116,7 +117,7 @@ pub async fn import_config_from_file(
     let backup_id = create_backup(&config_path)?;
 
     // 写入新配置到磁盘
-    fs::write(&config_path, &import_content)
+    crate::config::write_text_file(&config_path, &import_content)
         .map_err(|e| format!("Failed to write configuration: {}", e))?;
 
     // 更新内存中的状态
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+/// 供应商换用了下一个 Key（手动或限流时自动）
+pub const EVENT_PROVIDER_KEY_ROTATED: &str = "provider-key-rotated";
+
+/// 多 Key 轮换策略
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RotationStrategy {
+    /// 仅手动切换到下一个 Key
+    #[default]
+    Manual,
+    /// 每次切换到该供应商时使用下一个 Key
+    RoundRobin,
+    /// 当前 Key 被限流（429）时自动换用下一个 Key
+    FailoverOnRateLimit,
+}
+
+/// 轮换中的单个 Key
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RotationKey {
+    /// 明文 Key；密钥库已初始化时为密钥引用（见 secrets::protect_rotation_keys）
+    pub key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+}
+
+/// 同一供应商的多个 API Key；生效的 Key 写入 settings_config 中的 Key 字段
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyRotation {
+    #[serde(default)]
+    pub keys: Vec<RotationKey>,
+    #[serde(default)]
+    pub strategy: RotationStrategy,
+    /// 当前使用的 Key 序号
+    #[serde(default)]
+    pub active: usize,
+}
+
+/// 当前生效的 Key（已脱敏）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveKey {
+    pub provider_id: String,
+    pub index: usize,
+    pub total: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    pub masked: String,
+}
+
+fn rotation_of(provider: &Provider) -> Option<&KeyRotation> {
+    provider
+        .meta
+        .as_ref()
+        .and_then(|m| m.key_rotation.as_ref())
+        .filter(|r| !r.keys.is_empty())
+}
+
+/// 校验轮换配置：Key 不能为空或重复，当前序号必须有效
+pub fn validate(rotation: &KeyRotation) -> Result<(), String> {
+    for (i, entry) in rotation.keys.iter().enumerate() {
+        if entry.key.trim().is_empty() {
+            return Err(format!("第 {} 个 Key 为空", i + 1));
+        }
+        if rotation.keys[..i].iter().any(|k| k.key.trim() == entry.key.trim()) {
+            return Err(format!("第 {} 个 Key 与前面的 Key 重复", i + 1));
+        }
+    }
+    if !rotation.keys.is_empty() && rotation.active >= rotation.keys.len() {
+        return Err(format!("当前 Key 序号超出范围: {}", rotation.active));
+    }
+    Ok(())
+}
+
+/// 写入 Key 的字段：优先已有非空值的字段，否则取应用的第一个密钥字段
+fn key_pointer(app_type: &AppType, settings: &Value) -> Option<String> {
+    let pointers = crate::app_adapter::adapter_for(app_type).secret_pointers(settings);
+    pointers
+        .iter()
+        .find(|p| {
+            settings
+                .pointer(p)
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty())
+        })
+        .or_else(|| pointers.first())
+        .cloned()
+}
+
+/// 按 JSON Pointer 写入字符串，缺失的中间对象自动创建
+fn set_pointer(settings: &mut Value, pointer: &str, value: &str) -> Result<(), String> {
+    let mut current = settings;
+    let mut segments = pointer.trim_start_matches('/').split('/').peekable();
+    while let Some(segment) = segments.next() {
+        let obj = current
+            .as_object_mut()
+            .ok_or_else(|| format!("无法写入 Key 字段: {}", pointer))?;
+        if segments.peek().is_none() {
+            obj.insert(segment.to_string(), Value::String(value.to_string()));
+            return Ok(());
+        }
+        current = obj
+            .entry(segment.to_string())
+            .or_insert_with(|| Value::Object(Map::new()));
+    }
+    Ok(())
+}
+
+/// 将当前序号的 Key 写入供应商配置；未配置轮换时不做修改。
+/// Key 为密钥引用时写入的也是引用，写 live 配置时才解析为明文
+pub fn apply_active(app_type: &AppType, provider: &mut Provider) -> Result<(), String> {
+    let Some(rotation) = rotation_of(provider) else {
+        return Ok(());
+    };
+    let index = rotation.active.min(rotation.keys.len() - 1);
+    let key = rotation.keys[index].key.trim().to_string();
+    let pointer = key_pointer(app_type, &provider.settings_config)
+        .ok_or_else(|| format!("{} 不支持多 Key 轮换", app_type.as_str()))?;
+    set_pointer(&mut provider.settings_config, &pointer, &key)?;
+    if let Some(meta) = provider.meta.as_mut() {
+        meta.needs_key = false;
+    }
+    Ok(())
+}
+
+/// 换用下一个 Key 并写入供应商配置；只有一个 Key 时返回 false
+pub fn advance(app_type: &AppType, provider: &mut Provider) -> Result<bool, String> {
+    let Some(rotation) = provider
+        .meta
+        .as_mut()
+        .and_then(|m| m.key_rotation.as_mut())
+        .filter(|r| r.keys.len() > 1)
+    else {
+        return Ok(false);
+    };
+    rotation.active = (rotation.active + 1) % rotation.keys.len();
+    apply_active(app_type, provider)?;
+    Ok(true)
+}
+
+/// 切换到该供应商前调用：轮询策略下换用下一个 Key
+pub fn on_switch(app_type: &AppType, provider: &mut Provider) -> Result<bool, String> {
+    match rotation_of(provider).map(|r| r.strategy) {
+        Some(RotationStrategy::RoundRobin) => advance(app_type, provider),
+        _ => Ok(false),
+    }
+}
+
+/// 是否在限流时自动换用下一个 Key
+pub fn rotates_on_rate_limit(provider: &Provider) -> bool {
+    rotation_of(provider).is_some_and(|r| {
+        r.strategy == RotationStrategy::FailoverOnRateLimit && r.keys.len() > 1
+    })
+}
+
+pub fn active_key(provider: &Provider) -> Option<ActiveKey> {
+    let rotation = rotation_of(provider)?;
+    let index = rotation.active.min(rotation.keys.len() - 1);
+    let entry = &rotation.keys[index];
+    Some(ActiveKey {
+        provider_id: provider.id.clone(),
+        index,
+        total: rotation.keys.len(),
+        label: entry.label.clone(),
+        masked: match crate::secrets::resolve_value(entry.key.trim()) {
+            Ok(key) => crate::onboarding::mask_secret(&key),
+            Err(_) => "已加密".to_string(),
+        },
+    })
+}
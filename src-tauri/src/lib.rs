@@ -1,19 +1,100 @@
+mod active_sessions;
+mod agents;
+mod app_adapter;
 mod app_config;
+mod app_homes;
 mod app_store;
+mod audit;
+mod backups;
+mod claude_auth;
+mod claude_caches;
+mod claude_commands;
+mod claude_marketplace;
 mod claude_mcp;
+mod claude_models;
+mod claude_settings;
 mod claude_plugin;
+mod cli;
+mod code_blocks;
+mod codex_caches;
 mod codex_config;
+mod codex_profiles;
+mod codex_settings;
 mod commands;
 mod config;
+mod config_history;
+mod config_validation;
+mod config_watcher;
 mod conversation;
+mod conversation_bookmarks;
+mod conversation_edit;
+mod conversation_notes;
+mod conversation_search;
+mod conversation_stats;
+mod conversation_summaries;
+mod conversation_tags;
+mod conversation_tail;
+mod deep_link;
+mod event_rules;
+mod env_manager;
+mod env_profiles;
+mod error;
+mod events;
+mod export_naming;
+mod failover;
+mod file_lock;
+mod gemini_config;
 mod global_rules;
+mod hotkey;
+mod i18n;
 mod import_export;
+mod key_rotation;
+mod local_api;
+mod local_server;
+mod logging;
+mod markdown;
+mod notification_sinks;
+mod notifications;
 mod mcp;
+mod mcp_catalog;
+mod mcp_health;
 mod migration;
+mod onboarding;
+mod portable;
+mod project_profiles;
 mod provider;
+mod provider_connection;
+mod provider_discovery;
+mod provider_extras;
+mod provider_import;
+mod provider_groups;
+mod provider_matrix;
+mod provider_quota;
+mod provider_share;
+mod provider_trends;
+mod provider_validation;
+mod proxy;
+mod remote;
+mod secrets;
+mod self_check;
+mod session_edits;
 mod settings;
+mod setup_report;
+mod share_bundle;
 mod speedtest;
+mod staged_apply;
+mod statusline;
+mod switch_history;
+mod switch_preview;
+mod switch_schedule;
+mod sync;
+mod undo;
 mod usage_script;
+mod web_import;
+mod workflow_migration;
+mod workspaces;
+mod write_guard;
+mod wsl;
 mod store;
 
 use store::AppState;
@@ -30,15 +111,18 @@ fn create_tray_menu(
     app: &tauri::AppHandle,
     app_state: &AppState,
 ) -> Result<Menu<tauri::Wry>, String> {
-    let config = app_state
-        .config
-        .lock()
-        .map_err(|e| format!("获取锁失败: {}", e))?;
+    let config = app_state.config.lock().map_err(crate::error::lock_failed)?;
 
     let mut menu_builder = MenuBuilder::new(app);
 
     // 顶部：打开主界面
-    let show_main_item = MenuItem::with_id(app, "show_main", "打开主界面", true, None::<&str>)
+    let show_main_item = MenuItem::with_id(
+        app,
+        "show_main",
+        crate::i18n::t("tray.showMain"),
+        true,
+        None::<&str>,
+    )
         .map_err(|e| format!("创建打开主界面菜单失败: {}", e))?;
     menu_builder = menu_builder.item(&show_main_item).separator();
 
@@ -78,7 +162,7 @@ fn create_tray_menu(
                     app,
                     format!("claude_{}", id),
                     &provider.name,
-                    true,
+                    claude_manager.is_provider_enabled(id),
                     is_current,
                     None::<&str>,
                 )
@@ -90,7 +174,7 @@ fn create_tray_menu(
             let empty_hint = MenuItem::with_id(
                 app,
                 "claude_empty",
-                "  (无供应商，请在主界面添加)",
+                crate::i18n::t("tray.emptyHint"),
                 false,
                 None::<&str>,
             )
@@ -134,7 +218,7 @@ fn create_tray_menu(
                     app,
                     format!("codex_{}", id),
                     &provider.name,
-                    true,
+                    codex_manager.is_provider_enabled(id),
                     is_current,
                     None::<&str>,
                 )
@@ -146,7 +230,7 @@ fn create_tray_menu(
             let empty_hint = MenuItem::with_id(
                 app,
                 "codex_empty",
-                "  (无供应商，请在主界面添加)",
+                crate::i18n::t("tray.emptyHint"),
                 false,
                 None::<&str>,
             )
@@ -155,8 +239,86 @@ fn create_tray_menu(
         }
     }
 
+    // Gemini 为可选集成：未添加供应商时不在托盘中显示
+    if let Some(gemini_manager) = config
+        .get_manager(&crate::app_config::AppType::Gemini)
+        .filter(|m| !m.providers.is_empty())
+    {
+        let gemini_header =
+            MenuItem::with_id(app, "gemini_header", "─── Gemini ───", false, None::<&str>)
+                .map_err(|e| format!("创建Gemini标题失败: {}", e))?;
+        menu_builder = menu_builder.item(&gemini_header);
+
+        let mut sorted_providers: Vec<_> = gemini_manager.providers.iter().collect();
+        sorted_providers.sort_by(|(_, a), (_, b)| {
+            (a.sort_index.unwrap_or(usize::MAX), a.created_at, &a.name).cmp(&(
+                b.sort_index.unwrap_or(usize::MAX),
+                b.created_at,
+                &b.name,
+            ))
+        });
+
+        for (id, provider) in sorted_providers {
+            let item = CheckMenuItem::with_id(
+                app,
+                format!("gemini_{}", id),
+                &provider.name,
+                gemini_manager.is_provider_enabled(id),
+                gemini_manager.current == *id,
+                None::<&str>,
+            )
+            .map_err(|e| format!("创建菜单项失败: {}", e))?;
+            menu_builder = menu_builder.item(&item);
+        }
+    }
+
+    // 适配器应用（OpenCode、Crush）同为可选集成，菜单项 id 以应用名为前缀
+    for app_type in [
+        crate::app_config::AppType::OpenCode,
+        crate::app_config::AppType::Crush,
+    ] {
+        let Some(manager) = config
+            .get_manager(&app_type)
+            .filter(|m| !m.providers.is_empty())
+        else {
+            continue;
+        };
+        let name = crate::app_adapter::adapter_for(&app_type).display_name();
+        let header = MenuItem::with_id(
+            app,
+            format!("{}_header", app_type.as_str()),
+            format!("─── {} ───", name),
+            false,
+            None::<&str>,
+        )
+        .map_err(|e| format!("创建{}标题失败: {}", name, e))?;
+        menu_builder = menu_builder.item(&header);
+
+        let mut sorted_providers: Vec<_> = manager.providers.iter().collect();
+        sorted_providers.sort_by(|(_, a), (_, b)| {
+            (a.sort_index.unwrap_or(usize::MAX), a.created_at, &a.name).cmp(&(
+                b.sort_index.unwrap_or(usize::MAX),
+                b.created_at,
+                &b.name,
+            ))
+        });
+
+        for (id, provider) in sorted_providers {
+            let item = CheckMenuItem::with_id(
+                app,
+                format!("{}_{}", app_type.as_str(), id),
+                &provider.name,
+                manager.is_provider_enabled(id),
+                manager.current == *id,
+                None::<&str>,
+            )
+            .map_err(|e| format!("创建菜单项失败: {}", e))?;
+            menu_builder = menu_builder.item(&item);
+        }
+    }
+
     // 分隔符和退出菜单
-    let quit_item = MenuItem::with_id(app, "quit", "退出", true, None::<&str>)
+    let quit_item = MenuItem::with_id(app, "quit", crate::i18n::t("tray.quit"), true, None::<&str>)
         .map_err(|e| format!("创建退出菜单失败: {}", e))?;
 
     menu_builder = menu_builder.separator().item(&quit_item);
@@ -219,6 +381,7 @@ fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
                     &app_handle,
                     crate::app_config::AppType::Claude,
                     provider_id,
+                    crate::switch_history::SwitchInitiator::Tray,
                 )
                 .await
                 {
@@ -238,6 +401,7 @@ fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
                     &app_handle,
                     crate::app_config::AppType::Codex,
                     provider_id,
+                    crate::switch_history::SwitchInitiator::Tray,
                 )
                 .await
                 {
@@ -245,6 +409,45 @@ fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
                 }
             });
         }
+        id if id.starts_with("gemini_") => {
+            let provider_id = id.strip_prefix("gemini_").unwrap();
+            log::info!("切换到Gemini供应商: {}", provider_id);
+
+            let app_handle = app.clone();
+            let provider_id = provider_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = switch_provider_internal(
+                    &app_handle,
+                    crate::app_config::AppType::Gemini,
+                    provider_id,
+                    crate::switch_history::SwitchInitiator::Tray,
+                )
+                .await
+                {
+                    log::error!("切换Gemini供应商失败: {}", e);
+                }
+            });
+        }
+        id if id.starts_with("opencode_") || id.starts_with("crush_") => {
+            let (app_name, provider_id) = id.split_once('_').unwrap();
+            let app_type = crate::app_config::AppType::from(app_name);
+            log::info!("切换到{}供应商: {}", app_name, provider_id);
+
+            let app_handle = app.clone();
+            let provider_id = provider_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = switch_provider_internal(
+                    &app_handle,
+                    app_type,
+                    provider_id,
+                    crate::switch_history::SwitchInitiator::Tray,
+                )
+                .await
+                {
+                    log::error!("切换供应商失败: {}", e);
+                }
+            });
+        }
         _ => {
             log::warn!("未处理的菜单事件: {}", event_id);
         }
@@ -254,22 +457,30 @@ fn handle_tray_menu_event(app: &tauri::AppHandle, event_id: &str) {
 //
 
 /// 内部切换供应商函数
-async fn switch_provider_internal(
+pub(crate) async fn switch_provider_internal(
     app: &tauri::AppHandle,
     app_type: crate::app_config::AppType,
     provider_id: String,
+    initiator: crate::switch_history::SwitchInitiator,
 ) -> Result<(), String> {
     if let Some(app_state) = app.try_state::<AppState>() {
         // 在使用前先保存需要的值
         let app_type_str = app_type.as_str().to_string();
         let provider_id_clone = provider_id.clone();
 
+        // 托盘、快捷键与自动切换无法弹出确认，仅记录提示后继续切换
+        if let Err(warning) = crate::active_sessions::ensure_app_idle(&app_type, false) {
+            log::warn!("{}", warning);
+        }
+
         crate::commands::switch_provider(
             app_state.clone(),
             Some(app_type),
             None,
             None,
             provider_id,
+            Some(initiator),
+            Some(true),
         )
         .await?;
 
@@ -310,6 +521,11 @@ async fn update_tray_menu(
     Ok(false)
 }
 
+/// 处理命令行子命令（如 `cc-switch conversations cat <id>`）；返回 Some(退出码) 时不应再启动 GUI
+pub fn run_cli(args: &[String]) -> Option<i32> {
+    cli::run(args)
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     let mut builder = tauri::Builder::default();
@@ -325,6 +541,9 @@ pub fn run() {
         }));
     }
 
+    // 注册 cc-switch:// 链接（需在单实例插件之后注册，第二实例收到的链接会转发到这里）
+    builder = builder.plugin(tauri_plugin_deep_link::init());
+
     let builder = builder
         // 拦截窗口关闭：根据设置决定是否最小化到托盘
         .on_window_event(|window, event| {
@@ -349,6 +568,7 @@ pub fn run() {
         })
         .plugin(tauri_plugin_process::init())
         .plugin(tauri_plugin_dialog::init())
+        .plugin(tauri_plugin_notification::init())
         .plugin(tauri_plugin_opener::init())
         .plugin(tauri_plugin_store::Builder::new().build())
         .setup(|app| {
@@ -364,6 +584,10 @@ pub fn run() {
                     // 若配置不完整（如缺少 pubkey），跳过 Updater 而不中断应用
                     log::warn!("初始化 Updater 插件失败，已跳过：{}", e);
                 }
+                // 注册全局快捷键插件（在当前与上一个供应商之间切换）
+                if let Err(e) = app.handle().plugin(hotkey::plugin()) {
+                    log::warn!("初始化全局快捷键插件失败，已跳过：{}", e);
+                }
             }
             #[cfg(target_os = "macos")]
             {
@@ -395,16 +619,36 @@ pub fn run() {
                 }
             }
 
-            // 初始化日志
-            if cfg!(debug_assertions) {
-                app.handle().plugin(
-                    tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
-                        .build(),
-                )?;
-            }
+            // 初始化日志（写入 ~/.cc-switch/logs，级别取自设置）
+            logging::init()?;
+
+            // 注册事件总线使用的 AppHandle
+            events::init(app.handle().clone());
+
+            // 启动定时配置快照
+            backups::start_scheduler();
+
+            // 启动会话监视器，按会话 cwd 匹配工作区
+            workspaces::start_session_watcher(app.handle().clone());
+
+            // 启动定时健康检查（结果写入供应商趋势数据）
+            provider_trends::start_health_scheduler(app.handle().clone());
+
+            // 启动故障转移监视器（设置中启用后生效）
+            failover::start_failover_monitor(app.handle().clone());
+
+            // 启动定时状态报告（设置中配置间隔后生效）
+            setup_report::start_report_scheduler(app.handle().clone());
+
+            // 启动定时切换调度器（设置中配置规则后生效）
+            switch_schedule::start_switch_scheduler(app.handle().clone());
 
             // 初始化应用状态（仅创建一次，并在本函数末尾注入 manage）
+            // 启动自检：在加载 config.json 前检查配置文件能否解析，损坏时等待用户选择恢复方式
+            let startup_report = self_check::run();
+            if !startup_report.issues.is_empty() {
+                log::warn!("启动自检发现 {} 个损坏的配置文件", startup_report.issues.len());
+            }
             let app_state = AppState::new();
 
             // 迁移旧的 app_config_dir 配置到 Store
@@ -415,13 +659,18 @@ pub fn run() {
             // 首次启动迁移：扫描副本文件，合并到 config.json，并归档副本；旧 config.json 先归档
             {
                 let mut config_guard = app_state.config.lock().unwrap();
-                let migrated = migration::migrate_copies_into_config(&mut config_guard)?;
+                // config.json 损坏时内存中是默认配置，推迟到恢复后的下次启动再迁移
+                let migrated = !self_check::app_config_corrupt()
+                    && migration::migrate_copies_into_config(&mut config_guard)?;
                 if migrated {
                     log::info!("已将副本文件导入到 config.json，并完成归档");
                 }
                 // 确保两个 App 条目存在
                 config_guard.ensure_app(&app_config::AppType::Claude);
                 config_guard.ensure_app(&app_config::AppType::Codex);
+                config_guard.ensure_app(&app_config::AppType::Gemini);
+                config_guard.ensure_app(&app_config::AppType::OpenCode);
+                config_guard.ensure_app(&app_config::AppType::Crush);
             }
 
             // 保存配置
@@ -449,6 +698,40 @@ pub fn run() {
             let _tray = tray_builder.build(app)?;
             // 将同一个实例注入到全局状态，避免重复创建导致的不一致
             app.manage(app_state);
+
+            // 按设置注册全局快捷键
+            #[cfg(desktop)]
+            hotkey::register_from_settings(app.handle());
+
+            // 监听 live 配置的外部修改
+            if let Err(e) = config_watcher::start(app.handle()) {
+                log::warn!("{}", e);
+            }
+
+            // 启动本地自动化接口（设置中启用后生效）
+            if let Err(e) = local_api::reload(app.handle()) {
+                log::warn!("{}", e);
+            }
+
+            // 监听 cc-switch:// 链接（含冷启动时携带的链接）
+            #[cfg(desktop)]
+            {
+                use tauri_plugin_deep_link::DeepLinkExt;
+                // Linux 与 Windows 开发环境需在运行时注册 Scheme（安装包会在安装时注册）
+                #[cfg(any(target_os = "linux", all(debug_assertions, windows)))]
+                if let Err(e) = app.deep_link().register_all() {
+                    log::warn!("注册 {} 链接失败: {}", deep_link::SCHEME, e);
+                }
+                let handle = app.handle().clone();
+                app.deep_link().on_open_url(move |event| {
+                    let urls = event.urls().iter().map(|u| u.to_string()).collect();
+                    deep_link::handle_urls(&handle, urls);
+                });
+                if let Ok(Some(urls)) = app.deep_link().get_current() {
+                    let urls = urls.iter().map(|u| u.to_string()).collect();
+                    deep_link::handle_urls(app.handle(), urls);
+                }
+            }
             Ok(())
         })
         .invoke_handler(tauri::generate_handler![
@@ -458,6 +741,15 @@ pub fn run() {
             commands::update_provider,
             commands::delete_provider,
             commands::switch_provider,
+            commands::preview_switch,
+            commands::validate_provider,
+            commands::test_provider_connection,
+            commands::fetch_provider_models,
+            commands::get_claude_provider_models,
+            commands::set_claude_provider_models,
+            commands::suggest_claude_default_model,
+            commands::cycle_provider_key,
+            commands::benchmark_providers,
             commands::import_default_config,
             commands::get_claude_config_status,
             commands::get_config_status,
@@ -471,9 +763,23 @@ pub fn run() {
             commands::read_live_provider_settings,
             commands::get_settings,
             commands::save_settings,
+            commands::list_app_homes,
+            commands::select_app_home,
+            commands::switch_env_profile,
+            commands::list_wsl_distros,
+            commands::discover_wsl_homes,
+            commands::test_remote_connection,
+            commands::list_remote_conversations,
+            commands::read_remote_conversation,
+            commands::read_remote_rules,
+            commands::write_remote_rules,
+            commands::switch_remote_provider,
             commands::restart_app,
             commands::check_for_updates,
             commands::is_portable_mode,
+            commands::get_portable_info,
+            commands::enable_portable_mode,
+            commands::disable_portable_mode,
             commands::get_claude_plugin_status,
             commands::read_claude_plugin_config,
             commands::apply_claude_plugin_config,
@@ -486,6 +792,8 @@ pub fn run() {
             commands::validate_mcp_command,
             // usage query
             commands::query_provider_usage,
+            commands::get_provider_quota,
+            commands::get_cached_provider_quotas,
             // New MCP via config.json (SSOT)
             commands::get_mcp_config,
             commands::upsert_mcp_server_in_config,
@@ -493,10 +801,18 @@ pub fn run() {
             commands::set_mcp_enabled,
             commands::sync_enabled_mcp_to_claude,
             commands::sync_enabled_mcp_to_codex,
+            commands::sync_enabled_mcp_to_gemini,
             commands::import_mcp_from_claude,
             commands::import_mcp_from_codex,
+            commands::test_mcp_server,
+            commands::test_mcp_server_spec,
+            commands::get_mcp_catalog,
+            commands::refresh_mcp_catalog,
+            commands::install_mcp_from_catalog,
             // ours: endpoint speed test + custom endpoint management
             commands::test_api_endpoints,
+            commands::export_provider_matrix,
+            commands::probe_local_server,
             commands::get_custom_endpoints,
             commands::add_custom_endpoint,
             commands::remove_custom_endpoint,
@@ -509,15 +825,182 @@ pub fn run() {
             // conversation management
             commands::list_conversations,
             commands::search_conversations,
+            commands::get_active_sessions,
             commands::delete_conversation,
             commands::read_conversation_content,
+            commands::read_conversation_messages,
+            commands::tail_conversation,
+            commands::stop_conversation_tail,
+            commands::get_project_stats,
+            commands::summarize_conversation,
+            commands::export_conversations,
+            commands::generate_share_bundle,
+            commands::import_web_export,
+            commands::export_conversation_messages,
+            commands::trim_conversation,
+            commands::merge_conversations,
+            commands::verify_conversation,
+            commands::bulk_tag_conversations,
+            commands::get_conversation_notes,
+            commands::set_conversation_note,
+            commands::get_conversation_summary,
+            commands::list_conversation_summaries,
+            commands::generate_conversation_summary,
+            commands::generate_missing_conversation_summaries,
+            commands::delete_conversation_summary,
+            commands::extract_code_blocks,
+            commands::get_session_file_edits,
+            commands::add_message_bookmark,
+            commands::remove_message_bookmark,
+            commands::list_message_bookmarks,
+            commands::list_smart_tags,
+            commands::upsert_smart_tag,
+            commands::delete_smart_tag,
             // global rules management
             commands::read_claude_rules,
             commands::write_claude_rules,
+            commands::read_gemini_rules,
+            commands::write_gemini_rules,
             commands::list_codex_rules,
+            commands::list_rules_across_homes,
             commands::read_codex_rule,
             commands::write_codex_rule,
             commands::delete_codex_rule,
+            commands::reorder_codex_rules,
+            commands::estimate_rules_tokens,
+            commands::lint_rules,
+            commands::list_agents,
+            commands::list_agent_templates,
+            commands::read_agent,
+            commands::create_agent,
+            commands::write_agent,
+            commands::update_agent_frontmatter,
+            commands::delete_agent,
+            commands::copy_agent,
+            commands::validate_agent,
+            commands::list_slash_commands,
+            commands::read_slash_command,
+            commands::create_slash_command,
+            commands::write_slash_command,
+            commands::delete_slash_command,
+            commands::validate_slash_command,
+            commands::list_skills,
+            commands::read_skill,
+            commands::create_skill,
+            commands::write_skill,
+            commands::delete_skill,
+            commands::validate_skill,
+            commands::import_command_pack,
+            commands::list_codex_profiles,
+            commands::create_codex_profile,
+            commands::update_codex_profile,
+            commands::duplicate_codex_profile,
+            commands::delete_codex_profile,
+            commands::set_default_codex_profile,
+            commands::get_codex_quick_settings,
+            commands::get_codex_setting_options,
+            commands::set_codex_quick_setting,
+            commands::get_claude_settings,
+            commands::save_claude_settings,
+            commands::get_claude_settings_section,
+            commands::set_claude_settings_section,
+            commands::set_claude_env_var,
+            commands::list_claude_marketplaces,
+            commands::list_claude_plugins,
+            commands::set_claude_plugin_enabled,
+            commands::upsert_claude_marketplace,
+            commands::remove_claude_marketplace,
+            commands::get_claude_settings_schema,
+            commands::validate_claude_settings,
+            commands::get_statusline_status,
+            commands::install_statusline,
+            commands::uninstall_statusline,
+            commands::validate_config_text,
+            commands::probe_cli_versions,
+            commands::get_claude_auth_status,
+            commands::logout_claude_oauth,
+            commands::get_env_overview,
+            commands::set_settings_env_var,
+            commands::set_shell_env_export,
+            commands::reconcile_env_var,
+            commands::set_global_proxy,
+            commands::set_provider_proxy,
+            commands::test_proxy,
+            commands::render_markdown,
+            commands::test_notification_sink,
+            commands::parse_event_rule,
+            commands::create_snapshot,
+            commands::list_snapshots,
+            commands::restore_snapshot,
+            commands::restore_config_keys,
+            commands::get_startup_check,
+            commands::recover_config_file,
+            commands::prune_snapshots,
+            commands::list_write_conflicts,
+            commands::resolve_write_conflict,
+            commands::get_config_history_status,
+            commands::set_config_history,
+            commands::list_config_history,
+            commands::get_config_history_diff,
+            commands::purge_config_history,
+            commands::checkout_config_version,
+            commands::detect_workspace,
+            commands::get_project_env,
+            commands::get_profile_shell_hook,
+            commands::plan_workflow_migration,
+            commands::apply_workflow_migration,
+            commands::run_provider_health_checks,
+            commands::get_provider_trends,
+            commands::generate_setup_report,
+            commands::list_claude_caches,
+            commands::get_claude_cache_files,
+            commands::purge_claude_cache,
+            commands::list_codex_caches,
+            commands::purge_codex_cache,
+            commands::get_active_switch_schedules,
+            commands::get_switch_history,
+            commands::list_notifications,
+            commands::get_audit_log,
+            commands::get_recent_logs,
+            commands::open_log_folder,
+            commands::clear_notifications,
+            commands::switch_to_previous_provider,
+            commands::set_toggle_shortcut,
+            commands::set_focused_app,
+            commands::take_pending_deep_link,
+            commands::parse_provider_link,
+            commands::get_local_api_status,
+            commands::set_local_api,
+            commands::regenerate_local_api_token,
+            commands::get_sync_status,
+            commands::set_sync_backend,
+            commands::run_sync,
+            commands::list_undo_changes,
+            commands::rollback_last_change,
+            commands::export_rules,
+            commands::import_rules,
+            commands::get_secrets_status,
+            commands::unlock_secrets,
+            commands::lock_secrets,
+            commands::change_secrets_password,
+            commands::migrate_keys_to_vault,
+            commands::migrate_keys_to_plaintext,
+            commands::export_config_with_secrets,
+            commands::discover_providers,
+            commands::import_discovered_providers,
+            commands::scan_external_providers,
+            commands::parse_external_providers,
+            commands::detect_existing_setup,
+            commands::import_existing_setup,
+            commands::export_providers,
+            commands::import_providers,
+            commands::get_provider_groups,
+            commands::create_provider_group,
+            commands::rename_provider_group,
+            commands::delete_provider_group,
+            commands::reorder_provider_groups,
+            commands::move_providers_to_group,
+            commands::set_provider_group_disabled,
             // theirs: config import/export and dialogs
             import_export::export_config_to_file,
             import_export::import_config_from_file,
@@ -0,0 +1,476 @@
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use crate::app_config::AppType;
+use crate::store::AppState;
+
+/// 默认监听端口（仅绑定 127.0.0.1）
+pub const DEFAULT_PORT: u16 = 15721;
+const MAX_HEADER_BYTES: usize = 16 * 1024;
+const MAX_BODY_BYTES: usize = 64 * 1024;
+const REQUEST_TIMEOUT_SECS: u64 = 10;
+
+/// JSON-RPC 错误码
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const SERVER_ERROR: i64 = -32000;
+
+/// 本地自动化接口设置（默认关闭）
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 监听端口，默认 15721
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub port: Option<u16>,
+    /// 访问令牌，请求需携带 `Authorization: Bearer <token>`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+impl LocalApiSettings {
+    pub fn port(&self) -> u16 {
+        self.port.filter(|p| *p != 0).unwrap_or(DEFAULT_PORT)
+    }
+
+    /// 去除空白后的令牌；未设置或为空时返回 None
+    pub fn token(&self) -> Option<&str> {
+        self.token
+            .as_deref()
+            .map(str::trim)
+            .filter(|t| !t.is_empty())
+    }
+}
+
+/// 本地接口运行状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    /// JSON-RPC 地址，例如 http://127.0.0.1:15721/rpc
+    pub url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub token: Option<String>,
+}
+
+/// 当前供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ActiveProvider {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint: Option<String>,
+}
+
+/// 当前供应商的用量概况
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UsageStats {
+    #[serde(flatten)]
+    pub active: ActiveProvider,
+    /// 最近一次查询到的额度（可能已过期，参考 fetchedAt）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quota: Option<crate::provider_quota::ProviderQuota>,
+    /// 最近 24 小时内的切换次数
+    pub switches_last_24h: usize,
+}
+
+/// 正在运行的服务：监听端口与任务句柄
+static RUNNING: Mutex<Option<(u16, tauri::async_runtime::JoinHandle<()>)>> = Mutex::new(None);
+
+/// 生成随机访问令牌
+pub fn generate_token() -> String {
+    let mut bytes = [0u8; 32];
+    OsRng.fill_bytes(&mut bytes);
+    base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(bytes)
+}
+
+/// 按当前设置启动、重启或停止服务；端口不变时保持运行（令牌每次请求时读取）
+pub fn reload(app: &AppHandle) -> Result<(), String> {
+    let settings = crate::settings::get_settings()
+        .local_api
+        .unwrap_or_default();
+    let desired = (settings.enabled && settings.token().is_some()).then(|| settings.port());
+
+    let mut running = RUNNING.lock().map_err(crate::error::lock_failed)?;
+    if running.as_ref().map(|(port, _)| *port) == desired {
+        return Ok(());
+    }
+    if let Some((port, task)) = running.take() {
+        task.abort();
+        log::info!("本地接口已停止（端口 {}）", port);
+    }
+    let Some(port) = desired else {
+        return Ok(());
+    };
+
+    // 同步绑定，以便将端口占用等错误直接返回
+    let listener = std::net::TcpListener::bind(("127.0.0.1", port))
+        .map_err(|e| format!("本地接口监听端口 {} 失败: {}", port, e))?;
+    listener
+        .set_nonblocking(true)
+        .map_err(|e| format!("设置监听端口失败: {}", e))?;
+    let app = app.clone();
+    let task = tauri::async_runtime::spawn(async move {
+        let listener = match tokio::net::TcpListener::from_std(listener) {
+            Ok(listener) => listener,
+            Err(e) => {
+                log::error!("本地接口启动失败: {}", e);
+                return;
+            }
+        };
+        log::info!("本地接口已启动: http://127.0.0.1:{}/rpc", port);
+        loop {
+            match listener.accept().await {
+                Ok((stream, _)) => {
+                    let app = app.clone();
+                    tauri::async_runtime::spawn(handle_connection(app, stream));
+                }
+                Err(e) => log::warn!("本地接口接受连接失败: {}", e),
+            }
+        }
+    });
+    *running = Some((port, task));
+    Ok(())
+}
+
+/// 查询运行状态
+pub fn status() -> LocalApiStatus {
+    let settings = crate::settings::get_settings()
+        .local_api
+        .unwrap_or_default();
+    let port = settings.port();
+    let running = RUNNING.lock().ok().is_some_and(|running| running.is_some());
+    LocalApiStatus {
+        enabled: settings.enabled,
+        running,
+        port,
+        url: format!("http://127.0.0.1:{}/rpc", port),
+        token: settings.token().map(str::to_string),
+    }
+}
+
+struct HttpRequest {
+    method: String,
+    path: String,
+    authorization: Option<String>,
+    body: Vec<u8>,
+}
+
+fn find_header_end(buf: &[u8]) -> Option<usize> {
+    buf.windows(4).position(|w| w == b"\r\n\r\n")
+}
+
+/// 读取一个 HTTP/1.1 请求（不支持分块传输）
+async fn read_request(stream: &mut TcpStream) -> Result<HttpRequest, String> {
+    let mut buf = Vec::new();
+    let mut chunk = [0u8; 4096];
+    let header_end = loop {
+        if let Some(end) = find_header_end(&buf) {
+            break end;
+        }
+        if buf.len() > MAX_HEADER_BYTES {
+            return Err("请求头过大".to_string());
+        }
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("读取请求失败: {}", e))?;
+        if n == 0 {
+            return Err("连接已关闭".to_string());
+        }
+        buf.extend_from_slice(&chunk[..n]);
+    };
+
+    let head = String::from_utf8_lossy(&buf[..header_end]).to_string();
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next().unwrap_or("").split_whitespace();
+    let method = request_line.next().unwrap_or("").to_string();
+    let path = request_line.next().unwrap_or("").to_string();
+
+    let mut content_length = 0usize;
+    let mut authorization = None;
+    for line in lines {
+        let Some((name, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "content-length" => {
+                content_length = value
+                    .parse()
+                    .map_err(|_| "Content-Length 无效".to_string())?;
+            }
+            "authorization" => authorization = Some(value.to_string()),
+            _ => {}
+        }
+    }
+    if content_length > MAX_BODY_BYTES {
+        return Err("请求体过大".to_string());
+    }
+
+    let mut body = buf[header_end + 4..].to_vec();
+    while body.len() < content_length {
+        let n = stream
+            .read(&mut chunk)
+            .await
+            .map_err(|e| format!("读取请求失败: {}", e))?;
+        if n == 0 {
+            break;
+        }
+        body.extend_from_slice(&chunk[..n]);
+    }
+    body.truncate(content_length);
+
+    Ok(HttpRequest {
+        method,
+        path,
+        authorization,
+        body,
+    })
+}
+
+fn http_response(status: u16, body: &Value) -> Vec<u8> {
+    let reason = match status {
+        200 => "OK",
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        404 => "Not Found",
+        405 => "Method Not Allowed",
+        _ => "Internal Server Error",
+    };
+    let body = body.to_string();
+    format!(
+        "HTTP/1.1 {} {}\r\nContent-Type: application/json; charset=utf-8\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        reason,
+        body.len(),
+        body
+    )
+    .into_bytes()
+}
+
+/// 常量时间比较，避免通过响应时间猜测令牌
+fn token_matches(provided: &str, expected: &str) -> bool {
+    provided.len() == expected.len()
+        && provided
+            .bytes()
+            .zip(expected.bytes())
+            .fold(0u8, |acc, (a, b)| acc | (a ^ b))
+            == 0
+}
+
+fn authorized(request: &HttpRequest) -> bool {
+    let settings = crate::settings::get_settings()
+        .local_api
+        .unwrap_or_default();
+    let Some(expected) = settings.token() else {
+        return false;
+    };
+    request
+        .authorization
+        .as_deref()
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .is_some_and(|provided| token_matches(provided.trim(), expected))
+}
+
+async fn handle_connection(app: AppHandle, mut stream: TcpStream) {
+    let request = tokio::time::timeout(
+        Duration::from_secs(REQUEST_TIMEOUT_SECS),
+        read_request(&mut stream),
+    )
+    .await
+    .unwrap_or_else(|_| Err("读取请求超时".to_string()));
+
+    let response = match request {
+        Err(e) => http_response(400, &json!({ "error": e })),
+        Ok(request) if request.path != "/rpc" => {
+            http_response(404, &json!({ "error": "仅支持 /rpc" }))
+        }
+        Ok(request) if request.method != "POST" => {
+            http_response(405, &json!({ "error": "仅支持 POST" }))
+        }
+        Ok(request) if !authorized(&request) => {
+            http_response(401, &json!({ "error": "访问令牌无效" }))
+        }
+        Ok(request) => http_response(200, &handle_rpc(&app, &request.body).await),
+    };
+    if let Err(e) = stream.write_all(&response).await {
+        log::debug!("本地接口写入响应失败: {}", e);
+    }
+    let _ = stream.shutdown().await;
+}
+
+fn rpc_error(id: Value, code: i64, message: impl Into<String>) -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": id,
+        "error": { "code": code, "message": message.into() },
+    })
+}
+
+/// 处理一条 JSON-RPC 2.0 请求
+async fn handle_rpc(app: &AppHandle, body: &[u8]) -> Value {
+    let request: Value = match serde_json::from_slice(body) {
+        Ok(request) => request,
+        Err(e) => return rpc_error(Value::Null, PARSE_ERROR, format!("JSON 解析失败: {}", e)),
+    };
+    let id = request.get("id").cloned().unwrap_or(Value::Null);
+    let Some(method) = request.get("method").and_then(|m| m.as_str()) else {
+        return rpc_error(id, INVALID_REQUEST, "缺少 method");
+    };
+    let params = request.get("params").cloned().unwrap_or_else(|| json!({}));
+
+    let result = match method {
+        "getActive" => get_active(app, &params),
+        "switchProvider" => switch_provider(app, &params).await,
+        "usageStats" => usage_stats(app, &params),
+        _ => Err((METHOD_NOT_FOUND, format!("未知方法: {}", method))),
+    };
+    match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => rpc_error(id, code, message),
+    }
+}
+
+type RpcResult = Result<Value, (i64, String)>;
+
+fn param_str<'a>(params: &'a Value, key: &str) -> Option<&'a str> {
+    params
+        .get(key)
+        .and_then(|v| v.as_str())
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+}
+
+/// 解析 appType 参数；未指定时返回 None
+fn param_app(params: &Value) -> Result<Option<AppType>, (i64, String)> {
+    let Some(app) = param_str(params, "appType") else {
+        return Ok(None);
+    };
+    AppType::all()
+        .into_iter()
+        .find(|a| a.as_str() == app)
+        .map(Some)
+        .ok_or_else(|| (INVALID_PARAMS, format!("未知的应用类型: {}", app)))
+}
+
+fn to_value<T: Serialize>(value: T) -> RpcResult {
+    serde_json::to_value(value).map_err(|e| (SERVER_ERROR, format!("序列化失败: {}", e)))
+}
+
+/// 各应用的当前供应商；app_type 为空时返回全部应用
+fn active_providers(app: &AppHandle, app_type: Option<&AppType>) -> Vec<ActiveProvider> {
+    let state = app.state::<AppState>();
+    let Ok(config) = state.config.lock() else {
+        return Vec::new();
+    };
+    AppType::all()
+        .into_iter()
+        .filter(|a| app_type.is_none_or(|t| t.as_str() == a.as_str()))
+        .filter_map(|a| {
+            let manager = config.get_manager(&a)?;
+            let provider = manager.providers.get(&manager.current)?;
+            Some(ActiveProvider {
+                app_type: a.as_str().to_string(),
+                provider_id: provider.id.clone(),
+                provider_name: provider.name.clone(),
+                endpoint: provider.endpoint(&a),
+            })
+        })
+        .collect()
+}
+
+/// getActive：指定 appType 时返回单个对象，否则返回数组
+fn get_active(app: &AppHandle, params: &Value) -> RpcResult {
+    match param_app(params)? {
+        Some(app_type) => active_providers(app, Some(&app_type))
+            .into_iter()
+            .next()
+            .map(to_value)
+            .unwrap_or(Ok(Value::Null)),
+        None => to_value(active_providers(app, None)),
+    }
+}
+
+/// switchProvider：按 providerId 或 providerName 切换，appType 默认 claude
+async fn switch_provider(app: &AppHandle, params: &Value) -> RpcResult {
+    let app_type = param_app(params)?.unwrap_or(AppType::Claude);
+    let (provider_id, is_current) = {
+        let state = app.state::<AppState>();
+        let config = state
+            .config
+            .lock()
+            .map_err(|e| (SERVER_ERROR, crate::error::lock_failed(e)))?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| (SERVER_ERROR, crate::error::app_type_not_found(&app_type)))?;
+        let provider = if let Some(id) = param_str(params, "providerId") {
+            manager.providers.get(id)
+        } else if let Some(name) = param_str(params, "providerName") {
+            manager.providers.values().find(|p| p.name == name)
+        } else {
+            return Err((
+                INVALID_PARAMS,
+                "缺少 providerId 或 providerName".to_string(),
+            ));
+        };
+        let provider = provider.ok_or_else(|| {
+            let id = param_str(params, "providerId").or(param_str(params, "providerName"));
+            (
+                INVALID_PARAMS,
+                crate::error::provider_not_found(id.unwrap_or_default()),
+            )
+        })?;
+        (provider.id.clone(), provider.id == manager.current)
+    };
+    if !is_current {
+        crate::switch_provider_internal(
+            app,
+            app_type.clone(),
+            provider_id,
+            crate::switch_history::SwitchInitiator::Api,
+        )
+        .await
+        .map_err(|e| (SERVER_ERROR, e))?;
+    }
+    get_active(app, &json!({ "appType": app_type.as_str() }))
+}
+
+/// usageStats：当前供应商的缓存额度与最近 24 小时切换次数（不发起网络请求）
+fn usage_stats(app: &AppHandle, params: &Value) -> RpcResult {
+    let app_type = param_app(params)?;
+    let quotas = crate::provider_quota::list_cached_quotas();
+    let since = chrono::Utc::now().timestamp_millis() - 24 * 60 * 60 * 1000;
+    let history = crate::switch_history::get_history(None, None);
+    let stats: Vec<UsageStats> = active_providers(app, app_type.as_ref())
+        .into_iter()
+        .map(|active| UsageStats {
+            quota: quotas
+                .iter()
+                .find(|q| q.app_type == active.app_type && q.provider_id == active.provider_id)
+                .cloned(),
+            switches_last_24h: history
+                .iter()
+                .filter(|r| r.app_type == active.app_type && r.timestamp >= since)
+                .count(),
+            active,
+        })
+        .collect();
+    to_value(stats)
+}
@@ -0,0 +1,410 @@
+use reqwest::{Client, Url};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::process::{Child, Command, Stdio};
+use std::sync::{Mutex, OnceLock};
+use std::time::{Duration, Instant};
+
+use crate::provider::LocalServerConfig;
+
+/// 本地服务探测超时（秒）：本地服务响应应很快，过长等待只会拖慢切换
+const PROBE_TIMEOUT_SECS: u64 = 3;
+
+/// 默认就绪等待超时（秒）
+const DEFAULT_READY_TIMEOUT_SECS: u64 = 30;
+/// 就绪探测间隔（毫秒）
+const READY_POLL_INTERVAL_MS: u64 = 500;
+/// 停止服务时 SIGTERM 后等待进程组退出的时间，超时再 SIGKILL
+#[cfg(not(windows))]
+const TERMINATE_GRACE: Duration = Duration::from_secs(5);
+
+/// 由 cc-switch 启动的本地服务进程，键为 `<app>:<provider_id>`
+fn launched_servers() -> &'static Mutex<HashMap<String, LaunchedServer>> {
+    static LAUNCHED: OnceLock<Mutex<HashMap<String, LaunchedServer>>> = OnceLock::new();
+    LAUNCHED.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 本地推理服务状态
+#[derive(Debug, Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalServerStatus {
+    pub base_url: String,
+    pub running: bool,
+    /// 识别出的服务类型："ollama" / "lmstudio" / "openai-compatible"
+    pub kind: Option<String>,
+    pub models: Vec<String>,
+    pub error: Option<String>,
+}
+
+/// 判断端点是否指向本机（localhost / 回环地址 / 0.0.0.0）
+pub fn is_local_endpoint(url: &str) -> bool {
+    let Ok(parsed) = Url::parse(url.trim()) else {
+        return false;
+    };
+    let Some(host) = parsed.host_str() else {
+        return false;
+    };
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase();
+    if host == "localhost" || host.ends_with(".localhost") {
+        return true;
+    }
+    host.parse::<std::net::IpAddr>()
+        .map(|ip| ip.is_loopback() || ip.is_unspecified())
+        .unwrap_or(false)
+}
+
+fn build_client() -> Result<Client, String> {
+    Client::builder()
+        .timeout(Duration::from_secs(PROBE_TIMEOUT_SECS))
+        .no_proxy()
+        .user_agent("cc-switch-local-probe/1.0")
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {e}"))
+}
+
+/// 取 scheme://host:port 作为原生 API 的根地址
+fn origin_of(url: &Url) -> String {
+    let mut origin = format!(
+        "{}://{}",
+        url.scheme(),
+        url.host_str().unwrap_or("localhost")
+    );
+    if let Some(port) = url.port() {
+        origin.push_str(&format!(":{}", port));
+    }
+    origin
+}
+
+/// GET 并解析 JSON；连接失败返回 Err，非 2xx 或非 JSON 返回 Ok(None)
+async fn get_json(client: &Client, url: &str) -> Result<Option<Value>, reqwest::Error> {
+    let resp = client.get(url).send().await?;
+    if !resp.status().is_success() {
+        return Ok(None);
+    }
+    Ok(resp.json::<Value>().await.ok())
+}
+
+fn collect_names(list: Option<&Value>, key: &str) -> Vec<String> {
+    list.and_then(|v| v.as_array())
+        .map(|arr| {
+            arr.iter()
+                .filter_map(|m| m.get(key).and_then(|v| v.as_str()))
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// 探测本地推理服务：依次尝试 Ollama (/api/tags)、LM Studio (/api/v0/models)
+/// 与 OpenAI 兼容 (/v1/models) 的模型列表接口
+pub async fn probe_local_server(base_url: &str) -> LocalServerStatus {
+    let mut status = LocalServerStatus {
+        base_url: base_url.trim().to_string(),
+        running: false,
+        kind: None,
+        models: Vec::new(),
+        error: None,
+    };
+
+    let parsed = match Url::parse(base_url.trim()) {
+        Ok(url) => url,
+        Err(err) => {
+            status.error = Some(format!("URL 无效: {err}"));
+            return status;
+        }
+    };
+    let client = match build_client() {
+        Ok(client) => client,
+        Err(err) => {
+            status.error = Some(err);
+            return status;
+        }
+    };
+    let origin = origin_of(&parsed);
+
+    let probes: [(&str, String, &str, &str); 3] = [
+        ("ollama", format!("{}/api/tags", origin), "models", "name"),
+        (
+            "lmstudio",
+            format!("{}/api/v0/models", origin),
+            "data",
+            "id",
+        ),
+        (
+            "openai-compatible",
+            format!("{}/v1/models", origin),
+            "data",
+            "id",
+        ),
+    ];
+
+    for (kind, url, list_key, name_key) in probes {
+        match get_json(&client, &url).await {
+            Ok(Some(body)) => {
+                status.running = true;
+                status.kind = Some(kind.to_string());
+                status.models = collect_names(body.get(list_key), name_key);
+                status.error = None;
+                return status;
+            }
+            // 服务在线但不支持该接口，继续尝试下一种
+            Ok(None) => {
+                status.running = true;
+            }
+            Err(err) => {
+                if err.is_connect() || err.is_timeout() {
+                    status.error = Some(if err.is_timeout() {
+                        "请求超时".to_string()
+                    } else {
+                        "连接失败，本地服务可能未启动".to_string()
+                    });
+                    return status;
+                }
+                status.error = Some(err.to_string());
+            }
+        }
+    }
+
+    status
+}
+
+/// 切换前确认本地服务已在运行
+pub async fn ensure_running(base_url: &str) -> Result<LocalServerStatus, String> {
+    let status = probe_local_server(base_url).await;
+    if status.running {
+        Ok(status)
+    } else {
+        Err(format!(
+            "本地服务未运行: {} ({})",
+            base_url,
+            status.error.as_deref().unwrap_or("未知错误")
+        ))
+    }
+}
+
+/// 由 cc-switch 启动的本地服务。启动命令经 shell 执行，真正的服务往往是 shell 的子进程，
+/// 因此放入独立的进程组（Windows 上为作业对象），停止时结束整组进程
+struct LaunchedServer {
+    child: Child,
+    #[cfg(windows)]
+    job: Option<job::Job>,
+}
+
+impl LaunchedServer {
+    fn spawn(command: &str) -> std::io::Result<Self> {
+        #[cfg(windows)]
+        let mut cmd = {
+            let mut c = Command::new("cmd");
+            c.args(["/C", command]);
+            c
+        };
+        #[cfg(not(windows))]
+        let mut cmd = {
+            use std::os::unix::process::CommandExt;
+            let mut c = Command::new("sh");
+            c.args(["-c", command]);
+            // 以 shell 的 PID 作为新进程组 ID
+            c.process_group(0);
+            c
+        };
+        let child = cmd
+            .stdin(Stdio::null())
+            .stdout(Stdio::null())
+            .stderr(Stdio::null())
+            .spawn()?;
+
+        #[cfg(windows)]
+        let job = match job::Job::assign(&child) {
+            Ok(job) => Some(job),
+            Err(e) => {
+                log::warn!("创建作业对象失败，停止时仅结束启动进程: {}", e);
+                None
+            }
+        };
+        Ok(Self {
+            child,
+            #[cfg(windows)]
+            job,
+        })
+    }
+
+    fn try_wait(&mut self) -> std::io::Result<Option<std::process::ExitStatus>> {
+        self.child.try_wait()
+    }
+
+    /// 结束整个进程组并回收启动进程
+    fn kill(mut self) -> std::io::Result<()> {
+        #[cfg(not(windows))]
+        let result = {
+            // 负 PID 表示向整个进程组发送信号
+            let pgid = self.child.id() as libc::pid_t;
+            let signal = |signal: libc::c_int| {
+                if unsafe { libc::kill(-pgid, signal) } == 0 {
+                    Ok(())
+                } else {
+                    Err(std::io::Error::last_os_error())
+                }
+            };
+            // 先 SIGTERM，让服务保存状态、释放显存；宽限期后仍有进程存活再 SIGKILL
+            signal(libc::SIGTERM).and_then(|()| {
+                let deadline = Instant::now() + TERMINATE_GRACE;
+                while Instant::now() < deadline {
+                    // 回收已退出的启动进程，否则僵尸进程仍计入进程组
+                    let _ = self.child.try_wait();
+                    if signal(0).is_err() {
+                        return Ok(());
+                    }
+                    std::thread::sleep(Duration::from_millis(100));
+                }
+                signal(libc::SIGKILL)
+            })
+        };
+        #[cfg(windows)]
+        let result = match &self.job {
+            Some(job) => job.terminate(),
+            None => self.child.kill(),
+        };
+        // 进程组已全部退出时 kill 失败无关紧要，回退为只结束启动进程
+        let result = result.or_else(|_| self.child.kill());
+        let _ = self.child.wait();
+        result
+    }
+}
+
+#[cfg(windows)]
+mod job {
+    use std::os::windows::io::AsRawHandle;
+    use std::process::Child;
+    use windows_sys::Win32::Foundation::{CloseHandle, HANDLE};
+    use windows_sys::Win32::System::JobObjects::{
+        AssignProcessToJobObject, CreateJobObjectW, TerminateJobObject,
+    };
+
+    /// 作业对象句柄：启动进程及其后续创建的子进程都归属其中
+    pub struct Job(HANDLE);
+
+    // 句柄只在持有 launched_servers 锁时使用
+    unsafe impl Send for Job {}
+
+    impl Job {
+        pub fn assign(child: &Child) -> std::io::Result<Self> {
+            let handle = unsafe { CreateJobObjectW(std::ptr::null(), std::ptr::null()) };
+            if handle.is_null() {
+                return Err(std::io::Error::last_os_error());
+            }
+            let job = Job(handle);
+            if unsafe { AssignProcessToJobObject(job.0, child.as_raw_handle() as HANDLE) } == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(job)
+        }
+
+        pub fn terminate(&self) -> std::io::Result<()> {
+            if unsafe { TerminateJobObject(self.0, 1) } == 0 {
+                return Err(std::io::Error::last_os_error());
+            }
+            Ok(())
+        }
+    }
+
+    impl Drop for Job {
+        fn drop(&mut self) {
+            unsafe {
+                CloseHandle(self.0);
+            }
+        }
+    }
+}
+
+/// 确保本地服务可用：未运行时执行启动命令，并轮询探测直至就绪或超时
+pub async fn start_and_wait(
+    key: &str,
+    base_url: &str,
+    config: &LocalServerConfig,
+) -> Result<LocalServerStatus, String> {
+    let status = probe_local_server(base_url).await;
+    if status.running {
+        return Ok(status);
+    }
+
+    let command = config.start_command.trim();
+    if command.is_empty() {
+        return ensure_running(base_url).await;
+    }
+
+    log::info!("启动本地推理服务: {} ({})", command, key);
+    let server = LaunchedServer::spawn(command)
+        .map_err(|e| format!("执行启动命令失败: {}: {}", command, e))?;
+    let old = launched_servers()
+        .lock()
+        .map_err(crate::error::lock_failed)?
+        .insert(key.to_string(), server);
+    // 停止可能等待数秒，不在持锁期间进行
+    if let Some(old) = old {
+        let _ = old.kill();
+    }
+
+    let timeout = Duration::from_secs(
+        config
+            .ready_timeout_secs
+            .unwrap_or(DEFAULT_READY_TIMEOUT_SECS),
+    );
+    let started = Instant::now();
+    loop {
+        tokio::time::sleep(Duration::from_millis(READY_POLL_INTERVAL_MS)).await;
+
+        let status = probe_local_server(base_url).await;
+        if status.running {
+            return Ok(status);
+        }
+
+        // 启动命令失败退出则无需继续等待；正常退出（如 `lms server start` 等在后台启动服务后返回）
+        // 继续探测直至超时，保留记录以便停止仍在进程组中的服务
+        let exited = {
+            let mut launched = launched_servers()
+                .lock()
+                .map_err(crate::error::lock_failed)?;
+            match launched.get_mut(key).map(|s| s.try_wait()) {
+                Some(Ok(Some(exit))) if !exit.success() => {
+                    launched.remove(key);
+                    Some(exit)
+                }
+                _ => None,
+            }
+        };
+        if let Some(exit) = exited {
+            return Err(format!("本地服务启动命令已退出 ({}): {}", exit, command));
+        }
+
+        if started.elapsed() >= timeout {
+            stop_launched(key);
+            return Err(format!(
+                "等待本地服务就绪超时（{} 秒）: {}",
+                timeout.as_secs(),
+                base_url
+            ));
+        }
+    }
+}
+
+/// 停止由 cc-switch 启动的本地服务（非本程序启动的服务不受影响），返回是否执行了停止
+pub fn stop_launched(key: &str) -> bool {
+    let server = launched_servers()
+        .lock()
+        .ok()
+        .and_then(|mut launched| launched.remove(key));
+    match server {
+        Some(server) => {
+            if let Err(e) = server.kill() {
+                log::warn!("停止本地推理服务失败 ({}): {}", key, e);
+            }
+            log::info!("已停止本地推理服务: {}", key);
+            true
+        }
+        None => false,
+    }
+}
@@ -0,0 +1,234 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::OnceLock;
+use tracing_appender::rolling::{RollingFileAppender, Rotation};
+use tracing_subscriber::filter::{LevelFilter, Targets};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::reload;
+use tracing_subscriber::util::SubscriberInitExt;
+use tracing_subscriber::Registry;
+
+use crate::config::get_app_config_dir;
+
+/// 日志文件名前缀；按天轮换，文件为 `cc-switch.<日期>.log`
+const LOG_FILE_NAME: &str = "cc-switch";
+const LOG_FILE_SUFFIX: &str = "log";
+/// 最多保留的日志文件数（含当前文件）
+const KEEP_LOG_FILES: usize = 5;
+/// get_recent_logs 默认返回条数
+const DEFAULT_RECENT_LIMIT: usize = 500;
+/// 日志时间格式（本地时间）
+const TIMESTAMP_FORMAT: &str = "%Y-%m-%d %H:%M:%S";
+/// 依赖库的调试日志过于冗长，最多记录到 warn
+const NOISY_TARGETS: &[&str] = &["hyper", "hyper_util", "reqwest", "tao", "notify"];
+
+/// 运行时调整级别用的过滤器句柄
+static FILTER_HANDLE: OnceLock<reload::Handle<Targets, Registry>> = OnceLock::new();
+
+/// 日志级别（设置项）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum LogLevel {
+    Error,
+    Warn,
+    #[default]
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    fn filter(self) -> LevelFilter {
+        match self {
+            LogLevel::Error => LevelFilter::ERROR,
+            LogLevel::Warn => LevelFilter::WARN,
+            LogLevel::Info => LevelFilter::INFO,
+            LogLevel::Debug => LevelFilter::DEBUG,
+            LogLevel::Trace => LevelFilter::TRACE,
+        }
+    }
+
+    fn parse(tag: &str) -> Option<Self> {
+        match tag {
+            "ERROR" => Some(LogLevel::Error),
+            "WARN" => Some(LogLevel::Warn),
+            "INFO" => Some(LogLevel::Info),
+            "DEBUG" => Some(LogLevel::Debug),
+            "TRACE" => Some(LogLevel::Trace),
+            _ => None,
+        }
+    }
+}
+
+/// 解析后的一条日志
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LogEntry {
+    /// 本地时间，格式 `YYYY-MM-DD HH:MM:SS`
+    pub timestamp: String,
+    pub level: LogLevel,
+    /// 模块路径
+    pub target: String,
+    /// 日志内容（可能包含多行）
+    pub message: String,
+    /// 事件附带的结构化字段
+    #[serde(default, skip_serializing_if = "serde_json::Map::is_empty")]
+    pub fields: serde_json::Map<String, serde_json::Value>,
+}
+
+/// 日志目录 ~/.cc-switch/logs
+pub fn log_dir() -> PathBuf {
+    get_app_config_dir().join("logs")
+}
+
+fn configured_level() -> LogLevel {
+    crate::settings::get_settings()
+        .log_level
+        .unwrap_or_default()
+}
+
+fn targets_filter(level: LogLevel) -> Targets {
+    let level = level.filter();
+    NOISY_TARGETS
+        .iter()
+        .fold(Targets::new().with_default(level), |targets, target| {
+            targets.with_target(*target, level.min(LevelFilter::WARN))
+        })
+}
+
+/// 初始化 tracing：以 JSON 行写入 ~/.cc-switch/logs 并按天轮换；调试构建同时输出到终端。
+/// 现有的 `log` 宏调用经 tracing-log 桥接到同一订阅器
+pub fn init() -> Result<(), String> {
+    let appender = RollingFileAppender::builder()
+        .rotation(Rotation::DAILY)
+        .filename_prefix(LOG_FILE_NAME)
+        .filename_suffix(LOG_FILE_SUFFIX)
+        .max_log_files(KEEP_LOG_FILES)
+        .build(log_dir())
+        .map_err(|e| format!("创建日志文件失败: {}", e))?;
+    let timer = || tracing_subscriber::fmt::time::ChronoLocal::new(TIMESTAMP_FORMAT.to_string());
+
+    let (filter, handle) = reload::Layer::new(targets_filter(configured_level()));
+    let file_layer = tracing_subscriber::fmt::layer()
+        .json()
+        .with_timer(timer())
+        .with_current_span(false)
+        .with_span_list(false)
+        .with_writer(appender);
+    let stdout_layer = cfg!(debug_assertions).then(|| {
+        tracing_subscriber::fmt::layer()
+            .with_timer(timer())
+            .with_writer(std::io::stdout)
+    });
+
+    tracing_subscriber::registry()
+        .with(filter)
+        .with(file_layer)
+        .with(stdout_layer)
+        .try_init()
+        .map_err(|e| format!("初始化日志失败: {}", e))?;
+    let _ = FILTER_HANDLE.set(handle);
+    apply_level();
+    Ok(())
+}
+
+/// 按设置应用日志级别（启动后与保存设置时调用）
+pub fn apply_level() {
+    let level = configured_level();
+    if let Some(handle) = FILTER_HANDLE.get() {
+        if let Err(e) = handle.reload(targets_filter(level)) {
+            tracing::warn!("更新日志级别失败: {}", e);
+        }
+    }
+    // log 宏在桥接前按全局最大级别过滤
+    log::set_max_level(match level {
+        LogLevel::Error => log::LevelFilter::Error,
+        LogLevel::Warn => log::LevelFilter::Warn,
+        LogLevel::Info => log::LevelFilter::Info,
+        LogLevel::Debug => log::LevelFilter::Debug,
+        LogLevel::Trace => log::LevelFilter::Trace,
+    });
+}
+
+/// 按时间从新到旧列出日志文件
+fn log_files() -> Vec<PathBuf> {
+    let Ok(entries) = fs::read_dir(log_dir()) else {
+        return Vec::new();
+    };
+    let mut files: Vec<(PathBuf, std::time::SystemTime)> = entries
+        .filter_map(|e| e.ok())
+        .map(|e| e.path())
+        .filter(|p| {
+            p.file_name()
+                .map(|n| n.to_string_lossy())
+                .is_some_and(|n| n.starts_with(LOG_FILE_NAME) && n.ends_with(".log"))
+        })
+        .filter_map(|p| {
+            let modified = fs::metadata(&p).and_then(|m| m.modified()).ok()?;
+            Some((p, modified))
+        })
+        .collect();
+    files.sort_by(|a, b| b.1.cmp(&a.1));
+    files.into_iter().map(|(p, _)| p).collect()
+}
+
+/// 解析一行 JSON 日志：
+/// `{"timestamp":..,"level":..,"target":..,"fields":{"message":..,..}}`
+fn parse_line(line: &str) -> Option<LogEntry> {
+    #[derive(Deserialize)]
+    struct RawEntry {
+        timestamp: String,
+        level: String,
+        #[serde(default)]
+        target: String,
+        #[serde(default)]
+        fields: serde_json::Map<String, serde_json::Value>,
+    }
+
+    let mut raw: RawEntry = serde_json::from_str(line).ok()?;
+    let message = match raw.fields.remove("message") {
+        Some(serde_json::Value::String(text)) => text,
+        Some(other) => other.to_string(),
+        None => String::new(),
+    };
+    Some(LogEntry {
+        timestamp: raw.timestamp,
+        level: LogLevel::parse(&raw.level)?,
+        target: raw.target,
+        message,
+        fields: raw.fields,
+    })
+}
+
+/// 解析单个日志文件；无法识别的行（如旧格式日志）直接跳过
+fn parse_file(content: &str) -> Vec<LogEntry> {
+    content.lines().filter_map(parse_line).collect()
+}
+
+/// 最近的日志（最新在前）：只保留不低于 min_level 严重程度的记录，
+/// 必要时继续读取轮换前的旧文件
+pub fn recent(limit: Option<usize>, min_level: Option<LogLevel>) -> Vec<LogEntry> {
+    let limit = limit.unwrap_or(DEFAULT_RECENT_LIMIT);
+    let mut result = Vec::new();
+    for path in log_files() {
+        let content = match fs::read(&path) {
+            Ok(bytes) => String::from_utf8_lossy(&bytes).to_string(),
+            Err(e) => {
+                log::warn!("读取日志文件失败: {}: {}", path.display(), e);
+                continue;
+            }
+        };
+        result.extend(
+            parse_file(&content)
+                .into_iter()
+                .rev()
+                .filter(|e| min_level.is_none_or(|min| e.level <= min))
+                .take(limit - result.len()),
+        );
+        if result.len() >= limit {
+            break;
+        }
+    }
+    result
+}
@@ -0,0 +1,63 @@
+use pulldown_cmark::{html, CowStr, Event, Options, Parser, Tag};
+
+/// 允许出现在链接/图片地址中的协议，其余（如 javascript:、data:）一律替换为 `#`
+const SAFE_URL_SCHEMES: [&str; 3] = ["http:", "https:", "mailto:"];
+
+fn is_safe_url(url: &str) -> bool {
+    let lower = url.trim().to_ascii_lowercase();
+    match lower.find(|c| [':', '/', '?', '#'].contains(&c)) {
+        // 存在协议前缀时必须在白名单中
+        Some(pos) if lower.as_bytes()[pos] == b':' => {
+            SAFE_URL_SCHEMES.iter().any(|s| lower.starts_with(s))
+        }
+        // 相对路径与锚点
+        _ => true,
+    }
+}
+
+fn sanitize_url(url: CowStr<'_>) -> CowStr<'_> {
+    if is_safe_url(&url) {
+        url
+    } else {
+        CowStr::Borrowed("#")
+    }
+}
+
+/// 将 Markdown 渲染为净化后的 HTML：原始 HTML 按文本转义输出，不安全的链接地址被替换
+pub fn render_markdown(content: &str) -> String {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS
+        | Options::ENABLE_FOOTNOTES;
+
+    let events = Parser::new_ext(content, options).map(|event| match event {
+        Event::Html(raw) | Event::InlineHtml(raw) => Event::Text(raw),
+        Event::Start(Tag::Link {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Link {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        Event::Start(Tag::Image {
+            link_type,
+            dest_url,
+            title,
+            id,
+        }) => Event::Start(Tag::Image {
+            link_type,
+            dest_url: sanitize_url(dest_url),
+            title,
+            id,
+        }),
+        other => other,
+    });
+
+    let mut out = String::with_capacity(content.len() * 3 / 2);
+    html::push_html(&mut out, events);
+    out
+}
@@ -299,23 +299,15 @@ pub fn set_enabled_and_sync_for(
     }
 
     // 同步启用项
-    match app {
-        AppType::Claude => {
-            // 将启用项投影到 ~/.claude.json
-            sync_enabled_to_claude(config)?;
-        }
-        AppType::Codex => {
-            // 将启用项投影到 ~/.codex/config.toml
-            sync_enabled_to_codex(config)?;
-        }
-    }
+    sync_enabled_for(config, app)?;
     Ok(true)
 }
 
-/// 将 config.json 中 enabled==true 的项投影写入 ~/.claude.json
-pub fn sync_enabled_to_claude(config: &MultiAppConfig) -> Result<(), String> {
-    let enabled = collect_enabled_servers(&config.mcp.claude);
-    crate::claude_mcp::set_mcp_servers_map(&enabled)
+/// 将 config.json 中指定客户端的 enabled==true 项经适配器投影写入其 live 配置
+/// （~/.claude.json、~/.codex/config.toml、~/.gemini/settings.json 等）
+pub fn sync_enabled_for(config: &MultiAppConfig, app: &AppType) -> Result<(), String> {
+    let enabled = collect_enabled_servers(config.mcp_for(app));
+    crate::app_adapter::adapter_for(app).set_mcp_servers(&enabled)
 }
 
 /// 从 ~/.claude.json 导入 mcpServers 到 config.json（设为 enabled=true）。
@@ -560,18 +552,15 @@ pub fn import_from_codex(config: &mut MultiAppConfig) -> Result<usize, String> {
     Ok(changed_total)
 }
 
-/// 将 config.json 中 Codex 的 enabled==true 项以 TOML 形式写入 ~/.codex/config.toml 的 [mcp.servers]
+/// 将启用的 MCP 服务器以 TOML 形式写入 ~/.codex/config.toml 的 [mcp.servers]
 /// 策略：
 /// - 读取现有 config.toml；若语法无效则报错，不尝试覆盖
 /// - 仅更新 `mcp.servers` 或 `mcp_servers` 子表，保留 `mcp` 其它键
 /// - 仅写入启用项；无启用项时清理对应子表
-pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), String> {
+pub fn set_codex_mcp_servers(enabled: &HashMap<String, Value>) -> Result<(), String> {
     use toml::{value::Value as TomlValue, Table as TomlTable};
 
-    // 1) 收集启用项（Codex 维度）
-    let enabled = collect_enabled_servers(&config.mcp.codex);
-
-    // 2) 读取现有 config.toml 并解析为 Table（允许空文件）
+    // 1) 读取现有 config.toml 并解析为 Table（允许空文件）
     let base_text = crate::codex_config::read_and_validate_codex_config_text()?;
     let mut root: TomlTable = if base_text.trim().is_empty() {
         TomlTable::new()
@@ -580,7 +569,7 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), String> {
             .map_err(|e| format!("解析 config.toml 失败: {}", e))?
     };
 
-    // 3) 写入 servers 表（支持 mcp.servers 与 mcp_servers；优先沿用已有风格，默认 mcp_servers）
+    // 2) 写入 servers 表（支持 mcp.servers 与 mcp_servers；优先沿用已有风格，默认 mcp_servers）
     let prefer_mcp_servers = root.get("mcp_servers").is_some() || root.get("mcp").is_none();
     if enabled.is_empty() {
         // 无启用项：移除两种节点
@@ -722,7 +711,7 @@ pub fn sync_enabled_to_codex(config: &MultiAppConfig) -> Result<(), String> {
         }
     }
 
-    // 4) 序列化并写回 config.toml（仅改 TOML，不触碰 auth.json）
+    // 3) 序列化并写回 config.toml（仅改 TOML，不触碰 auth.json）
     let new_text = toml::to_string(&TomlValue::Table(root))
         .map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
     let path = crate::codex_config::get_codex_config_path();
@@ -0,0 +1,233 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashMap;
+use std::path::PathBuf;
+
+use crate::app_config::{AppType, MultiAppConfig};
+
+/// 随应用打包的 MCP 目录
+const BUNDLED_CATALOG: &str = include_str!("../resources/mcp_catalog.json");
+const REFRESH_TIMEOUT_SECS: u64 = 15;
+
+/// 安装时需要用户填写的变量；服务器定义中以 `<NAME>` 作为占位符
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpCatalogVariable {
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 是否为密钥（界面上以密码框输入）
+    #[serde(default)]
+    pub secret: bool,
+}
+
+/// MCP 目录条目
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpCatalogEntry {
+    pub id: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub homepage: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub docs: Option<String>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    /// 服务器连接定义（stdio/http），可含 `<NAME>` 占位符
+    pub server: Value,
+    #[serde(default)]
+    pub variables: Vec<McpCatalogVariable>,
+}
+
+/// MCP 目录（内置 + 远程刷新合并后的结果）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpCatalog {
+    pub version: u32,
+    /// 最近一次远程刷新的时间（毫秒时间戳）；从未刷新时为 None
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub refreshed_at: Option<i64>,
+    pub servers: Vec<McpCatalogEntry>,
+}
+
+/// 从目录安装 MCP 服务器的结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpInstallResult {
+    pub id: String,
+    pub apps: Vec<String>,
+    /// 所有变量均已填写时直接启用，否则以禁用状态写入，待用户补全占位符
+    pub enabled: bool,
+    pub missing_variables: Vec<String>,
+}
+
+/// 远程目录缓存 ~/.cc-switch/mcp_catalog.json
+fn cache_path() -> PathBuf {
+    crate::config::get_app_config_dir().join("mcp_catalog.json")
+}
+
+fn validate_catalog(catalog: &McpCatalog) -> Result<(), String> {
+    for entry in &catalog.servers {
+        if entry.id.trim().is_empty() {
+            return Err("MCP 目录条目缺少 id".to_string());
+        }
+        if !entry.server.is_object() {
+            return Err(format!(
+                "MCP 目录条目 {} 的 server 必须为 JSON 对象",
+                entry.id
+            ));
+        }
+    }
+    Ok(())
+}
+
+fn bundled_catalog() -> McpCatalog {
+    serde_json::from_str(BUNDLED_CATALOG).unwrap_or_else(|e| {
+        log::error!("解析内置 MCP 目录失败: {}", e);
+        McpCatalog::default()
+    })
+}
+
+/// 读取目录：内置目录叠加远程缓存（同 id 以远程为准）
+pub fn load_catalog() -> McpCatalog {
+    let mut catalog = bundled_catalog();
+    let path = cache_path();
+    if !path.exists() {
+        return catalog;
+    }
+    let remote = match crate::config::read_json_file::<McpCatalog>(&path) {
+        Ok(remote) => remote,
+        Err(e) => {
+            log::warn!("读取 MCP 目录缓存失败，使用内置目录: {}", e);
+            return catalog;
+        }
+    };
+    for entry in remote.servers {
+        match catalog.servers.iter_mut().find(|e| e.id == entry.id) {
+            Some(existing) => *existing = entry,
+            None => catalog.servers.push(entry),
+        }
+    }
+    catalog.version = catalog.version.max(remote.version);
+    catalog.refreshed_at = remote.refreshed_at;
+    catalog
+}
+
+/// 从远程地址下载目录并缓存，返回合并后的目录
+pub async fn refresh_catalog(url: &str) -> Result<McpCatalog, String> {
+    let url = url.trim();
+    if url.is_empty() {
+        return Err("未配置 MCP 目录地址".to_string());
+    }
+    let client = crate::speedtest::build_client(REFRESH_TIMEOUT_SECS)?;
+    let response = client
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| format!("下载 MCP 目录失败: {}", e))?;
+    if !response.status().is_success() {
+        return Err(format!(
+            "下载 MCP 目录失败: HTTP {}",
+            response.status().as_u16()
+        ));
+    }
+    let mut remote = response
+        .json::<McpCatalog>()
+        .await
+        .map_err(|e| format!("解析 MCP 目录失败: {}", e))?;
+    validate_catalog(&remote)?;
+    remote.refreshed_at = Some(chrono::Utc::now().timestamp_millis());
+    crate::config::write_json_file(&cache_path(), &remote)?;
+    Ok(load_catalog())
+}
+
+/// 将字符串中的 `<NAME>` 占位符替换为用户填写的值
+fn fill_placeholders(value: &mut Value, values: &HashMap<String, String>) {
+    match value {
+        Value::String(s) => {
+            for (name, val) in values {
+                let placeholder = format!("<{}>", name);
+                if s.contains(&placeholder) {
+                    *s = s.replace(&placeholder, val);
+                }
+            }
+        }
+        Value::Array(items) => items
+            .iter_mut()
+            .for_each(|item| fill_placeholders(item, values)),
+        Value::Object(map) => map
+            .values_mut()
+            .for_each(|item| fill_placeholders(item, values)),
+        _ => {}
+    }
+}
+
+/// 按目录条目生成 config.json 中的 MCP 条目，返回 (条目, 未填写的变量)
+fn build_entry(entry: &McpCatalogEntry, values: &HashMap<String, String>) -> (Value, Vec<String>) {
+    let values: HashMap<String, String> = values
+        .iter()
+        .filter(|(_, v)| !v.trim().is_empty())
+        .map(|(k, v)| (k.clone(), v.trim().to_string()))
+        .collect();
+    let mut server = entry.server.clone();
+    fill_placeholders(&mut server, &values);
+
+    let rendered = server.to_string();
+    let missing: Vec<String> = entry
+        .variables
+        .iter()
+        .filter(|v| rendered.contains(&format!("<{}>", v.name)))
+        .map(|v| v.name.clone())
+        .collect();
+
+    let mut obj = serde_json::Map::new();
+    obj.insert("id".to_string(), json!(entry.id));
+    obj.insert("name".to_string(), json!(entry.name));
+    for (key, value) in [
+        ("description", &entry.description),
+        ("homepage", &entry.homepage),
+        ("docs", &entry.docs),
+    ] {
+        if let Some(value) = value {
+            obj.insert(key.to_string(), json!(value));
+        }
+    }
+    if !entry.tags.is_empty() {
+        obj.insert("tags".to_string(), json!(entry.tags));
+    }
+    obj.insert("enabled".to_string(), json!(missing.is_empty()));
+    obj.insert("source".to_string(), json!("catalog"));
+    obj.insert("server".to_string(), server);
+    (Value::Object(obj), missing)
+}
+
+/// 将目录中的服务器写入各应用的 MCP 配置（config.json），同步到 live 由调用方完成
+pub fn install(
+    config: &mut MultiAppConfig,
+    catalog_id: &str,
+    apps: &[AppType],
+    values: &HashMap<String, String>,
+) -> Result<McpInstallResult, String> {
+    let catalog = load_catalog();
+    let entry = catalog
+        .servers
+        .iter()
+        .find(|e| e.id == catalog_id)
+        .ok_or_else(|| format!("MCP 目录中不存在: {}", catalog_id))?;
+    if apps.is_empty() {
+        return Err("请至少选择一个应用".to_string());
+    }
+
+    let (spec, missing) = build_entry(entry, values);
+    for app in apps {
+        crate::mcp::upsert_in_config_for(config, app, &entry.id, spec.clone())?;
+    }
+    Ok(McpInstallResult {
+        id: entry.id.clone(),
+        apps: apps.iter().map(|a| a.as_str().to_string()).collect(),
+        enabled: missing.is_empty(),
+        missing_variables: missing,
+    })
+}
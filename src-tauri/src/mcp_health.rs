@@ -0,0 +1,557 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::path::Path;
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// initialize 时声明的协议版本（服务器可协商为其支持的版本）
+const PROTOCOL_VERSION: &str = "2025-03-26";
+const DEFAULT_TIMEOUT_SECS: u64 = 20;
+const MAX_TIMEOUT_SECS: u64 = 120;
+/// stderr / 错误响应体最多保留的字符数
+const MAX_OUTPUT_CHARS: usize = 2000;
+
+const INITIALIZE_ID: u64 = 1;
+const TOOLS_LIST_ID: u64 = 2;
+
+/// MCP 服务器声明的工具
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpToolInfo {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+/// MCP 服务器健康检查结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct McpHealthResult {
+    pub ok: bool,
+    /// stdio 或 http
+    pub transport: String,
+    /// 完成 initialize 握手的耗时
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub server_version: Option<String>,
+    /// 服务器协商后的协议版本
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub protocol_version: Option<String>,
+    pub tools: Vec<McpToolInfo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// stdio 进程的 stderr 输出（截断，便于定位启动失败原因）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stderr: Option<String>,
+}
+
+fn initialize_request() -> Value {
+    json!({
+        "jsonrpc": "2.0",
+        "id": INITIALIZE_ID,
+        "method": "initialize",
+        "params": {
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": {},
+            "clientInfo": { "name": "cc-switch", "version": env!("CARGO_PKG_VERSION") },
+        },
+    })
+}
+
+fn initialized_notification() -> Value {
+    json!({ "jsonrpc": "2.0", "method": "notifications/initialized" })
+}
+
+fn tools_list_request() -> Value {
+    json!({ "jsonrpc": "2.0", "id": TOOLS_LIST_ID, "method": "tools/list", "params": {} })
+}
+
+fn truncate_chars(text: &str, max: usize) -> String {
+    let text = text.trim();
+    if text.chars().count() <= max {
+        return text.to_string();
+    }
+    let truncated: String = text.chars().take(max).collect();
+    format!("{}…", truncated)
+}
+
+/// 取 JSON-RPC 响应的 result；服务器返回 error 时转为错误信息
+fn rpc_result<'a>(response: &'a Value, method: &str) -> Result<&'a Value, String> {
+    if let Some(err) = response.get("error") {
+        let message = err
+            .get("message")
+            .and_then(|m| m.as_str())
+            .unwrap_or("未知错误");
+        return Err(format!("{} 失败: {}", method, message));
+    }
+    response
+        .get("result")
+        .ok_or_else(|| format!("{} 响应缺少 result 字段", method))
+}
+
+/// 记录 initialize 响应中的服务器信息，返回服务器是否声明了 tools 能力
+fn apply_initialize(response: &Value, result: &mut McpHealthResult) -> Result<bool, String> {
+    let init = rpc_result(response, "initialize")?;
+    let info = init.get("serverInfo");
+    let text = |v: Option<&Value>| v.and_then(|v| v.as_str()).map(|s| s.to_string());
+    result.server_name = text(info.and_then(|i| i.get("name")));
+    result.server_version = text(info.and_then(|i| i.get("version")));
+    result.protocol_version = text(init.get("protocolVersion"));
+    Ok(init
+        .get("capabilities")
+        .and_then(|c| c.get("tools"))
+        .is_some())
+}
+
+fn parse_tools(response: &Value) -> Result<Vec<McpToolInfo>, String> {
+    let list = rpc_result(response, "tools/list")?;
+    Ok(list
+        .get("tools")
+        .and_then(|t| t.as_array())
+        .map(|tools| {
+            tools
+                .iter()
+                .filter_map(|tool| {
+                    Some(McpToolInfo {
+                        name: tool.get("name")?.as_str()?.to_string(),
+                        description: tool
+                            .get("description")
+                            .and_then(|d| d.as_str())
+                            .map(|s| s.to_string()),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default())
+}
+
+/// 看起来仍是模板占位符的值（如 `<YOUR_API_KEY>`、`${TOKEN}`）
+fn is_placeholder(value: &str) -> bool {
+    let v = value.trim();
+    (v.starts_with('<') && v.ends_with('>')) || (v.starts_with("${") && v.ends_with('}'))
+}
+
+/// 读取字符串键值对象（env / headers），空值或占位符视为配置错误
+fn string_pairs(spec: &Value, field: &str, label: &str) -> Result<Vec<(String, String)>, String> {
+    let Some(value) = spec.get(field).filter(|v| !v.is_null()) else {
+        return Ok(Vec::new());
+    };
+    let obj = value
+        .as_object()
+        .ok_or_else(|| format!("{} 字段必须为对象", field))?;
+    let mut pairs = Vec::new();
+    for (key, value) in obj {
+        let value = value
+            .as_str()
+            .ok_or_else(|| format!("{} {} 的值必须为字符串", label, key))?;
+        if value.trim().is_empty() {
+            return Err(format!("{} {} 未设置值", label, key));
+        }
+        if is_placeholder(value) {
+            return Err(format!("{} {} 仍为占位符: {}", label, key, value));
+        }
+        pairs.push((key.clone(), value.to_string()));
+    }
+    Ok(pairs)
+}
+
+/// 检查 MCP 服务器：启动命令（stdio）或连接 URL（http），完成 initialize 握手并列出工具。
+/// 配置错误、启动失败与超时均体现在返回结果的 error 中
+pub async fn check_server(spec: &Value, timeout_secs: Option<u64>) -> McpHealthResult {
+    let timeout = Duration::from_secs(
+        timeout_secs
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .clamp(1, MAX_TIMEOUT_SECS),
+    );
+    let transport = spec
+        .get("type")
+        .and_then(|t| t.as_str())
+        .unwrap_or("stdio")
+        .to_string();
+    let mut result = McpHealthResult {
+        transport: transport.clone(),
+        ..Default::default()
+    };
+
+    let outcome = match transport.as_str() {
+        "stdio" => {
+            let spec = spec.clone();
+            match tauri::async_runtime::spawn_blocking(move || {
+                let mut result = McpHealthResult::default();
+                let outcome = check_stdio(&spec, timeout, &mut result);
+                (result, outcome)
+            })
+            .await
+            {
+                Ok((checked, outcome)) => {
+                    result = McpHealthResult {
+                        transport: transport.clone(),
+                        ..checked
+                    };
+                    outcome
+                }
+                Err(e) => Err(format!("检查任务异常: {}", e)),
+            }
+        }
+        "http" => check_http(spec, timeout, &mut result).await,
+        other => Err(format!("不支持的 MCP 服务器类型: {}", other)),
+    };
+
+    match outcome {
+        Ok(()) => result.ok = true,
+        Err(e) => result.error = Some(e),
+    }
+    result
+}
+
+// ==================== stdio ====================
+
+/// 与子进程的 JSON-RPC 会话；drop 时结束子进程
+struct StdioSession {
+    child: Child,
+    stdin: ChildStdin,
+    lines: mpsc::Receiver<String>,
+    deadline: Instant,
+    /// stdout 中第一条非 JSON 输出（stdio 服务器不应向 stdout 打印日志）
+    stray_output: Option<String>,
+}
+
+impl StdioSession {
+    fn send(&mut self, message: &Value) -> Result<(), String> {
+        writeln!(self.stdin, "{}", message)
+            .and_then(|_| self.stdin.flush())
+            .map_err(|e| format!("写入 MCP 服务器 stdin 失败: {}", e))
+    }
+
+    fn wait_response(&mut self, id: u64, method: &str) -> Result<Value, String> {
+        loop {
+            let remaining = self.deadline.saturating_duration_since(Instant::now());
+            match self.lines.recv_timeout(remaining) {
+                Ok(line) => {
+                    let line = line.trim();
+                    if line.is_empty() {
+                        continue;
+                    }
+                    match serde_json::from_str::<Value>(line) {
+                        Ok(value) if value.get("id").and_then(|v| v.as_u64()) == Some(id) => {
+                            return Ok(value)
+                        }
+                        Ok(_) => {}
+                        Err(_) => {
+                            if self.stray_output.is_none() {
+                                self.stray_output = Some(truncate_chars(line, 200));
+                            }
+                        }
+                    }
+                }
+                Err(RecvTimeoutError::Timeout) => {
+                    let mut message = format!("等待 {} 响应超时", method);
+                    if let Some(stray) = &self.stray_output {
+                        message.push_str(&format!("（stdout 中出现非 JSON 输出: {}）", stray));
+                    }
+                    return Err(message);
+                }
+                Err(RecvTimeoutError::Disconnected) => {
+                    // stdout 已关闭：稍候读取退出码
+                    std::thread::sleep(Duration::from_millis(100));
+                    let code = self.child.try_wait().ok().flatten().and_then(|s| s.code());
+                    return Err(match code {
+                        Some(code) => format!("MCP 服务器进程已退出（退出码 {}）", code),
+                        None => "MCP 服务器关闭了 stdout".to_string(),
+                    });
+                }
+            }
+        }
+    }
+}
+
+impl Drop for StdioSession {
+    fn drop(&mut self) {
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+fn build_command(command: &str) -> Command {
+    // Windows 上 npx 等为 .cmd 脚本，需经 cmd 启动
+    if cfg!(windows) {
+        let mut c = Command::new("cmd");
+        c.args(["/C", command]);
+        c
+    } else {
+        Command::new(command)
+    }
+}
+
+fn check_stdio(
+    spec: &Value,
+    timeout: Duration,
+    result: &mut McpHealthResult,
+) -> Result<(), String> {
+    let command = spec
+        .get("command")
+        .and_then(|c| c.as_str())
+        .map(str::trim)
+        .filter(|c| !c.is_empty())
+        .ok_or_else(|| "stdio 类型的 MCP 服务器缺少 command 字段".to_string())?;
+    if !crate::claude_mcp::validate_command_in_path(command)? {
+        return Err(format!(
+            "未找到可执行文件: {}（请确认已安装且位于 PATH 中）",
+            command
+        ));
+    }
+    let args: Vec<String> = match spec.get("args") {
+        None | Some(Value::Null) => Vec::new(),
+        Some(Value::Array(items)) => items
+            .iter()
+            .map(|v| {
+                v.as_str()
+                    .map(|s| s.to_string())
+                    .ok_or_else(|| "args 必须为字符串数组".to_string())
+            })
+            .collect::<Result<_, _>>()?,
+        Some(_) => return Err("args 必须为字符串数组".to_string()),
+    };
+    let env = string_pairs(spec, "env", "环境变量")?;
+
+    let mut cmd = build_command(command);
+    cmd.args(&args)
+        .envs(env)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    if let Some(cwd) = spec
+        .get("cwd")
+        .and_then(|c| c.as_str())
+        .filter(|c| !c.trim().is_empty())
+    {
+        if !Path::new(cwd).is_dir() {
+            return Err(format!("工作目录不存在: {}", cwd));
+        }
+        cmd.current_dir(cwd);
+    }
+
+    let mut child = cmd
+        .spawn()
+        .map_err(|e| format!("启动 MCP 服务器失败: {}: {}", command, e))?;
+    let stdin = child.stdin.take().ok_or("无法获取子进程 stdin")?;
+    let stdout = child.stdout.take().ok_or("无法获取子进程 stdout")?;
+    let stderr = child.stderr.take().ok_or("无法获取子进程 stderr")?;
+
+    // 读取线程随子进程结束而退出，不等待其结束（子进程派生的进程可能仍持有管道）
+    let (tx, lines) = mpsc::channel();
+    std::thread::spawn(move || {
+        for line in BufReader::new(stdout).lines() {
+            let Ok(line) = line else { break };
+            if tx.send(line).is_err() {
+                break;
+            }
+        }
+    });
+    let stderr_buf = Arc::new(Mutex::new(String::new()));
+    {
+        let stderr_buf = Arc::clone(&stderr_buf);
+        std::thread::spawn(move || {
+            let mut reader = BufReader::new(stderr);
+            let mut chunk = [0u8; 4096];
+            while let Ok(n) = reader.read(&mut chunk) {
+                if n == 0 {
+                    break;
+                }
+                if let Ok(mut buf) = stderr_buf.lock() {
+                    buf.push_str(&String::from_utf8_lossy(&chunk[..n]));
+                    // 仅保留尾部
+                    if buf.len() > MAX_OUTPUT_CHARS * 8 {
+                        let cut = buf.len() - MAX_OUTPUT_CHARS * 4;
+                        let cut = (cut..buf.len())
+                            .find(|i| buf.is_char_boundary(*i))
+                            .unwrap_or(buf.len());
+                        buf.drain(..cut);
+                    }
+                }
+            }
+        });
+    }
+
+    let started = Instant::now();
+    let mut session = StdioSession {
+        child,
+        stdin,
+        lines,
+        deadline: started + timeout,
+        stray_output: None,
+    };
+    let outcome: Result<(), String> = (|| {
+        session.send(&initialize_request())?;
+        let init = session.wait_response(INITIALIZE_ID, "initialize")?;
+        result.latency_ms = Some(started.elapsed().as_millis());
+        let has_tools = apply_initialize(&init, result)?;
+        session.send(&initialized_notification())?;
+        if has_tools {
+            session.send(&tools_list_request())?;
+            let list = session.wait_response(TOOLS_LIST_ID, "tools/list")?;
+            result.tools = parse_tools(&list)?;
+        }
+        Ok(())
+    })();
+    drop(session);
+
+    if outcome.is_err() {
+        // 给 stderr 读取线程一点时间收尾
+        std::thread::sleep(Duration::from_millis(100));
+    }
+    let stderr_text = stderr_buf.lock().map(|b| b.clone()).unwrap_or_default();
+    if !stderr_text.trim().is_empty() {
+        let tail: Vec<char> = stderr_text.trim().chars().collect();
+        let start = tail.len().saturating_sub(MAX_OUTPUT_CHARS);
+        result.stderr = Some(tail[start..].iter().collect());
+    }
+    outcome
+}
+
+// ==================== http ====================
+
+/// 发送 JSON-RPC 消息（Streamable HTTP）。响应可能是 JSON 或 SSE 流；
+/// 通知（id 为 None）只检查状态码。返回响应与服务器分配的会话 id
+async fn post_rpc(
+    client: &reqwest::Client,
+    url: &str,
+    headers: &[(String, String)],
+    session_id: Option<&str>,
+    message: &Value,
+    id: Option<u64>,
+) -> Result<(Option<Value>, Option<String>), String> {
+    let mut request = client
+        .post(url)
+        .header("Accept", "application/json, text/event-stream")
+        .json(message);
+    for (key, value) in headers {
+        request = request.header(key, value);
+    }
+    if let Some(session_id) = session_id {
+        request = request.header("Mcp-Session-Id", session_id);
+    }
+    let mut response = request.send().await.map_err(|e| {
+        if e.is_timeout() {
+            "连接 MCP 服务器超时".to_string()
+        } else if e.is_connect() {
+            format!("无法连接 MCP 服务器: {}", e)
+        } else {
+            format!("请求 MCP 服务器失败: {}", e)
+        }
+    })?;
+
+    let status = response.status();
+    let new_session = response
+        .headers()
+        .get("mcp-session-id")
+        .and_then(|v| v.to_str().ok())
+        .map(|s| s.to_string());
+    if !status.is_success() {
+        let body = response.text().await.unwrap_or_default();
+        let mut message = format!("HTTP {}", status.as_u16());
+        if !body.trim().is_empty() {
+            message.push_str(&format!(": {}", truncate_chars(&body, MAX_OUTPUT_CHARS)));
+        }
+        return Err(message);
+    }
+    let Some(id) = id else {
+        return Ok((None, new_session));
+    };
+
+    let is_sse = response
+        .headers()
+        .get(reqwest::header::CONTENT_TYPE)
+        .and_then(|v| v.to_str().ok())
+        .is_some_and(|ct| ct.contains("text/event-stream"));
+    if !is_sse {
+        let value = response
+            .json::<Value>()
+            .await
+            .map_err(|e| format!("解析 MCP 响应失败: {}", e))?;
+        return Ok((Some(value), new_session));
+    }
+
+    // SSE：逐块读取，直到出现目标 id 的 data 事件
+    let mut buffer = String::new();
+    loop {
+        let chunk = response
+            .chunk()
+            .await
+            .map_err(|e| format!("读取 MCP 事件流失败: {}", e))?;
+        let Some(chunk) = chunk else {
+            return Err("MCP 事件流在收到响应前结束".to_string());
+        };
+        buffer.push_str(&String::from_utf8_lossy(&chunk));
+        while let Some(pos) = buffer.find('\n') {
+            let line: String = buffer.drain(..=pos).collect();
+            let Some(data) = line.trim().strip_prefix("data:") else {
+                continue;
+            };
+            if let Ok(value) = serde_json::from_str::<Value>(data.trim()) {
+                if value.get("id").and_then(|v| v.as_u64()) == Some(id) {
+                    return Ok((Some(value), new_session));
+                }
+            }
+        }
+    }
+}
+
+async fn check_http(
+    spec: &Value,
+    timeout: Duration,
+    result: &mut McpHealthResult,
+) -> Result<(), String> {
+    let url = spec
+        .get("url")
+        .and_then(|u| u.as_str())
+        .map(str::trim)
+        .filter(|u| !u.is_empty())
+        .ok_or_else(|| "http 类型的 MCP 服务器缺少 url 字段".to_string())?;
+    let headers = string_pairs(spec, "headers", "请求头")?;
+    let client = crate::speedtest::build_client(timeout.as_secs())?;
+
+    let started = Instant::now();
+    let (init, session_id) = post_rpc(
+        &client,
+        url,
+        &headers,
+        None,
+        &initialize_request(),
+        Some(INITIALIZE_ID),
+    )
+    .await?;
+    result.latency_ms = Some(started.elapsed().as_millis());
+    let init = init.ok_or("initialize 无响应")?;
+    let has_tools = apply_initialize(&init, result)?;
+
+    let session_id = session_id.as_deref();
+    post_rpc(
+        &client,
+        url,
+        &headers,
+        session_id,
+        &initialized_notification(),
+        None,
+    )
+    .await?;
+    if has_tools {
+        let (list, _) = post_rpc(
+            &client,
+            url,
+            &headers,
+            session_id,
+            &tools_list_request(),
+            Some(TOOLS_LIST_ID),
+        )
+        .await?;
+        result.tools = parse_tools(&list.ok_or("tools/list 无响应")?)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,284 @@
+use reqwest::Client;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+
+/// 外发通知请求超时（秒）
+const SINK_TIMEOUT_SECS: u64 = 10;
+/// 默认最大重试次数（不含首次发送）
+const DEFAULT_MAX_RETRIES: u32 = 3;
+/// 重试初始退避（毫秒），之后每次翻倍
+const RETRY_BASE_DELAY_MS: u64 = 1000;
+
+/// 可订阅的事件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationEvent {
+    /// 切换供应商
+    Switch,
+    /// 自动故障切换
+    Failover,
+    /// 预算/额度告警
+    BudgetAlert,
+    /// 事件规则触发的通知
+    Rule,
+}
+
+impl NotificationEvent {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            NotificationEvent::Switch => "switch",
+            NotificationEvent::Failover => "failover",
+            NotificationEvent::BudgetAlert => "budgetAlert",
+            NotificationEvent::Rule => "rule",
+        }
+    }
+}
+
+/// 通知渠道类型，决定默认的请求体格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SinkKind {
+    /// POST 完整 JSON 负载
+    Webhook,
+    /// Slack 兼容的 Incoming Webhook（{"text": ...}）
+    Slack,
+    /// ntfy：纯文本正文 + Title 头
+    Ntfy,
+}
+
+/// 外发通知渠道配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSink {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub kind: SinkKind,
+    pub url: String,
+    #[serde(default = "default_sink_enabled")]
+    pub enabled: bool,
+    /// 订阅的事件，为空表示全部事件
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub events: Vec<NotificationEvent>,
+    /// 自定义请求体模板，支持 {{event}} {{title}} {{message}} {{app}} {{provider}} {{timestamp}}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub template: Option<String>,
+    /// 附加请求头（如鉴权）
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_retries: Option<u32>,
+}
+
+fn default_sink_enabled() -> bool {
+    true
+}
+
+impl NotificationSink {
+    fn subscribes(&self, event: NotificationEvent) -> bool {
+        self.enabled && (self.events.is_empty() || self.events.contains(&event))
+    }
+}
+
+/// 通知负载
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationPayload {
+    pub event: NotificationEvent,
+    pub title: String,
+    pub message: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_name: Option<String>,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+}
+
+impl NotificationPayload {
+    pub fn new(
+        event: NotificationEvent,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            event,
+            title: title.into(),
+            message: message.into(),
+            app_type: None,
+            provider_id: None,
+            provider_name: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn with_provider(mut self, app_type: &str, id: &str, name: &str) -> Self {
+        self.app_type = Some(app_type.to_string());
+        self.provider_id = Some(id.to_string());
+        self.provider_name = Some(name.to_string());
+        self
+    }
+}
+
+/// 渲染模板；模板为 JSON 时对替换值做 JSON 字符串转义
+fn render_template(template: &str, payload: &NotificationPayload) -> String {
+    let is_json = matches!(template.trim_start().chars().next(), Some('{') | Some('['));
+    let escape = |value: &str| -> String {
+        if is_json {
+            let quoted = serde_json::to_string(value).unwrap_or_default();
+            quoted[1..quoted.len() - 1].to_string()
+        } else {
+            value.to_string()
+        }
+    };
+    let timestamp = chrono::DateTime::from_timestamp_millis(payload.timestamp)
+        .map(|t| t.to_rfc3339())
+        .unwrap_or_default();
+    let vars = [
+        ("event", payload.event.as_str().to_string()),
+        ("title", payload.title.clone()),
+        ("message", payload.message.clone()),
+        ("app", payload.app_type.clone().unwrap_or_default()),
+        (
+            "provider",
+            payload
+                .provider_name
+                .clone()
+                .or_else(|| payload.provider_id.clone())
+                .unwrap_or_default(),
+        ),
+        ("timestamp", timestamp),
+    ];
+
+    let mut out = template.to_string();
+    for (key, value) in vars {
+        out = out.replace(&format!("{{{{{}}}}}", key), &escape(&value));
+    }
+    out
+}
+
+/// 构造请求体与 Content-Type
+fn build_body(
+    sink: &NotificationSink,
+    payload: &NotificationPayload,
+) -> Result<(String, &'static str), String> {
+    if let Some(template) = sink.template.as_deref().filter(|t| !t.trim().is_empty()) {
+        let body = render_template(template, payload);
+        let content_type = if serde_json::from_str::<serde_json::Value>(&body).is_ok() {
+            "application/json"
+        } else {
+            "text/plain; charset=utf-8"
+        };
+        return Ok((body, content_type));
+    }
+
+    match sink.kind {
+        SinkKind::Webhook => serde_json::to_string(payload)
+            .map(|body| (body, "application/json"))
+            .map_err(|e| format!("序列化通知失败: {}", e)),
+        SinkKind::Slack => {
+            let text = format!("*{}*\n{}", payload.title, payload.message);
+            Ok((
+                serde_json::json!({ "text": text }).to_string(),
+                "application/json",
+            ))
+        }
+        // 非 ASCII 标题无法放入 Title 请求头，改为写在正文首行
+        SinkKind::Ntfy if !payload.title.is_ascii() => Ok((
+            format!("{}\n{}", payload.title, payload.message),
+            "text/plain; charset=utf-8",
+        )),
+        SinkKind::Ntfy => Ok((payload.message.clone(), "text/plain; charset=utf-8")),
+    }
+}
+
+async fn send_once(
+    client: &Client,
+    sink: &NotificationSink,
+    body: &str,
+    content_type: &str,
+    title: &str,
+) -> Result<(), String> {
+    let mut request = client
+        .post(sink.url.trim())
+        .header("Content-Type", content_type)
+        .body(body.to_string());
+    if sink.kind == SinkKind::Ntfy && sink.template.is_none() && title.is_ascii() {
+        // ntfy 通过 Title 请求头传递标题
+        request = request.header("Title", title.replace(['\r', '\n'], " "));
+    }
+    for (key, value) in &sink.headers {
+        request = request.header(key.as_str(), value.as_str());
+    }
+
+    let resp = request
+        .send()
+        .await
+        .map_err(|e| format!("发送通知失败: {}", e))?;
+    if resp.status().is_success() {
+        Ok(())
+    } else {
+        Err(format!("通知渠道返回错误状态: {}", resp.status()))
+    }
+}
+
+/// 发送到单个渠道（带重试与指数退避）
+pub async fn send_to_sink(
+    sink: &NotificationSink,
+    payload: &NotificationPayload,
+) -> Result<(), String> {
+    if sink.url.trim().is_empty() {
+        return Err("通知渠道 URL 不能为空".to_string());
+    }
+    let (body, content_type) = build_body(sink, payload)?;
+    let client = Client::builder()
+        .timeout(Duration::from_secs(SINK_TIMEOUT_SECS))
+        .user_agent("cc-switch-notify/1.0")
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+    let max_retries = sink.max_retries.unwrap_or(DEFAULT_MAX_RETRIES);
+    let mut attempt = 0u32;
+    loop {
+        match send_once(&client, sink, &body, content_type, &payload.title).await {
+            Ok(()) => return Ok(()),
+            Err(err) if attempt >= max_retries => return Err(err),
+            Err(err) => {
+                let delay = RETRY_BASE_DELAY_MS.saturating_mul(1u64 << attempt.min(10));
+                log::warn!(
+                    "通知渠道 {} 发送失败，{} 毫秒后重试 ({}/{}): {}",
+                    sink.id,
+                    delay,
+                    attempt + 1,
+                    max_retries,
+                    err
+                );
+                tokio::time::sleep(Duration::from_millis(delay)).await;
+                attempt += 1;
+            }
+        }
+    }
+}
+
+/// 向所有订阅该事件的渠道异步发送通知（不阻塞调用方）
+pub fn dispatch(payload: NotificationPayload) {
+    let sinks: Vec<NotificationSink> = crate::settings::get_settings()
+        .notification_sinks
+        .into_iter()
+        .filter(|s| s.subscribes(payload.event))
+        .collect();
+    if sinks.is_empty() {
+        return;
+    }
+
+    tauri::async_runtime::spawn(async move {
+        for sink in sinks {
+            if let Err(err) = send_to_sink(&sink, &payload).await {
+                log::warn!("通知渠道 {} 发送最终失败: {}", sink.id, err);
+            }
+        }
+    });
+}
@@ -0,0 +1,188 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tauri::Emitter;
+use tauri_plugin_notification::NotificationExt;
+
+use crate::config::get_app_config_dir;
+
+/// 新通知（前端据此更新事件日志）
+pub const EVENT_NOTIFICATION: &str = "notification";
+
+/// 额度低于该百分比时提醒
+const DEFAULT_QUOTA_LOW_PERCENT: f64 = 10.0;
+
+/// 序列化追加 notifications.jsonl
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 事件日志文件 ~/.cc-switch/notifications.jsonl（每行一条记录，只追加）
+fn get_log_path() -> PathBuf {
+    get_app_config_dir().join("notifications.jsonl")
+}
+
+/// 后台事件的通知类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum NotificationCategory {
+    /// 自动故障转移
+    Failover,
+    /// 定时切换
+    ScheduledSwitch,
+    /// 定时快照完成
+    BackupCompleted,
+    /// live 配置被外部修改
+    ExternalChange,
+    /// 额度即将用尽
+    QuotaLow,
+}
+
+/// 通知设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationSettings {
+    /// 是否弹出系统通知；关闭后仍写入事件日志
+    #[serde(default = "default_os_enabled")]
+    pub os_enabled: bool,
+    /// 不弹出系统通知的类别
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub muted: Vec<NotificationCategory>,
+    /// 额度剩余低于该百分比时提醒（默认 10）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_low_percent: Option<f64>,
+}
+
+fn default_os_enabled() -> bool {
+    true
+}
+
+impl Default for NotificationSettings {
+    fn default() -> Self {
+        Self {
+            os_enabled: true,
+            muted: Vec::new(),
+            quota_low_percent: None,
+        }
+    }
+}
+
+/// 事件日志中的一条通知
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationRecord {
+    pub category: NotificationCategory,
+    pub title: String,
+    pub message: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub app_type: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+}
+
+impl NotificationRecord {
+    pub fn new(
+        category: NotificationCategory,
+        title: impl Into<String>,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            category,
+            title: title.into(),
+            message: message.into(),
+            app_type: None,
+            provider_id: None,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        }
+    }
+
+    pub fn with_provider(mut self, app_type: &str, provider_id: &str) -> Self {
+        self.app_type = Some(app_type.to_string());
+        self.provider_id = Some(provider_id.to_string());
+        self
+    }
+}
+
+/// 额度提醒阈值（百分比）
+pub fn quota_low_percent() -> f64 {
+    crate::settings::get_settings()
+        .notifications
+        .quota_low_percent
+        .filter(|p| *p > 0.0)
+        .unwrap_or(DEFAULT_QUOTA_LOW_PERCENT)
+}
+
+fn append(record: &NotificationRecord) {
+    let line = match serde_json::to_string(record) {
+        Ok(line) => line,
+        Err(e) => {
+            log::warn!("序列化通知失败: {}", e);
+            return;
+        }
+    };
+    let _guard = LOG_LOCK.lock();
+    let path = get_log_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = result {
+        log::warn!("写入事件日志失败: {}: {}", path.display(), e);
+    }
+}
+
+/// 发出通知：写入事件日志并转发到前端，按设置弹出系统通知
+pub fn notify(record: NotificationRecord) {
+    append(&record);
+    let Some(app) = crate::events::handle() else {
+        return;
+    };
+    if let Err(e) = app.emit(EVENT_NOTIFICATION, &record) {
+        log::warn!("转发通知到前端失败: {}", e);
+    }
+
+    let settings = crate::settings::get_settings().notifications;
+    if !settings.os_enabled || settings.muted.contains(&record.category) {
+        return;
+    }
+    if let Err(e) = app
+        .notification()
+        .builder()
+        .title(&record.title)
+        .body(&record.message)
+        .show()
+    {
+        log::warn!("发送系统通知失败: {}", e);
+    }
+}
+
+/// 读取事件日志（最新在前）；limit 为空时不限制条数
+pub fn list(limit: Option<usize>) -> Vec<NotificationRecord> {
+    let content = {
+        let _guard = LOG_LOCK.lock();
+        fs::read_to_string(get_log_path()).unwrap_or_default()
+    };
+    content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<NotificationRecord>(line).ok())
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// 清空事件日志
+pub fn clear() -> Result<(), String> {
+    let _guard = LOG_LOCK.lock();
+    let path = get_log_path();
+    if path.exists() {
+        fs::remove_file(&path).map_err(|e| format!("清空事件日志失败: {}", e))?;
+    }
+    Ok(())
+}
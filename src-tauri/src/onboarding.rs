@@ -0,0 +1,295 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider_discovery::{DiscoverySource, ProviderCandidate};
+
+/// 现有配置使用的认证方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum AuthKind {
+    /// 配置文件中的 API Key / Auth Token
+    ApiKey,
+    /// 官方账号登录（Claude 订阅、ChatGPT 登录）
+    OAuth,
+    None,
+}
+
+/// 检测到的规则文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct DetectedRule {
+    pub name: String,
+    pub path: String,
+    pub size: u64,
+}
+
+/// 单个应用的现有配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AppSetupReport {
+    pub app_type: String,
+    pub config_dir: String,
+    /// 配置目录是否存在
+    pub installed: bool,
+    pub auth: AuthKind,
+    /// API Key 仅显示首尾几位
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub masked_key: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub base_url: Option<String>,
+    pub models: Vec<String>,
+    /// 由 live 配置生成的候选供应商（官方登录且无自定义配置时为 None）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider: Option<ProviderCandidate>,
+    pub rules: Vec<DetectedRule>,
+    /// live 配置中的 MCP 服务器 id
+    pub mcp_servers: Vec<String>,
+    /// 子代理文件名（仅 Claude）
+    pub agents: Vec<String>,
+    /// 斜杠命令（仅 Claude）
+    pub commands: Vec<String>,
+}
+
+/// 首次运行时检测到的现有配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExistingSetupReport {
+    /// cc-switch 中尚无任何供应商
+    pub first_run: bool,
+    pub apps: Vec<AppSetupReport>,
+    /// 来自环境变量的候选供应商
+    pub env_candidates: Vec<ProviderCandidate>,
+}
+
+/// 一键导入的结果
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupImportResult {
+    /// 新增的供应商 { appType, id }
+    pub providers: Vec<Value>,
+    /// 设为当前供应商的应用
+    pub current_set: Vec<String>,
+    pub mcp_imported: usize,
+    /// 非致命错误（某一项导入失败不影响其他项）
+    pub warnings: Vec<String>,
+}
+
+pub(crate) fn mask_secret(secret: &str) -> String {
+    let chars: Vec<char> = secret.chars().collect();
+    if chars.len() <= 8 {
+        return "****".to_string();
+    }
+    let head: String = chars[..4].iter().collect();
+    let tail: String = chars[chars.len() - 4..].iter().collect();
+    format!("{}…{}", head, tail)
+}
+
+fn detected_rule(path: &Path) -> Option<DetectedRule> {
+    let meta = std::fs::metadata(path).ok().filter(|m| m.is_file())?;
+    Some(DetectedRule {
+        name: path.file_name()?.to_string_lossy().to_string(),
+        path: path.to_string_lossy().to_string(),
+        size: meta.len(),
+    })
+}
+
+/// Claude 官方登录：~/.claude/.credentials.json，或 ~/.claude.json 中的 oauthAccount（macOS 凭据在钥匙串中）
+fn claude_oauth_present() -> bool {
+    if crate::config::get_claude_config_dir()
+        .join(".credentials.json")
+        .is_file()
+    {
+        return true;
+    }
+    crate::config::read_json_file::<Value>(&crate::claude_mcp::user_config_path())
+        .ok()
+        .is_some_and(|v| v.get("oauthAccount").is_some_and(|a| !a.is_null()))
+}
+
+/// Codex ChatGPT 登录：auth.json 中存在 tokens
+fn codex_oauth_present() -> bool {
+    crate::config::read_json_file::<Value>(&crate::codex_config::get_codex_auth_path())
+        .ok()
+        .is_some_and(|v| v.get("tokens").is_some_and(|t| !t.is_null()))
+}
+
+fn live_mcp_ids(app: &AppType) -> Vec<String> {
+    let mut ids: Vec<String> = match app {
+        AppType::Claude => crate::claude_mcp::read_mcp_json()
+            .ok()
+            .flatten()
+            .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+            .and_then(|v| v.get("mcpServers").and_then(|m| m.as_object()).cloned())
+            .map(|m| m.keys().cloned().collect())
+            .unwrap_or_default(),
+        AppType::Codex => crate::codex_config::read_codex_config_text()
+            .ok()
+            .and_then(|text| toml::from_str::<toml::Table>(&text).ok())
+            .map(|root| {
+                // 兼容 [mcp_servers.<id>] 与 [mcp.servers.<id>]
+                let direct = root.get("mcp_servers").and_then(|v| v.as_table());
+                let nested = root
+                    .get("mcp")
+                    .and_then(|v| v.get("servers"))
+                    .and_then(|v| v.as_table());
+                direct
+                    .into_iter()
+                    .chain(nested)
+                    .flat_map(|t| t.keys().cloned())
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    };
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+fn app_report(app: AppType, candidates: &[ProviderCandidate]) -> AppSetupReport {
+    let adapter = crate::app_adapter::adapter_for(&app);
+    let config_dir = adapter.config_dir();
+    let live_source = match app {
+        AppType::Claude => DiscoverySource::ClaudeSettings,
+        _ => DiscoverySource::CodexLive,
+    };
+    let provider = candidates
+        .iter()
+        .find(|c| c.app_type == app.as_str() && c.source == live_source)
+        .cloned();
+
+    let settings = provider.as_ref().map(|c| &c.provider.settings_config);
+    let key = settings.and_then(|s| adapter.api_key(s));
+    let oauth = match app {
+        AppType::Claude => claude_oauth_present(),
+        _ => codex_oauth_present(),
+    };
+    let base_url = settings.and_then(|s| adapter.base_url(s));
+    let models = settings.map(|s| adapter.models(s)).unwrap_or_default();
+    let auth = if key.is_some() {
+        AuthKind::ApiKey
+    } else if oauth {
+        AuthKind::OAuth
+    } else {
+        AuthKind::None
+    };
+
+    let mut rules = Vec::new();
+    let mut agents = Vec::new();
+    let mut commands = Vec::new();
+    match app {
+        AppType::Claude => {
+            rules.extend(adapter.rules_path().and_then(|p| detected_rule(&p)));
+            agents = crate::agents::list_agents(None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|a| a.file_name)
+                .collect();
+            commands = crate::claude_commands::list_commands(None)
+                .unwrap_or_default()
+                .into_iter()
+                .map(|c| c.command)
+                .collect();
+        }
+        _ => {
+            rules.extend(detected_rule(&config_dir.join("AGENTS.md")));
+            rules.extend(
+                crate::global_rules::list_codex_rules()
+                    .unwrap_or_default()
+                    .iter()
+                    .filter_map(|r| detected_rule(Path::new(&r.path))),
+            );
+        }
+    }
+
+    AppSetupReport {
+        app_type: app.as_str().to_string(),
+        installed: config_dir.is_dir(),
+        config_dir: config_dir.to_string_lossy().to_string(),
+        auth,
+        masked_key: key.as_deref().map(mask_secret),
+        base_url,
+        models,
+        provider,
+        rules,
+        mcp_servers: live_mcp_ids(&app),
+        agents,
+        commands,
+    }
+}
+
+/// 检测 ~/.claude 与 ~/.codex 的现有配置：当前供应商、端点与认证方式、规则、MCP 服务器与子代理
+pub fn detect_existing_setup(config: &MultiAppConfig) -> ExistingSetupReport {
+    let candidates = crate::provider_discovery::discover_providers(config);
+    let first_run = [AppType::Claude, AppType::Codex].iter().all(|app| {
+        config
+            .get_manager(app)
+            .is_none_or(|m| m.providers.is_empty())
+    });
+    ExistingSetupReport {
+        first_run,
+        apps: vec![
+            app_report(AppType::Claude, &candidates),
+            app_report(AppType::Codex, &candidates),
+        ],
+        env_candidates: candidates
+            .iter()
+            .filter(|c| c.source == DiscoverySource::Env)
+            .cloned()
+            .collect(),
+    }
+}
+
+/// 一键导入：导入 live 配置（及可选的环境变量）生成的供应商并在尚无当前供应商时设为当前，
+/// 同时导入 Claude / Codex 的 MCP 服务器。规则、子代理等文件保留在原位置，无需导入
+pub fn import_existing_setup(
+    config: &mut MultiAppConfig,
+    include_env: bool,
+) -> SetupImportResult {
+    let mut result = SetupImportResult::default();
+    let report = detect_existing_setup(config);
+
+    let live: Vec<ProviderCandidate> = report
+        .apps
+        .iter()
+        .filter_map(|app| app.provider.clone())
+        .filter(|c| c.duplicate_of.is_none())
+        .collect();
+    for (app_type, id) in crate::provider_discovery::import_candidates(config, live) {
+        // live 配置即当前正在使用的供应商
+        if let Some(manager) = config.get_manager_mut(&AppType::from(app_type.as_str())) {
+            if manager.current.is_empty() {
+                manager.current = id.clone();
+                result.current_set.push(app_type.clone());
+            }
+        }
+        result
+            .providers
+            .push(serde_json::json!({ "appType": app_type, "id": id }));
+    }
+
+    if include_env {
+        let env: Vec<ProviderCandidate> = report
+            .env_candidates
+            .into_iter()
+            .filter(|c| c.duplicate_of.is_none())
+            .collect();
+        for (app_type, id) in crate::provider_discovery::import_candidates(config, env) {
+            result
+                .providers
+                .push(serde_json::json!({ "appType": app_type, "id": id }));
+        }
+    }
+
+    match crate::mcp::import_from_claude(config) {
+        Ok(n) => result.mcp_imported += n,
+        Err(e) => result.warnings.push(format!("导入 Claude MCP 失败: {}", e)),
+    }
+    match crate::mcp::import_from_codex(config) {
+        Ok(n) => result.mcp_imported += n,
+        Err(e) => result.warnings.push(format!("导入 Codex MCP 失败: {}", e)),
+    }
+    result
+}
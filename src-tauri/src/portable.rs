@@ -0,0 +1,158 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+/// 便携模式标记文件，位于可执行文件所在目录
+const PORTABLE_MARKER: &str = "portable.ini";
+
+/// 未指定 data_dir 时的数据目录（相对于可执行文件所在目录）
+const DEFAULT_DATA_DIR: &str = "data";
+
+/// 便携模式状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PortableInfo {
+    pub enabled: bool,
+    /// 标记文件路径
+    pub marker_path: String,
+    /// 当前生效的数据目录（未启用时为 None）
+    pub data_dir: Option<String>,
+}
+
+fn exe_dir() -> Option<PathBuf> {
+    std::env::current_exe()
+        .ok()
+        .and_then(|exe| exe.parent().map(Path::to_path_buf))
+}
+
+fn marker_path() -> Option<PathBuf> {
+    exe_dir().map(|dir| dir.join(PORTABLE_MARKER))
+}
+
+/// 读取 portable.ini 中的 data_dir（忽略 [section]、注释与空行）
+fn parse_data_dir(text: &str) -> Option<String> {
+    text.lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty() && !line.starts_with(['#', ';', '[']))
+        .filter_map(|line| line.split_once('='))
+        .find(|(key, _)| key.trim() == "data_dir")
+        .map(|(_, value)| value.trim().trim_matches('"').to_string())
+        .filter(|value| !value.is_empty())
+}
+
+/// 解析数据目录：相对路径相对于可执行文件所在目录，便于整个目录随 U 盘或同步盘移动
+fn resolve_data_dir(exe_dir: &Path, raw: Option<&str>) -> PathBuf {
+    let raw = raw.unwrap_or(DEFAULT_DATA_DIR);
+    let path = PathBuf::from(raw);
+    if path.is_absolute() {
+        path
+    } else {
+        exe_dir.join(path)
+    }
+}
+
+fn detect() -> Option<PathBuf> {
+    let dir = exe_dir()?;
+    let marker = dir.join(PORTABLE_MARKER);
+    if !marker.is_file() {
+        return None;
+    }
+    let text = fs::read_to_string(&marker).unwrap_or_default();
+    let data_dir = resolve_data_dir(&dir, parse_data_dir(&text).as_deref());
+    log::info!("便携模式：数据目录 {}", data_dir.display());
+    Some(data_dir)
+}
+
+/// 便携模式下的数据目录；启动时检测一次，切换便携模式需重启生效
+pub fn data_dir() -> Option<PathBuf> {
+    static DATA_DIR: OnceLock<Option<PathBuf>> = OnceLock::new();
+    DATA_DIR.get_or_init(detect).clone()
+}
+
+pub fn is_enabled() -> bool {
+    data_dir().is_some()
+}
+
+pub fn info() -> PortableInfo {
+    PortableInfo {
+        enabled: is_enabled(),
+        marker_path: marker_path()
+            .map(|p| p.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        data_dir: data_dir().map(|p| p.to_string_lossy().to_string()),
+    }
+}
+
+/// 递归复制目录（目标中已存在的文件保留不覆盖）
+fn copy_dir(src: &Path, dest: &Path) -> Result<(), String> {
+    fs::create_dir_all(dest).map_err(|e| format!("创建目录失败: {}: {}", dest.display(), e))?;
+    let entries =
+        fs::read_dir(src).map_err(|e| format!("读取目录失败: {}: {}", src.display(), e))?;
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let target = dest.join(entry.file_name());
+        if path.is_dir() {
+            copy_dir(&path, &target)?;
+        } else if !target.exists() {
+            fs::copy(&path, &target)
+                .map_err(|e| format!("复制文件失败: {}: {}", path.display(), e))?;
+        }
+    }
+    Ok(())
+}
+
+/// 启用便携模式：写入 portable.ini，可选将当前配置目录复制到数据目录。重启后生效
+pub fn enable(data_dir: Option<&str>, copy_existing: bool) -> Result<PortableInfo, String> {
+    let dir = exe_dir().ok_or_else(|| "获取可执行文件目录失败".to_string())?;
+    let data_dir = data_dir.map(str::trim).filter(|s| !s.is_empty());
+    let target = resolve_data_dir(&dir, data_dir);
+
+    if copy_existing {
+        let current = crate::config::get_app_config_dir();
+        if target.starts_with(&current) && target != current {
+            return Err("数据目录不能位于当前配置目录内部".to_string());
+        }
+        if current.is_dir() && current != target {
+            copy_dir(&current, &target)?;
+        }
+        // 配置目录被覆盖时 settings.json 仍在 ~/.cc-switch，需单独复制
+        let settings = crate::settings::AppSettings::settings_path();
+        let settings_target = target.join("settings.json");
+        if settings.is_file() && !settings_target.exists() {
+            fs::copy(&settings, &settings_target)
+                .map_err(|e| format!("复制设置文件失败: {}", e))?;
+        }
+    } else {
+        fs::create_dir_all(&target)
+            .map_err(|e| format!("创建数据目录失败: {}: {}", target.display(), e))?;
+    }
+
+    let mut content = String::from("[portable]\n");
+    content.push_str("# 数据目录，相对路径相对于本文件所在目录\n");
+    content.push_str(&format!("data_dir={}\n", data_dir.unwrap_or(DEFAULT_DATA_DIR)));
+    let marker = dir.join(PORTABLE_MARKER);
+    fs::write(&marker, content).map_err(|e| {
+        format!(
+            "写入 {} 失败（可执行文件目录可能不可写）: {}",
+            marker.display(),
+            e
+        )
+    })?;
+
+    Ok(PortableInfo {
+        enabled: true,
+        marker_path: marker.to_string_lossy().to_string(),
+        data_dir: Some(target.to_string_lossy().to_string()),
+    })
+}
+
+/// 关闭便携模式：删除 portable.ini（数据目录保留）。重启后生效
+pub fn disable() -> Result<(), String> {
+    let marker = marker_path().ok_or_else(|| "获取可执行文件目录失败".to_string())?;
+    if marker.exists() {
+        fs::remove_file(&marker)
+            .map_err(|e| format!("删除 {} 失败: {}", marker.display(), e))?;
+    }
+    Ok(())
+}
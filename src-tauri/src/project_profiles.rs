@@ -0,0 +1,250 @@
+//! 项目级供应商：按工作区（见 `workspaces`）为当前目录生成环境变量，
+//! 由 shell 钩子在进入项目目录时注入，不修改全局 live 配置。
+
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+
+use crate::app_config::{AppType, MultiAppConfig};
+
+/// 当前生效的工作区 id
+pub const PROFILE_ENV: &str = "CC_SWITCH_PROFILE";
+/// 上次注入的变量名（逗号分隔），离开项目目录时据此清除
+pub const PROFILE_VARS_ENV: &str = "CC_SWITCH_PROFILE_VARS";
+
+/// 输出的 shell 语法
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ShellKind {
+    /// bash / zsh / sh
+    Posix,
+    Fish,
+    PowerShell,
+}
+
+impl ShellKind {
+    pub fn parse(value: &str) -> Result<Self, String> {
+        match value {
+            "sh" | "bash" | "zsh" | "posix" => Ok(Self::Posix),
+            "fish" => Ok(Self::Fish),
+            "powershell" | "pwsh" => Ok(Self::PowerShell),
+            other => Err(format!(
+                "不支持的 shell: {}（可选 bash、zsh、fish、powershell）",
+                other
+            )),
+        }
+    }
+}
+
+/// 某个目录对应的项目环境
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProjectEnv {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub workspace_name: Option<String>,
+    /// 需注入的环境变量（未命中工作区时为空）
+    pub vars: BTreeMap<String, String>,
+    /// 无法注入的原因，例如供应商不存在或 API Key 已加密
+    pub warnings: Vec<String>,
+}
+
+fn is_env_name(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// 计算 cwd 所在工作区绑定的供应商对应的环境变量：
+/// Claude / Gemini 取 settings.env 中的全部字符串项；Codex 取 OPENAI_API_KEY 与 OPENAI_BASE_URL；
+/// OpenCode / Crush 的凭据只存在于其配置文件中，不导出环境变量
+pub fn project_env(config: &MultiAppConfig, cwd: &str) -> ProjectEnv {
+    let Some(workspace) = crate::workspaces::match_workspace(cwd) else {
+        return ProjectEnv::default();
+    };
+    let mut env = ProjectEnv {
+        workspace_id: Some(workspace.id.clone()),
+        workspace_name: Some(workspace.name.clone()),
+        ..Default::default()
+    };
+
+    for app_type in AppType::all() {
+        let Some(provider_id) = workspace.provider_for(&app_type) else {
+            continue;
+        };
+        let Some(provider) = config
+            .get_manager(&app_type)
+            .and_then(|m| m.providers.get(provider_id))
+        else {
+            env.warnings.push(format!(
+                "{} {}",
+                app_type.as_str(),
+                crate::error::provider_not_found(provider_id)
+            ));
+            continue;
+        };
+        if provider.needs_key() {
+            env.warnings
+                .push(format!("供应商 {} 尚未填写 API Key", provider.name));
+            continue;
+        }
+        let settings = match crate::secrets::resolve_settings(&app_type, &provider.settings_config)
+        {
+            Ok(settings) => settings,
+            Err(e) => {
+                env.warnings.push(format!(
+                    "供应商 {} 的 API Key 无法读取: {}",
+                    provider.name, e
+                ));
+                continue;
+            }
+        };
+
+        match app_type {
+            AppType::Claude | AppType::Gemini => {
+                if let Some(map) = settings.get("env").and_then(|v| v.as_object()) {
+                    for (key, value) in map {
+                        if let Some(value) = value.as_str().filter(|_| is_env_name(key)) {
+                            env.vars.insert(key.clone(), value.to_string());
+                        }
+                    }
+                }
+            }
+            AppType::Codex => {
+                if let Some(key) = settings
+                    .pointer("/auth/OPENAI_API_KEY")
+                    .and_then(|v| v.as_str())
+                    .filter(|s| !s.is_empty())
+                {
+                    env.vars
+                        .insert("OPENAI_API_KEY".to_string(), key.to_string());
+                }
+                if let Some(url) = provider.endpoint(&app_type) {
+                    env.vars.insert("OPENAI_BASE_URL".to_string(), url);
+                }
+            }
+            AppType::OpenCode | AppType::Crush => {}
+        }
+    }
+
+    if !env.vars.is_empty() {
+        env.vars
+            .insert(PROFILE_ENV.to_string(), workspace.id.clone());
+    }
+    env
+}
+
+pub(crate) fn quote(shell: ShellKind, value: &str) -> String {
+    match shell {
+        ShellKind::Posix => format!("'{}'", value.replace('\'', "'\\''")),
+        ShellKind::Fish => format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'")),
+        ShellKind::PowerShell => format!("'{}'", value.replace('\'', "''")),
+    }
+}
+
+fn export_line(shell: ShellKind, name: &str, value: &str) -> String {
+    let value = quote(shell, value);
+    match shell {
+        ShellKind::Posix => format!("export {}={}\n", name, value),
+        ShellKind::Fish => format!("set -gx {} {}\n", name, value),
+        ShellKind::PowerShell => format!("$env:{} = {}\n", name, value),
+    }
+}
+
+fn unset_line(shell: ShellKind, name: &str) -> String {
+    match shell {
+        ShellKind::Posix => format!("unset {}\n", name),
+        ShellKind::Fish => format!("set -e {}\n", name),
+        ShellKind::PowerShell => {
+            format!("Remove-Item Env:{} -ErrorAction SilentlyContinue\n", name)
+        }
+    }
+}
+
+/// 生成供 shell eval 的脚本：清除上次注入但本次不再需要的变量，再导出本次的变量。
+/// previous 为上次注入的变量名（取自 CC_SWITCH_PROFILE_VARS）
+pub fn render_env(env: &ProjectEnv, previous: &[String], shell: ShellKind) -> String {
+    let mut out = String::new();
+    for name in previous {
+        if is_env_name(name) && !env.vars.contains_key(name) {
+            out.push_str(&unset_line(shell, name));
+        }
+    }
+    for (name, value) in &env.vars {
+        out.push_str(&export_line(shell, name, value));
+    }
+    if env.vars.is_empty() {
+        if !previous.is_empty() {
+            out.push_str(&unset_line(shell, PROFILE_VARS_ENV));
+        }
+    } else {
+        let names: Vec<&str> = env.vars.keys().map(|k| k.as_str()).collect();
+        out.push_str(&export_line(shell, PROFILE_VARS_ENV, &names.join(",")));
+    }
+    out
+}
+
+/// 生成 shell 钩子：进入目录时调用 `cc-switch profile env` 并 eval 其输出
+pub fn shell_hook(shell: &str, exe: &str) -> Result<String, String> {
+    let hook = match shell {
+        "bash" => format!(
+            r#"_cc_switch_profile() {{
+  if [[ "$PWD" != "$_CC_SWITCH_LAST_PWD" ]]; then
+    _CC_SWITCH_LAST_PWD="$PWD"
+    eval "$({exe} profile env --shell sh)"
+  fi
+}}
+if [[ ";${{PROMPT_COMMAND}};" != *";_cc_switch_profile;"* ]]; then
+  PROMPT_COMMAND="_cc_switch_profile${{PROMPT_COMMAND:+;$PROMPT_COMMAND}}"
+fi
+"#,
+            exe = quote(ShellKind::Posix, exe)
+        ),
+        "zsh" => format!(
+            r#"_cc_switch_profile() {{
+  eval "$({exe} profile env --shell sh)"
+}}
+autoload -Uz add-zsh-hook
+add-zsh-hook chpwd _cc_switch_profile
+_cc_switch_profile
+"#,
+            exe = quote(ShellKind::Posix, exe)
+        ),
+        "fish" => format!(
+            r#"function __cc_switch_profile --on-variable PWD
+    {exe} profile env --shell fish | source
+end
+__cc_switch_profile
+"#,
+            exe = quote(ShellKind::Fish, exe)
+        ),
+        "powershell" | "pwsh" => format!(
+            r#"$global:__CcSwitchLastPwd = $null
+$global:__CcSwitchPrompt = $function:prompt
+function global:prompt {{
+    if ($PWD.Path -ne $global:__CcSwitchLastPwd) {{
+        $global:__CcSwitchLastPwd = $PWD.Path
+        (& {exe} profile env --shell powershell) -join "`n" | Invoke-Expression
+    }}
+    & $global:__CcSwitchPrompt
+}}
+"#,
+            exe = quote(ShellKind::PowerShell, exe)
+        ),
+        other => {
+            return Err(format!(
+                "不支持的 shell: {}（可选 bash、zsh、fish、powershell）",
+                other
+            ))
+        }
+    };
+    Ok(hook)
+}
+
+/// 当前可执行文件路径，用于生成钩子
+pub fn current_exe() -> Result<String, String> {
+    std::env::current_exe()
+        .map(|p| p.to_string_lossy().to_string())
+        .map_err(|e| format!("获取程序路径失败: {}", e))
+}
@@ -2,6 +2,8 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use crate::app_config::AppType;
+
 // SSOT 模式：不再写供应商副本文件
 
 /// 供应商结构体
@@ -46,6 +48,27 @@ impl Provider {
             meta: None,
         }
     }
+
+    /// 提取 API 端点（由应用适配器解析，例如 Claude 的 env.ANTHROPIC_BASE_URL、
+    /// Codex 当前 model_provider 的 base_url）
+    pub fn endpoint(&self, app_type: &AppType) -> Option<String> {
+        crate::app_adapter::adapter_for(app_type).base_url(&self.settings_config)
+    }
+
+    /// 所属分组 id
+    pub fn group_id(&self) -> Option<&str> {
+        self.meta.as_ref().and_then(|m| m.group_id.as_deref())
+    }
+
+    /// 是否仍需补充 API Key（见 ProviderMeta.needs_key）
+    pub fn needs_key(&self) -> bool {
+        self.meta.as_ref().is_some_and(|m| m.needs_key)
+    }
+
+    /// 提取配置中声明的模型（去重，保持出现顺序）
+    pub fn models(&self, app_type: &AppType) -> Vec<String> {
+        crate::app_adapter::adapter_for(app_type).models(&self.settings_config)
+    }
 }
 
 /// 供应商管理器
@@ -53,6 +76,20 @@ impl Provider {
 pub struct ProviderManager {
     pub providers: HashMap<String, Provider>,
     pub current: String,
+    /// 供应商分组（按显示顺序）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<ProviderGroup>,
+}
+
+/// 供应商分组
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderGroup {
+    pub id: String,
+    pub name: String,
+    /// 停用后组内供应商不能被切换，也不参与故障转移与定时健康检查
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub disabled: bool,
 }
 
 /// 用量查询脚本配置
@@ -108,6 +145,69 @@ pub struct ProviderMeta {
     /// 用量查询脚本配置
     #[serde(skip_serializing_if = "Option::is_none")]
     pub usage_script: Option<UsageScript>,
+    /// 备注（用于对比矩阵等展示）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notes: Option<String>,
+    /// 本地推理服务启动配置（仅 category 为 local 的供应商生效）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_server: Option<LocalServerConfig>,
+    /// 余额 / 额度查询接口
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quota_endpoint: Option<QuotaEndpoint>,
+    /// 从分享列表导入且未包含 API Key，补充 Key 前禁止切换
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub needs_key: bool,
+    /// 所属分组 id（见 ProviderManager.groups）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub group_id: Option<String>,
+    /// 供应商专用代理，优先于全局代理；url 为空表示直连
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<crate::proxy::ProxyConfig>,
+    /// 最近一次从端点获取的模型列表
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub models: Vec<String>,
+    /// 切换时合并进 live 配置的附加请求头与配置片段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub extras: Option<crate::provider_extras::ProviderExtras>,
+    /// 同一供应商的多个 API Key 及轮换策略
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub key_rotation: Option<crate::key_rotation::KeyRotation>,
+}
+
+/// 余额 / 额度查询接口配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct QuotaEndpoint {
+    /// 查询地址，支持 {{baseUrl}} 占位符
+    pub url: String,
+    /// 自定义请求头，支持 {{apiKey}} 占位符；为空时使用 Authorization: Bearer <apiKey>
+    #[serde(default, skip_serializing_if = "HashMap::is_empty")]
+    pub headers: HashMap<String, String>,
+    /// 响应 JSON 中剩余额度的路径，例如 `data.balance`
+    pub remaining_path: String,
+    /// 响应 JSON 中总额度的路径
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub total_path: Option<String>,
+    /// 单位，例如 "USD"
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// 缓存有效期（分钟，默认 30）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cache_minutes: Option<u64>,
+}
+
+/// 本地推理服务启动配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct LocalServerConfig {
+    /// 启动命令，例如 `ollama serve`
+    pub start_command: String,
+    /// 等待服务就绪的超时（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ready_timeout_secs: Option<u64>,
+    /// 切换到其他供应商时是否停止由 cc-switch 启动的服务
+    #[serde(default)]
+    pub stop_on_switch_away: bool,
 }
 
 impl ProviderManager {
@@ -115,4 +215,14 @@ impl ProviderManager {
     pub fn get_all_providers(&self) -> &HashMap<String, Provider> {
         &self.providers
     }
+
+    /// 供应商是否可用：存在且所属分组未停用（分组不存在视为未分组）
+    pub fn is_provider_enabled(&self, id: &str) -> bool {
+        let Some(provider) = self.providers.get(id) else {
+            return false;
+        };
+        provider
+            .group_id()
+            .is_none_or(|group_id| !self.groups.iter().any(|g| g.id == group_id && g.disabled))
+    }
 }
@@ -0,0 +1,588 @@
+use reqwest::{RequestBuilder, StatusCode};
+use serde::{Deserialize, Serialize};
+use std::time::Instant;
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+const DEFAULT_CLAUDE_BASE_URL: &str = "https://api.anthropic.com";
+const DEFAULT_OPENAI_BASE_URL: &str = "https://api.openai.com/v1";
+const DEFAULT_GEMINI_BASE_URL: &str = "https://generativelanguage.googleapis.com";
+const ANTHROPIC_VERSION: &str = "2023-06-01";
+/// 错误响应体最多保留的字符数
+const MAX_ERROR_BODY_CHARS: usize = 2000;
+
+/// 连接测试使用的请求方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConnectionProbe {
+    /// GET 模型列表
+    ModelsList,
+    /// 1 token 补全请求（模型列表不可用时的回退）
+    Completion,
+}
+
+/// 供应商连接测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionTestResult {
+    pub ok: bool,
+    pub url: String,
+    pub probe: ConnectionProbe,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 失败时的响应体（截断）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error_body: Option<String>,
+}
+
+/// 认证方式
+enum Credential {
+    /// x-api-key 请求头
+    ApiKey(String),
+    /// Authorization: Bearer
+    Bearer(String),
+    /// x-goog-api-key 请求头（Gemini）
+    GoogApiKey(String),
+}
+
+struct ConnectionTarget {
+    base_url: String,
+    credential: Option<Credential>,
+    model: Option<String>,
+    /// Codex wire_api = "responses"
+    responses_api: bool,
+}
+
+fn env_str<'a>(provider: &'a Provider, key: &str) -> Option<&'a str> {
+    provider
+        .settings_config
+        .get("env")
+        .and_then(|env| env.get(key))
+        .and_then(|v| v.as_str())
+        .map(|s| s.trim())
+        .filter(|s| !s.is_empty())
+}
+
+fn resolve_target(app_type: &AppType, provider: &Provider) -> ConnectionTarget {
+    let base_url = provider
+        .endpoint(app_type)
+        .map(|s| s.trim().trim_end_matches('/').to_string())
+        .filter(|s| !s.is_empty());
+    let model = provider.models(app_type).into_iter().next();
+
+    match app_type {
+        AppType::Claude => {
+            let credential = env_str(provider, "ANTHROPIC_AUTH_TOKEN")
+                .map(|t| Credential::Bearer(t.to_string()))
+                .or_else(|| {
+                    env_str(provider, "ANTHROPIC_API_KEY")
+                        .map(|k| Credential::ApiKey(k.to_string()))
+                });
+            ConnectionTarget {
+                base_url: base_url.unwrap_or_else(|| DEFAULT_CLAUDE_BASE_URL.to_string()),
+                credential,
+                model,
+                responses_api: false,
+            }
+        }
+        AppType::Codex => {
+            let credential = provider
+                .settings_config
+                .get("auth")
+                .and_then(|auth| auth.get("OPENAI_API_KEY"))
+                .and_then(|v| v.as_str())
+                .map(|s| s.trim())
+                .filter(|s| !s.is_empty())
+                .map(|k| Credential::Bearer(k.to_string()));
+            let responses_api = provider
+                .settings_config
+                .get("config")
+                .and_then(|v| v.as_str())
+                .and_then(|text| toml::from_str::<toml::Table>(text).ok())
+                .is_some_and(|table| {
+                    let current = table.get("model_provider").and_then(|v| v.as_str());
+                    table
+                        .get("model_providers")
+                        .and_then(|v| v.as_table())
+                        .and_then(|providers| current.and_then(|name| providers.get(name)))
+                        .and_then(|p| p.get("wire_api"))
+                        .and_then(|v| v.as_str())
+                        == Some("responses")
+                });
+            ConnectionTarget {
+                base_url: base_url.unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string()),
+                credential,
+                model,
+                responses_api,
+            }
+        }
+        AppType::Gemini => ConnectionTarget {
+            base_url: base_url.unwrap_or_else(|| DEFAULT_GEMINI_BASE_URL.to_string()),
+            credential: env_str(provider, "GEMINI_API_KEY")
+                .map(|k| Credential::GoogApiKey(k.to_string())),
+            model,
+            responses_api: false,
+        },
+        // 按 OpenAI 兼容接口探测；OpenCode 的模型写作 "<供应商>/<模型>"，请求时去掉前缀
+        AppType::OpenCode | AppType::Crush => ConnectionTarget {
+            base_url: base_url.unwrap_or_else(|| DEFAULT_OPENAI_BASE_URL.to_string()),
+            credential: crate::app_adapter::adapter_for(app_type)
+                .api_key(&provider.settings_config)
+                .map(Credential::Bearer),
+            model: match app_type {
+                AppType::OpenCode => model.map(|m| match m.split_once('/') {
+                    Some((_, id)) => id.to_string(),
+                    None => m,
+                }),
+                _ => model,
+            },
+            responses_api: false,
+        },
+    }
+}
+
+/// Gemini 的接口路径带 /v1beta 前缀，Base URL 通常不含；若已包含版本段则不重复拼接
+fn gemini_url(base: &str, path: &str) -> String {
+    if base.ends_with("/v1beta") || base.ends_with("/v1") {
+        format!("{}/{}", base, path)
+    } else {
+        format!("{}/v1beta/{}", base, path)
+    }
+}
+
+/// Claude 的接口路径带 /v1 前缀，Base URL 通常不含；若已包含则不重复拼接
+fn claude_url(base: &str, path: &str) -> String {
+    if base.ends_with("/v1") {
+        format!("{}/{}", base, path)
+    } else {
+        format!("{}/v1/{}", base, path)
+    }
+}
+
+fn authorize(
+    request: RequestBuilder,
+    app_type: &AppType,
+    target: &ConnectionTarget,
+) -> RequestBuilder {
+    let request = match &target.credential {
+        Some(Credential::ApiKey(key)) => request.header("x-api-key", key),
+        Some(Credential::Bearer(token)) => request.bearer_auth(token),
+        Some(Credential::GoogApiKey(key)) => request.header("x-goog-api-key", key),
+        None => request,
+    };
+    match app_type {
+        AppType::Claude => request.header("anthropic-version", ANTHROPIC_VERSION),
+        AppType::Codex | AppType::Gemini | AppType::OpenCode | AppType::Crush => request,
+    }
+}
+
+fn models_request(
+    client: &reqwest::Client,
+    app_type: &AppType,
+    target: &ConnectionTarget,
+) -> (String, RequestBuilder) {
+    let url = match app_type {
+        AppType::Claude => claude_url(&target.base_url, "models"),
+        AppType::Codex | AppType::OpenCode | AppType::Crush => {
+            format!("{}/models", target.base_url)
+        }
+        AppType::Gemini => gemini_url(&target.base_url, "models"),
+    };
+    let request = authorize(client.get(&url), app_type, target);
+    (url, request)
+}
+
+fn completion_request(
+    client: &reqwest::Client,
+    app_type: &AppType,
+    target: &ConnectionTarget,
+    prompt: &str,
+    max_tokens: u32,
+) -> Option<(String, RequestBuilder)> {
+    let model = target.model.as_deref()?;
+    let (url, body) = match app_type {
+        AppType::Claude => (
+            claude_url(&target.base_url, "messages"),
+            serde_json::json!({
+                "model": model,
+                "max_tokens": max_tokens,
+                "messages": [{ "role": "user", "content": prompt }],
+            }),
+        ),
+        AppType::Codex if target.responses_api => (
+            format!("{}/responses", target.base_url),
+            serde_json::json!({
+                "model": model,
+                "input": prompt,
+                // Responses 接口要求至少 16
+                "max_output_tokens": max_tokens.max(16),
+            }),
+        ),
+        AppType::Codex | AppType::OpenCode | AppType::Crush => (
+            format!("{}/chat/completions", target.base_url),
+            serde_json::json!({
+                "model": model,
+                "max_tokens": max_tokens,
+                "messages": [{ "role": "user", "content": prompt }],
+            }),
+        ),
+        AppType::Gemini => (
+            gemini_url(
+                &target.base_url,
+                &format!("models/{}:generateContent", model),
+            ),
+            serde_json::json!({
+                "contents": [{ "role": "user", "parts": [{ "text": prompt }] }],
+                "generationConfig": { "maxOutputTokens": max_tokens },
+            }),
+        ),
+    };
+    let request = authorize(client.post(&url).json(&body), app_type, target);
+    Some((url, request))
+}
+
+async fn send_probe(
+    url: String,
+    probe: ConnectionProbe,
+    request: RequestBuilder,
+) -> ConnectionTestResult {
+    let start = Instant::now();
+    match request.send().await {
+        Ok(resp) => {
+            let latency = start.elapsed().as_millis();
+            let status = resp.status();
+            let error_body = if status.is_success() {
+                None
+            } else {
+                resp.text()
+                    .await
+                    .ok()
+                    .map(|body| body.chars().take(MAX_ERROR_BODY_CHARS).collect())
+            };
+            ConnectionTestResult {
+                ok: status.is_success(),
+                url,
+                probe,
+                latency_ms: Some(latency),
+                status: Some(status.as_u16()),
+                error: (!status.is_success()).then(|| describe_status(status)),
+                error_body,
+            }
+        }
+        Err(err) => ConnectionTestResult {
+            ok: false,
+            url,
+            probe,
+            latency_ms: None,
+            status: err.status().map(|s| s.as_u16()),
+            error: Some(if err.is_timeout() {
+                "请求超时".to_string()
+            } else if err.is_connect() {
+                "连接失败".to_string()
+            } else {
+                err.to_string()
+            }),
+            error_body: None,
+        },
+    }
+}
+
+fn describe_status(status: StatusCode) -> String {
+    match status.as_u16() {
+        401 => "认证失败：API Key 无效或已过期".to_string(),
+        403 => "无访问权限：API Key 没有该接口或模型的权限".to_string(),
+        404 => "接口不存在：请检查 Base URL".to_string(),
+        429 => "请求过于频繁或额度已用尽".to_string(),
+        code if code >= 500 => format!("服务端错误: HTTP {}", code),
+        code => format!("请求失败: HTTP {}", code),
+    }
+}
+
+/// 使用供应商的端点与密钥发起最小请求：先请求模型列表，
+/// 若该接口不存在（404/405）则回退到 1 token 补全
+pub async fn test_provider_connection(
+    app_type: &AppType,
+    provider: &Provider,
+    timeout_secs: Option<u64>,
+) -> Result<ConnectionTestResult, String> {
+    let target = resolve_target(app_type, provider);
+    if target.credential.is_none() {
+        return Err("供应商未配置 API Key，无法测试连接".to_string());
+    }
+    let client = crate::speedtest::build_client(crate::speedtest::sanitize_timeout(timeout_secs))?;
+
+    let (url, request) = models_request(&client, app_type, &target);
+    let result = send_probe(url, ConnectionProbe::ModelsList, request).await;
+    let models_unsupported = matches!(result.status, Some(404) | Some(405));
+    if !models_unsupported {
+        return Ok(result);
+    }
+
+    match completion_request(&client, app_type, &target, "ping", 1) {
+        Some((url, request)) => Ok(send_probe(url, ConnectionProbe::Completion, request).await),
+        None => Ok(result),
+    }
+}
+
+/// 只请求模型列表：不回退到补全请求，不产生计费，适合后台周期性探测
+pub async fn probe_models_list(
+    app_type: &AppType,
+    provider: &Provider,
+    timeout_secs: Option<u64>,
+) -> Result<ConnectionTestResult, String> {
+    let target = resolve_target(app_type, provider);
+    if target.credential.is_none() {
+        return Err("供应商未配置 API Key，无法测试连接".to_string());
+    }
+    let client = crate::speedtest::build_client(crate::speedtest::sanitize_timeout(timeout_secs))?;
+    let (url, request) = models_request(&client, app_type, &target);
+    Ok(send_probe(url, ConnectionProbe::ModelsList, request).await)
+}
+
+/// 从模型列表接口的响应中提取模型 id：OpenAI / Anthropic 为 data[].id，Gemini 为 models[].name
+fn parse_model_ids(body: &serde_json::Value) -> Vec<String> {
+    let mut ids: Vec<String> = body
+        .get("data")
+        .and_then(|v| v.as_array())
+        .map(|items| {
+            items
+                .iter()
+                .filter_map(|m| m.get("id").and_then(|v| v.as_str()))
+                .map(|s| s.to_string())
+                .collect()
+        })
+        .or_else(|| {
+            body.get("models").and_then(|v| v.as_array()).map(|items| {
+                items
+                    .iter()
+                    .filter_map(|m| m.get("name").and_then(|v| v.as_str()))
+                    .map(|s| s.trim_start_matches("models/").to_string())
+                    .collect()
+            })
+        })
+        .unwrap_or_default();
+    ids.sort();
+    ids.dedup();
+    ids
+}
+
+/// 使用供应商的端点与密钥请求模型列表
+pub async fn fetch_models(
+    app_type: &AppType,
+    provider: &Provider,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<String>, String> {
+    let target = resolve_target(app_type, provider);
+    if target.credential.is_none() {
+        return Err("供应商未配置 API Key，无法获取模型列表".to_string());
+    }
+    let client = crate::speedtest::build_client(crate::speedtest::sanitize_timeout(timeout_secs))?;
+    let (url, request) = models_request(&client, app_type, &target);
+    let resp = request.send().await.map_err(request_error)?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("{}: {}", describe_status(status), url));
+    }
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("解析模型列表失败: {}", e))?;
+    let ids = parse_model_ids(&body);
+    if ids.is_empty() {
+        return Err("端点未返回任何模型".to_string());
+    }
+    Ok(ids)
+}
+
+/// 从补全响应中提取文本：Anthropic 为 content[].text，Chat Completions 为 choices[0].message.content，
+/// Responses 为 output[].content[].text，Gemini 为 candidates[0].content.parts[].text
+fn parse_completion_text(body: &serde_json::Value) -> Option<String> {
+    let join = |parts: Vec<&str>| Some(parts.concat()).filter(|s| !s.trim().is_empty());
+    if let Some(content) = body.get("content").and_then(|v| v.as_array()) {
+        return join(
+            content
+                .iter()
+                .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                .collect(),
+        );
+    }
+    if let Some(text) = body
+        .pointer("/choices/0/message/content")
+        .and_then(|v| v.as_str())
+    {
+        return join(vec![text]);
+    }
+    if let Some(output) = body.get("output").and_then(|v| v.as_array()) {
+        return join(
+            output
+                .iter()
+                .filter_map(|item| item.get("content").and_then(|c| c.as_array()))
+                .flatten()
+                .filter(|c| c.get("type").and_then(|t| t.as_str()) == Some("output_text"))
+                .filter_map(|c| c.get("text").and_then(|t| t.as_str()))
+                .collect(),
+        );
+    }
+    let parts = body
+        .pointer("/candidates/0/content/parts")
+        .and_then(|v| v.as_array())?;
+    join(
+        parts
+            .iter()
+            .filter_map(|p| p.get("text").and_then(|t| t.as_str()))
+            .collect(),
+    )
+}
+
+/// 使用供应商的端点、密钥与首个模型发送一次补全请求，返回生成的文本
+pub async fn generate_text(
+    app_type: &AppType,
+    provider: &Provider,
+    prompt: &str,
+    max_tokens: u32,
+    timeout_secs: Option<u64>,
+) -> Result<String, String> {
+    let target = resolve_target(app_type, provider);
+    if target.credential.is_none() {
+        return Err("供应商未配置 API Key，无法发送请求".to_string());
+    }
+    let client = crate::speedtest::build_client(crate::speedtest::sanitize_timeout(timeout_secs))?;
+    let (url, request) = completion_request(&client, app_type, &target, prompt, max_tokens)
+        .ok_or_else(|| "供应商配置中未声明模型，无法发送请求".to_string())?;
+    let resp = request.send().await.map_err(request_error)?;
+    let status = resp.status();
+    if !status.is_success() {
+        return Err(format!("{}: {}", describe_status(status), url));
+    }
+    let body: serde_json::Value = resp
+        .json()
+        .await
+        .map_err(|e| format!("解析响应失败: {}", e))?;
+    parse_completion_text(&body).ok_or_else(|| "响应中没有文本内容".to_string())
+}
+
+/// 单个供应商的基准测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderBenchmark {
+    pub app_type: String,
+    pub provider_id: String,
+    pub provider_name: String,
+    /// 排名（从 1 开始），无法完成测试的供应商为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rank: Option<usize>,
+    pub base_url: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub model: Option<String>,
+    /// 首字节时间（请求模型列表至收到响应头）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ttfb_ms: Option<u128>,
+    /// 1 token 补全的完整往返时间
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completion_status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+fn request_error(err: reqwest::Error) -> String {
+    if err.is_timeout() {
+        "请求超时".to_string()
+    } else if err.is_connect() {
+        "连接失败".to_string()
+    } else {
+        err.to_string()
+    }
+}
+
+async fn benchmark_one(
+    client: reqwest::Client,
+    app_type: AppType,
+    provider: Provider,
+) -> ProviderBenchmark {
+    let target = resolve_target(&app_type, &provider);
+    let mut result = ProviderBenchmark {
+        app_type: app_type.as_str().to_string(),
+        provider_id: provider.id.clone(),
+        provider_name: provider.name.clone(),
+        rank: None,
+        base_url: target.base_url.clone(),
+        model: target.model.clone(),
+        ttfb_ms: None,
+        completion_ms: None,
+        completion_status: None,
+        error: None,
+    };
+    if target.credential.is_none() {
+        result.error = Some("未配置 API Key".to_string());
+        return result;
+    }
+
+    // 首字节时间：请求模型列表，send() 在收到响应头时返回
+    let (_, request) = models_request(&client, &app_type, &target);
+    let start = Instant::now();
+    match request.send().await {
+        Ok(_) => result.ttfb_ms = Some(start.elapsed().as_millis()),
+        Err(e) => {
+            result.error = Some(request_error(e));
+            return result;
+        }
+    }
+
+    // 补全往返：发送 1 token 请求并读完响应体
+    let Some((_, request)) = completion_request(&client, &app_type, &target, "ping", 1) else {
+        result.error = Some("配置中未声明模型，跳过补全测试".to_string());
+        return result;
+    };
+    let start = Instant::now();
+    match request.send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            let body = resp.bytes().await;
+            result.completion_status = Some(status.as_u16());
+            match body {
+                Ok(_) if status.is_success() => {
+                    result.completion_ms = Some(start.elapsed().as_millis())
+                }
+                Ok(_) => result.error = Some(describe_status(status)),
+                Err(e) => result.error = Some(request_error(e)),
+            }
+        }
+        Err(e) => result.error = Some(request_error(e)),
+    }
+    result
+}
+
+/// 并发测试多个供应商的首字节时间与补全往返时间，按速度排序：
+/// 补全成功的按补全耗时排序，其次是仅测得首字节时间的，最后是失败的
+pub async fn benchmark_providers(
+    providers: Vec<(AppType, Provider)>,
+    timeout_secs: Option<u64>,
+) -> Result<Vec<ProviderBenchmark>, String> {
+    let client = crate::speedtest::build_client(crate::speedtest::sanitize_timeout(timeout_secs))?;
+    let tasks = providers
+        .into_iter()
+        .map(|(app_type, provider)| benchmark_one(client.clone(), app_type, provider));
+    let mut results = futures::future::join_all(tasks).await;
+
+    results.sort_by_key(|r| match (r.completion_ms, r.ttfb_ms) {
+        (Some(completion), _) => (0, completion),
+        (None, Some(ttfb)) => (1, ttfb),
+        (None, None) => (2, 0),
+    });
+    let mut rank = 0;
+    for result in results.iter_mut() {
+        if result.completion_ms.is_some() || result.ttfb_ms.is_some() {
+            rank += 1;
+            result.rank = Some(rank);
+        }
+    }
+    Ok(results)
+}
@@ -0,0 +1,291 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+
+/// 候选供应商的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum DiscoverySource {
+    /// ANTHROPIC_* / OPENAI_* / GEMINI_* 环境变量
+    Env,
+    /// ~/.claude/settings.json
+    ClaudeSettings,
+    /// ~/.codex/auth.json + config.toml
+    CodexLive,
+    /// ~/.gemini/.env + settings.json
+    GeminiLive,
+    /// 适配器应用（OpenCode、Crush 等）的配置文件
+    AdapterLive,
+    /// 其他供应商切换工具的配置文件
+    ExternalTool,
+}
+
+/// 扫描到的候选供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderCandidate {
+    pub app_type: String,
+    pub source: DiscoverySource,
+    /// 来源文件路径（环境变量来源为空）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source_path: Option<String>,
+    pub provider: Provider,
+    /// 与已有供应商重复时为其 id
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duplicate_of: Option<String>,
+}
+
+fn env_var(name: &str) -> Option<String> {
+    std::env::var(name)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+/// 去重标识：端点（忽略末尾斜杠与大小写）+ API Key
+pub(crate) fn identity(app_type: &AppType, provider: &Provider) -> (String, String) {
+    // 已加密的 Key 需解析后比较；未解锁时按引用比较
+    let provider =
+        crate::secrets::resolve_provider(app_type, provider).unwrap_or_else(|_| provider.clone());
+    let endpoint = provider
+        .endpoint(app_type)
+        .map(|e| e.trim().trim_end_matches('/').to_lowercase())
+        .unwrap_or_default();
+    (
+        endpoint,
+        crate::app_adapter::adapter_for(app_type)
+            .api_key(&provider.settings_config)
+            .unwrap_or_default(),
+    )
+}
+
+/// 根据端点生成供应商名称，例如 `api.example.com`
+fn name_for(app_type: &AppType, endpoint: Option<&str>) -> String {
+    endpoint
+        .and_then(|e| reqwest::Url::parse(e.trim()).ok())
+        .and_then(|url| url.host_str().map(|h| h.to_string()))
+        .unwrap_or_else(|| match app_type {
+            AppType::Claude => "Claude 官方".to_string(),
+            AppType::Codex => "Codex 官方".to_string(),
+            AppType::Gemini => "Gemini 官方".to_string(),
+            AppType::OpenCode | AppType::Crush => crate::app_adapter::adapter_for(app_type)
+                .display_name()
+                .to_string(),
+        })
+}
+
+pub(crate) fn codex_config_for(name: &str, base_url: &str, model: &str) -> String {
+    let key: String = name
+        .to_lowercase()
+        .chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect();
+    let key = key.trim_matches('_');
+    let key = if key.is_empty() { "custom" } else { key };
+    format!(
+        "model_provider = \"{key}\"\nmodel = \"{model}\"\n\n[model_providers.{key}]\nname = \"{key}\"\nbase_url = \"{base_url}\"\nwire_api = \"responses\"\nrequires_openai_auth = true\n"
+    )
+}
+
+fn env_candidates() -> Vec<(AppType, Value)> {
+    let mut found = Vec::new();
+
+    let claude_keys = [
+        "ANTHROPIC_BASE_URL",
+        "ANTHROPIC_AUTH_TOKEN",
+        "ANTHROPIC_API_KEY",
+        "ANTHROPIC_MODEL",
+        "ANTHROPIC_SMALL_FAST_MODEL",
+    ];
+    let env: serde_json::Map<String, Value> = claude_keys
+        .iter()
+        .filter_map(|k| env_var(k).map(|v| (k.to_string(), Value::String(v))))
+        .collect();
+    if env.contains_key("ANTHROPIC_AUTH_TOKEN") || env.contains_key("ANTHROPIC_API_KEY") {
+        found.push((AppType::Claude, json!({ "env": env })));
+    }
+
+    if let Some(key) = env_var("OPENAI_API_KEY") {
+        let config = env_var("OPENAI_BASE_URL")
+            .map(|base_url| {
+                let name = name_for(&AppType::Codex, Some(&base_url));
+                let model = env_var("OPENAI_MODEL").unwrap_or_else(|| "gpt-5-codex".to_string());
+                codex_config_for(&name, &base_url, &model)
+            })
+            .unwrap_or_default();
+        found.push((
+            AppType::Codex,
+            json!({ "auth": { "OPENAI_API_KEY": key }, "config": config }),
+        ));
+    }
+
+    let gemini_keys = ["GEMINI_API_KEY", "GOOGLE_GEMINI_BASE_URL", "GEMINI_MODEL"];
+    let env: serde_json::Map<String, Value> = gemini_keys
+        .iter()
+        .filter_map(|k| env_var(k).map(|v| (k.to_string(), Value::String(v))))
+        .collect();
+    if env.contains_key("GEMINI_API_KEY") {
+        found.push((AppType::Gemini, json!({ "env": env })));
+    }
+    found
+}
+
+/// 扫描环境变量与现有 live 配置，返回可导入的候选供应商（已标记与现有供应商的重复项）。
+/// 注意：从桌面启动时进程通常不继承 shell 中导出的环境变量
+pub fn discover_providers(config: &MultiAppConfig) -> Vec<ProviderCandidate> {
+    let mut raw: Vec<(AppType, DiscoverySource, Option<String>, Value)> = Vec::new();
+
+    for (app_type, settings) in env_candidates() {
+        raw.push((app_type, DiscoverySource::Env, None, settings));
+    }
+
+    let claude_path = crate::config::get_claude_settings_path();
+    if claude_path.exists() {
+        match crate::config::read_json_file::<Value>(&claude_path) {
+            Ok(settings) if settings.is_object() => raw.push((
+                AppType::Claude,
+                DiscoverySource::ClaudeSettings,
+                Some(claude_path.to_string_lossy().to_string()),
+                settings,
+            )),
+            Ok(_) => {}
+            Err(e) => log::warn!("读取 Claude 配置失败: {}", e),
+        }
+    }
+
+    let auth_path = crate::codex_config::get_codex_auth_path();
+    if auth_path.exists() {
+        match crate::config::read_json_file::<Value>(&auth_path) {
+            Ok(auth) => {
+                let config_text =
+                    std::fs::read_to_string(crate::codex_config::get_codex_config_path())
+                        .unwrap_or_default();
+                raw.push((
+                    AppType::Codex,
+                    DiscoverySource::CodexLive,
+                    Some(auth_path.to_string_lossy().to_string()),
+                    json!({ "auth": auth, "config": config_text }),
+                ));
+            }
+            Err(e) => log::warn!("读取 Codex auth.json 失败: {}", e),
+        }
+    }
+
+    if crate::gemini_config::gemini_live_exists() {
+        match crate::gemini_config::read_gemini_live() {
+            Ok(settings) => raw.push((
+                AppType::Gemini,
+                DiscoverySource::GeminiLive,
+                Some(
+                    crate::gemini_config::get_gemini_config_dir()
+                        .to_string_lossy()
+                        .to_string(),
+                ),
+                settings,
+            )),
+            Err(e) => log::warn!("读取 Gemini 配置失败: {}", e),
+        }
+    }
+
+    for app_type in [AppType::OpenCode, AppType::Crush] {
+        let adapter = crate::app_adapter::adapter_for(&app_type);
+        if !adapter.live_exists() {
+            continue;
+        }
+        match adapter.read_live() {
+            Ok(settings) if adapter.validate(&settings).is_ok() => raw.push((
+                app_type,
+                DiscoverySource::AdapterLive,
+                Some(adapter.config_dir().to_string_lossy().to_string()),
+                settings,
+            )),
+            Ok(_) => {}
+            Err(e) => log::warn!("读取 {} 配置失败: {}", adapter.display_name(), e),
+        }
+    }
+
+    let mut seen: HashSet<(String, String, String)> = HashSet::new();
+    let mut candidates = Vec::new();
+    for (app_type, source, source_path, settings) in raw {
+        let mut provider = Provider::with_id(String::new(), String::new(), settings, None);
+        let endpoint = provider.endpoint(&app_type);
+        provider.name = name_for(&app_type, endpoint.as_deref());
+
+        let (endpoint, key) = identity(&app_type, &provider);
+        // 候选之间去重（同一 Key 可能同时出现在环境变量与 live 配置中）
+        if !seen.insert((app_type.as_str().to_string(), endpoint.clone(), key.clone())) {
+            continue;
+        }
+        let duplicate_of = duplicate_of(config, &app_type, &(endpoint, key));
+
+        candidates.push(ProviderCandidate {
+            app_type: app_type.as_str().to_string(),
+            source,
+            source_path,
+            provider,
+            duplicate_of,
+        });
+    }
+    candidates
+}
+
+/// 与给定去重标识相同的已有供应商 id
+pub(crate) fn duplicate_of(
+    config: &MultiAppConfig,
+    app_type: &AppType,
+    identity_of_candidate: &(String, String),
+) -> Option<String> {
+    config.get_manager(app_type).and_then(|manager| {
+        manager
+            .providers
+            .values()
+            .find(|p| &identity(app_type, p) == identity_of_candidate)
+            .map(|p| p.id.clone())
+    })
+}
+
+pub(crate) fn unique_id(existing: &HashSet<String>, base: &str) -> String {
+    let base = crate::config::sanitize_provider_name(base);
+    if !existing.contains(&base) {
+        return base;
+    }
+    (2..)
+        .map(|i| format!("{}-{}", base, i))
+        .find(|id| !existing.contains(id))
+        .unwrap_or(base)
+}
+
+/// 导入选中的候选供应商，返回新增的 (应用, 供应商 id)；与现有供应商重复的会被跳过
+pub fn import_candidates(
+    config: &mut MultiAppConfig,
+    candidates: Vec<ProviderCandidate>,
+) -> Vec<(String, String)> {
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut added = Vec::new();
+    for candidate in candidates {
+        let app_type = AppType::from(candidate.app_type.as_str());
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        let identity_of_candidate = identity(&app_type, &candidate.provider);
+        if manager
+            .providers
+            .values()
+            .any(|p| identity(&app_type, p) == identity_of_candidate)
+        {
+            continue;
+        }
+
+        let existing: HashSet<String> = manager.providers.keys().cloned().collect();
+        let mut provider = candidate.provider;
+        provider.id = unique_id(&existing, &provider.name);
+        provider.created_at = Some(now);
+        provider.sort_index = None;
+        added.push((app_type.as_str().to_string(), provider.id.clone()));
+        manager.providers.insert(provider.id.clone(), provider);
+    }
+    added
+}
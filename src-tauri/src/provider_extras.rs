@@ -0,0 +1,333 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::collections::BTreeMap;
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+/// Claude Code 读取的自定义请求头环境变量（每行一个 `Name: Value`）
+const CLAUDE_HEADERS_ENV: &str = "ANTHROPIC_CUSTOM_HEADERS";
+
+/// 供应商的附加设置：切换到该供应商时合并进 live 配置，回填（切换离开）前移除
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderExtras {
+    /// 自定义请求头：Claude 写入 ANTHROPIC_CUSTOM_HEADERS，Codex 写入当前
+    /// [model_providers.<id>] 的 http_headers
+    #[serde(default, skip_serializing_if = "BTreeMap::is_empty")]
+    pub headers: BTreeMap<String, String>,
+    /// 深度合并进 Claude settings.json 的 JSON 对象片段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub json: Option<Value>,
+    /// 深度合并进 Codex config.toml 的 TOML 片段
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toml: Option<String>,
+}
+
+impl ProviderExtras {
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty() && self.json.is_none() && self.toml.is_none()
+    }
+}
+
+fn extras_of(provider: &Provider) -> Option<&ProviderExtras> {
+    provider
+        .meta
+        .as_ref()
+        .and_then(|m| m.extras.as_ref())
+        .filter(|e| !e.is_empty())
+}
+
+/// 校验附加设置：请求头名称合法、JSON 片段为对象、TOML 片段可解析，且不支持的应用不能配置
+pub fn validate(app_type: &AppType, extras: &ProviderExtras) -> Result<(), String> {
+    for (name, value) in &extras.headers {
+        let valid_name = !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+        if !valid_name {
+            return Err(format!("请求头名称不合法: {}", name));
+        }
+        if value.contains(['\r', '\n']) {
+            return Err(format!("请求头 {} 的值不能包含换行", name));
+        }
+    }
+    if let Some(json) = &extras.json {
+        if !json.is_object() {
+            return Err("JSON 片段必须是对象".to_string());
+        }
+    }
+    if let Some(text) = &extras.toml {
+        toml::from_str::<toml::Table>(text).map_err(|e| format!("TOML 片段无效: {}", e))?;
+    }
+
+    let supported = match app_type {
+        AppType::Claude => extras.toml.is_none(),
+        AppType::Codex => extras.json.is_none(),
+        AppType::Gemini | AppType::OpenCode | AppType::Crush => extras.is_empty(),
+    };
+    if supported {
+        Ok(())
+    } else {
+        Err(format!("{} 不支持该类附加设置", app_type.as_str()))
+    }
+}
+
+/// 深度合并：对象逐键合并，其余值由 patch 覆盖
+fn merge_json(target: &mut Map<String, Value>, patch: &Map<String, Value>) {
+    for (key, value) in patch {
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(existing)), Value::Object(patch)) => merge_json(existing, patch),
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+/// 移除由 patch 合并进来的值（仅移除取值一致的叶子），并清理因此变空的对象
+fn unmerge_json(target: &mut Map<String, Value>, patch: &Map<String, Value>) {
+    for (key, value) in patch {
+        match (target.get_mut(key), value) {
+            (Some(Value::Object(existing)), Value::Object(patch)) => {
+                unmerge_json(existing, patch);
+                if existing.is_empty() {
+                    target.remove(key);
+                }
+            }
+            (Some(existing), _) if existing == value => {
+                target.remove(key);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn merge_toml(target: &mut toml::Table, patch: &toml::Table) {
+    for (key, value) in patch {
+        match (target.get_mut(key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(patch)) => {
+                merge_toml(existing, patch)
+            }
+            _ => {
+                target.insert(key.clone(), value.clone());
+            }
+        }
+    }
+}
+
+fn unmerge_toml(target: &mut toml::Table, patch: &toml::Table) {
+    for (key, value) in patch {
+        match (target.get_mut(key), value) {
+            (Some(toml::Value::Table(existing)), toml::Value::Table(patch)) => {
+                unmerge_toml(existing, patch);
+                if existing.is_empty() {
+                    target.remove(key);
+                }
+            }
+            (Some(existing), _) if existing == value => {
+                target.remove(key);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn header_lines(headers: &BTreeMap<String, String>) -> Vec<String> {
+    headers
+        .iter()
+        .map(|(name, value)| format!("{}: {}", name, value.trim()))
+        .collect()
+}
+
+/// 修改 Codex 配置文本；edit 返回 false 时不改写文本
+fn edit_codex_config(
+    settings: &mut Value,
+    edit: impl FnOnce(&mut toml::Table) -> bool,
+) -> Result<(), String> {
+    let Some(obj) = settings.as_object_mut() else {
+        return Ok(());
+    };
+    let text = obj.get("config").and_then(|v| v.as_str()).unwrap_or("");
+    let mut root: toml::Table = if text.trim().is_empty() {
+        toml::Table::new()
+    } else {
+        toml::from_str(text).map_err(|e| format!("解析 config.toml 失败: {}", e))?
+    };
+    if !edit(&mut root) {
+        return Ok(());
+    }
+    let new_text = toml::to_string(&root).map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
+    obj.insert("config".to_string(), Value::String(new_text));
+    Ok(())
+}
+
+/// 当前 model_provider 对应的 http_headers 表；未声明 model_provider 时为 None
+fn codex_headers_table(root: &mut toml::Table) -> Option<&mut toml::Table> {
+    let current = root.get("model_provider")?.as_str()?.to_string();
+    root.get_mut("model_providers")?
+        .as_table_mut()?
+        .get_mut(&current)?
+        .as_table_mut()?
+        .entry("http_headers")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+}
+
+/// 将供应商的附加设置合并进即将写入 live 的配置
+pub fn apply_to_settings(
+    app_type: &AppType,
+    settings: &mut Value,
+    provider: &Provider,
+) -> Result<(), String> {
+    let Some(extras) = extras_of(provider) else {
+        return Ok(());
+    };
+    match app_type {
+        AppType::Claude => {
+            let Some(obj) = settings.as_object_mut() else {
+                return Ok(());
+            };
+            if let Some(Value::Object(patch)) = &extras.json {
+                merge_json(obj, patch);
+            }
+            if !extras.headers.is_empty() {
+                let env = obj
+                    .entry("env")
+                    .or_insert_with(|| Value::Object(Map::new()))
+                    .as_object_mut()
+                    .ok_or_else(|| "env 必须是 JSON 对象".to_string())?;
+                let mut lines: Vec<String> = env
+                    .get(CLAUDE_HEADERS_ENV)
+                    .and_then(|v| v.as_str())
+                    .map(|s| s.lines().map(str::to_string).collect())
+                    .unwrap_or_default();
+                lines.extend(header_lines(&extras.headers));
+                env.insert(
+                    CLAUDE_HEADERS_ENV.to_string(),
+                    Value::String(lines.join("\n")),
+                );
+            }
+            Ok(())
+        }
+        AppType::Codex => {
+            let patch = match &extras.toml {
+                Some(text) => Some(
+                    toml::from_str::<toml::Table>(text)
+                        .map_err(|e| format!("TOML 片段无效: {}", e))?,
+                ),
+                None => None,
+            };
+            edit_codex_config(settings, |root| {
+                if let Some(patch) = &patch {
+                    merge_toml(root, patch);
+                }
+                if !extras.headers.is_empty() {
+                    match codex_headers_table(root) {
+                        Some(table) => {
+                            for (name, value) in &extras.headers {
+                                table.insert(name.clone(), toml::Value::String(value.clone()));
+                            }
+                        }
+                        None => {
+                            log::warn!("config.toml 未声明 model_provider，跳过自定义请求头")
+                        }
+                    }
+                }
+                true
+            })
+        }
+        AppType::Gemini | AppType::OpenCode | AppType::Crush => Ok(()),
+    }
+}
+
+/// 从 live 配置中移除由附加设置写入的内容（仅移除取值一致的项），用于回填到供应商前
+pub fn strip_from_settings(
+    app_type: &AppType,
+    settings: &mut Value,
+    provider: &Provider,
+) -> Result<(), String> {
+    let Some(extras) = extras_of(provider) else {
+        return Ok(());
+    };
+    match app_type {
+        AppType::Claude => {
+            let Some(obj) = settings.as_object_mut() else {
+                return Ok(());
+            };
+            if !extras.headers.is_empty() {
+                if let Some(env) = obj.get_mut("env").and_then(|v| v.as_object_mut()) {
+                    let added = header_lines(&extras.headers);
+                    let remaining: Vec<&str> = env
+                        .get(CLAUDE_HEADERS_ENV)
+                        .and_then(|v| v.as_str())
+                        .map(|s| {
+                            s.lines()
+                                .filter(|line| !added.iter().any(|a| a == line))
+                                .collect()
+                        })
+                        .unwrap_or_default();
+                    if remaining.is_empty() {
+                        env.remove(CLAUDE_HEADERS_ENV);
+                    } else {
+                        let joined = remaining.join("\n");
+                        env.insert(CLAUDE_HEADERS_ENV.to_string(), Value::String(joined));
+                    }
+                    if env.is_empty() {
+                        obj.remove("env");
+                    }
+                }
+            }
+            if let Some(Value::Object(patch)) = &extras.json {
+                unmerge_json(obj, patch);
+            }
+            Ok(())
+        }
+        AppType::Codex => {
+            let has_config = settings
+                .get("config")
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty());
+            if !has_config {
+                return Ok(());
+            }
+            let patch = match &extras.toml {
+                Some(text) => toml::from_str::<toml::Table>(text).ok(),
+                None => None,
+            };
+            edit_codex_config(settings, |root| {
+                if !extras.headers.is_empty() {
+                    if let Some(table) = codex_headers_table(root) {
+                        table.retain(|name, value| {
+                            extras.headers.get(name).map(|v| v.as_str()) != value.as_str()
+                        });
+                    }
+                    // 清理因移除请求头而变空的 http_headers
+                    let current = root
+                        .get("model_provider")
+                        .and_then(|v| v.as_str())
+                        .map(str::to_string);
+                    if let Some(provider) = current.and_then(|current| {
+                        root.get_mut("model_providers")
+                            .and_then(|v| v.as_table_mut())
+                            .and_then(|providers| providers.get_mut(&current))
+                            .and_then(|v| v.as_table_mut())
+                    }) {
+                        if provider
+                            .get("http_headers")
+                            .and_then(|v| v.as_table())
+                            .is_some_and(|t| t.is_empty())
+                        {
+                            provider.remove("http_headers");
+                        }
+                    }
+                }
+                if let Some(patch) = &patch {
+                    unmerge_toml(root, patch);
+                }
+                true
+            })
+        }
+        AppType::Gemini | AppType::OpenCode | AppType::Crush => Ok(()),
+    }
+}
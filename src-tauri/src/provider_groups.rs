@@ -0,0 +1,190 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+use crate::provider::{ProviderGroup, ProviderManager};
+
+/// 分组及其供应商（按 sortIndex 排序）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderGroupInfo {
+    /// 未分组的供应商 id 为 None
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    pub name: String,
+    pub disabled: bool,
+    pub provider_ids: Vec<String>,
+}
+
+fn find_group<'a>(
+    manager: &'a mut ProviderManager,
+    group_id: &str,
+) -> Result<&'a mut ProviderGroup, String> {
+    manager
+        .groups
+        .iter_mut()
+        .find(|g| g.id == group_id)
+        .ok_or_else(|| format!("分组不存在: {}", group_id))
+}
+
+fn normalize_name(
+    manager: &ProviderManager,
+    name: &str,
+    except: Option<&str>,
+) -> Result<String, String> {
+    let name = name.trim();
+    if name.is_empty() {
+        return Err("分组名称不能为空".to_string());
+    }
+    if manager
+        .groups
+        .iter()
+        .any(|g| g.name == name && Some(g.id.as_str()) != except)
+    {
+        return Err(format!("分组名称已存在: {}", name));
+    }
+    Ok(name.to_string())
+}
+
+/// 按显示顺序列出分组；末尾附加未分组的供应商（无未分组供应商时省略）
+pub fn list_groups(manager: &ProviderManager) -> Vec<ProviderGroupInfo> {
+    let mut providers: Vec<_> = manager.providers.values().collect();
+    providers.sort_by(|a, b| {
+        (a.sort_index.unwrap_or(usize::MAX), a.created_at, &a.name).cmp(&(
+            b.sort_index.unwrap_or(usize::MAX),
+            b.created_at,
+            &b.name,
+        ))
+    });
+    let known: HashSet<&str> = manager.groups.iter().map(|g| g.id.as_str()).collect();
+
+    let mut groups: Vec<ProviderGroupInfo> = manager
+        .groups
+        .iter()
+        .map(|group| ProviderGroupInfo {
+            id: Some(group.id.clone()),
+            name: group.name.clone(),
+            disabled: group.disabled,
+            provider_ids: providers
+                .iter()
+                .filter(|p| p.group_id() == Some(group.id.as_str()))
+                .map(|p| p.id.clone())
+                .collect(),
+        })
+        .collect();
+
+    let ungrouped: Vec<String> = providers
+        .iter()
+        .filter(|p| p.group_id().is_none_or(|id| !known.contains(id)))
+        .map(|p| p.id.clone())
+        .collect();
+    if !ungrouped.is_empty() {
+        groups.push(ProviderGroupInfo {
+            id: None,
+            name: "未分组".to_string(),
+            disabled: false,
+            provider_ids: ungrouped,
+        });
+    }
+    groups
+}
+
+/// 新建分组（追加到末尾）
+pub fn create_group(manager: &mut ProviderManager, name: &str) -> Result<ProviderGroup, String> {
+    let name = normalize_name(manager, name, None)?;
+    let existing: HashSet<String> = manager.groups.iter().map(|g| g.id.clone()).collect();
+    let group = ProviderGroup {
+        id: crate::provider_discovery::unique_id(&existing, &name),
+        name,
+        disabled: false,
+    };
+    manager.groups.push(group.clone());
+    Ok(group)
+}
+
+pub fn rename_group(
+    manager: &mut ProviderManager,
+    group_id: &str,
+    name: &str,
+) -> Result<(), String> {
+    let name = normalize_name(manager, name, Some(group_id))?;
+    find_group(manager, group_id)?.name = name;
+    Ok(())
+}
+
+/// 删除分组，组内供应商变为未分组
+pub fn delete_group(manager: &mut ProviderManager, group_id: &str) -> Result<(), String> {
+    find_group(manager, group_id)?;
+    manager.groups.retain(|g| g.id != group_id);
+    for provider in manager.providers.values_mut() {
+        if provider.group_id() == Some(group_id) {
+            if let Some(meta) = provider.meta.as_mut() {
+                meta.group_id = None;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// 按给定顺序重排分组；未列出的分组保持原相对顺序排在末尾
+pub fn reorder_groups(manager: &mut ProviderManager, group_ids: &[String]) -> Result<(), String> {
+    if let Some(unknown) = group_ids
+        .iter()
+        .find(|id| !manager.groups.iter().any(|g| &g.id == *id))
+    {
+        return Err(format!("分组不存在: {}", unknown));
+    }
+    manager.groups.sort_by_key(|g| {
+        group_ids
+            .iter()
+            .position(|id| id == &g.id)
+            .unwrap_or(usize::MAX)
+    });
+    Ok(())
+}
+
+/// 将供应商移动到分组（group_id 为 None 时移出分组），返回实际移动的数量
+pub fn move_providers(
+    manager: &mut ProviderManager,
+    provider_ids: &[String],
+    group_id: Option<&str>,
+) -> Result<usize, String> {
+    if let Some(group_id) = group_id {
+        find_group(manager, group_id)?;
+    }
+    let mut moved = 0;
+    for id in provider_ids {
+        let provider = manager
+            .providers
+            .get_mut(id)
+            .ok_or_else(|| crate::error::provider_not_found(&id))?;
+        if provider.group_id() == group_id {
+            continue;
+        }
+        provider.meta.get_or_insert_with(Default::default).group_id =
+            group_id.map(|s| s.to_string());
+        moved += 1;
+    }
+    Ok(moved)
+}
+
+/// 批量启用 / 停用分组；停用包含当前供应商的分组会被拒绝
+pub fn set_group_disabled(
+    manager: &mut ProviderManager,
+    group_id: &str,
+    disabled: bool,
+) -> Result<(), String> {
+    if disabled {
+        if let Some(current) = manager
+            .providers
+            .get(&manager.current)
+            .filter(|p| p.group_id() == Some(group_id))
+        {
+            return Err(format!(
+                "分组包含当前使用的供应商 {}，请先切换到其他供应商",
+                current.name
+            ));
+        }
+    }
+    find_group(manager, group_id)?.disabled = disabled;
+    Ok(())
+}
@@ -0,0 +1,325 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+use crate::provider_discovery::{DiscoverySource, ProviderCandidate};
+
+/// 可导入配置的其他供应商切换工具
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ExternalTool {
+    /// ccs：~/.ccs/config.json 中的 profiles 指向各自的 settings.json
+    Ccs,
+    /// claude-code-router：~/.claude-code-router/config.json 中的 Providers 列表
+    ClaudeCodeRouter,
+    /// 通用 JSON：供应商数组或 { providers / profiles: [...] }，按常见字段名映射
+    Generic,
+}
+
+/// 在默认位置找到的其他工具配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalToolSource {
+    pub tool: ExternalTool,
+    pub path: String,
+    pub candidates: Vec<ProviderCandidate>,
+    /// 解析失败时的错误信息
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+const BASE_URL_KEYS: &[&str] = &[
+    "baseUrl",
+    "baseURL",
+    "base_url",
+    "apiBaseUrl",
+    "api_base_url",
+    "endpoint",
+    "url",
+    "ANTHROPIC_BASE_URL",
+];
+const API_KEY_KEYS: &[&str] = &[
+    "apiKey",
+    "api_key",
+    "authToken",
+    "auth_token",
+    "token",
+    "key",
+    "ANTHROPIC_AUTH_TOKEN",
+    "ANTHROPIC_API_KEY",
+];
+const MODEL_KEYS: &[&str] = &["model", "defaultModel", "default_model", "ANTHROPIC_MODEL"];
+const NAME_KEYS: &[&str] = &["name", "label", "title", "id"];
+
+fn first_str(obj: &Map<String, Value>, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .filter_map(|k| obj.get(*k).and_then(|v| v.as_str()))
+        .map(|s| s.trim().to_string())
+        .find(|s| !s.is_empty())
+}
+
+/// `$VAR` / `${VAR}` 形式的 Key 从环境变量读取，读取不到时视为未填写
+fn resolve_key(raw: Option<String>) -> Option<String> {
+    let raw = raw?;
+    let Some(var) = raw.strip_prefix('$') else {
+        return Some(raw);
+    };
+    let var = var.trim_start_matches('{').trim_end_matches('}');
+    std::env::var(var)
+        .ok()
+        .map(|v| v.trim().to_string())
+        .filter(|v| !v.is_empty())
+}
+
+fn read_json(path: &Path) -> Result<Value, String> {
+    let text = std::fs::read_to_string(path)
+        .map_err(|e| format!("读取文件失败: {}: {}", path.display(), e))?;
+    serde_json::from_str(&text)
+        .map_err(|e| format!("解析 JSON 失败: {}: {}", path.display(), e))
+}
+
+fn candidate(
+    config: &MultiAppConfig,
+    app_type: AppType,
+    name: &str,
+    settings: Value,
+    source_path: &Path,
+) -> ProviderCandidate {
+    let mut provider = Provider::with_id(String::new(), name.to_string(), settings, None);
+    if !crate::provider_share::has_api_key(&app_type, &provider.settings_config) {
+        provider.meta.get_or_insert_with(Default::default).needs_key = true;
+    }
+    let identity = crate::provider_discovery::identity(&app_type, &provider);
+    ProviderCandidate {
+        duplicate_of: crate::provider_discovery::duplicate_of(config, &app_type, &identity),
+        app_type: app_type.as_str().to_string(),
+        source: DiscoverySource::ExternalTool,
+        source_path: Some(source_path.to_string_lossy().to_string()),
+        provider,
+    }
+}
+
+/// 由端点、Key 与模型生成 Claude 配置
+fn claude_settings(base_url: Option<String>, key: Option<String>, model: Option<String>) -> Value {
+    let mut env = Map::new();
+    if let Some(base_url) = base_url {
+        env.insert("ANTHROPIC_BASE_URL".to_string(), Value::String(base_url));
+    }
+    env.insert(
+        "ANTHROPIC_AUTH_TOKEN".to_string(),
+        Value::String(key.unwrap_or_default()),
+    );
+    if let Some(model) = model {
+        env.insert("ANTHROPIC_MODEL".to_string(), Value::String(model));
+    }
+    json!({ "env": env })
+}
+
+fn parse_ccs(config: &MultiAppConfig, path: &Path) -> Result<Vec<ProviderCandidate>, String> {
+    let root = read_json(path)?;
+    let profiles = root
+        .get("profiles")
+        .and_then(|v| v.as_object())
+        .ok_or_else(|| "未找到 profiles 字段，不是 ccs 配置".to_string())?;
+    let base_dir = path.parent().unwrap_or(Path::new("."));
+    let claude_settings_path = crate::config::get_claude_settings_path();
+
+    let mut candidates = Vec::new();
+    for (name, target) in profiles {
+        let Some(target) = target.as_str() else {
+            continue;
+        };
+        let mut settings_path = crate::settings::resolve_override_path(target.trim());
+        if settings_path.is_relative() {
+            settings_path = base_dir.join(settings_path);
+        }
+        // 指向 Claude 自身配置的 profile 由「扫描现有配置」处理
+        if settings_path == claude_settings_path {
+            continue;
+        }
+        match read_json(&settings_path) {
+            Ok(settings) if settings.get("env").is_some_and(|e| e.is_object()) => {
+                candidates.push(candidate(
+                    config,
+                    AppType::Claude,
+                    name,
+                    settings,
+                    &settings_path,
+                ));
+            }
+            Ok(_) => log::warn!("ccs profile {} 缺少 env，已跳过", name),
+            Err(e) => log::warn!("读取 ccs profile {} 失败: {}", name, e),
+        }
+    }
+    Ok(candidates)
+}
+
+fn parse_claude_code_router(
+    config: &MultiAppConfig,
+    path: &Path,
+) -> Result<Vec<ProviderCandidate>, String> {
+    let root = read_json(path)?;
+    let providers = root
+        .get("Providers")
+        .or_else(|| root.get("providers"))
+        .and_then(|v| v.as_array())
+        .ok_or_else(|| {
+            "未找到 Providers 字段，不是 claude-code-router 配置".to_string()
+        })?;
+
+    let mut candidates = Vec::new();
+    for entry in providers.iter().filter_map(|v| v.as_object()) {
+        let Some(url) = first_str(entry, &["api_base_url", "apiBaseUrl", "baseUrl"]) else {
+            continue;
+        };
+        let name = first_str(entry, NAME_KEYS).unwrap_or_else(|| url.clone());
+        let key = resolve_key(first_str(entry, &["api_key", "apiKey"]));
+        let model = entry
+            .get("models")
+            .and_then(|v| v.as_array())
+            .and_then(|models| models.iter().find_map(|m| m.as_str()))
+            .map(|m| m.to_string());
+        let url = url.trim_end_matches('/');
+
+        // Anthropic 协议端点导入为 Claude 供应商，其余（OpenAI chat/completions）导入为 Codex
+        if let Some(base) = url.strip_suffix("/v1/messages") {
+            let settings = claude_settings(Some(base.to_string()), key, model);
+            candidates.push(candidate(config, AppType::Claude, &name, settings, path));
+            continue;
+        }
+        let base = url.strip_suffix("/chat/completions").unwrap_or(url);
+        let model = model.unwrap_or_else(|| "gpt-5-codex".to_string());
+        let codex_config = crate::provider_discovery::codex_config_for(&name, base, &model)
+            .replace("wire_api = \"responses\"", "wire_api = \"chat\"");
+        let settings = json!({
+            "auth": { "OPENAI_API_KEY": key.unwrap_or_default() },
+            "config": codex_config,
+        });
+        candidates.push(candidate(config, AppType::Codex, &name, settings, path));
+    }
+    Ok(candidates)
+}
+
+fn parse_generic(config: &MultiAppConfig, path: &Path) -> Result<Vec<ProviderCandidate>, String> {
+    let root = read_json(path)?;
+    let list = ["providers", "profiles", "configs", "items"]
+        .iter()
+        .find_map(|k| root.get(*k))
+        .unwrap_or(&root);
+
+    // 数组：名称取自字段；对象：名称取自键
+    let entries: Vec<(Option<String>, &Map<String, Value>)> = match list {
+        Value::Array(items) => items
+            .iter()
+            .filter_map(|v| v.as_object())
+            .map(|obj| (None, obj))
+            .collect(),
+        Value::Object(map) => map
+            .iter()
+            .filter_map(|(k, v)| v.as_object().map(|obj| (Some(k.clone()), obj)))
+            .collect(),
+        _ => Vec::new(),
+    };
+
+    let mut candidates = Vec::new();
+    for (key_name, entry) in entries {
+        let env = entry.get("env").and_then(|v| v.as_object());
+        let name = first_str(entry, NAME_KEYS).or(key_name);
+        let settings = if let Some(env) = env.filter(|env| {
+            env.contains_key("ANTHROPIC_AUTH_TOKEN") || env.contains_key("ANTHROPIC_API_KEY")
+        }) {
+            json!({ "env": env })
+        } else {
+            let lookup = |keys: &[&str]| {
+                first_str(entry, keys).or_else(|| env.and_then(|e| first_str(e, keys)))
+            };
+            let base_url = lookup(BASE_URL_KEYS);
+            let key = resolve_key(lookup(API_KEY_KEYS));
+            // 既无端点也无 Key 的对象不是供应商
+            if base_url.is_none() && key.is_none() {
+                continue;
+            }
+            claude_settings(base_url, key, lookup(MODEL_KEYS))
+        };
+        let name = name.unwrap_or_else(|| {
+            Provider::with_id(String::new(), String::new(), settings.clone(), None)
+                .endpoint(&AppType::Claude)
+                .and_then(|e| reqwest::Url::parse(&e).ok())
+                .and_then(|u| u.host_str().map(|h| h.to_string()))
+                .unwrap_or_else(|| "imported".to_string())
+        });
+        candidates.push(candidate(config, AppType::Claude, &name, settings, path));
+    }
+    if candidates.is_empty() {
+        return Err("未在文件中识别到供应商".to_string());
+    }
+    Ok(candidates)
+}
+
+/// 根据文件内容推断格式
+fn detect_tool(path: &Path) -> ExternalTool {
+    match read_json(path) {
+        Ok(root) if root.get("profiles").is_some_and(|p| {
+            p.as_object()
+                .is_some_and(|m| !m.is_empty() && m.values().all(|v| v.is_string()))
+        }) =>
+        {
+            ExternalTool::Ccs
+        }
+        Ok(root) if root.get("Providers").is_some_and(|p| p.is_array()) => {
+            ExternalTool::ClaudeCodeRouter
+        }
+        _ => ExternalTool::Generic,
+    }
+}
+
+/// 解析指定文件中的供应商；tool 为 None 时按内容推断格式。结果可通过 import_candidates 导入
+pub fn parse_file(
+    config: &MultiAppConfig,
+    path: &Path,
+    tool: Option<ExternalTool>,
+) -> Result<Vec<ProviderCandidate>, String> {
+    if !path.is_file() {
+        return Err(format!("文件不存在: {}", path.display()));
+    }
+    match tool.unwrap_or_else(|| detect_tool(path)) {
+        ExternalTool::Ccs => parse_ccs(config, path),
+        ExternalTool::ClaudeCodeRouter => parse_claude_code_router(config, path),
+        ExternalTool::Generic => parse_generic(config, path),
+    }
+}
+
+fn known_locations() -> Vec<(ExternalTool, PathBuf)> {
+    let Some(home) = dirs::home_dir() else {
+        return Vec::new();
+    };
+    vec![
+        (ExternalTool::Ccs, home.join(".ccs").join("config.json")),
+        (
+            ExternalTool::ClaudeCodeRouter,
+            home.join(".claude-code-router").join("config.json"),
+        ),
+    ]
+}
+
+/// 扫描已知工具的默认配置位置
+pub fn scan(config: &MultiAppConfig) -> Vec<ExternalToolSource> {
+    known_locations()
+        .into_iter()
+        .filter(|(_, path)| path.is_file())
+        .map(|(tool, path)| {
+            let (candidates, error) = match parse_file(config, &path, Some(tool)) {
+                Ok(candidates) => (candidates, None),
+                Err(e) => (Vec::new(), Some(e)),
+            };
+            ExternalToolSource {
+                tool,
+                path: path.to_string_lossy().to_string(),
+                candidates,
+                error,
+            }
+        })
+        .collect()
+}
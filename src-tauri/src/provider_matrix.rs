@@ -0,0 +1,144 @@
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+use crate::provider_quota::ProviderQuota;
+
+/// 对比矩阵导出格式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum MatrixFormat {
+    Markdown,
+    Csv,
+}
+
+/// 对比矩阵中的一行（一个供应商）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderMatrixRow {
+    pub app_type: String,
+    pub id: String,
+    pub name: String,
+    pub endpoint: Option<String>,
+    pub models: Vec<String>,
+    /// 平均延迟（毫秒）
+    pub latency_ms: Option<u128>,
+    /// 失败率（0.0 ~ 1.0）
+    pub failure_rate: Option<f64>,
+    /// 缓存的额度查询结果（剩余 / 总额，含单位）
+    pub quota: Option<String>,
+    pub notes: Option<String>,
+}
+
+const HEADERS: [&str; 9] = [
+    "App",
+    "Provider",
+    "ID",
+    "Endpoint",
+    "Models",
+    "Latency (ms)",
+    "Failure Rate",
+    "Quota",
+    "Notes",
+];
+
+/// 格式化额度：`剩余 / 总额 单位`；查询失败或没有数值时返回 None
+fn format_quota(quota: &ProviderQuota) -> Option<String> {
+    if quota.error.is_some() {
+        return None;
+    }
+    let amount = match (quota.remaining, quota.total) {
+        (Some(remaining), Some(total)) => format!("{:.2} / {:.2}", remaining, total),
+        (Some(remaining), None) => format!("{:.2}", remaining),
+        (None, Some(total)) => format!("? / {:.2}", total),
+        (None, None) => return None,
+    };
+    Some(match quota.unit.as_deref().filter(|u| !u.is_empty()) {
+        Some(unit) => format!("{} {}", amount, unit),
+        None => amount,
+    })
+}
+
+/// 从配置中收集所有供应商的基础信息（不含延迟等需要测量的数据）；
+/// 额度取自额度查询缓存，不发起网络请求
+pub fn collect_rows(config: &MultiAppConfig) -> Vec<ProviderMatrixRow> {
+    let quotas = crate::provider_quota::list_cached_quotas();
+    let mut rows = Vec::new();
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager(&app_type) else {
+            continue;
+        };
+        let mut providers: Vec<&Provider> = manager.providers.values().collect();
+        providers.sort_by(|a, b| a.sort_index.cmp(&b.sort_index).then(a.name.cmp(&b.name)));
+        for provider in providers {
+            rows.push(ProviderMatrixRow {
+                app_type: app_type.as_str().to_string(),
+                id: provider.id.clone(),
+                name: provider.name.clone(),
+                endpoint: provider.endpoint(&app_type),
+                models: provider.models(&app_type),
+                latency_ms: None,
+                failure_rate: None,
+                quota: quotas
+                    .iter()
+                    .find(|q| q.app_type == app_type.as_str() && q.provider_id == provider.id)
+                    .and_then(format_quota),
+                notes: provider.meta.as_ref().and_then(|m| m.notes.clone()),
+            });
+        }
+    }
+    rows
+}
+
+fn row_cells(row: &ProviderMatrixRow) -> [String; 9] {
+    [
+        row.app_type.clone(),
+        row.name.clone(),
+        row.id.clone(),
+        row.endpoint.clone().unwrap_or_default(),
+        row.models.join(", "),
+        row.latency_ms.map(|v| v.to_string()).unwrap_or_default(),
+        row.failure_rate
+            .map(|v| format!("{:.1}%", v * 100.0))
+            .unwrap_or_default(),
+        row.quota.clone().unwrap_or_default(),
+        row.notes.clone().unwrap_or_default(),
+    ]
+}
+
+fn escape_markdown(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn escape_csv(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+/// 将矩阵渲染为 Markdown 表格或 CSV 文本
+pub fn render(rows: &[ProviderMatrixRow], format: MatrixFormat) -> String {
+    let mut out = String::new();
+    match format {
+        MatrixFormat::Markdown => {
+            out.push_str(&format!("| {} |\n", HEADERS.join(" | ")));
+            out.push_str(&format!("|{}\n", " --- |".repeat(HEADERS.len())));
+            for row in rows {
+                let cells: Vec<String> = row_cells(row).iter().map(|c| escape_markdown(c)).collect();
+                out.push_str(&format!("| {} |\n", cells.join(" | ")));
+            }
+        }
+        MatrixFormat::Csv => {
+            out.push_str(&HEADERS.join(","));
+            out.push('\n');
+            for row in rows {
+                let cells: Vec<String> = row_cells(row).iter().map(|c| escape_csv(c)).collect();
+                out.push_str(&cells.join(","));
+                out.push('\n');
+            }
+        }
+    }
+    out
+}
@@ -0,0 +1,237 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+use crate::provider::QuotaEndpoint;
+
+/// 默认缓存有效期（分钟）
+const DEFAULT_CACHE_MINUTES: u64 = 30;
+const QUOTA_TIMEOUT_SECS: u64 = 10;
+
+/// 序列化读写 provider_quota.json
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 额度缓存文件 ~/.cc-switch/provider_quota.json
+fn get_quota_path() -> PathBuf {
+    get_app_config_dir().join("provider_quota.json")
+}
+
+/// 供应商额度查询结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderQuota {
+    pub app_type: String,
+    pub provider_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remaining: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit: Option<String>,
+    /// 查询时间（秒）
+    pub fetched_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+    /// 是否来自缓存
+    #[serde(default, skip_deserializing)]
+    pub cached: bool,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct QuotaStore {
+    /// 以 `<app>:<provider_id>` 为键
+    #[serde(default)]
+    entries: HashMap<String, ProviderQuota>,
+}
+
+impl QuotaStore {
+    fn load() -> Self {
+        let path = get_quota_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取额度缓存失败，将重新查询: {}", e);
+            Self::default()
+        })
+    }
+}
+
+fn cache_key(app_type: &str, provider_id: &str) -> String {
+    format!("{}:{}", app_type, provider_id)
+}
+
+/// 读取缓存的额度；超过有效期（max_age_minutes）时返回 None
+pub fn cached_quota(
+    app_type: &str,
+    provider_id: &str,
+    max_age_minutes: Option<u64>,
+) -> Option<ProviderQuota> {
+    let max_age = max_age_minutes.unwrap_or(DEFAULT_CACHE_MINUTES) as i64 * 60;
+    let _guard = STORE_LOCK.lock();
+    let mut quota = QuotaStore::load()
+        .entries
+        .remove(&cache_key(app_type, provider_id))?;
+    if chrono::Utc::now().timestamp() - quota.fetched_at > max_age {
+        return None;
+    }
+    quota.cached = true;
+    Some(quota)
+}
+
+/// 列出所有缓存的额度（不发起请求，供供应商列表展示）
+pub fn list_cached_quotas() -> Vec<ProviderQuota> {
+    let _guard = STORE_LOCK.lock();
+    QuotaStore::load()
+        .entries
+        .into_values()
+        .map(|mut quota| {
+            quota.cached = true;
+            quota
+        })
+        .collect()
+}
+
+fn store_quota(quota: &ProviderQuota) {
+    let _guard = STORE_LOCK.lock();
+    let mut store = QuotaStore::load();
+    store.entries.insert(
+        cache_key(&quota.app_type, &quota.provider_id),
+        quota.clone(),
+    );
+    if let Err(e) = write_json_file(&get_quota_path(), &store) {
+        log::warn!("保存额度缓存失败: {}", e);
+    }
+}
+
+/// 按 `a.b.0.c` 形式的路径取数值；兼容数字字符串
+fn number_at(value: &serde_json::Value, path: &str) -> Option<f64> {
+    let node = path
+        .split('.')
+        .filter(|s| !s.is_empty())
+        .try_fold(value, |node, segment| match segment.parse::<usize>() {
+            Ok(index) if node.is_array() => node.get(index),
+            _ => node.get(segment),
+        })?;
+    match node {
+        serde_json::Value::Number(n) => n.as_f64(),
+        serde_json::Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
+
+async fn request_quota(
+    endpoint: &QuotaEndpoint,
+    api_key: &str,
+    base_url: &str,
+) -> Result<(Option<f64>, Option<f64>), String> {
+    let url = endpoint
+        .url
+        .replace("{{baseUrl}}", base_url.trim_end_matches('/'));
+    let client = crate::speedtest::build_client(QUOTA_TIMEOUT_SECS)?;
+    let mut request = client.get(&url);
+    if endpoint.headers.is_empty() {
+        request = request.bearer_auth(api_key);
+    } else {
+        for (name, value) in &endpoint.headers {
+            request = request.header(name, value.replace("{{apiKey}}", api_key));
+        }
+    }
+
+    let response = request
+        .send()
+        .await
+        .map_err(|e| format!("请求额度接口失败: {}", e))?;
+    let status = response.status();
+    if !status.is_success() {
+        return Err(format!("额度接口返回 HTTP {}", status.as_u16()));
+    }
+    let body: serde_json::Value = response
+        .json()
+        .await
+        .map_err(|e| format!("解析额度响应失败: {}", e))?;
+
+    let remaining = number_at(&body, &endpoint.remaining_path)
+        .ok_or_else(|| format!("响应中未找到剩余额度字段: {}", endpoint.remaining_path))?;
+    let total = endpoint
+        .total_path
+        .as_deref()
+        .and_then(|path| number_at(&body, path));
+    Ok((Some(remaining), total))
+}
+
+/// 查询供应商额度并写入缓存；查询失败时结果中带 error，同样会被缓存
+pub async fn fetch_quota(
+    app_type: &str,
+    provider_id: &str,
+    endpoint: &QuotaEndpoint,
+    api_key: &str,
+    base_url: &str,
+) -> ProviderQuota {
+    let (remaining, total, error) = match request_quota(endpoint, api_key, base_url).await {
+        Ok((remaining, total)) => (remaining, total, None),
+        Err(e) => (None, None, Some(e)),
+    };
+    let quota = ProviderQuota {
+        app_type: app_type.to_string(),
+        provider_id: provider_id.to_string(),
+        remaining,
+        total,
+        unit: endpoint.unit.clone(),
+        fetched_at: chrono::Utc::now().timestamp(),
+        error,
+        cached: false,
+    };
+    let was_low = {
+        let _guard = STORE_LOCK.lock();
+        QuotaStore::load()
+            .entries
+            .get(&cache_key(app_type, provider_id))
+            .is_some_and(is_low)
+    };
+    store_quota(&quota);
+    // 仅在从充足变为不足时提醒一次
+    if !was_low && is_low(&quota) {
+        notify_quota_low(&quota);
+    }
+    quota
+}
+
+/// 剩余额度占总额度的百分比；缺少总额度时无法判断
+fn remaining_percent(quota: &ProviderQuota) -> Option<f64> {
+    let total = quota.total.filter(|t| *t > 0.0)?;
+    Some(quota.remaining? / total * 100.0)
+}
+
+fn is_low(quota: &ProviderQuota) -> bool {
+    remaining_percent(quota).is_some_and(|p| p <= crate::notifications::quota_low_percent())
+}
+
+fn notify_quota_low(quota: &ProviderQuota) {
+    let unit = quota
+        .unit
+        .as_deref()
+        .map(|u| format!(" {}", u))
+        .unwrap_or_default();
+    let percent = format!("{:.1}", remaining_percent(quota).unwrap_or_default());
+    let message = crate::i18n::tf(
+        "notification.quotaLow.message",
+        &[
+            ("app", &quota.app_type),
+            ("provider", &quota.provider_id),
+            ("percent", &percent),
+            ("remaining", &quota.remaining.unwrap_or_default()),
+            ("unit", &unit),
+        ],
+    );
+    crate::notifications::notify(
+        crate::notifications::NotificationRecord::new(
+            crate::notifications::NotificationCategory::QuotaLow,
+            crate::i18n::t("notification.quotaLow.title"),
+            message,
+        )
+        .with_provider(&quota.app_type, &quota.provider_id),
+    );
+}
@@ -0,0 +1,341 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::collections::HashSet;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+
+/// 分享文件格式标识
+const SHARE_FORMAT: &str = "cc-switch-providers";
+const SHARE_VERSION: u32 = 1;
+
+/// 可分享的供应商列表文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedProviders {
+    pub format: String,
+    pub version: u32,
+    /// 导出时间（秒）
+    pub exported_at: i64,
+    /// 是否包含 API Key
+    pub secrets_included: bool,
+    pub providers: Vec<SharedProvider>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedProvider {
+    pub app_type: String,
+    pub provider: Provider,
+}
+
+/// 导入结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedImportResult {
+    /// 新增的供应商
+    pub imported: Vec<SharedImportedProvider>,
+    /// 与现有供应商同名且端点相同而跳过的供应商名称
+    pub skipped: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SharedImportedProvider {
+    pub app_type: String,
+    pub id: String,
+    pub name: String,
+    pub needs_key: bool,
+    /// 导入时出于安全原因移除的字段，需由用户确认后重新配置
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub removed_fields: Vec<String>,
+}
+
+fn non_empty(value: Option<&Value>) -> bool {
+    value
+        .and_then(|v| v.as_str())
+        .is_some_and(|s| !s.trim().is_empty())
+}
+
+/// 配置中是否已填写 API Key（加密引用同样视为已填写；Codex 官方登录的 tokens 也算）
+pub fn has_api_key(app_type: &AppType, settings: &Value) -> bool {
+    match app_type {
+        AppType::Claude => ["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"]
+            .iter()
+            .any(|k| non_empty(settings.pointer(&format!("/env/{}", k)))),
+        AppType::Codex => {
+            non_empty(settings.pointer("/auth/OPENAI_API_KEY"))
+                || settings
+                    .pointer("/auth/tokens")
+                    .is_some_and(|t| !t.is_null())
+        }
+        AppType::Gemini => non_empty(settings.pointer("/env/GEMINI_API_KEY")),
+        AppType::OpenCode | AppType::Crush => crate::app_adapter::adapter_for(app_type)
+            .api_key(settings)
+            .is_some(),
+    }
+}
+
+/// 清空配置中的 API Key，返回是否确有密钥被移除
+fn strip_secrets(app_type: &AppType, settings: &mut Value) -> bool {
+    if !has_api_key(app_type, settings) {
+        return false;
+    }
+    match app_type {
+        AppType::Claude => {
+            if let Some(env) = settings.get_mut("env").and_then(|v| v.as_object_mut()) {
+                for key in ["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"] {
+                    if let Some(slot) = env.get_mut(key) {
+                        *slot = Value::String(String::new());
+                    }
+                }
+            }
+        }
+        AppType::Codex => {
+            if let Some(obj) = settings.as_object_mut() {
+                obj.insert("auth".to_string(), json!({ "OPENAI_API_KEY": "" }));
+            }
+        }
+        AppType::Gemini => {
+            if let Some(slot) = settings.pointer_mut("/env/GEMINI_API_KEY") {
+                *slot = Value::String(String::new());
+            }
+        }
+        AppType::OpenCode | AppType::Crush => {
+            for pointer in crate::app_adapter::adapter_for(app_type).secret_pointers(settings) {
+                if let Some(slot) = settings.pointer_mut(&pointer) {
+                    *slot = Value::String(String::new());
+                }
+            }
+        }
+    }
+    true
+}
+
+/// 导出全部供应商为可分享的 JSON；include_secrets 为 false 时清空 API Key 与 meta 中可能带凭据的字段，
+/// 并标记为需要补充 Key
+pub fn export_providers(config: &MultiAppConfig, include_secrets: bool) -> Result<String, String> {
+    let mut providers = Vec::new();
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager(&app_type) else {
+            continue;
+        };
+        let mut list: Vec<&Provider> = manager.providers.values().collect();
+        list.sort_by_key(|p| (p.sort_index.unwrap_or(usize::MAX), p.created_at));
+
+        for provider in list {
+            let mut shared = provider.clone();
+            shared.created_at = None;
+            shared.sort_index = None;
+            if include_secrets {
+                shared.settings_config =
+                    crate::secrets::resolve_settings(&app_type, &shared.settings_config)?;
+            } else {
+                let stripped = strip_secrets(&app_type, &mut shared.settings_config);
+                // 额度查询请求头、代理地址、用量脚本等也可能带有凭据
+                let meta_stripped = !strip_sensitive_meta(&mut shared).is_empty();
+                if stripped || meta_stripped {
+                    shared.meta.get_or_insert_with(Default::default).needs_key = true;
+                }
+                // 轮换中的 Key 同样不导出
+                if let Some(meta) = shared.meta.as_mut() {
+                    meta.key_rotation = None;
+                }
+            }
+            providers.push(SharedProvider {
+                app_type: app_type.as_str().to_string(),
+                provider: shared,
+            });
+        }
+    }
+
+    let shared = SharedProviders {
+        format: SHARE_FORMAT.to_string(),
+        version: SHARE_VERSION,
+        exported_at: chrono::Utc::now().timestamp(),
+        secrets_included: include_secrets,
+        providers,
+    };
+    serde_json::to_string_pretty(&shared).map_err(|e| format!("序列化供应商列表失败: {}", e))
+}
+
+/// Claude settings.json 中会执行本地命令的字段
+const CLAUDE_COMMAND_KEYS: [&str; 5] = [
+    "hooks",
+    "apiKeyHelper",
+    "statusLine",
+    "awsAuthRefresh",
+    "awsCredentialExport",
+];
+
+/// Codex config.toml 中会执行本地命令的表 / 字段
+const CODEX_COMMAND_KEYS: [&str; 2] = ["notify", "mcp_servers"];
+
+/// 清空 meta 中会执行命令或可能携带凭据的字段（启动命令、额度查询接口、用量脚本、代理、附加配置），
+/// 返回被清空的字段
+fn strip_sensitive_meta(provider: &mut Provider) -> Vec<String> {
+    let mut removed = Vec::new();
+    if let Some(meta) = provider.meta.as_mut() {
+        if meta.local_server.take().is_some() {
+            removed.push("meta.localServer".to_string());
+        }
+        if meta.quota_endpoint.take().is_some() {
+            removed.push("meta.quotaEndpoint".to_string());
+        }
+        if meta.usage_script.take().is_some() {
+            removed.push("meta.usageScript".to_string());
+        }
+        if meta.proxy.take().is_some() {
+            removed.push("meta.proxy".to_string());
+        }
+        if meta.extras.take().is_some() {
+            removed.push("meta.extras".to_string());
+        }
+    }
+    removed
+}
+
+/// 移除 Codex config.toml 中的 notify 与 [mcp_servers.*]，保留其余内容与格式；
+/// 无法解析时整体丢弃 config
+fn strip_codex_commands(settings: &mut Value, removed: &mut Vec<String>) {
+    let Some(config) = settings.get("config").and_then(|v| v.as_str()) else {
+        return;
+    };
+    match config.parse::<toml_edit::DocumentMut>() {
+        Ok(mut doc) => {
+            let mut changed = false;
+            for key in CODEX_COMMAND_KEYS {
+                if doc.remove(key).is_some() {
+                    removed.push(format!("config.{}", key));
+                    changed = true;
+                }
+            }
+            if changed {
+                settings["config"] = Value::String(doc.to_string());
+            }
+        }
+        Err(_) => {
+            settings["config"] = Value::String(String::new());
+            removed.push("config".to_string());
+        }
+    }
+}
+
+/// 移除分享列表中会执行命令或把 API Key 发往其他地址的配置，返回被移除的字段：
+/// meta 中的敏感字段（见 strip_sensitive_meta）、Claude 配置中的 hooks / apiKeyHelper 等命令字段，
+/// 以及 Codex config.toml 中的 notify 与 MCP 服务器
+fn strip_untrusted(app_type: &AppType, provider: &mut Provider) -> Vec<String> {
+    let mut removed = strip_sensitive_meta(provider);
+    if matches!(app_type, AppType::Codex) {
+        strip_codex_commands(&mut provider.settings_config, &mut removed);
+    }
+    if matches!(app_type, AppType::Claude) {
+        if let Some(settings) = provider.settings_config.as_object_mut() {
+            for key in CLAUDE_COMMAND_KEYS {
+                if settings.remove(key).is_some() {
+                    removed.push(key.to_string());
+                }
+            }
+        }
+    }
+    removed
+}
+
+fn normalized_endpoint(app_type: &AppType, provider: &Provider) -> String {
+    provider
+        .endpoint(app_type)
+        .map(|e| e.trim().trim_end_matches('/').to_lowercase())
+        .unwrap_or_default()
+}
+
+/// 导入分享的供应商列表；同一应用下名称与端点都相同的供应商视为重复并跳过，
+/// id 冲突时自动追加序号。会执行命令或外发 API Key 的字段一律移除（见 strip_untrusted）
+pub fn import_providers(
+    config: &mut MultiAppConfig,
+    json_text: &str,
+) -> Result<SharedImportResult, String> {
+    let shared: SharedProviders =
+        serde_json::from_str(json_text).map_err(|e| format!("解析供应商列表失败: {}", e))?;
+    if shared.format != SHARE_FORMAT {
+        return Err(format!("不支持的文件格式: {}", shared.format));
+    }
+    if shared.version > SHARE_VERSION {
+        return Err(format!(
+            "供应商列表版本 {} 高于当前支持的版本 {}，请先升级 cc-switch",
+            shared.version, SHARE_VERSION
+        ));
+    }
+
+    let now = chrono::Utc::now().timestamp_millis();
+    let mut result = SharedImportResult {
+        imported: Vec::new(),
+        skipped: Vec::new(),
+    };
+    for entry in shared.providers {
+        let app_type = AppType::from(entry.app_type.as_str());
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        let mut provider = entry.provider;
+        if !provider.settings_config.is_object() {
+            return Err(format!("供应商 {} 的配置不是 JSON 对象", provider.name));
+        }
+
+        let endpoint = normalized_endpoint(&app_type, &provider);
+        if manager
+            .providers
+            .values()
+            .any(|p| p.name == provider.name && normalized_endpoint(&app_type, p) == endpoint)
+        {
+            result.skipped.push(provider.name);
+            continue;
+        }
+
+        // 加密引用只在导出方的密钥库中有效，视同未包含 Key
+        if crate::secrets::has_secret_refs(&app_type, &provider.settings_config)
+            && strip_secrets(&app_type, &mut provider.settings_config)
+        {
+            provider.meta.get_or_insert_with(Default::default).needs_key = true;
+        }
+
+        let removed_fields = strip_untrusted(&app_type, &mut provider);
+        if !removed_fields.is_empty() {
+            log::warn!(
+                "导入供应商 {} 时移除了不受信任的字段: {}",
+                provider.name,
+                removed_fields.join(", ")
+            );
+        }
+
+        // 分组只在导出方的配置中有效
+        if let Some(meta) = provider.meta.as_mut() {
+            if meta
+                .group_id
+                .as_ref()
+                .is_some_and(|id| !manager.groups.iter().any(|g| &g.id == id))
+            {
+                meta.group_id = None;
+            }
+        }
+
+        let existing: HashSet<String> = manager.providers.keys().cloned().collect();
+        let base = if provider.id.trim().is_empty() {
+            provider.name.clone()
+        } else {
+            provider.id.clone()
+        };
+        provider.id = crate::provider_discovery::unique_id(&existing, &base);
+        provider.created_at = Some(now);
+        provider.sort_index = None;
+
+        result.imported.push(SharedImportedProvider {
+            app_type: app_type.as_str().to_string(),
+            id: provider.id.clone(),
+            name: provider.name.clone(),
+            needs_key: provider.needs_key(),
+            removed_fields,
+        });
+        manager.providers.insert(provider.id.clone(), provider);
+    }
+    Ok(result)
+}
@@ -0,0 +1,377 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+
+/// 健康检查失败事件
+pub const EVENT_PROVIDER_HEALTH_FAILED: &str = "provider-health-failed";
+
+const HOUR: i64 = 3600;
+const DAY: i64 = 24 * HOUR;
+/// 原始采样保留时长，超出后合并为小时桶
+const RAW_RETENTION: i64 = DAY;
+/// 小时桶保留时长，超出后合并为天桶
+const HOURLY_RETENTION: i64 = 7 * DAY;
+/// 数据最长保留时长
+const MAX_RETENTION: i64 = 90 * DAY;
+
+/// 序列化读写 provider_trends.json，避免并发记录时互相覆盖
+static STORE_LOCK: Mutex<()> = Mutex::new(());
+
+/// 趋势存储文件 ~/.cc-switch/provider_trends.json
+fn get_trends_path() -> PathBuf {
+    get_app_config_dir().join("provider_trends.json")
+}
+
+/// 时间序列中的一个点（原始采样或降采样后的桶）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TrendPoint {
+    /// 起始时间（秒）
+    pub ts: i64,
+    /// 桶宽度（秒），0 表示原始采样
+    pub resolution: i64,
+    pub samples: u32,
+    pub failures: u32,
+    /// 成功采样的平均延迟
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub avg_latency_ms: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_latency_ms: Option<u64>,
+}
+
+impl TrendPoint {
+    fn latency_count(&self) -> u32 {
+        if self.avg_latency_ms.is_some() {
+            self.samples - self.failures
+        } else {
+            0
+        }
+    }
+
+    /// 合并另一个点（按成功采样数加权平均延迟）
+    fn merge(&mut self, other: &TrendPoint) {
+        let (a, b) = (self.latency_count(), other.latency_count());
+        self.avg_latency_ms = match (self.avg_latency_ms, other.avg_latency_ms) {
+            (Some(x), Some(y)) if a + b > 0 => Some((x * a as f64 + y * b as f64) / (a + b) as f64),
+            (x, y) => x.or(y),
+        };
+        self.max_latency_ms = self.max_latency_ms.max(other.max_latency_ms);
+        self.samples += other.samples;
+        self.failures += other.failures;
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct TrendStore {
+    /// 以 `<app>:<provider_id>` 为键
+    #[serde(default)]
+    series: HashMap<String, Vec<TrendPoint>>,
+}
+
+impl TrendStore {
+    fn load() -> Self {
+        let path = get_trends_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取供应商趋势数据失败，将重新记录: {}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(&get_trends_path(), self).map_err(Into::into)
+    }
+}
+
+fn series_key(app_type: &str, provider_id: &str) -> String {
+    format!("{}:{}", app_type, provider_id)
+}
+
+/// 降采样：1 天前的原始采样合并为小时桶，7 天前的合并为天桶，丢弃 90 天前的数据
+fn downsample(points: Vec<TrendPoint>, now: i64) -> Vec<TrendPoint> {
+    let mut buckets: Vec<TrendPoint> = Vec::with_capacity(points.len());
+    for point in points {
+        let age = now - point.ts;
+        if age > MAX_RETENTION {
+            continue;
+        }
+        let resolution = if age > HOURLY_RETENTION {
+            DAY
+        } else if age > RAW_RETENTION {
+            HOUR
+        } else {
+            0
+        };
+        if resolution <= point.resolution {
+            buckets.push(point);
+            continue;
+        }
+
+        let start = point.ts - point.ts.rem_euclid(resolution);
+        match buckets
+            .iter_mut()
+            .find(|b| b.resolution == resolution && b.ts == start)
+        {
+            Some(bucket) => bucket.merge(&point),
+            None => buckets.push(TrendPoint {
+                ts: start,
+                resolution,
+                ..point
+            }),
+        }
+    }
+    buckets.sort_by_key(|p| p.ts);
+    buckets
+}
+
+/// 记录一次健康检查 / 探测结果
+pub fn record_sample(
+    app_type: &str,
+    provider_id: &str,
+    latency_ms: Option<u64>,
+    ok: bool,
+) -> Result<(), String> {
+    let _guard = STORE_LOCK.lock().map_err(crate::error::lock_failed)?;
+    let now = chrono::Utc::now().timestamp();
+    let mut store = TrendStore::load();
+    let mut points = store
+        .series
+        .remove(&series_key(app_type, provider_id))
+        .unwrap_or_default();
+    points.push(TrendPoint {
+        ts: now,
+        resolution: 0,
+        samples: 1,
+        failures: u32::from(!ok),
+        avg_latency_ms: latency_ms.filter(|_| ok).map(|v| v as f64),
+        max_latency_ms: latency_ms.filter(|_| ok),
+    });
+    store
+        .series
+        .insert(series_key(app_type, provider_id), downsample(points, now));
+    store.save()
+}
+
+/// 查询时间范围
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum TrendRange {
+    #[serde(rename = "24h")]
+    Day,
+    #[serde(rename = "7d")]
+    Week,
+    #[serde(rename = "30d")]
+    Month,
+    #[serde(rename = "90d")]
+    Quarter,
+}
+
+impl TrendRange {
+    fn seconds(&self) -> i64 {
+        match self {
+            TrendRange::Day => DAY,
+            TrendRange::Week => 7 * DAY,
+            TrendRange::Month => 30 * DAY,
+            TrendRange::Quarter => 90 * DAY,
+        }
+    }
+}
+
+/// 供应商趋势查询结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderTrends {
+    pub app_type: String,
+    pub provider_id: String,
+    pub points: Vec<TrendPoint>,
+    pub samples: u32,
+    /// 失败率（0.0 ~ 1.0），无采样时为 None
+    pub failure_rate: Option<f64>,
+    pub avg_latency_ms: Option<f64>,
+}
+
+/// 获取供应商在指定时间范围内的趋势数据
+pub fn get_provider_trends(app_type: &str, provider_id: &str, range: TrendRange) -> ProviderTrends {
+    let since = chrono::Utc::now().timestamp() - range.seconds();
+    let points: Vec<TrendPoint> = {
+        let _guard = STORE_LOCK.lock();
+        TrendStore::load()
+            .series
+            .remove(&series_key(app_type, provider_id))
+            .unwrap_or_default()
+    }
+    .into_iter()
+    .filter(|p| p.ts + p.resolution >= since)
+    .collect();
+
+    let mut total: Option<TrendPoint> = None;
+    for point in &points {
+        match total.as_mut() {
+            Some(t) => t.merge(point),
+            None => total = Some(point.clone()),
+        }
+    }
+
+    ProviderTrends {
+        app_type: app_type.to_string(),
+        provider_id: provider_id.to_string(),
+        samples: total.as_ref().map(|t| t.samples).unwrap_or(0),
+        failure_rate: total
+            .as_ref()
+            .filter(|t| t.samples > 0)
+            .map(|t| t.failures as f64 / t.samples as f64),
+        avg_latency_ms: total.as_ref().and_then(|t| t.avg_latency_ms),
+        points,
+    }
+}
+
+/// 健康检查目标
+#[derive(Debug, Clone)]
+pub struct HealthCheckTarget {
+    pub app_type: String,
+    pub provider_id: String,
+    pub endpoint: String,
+}
+
+/// 单个供应商的健康检查结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheckResult {
+    pub app_type: String,
+    pub provider_id: String,
+    pub endpoint: String,
+    pub ok: bool,
+    pub latency_ms: Option<u64>,
+    pub status: Option<u16>,
+    pub error: Option<String>,
+}
+
+/// 从配置收集所有配置了端点的供应商
+pub fn collect_targets(config: &crate::app_config::MultiAppConfig) -> Vec<HealthCheckTarget> {
+    use crate::app_config::AppType;
+
+    let mut targets = Vec::new();
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager(&app_type) else {
+            continue;
+        };
+        for provider in manager.providers.values() {
+            // 停用分组中的供应商不参与定时健康检查
+            if !manager.is_provider_enabled(&provider.id) {
+                continue;
+            }
+            if let Some(endpoint) = provider.endpoint(&app_type) {
+                targets.push(HealthCheckTarget {
+                    app_type: app_type.as_str().to_string(),
+                    provider_id: provider.id.clone(),
+                    endpoint,
+                });
+            }
+        }
+    }
+    targets
+}
+
+/// 仅测量端点延迟与可达性，不写入趋势数据、不发布事件
+pub async fn probe_targets(
+    targets: Vec<HealthCheckTarget>,
+) -> Result<Vec<HealthCheckResult>, String> {
+    let mut urls: Vec<String> = targets
+        .iter()
+        .map(|t| t.endpoint.trim().to_string())
+        .collect();
+    urls.sort();
+    urls.dedup();
+    let latencies = crate::speedtest::test_endpoints(urls, None).await?;
+
+    Ok(targets
+        .into_iter()
+        .map(|target| {
+            let probe = latencies.iter().find(|l| l.url == target.endpoint.trim());
+            let latency_ms = probe.and_then(|l| l.latency).map(|v| v as u64);
+            let status = probe.and_then(|l| l.status);
+            // 能收到响应即视为可达；5xx 视为服务端故障
+            let ok = latency_ms.is_some() && status.is_none_or(|s| s < 500);
+            let error = probe.and_then(|l| l.error.clone()).or_else(|| {
+                status
+                    .filter(|s| *s >= 500)
+                    .map(|s| format!("服务端错误: HTTP {}", s))
+            });
+            HealthCheckResult {
+                app_type: target.app_type,
+                provider_id: target.provider_id,
+                endpoint: target.endpoint,
+                ok,
+                latency_ms,
+                status,
+                error,
+            }
+        })
+        .collect())
+}
+
+/// 执行健康检查：测量端点延迟、写入趋势数据，并为失败的供应商发布 provider-health-failed 事件
+pub async fn run_health_checks(
+    targets: Vec<HealthCheckTarget>,
+) -> Result<Vec<HealthCheckResult>, String> {
+    let results = probe_targets(targets).await?;
+    for result in &results {
+        if let Err(e) = record_sample(
+            &result.app_type,
+            &result.provider_id,
+            result.latency_ms,
+            result.ok,
+        ) {
+            log::warn!("记录供应商趋势失败: {}", e);
+        }
+        if !result.ok {
+            crate::events::publish(
+                crate::events::AppEvent::new(EVENT_PROVIDER_HEALTH_FAILED)
+                    .with_provider(&result.app_type, &result.provider_id)
+                    .with_data(serde_json::json!({
+                        "endpoint": result.endpoint,
+                        "error": result.error,
+                    })),
+            );
+        }
+    }
+    Ok(results)
+}
+
+/// 启动定时健康检查（间隔由设置 healthCheckIntervalMinutes 控制，为空或 0 表示关闭）
+pub fn start_health_scheduler(app: tauri::AppHandle) {
+    use tauri::Manager;
+
+    tauri::async_runtime::spawn(async move {
+        let mut last_run: Option<std::time::Instant> = None;
+        loop {
+            tokio::time::sleep(std::time::Duration::from_secs(60)).await;
+
+            let Some(minutes) = crate::settings::get_settings()
+                .health_check_interval_minutes
+                .filter(|m| *m > 0)
+            else {
+                continue;
+            };
+            if last_run.is_some_and(|t| t.elapsed().as_secs() < minutes * 60) {
+                continue;
+            }
+            last_run = Some(std::time::Instant::now());
+
+            let targets = {
+                let state = app.state::<crate::store::AppState>();
+                let Ok(config) = state.config.lock() else {
+                    continue;
+                };
+                collect_targets(&config)
+            };
+            if let Err(e) = run_health_checks(targets).await {
+                log::warn!("定时健康检查失败: {}", e);
+            }
+        }
+    });
+}
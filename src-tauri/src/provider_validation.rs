@@ -0,0 +1,496 @@
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+/// 校验问题的严重程度
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ValidationSeverity {
+    /// 应用后 CLI 将无法工作
+    Error,
+    /// 可能导致请求失败
+    Warning,
+    Info,
+}
+
+/// 单条校验结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ValidationIssue {
+    /// 问题所在字段，例如 `env.ANTHROPIC_BASE_URL`、`config.model`
+    pub field: String,
+    pub severity: ValidationSeverity,
+    /// 机器可读的问题代码
+    pub code: String,
+    pub message: String,
+}
+
+/// 供应商校验报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProviderValidation {
+    /// 不存在 error 级别问题
+    pub valid: bool,
+    pub issues: Vec<ValidationIssue>,
+}
+
+#[derive(Default)]
+struct Issues(Vec<ValidationIssue>);
+
+impl Issues {
+    fn push(&mut self, field: &str, severity: ValidationSeverity, code: &str, message: String) {
+        self.0.push(ValidationIssue {
+            field: field.to_string(),
+            severity,
+            code: code.to_string(),
+            message,
+        });
+    }
+}
+
+/// 常见的占位符写法（来自模板未替换）
+const KEY_PLACEHOLDERS: [&str; 5] = ["your", "xxx", "<", "api_key", "api-key"];
+
+fn check_api_key(issues: &mut Issues, field: &str, key: Option<&str>, required: bool) {
+    let Some(key) = key.filter(|k| !k.is_empty()) else {
+        if required {
+            issues.push(
+                field,
+                ValidationSeverity::Warning,
+                "missing-api-key",
+                "未配置 API Key，请求可能被拒绝".to_string(),
+            );
+        }
+        return;
+    };
+    if key.trim() != key || key.chars().any(|c| c.is_whitespace()) {
+        issues.push(
+            field,
+            ValidationSeverity::Error,
+            "api-key-whitespace",
+            "API Key 含有空白字符".to_string(),
+        );
+    }
+    if !key.is_ascii() {
+        issues.push(
+            field,
+            ValidationSeverity::Error,
+            "api-key-non-ascii",
+            "API Key 含有非 ASCII 字符，无法放入请求头".to_string(),
+        );
+    }
+    let lower = key.to_ascii_lowercase();
+    if KEY_PLACEHOLDERS.iter().any(|p| lower.contains(p)) {
+        issues.push(
+            field,
+            ValidationSeverity::Warning,
+            "api-key-placeholder",
+            "API Key 看起来是未替换的占位符".to_string(),
+        );
+    } else if key.len() < 16 {
+        issues.push(
+            field,
+            ValidationSeverity::Warning,
+            "api-key-short",
+            format!("API Key 长度仅 {} 个字符，可能不完整", key.len()),
+        );
+    }
+}
+
+fn check_base_url(issues: &mut Issues, field: &str, url: &str) {
+    let parsed = match Url::parse(url.trim()) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            issues.push(
+                field,
+                ValidationSeverity::Error,
+                "invalid-base-url",
+                format!("Base URL 无效: {}", e),
+            );
+            return;
+        }
+    };
+    match parsed.scheme() {
+        "https" => {}
+        "http" if crate::local_server::is_local_endpoint(url) => {}
+        "http" => issues.push(
+            field,
+            ValidationSeverity::Warning,
+            "insecure-base-url",
+            "Base URL 使用明文 http，API Key 将以明文传输".to_string(),
+        ),
+        other => {
+            issues.push(
+                field,
+                ValidationSeverity::Error,
+                "unsupported-scheme",
+                format!("Base URL 协议不受支持: {}", other),
+            );
+            return;
+        }
+    }
+    if url != url.trim() {
+        issues.push(
+            field,
+            ValidationSeverity::Warning,
+            "base-url-whitespace",
+            "Base URL 首尾含有空白字符".to_string(),
+        );
+    }
+}
+
+fn check_model(issues: &mut Issues, field: &str, model: &str) {
+    if model.trim().is_empty() {
+        issues.push(
+            field,
+            ValidationSeverity::Error,
+            "empty-model",
+            "模型名称为空".to_string(),
+        );
+        return;
+    }
+    if model.chars().any(|c| c.is_whitespace()) {
+        issues.push(
+            field,
+            ValidationSeverity::Error,
+            "model-whitespace",
+            format!("模型名称含有空白字符: {:?}", model),
+        );
+    } else if !model
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || "-_.:/@[]".contains(c))
+    {
+        issues.push(
+            field,
+            ValidationSeverity::Warning,
+            "model-unusual-chars",
+            format!("模型名称含有不常见的字符: {}", model),
+        );
+    }
+}
+
+fn validate_claude(provider: &Provider, issues: &mut Issues) -> Option<String> {
+    let Some(settings) = provider.settings_config.as_object() else {
+        issues.push(
+            "settingsConfig",
+            ValidationSeverity::Error,
+            "not-object",
+            "Claude 配置必须是 JSON 对象".to_string(),
+        );
+        return None;
+    };
+    if let Err(e) = serde_json::to_string_pretty(settings) {
+        issues.push(
+            "settingsConfig",
+            ValidationSeverity::Error,
+            "not-serializable",
+            format!("无法序列化为 settings.json: {}", e),
+        );
+    }
+    let env = match settings.get("env") {
+        None => return None,
+        Some(env) => match env.as_object() {
+            Some(env) => env,
+            None => {
+                issues.push(
+                    "env",
+                    ValidationSeverity::Error,
+                    "env-not-object",
+                    "env 必须是 JSON 对象".to_string(),
+                );
+                return None;
+            }
+        },
+    };
+    for (key, value) in env {
+        if !value.is_string() {
+            issues.push(
+                &format!("env.{}", key),
+                ValidationSeverity::Warning,
+                "env-not-string",
+                format!("环境变量 {} 不是字符串，CLI 可能无法识别", key),
+            );
+        }
+    }
+
+    let base_url = env.get("ANTHROPIC_BASE_URL").and_then(|v| v.as_str());
+    if let Some(url) = base_url {
+        check_base_url(issues, "env.ANTHROPIC_BASE_URL", url);
+    }
+    let key_name = ["ANTHROPIC_AUTH_TOKEN", "ANTHROPIC_API_KEY"]
+        .into_iter()
+        .find(|k| env.contains_key(*k))
+        .unwrap_or("ANTHROPIC_AUTH_TOKEN");
+    let key = env.get(key_name).and_then(|v| v.as_str());
+    // 未设置 Base URL 时视为官方登录，无需 API Key；本地服务通常也不校验 Key
+    let required = base_url.is_some() && provider.category.as_deref() != Some("local");
+    check_api_key(issues, &format!("env.{}", key_name), key, required);
+
+    for (key, value) in env {
+        if key.starts_with("ANTHROPIC_") && key.ends_with("_MODEL") {
+            if let Some(model) = value.as_str() {
+                check_model(issues, &format!("env.{}", key), model);
+            }
+        }
+    }
+    base_url.map(|s| s.trim().to_string())
+}
+
+fn validate_codex(provider: &Provider, issues: &mut Issues) -> Option<String> {
+    let Some(settings) = provider.settings_config.as_object() else {
+        issues.push(
+            "settingsConfig",
+            ValidationSeverity::Error,
+            "not-object",
+            "Codex 配置必须是 JSON 对象".to_string(),
+        );
+        return None;
+    };
+
+    match settings.get("auth") {
+        Some(auth) if auth.is_object() => {
+            let key = auth.get("OPENAI_API_KEY").and_then(|v| v.as_str());
+            let required = provider.category.as_deref() != Some("local")
+                && auth.get("tokens").is_none_or(|t| t.is_null());
+            check_api_key(issues, "auth.OPENAI_API_KEY", key, required);
+        }
+        Some(_) => issues.push(
+            "auth",
+            ValidationSeverity::Error,
+            "auth-not-object",
+            "Codex auth 配置必须是 JSON 对象".to_string(),
+        ),
+        None => issues.push(
+            "auth",
+            ValidationSeverity::Error,
+            "missing-auth",
+            "Codex 配置缺少 auth 字段".to_string(),
+        ),
+    }
+
+    let text = match settings.get("config") {
+        None | Some(serde_json::Value::Null) => return None,
+        Some(value) => match value.as_str() {
+            Some(text) => text,
+            None => {
+                issues.push(
+                    "config",
+                    ValidationSeverity::Error,
+                    "config-not-string",
+                    "Codex config 字段必须是字符串".to_string(),
+                );
+                return None;
+            }
+        },
+    };
+    let table: toml::Table = match toml::from_str(text) {
+        Ok(table) => table,
+        Err(e) => {
+            issues.push(
+                "config",
+                ValidationSeverity::Error,
+                "invalid-toml",
+                format!("config.toml 语法错误: {}", e),
+            );
+            return None;
+        }
+    };
+
+    match table.get("model").map(|v| v.as_str()) {
+        Some(Some(model)) => check_model(issues, "config.model", model),
+        Some(None) => issues.push(
+            "config.model",
+            ValidationSeverity::Error,
+            "model-not-string",
+            "model 必须是字符串".to_string(),
+        ),
+        None => issues.push(
+            "config.model",
+            ValidationSeverity::Info,
+            "missing-model",
+            "未指定 model，将使用 Codex 默认模型".to_string(),
+        ),
+    }
+
+    let providers = table.get("model_providers").and_then(|v| v.as_table());
+    if let Some(name) = table.get("model_provider").and_then(|v| v.as_str()) {
+        let known = ["openai", "oss"].contains(&name);
+        if !known && !providers.is_some_and(|p| p.contains_key(name)) {
+            issues.push(
+                "config.model_provider",
+                ValidationSeverity::Error,
+                "unknown-model-provider",
+                format!(
+                    "model_provider = \"{}\" 未在 [model_providers] 中定义",
+                    name
+                ),
+            );
+        }
+    }
+
+    let endpoint = provider.endpoint(&AppType::Codex)?;
+    check_base_url(issues, "config.base_url", &endpoint);
+    Some(endpoint.trim().to_string())
+}
+
+fn validate_gemini(provider: &Provider, issues: &mut Issues) -> Option<String> {
+    let Some(settings) = provider.settings_config.as_object() else {
+        issues.push(
+            "settingsConfig",
+            ValidationSeverity::Error,
+            "not-object",
+            "Gemini 配置必须是 JSON 对象".to_string(),
+        );
+        return None;
+    };
+    if settings
+        .get("config")
+        .is_some_and(|v| !(v.is_object() || v.is_null()))
+    {
+        issues.push(
+            "config",
+            ValidationSeverity::Error,
+            "config-not-object",
+            "Gemini config 字段必须是 JSON 对象".to_string(),
+        );
+    }
+    let env = match settings.get("env") {
+        None => return None,
+        Some(env) => match env.as_object() {
+            Some(env) => env,
+            None => {
+                issues.push(
+                    "env",
+                    ValidationSeverity::Error,
+                    "env-not-object",
+                    "env 必须是 JSON 对象".to_string(),
+                );
+                return None;
+            }
+        },
+    };
+
+    let base_url = env.get("GOOGLE_GEMINI_BASE_URL").and_then(|v| v.as_str());
+    if let Some(url) = base_url {
+        check_base_url(issues, "env.GOOGLE_GEMINI_BASE_URL", url);
+    }
+    let key = env.get("GEMINI_API_KEY").and_then(|v| v.as_str());
+    // 未设置 Base URL 时可能使用 Google 账号登录，无需 API Key
+    let required = base_url.is_some() && provider.category.as_deref() != Some("local");
+    check_api_key(issues, "env.GEMINI_API_KEY", key, required);
+
+    if let Some(model) = env.get("GEMINI_MODEL").and_then(|v| v.as_str()) {
+        check_model(issues, "env.GEMINI_MODEL", model);
+    }
+    base_url.map(|s| s.trim().to_string())
+}
+
+/// 适配器应用（OpenCode、Crush）：结构由适配器校验，逐个检查供应商定义中的 Base URL 与 API Key
+fn validate_adapter(
+    app_type: &AppType,
+    provider: &Provider,
+    issues: &mut Issues,
+) -> Option<String> {
+    let adapter = crate::app_adapter::adapter_for(app_type);
+    let settings = &provider.settings_config;
+    if let Err(e) = adapter.validate(settings) {
+        issues.push(
+            "settingsConfig",
+            ValidationSeverity::Error,
+            "invalid-structure",
+            e,
+        );
+        return None;
+    }
+    let base_url = adapter.base_url(settings);
+    if let Some(url) = base_url.as_deref() {
+        check_base_url(issues, "baseUrl", url);
+    }
+    let required = provider.category.as_deref() != Some("local");
+    for pointer in adapter.secret_pointers(settings) {
+        let field = pointer.trim_start_matches('/').replace('/', ".");
+        let key = settings.pointer(&pointer).and_then(|v| v.as_str());
+        check_api_key(issues, &field, key, required && base_url.is_some());
+    }
+    for model in provider.models(app_type) {
+        check_model(issues, "model", &model);
+    }
+    base_url
+}
+
+/// 校验供应商配置；check_network 为 true 时额外探测 Base URL 可达性
+pub async fn validate_provider(
+    app_type: &AppType,
+    provider: &Provider,
+    check_network: bool,
+) -> ProviderValidation {
+    let mut issues = Issues::default();
+    let endpoint = match app_type {
+        AppType::Claude => validate_claude(provider, &mut issues),
+        AppType::Codex => validate_codex(provider, &mut issues),
+        AppType::Gemini => validate_gemini(provider, &mut issues),
+        AppType::OpenCode | AppType::Crush => validate_adapter(app_type, provider, &mut issues),
+    };
+
+    if check_network {
+        if let Some(url) = endpoint.filter(|u| Url::parse(u).is_ok()) {
+            let field = match app_type {
+                AppType::Claude => "env.ANTHROPIC_BASE_URL",
+                AppType::Codex => "config.base_url",
+                AppType::Gemini => "env.GOOGLE_GEMINI_BASE_URL",
+                AppType::OpenCode | AppType::Crush => "baseUrl",
+            };
+            match crate::speedtest::test_endpoints(vec![url], Some(8)).await {
+                Ok(results) => {
+                    if let Some(result) = results.first() {
+                        if result.latency.is_none() {
+                            issues.push(
+                                field,
+                                ValidationSeverity::Warning,
+                                "unreachable",
+                                format!(
+                                    "无法连接 Base URL: {}",
+                                    result.error.as_deref().unwrap_or("未知错误")
+                                ),
+                            );
+                        } else if let Some(status) = result.status.filter(|s| *s >= 500) {
+                            issues.push(
+                                field,
+                                ValidationSeverity::Warning,
+                                "server-error",
+                                format!("Base URL 返回服务端错误: HTTP {}", status),
+                            );
+                        }
+                    }
+                }
+                Err(e) => issues.push(
+                    field,
+                    ValidationSeverity::Warning,
+                    "probe-failed",
+                    format!("连通性探测失败: {}", e),
+                ),
+            }
+        }
+    }
+
+    if provider.needs_key()
+        && !crate::provider_share::has_api_key(app_type, &provider.settings_config)
+    {
+        issues.push(
+            "settingsConfig",
+            ValidationSeverity::Error,
+            "needs-key",
+            "该供应商导入时未包含 API Key，请先填写后再使用".to_string(),
+        );
+    }
+
+    let issues = issues.0;
+    ProviderValidation {
+        valid: !issues
+            .iter()
+            .any(|i| i.severity == ValidationSeverity::Error),
+        issues,
+    }
+}
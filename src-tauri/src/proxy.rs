@@ -0,0 +1,319 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::time::{Duration, Instant};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+
+/// 代理测试的默认目标
+const DEFAULT_TEST_URL: &str = "https://api.anthropic.com";
+const SUPPORTED_SCHEMES: [&str; 4] = ["http", "https", "socks5", "socks5h"];
+
+/// 代理配置；全局配置保存在设置中，供应商配置保存在 meta.proxy 中
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyConfig {
+    /// 代理地址（http://、https://、socks5://、socks5h://）；
+    /// 供应商配置中为空表示该供应商直连，不使用全局代理
+    pub url: String,
+    /// 不走代理的主机（逗号分隔），写入 NO_PROXY
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub no_proxy: Option<String>,
+}
+
+/// 代理连通性测试结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxyTestResult {
+    pub success: bool,
+    pub target: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<u16>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latency_ms: Option<u128>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 校验代理地址格式
+pub fn validate_proxy(proxy: &ProxyConfig) -> Result<(), String> {
+    let url = reqwest::Url::parse(proxy.url.trim())
+        .map_err(|e| format!("代理地址无效: {} ({})", proxy.url, e))?;
+    if !SUPPORTED_SCHEMES.contains(&url.scheme()) {
+        return Err(format!(
+            "不支持的代理协议: {}（支持 {}）",
+            url.scheme(),
+            SUPPORTED_SCHEMES.join(" / ")
+        ));
+    }
+    if url.host_str().is_none() {
+        return Err(format!("代理地址缺少主机: {}", proxy.url));
+    }
+    Ok(())
+}
+
+/// 全局代理
+pub fn global_proxy() -> Option<ProxyConfig> {
+    crate::settings::get_settings()
+        .proxy
+        .filter(|p| !p.url.trim().is_empty())
+}
+
+/// 供应商实际使用的代理：供应商配置优先（url 为空表示直连），否则使用全局代理
+pub fn effective_proxy(provider: &Provider) -> Option<ProxyConfig> {
+    match provider.meta.as_ref().and_then(|m| m.proxy.clone()) {
+        Some(proxy) if proxy.url.trim().is_empty() => None,
+        Some(proxy) => Some(proxy),
+        None => global_proxy(),
+    }
+}
+
+/// 代理对应的环境变量
+fn proxy_vars(proxy: &ProxyConfig) -> Vec<(&'static str, String)> {
+    let url = proxy.url.trim().to_string();
+    let mut vars = vec![("HTTP_PROXY", url.clone()), ("HTTPS_PROXY", url)];
+    if let Some(no_proxy) = proxy.no_proxy.as_deref().map(str::trim) {
+        if !no_proxy.is_empty() {
+            vars.push(("NO_PROXY", no_proxy.to_string()));
+        }
+    }
+    vars
+}
+
+fn env_object(settings: &mut Value) -> Option<&mut Map<String, Value>> {
+    let obj = settings.as_object_mut()?;
+    obj.entry("env")
+        .or_insert_with(|| Value::Object(Map::new()))
+        .as_object_mut()
+}
+
+/// 修改 Codex config 文本中的 [shell_environment_policy.set]；edit 返回 false 时不改写文本
+fn edit_codex_env(
+    settings: &mut Value,
+    edit: impl FnOnce(&mut toml::Table) -> bool,
+) -> Result<(), String> {
+    let Some(obj) = settings.as_object_mut() else {
+        return Ok(());
+    };
+    let text = obj.get("config").and_then(|v| v.as_str()).unwrap_or("");
+    let mut root: toml::Table = if text.trim().is_empty() {
+        toml::Table::new()
+    } else {
+        toml::from_str(text).map_err(|e| format!("解析 config.toml 失败: {}", e))?
+    };
+    let policy = root
+        .entry("shell_environment_policy")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| "shell_environment_policy 必须是表".to_string())?;
+    let set = policy
+        .entry("set")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| "shell_environment_policy.set 必须是表".to_string())?;
+    if !edit(set) {
+        return Ok(());
+    }
+    // 清理因本次修改而留下的空表
+    if set.is_empty() {
+        policy.remove("set");
+    }
+    if policy.is_empty() {
+        root.remove("shell_environment_policy");
+    }
+    let new_text = toml::to_string(&root).map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
+    obj.insert("config".to_string(), Value::String(new_text));
+    Ok(())
+}
+
+/// 将代理写入 live 配置：Claude / Gemini 写入 env，Codex 写入 config.toml 的
+/// [shell_environment_policy.set]；其余应用不支持，保持不变
+pub fn apply_to_settings(
+    app_type: &AppType,
+    settings: &mut Value,
+    proxy: &ProxyConfig,
+) -> Result<(), String> {
+    let vars = proxy_vars(proxy);
+    match app_type {
+        AppType::Claude | AppType::Gemini => {
+            if let Some(env) = env_object(settings) {
+                for (key, value) in vars {
+                    env.insert(key.to_string(), Value::String(value));
+                }
+            }
+            Ok(())
+        }
+        AppType::Codex => edit_codex_env(settings, |set| {
+            for (key, value) in vars {
+                set.insert(key.to_string(), toml::Value::String(value));
+            }
+            true
+        }),
+        AppType::OpenCode | AppType::Crush => Ok(()),
+    }
+}
+
+/// 从 live 配置中移除由代理写入的变量（仅移除取值与代理一致的项），用于回填到供应商前
+pub fn strip_from_settings(
+    app_type: &AppType,
+    settings: &mut Value,
+    proxy: &ProxyConfig,
+) -> Result<(), String> {
+    let vars = proxy_vars(proxy);
+    match app_type {
+        AppType::Claude | AppType::Gemini => {
+            let Some(obj) = settings.as_object_mut() else {
+                return Ok(());
+            };
+            let Some(env) = obj.get_mut("env").and_then(|v| v.as_object_mut()) else {
+                return Ok(());
+            };
+            let before = env.len();
+            env.retain(|key, v| {
+                !vars
+                    .iter()
+                    .any(|(k, value)| *k == key.as_str() && v.as_str() == Some(value.as_str()))
+            });
+            if env.is_empty() && before > 0 {
+                obj.remove("env");
+            }
+            Ok(())
+        }
+        AppType::Codex => {
+            let has_config = settings
+                .get("config")
+                .and_then(|v| v.as_str())
+                .is_some_and(|s| !s.trim().is_empty());
+            if !has_config {
+                return Ok(());
+            }
+            edit_codex_env(settings, |set| {
+                let before = set.len();
+                set.retain(|key, v| {
+                    !vars
+                        .iter()
+                        .any(|(k, value)| *k == key && v.as_str() == Some(value.as_str()))
+                });
+                set.len() != before
+            })
+        }
+        AppType::OpenCode | AppType::Crush => Ok(()),
+    }
+}
+
+/// 生成写入 live 的配置：解析加密的 API Key，并叠加供应商的附加设置、实际使用的代理与
+/// cc-switch 状态栏
+pub fn live_settings_for(app_type: &AppType, provider: &Provider) -> Result<Value, String> {
+    let settings = crate::secrets::resolve_settings(app_type, &provider.settings_config)?;
+    layer_live_settings(app_type, provider, settings)
+}
+
+/// 在 settings 上叠加供应商的附加设置、实际使用的代理与状态栏（不解析密钥）
+pub fn layer_live_settings(
+    app_type: &AppType,
+    provider: &Provider,
+    mut settings: Value,
+) -> Result<Value, String> {
+    crate::provider_extras::apply_to_settings(app_type, &mut settings, provider)?;
+    if let Some(proxy) = effective_proxy(provider) {
+        apply_to_settings(app_type, &mut settings, &proxy)?;
+    }
+    crate::statusline::apply_to_settings(app_type, &mut settings);
+    Ok(settings)
+}
+
+/// 代理配置变化后，就地更新当前 live 配置：移除旧代理变量并写入新代理
+pub fn reapply_live(
+    app_type: &AppType,
+    old: Option<&ProxyConfig>,
+    new: Option<&ProxyConfig>,
+) -> Result<(), String> {
+    if old == new {
+        return Ok(());
+    }
+    let adapter = crate::app_adapter::adapter_for(app_type);
+    if !adapter.live_exists() {
+        return Ok(());
+    }
+    let mut live = adapter.read_live()?;
+    if let Some(old) = old {
+        strip_from_settings(app_type, &mut live, old)?;
+    }
+    if let Some(new) = new {
+        apply_to_settings(app_type, &mut live, new)?;
+    }
+    crate::undo::capture("proxy", &crate::undo::live_config_paths(app_type));
+    adapter.write_live(&live)
+}
+
+/// 全局代理变化后，更新未单独配置代理的当前供应商的 live 配置
+pub fn reapply_global(
+    config: &MultiAppConfig,
+    old: Option<&ProxyConfig>,
+    new: Option<&ProxyConfig>,
+) {
+    if old == new {
+        return;
+    }
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager(&app_type) else {
+            continue;
+        };
+        let Some(current) = manager.providers.get(&manager.current) else {
+            continue;
+        };
+        if current.meta.as_ref().is_some_and(|m| m.proxy.is_some()) {
+            continue;
+        }
+        if let Err(e) = reapply_live(&app_type, old, new) {
+            log::warn!("更新 {} 的代理配置失败: {}", app_type.as_str(), e);
+        }
+    }
+}
+
+/// 通过代理请求目标地址；收到任意 HTTP 响应即视为代理可用
+pub async fn test_proxy(
+    proxy: &ProxyConfig,
+    target: Option<&str>,
+    timeout_secs: u64,
+) -> Result<ProxyTestResult, String> {
+    validate_proxy(proxy)?;
+    let target = target
+        .map(str::trim)
+        .filter(|t| !t.is_empty())
+        .unwrap_or(DEFAULT_TEST_URL)
+        .to_string();
+    let mut reqwest_proxy =
+        reqwest::Proxy::all(proxy.url.trim()).map_err(|e| format!("代理地址无效: {}", e))?;
+    if let Some(no_proxy) = proxy.no_proxy.as_deref() {
+        reqwest_proxy = reqwest_proxy.no_proxy(reqwest::NoProxy::from_string(no_proxy));
+    }
+    let client = reqwest::Client::builder()
+        .timeout(Duration::from_secs(timeout_secs))
+        .proxy(reqwest_proxy)
+        .user_agent("cc-switch-proxy-test/1.0")
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))?;
+
+    let start = Instant::now();
+    Ok(match client.get(&target).send().await {
+        Ok(response) => ProxyTestResult {
+            success: true,
+            target,
+            status: Some(response.status().as_u16()),
+            latency_ms: Some(start.elapsed().as_millis()),
+            error: None,
+        },
+        Err(e) => ProxyTestResult {
+            success: false,
+            target,
+            status: None,
+            latency_ms: None,
+            error: Some(if e.is_timeout() {
+                "请求超时".to_string()
+            } else {
+                format!("请求失败: {}", e)
+            }),
+        },
+    })
+}
@@ -0,0 +1,425 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use ssh2::{CheckResult, KnownHostFileKind, RenameFlags, Session, Sftp};
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::time::Duration;
+
+use crate::app_config::AppType;
+use crate::provider::Provider;
+
+/// 建立 TCP 连接的超时
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// SSH 会话内单次操作的超时（毫秒）
+const SESSION_TIMEOUT_MS: u32 = 30_000;
+
+fn default_port() -> u16 {
+    22
+}
+
+/// 远程主机连接配置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteProfile {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    pub host: String,
+    #[serde(default = "default_port")]
+    pub port: u16,
+    pub user: String,
+    /// 私钥路径（支持 ~ 前缀）；为空时使用 ssh-agent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub identity_file: Option<String>,
+    /// 远程 Claude 配置目录，默认 ~/.claude
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_dir: Option<String>,
+    /// 远程 Codex 配置目录，默认 ~/.codex
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_dir: Option<String>,
+}
+
+/// 远程主机上的对话记录（只含文件信息，内容按需读取）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RemoteConversation {
+    pub id: String,
+    pub app_type: String,
+    /// 远程主机上的绝对路径
+    pub file_path: String,
+    pub file_size: u64,
+    pub modified_at: i64,
+    pub project_name: Option<String>,
+}
+
+/// 已建立的 SFTP 连接
+struct RemoteSession {
+    // 保持会话存活，sftp 依赖其底层连接
+    _session: Session,
+    sftp: Sftp,
+    home: String,
+}
+
+fn find_profile(profile_id: &str) -> Result<RemoteProfile, String> {
+    crate::settings::get_settings()
+        .remote_profiles
+        .into_iter()
+        .find(|p| p.id == profile_id)
+        .ok_or_else(|| format!("远程主机配置不存在: {}", profile_id))
+}
+
+/// 按 ~/.ssh/known_hosts 校验主机密钥，未登记或不一致时拒绝连接
+fn verify_host_key(session: &Session, profile: &RemoteProfile) -> Result<(), String> {
+    let (key, _) = session
+        .host_key()
+        .ok_or_else(|| "无法获取远程主机密钥".to_string())?;
+    let mut known_hosts = session
+        .known_hosts()
+        .map_err(|e| format!("读取 known_hosts 失败: {}", e))?;
+    let known_hosts_path = dirs::home_dir()
+        .ok_or_else(|| "无法获取用户主目录".to_string())?
+        .join(".ssh")
+        .join("known_hosts");
+    if known_hosts_path.exists() {
+        known_hosts
+            .read_file(&known_hosts_path, KnownHostFileKind::OpenSSH)
+            .map_err(|e| format!("读取 known_hosts 失败: {}", e))?;
+    }
+    match known_hosts.check_port(&profile.host, profile.port, key) {
+        CheckResult::Match => Ok(()),
+        CheckResult::NotFound => Err(format!(
+            "{} 不在 known_hosts 中，请先在终端通过 ssh 连接一次以确认主机密钥",
+            profile.host
+        )),
+        CheckResult::Mismatch => Err(format!(
+            "{} 的主机密钥与 known_hosts 不一致，已拒绝连接",
+            profile.host
+        )),
+        CheckResult::Failure => Err("校验主机密钥失败".to_string()),
+    }
+}
+
+fn connect(profile: &RemoteProfile) -> Result<RemoteSession, String> {
+    let addr = (profile.host.as_str(), profile.port)
+        .to_socket_addrs()
+        .map_err(|e| format!("解析主机地址失败: {}", e))?
+        .next()
+        .ok_or_else(|| format!("无法解析主机: {}", profile.host))?;
+    let tcp = TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT)
+        .map_err(|e| format!("连接 {}:{} 失败: {}", profile.host, profile.port, e))?;
+
+    let mut session = Session::new().map_err(|e| format!("创建 SSH 会话失败: {}", e))?;
+    session.set_tcp_stream(tcp);
+    session.set_timeout(SESSION_TIMEOUT_MS);
+    session
+        .handshake()
+        .map_err(|e| format!("SSH 握手失败: {}", e))?;
+    verify_host_key(&session, profile)?;
+
+    match profile
+        .identity_file
+        .as_deref()
+        .map(str::trim)
+        .filter(|s| !s.is_empty())
+    {
+        Some(identity) => {
+            let key_path = crate::settings::resolve_override_path(identity);
+            session
+                .userauth_pubkey_file(&profile.user, None, &key_path, None)
+                .map_err(|e| format!("私钥认证失败: {}", e))?;
+        }
+        None => session
+            .userauth_agent(&profile.user)
+            .map_err(|e| format!("ssh-agent 认证失败: {}", e))?,
+    }
+    if !session.authenticated() {
+        return Err("SSH 认证失败".to_string());
+    }
+
+    let sftp = session
+        .sftp()
+        .map_err(|e| format!("打开 SFTP 通道失败: {}", e))?;
+    let home = sftp
+        .realpath(Path::new("."))
+        .map_err(|e| format!("获取远程主目录失败: {}", e))?
+        .to_string_lossy()
+        .to_string();
+    Ok(RemoteSession {
+        _session: session,
+        sftp,
+        home,
+    })
+}
+
+/// 远程路径拼接（始终使用 /，不受本机路径分隔符影响）
+fn join(base: &str, name: &str) -> String {
+    format!("{}/{}", base.trim_end_matches('/'), name)
+}
+
+impl RemoteSession {
+    /// 解析配置中的远程目录：~ 与相对路径均相对于远程主目录
+    fn resolve_dir(&self, configured: Option<&str>, default_name: &str) -> String {
+        match configured.map(str::trim).filter(|s| !s.is_empty()) {
+            Some(dir) if dir.starts_with('/') => dir.to_string(),
+            Some(dir) => {
+                let rel = dir.strip_prefix("~").unwrap_or(dir).trim_start_matches('/');
+                if rel.is_empty() {
+                    self.home.clone()
+                } else {
+                    join(&self.home, rel)
+                }
+            }
+            None => join(&self.home, default_name),
+        }
+    }
+
+    fn app_dir(&self, profile: &RemoteProfile, app: &AppType) -> Result<String, String> {
+        match app {
+            AppType::Claude => Ok(self.resolve_dir(profile.claude_dir.as_deref(), ".claude")),
+            AppType::Codex => Ok(self.resolve_dir(profile.codex_dir.as_deref(), ".codex")),
+            _ => Err(format!("远程模式暂不支持 {}", app.as_str())),
+        }
+    }
+
+    /// 列出目录项：(名称, 完整路径, 是否目录, 大小, 修改时间)；目录不存在时返回空
+    fn list_dir(&self, dir: &str) -> Vec<(String, String, bool, u64, i64)> {
+        let Ok(entries) = self.sftp.readdir(Path::new(dir)) else {
+            return Vec::new();
+        };
+        entries
+            .into_iter()
+            .filter_map(|(path, stat)| {
+                let name = path.file_name()?.to_string_lossy().to_string();
+                Some((
+                    name.clone(),
+                    join(dir, &name),
+                    stat.is_dir(),
+                    stat.size.unwrap_or(0),
+                    stat.mtime.unwrap_or(0) as i64,
+                ))
+            })
+            .collect()
+    }
+
+    fn exists(&self, path: &str) -> bool {
+        self.sftp.stat(Path::new(path)).is_ok()
+    }
+
+    fn read_text(&self, path: &str) -> Result<String, String> {
+        let mut file = self
+            .sftp
+            .open(Path::new(path))
+            .map_err(|e| format!("打开远程文件失败: {}: {}", path, e))?;
+        let mut bytes = Vec::new();
+        file.read_to_end(&mut bytes)
+            .map_err(|e| format!("读取远程文件失败: {}: {}", path, e))?;
+        Ok(String::from_utf8_lossy(&bytes).into_owned())
+    }
+
+    /// 先写入同目录临时文件再改名，避免远程文件只写了一半
+    fn write_atomic(&self, path: &str, data: &[u8]) -> Result<(), String> {
+        if let Some((parent, _)) = path.rsplit_once('/') {
+            if !parent.is_empty() && !self.exists(parent) {
+                self.sftp
+                    .mkdir(Path::new(parent), 0o755)
+                    .map_err(|e| format!("创建远程目录失败: {}: {}", parent, e))?;
+            }
+        }
+        let tmp = format!("{}.tmp.{}", path, chrono::Utc::now().timestamp_millis());
+        {
+            let mut file = self
+                .sftp
+                .create(Path::new(&tmp))
+                .map_err(|e| format!("创建远程临时文件失败: {}: {}", tmp, e))?;
+            file.write_all(data)
+                .map_err(|e| format!("写入远程文件失败: {}: {}", tmp, e))?;
+        }
+        let flags = Some(RenameFlags::OVERWRITE | RenameFlags::ATOMIC | RenameFlags::NATIVE);
+        if self
+            .sftp
+            .rename(Path::new(&tmp), Path::new(path), flags)
+            .is_err()
+        {
+            // 部分 SFTP 服务端不支持覆盖式改名：先删除目标再改名
+            let _ = self.sftp.unlink(Path::new(path));
+            if let Err(e) = self.sftp.rename(Path::new(&tmp), Path::new(path), None) {
+                let _ = self.sftp.unlink(Path::new(&tmp));
+                return Err(format!("替换远程文件失败: {}: {}", path, e));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 测试连接，返回远程主目录
+pub fn test_connection(profile_id: &str) -> Result<String, String> {
+    let profile = find_profile(profile_id)?;
+    Ok(connect(&profile)?.home)
+}
+
+/// 列出远程主机上的 Claude（projects/<项目>/*.jsonl）或 Codex（sessions/年/月/日/*.jsonl）对话
+pub fn list_conversations(
+    profile_id: &str,
+    app: &AppType,
+) -> Result<Vec<RemoteConversation>, String> {
+    let profile = find_profile(profile_id)?;
+    let remote = connect(&profile)?;
+    let app_dir = remote.app_dir(&profile, app)?;
+
+    let mut files = Vec::new();
+    match app {
+        AppType::Claude => {
+            let projects_dir = join(&app_dir, "projects");
+            for (project, project_path, is_dir, _, _) in remote.list_dir(&projects_dir) {
+                if !is_dir || project.starts_with('.') {
+                    continue;
+                }
+                for (name, path, is_dir, size, mtime) in remote.list_dir(&project_path) {
+                    if !is_dir && name.ends_with(".jsonl") {
+                        files.push((name, path, size, mtime, Some(project.clone())));
+                    }
+                }
+            }
+        }
+        _ => {
+            let mut dirs = vec![join(&app_dir, "sessions")];
+            for _ in 0..3 {
+                dirs = dirs
+                    .iter()
+                    .flat_map(|dir| remote.list_dir(dir))
+                    .filter(|(_, _, is_dir, _, _)| *is_dir)
+                    .map(|(_, path, _, _, _)| path)
+                    .collect();
+            }
+            for dir in dirs {
+                for (name, path, is_dir, size, mtime) in remote.list_dir(&dir) {
+                    if !is_dir && name.ends_with(".jsonl") {
+                        files.push((name, path, size, mtime, None));
+                    }
+                }
+            }
+        }
+    }
+
+    let mut conversations: Vec<RemoteConversation> = files
+        .into_iter()
+        .map(|(name, path, size, mtime, project)| RemoteConversation {
+            id: name.trim_end_matches(".jsonl").to_string(),
+            app_type: app.as_str().to_string(),
+            file_path: path,
+            file_size: size,
+            modified_at: mtime,
+            project_name: project,
+        })
+        .collect();
+    conversations.sort_by_key(|c| std::cmp::Reverse(c.modified_at));
+    Ok(conversations)
+}
+
+/// 读取远程对话文件；只允许读取 Claude / Codex 配置目录内的文件
+pub fn read_conversation(profile_id: &str, file_path: &str) -> Result<String, String> {
+    let profile = find_profile(profile_id)?;
+    let remote = connect(&profile)?;
+    let allowed = [AppType::Claude, AppType::Codex]
+        .iter()
+        .filter_map(|app| remote.app_dir(&profile, app).ok())
+        .any(|dir| file_path.starts_with(&format!("{}/", dir)) && !file_path.contains("/../"));
+    if !allowed {
+        return Err("只能读取远程 Claude / Codex 目录中的对话文件".to_string());
+    }
+    remote.read_text(file_path)
+}
+
+/// 远程全局规则文件：Claude 为 CLAUDE.md，Codex 为 AGENTS.md
+fn rules_path(
+    remote: &RemoteSession,
+    profile: &RemoteProfile,
+    app: &AppType,
+) -> Result<String, String> {
+    let file_name = match app {
+        AppType::Codex => "AGENTS.md",
+        _ => "CLAUDE.md",
+    };
+    Ok(join(&remote.app_dir(profile, app)?, file_name))
+}
+
+/// 读取远程全局规则；文件不存在时返回空字符串
+pub fn read_rules(profile_id: &str, app: &AppType) -> Result<String, String> {
+    let profile = find_profile(profile_id)?;
+    let remote = connect(&profile)?;
+    let path = rules_path(&remote, &profile, app)?;
+    if !remote.exists(&path) {
+        return Ok(String::new());
+    }
+    remote.read_text(&path)
+}
+
+/// 写入远程全局规则
+pub fn write_rules(profile_id: &str, app: &AppType, content: &str) -> Result<(), String> {
+    let profile = find_profile(profile_id)?;
+    let remote = connect(&profile)?;
+    let path = rules_path(&remote, &profile, app)?;
+    remote.write_atomic(&path, content.as_bytes())
+}
+
+/// 将本地供应商写入远程主机的 live 配置（Claude settings.json；Codex auth.json + config.toml）
+pub fn switch_provider(
+    profile_id: &str,
+    app: &AppType,
+    provider: &Provider,
+) -> Result<(), String> {
+    let settings = crate::proxy::live_settings_for(app, provider)?;
+    crate::app_adapter::adapter_for(app).validate(&settings)?;
+
+    let profile = find_profile(profile_id)?;
+    let remote = connect(&profile)?;
+    let app_dir = remote.app_dir(&profile, app)?;
+
+    match app {
+        AppType::Claude => {
+            let json = serde_json::to_string_pretty(&settings)
+                .map_err(|e| format!("序列化配置失败: {}", e))?;
+            remote.write_atomic(&join(&app_dir, "settings.json"), json.as_bytes())?;
+        }
+        _ => {
+            let auth = settings
+                .get("auth")
+                .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
+            let auth_json = serde_json::to_string_pretty(auth)
+                .map_err(|e| format!("序列化 auth 失败: {}", e))?;
+            let config_text = settings.get("config").and_then(Value::as_str).unwrap_or("");
+
+            let auth_path = join(&app_dir, "auth.json");
+            let config_path = join(&app_dir, "config.toml");
+            let old_auth = remote
+                .exists(&auth_path)
+                .then(|| remote.read_text(&auth_path))
+                .transpose()?;
+            remote.write_atomic(&auth_path, auth_json.as_bytes())?;
+            if let Err(e) = remote.write_atomic(&config_path, config_text.as_bytes()) {
+                // config.toml 写入失败时回滚 auth.json
+                let rollback = match &old_auth {
+                    Some(old) => remote.write_atomic(&auth_path, old.as_bytes()),
+                    None => remote
+                        .sftp
+                        .unlink(Path::new(&auth_path))
+                        .map_err(|e| e.to_string()),
+                };
+                if let Err(rollback_err) = rollback {
+                    log::warn!("回滚远程 auth.json 失败: {}", rollback_err);
+                }
+                return Err(e);
+            }
+        }
+    }
+
+    log::info!(
+        "已将供应商 {} 写入远程主机 {} 的 {} 配置",
+        provider.id,
+        profile.host,
+        app.as_str()
+    );
+    Ok(())
+}
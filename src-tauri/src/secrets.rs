@@ -0,0 +1,658 @@
+use argon2::Argon2;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng};
+use chacha20poly1305::{XChaCha20Poly1305, XNonce};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+use crate::key_rotation::KeyRotation;
+use crate::provider::Provider;
+
+/// 配置中密钥引用的前缀：`cc-switch-secret:<app>/<provider_id>/<字段>`
+pub const SECRET_REF_PREFIX: &str = "cc-switch-secret:";
+/// 用于校验主密码的固定明文
+const VERIFIER_PLAINTEXT: &[u8] = b"cc-switch-vault";
+const VAULT_VERSION: u32 = 1;
+const SALT_LEN: usize = 16;
+
+/// 已解锁的密钥（仅保存在内存中）
+static UNLOCKED_KEY: Mutex<Option<[u8; 32]>> = Mutex::new(None);
+/// 序列化读写 secrets.json
+static VAULT_LOCK: Mutex<()> = Mutex::new(());
+
+/// 密钥库文件 ~/.cc-switch/secrets.json
+fn get_vault_path() -> PathBuf {
+    get_app_config_dir().join("secrets.json")
+}
+
+/// 各应用中视为密钥的字段（JSON Pointer，由应用适配器提供，可随配置内容变化）
+fn secret_fields(app_type: &AppType, settings: &Value) -> Vec<String> {
+    crate::app_adapter::adapter_for(app_type).secret_pointers(settings)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Vault {
+    version: u32,
+    salt: String,
+    /// 用主密码加密的固定明文，用于校验密码
+    verifier: String,
+    /// 引用名 → `<nonce>:<密文>`（base64）
+    #[serde(default)]
+    entries: BTreeMap<String, String>,
+    /// 引用名 → 加盐的明文摘要，未解锁时用于判断 live 配置中的 Key 是否被修改
+    #[serde(default)]
+    digests: BTreeMap<String, String>,
+}
+
+impl Vault {
+    fn load() -> Result<Option<Self>, String> {
+        let path = get_vault_path();
+        if !path.exists() {
+            return Ok(None);
+        }
+        read_json_file(&path).map(Some).map_err(Into::into)
+    }
+
+    fn save(&mut self) -> Result<(), String> {
+        let entries = &self.entries;
+        self.digests.retain(|name, _| entries.contains_key(name));
+        write_json_file(&get_vault_path(), self).map_err(Into::into)
+    }
+
+    fn digest(&self, plain: &str) -> String {
+        let mut hasher = Sha256::new();
+        hasher.update(self.salt.as_bytes());
+        hasher.update(plain.as_bytes());
+        hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect()
+    }
+
+    /// 加密写入密钥并记录摘要
+    fn insert(&mut self, key: &[u8; 32], name: &str, plain: &str) -> Result<(), String> {
+        let digest = self.digest(plain);
+        self.entries
+            .insert(name.to_string(), encrypt(key, plain.as_bytes())?);
+        self.digests.insert(name.to_string(), digest);
+        Ok(())
+    }
+
+    /// 明文是否与引用保存的密钥相同：已解锁时解密比较，未解锁时比较摘要（无摘要时无法判断）
+    fn matches(&self, key: Option<&[u8; 32]>, name: &str, plain: &str) -> Option<bool> {
+        match key {
+            Some(key) => {
+                let stored = decrypt(key, self.entries.get(name)?).ok()?;
+                Some(stored == plain.as_bytes())
+            }
+            None => Some(*self.digests.get(name)? == self.digest(plain)),
+        }
+    }
+
+    /// 为缺少摘要的条目补齐摘要（旧版本创建的密钥库），返回是否有修改
+    fn fill_digests(&mut self, key: &[u8; 32]) -> Result<bool, String> {
+        let missing: Vec<String> = self
+            .entries
+            .keys()
+            .filter(|name| !self.digests.contains_key(*name))
+            .cloned()
+            .collect();
+        for name in &missing {
+            let plain = String::from_utf8(decrypt(key, &self.entries[name])?)
+                .map_err(|_| format!("密钥内容无效: {}", name))?;
+            let digest = self.digest(&plain);
+            self.digests.insert(name.clone(), digest);
+        }
+        Ok(!missing.is_empty())
+    }
+}
+
+/// 密钥库状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsStatus {
+    /// 是否已设置主密码
+    pub initialized: bool,
+    pub unlocked: bool,
+    /// 密钥库中保存的密钥数量
+    pub stored: usize,
+}
+
+/// 迁移结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretsMigration {
+    /// 处理的密钥字段数量
+    pub keys: usize,
+    /// 涉及的供应商数量
+    pub providers: usize,
+}
+
+pub(crate) fn derive_key(password: &str, salt: &[u8]) -> Result<[u8; 32], String> {
+    let mut key = [0u8; 32];
+    Argon2::default()
+        .hash_password_into(password.as_bytes(), salt, &mut key)
+        .map_err(|e| format!("派生密钥失败: {}", e))?;
+    Ok(key)
+}
+
+pub(crate) fn encrypt(key: &[u8; 32], plaintext: &[u8]) -> Result<String, String> {
+    let cipher = XChaCha20Poly1305::new(key.into());
+    let nonce = XChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(&nonce, plaintext)
+        .map_err(|_| "加密失败".to_string())?;
+    Ok(format!(
+        "{}:{}",
+        BASE64.encode(nonce),
+        BASE64.encode(ciphertext)
+    ))
+}
+
+pub(crate) fn decrypt(key: &[u8; 32], encoded: &str) -> Result<Vec<u8>, String> {
+    let (nonce, ciphertext) = encoded.split_once(':').ok_or("密文格式错误")?;
+    let nonce = BASE64
+        .decode(nonce)
+        .map_err(|e| format!("密文格式错误: {}", e))?;
+    if nonce.len() != 24 {
+        return Err("密文格式错误".to_string());
+    }
+    let ciphertext = BASE64
+        .decode(ciphertext)
+        .map_err(|e| format!("密文格式错误: {}", e))?;
+    XChaCha20Poly1305::new(key.into())
+        .decrypt(XNonce::from_slice(&nonce), ciphertext.as_slice())
+        .map_err(|_| "解密失败".to_string())
+}
+
+fn unlocked_key() -> Result<[u8; 32], String> {
+    UNLOCKED_KEY
+        .lock()
+        .map_err(crate::error::lock_failed)?
+        .ok_or_else(|| crate::i18n::t("error.vaultLocked"))
+}
+
+fn is_unlocked() -> bool {
+    UNLOCKED_KEY.lock().map(|k| k.is_some()).unwrap_or(false)
+}
+
+pub fn status() -> Result<SecretsStatus, String> {
+    let _guard = VAULT_LOCK.lock();
+    let vault = Vault::load()?;
+    Ok(SecretsStatus {
+        initialized: vault.is_some(),
+        unlocked: is_unlocked(),
+        stored: vault.map(|v| v.entries.len()).unwrap_or(0),
+    })
+}
+
+/// 使用主密码解锁；首次调用时以该密码创建密钥库
+pub fn unlock(password: &str) -> Result<SecretsStatus, String> {
+    if password.is_empty() {
+        return Err("主密码不能为空".to_string());
+    }
+    {
+        let _guard = VAULT_LOCK.lock();
+        let key = match Vault::load()? {
+            Some(mut vault) => {
+                let salt = BASE64
+                    .decode(&vault.salt)
+                    .map_err(|e| format!("密钥库格式错误: {}", e))?;
+                let key = derive_key(password, &salt)?;
+                if !decrypt(&key, &vault.verifier).is_ok_and(|p| p == VERIFIER_PLAINTEXT) {
+                    return Err("主密码错误".to_string());
+                }
+                if vault.fill_digests(&key)? {
+                    vault.save()?;
+                }
+                key
+            }
+            None => {
+                let mut salt = [0u8; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+                let key = derive_key(password, &salt)?;
+                Vault {
+                    version: VAULT_VERSION,
+                    salt: BASE64.encode(salt),
+                    verifier: encrypt(&key, VERIFIER_PLAINTEXT)?,
+                    entries: BTreeMap::new(),
+                    digests: BTreeMap::new(),
+                }
+                .save()?;
+                key
+            }
+        };
+        *UNLOCKED_KEY.lock().map_err(crate::error::lock_failed)? = Some(key);
+    }
+    status()
+}
+
+/// 清除内存中的密钥
+pub fn lock() {
+    if let Ok(mut key) = UNLOCKED_KEY.lock() {
+        *key = None;
+    }
+}
+
+/// 修改主密码：用新密码重新加密所有密钥
+pub fn change_password(old_password: &str, new_password: &str) -> Result<(), String> {
+    if new_password.is_empty() {
+        return Err("主密码不能为空".to_string());
+    }
+    let _guard = VAULT_LOCK.lock();
+    let mut vault = Vault::load()?.ok_or("尚未设置主密码")?;
+    let salt = BASE64
+        .decode(&vault.salt)
+        .map_err(|e| format!("密钥库格式错误: {}", e))?;
+    let old_key = derive_key(old_password, &salt)?;
+    if !decrypt(&old_key, &vault.verifier).is_ok_and(|p| p == VERIFIER_PLAINTEXT) {
+        return Err("主密码错误".to_string());
+    }
+
+    let mut new_salt = [0u8; SALT_LEN];
+    OsRng.fill_bytes(&mut new_salt);
+    let new_key = derive_key(new_password, &new_salt)?;
+    let mut entries = BTreeMap::new();
+    for (name, encoded) in &vault.entries {
+        let plain = decrypt(&old_key, encoded)?;
+        entries.insert(name.clone(), encrypt(&new_key, &plain)?);
+    }
+    vault.salt = BASE64.encode(new_salt);
+    vault.verifier = encrypt(&new_key, VERIFIER_PLAINTEXT)?;
+    vault.entries = entries;
+    // 摘要以盐值计算，需随新盐值重新生成
+    vault.digests.clear();
+    vault.fill_digests(&new_key)?;
+    vault.save()?;
+
+    *UNLOCKED_KEY.lock().map_err(crate::error::lock_failed)? = Some(new_key);
+    Ok(())
+}
+
+pub fn is_secret_ref(value: &Value) -> bool {
+    value
+        .as_str()
+        .is_some_and(|s| s.starts_with(SECRET_REF_PREFIX))
+}
+
+fn ref_name(app_type: &AppType, provider_id: &str, pointer: &str) -> String {
+    // 两段式字段（/env/KEY）沿用字段名；更深的路径保留完整路径以免同名字段冲突
+    let field = if pointer.matches('/').count() <= 2 {
+        pointer.rsplit('/').next().unwrap_or(pointer)
+    } else {
+        pointer.trim_start_matches('/')
+    };
+    format!("{}/{}/{}", app_type.as_str(), provider_id, field)
+}
+
+/// 配置中是否含有密钥引用
+pub fn has_secret_refs(app_type: &AppType, settings: &Value) -> bool {
+    secret_fields(app_type, settings)
+        .iter()
+        .any(|p| settings.pointer(p).is_some_and(is_secret_ref))
+}
+
+/// 将配置中的密钥引用替换为真实值（写入 live 配置前调用）
+pub fn resolve_settings(app_type: &AppType, settings: &Value) -> Result<Value, String> {
+    if !has_secret_refs(app_type, settings) {
+        return Ok(settings.clone());
+    }
+    let key = unlocked_key()?;
+    let _guard = VAULT_LOCK.lock();
+    let vault = Vault::load()?.ok_or_else(|| crate::i18n::t("error.vaultMissing"))?;
+    let mut resolved = settings.clone();
+    for pointer in secret_fields(app_type, settings) {
+        let Some(slot) = resolved.pointer_mut(&pointer) else {
+            continue;
+        };
+        let Some(name) = slot
+            .as_str()
+            .and_then(|s| s.strip_prefix(SECRET_REF_PREFIX))
+        else {
+            continue;
+        };
+        let encoded = vault
+            .entries
+            .get(name)
+            .ok_or_else(|| format!("密钥库中缺少密钥: {}", name))?;
+        let plain = String::from_utf8(decrypt(&key, encoded)?)
+            .map_err(|_| format!("密钥内容无效: {}", name))?;
+        *slot = Value::String(plain);
+    }
+    Ok(resolved)
+}
+
+/// 返回密钥已解析的供应商副本
+pub fn resolve_provider(app_type: &AppType, provider: &Provider) -> Result<Provider, String> {
+    let mut resolved = provider.clone();
+    resolved.settings_config = resolve_settings(app_type, &provider.settings_config)?;
+    Ok(resolved)
+}
+
+/// 将明文密钥写入密钥库并替换为引用，返回处理的字段数量
+fn protect_fields(
+    vault: &mut Vault,
+    key: &[u8; 32],
+    app_type: &AppType,
+    provider_id: &str,
+    settings: &mut Value,
+    only: Option<&[&str]>,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for pointer in secret_fields(app_type, settings) {
+        if only.is_some_and(|only| !only.contains(&pointer.as_str())) {
+            continue;
+        }
+        let Some(slot) = settings.pointer_mut(&pointer) else {
+            continue;
+        };
+        let Some(plain) = slot.as_str().filter(|s| !s.is_empty()) else {
+            continue;
+        };
+        if plain.starts_with(SECRET_REF_PREFIX) {
+            continue;
+        }
+        let name = ref_name(app_type, provider_id, &pointer);
+        vault.insert(key, &name, plain)?;
+        *slot = Value::String(format!("{}{}", SECRET_REF_PREFIX, name));
+        count += 1;
+    }
+    Ok(count)
+}
+
+/// 轮换 Key 的引用名：按 Key 内容的摘要命名，顺序调整后引用不变
+fn rotation_ref_name(app_type: &AppType, provider_id: &str, key: &str) -> String {
+    let digest = Sha256::digest(key.as_bytes());
+    let short: String = digest[..8].iter().map(|b| format!("{:02x}", b)).collect();
+    format!("{}/{}/rotation-{}", app_type.as_str(), provider_id, short)
+}
+
+/// 将轮换中的明文 Key 写入密钥库并替换为引用，同时移除已不再使用的轮换 Key 条目；返回处理的 Key 数量
+fn protect_rotation_in(
+    vault: &mut Vault,
+    key: &[u8; 32],
+    app_type: &AppType,
+    provider_id: &str,
+    rotation: &mut KeyRotation,
+) -> Result<usize, String> {
+    let mut count = 0;
+    for entry in rotation.keys.iter_mut() {
+        let plain = entry.key.trim();
+        if plain.is_empty() || plain.starts_with(SECRET_REF_PREFIX) {
+            continue;
+        }
+        let name = rotation_ref_name(app_type, provider_id, plain);
+        vault.insert(key, &name, plain)?;
+        entry.key = format!("{}{}", SECRET_REF_PREFIX, name);
+        count += 1;
+    }
+    let prefix = format!("{}/{}/rotation-", app_type.as_str(), provider_id);
+    let used: Vec<&str> = rotation
+        .keys
+        .iter()
+        .filter_map(|k| k.key.strip_prefix(SECRET_REF_PREFIX))
+        .collect();
+    vault
+        .entries
+        .retain(|name, _| !name.starts_with(&prefix) || used.contains(&name.as_str()));
+    Ok(count)
+}
+
+/// 密钥库已初始化时，将供应商多 Key 轮换中的明文 Key 加密保存；密钥库未解锁时报错，
+/// 避免明文 Key 写入 config.json
+pub fn protect_rotation_keys(app_type: &AppType, provider: &mut Provider) -> Result<usize, String> {
+    let provider_id = provider.id.clone();
+    let Some(rotation) = provider.meta.as_mut().and_then(|m| m.key_rotation.as_mut()) else {
+        return Ok(0);
+    };
+    let has_plaintext = rotation
+        .keys
+        .iter()
+        .any(|k| !k.key.trim().starts_with(SECRET_REF_PREFIX));
+    if !has_plaintext || !get_vault_path().exists() {
+        return Ok(0);
+    }
+    let key = unlocked_key()?;
+    let _guard = VAULT_LOCK.lock();
+    let mut vault = Vault::load()?.ok_or_else(|| crate::i18n::t("error.vaultMissing"))?;
+    let count = protect_rotation_in(&mut vault, &key, app_type, &provider_id, rotation)?;
+    vault.save()?;
+    Ok(count)
+}
+
+/// 解析单个值：密钥引用返回明文，其余原样返回
+pub fn resolve_value(value: &str) -> Result<String, String> {
+    let Some(name) = value.strip_prefix(SECRET_REF_PREFIX) else {
+        return Ok(value.to_string());
+    };
+    let key = unlocked_key()?;
+    let _guard = VAULT_LOCK.lock();
+    let vault = Vault::load()?.ok_or_else(|| crate::i18n::t("error.vaultMissing"))?;
+    let encoded = vault
+        .entries
+        .get(name)
+        .ok_or_else(|| format!("密钥库中缺少密钥: {}", name))?;
+    String::from_utf8(decrypt(&key, encoded)?).map_err(|_| format!("密钥内容无效: {}", name))
+}
+
+/// 将轮换 Key 中的引用解析为明文
+fn resolve_rotation(rotation: &mut KeyRotation) -> Result<(), String> {
+    for entry in rotation.keys.iter_mut() {
+        entry.key = resolve_value(&entry.key)?;
+    }
+    Ok(())
+}
+
+/// 回填 live 配置或编辑供应商时保持加密：previous 中为引用的字段，若 updated 中变为明文，
+/// 与原密钥相同时沿用原引用；已修改时写回密钥库，密钥库未解锁则报错，不丢弃修改
+pub fn keep_protected(
+    app_type: &AppType,
+    provider_id: &str,
+    previous: &Value,
+    updated: &mut Value,
+) -> Result<(), String> {
+    let protected: Vec<String> = secret_fields(app_type, previous)
+        .into_iter()
+        .filter(|p| previous.pointer(p).is_some_and(is_secret_ref))
+        .collect();
+    if protected.is_empty() {
+        return Ok(());
+    }
+
+    let key = unlocked_key().ok();
+    let _guard = VAULT_LOCK.lock();
+    let mut vault = Vault::load()?.ok_or_else(|| crate::i18n::t("error.vaultMissing"))?;
+    let mut changed = false;
+    for pointer in protected {
+        let Some(previous_ref) = previous.pointer(&pointer) else {
+            continue;
+        };
+        let Some(name) = previous_ref
+            .as_str()
+            .and_then(|s| s.strip_prefix(SECRET_REF_PREFIX))
+        else {
+            continue;
+        };
+        let Some(slot) = updated.pointer_mut(&pointer) else {
+            continue;
+        };
+        // 已是引用或已清空的字段保持原样
+        let Some(plain) = slot
+            .as_str()
+            .filter(|s| !s.is_empty() && !s.starts_with(SECRET_REF_PREFIX))
+        else {
+            continue;
+        };
+        if vault.matches(key.as_ref(), name, plain) == Some(true) {
+            *slot = previous_ref.clone();
+            continue;
+        }
+        let Some(key) = key.as_ref() else {
+            return Err(crate::i18n::t("error.vaultLockedEdited"));
+        };
+        let name = ref_name(app_type, provider_id, &pointer);
+        vault.insert(key, &name, plain)?;
+        *slot = Value::String(format!("{}{}", SECRET_REF_PREFIX, name));
+        changed = true;
+    }
+    if changed {
+        vault.save()?;
+    }
+    Ok(())
+}
+
+/// 密钥库已初始化时，将供应商配置中新填写的明文 Key 加密保存；密钥库未解锁时报错，
+/// 避免明文 Key 写入 config.json
+pub fn protect_new_keys(app_type: &AppType, provider: &mut Provider) -> Result<usize, String> {
+    let has_plaintext = secret_fields(app_type, &provider.settings_config)
+        .iter()
+        .filter_map(|p| provider.settings_config.pointer(p).and_then(|v| v.as_str()))
+        .any(|s| !s.is_empty() && !s.starts_with(SECRET_REF_PREFIX));
+    if !has_plaintext || !get_vault_path().exists() {
+        return Ok(0);
+    }
+    let key = unlocked_key()?;
+    let _guard = VAULT_LOCK.lock();
+    let mut vault = Vault::load()?.ok_or_else(|| crate::i18n::t("error.vaultMissing"))?;
+    let count = protect_fields(
+        &mut vault,
+        &key,
+        app_type,
+        &provider.id,
+        &mut provider.settings_config,
+        None,
+    )?;
+    vault.save()?;
+    Ok(count)
+}
+
+/// 删除供应商在密钥库中的全部密钥
+pub fn remove_provider_secrets(app_type: &AppType, provider_id: &str) -> Result<(), String> {
+    let prefix = format!("{}/{}/", app_type.as_str(), provider_id);
+    let _guard = VAULT_LOCK.lock();
+    let Some(mut vault) = Vault::load()? else {
+        return Ok(());
+    };
+    let before = vault.entries.len();
+    vault.entries.retain(|name, _| !name.starts_with(&prefix));
+    if vault.entries.len() != before {
+        vault.save()?;
+    }
+    Ok(())
+}
+
+/// 将所有供应商的明文 API Key 迁移到密钥库
+pub fn migrate_to_vault(config: &mut MultiAppConfig) -> Result<SecretsMigration, String> {
+    let key = unlocked_key()?;
+    let _guard = VAULT_LOCK.lock();
+    let mut vault = Vault::load()?.ok_or_else(|| crate::i18n::t("error.vaultMissing"))?;
+    let mut result = SecretsMigration {
+        keys: 0,
+        providers: 0,
+    };
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        for provider in manager.providers.values_mut() {
+            let mut count = protect_fields(
+                &mut vault,
+                &key,
+                &app_type,
+                &provider.id,
+                &mut provider.settings_config,
+                None,
+            )?;
+            if let Some(rotation) = provider.meta.as_mut().and_then(|m| m.key_rotation.as_mut()) {
+                count += protect_rotation_in(&mut vault, &key, &app_type, &provider.id, rotation)?;
+            }
+            if count > 0 {
+                result.keys += count;
+                result.providers += 1;
+            }
+        }
+    }
+    if result.keys > 0 {
+        vault.save()?;
+    }
+    Ok(result)
+}
+
+/// 将密钥引用还原为明文，并从密钥库中移除对应条目
+pub fn migrate_to_plaintext(config: &mut MultiAppConfig) -> Result<SecretsMigration, String> {
+    let mut result = SecretsMigration {
+        keys: 0,
+        providers: 0,
+    };
+    let mut released = Vec::new();
+    for app_type in AppType::all() {
+        let Some(manager) = config.get_manager_mut(&app_type) else {
+            continue;
+        };
+        for provider in manager.providers.values_mut() {
+            if let Some(rotation) = provider.meta.as_mut().and_then(|m| m.key_rotation.as_mut()) {
+                for entry in rotation.keys.iter() {
+                    if let Some(name) = entry.key.strip_prefix(SECRET_REF_PREFIX) {
+                        released.push(name.to_string());
+                        result.keys += 1;
+                    }
+                }
+                resolve_rotation(rotation)?;
+            }
+            if !has_secret_refs(&app_type, &provider.settings_config) {
+                continue;
+            }
+            for pointer in secret_fields(&app_type, &provider.settings_config) {
+                if let Some(name) = provider
+                    .settings_config
+                    .pointer(&pointer)
+                    .and_then(|v| v.as_str())
+                    .and_then(|s| s.strip_prefix(SECRET_REF_PREFIX))
+                {
+                    released.push(name.to_string());
+                    result.keys += 1;
+                }
+            }
+            provider.settings_config = resolve_settings(&app_type, &provider.settings_config)?;
+            result.providers += 1;
+        }
+    }
+
+    if !released.is_empty() {
+        let _guard = VAULT_LOCK.lock();
+        if let Some(mut vault) = Vault::load()? {
+            for name in &released {
+                vault.entries.remove(name);
+            }
+            vault.save()?;
+        }
+    }
+    Ok(result)
+}
+
+/// 返回密钥已全部解析为明文的配置副本（用于导出）
+pub fn export_with_secrets(config: &MultiAppConfig) -> Result<MultiAppConfig, String> {
+    let mut exported = config.clone();
+    for app_type in AppType::all() {
+        let Some(manager) = exported.get_manager_mut(&app_type) else {
+            continue;
+        };
+        for provider in manager.providers.values_mut() {
+            provider.settings_config = resolve_settings(&app_type, &provider.settings_config)?;
+            if let Some(rotation) = provider.meta.as_mut().and_then(|m| m.key_rotation.as_mut()) {
+                resolve_rotation(rotation)?;
+            }
+        }
+    }
+    Ok(exported)
+}
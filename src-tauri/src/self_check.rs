@@ -0,0 +1,255 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+/// 最近一次自检结果；启动时写入，恢复后刷新
+static REPORT: Mutex<Option<StartupReport>> = Mutex::new(None);
+
+/// 启动自检覆盖的配置文件
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum CheckTarget {
+    /// cc-switch 自身的 config.json
+    AppConfig,
+    /// Claude settings.json
+    ClaudeSettings,
+    /// Codex config.toml
+    CodexConfig,
+}
+
+const ALL_TARGETS: [CheckTarget; 3] = [
+    CheckTarget::AppConfig,
+    CheckTarget::ClaudeSettings,
+    CheckTarget::CodexConfig,
+];
+
+impl CheckTarget {
+    fn path(self) -> PathBuf {
+        match self {
+            CheckTarget::AppConfig => crate::config::get_app_config_path(),
+            CheckTarget::ClaudeSettings => crate::config::get_claude_settings_path(),
+            CheckTarget::CodexConfig => crate::codex_config::get_codex_config_path(),
+        }
+    }
+
+    /// 快照中的归档路径（见 backups）
+    fn archive_path(self) -> &'static str {
+        match self {
+            CheckTarget::AppConfig => "cc-switch/config.json",
+            CheckTarget::ClaudeSettings => "claude/settings.json",
+            CheckTarget::CodexConfig => "codex/config.toml",
+        }
+    }
+
+    fn parse(self, text: &str) -> Result<(), String> {
+        match self {
+            CheckTarget::AppConfig => crate::app_config::MultiAppConfig::validate_text(text),
+            CheckTarget::ClaudeSettings => {
+                if text.trim().is_empty() {
+                    return Ok(());
+                }
+                let value: serde_json::Value =
+                    serde_json::from_str(text).map_err(|e| format!("解析 JSON 失败: {}", e))?;
+                if !value.is_object() {
+                    return Err("顶层必须是 JSON 对象".to_string());
+                }
+                Ok(())
+            }
+            CheckTarget::CodexConfig => toml::from_str::<toml::Table>(text)
+                .map(|_| ())
+                .map_err(|e| format!("解析 TOML 失败: {}", e)),
+        }
+    }
+}
+
+/// 可用于恢复的备份来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BackupSource {
+    /// 配置快照（~/.cc-switch/snapshots）
+    Snapshot,
+    /// 保存 config.json 前留下的 config.json.bak
+    AppConfigBak,
+}
+
+/// 最新的可用备份（内容已确认可解析）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BackupCandidate {
+    pub source: BackupSource,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub snapshot_id: Option<String>,
+    /// 秒级时间戳
+    pub created_at: i64,
+}
+
+/// 损坏的配置文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct IntegrityIssue {
+    pub target: CheckTarget,
+    pub path: String,
+    pub error: String,
+    /// 没有可用备份时为空，只能重置
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backup: Option<BackupCandidate>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StartupReport {
+    /// 毫秒时间戳
+    pub checked_at: i64,
+    pub issues: Vec<IntegrityIssue>,
+}
+
+/// 处理损坏文件的方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum RecoveryAction {
+    /// 用最新的可用备份覆盖
+    RestoreBackup,
+    /// 将损坏文件改名保留，以空配置重新开始
+    Reset,
+}
+
+fn bak_path() -> PathBuf {
+    crate::config::get_app_config_dir().join("config.json.bak")
+}
+
+fn modified_secs(path: &std::path::Path) -> Option<i64> {
+    let modified = fs::metadata(path).and_then(|m| m.modified()).ok()?;
+    let secs = modified
+        .duration_since(std::time::UNIX_EPOCH)
+        .ok()?
+        .as_secs();
+    Some(secs as i64)
+}
+
+/// 查找最新的可用备份：按时间比较快照与 config.json.bak
+fn latest_backup(target: CheckTarget) -> Option<BackupCandidate> {
+    let snapshot = crate::backups::list_snapshots()
+        .unwrap_or_default()
+        .into_iter()
+        .find_map(|info| {
+            let text = crate::backups::read_snapshot_entry(&info.id, target.archive_path())
+                .ok()
+                .flatten()?;
+            target.parse(&text).ok()?;
+            Some(BackupCandidate {
+                source: BackupSource::Snapshot,
+                snapshot_id: Some(info.id),
+                created_at: info.created_at,
+            })
+        });
+    if target != CheckTarget::AppConfig {
+        return snapshot;
+    }
+
+    let bak = bak_path();
+    let bak = fs::read_to_string(&bak)
+        .ok()
+        .filter(|text| target.parse(text).is_ok())
+        .map(|_| BackupCandidate {
+            source: BackupSource::AppConfigBak,
+            snapshot_id: None,
+            created_at: modified_secs(&bak).unwrap_or_default(),
+        });
+    match (snapshot, bak) {
+        (Some(s), Some(b)) => Some(if b.created_at > s.created_at { b } else { s }),
+        (s, b) => s.or(b),
+    }
+}
+
+fn check(target: CheckTarget) -> Option<IntegrityIssue> {
+    let path = target.path();
+    if !path.exists() {
+        return None;
+    }
+    let error = match fs::read(&path) {
+        Ok(bytes) => match String::from_utf8(bytes) {
+            Ok(text) => target.parse(&text).err()?,
+            Err(_) => "文件不是有效的 UTF-8 文本".to_string(),
+        },
+        Err(e) => format!("读取文件失败: {}", e),
+    };
+    log::warn!("启动自检发现损坏的配置文件: {}: {}", path.display(), error);
+    Some(IntegrityIssue {
+        target,
+        path: path.to_string_lossy().to_string(),
+        error,
+        backup: latest_backup(target),
+    })
+}
+
+/// 检查全部配置文件并保存结果；应在加载 config.json 之前调用
+pub fn run() -> StartupReport {
+    let report = StartupReport {
+        checked_at: chrono::Utc::now().timestamp_millis(),
+        issues: ALL_TARGETS.iter().filter_map(|t| check(*t)).collect(),
+    };
+    if let Ok(mut guard) = REPORT.lock() {
+        *guard = Some(report.clone());
+    }
+    report
+}
+
+/// 最近一次自检结果；尚未检查时立即检查
+pub fn report() -> StartupReport {
+    let cached = REPORT.lock().ok().and_then(|guard| guard.clone());
+    cached.unwrap_or_else(run)
+}
+
+/// config.json 是否已损坏且尚未处理；此时拒绝保存，避免用默认配置覆盖原文件
+pub fn app_config_corrupt() -> bool {
+    REPORT.lock().ok().is_some_and(|guard| {
+        guard.as_ref().is_some_and(|r| {
+            r.issues
+                .iter()
+                .any(|issue| issue.target == CheckTarget::AppConfig)
+        })
+    })
+}
+
+/// 处理损坏的配置文件，返回重新检查后的结果
+pub fn recover(target: CheckTarget, action: RecoveryAction) -> Result<StartupReport, String> {
+    let path = target.path();
+    // 恢复是用户明确选择的覆盖，不再按外部修改拦截
+    if crate::write_guard::is_managed(&path) {
+        crate::write_guard::resolve(
+            &path.to_string_lossy(),
+            crate::write_guard::ConflictResolution::Overwrite,
+        )?;
+    }
+
+    match action {
+        RecoveryAction::RestoreBackup => {
+            let backup = latest_backup(target).ok_or_else(|| "没有可用于恢复的备份".to_string())?;
+            match (backup.source, backup.snapshot_id) {
+                (BackupSource::Snapshot, Some(id)) => {
+                    let only = [target.archive_path().to_string()];
+                    crate::backups::restore_snapshot(&id, Some(&only[..]))?;
+                }
+                _ => {
+                    let bytes = fs::read(bak_path()).map_err(|e| format!("读取备份失败: {}", e))?;
+                    crate::undo::capture("self-check-restore", std::slice::from_ref(&path));
+                    crate::config::atomic_write(&path, &bytes)?;
+                }
+            }
+        }
+        RecoveryAction::Reset => {
+            if path.exists() {
+                let ts = chrono::Local::now().format("%Y%m%d-%H%M%S");
+                let file_name = path
+                    .file_name()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_default();
+                let kept = path.with_file_name(format!("{}.corrupt-{}", file_name, ts));
+                fs::rename(&path, &kept).map_err(|e| format!("保留损坏文件失败: {}", e))?;
+                log::info!("已将损坏的配置文件改名为: {}", kept.display());
+            }
+        }
+    }
+    Ok(run())
+}
@@ -0,0 +1,337 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::collections::{BTreeMap, HashMap};
+use std::path::Path;
+
+use crate::conversation::{
+    read_conversation_messages, read_session_cwd, ReadingBlock, ReadingOptions,
+};
+
+/// 文件修改的类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum FileEditKind {
+    /// 替换文件中的片段（Claude Edit / MultiEdit，Gemini replace，补丁中的 Update File）
+    Edit,
+    /// 写入整个文件（Claude Write，Gemini write_file）
+    Write,
+    /// 新建文件（补丁中的 Add File）
+    Add,
+    /// 删除文件（补丁中的 Delete File）
+    Delete,
+}
+
+/// 会话中的一次文件修改
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEdit {
+    /// 工具调用所在消息的条目序号
+    pub message_index: usize,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<String>,
+    /// 工具名（Edit、Write、apply_patch …）
+    pub tool: String,
+    pub kind: FileEditKind,
+    pub path: String,
+    /// 补丁中的 Move to 目标路径
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub moved_to: Option<String>,
+    /// 统一 diff 风格的改动内容
+    pub diff: String,
+    pub additions: usize,
+    pub deletions: usize,
+    /// 工具结果为错误（修改可能未生效）
+    pub failed: bool,
+}
+
+/// 按文件汇总
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileEditSummary {
+    pub path: String,
+    pub edits: usize,
+    pub additions: usize,
+    pub deletions: usize,
+    /// 其中失败的修改次数
+    pub failed: usize,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SessionEdits {
+    /// 会话工作目录；补丁中的相对路径按此解析
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cwd: Option<String>,
+    /// 按路径排序的文件汇总
+    pub files: Vec<FileEditSummary>,
+    /// 按会话顺序排列的全部修改
+    pub edits: Vec<FileEdit>,
+}
+
+/// 一次修改的解析结果（未附带消息信息）
+struct ParsedEdit {
+    kind: FileEditKind,
+    path: String,
+    moved_to: Option<String>,
+    diff: String,
+}
+
+fn str_field<'a>(input: &'a Value, keys: &[&str]) -> Option<&'a str> {
+    keys.iter()
+        .find_map(|key| input.get(*key).and_then(|v| v.as_str()))
+}
+
+/// 片段替换的 diff：去掉首尾相同的行作为上下文，其余行按删除 / 新增列出
+fn replace_diff(old: &str, new: &str) -> String {
+    let old_lines: Vec<&str> = old.lines().collect();
+    let new_lines: Vec<&str> = new.lines().collect();
+    let prefix = old_lines
+        .iter()
+        .zip(&new_lines)
+        .take_while(|(a, b)| a == b)
+        .count();
+    let suffix = old_lines[prefix..]
+        .iter()
+        .rev()
+        .zip(new_lines[prefix..].iter().rev())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut out = String::from("@@\n");
+    for line in &old_lines[..prefix] {
+        out.push_str(&format!(" {}\n", line));
+    }
+    for line in &old_lines[prefix..old_lines.len() - suffix] {
+        out.push_str(&format!("-{}\n", line));
+    }
+    for line in &new_lines[prefix..new_lines.len() - suffix] {
+        out.push_str(&format!("+{}\n", line));
+    }
+    for line in &old_lines[old_lines.len() - suffix..] {
+        out.push_str(&format!(" {}\n", line));
+    }
+    out
+}
+
+fn added_diff(content: &str) -> String {
+    let mut out = String::from("@@\n");
+    for line in content.lines() {
+        out.push_str(&format!("+{}\n", line));
+    }
+    out
+}
+
+/// 在工具输入中查找 apply_patch 补丁文本（custom_tool_call 的 input 为补丁本身，
+/// shell 调用中补丁位于 command 数组或 heredoc 中）
+fn find_patch(input: &Value) -> Option<String> {
+    let texts: Vec<&str> = match input {
+        Value::String(s) => vec![s.as_str()],
+        Value::Object(_) => ["input", "patch", "command", "cmd"]
+            .iter()
+            .filter_map(|key| input.get(*key))
+            .flat_map(|v| match v {
+                Value::String(s) => vec![s.as_str()],
+                Value::Array(items) => items.iter().filter_map(|i| i.as_str()).collect(),
+                _ => Vec::new(),
+            })
+            .collect(),
+        _ => Vec::new(),
+    };
+    texts.into_iter().find_map(|text| {
+        let start = text.find("*** Begin Patch")?;
+        let rest = &text[start..];
+        let end = rest
+            .find("*** End Patch")
+            .map(|i| i + "*** End Patch".len())
+            .unwrap_or(rest.len());
+        Some(rest[..end].to_string())
+    })
+}
+
+/// 解析 Codex apply_patch 格式：*** Add File / *** Update File（可带 *** Move to）/ *** Delete File
+fn parse_patch(patch: &str) -> Vec<ParsedEdit> {
+    let mut edits: Vec<ParsedEdit> = Vec::new();
+    for line in patch.lines() {
+        let header = [
+            ("*** Add File: ", FileEditKind::Add),
+            ("*** Update File: ", FileEditKind::Edit),
+            ("*** Delete File: ", FileEditKind::Delete),
+        ]
+        .into_iter()
+        .find_map(|(prefix, kind)| line.strip_prefix(prefix).map(|path| (kind, path)));
+        if let Some((kind, path)) = header {
+            edits.push(ParsedEdit {
+                kind,
+                path: path.trim().to_string(),
+                moved_to: None,
+                diff: String::new(),
+            });
+            continue;
+        }
+        if line.starts_with("*** Begin Patch") || line.starts_with("*** End Patch") {
+            continue;
+        }
+        let Some(current) = edits.last_mut() else {
+            continue;
+        };
+        if let Some(target) = line.strip_prefix("*** Move to: ") {
+            current.moved_to = Some(target.trim().to_string());
+        } else if line != "*** End of File" {
+            current.diff.push_str(line);
+            current.diff.push('\n');
+        }
+    }
+    edits
+}
+
+/// 将一次工具调用解析为文件修改；不是文件修改工具时返回空
+fn parse_tool_use(name: &str, input: &Value) -> Vec<ParsedEdit> {
+    let path = || str_field(input, &["file_path", "notebook_path", "path"]).map(|s| s.to_string());
+    match name {
+        "Edit" | "replace" => {
+            let Some(path) = path() else {
+                return Vec::new();
+            };
+            let old = str_field(input, &["old_string"]).unwrap_or("");
+            let new = str_field(input, &["new_string"]).unwrap_or("");
+            vec![ParsedEdit {
+                kind: FileEditKind::Edit,
+                path,
+                moved_to: None,
+                diff: replace_diff(old, new),
+            }]
+        }
+        "MultiEdit" => {
+            let Some(path) = path() else {
+                return Vec::new();
+            };
+            let diff = input
+                .get("edits")
+                .and_then(|e| e.as_array())
+                .map(|edits| {
+                    edits
+                        .iter()
+                        .map(|edit| {
+                            replace_diff(
+                                str_field(edit, &["old_string"]).unwrap_or(""),
+                                str_field(edit, &["new_string"]).unwrap_or(""),
+                            )
+                        })
+                        .collect::<String>()
+                })
+                .unwrap_or_default();
+            vec![ParsedEdit {
+                kind: FileEditKind::Edit,
+                path,
+                moved_to: None,
+                diff,
+            }]
+        }
+        "Write" | "write_file" => {
+            let Some(path) = path() else {
+                return Vec::new();
+            };
+            vec![ParsedEdit {
+                kind: FileEditKind::Write,
+                path,
+                moved_to: None,
+                diff: added_diff(str_field(input, &["content"]).unwrap_or("")),
+            }]
+        }
+        "NotebookEdit" => {
+            let Some(path) = path() else {
+                return Vec::new();
+            };
+            vec![ParsedEdit {
+                kind: FileEditKind::Edit,
+                path,
+                moved_to: None,
+                diff: added_diff(str_field(input, &["new_source"]).unwrap_or("")),
+            }]
+        }
+        _ => find_patch(input)
+            .map(|patch| parse_patch(&patch))
+            .unwrap_or_default(),
+    }
+}
+
+fn count_lines(diff: &str, marker: char) -> usize {
+    diff.lines().filter(|l| l.starts_with(marker)).count()
+}
+
+/// 相对路径按会话工作目录解析
+fn resolve(path: String, cwd: Option<&str>) -> String {
+    match cwd {
+        Some(cwd) if Path::new(&path).is_relative() => {
+            Path::new(cwd).join(&path).to_string_lossy().to_string()
+        }
+        _ => path,
+    }
+}
+
+/// 提取会话中的文件修改：Claude Edit / MultiEdit / Write / NotebookEdit、Gemini replace / write_file
+/// 以及 Codex apply_patch；工具结果为错误的修改标记为失败
+pub fn session_edits(file_path: &str) -> Result<SessionEdits, String> {
+    let messages = read_conversation_messages(file_path, &ReadingOptions::default())?;
+    let cwd = read_session_cwd(Path::new(file_path));
+
+    let failed_calls: HashMap<&str, bool> = messages
+        .iter()
+        .flat_map(|m| m.blocks.iter())
+        .filter_map(|block| match block {
+            ReadingBlock::ToolResult {
+                tool_use_id,
+                is_error,
+                ..
+            } => Some((tool_use_id.as_str(), *is_error)),
+            _ => None,
+        })
+        .collect();
+
+    let mut edits = Vec::new();
+    for message in &messages {
+        for block in &message.blocks {
+            let ReadingBlock::ToolUse { id, name, input } = block else {
+                continue;
+            };
+            let failed = failed_calls.get(id.as_str()).copied().unwrap_or(false);
+            for parsed in parse_tool_use(name, input) {
+                edits.push(FileEdit {
+                    message_index: message.index,
+                    timestamp: message.timestamp.clone(),
+                    tool: name.clone(),
+                    kind: parsed.kind,
+                    path: resolve(parsed.path, cwd.as_deref()),
+                    moved_to: parsed.moved_to.map(|p| resolve(p, cwd.as_deref())),
+                    additions: count_lines(&parsed.diff, '+'),
+                    deletions: count_lines(&parsed.diff, '-'),
+                    diff: parsed.diff,
+                    failed,
+                });
+            }
+        }
+    }
+
+    let mut files: BTreeMap<&str, FileEditSummary> = BTreeMap::new();
+    for edit in &edits {
+        let summary = files
+            .entry(edit.path.as_str())
+            .or_insert_with(|| FileEditSummary {
+                path: edit.path.clone(),
+                edits: 0,
+                additions: 0,
+                deletions: 0,
+                failed: 0,
+            });
+        summary.edits += 1;
+        if edit.failed {
+            summary.failed += 1;
+        } else {
+            summary.additions += edit.additions;
+            summary.deletions += edit.deletions;
+        }
+    }
+    let files: Vec<FileEditSummary> = files.into_values().collect();
+
+    Ok(SessionEdits { cwd, files, edits })
+}
@@ -30,6 +30,38 @@ pub struct AppSettings {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub codex_config_dir: Option<String>,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gemini_config_dir: Option<String>,
+    /// Claude 对话记录目录（可多个，留空时使用配置目录下的 projects）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub claude_conversation_dirs: Vec<String>,
+    /// Codex 对话记录目录（可多个，留空时使用配置目录下的 sessions）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub codex_conversation_dirs: Vec<String>,
+    /// Gemini 对话记录目录（可多个，留空时使用配置目录下的 tmp）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub gemini_conversation_dirs: Vec<String>,
+    /// 已登记的多个 Claude / Codex 配置目录
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub app_homes: Vec<crate::app_homes::AppHome>,
+    /// 切换 Claude 供应商时写入的配置目录名称（为空时使用 claude_config_dir）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_claude_home: Option<String>,
+    /// 切换 Codex 供应商时写入的配置目录名称（为空时使用 codex_config_dir）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_codex_home: Option<String>,
+    /// 环境配置：成组切换 Claude / Codex 配置目录
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_profiles: Vec<crate::env_profiles::EnvProfile>,
+    /// 当前环境配置 id
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub active_env_profile: Option<String>,
+    /// WSL 模式（Windows）：配置目录位于所选发行版中
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub wsl: Option<crate::wsl::WslSettings>,
+    /// 远程主机（SSH）连接配置
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub remote_profiles: Vec<crate::remote::RemoteProfile>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub language: Option<String>,
     /// Claude 自定义端点列表
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
@@ -37,6 +69,78 @@ pub struct AppSettings {
     /// Codex 自定义端点列表
     #[serde(default, skip_serializing_if = "HashMap::is_empty")]
     pub custom_endpoints_codex: HashMap<String, CustomEndpoint>,
+    /// 全局规则 token 预算（CLAUDE.md 与已启用 Codex 规则合计）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub rules_token_budget: Option<usize>,
+    /// 外发通知渠道（Webhook / Slack / ntfy）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub notification_sinks: Vec<crate::notification_sinks::NotificationSink>,
+    /// 后台事件的系统通知与事件日志
+    #[serde(default)]
+    pub notifications: crate::notifications::NotificationSettings,
+    /// 事件规则（when <event> and <condition> then <action>）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub event_rules: Vec<crate::event_rules::EventRule>,
+    /// 定时配置快照间隔（小时），为空或 0 表示关闭
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_interval_hours: Option<u64>,
+    /// 保留的配置快照数量
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_retain: Option<usize>,
+    /// 保留的写入前快照数量（与上面的数量分开计数）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backup_pre_write_retain: Option<usize>,
+    /// 工作区：项目目录与供应商绑定
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<crate::workspaces::Workspace>,
+    /// 定时健康检查间隔（分钟），为空或 0 表示关闭
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub health_check_interval_minutes: Option<u64>,
+    /// 批量导出文件名模板，支持 {project} {date} {title} {id} {app}
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub export_name_template: Option<String>,
+    /// 批量导出目标文件已存在时的处理策略
+    #[serde(default)]
+    pub export_collision_policy: crate::export_naming::CollisionPolicy,
+    /// 自动故障转移
+    #[serde(default)]
+    pub failover: crate::failover::FailoverSettings,
+    /// 定时状态报告间隔（天），为空或 0 表示关闭
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup_report_interval_days: Option<u64>,
+    /// 状态报告保存目录，默认 ~/.cc-switch/reports
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub setup_report_dir: Option<String>,
+    /// 定时切换规则（按顺序取第一条命中的规则）
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub switch_schedules: Vec<crate::switch_schedule::SwitchSchedule>,
+    /// MCP 目录远程刷新地址（返回与内置目录相同结构的 JSON）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub mcp_catalog_url: Option<String>,
+    /// 全局代理，写入各应用的 live 配置（供应商可在 meta.proxy 中单独覆盖）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub proxy: Option<crate::proxy::ProxyConfig>,
+    /// 在当前与上一个供应商之间切换的全局快捷键，例如 CmdOrCtrl+Shift+P
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub toggle_shortcut: Option<String>,
+    /// 本地自动化接口（JSON-RPC，默认关闭）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub local_api: Option<crate::local_api::LocalApiSettings>,
+    /// Claude Code 状态栏（显示当前供应商、模型与会话费用）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub statusline: Option<crate::statusline::StatuslineSettings>,
+    /// 云同步（WebDAV / S3 / Gist，端到端加密）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sync: Option<crate::sync::SyncSettings>,
+    /// 配置版本库（~/.cc-switch 的本地 git 仓库，每次修改自动提交）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_history: Option<crate::config_history::ConfigHistorySettings>,
+    /// 暂存验证：写入 CLI 配置前先在暂存目录验证
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub staged_apply: Option<crate::staged_apply::StagedApplySettings>,
+    /// 日志级别（默认 info），日志写入 ~/.cc-switch/logs
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub log_level: Option<crate::logging::LogLevel>,
 }
 
 fn default_show_in_tray() -> bool {
@@ -55,15 +159,54 @@ impl Default for AppSettings {
             enable_claude_plugin_integration: false,
             claude_config_dir: None,
             codex_config_dir: None,
+            gemini_config_dir: None,
+            claude_conversation_dirs: Vec::new(),
+            codex_conversation_dirs: Vec::new(),
+            gemini_conversation_dirs: Vec::new(),
+            app_homes: Vec::new(),
+            active_claude_home: None,
+            active_codex_home: None,
+            env_profiles: Vec::new(),
+            active_env_profile: None,
+            wsl: None,
+            remote_profiles: Vec::new(),
             language: None,
             custom_endpoints_claude: HashMap::new(),
             custom_endpoints_codex: HashMap::new(),
+            rules_token_budget: None,
+            notification_sinks: Vec::new(),
+            notifications: Default::default(),
+            event_rules: Vec::new(),
+            backup_interval_hours: None,
+            backup_retain: None,
+            backup_pre_write_retain: None,
+            workspaces: Vec::new(),
+            health_check_interval_minutes: None,
+            export_name_template: None,
+            export_collision_policy: Default::default(),
+            failover: Default::default(),
+            setup_report_interval_days: None,
+            setup_report_dir: None,
+            switch_schedules: Vec::new(),
+            mcp_catalog_url: None,
+            proxy: None,
+            toggle_shortcut: None,
+            local_api: None,
+            statusline: None,
+            sync: None,
+            config_history: None,
+            staged_apply: None,
+            log_level: None,
         }
     }
 }
 
 impl AppSettings {
-    fn settings_path() -> PathBuf {
+    pub(crate) fn settings_path() -> PathBuf {
+        // 便携模式的数据目录由 portable.ini 决定，不依赖 settings，可直接使用
+        if let Some(dir) = crate::portable::data_dir() {
+            return dir.join("settings.json");
+        }
         // settings.json 必须使用固定路径，不能被 app_config_dir 覆盖
         // 否则会造成循环依赖：读取 settings 需要知道路径，但路径在 settings 中
         dirs::home_dir()
@@ -87,6 +230,63 @@ impl AppSettings {
             .filter(|s| !s.is_empty())
             .map(|s| s.to_string());
 
+        self.gemini_config_dir = self
+            .gemini_config_dir
+            .as_ref()
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| s.to_string());
+
+        for dirs in [
+            &mut self.claude_conversation_dirs,
+            &mut self.codex_conversation_dirs,
+            &mut self.gemini_conversation_dirs,
+        ] {
+            let mut seen = std::collections::HashSet::new();
+            *dirs = dirs
+                .iter()
+                .map(|s| s.trim().to_string())
+                .filter(|s| !s.is_empty() && seen.insert(s.clone()))
+                .collect();
+        }
+
+        for home in &mut self.app_homes {
+            home.name = home.name.trim().to_string();
+            home.dir = home.dir.trim().to_string();
+        }
+        let mut seen = std::collections::HashSet::new();
+        self.app_homes.retain(|h| {
+            !h.name.is_empty()
+                && !h.dir.is_empty()
+                && matches!(h.app_type.as_str(), "claude" | "codex")
+                && seen.insert((h.app_type.clone(), h.name.clone()))
+        });
+        // 选中的配置目录已被移除时回到默认目录
+        for (app, active) in [
+            ("claude", &mut self.active_claude_home),
+            ("codex", &mut self.active_codex_home),
+        ] {
+            let known = active.as_ref().is_some_and(|name| {
+                self.app_homes
+                    .iter()
+                    .any(|h| h.app_type == app && &h.name == name)
+            });
+            if !known {
+                *active = None;
+            }
+        }
+        // 单独选择了其他配置目录后，当前环境配置不再成立
+        let profile_matches = self.active_env_profile.as_ref().is_some_and(|id| {
+            self.env_profiles.iter().any(|p| {
+                &p.id == id
+                    && p.claude_home == self.active_claude_home
+                    && p.codex_home == self.active_codex_home
+            })
+        });
+        if !profile_matches {
+            self.active_env_profile = None;
+        }
+
         self.language = self
             .language
             .as_ref()
@@ -128,9 +328,29 @@ impl AppSettings {
 
         let json = serde_json::to_string_pretty(&normalized)
             .map_err(|e| format!("序列化设置失败: {}", e))?;
-        fs::write(&path, json).map_err(|e| format!("写入设置失败: {}", e))?;
+        crate::config::write_text_file(&path, &json).map_err(|e| format!("写入设置失败: {}", e))?;
         Ok(())
     }
+
+    /// WSL 模式下所选发行版中的用户主目录
+    fn wsl_home_dir(&self) -> Option<PathBuf> {
+        self.wsl
+            .as_ref()
+            .filter(|w| w.enabled && !w.distro.trim().is_empty() && !w.user.trim().is_empty())
+            .map(|w| crate::wsl::home_path(w.distro.trim(), w.user.trim()))
+    }
+
+    /// 当前选中的已登记配置目录
+    fn active_home_dir(&self, app: &str) -> Option<PathBuf> {
+        let active = match app {
+            "codex" => self.active_codex_home.as_ref(),
+            _ => self.active_claude_home.as_ref(),
+        }?;
+        self.app_homes
+            .iter()
+            .find(|h| h.app_type == app && &h.name == active)
+            .map(|h| resolve_override_path(&h.dir))
+    }
 }
 
 fn settings_store() -> &'static RwLock<AppSettings> {
@@ -138,7 +358,7 @@ fn settings_store() -> &'static RwLock<AppSettings> {
     STORE.get_or_init(|| RwLock::new(AppSettings::load()))
 }
 
-fn resolve_override_path(raw: &str) -> PathBuf {
+pub(crate) fn resolve_override_path(raw: &str) -> PathBuf {
     if raw == "~" {
         if let Some(home) = dirs::home_dir() {
             return home;
@@ -164,23 +384,114 @@ pub fn update_settings(mut new_settings: AppSettings) -> Result<(), String> {
     new_settings.normalize_paths();
     new_settings.save()?;
 
-    let mut guard = settings_store().write().expect("写入设置锁失败");
-    *guard = new_settings;
+    {
+        let mut guard = settings_store().write().expect("写入设置锁失败");
+        *guard = new_settings;
+    }
+    crate::logging::apply_level();
+    crate::config_history::auto_commit();
     Ok(())
 }
 
+/// 界面语言（与前端共享）；未设置时返回 None
+pub fn get_language() -> Option<String> {
+    settings_store().read().ok()?.language.clone()
+}
+
 pub fn get_claude_override_dir() -> Option<PathBuf> {
     let settings = settings_store().read().ok()?;
+    if let Some(dir) = settings.active_home_dir("claude") {
+        return Some(dir);
+    }
     settings
         .claude_config_dir
         .as_ref()
         .map(|p| resolve_override_path(p))
+        .or_else(|| settings.wsl_home_dir().map(|home| home.join(".claude")))
 }
 
 pub fn get_codex_override_dir() -> Option<PathBuf> {
     let settings = settings_store().read().ok()?;
+    if let Some(dir) = settings.active_home_dir("codex") {
+        return Some(dir);
+    }
     settings
         .codex_config_dir
         .as_ref()
         .map(|p| resolve_override_path(p))
+        .or_else(|| settings.wsl_home_dir().map(|home| home.join(".codex")))
+}
+
+pub fn get_gemini_override_dir() -> Option<PathBuf> {
+    let settings = settings_store().read().ok()?;
+    settings
+        .gemini_config_dir
+        .as_ref()
+        .map(|p| resolve_override_path(p))
+        .or_else(|| settings.wsl_home_dir().map(|home| home.join(".gemini")))
+}
+
+/// WSL 模式下所选发行版中的用户主目录
+pub fn get_wsl_home_dir() -> Option<PathBuf> {
+    let settings = settings_store().read().ok()?;
+    settings.wsl_home_dir()
+}
+
+/// 当前选中的已登记 Claude 配置目录
+pub fn get_active_claude_home_dir() -> Option<PathBuf> {
+    let settings = settings_store().read().ok()?;
+    settings.active_home_dir("claude")
+}
+
+/// 当前选中的已登记配置目录名称
+pub fn get_active_home(app: &crate::app_config::AppType) -> Option<String> {
+    let settings = settings_store().read().ok()?;
+    match app {
+        crate::app_config::AppType::Claude => settings.active_claude_home.clone(),
+        crate::app_config::AppType::Codex => settings.active_codex_home.clone(),
+        _ => None,
+    }
+}
+
+pub fn get_claude_conversation_dirs() -> Vec<PathBuf> {
+    let Ok(settings) = settings_store().read() else {
+        return Vec::new();
+    };
+    settings
+        .claude_conversation_dirs
+        .iter()
+        .map(|p| resolve_override_path(p))
+        .collect()
+}
+
+pub fn get_codex_conversation_dirs() -> Vec<PathBuf> {
+    let Ok(settings) = settings_store().read() else {
+        return Vec::new();
+    };
+    settings
+        .codex_conversation_dirs
+        .iter()
+        .map(|p| resolve_override_path(p))
+        .collect()
+}
+
+pub fn get_gemini_conversation_dirs() -> Vec<PathBuf> {
+    let Ok(settings) = settings_store().read() else {
+        return Vec::new();
+    };
+    settings
+        .gemini_conversation_dirs
+        .iter()
+        .map(|p| resolve_override_path(p))
+        .collect()
+}
+
+pub fn get_setup_report_override_dir() -> Option<PathBuf> {
+    let settings = settings_store().read().ok()?;
+    settings
+        .setup_report_dir
+        .as_deref()
+        .map(str::trim)
+        .filter(|p| !p.is_empty())
+        .map(resolve_override_path)
 }
\ No newline at end of file
@@ -0,0 +1,384 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use crate::app_config::MultiAppConfig;
+use crate::config::{get_app_config_dir, write_text_file};
+use crate::conversation_stats::TokenTotals;
+use crate::global_rules::RuleLintSeverity;
+use crate::provider_trends::TrendRange;
+
+/// 报告文件名前缀
+const REPORT_PREFIX: &str = "setup-report_";
+/// 调度器检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(3600);
+/// 失败率超过该值的供应商会出现在维护建议中
+const UNRELIABLE_FAILURE_RATE: f64 = 0.2;
+/// 对话记录总大小超过该值时建议清理
+const LARGE_STORAGE_BYTES: u64 = 1024 * 1024 * 1024;
+/// Claude todos / shell-snapshots（及 Codex 历史与日志）超过该大小或文件数时建议清理
+const LARGE_CACHE_BYTES: u64 = 100 * 1024 * 1024;
+const LARGE_CACHE_FILES: usize = 5000;
+/// 报告统计的时间窗口
+const REPORT_WINDOW_DAYS: u64 = 7;
+
+/// 已生成的报告
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SetupReportInfo {
+    pub path: String,
+    pub generated_at: i64,
+    /// 维护建议数量
+    pub suggestions: usize,
+}
+
+/// 报告目录：设置中的 setupReportDir，默认 ~/.cc-switch/reports
+pub fn get_report_dir() -> PathBuf {
+    crate::settings::get_setup_report_override_dir()
+        .unwrap_or_else(|| get_app_config_dir().join("reports"))
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .flatten()
+        .map(|entry| match entry.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&entry.path()),
+            Ok(t) if t.is_file() => entry.metadata().map(|m| m.len()).unwrap_or(0),
+            _ => 0,
+        })
+        .sum()
+}
+
+fn format_bytes(bytes: u64) -> String {
+    const UNITS: [&str; 4] = ["B", "KB", "MB", "GB"];
+    let mut value = bytes as f64;
+    let mut unit = 0;
+    while value >= 1024.0 && unit < UNITS.len() - 1 {
+        value /= 1024.0;
+        unit += 1;
+    }
+    if unit == 0 {
+        format!("{} B", bytes)
+    } else {
+        format!("{:.1} {}", value, UNITS[unit])
+    }
+}
+
+fn escape_cell(cell: &str) -> String {
+    cell.replace('|', "\\|").replace(['\r', '\n'], " ")
+}
+
+fn storage_section(out: &mut String, suggestions: &mut Vec<String>) {
+    let claude: u64 = crate::conversation::claude_conversation_roots()
+        .iter()
+        .map(|dir| dir_size(dir))
+        .sum();
+    let codex: u64 = crate::conversation::codex_conversation_roots()
+        .iter()
+        .map(|dir| dir_size(dir))
+        .sum();
+    let snapshots = crate::backups::list_snapshots().unwrap_or_default();
+    let snapshot_bytes: u64 = snapshots.iter().map(|s| s.size).sum();
+    let caches = crate::claude_caches::summaries();
+    let codex_caches = crate::codex_caches::summaries();
+
+    out.push_str("## Storage\n\n| Item | Size |\n| --- | --- |\n");
+    out.push_str(&format!(
+        "| Claude conversations | {} |\n",
+        format_bytes(claude)
+    ));
+    out.push_str(&format!("| Codex sessions | {} |\n", format_bytes(codex)));
+    for cache in &caches {
+        out.push_str(&format!(
+            "| {} ({}) | {} |\n",
+            cache.kind.label(),
+            cache.files,
+            format_bytes(cache.bytes)
+        ));
+    }
+    for cache in &codex_caches {
+        out.push_str(&format!(
+            "| {} | {} |\n",
+            cache.target.label(),
+            format_bytes(cache.bytes)
+        ));
+    }
+    out.push_str(&format!(
+        "| Config snapshots ({}) | {} |\n\n",
+        snapshots.len(),
+        format_bytes(snapshot_bytes)
+    ));
+
+    if claude + codex > LARGE_STORAGE_BYTES {
+        suggestions.push(format!(
+            "Conversations take up {}; consider exporting and cleaning up old ones",
+            format_bytes(claude + codex)
+        ));
+    }
+    let cache_bytes: u64 = caches.iter().map(|c| c.bytes).sum();
+    let cache_files: usize = caches.iter().map(|c| c.files).sum();
+    if cache_bytes > LARGE_CACHE_BYTES || cache_files > LARGE_CACHE_FILES {
+        suggestions.push(format!(
+            "Claude todos / shell-snapshots caches hold {} files ({}); clean up old entries in Maintenance",
+            cache_files,
+            format_bytes(cache_bytes)
+        ));
+    }
+    let codex_cache_bytes: u64 = codex_caches.iter().map(|c| c.bytes).sum();
+    if codex_cache_bytes > LARGE_CACHE_BYTES {
+        suggestions.push(format!(
+            "Codex input history and logs take up {}; consider truncating or clearing them",
+            format_bytes(codex_cache_bytes)
+        ));
+    }
+    let backups_enabled = crate::settings::get_settings()
+        .backup_interval_hours
+        .is_some_and(|h| h > 0);
+    if snapshots.is_empty() {
+        suggestions.push(
+            "No config snapshots yet; create one manually or enable scheduled snapshots"
+                .to_string(),
+        );
+    } else if !backups_enabled {
+        suggestions.push("Scheduled config snapshots are disabled".to_string());
+    }
+}
+
+/// 各应用在统计窗口内有写入的会话数、大小、token 用量与估算费用
+fn usage_section(out: &mut String) {
+    let since = SystemTime::now() - Duration::from_secs(REPORT_WINDOW_DAYS * 24 * 3600);
+    let files = crate::conversation::recent_session_files(since);
+    out.push_str(&format!(
+        "## Usage (last {} days)\n\n| App | Active sessions | Size | Input | Output | Cache | Total tokens | Est. cost (USD) |\n| --- | --- | --- | --- | --- | --- | --- | --- |\n",
+        REPORT_WINDOW_DAYS
+    ));
+    for adapter in crate::app_adapter::all_adapters() {
+        let app = adapter.app_type().as_str();
+        let sessions: Vec<&PathBuf> = files
+            .iter()
+            .filter(|(a, _)| a == app)
+            .map(|(_, p)| p)
+            .collect();
+        let bytes: u64 = sessions
+            .iter()
+            .filter_map(|p| fs::metadata(p).ok())
+            .map(|m| m.len())
+            .sum();
+        let mut tokens = TokenTotals::default();
+        for path in &sessions {
+            match crate::conversation_stats::session_tokens(&path.to_string_lossy()) {
+                Ok(session) => tokens.add(&session),
+                Err(e) => log::debug!("统计会话用量失败 {}: {}", path.display(), e),
+            }
+        }
+        out.push_str(&format!(
+            "| {} | {} | {} | {} | {} | {} | {} | {:.2} |\n",
+            adapter.display_name(),
+            sessions.len(),
+            format_bytes(bytes),
+            tokens.input,
+            tokens.output,
+            tokens.cache_creation + tokens.cache_read,
+            tokens.total,
+            tokens.cost_usd
+        ));
+    }
+    out.push_str(
+        "\nTokens cover whole sessions written to in this period. Costs are estimated from built-in list prices; models without a known price are not counted.\n\n",
+    );
+}
+
+fn reliability_section(config: &MultiAppConfig, out: &mut String, suggestions: &mut Vec<String>) {
+    out.push_str(&format!(
+        "## Provider reliability (last {} days)\n\n",
+        REPORT_WINDOW_DAYS
+    ));
+    let mut rows = String::new();
+    for row in crate::provider_matrix::collect_rows(config) {
+        let trends =
+            crate::provider_trends::get_provider_trends(&row.app_type, &row.id, TrendRange::Week);
+        if trends.samples == 0 {
+            continue;
+        }
+        let failure_rate = trends.failure_rate.unwrap_or(0.0);
+        rows.push_str(&format!(
+            "| {} | {} | {} | {:.1}% | {} |\n",
+            row.app_type,
+            escape_cell(&row.name),
+            trends.samples,
+            failure_rate * 100.0,
+            trends
+                .avg_latency_ms
+                .map(|v| format!("{:.0}", v))
+                .unwrap_or_default()
+        ));
+        if failure_rate > UNRELIABLE_FAILURE_RATE {
+            suggestions.push(format!(
+                "{} provider {} failed {:.1}% of health checks in the last {} days; check it or lower its failover priority",
+                row.app_type,
+                row.name,
+                failure_rate * 100.0,
+                REPORT_WINDOW_DAYS
+            ));
+        }
+    }
+
+    if rows.is_empty() {
+        out.push_str("No health check samples recorded.\n\n");
+        if crate::settings::get_settings()
+            .health_check_interval_minutes
+            .is_none_or(|m| m == 0)
+        {
+            suggestions.push(
+                "Scheduled health checks are disabled, so provider reliability cannot be tracked"
+                    .to_string(),
+            );
+        }
+    } else {
+        out.push_str(
+            "| App | Provider | Samples | Failure Rate | Avg Latency (ms) |\n| --- | --- | --- | --- | --- |\n",
+        );
+        out.push_str(&rows);
+        out.push('\n');
+    }
+}
+
+fn rules_section(out: &mut String, suggestions: &mut Vec<String>) {
+    out.push_str("## Rules\n\n");
+    match crate::global_rules::estimate_rules_tokens(None) {
+        Ok(report) => {
+            out.push_str(&format!(
+                "Token budget: {} / {}{}\n\n",
+                report.total_tokens,
+                report.budget,
+                if report.over_budget {
+                    " (over budget)"
+                } else {
+                    ""
+                }
+            ));
+            if report.over_budget {
+                suggestions.push(format!(
+                    "Rules use {} tokens, over the {} token budget",
+                    report.total_tokens, report.budget
+                ));
+            }
+        }
+        Err(e) => out.push_str(&format!("Token estimate unavailable: {}\n\n", e)),
+    }
+
+    match crate::global_rules::lint_rules() {
+        Ok(diagnostics) => {
+            let count = |severity: RuleLintSeverity| {
+                diagnostics
+                    .iter()
+                    .filter(|d| d.severity == severity)
+                    .count()
+            };
+            let (errors, warnings) = (
+                count(RuleLintSeverity::Error),
+                count(RuleLintSeverity::Warning),
+            );
+            out.push_str(&format!(
+                "Lint: {} error(s), {} warning(s)\n\n",
+                errors, warnings
+            ));
+            if errors + warnings > 0 {
+                suggestions.push(format!(
+                    "Rules lint found {} error(s) and {} warning(s)",
+                    errors, warnings
+                ));
+            }
+        }
+        Err(e) => out.push_str(&format!("Lint unavailable: {}\n\n", e)),
+    }
+}
+
+/// 生成状态报告 Markdown（标题、表格与维护建议均为英文），返回 (内容, 维护建议数量)
+pub fn render_setup_report(config: &MultiAppConfig) -> (String, usize) {
+    let now = chrono::Local::now();
+    let mut out = format!(
+        "# cc-switch setup report\n\nGenerated at {}\n\n",
+        now.format("%Y-%m-%d %H:%M")
+    );
+    let mut suggestions = Vec::new();
+
+    storage_section(&mut out, &mut suggestions);
+    usage_section(&mut out);
+    reliability_section(config, &mut out, &mut suggestions);
+    rules_section(&mut out, &mut suggestions);
+
+    out.push_str("## Maintenance suggestions\n\n");
+    if suggestions.is_empty() {
+        out.push_str("Nothing to do.\n");
+    } else {
+        for suggestion in &suggestions {
+            out.push_str(&format!("- {}\n", suggestion));
+        }
+    }
+    (out, suggestions.len())
+}
+
+/// 生成状态报告并写入报告目录（文件名 setup-report_<日期>.md，同日重复生成会覆盖）
+pub fn write_setup_report(config: &MultiAppConfig) -> Result<SetupReportInfo, String> {
+    let (content, suggestions) = render_setup_report(config);
+    let now = chrono::Local::now();
+    let path = get_report_dir().join(format!("{}{}.md", REPORT_PREFIX, now.format("%Y-%m-%d")));
+    write_text_file(&path, &content)?;
+    Ok(SetupReportInfo {
+        path: path.to_string_lossy().to_string(),
+        generated_at: now.timestamp(),
+        suggestions,
+    })
+}
+
+/// 最近一次生成报告的时间（按报告文件的修改时间）
+fn latest_report_time() -> Option<SystemTime> {
+    fs::read_dir(get_report_dir())
+        .ok()?
+        .flatten()
+        .filter(|e| e.file_name().to_string_lossy().starts_with(REPORT_PREFIX))
+        .filter_map(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .max()
+}
+
+/// 启动定时状态报告（间隔由设置 setupReportIntervalDays 控制，为空或 0 表示关闭）
+pub fn start_report_scheduler(app: tauri::AppHandle) {
+    use tauri::Manager;
+
+    tauri::async_runtime::spawn(async move {
+        loop {
+            tokio::time::sleep(SCHEDULER_TICK).await;
+
+            let Some(days) = crate::settings::get_settings()
+                .setup_report_interval_days
+                .filter(|d| *d > 0)
+            else {
+                continue;
+            };
+            let due = latest_report_time()
+                .and_then(|t| t.elapsed().ok())
+                .is_none_or(|elapsed| elapsed.as_secs() >= days * 24 * 3600);
+            if !due {
+                continue;
+            }
+
+            let config = {
+                let state = app.state::<crate::store::AppState>();
+                let Ok(config) = state.config.lock() else {
+                    continue;
+                };
+                config.clone()
+            };
+            match tauri::async_runtime::spawn_blocking(move || write_setup_report(&config)).await {
+                Ok(Ok(info)) => log::info!("已生成状态报告: {}", info.path),
+                Ok(Err(e)) => log::warn!("生成状态报告失败: {}", e),
+                Err(e) => log::warn!("生成状态报告失败: {}", e),
+            }
+        }
+    });
+}
@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use std::path::Path;
+
+use crate::export_naming::{export_naming_settings, reserve_export_path};
+
+use crate::conversation::{
+    conversation_title, list_conversations, read_conversation_messages,
+    render_conversation_markdown, ReadingOptions,
+};
+
+/// 每个对话页面所在的子目录
+const PAGES_DIR: &str = "conversations";
+
+const STYLE: &str = r#"
+body { max-width: 920px; margin: 0 auto; padding: 24px; font-family: -apple-system, BlinkMacSystemFont, "Segoe UI", sans-serif; line-height: 1.6; color: #1f2328; }
+a { color: #0969da; text-decoration: none; }
+a:hover { text-decoration: underline; }
+pre { background: #f6f8fa; padding: 12px; border-radius: 6px; overflow-x: auto; }
+code { font-family: ui-monospace, SFMono-Regular, Menlo, monospace; font-size: 0.9em; }
+blockquote { margin: 0; padding: 0 12px; color: #59636e; border-left: 3px solid #d1d9e0; }
+table { border-collapse: collapse; width: 100%; }
+th, td { border: 1px solid #d1d9e0; padding: 6px 10px; text-align: left; }
+h2 { font-size: 1.1em; border-bottom: 1px solid #d1d9e0; padding-bottom: 4px; margin-top: 2em; }
+.meta { color: #59636e; font-size: 0.9em; }
+"#;
+
+/// 生成结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ShareBundle {
+    pub dir: String,
+    /// 入口页 index.html 的路径
+    pub index: String,
+    pub pages: Vec<String>,
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+/// 对话 id 作为文件名时只保留安全字符
+fn page_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.html", name)
+}
+
+fn page(title: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html>\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}</title>\n<style>{}</style>\n</head>\n<body>\n{}</body>\n</html>\n",
+        escape_html(title),
+        STYLE,
+        body
+    )
+}
+
+fn format_date(ts: i64) -> String {
+    chrono::DateTime::from_timestamp(ts, 0)
+        .map(|dt| {
+            dt.with_timezone(&chrono::Local)
+                .format("%Y-%m-%d %H:%M")
+                .to_string()
+        })
+        .unwrap_or_default()
+}
+
+/// 将选中的对话渲染为静态网站：dest_dir/index.html 列出全部对话，
+/// 每个对话一个页面（conversations/<id>.html），不依赖外部资源，可直接部署到任意静态托管。
+/// 目标目录中已有的同名文件按设置中的冲突策略处理，被跳过的页面不出现在入口页中
+pub fn build(
+    file_paths: &[String],
+    dest_dir: &str,
+    title: Option<&str>,
+) -> Result<ShareBundle, String> {
+    if file_paths.is_empty() {
+        return Err("请至少选择一个对话".to_string());
+    }
+    let dest = Path::new(dest_dir);
+    let bundle_title = title
+        .map(|t| t.trim())
+        .filter(|t| !t.is_empty())
+        .unwrap_or("Conversations");
+    let all = list_conversations(None)?;
+    let (_, policy) = export_naming_settings();
+    // 先占用入口页，避免写完对话页面后才发现入口页被跳过
+    let index = reserve_export_path(dest, Path::new("index"), "html", policy)?
+        .ok_or_else(|| format!("{} 中已存在 index.html，已按冲突策略跳过", dest.display()))?;
+
+    let mut rows = String::new();
+    let mut pages = Vec::with_capacity(file_paths.len());
+    let mut used = std::collections::HashSet::new();
+    for file_path in file_paths {
+        let meta = all
+            .iter()
+            .find(|m| &m.file_path == file_path)
+            .ok_or_else(|| format!("对话不存在: {}", file_path))?;
+        let mut messages = read_conversation_messages(
+            file_path,
+            &ReadingOptions {
+                collapse_tools: true,
+                ..Default::default()
+            },
+        )?;
+        let page_title = conversation_title(&messages);
+        // 备注是个人笔记，不出现在分享页面中
+        for message in messages.iter_mut() {
+            message.note = None;
+        }
+        let markdown = render_conversation_markdown(meta, page_title.as_deref(), None, &messages);
+        // 不同应用的会话 id 可能重名
+        let mut name = page_name(&meta.id);
+        let mut n = 2;
+        while !used.insert(name.clone()) {
+            name = page_name(&format!("{}-{}", meta.id, n));
+            n += 1;
+        }
+        let body = format!(
+            "<p class=\"meta\"><a href=\"../index.html\">← {}</a></p>\n{}",
+            escape_html(bundle_title),
+            crate::markdown::render_markdown(&markdown)
+        );
+        let stem = name.trim_end_matches(".html");
+        let Some(target) =
+            reserve_export_path(&dest.join(PAGES_DIR), Path::new(stem), "html", policy)?
+        else {
+            continue;
+        };
+        crate::config::write_text_file(
+            &target,
+            &page(page_title.as_deref().unwrap_or(&meta.id), &body),
+        )?;
+        pages.push(target.to_string_lossy().to_string());
+        // 追加序号时实际文件名与 name 不同
+        let written_name = target
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or(name);
+
+        rows.push_str(&format!(
+            "<tr><td><a href=\"{}/{}\">{}</a></td><td>{}</td><td>{}</td><td>{}</td><td>{}</td></tr>\n",
+            PAGES_DIR,
+            written_name,
+            escape_html(page_title.as_deref().unwrap_or(&meta.id)),
+            escape_html(&meta.app_type),
+            escape_html(meta.project_name.as_deref().unwrap_or("")),
+            format_date(meta.created_at.unwrap_or(meta.modified_at)),
+            meta.message_count
+        ));
+    }
+
+    let index_body = format!(
+        "<h1>{}</h1>\n<p class=\"meta\">{} · {}</p>\n<table>\n<thead><tr><th>Title</th><th>App</th><th>Project</th><th>Date</th><th>Messages</th></tr></thead>\n<tbody>\n{}</tbody>\n</table>\n",
+        escape_html(bundle_title),
+        pages.len(),
+        format_date(chrono::Utc::now().timestamp()),
+        rows
+    );
+    crate::config::write_text_file(&index, &page(bundle_title, &index_body))?;
+    log::info!("已生成分享包: {}（{} 个对话）", dest.display(), pages.len());
+
+    Ok(ShareBundle {
+        dir: dest.to_string_lossy().to_string(),
+        index: index.to_string_lossy().to_string(),
+        pages,
+    })
+}
@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::Path;
+use std::process::{Command, Stdio};
+use std::time::{Duration, Instant};
+use tempfile::TempDir;
+
+use crate::app_config::AppType;
+
+/// CLI 验证的默认超时（秒）
+const DEFAULT_TIMEOUT_SECS: u64 = 20;
+
+/// 暂存验证：写入 CLI 配置前先写到暂存目录并验证，通过后才替换正式文件
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StagedApplySettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 只做解析校验，不运行 CLI
+    #[serde(default)]
+    pub parse_only: bool,
+    /// CLI 验证超时（秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_secs: Option<u64>,
+}
+
+/// CLI 探测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CliProbe {
+    pub app_type: String,
+    pub found: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+/// 受暂存验证保护的文件类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StagedKind {
+    /// Claude settings.json（按 Schema 校验）
+    ClaudeSettings,
+    /// Codex config.toml（按已知字段校验）
+    CodexConfig,
+    /// 其他 JSON（auth.json、.claude.json）
+    Json,
+    /// 规则文件，只运行 CLI
+    Markdown,
+}
+
+fn settings() -> Option<StagedApplySettings> {
+    crate::settings::get_settings()
+        .staged_apply
+        .filter(|s| s.enabled)
+}
+
+/// 文件所属应用及类型；不受保护的文件返回 None
+fn classify(path: &Path) -> Option<(AppType, StagedKind)> {
+    let claude_dir = crate::config::get_claude_config_dir();
+    let codex_dir = crate::codex_config::get_codex_config_dir();
+    let candidates = [
+        (
+            crate::config::get_claude_settings_path(),
+            AppType::Claude,
+            StagedKind::ClaudeSettings,
+        ),
+        (
+            crate::claude_mcp::user_config_path(),
+            AppType::Claude,
+            StagedKind::Json,
+        ),
+        (
+            claude_dir.join("CLAUDE.md"),
+            AppType::Claude,
+            StagedKind::Markdown,
+        ),
+        (
+            crate::codex_config::get_codex_config_path(),
+            AppType::Codex,
+            StagedKind::CodexConfig,
+        ),
+        (
+            crate::codex_config::get_codex_auth_path(),
+            AppType::Codex,
+            StagedKind::Json,
+        ),
+        (
+            codex_dir.join("AGENTS.md"),
+            AppType::Codex,
+            StagedKind::Markdown,
+        ),
+    ];
+    candidates
+        .into_iter()
+        .find(|(candidate, _, _)| candidate == path)
+        .map(|(_, app, kind)| (app, kind))
+}
+
+/// 解析校验，返回 error 级别的问题
+fn dry_parse(kind: StagedKind, app: &AppType, data: &[u8]) -> Result<(), String> {
+    let text = std::str::from_utf8(data).map_err(|_| "内容不是有效的 UTF-8".to_string())?;
+    match kind {
+        StagedKind::ClaudeSettings | StagedKind::CodexConfig => {
+            let result = crate::config_validation::validate_config_text(app, text);
+            if result.valid {
+                return Ok(());
+            }
+            let errors: Vec<String> = result
+                .diagnostics
+                .iter()
+                .filter(|d| d.severity == crate::provider_validation::ValidationSeverity::Error)
+                .map(|d| match d.line {
+                    Some(line) => format!("第 {} 行: {}", line, d.message),
+                    None => d.message.clone(),
+                })
+                .collect();
+            Err(errors.join("; "))
+        }
+        StagedKind::Json => serde_json::from_str::<serde_json::Value>(text)
+            .map(|_| ())
+            .map_err(|e| format!("JSON 语法错误: {}", e)),
+        StagedKind::Markdown => Ok(()),
+    }
+}
+
+fn cli_command(app: &AppType) -> (&'static str, &'static str) {
+    match app {
+        AppType::Codex => ("codex", "CODEX_HOME"),
+        _ => ("claude", "CLAUDE_CONFIG_DIR"),
+    }
+}
+
+fn build_command(program: &str) -> Command {
+    // Windows 上 npm 安装的 CLI 为 .cmd 脚本，需经 cmd 启动
+    #[cfg(windows)]
+    let mut cmd = {
+        use std::os::windows::process::CommandExt;
+        let mut cmd = Command::new("cmd");
+        cmd.args(["/C", program]);
+        // CREATE_NO_WINDOW：不弹出控制台窗口
+        cmd.creation_flags(0x0800_0000);
+        cmd
+    };
+    #[cfg(not(windows))]
+    let mut cmd = Command::new(program);
+    cmd.arg("--version")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped());
+    cmd
+}
+
+/// 运行 `<cli> --version`；home 非空时以其作为配置目录。CLI 不存在时返回 Ok(None)
+fn run_cli(
+    app: &AppType,
+    home: Option<&Path>,
+    timeout: Duration,
+) -> Result<Option<String>, String> {
+    let (program, home_var) = cli_command(app);
+    let mut cmd = build_command(program);
+    if let Some(home) = home {
+        cmd.env(home_var, home);
+    }
+    let mut child = match cmd.spawn() {
+        Ok(child) => child,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(None),
+        Err(e) => return Err(format!("启动 {} 失败: {}", program, e)),
+    };
+
+    let start = Instant::now();
+    loop {
+        match child.try_wait() {
+            Ok(Some(_)) => break,
+            Ok(None) if start.elapsed() >= timeout => {
+                let _ = child.kill();
+                let _ = child.wait();
+                return Err(format!(
+                    "{} --version 未在 {} 秒内退出",
+                    program,
+                    timeout.as_secs()
+                ));
+            }
+            Ok(None) => std::thread::sleep(Duration::from_millis(100)),
+            Err(e) => return Err(format!("等待 {} 退出失败: {}", program, e)),
+        }
+    }
+    let output = child
+        .wait_with_output()
+        .map_err(|e| format!("读取 {} 输出失败: {}", program, e))?;
+    let stdout = String::from_utf8_lossy(&output.stdout).trim().to_string();
+    if output.status.success() {
+        return Ok(Some(stdout));
+    }
+    // cmd /C 找不到命令时退出码非零，且无标准输出
+    #[cfg(windows)]
+    {
+        if stdout.is_empty() && output.status.code() == Some(1) {
+            return Ok(None);
+        }
+    }
+    let stderr = String::from_utf8_lossy(&output.stderr).trim().to_string();
+    Err(format!(
+        "{} --version 退出码 {}: {}",
+        program,
+        output.status.code().unwrap_or(-1),
+        if stderr.is_empty() { stdout } else { stderr }
+    ))
+}
+
+/// 创建暂存目录：放入待写入的文件，Codex 另复制同目录的 auth.json / config.toml。
+/// 暂存内容含 API Key，目录位于 ~/.cc-switch/staging 下且仅当前用户可访问（0700），离开作用域时删除
+fn stage(path: &Path, data: &[u8], app: &AppType) -> Result<TempDir, String> {
+    let root = crate::config::get_app_config_dir().join("staging");
+    fs::create_dir_all(&root).map_err(|e| format!("创建暂存目录失败: {}", e))?;
+    let staging = tempfile::Builder::new()
+        .prefix("apply-")
+        .tempdir_in(&root)
+        .map_err(|e| format!("创建暂存目录失败: {}", e))?;
+    let dir = staging.path();
+
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| "无效的文件名".to_string())?;
+    fs::write(dir.join(file_name), data).map_err(|e| format!("写入暂存文件失败: {}", e))?;
+
+    if matches!(app, AppType::Codex) {
+        for sibling in [
+            crate::codex_config::get_codex_config_path(),
+            crate::codex_config::get_codex_auth_path(),
+        ] {
+            if sibling != path && sibling.is_file() {
+                if let Some(name) = sibling.file_name() {
+                    let _ = fs::copy(&sibling, dir.join(name));
+                }
+            }
+        }
+    }
+    Ok(staging)
+}
+
+/// 在异步运行时的工作线程上调用时（切换供应商等异步命令），将等待 CLI 的阻塞操作移交给运行时，
+/// 避免占住工作线程
+fn run_blocking<T>(f: impl FnOnce() -> T) -> T {
+    match tokio::runtime::Handle::try_current() {
+        Ok(handle) if handle.runtime_flavor() == tokio::runtime::RuntimeFlavor::MultiThread => {
+            tokio::task::block_in_place(f)
+        }
+        _ => f(),
+    }
+}
+
+/// 写入受保护的 CLI 配置前调用：开启暂存验证时先解析校验，再以暂存目录为配置目录运行
+/// `claude --version` / `codex --version`，失败时拒绝写入。未开启或不受保护的文件直接通过
+pub fn verify(path: &Path, data: &[u8]) -> Result<(), String> {
+    let Some(settings) = settings() else {
+        return Ok(());
+    };
+    let Some((app, kind)) = classify(path) else {
+        return Ok(());
+    };
+
+    dry_parse(kind, &app, data)
+        .map_err(|e| format!("暂存验证失败，未写入 {}: {}", path.display(), e))?;
+    if settings.parse_only {
+        return Ok(());
+    }
+
+    let timeout =
+        Duration::from_secs(settings.timeout_secs.unwrap_or(DEFAULT_TIMEOUT_SECS).max(1));
+    let staging = stage(path, data, &app)?;
+    let result = run_blocking(|| run_cli(&app, Some(staging.path()), timeout));
+    drop(staging);
+    match result {
+        Ok(Some(_)) => Ok(()),
+        Ok(None) => {
+            log::info!("未找到 {} CLI，仅做解析校验", cli_command(&app).0);
+            Ok(())
+        }
+        Err(e) => Err(format!(
+            "暂存验证失败，未写入 {}: {}",
+            path.display(),
+            e
+        )),
+    }
+}
+
+/// 探测 claude / codex CLI 是否可用（使用当前配置目录）
+pub fn probe_clis() -> Vec<CliProbe> {
+    let timeout = Duration::from_secs(
+        settings()
+            .and_then(|s| s.timeout_secs)
+            .unwrap_or(DEFAULT_TIMEOUT_SECS)
+            .max(1),
+    );
+    [AppType::Claude, AppType::Codex]
+        .iter()
+        .map(|app| {
+            let (found, version, error) = match run_cli(app, None, timeout) {
+                Ok(Some(version)) => (true, Some(version), None),
+                Ok(None) => (false, None, None),
+                Err(e) => (true, None, Some(e)),
+            };
+            CliProbe {
+                app_type: app.as_str().to_string(),
+                found,
+                version,
+                error,
+            }
+        })
+        .collect()
+}
@@ -0,0 +1,226 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::path::PathBuf;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::claude_settings::ClaudeSettingsSection;
+
+/// 默认显示格式
+pub const DEFAULT_FORMAT: &str = "⚡ {provider} | {model} | ${cost}";
+
+/// 状态栏设置
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatuslineSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 显示格式，支持 {provider}、{model}、{cost}、{dir} 占位符
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub format: Option<String>,
+}
+
+impl StatuslineSettings {
+    pub fn format(&self) -> &str {
+        self.format
+            .as_deref()
+            .filter(|f| !f.trim().is_empty())
+            .unwrap_or(DEFAULT_FORMAT)
+    }
+}
+
+/// 状态栏安装状态
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct StatuslineStatus {
+    pub enabled: bool,
+    pub format: String,
+    pub script_path: String,
+    pub script_exists: bool,
+    /// ~/.claude/settings.json 当前是否指向 cc-switch 状态栏
+    pub configured: bool,
+}
+
+fn settings() -> StatuslineSettings {
+    crate::settings::get_settings()
+        .statusline
+        .unwrap_or_default()
+}
+
+/// 状态栏脚本路径（~/.cc-switch/statusline.sh，Windows 为 statusline.cmd）
+pub fn script_path() -> PathBuf {
+    let name = if cfg!(windows) {
+        "statusline.cmd"
+    } else {
+        "statusline.sh"
+    };
+    crate::config::get_app_config_dir().join(name)
+}
+
+/// 写入 settings.json 的 statusLine.command
+fn script_command() -> String {
+    let path = script_path().to_string_lossy().to_string();
+    if cfg!(windows) {
+        format!("\"{}\"", path)
+    } else {
+        crate::project_profiles::quote(crate::project_profiles::ShellKind::Posix, &path)
+    }
+}
+
+fn status_line_value() -> Value {
+    json!({ "type": "command", "command": script_command(), "padding": 0 })
+}
+
+fn is_ours(status_line: &Value) -> bool {
+    status_line.get("command").and_then(|c| c.as_str()) == Some(script_command().as_str())
+}
+
+/// 生成脚本：调用 `cc-switch statusline`，由其读取 Claude Code 传入的会话 JSON
+fn render_script(exe: &str) -> String {
+    if cfg!(windows) {
+        format!("@echo off\r\n\"{}\" statusline\r\n", exe.replace('%', "%%"))
+    } else {
+        format!(
+            "#!/bin/sh\n# 由 CC Switch 生成，请勿手动修改\nexec {} statusline\n",
+            crate::project_profiles::quote(crate::project_profiles::ShellKind::Posix, exe)
+        )
+    }
+}
+
+fn write_script() -> Result<(), String> {
+    let path = script_path();
+    let exe = crate::project_profiles::current_exe()?;
+    crate::config::write_text_file(&path, &render_script(&exe))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))
+            .map_err(|e| format!("设置脚本权限失败: {}: {}", path.display(), e))?;
+    }
+    Ok(())
+}
+
+/// 状态栏是否已启用且脚本存在
+fn active() -> bool {
+    settings().enabled && script_path().exists()
+}
+
+/// 写入 live 前注入状态栏（仅 Claude；供应商自带 statusLine 时保持不变）
+pub fn apply_to_settings(app_type: &AppType, settings: &mut Value) {
+    if !matches!(app_type, AppType::Claude) || !active() {
+        return;
+    }
+    if let Some(obj) = settings.as_object_mut() {
+        obj.entry("statusLine").or_insert_with(status_line_value);
+    }
+}
+
+/// 回填到供应商前移除由 cc-switch 注入的状态栏
+pub fn strip_from_settings(app_type: &AppType, settings: &mut Value) {
+    if !matches!(app_type, AppType::Claude) {
+        return;
+    }
+    let Some(obj) = settings.as_object_mut() else {
+        return;
+    };
+    if obj.get("statusLine").is_some_and(is_ours) {
+        obj.remove("statusLine");
+    }
+}
+
+fn configured() -> bool {
+    crate::claude_settings::get_section(ClaudeSettingsSection::StatusLine)
+        .map(|v| is_ours(&v))
+        .unwrap_or(false)
+}
+
+/// 查询安装状态
+pub fn status() -> StatuslineStatus {
+    let settings = settings();
+    let path = script_path();
+    StatuslineStatus {
+        enabled: settings.enabled,
+        format: settings.format().to_string(),
+        script_path: path.to_string_lossy().to_string(),
+        script_exists: path.exists(),
+        configured: configured(),
+    }
+}
+
+/// 安装状态栏：生成脚本、保存设置，并写入 ~/.claude/settings.json 的 statusLine
+pub fn install(format: Option<String>) -> Result<StatuslineStatus, String> {
+    write_script()?;
+    let mut app_settings = crate::settings::get_settings();
+    let mut statusline = app_settings.statusline.take().unwrap_or_default();
+    statusline.enabled = true;
+    if let Some(format) = format {
+        statusline.format = Some(format).filter(|f| !f.trim().is_empty());
+    }
+    app_settings.statusline = Some(statusline);
+    crate::settings::update_settings(app_settings)?;
+    crate::claude_settings::set_section(ClaudeSettingsSection::StatusLine, status_line_value())?;
+    Ok(status())
+}
+
+/// 卸载状态栏：移除 settings.json 中的 statusLine（仅当指向 cc-switch 时）并删除脚本
+pub fn uninstall() -> Result<StatuslineStatus, String> {
+    if configured() {
+        crate::claude_settings::set_section(ClaudeSettingsSection::StatusLine, Value::Null)?;
+    }
+    let path = script_path();
+    if path.exists() {
+        crate::config::delete_file(&path)?;
+    }
+    let mut app_settings = crate::settings::get_settings();
+    if let Some(statusline) = app_settings.statusline.as_mut() {
+        statusline.enabled = false;
+    }
+    crate::settings::update_settings(app_settings)?;
+    Ok(status())
+}
+
+fn format_cost(input: &Value) -> String {
+    input
+        .pointer("/cost/total_cost_usd")
+        .and_then(|v| v.as_f64())
+        .map(|cost| format!("{:.2}", cost))
+        .unwrap_or_else(|| "0.00".to_string())
+}
+
+/// 根据 Claude Code 传入的会话 JSON 生成状态栏文本
+pub fn render(input: &Value, config: &MultiAppConfig, format: &str) -> String {
+    let provider = config
+        .get_manager(&AppType::Claude)
+        .and_then(|m| m.providers.get(&m.current))
+        .map(|p| p.name.clone())
+        .unwrap_or_else(|| "-".to_string());
+    let model = input
+        .pointer("/model/display_name")
+        .or_else(|| input.pointer("/model/id"))
+        .and_then(|v| v.as_str())
+        .unwrap_or("-");
+    let dir = input
+        .pointer("/workspace/current_dir")
+        .or_else(|| input.get("cwd"))
+        .and_then(|v| v.as_str())
+        .and_then(|d| std::path::Path::new(d).file_name())
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format
+        .replace("{provider}", &provider)
+        .replace("{model}", model)
+        .replace("{cost}", &format_cost(input))
+        .replace("{dir}", &dir)
+}
+
+/// `cc-switch statusline` 入口：从标准输入读取会话 JSON，输出一行状态栏文本
+pub fn render_from_stdin() -> Result<String, String> {
+    use std::io::Read;
+
+    let mut raw = String::new();
+    std::io::stdin()
+        .read_to_string(&mut raw)
+        .map_err(|e| format!("读取标准输入失败: {}", e))?;
+    let input: Value = serde_json::from_str(raw.trim()).unwrap_or(Value::Null);
+    let config = MultiAppConfig::load()?;
+    Ok(render(&input, &config, settings().format()))
+}
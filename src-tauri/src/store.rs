@@ -21,11 +21,11 @@ impl AppState {
 
     /// 保存配置到文件
     pub fn save(&self) -> Result<(), String> {
-        let config = self
-            .config
-            .lock()
-            .map_err(|e| format!("获取锁失败: {}", e))?;
-
-        config.save()
+        {
+            let config = self.config.lock().map_err(crate::error::lock_failed)?;
+            config.save()?;
+        }
+        crate::config_history::auto_commit();
+        Ok(())
     }
 }
@@ -0,0 +1,104 @@
+use serde::{Deserialize, Serialize};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use crate::config::get_app_config_dir;
+
+/// 序列化追加 switch_history.jsonl
+static LOG_LOCK: Mutex<()> = Mutex::new(());
+
+/// 切换历史文件 ~/.cc-switch/switch_history.jsonl（每行一条记录，只追加）
+fn get_history_path() -> PathBuf {
+    get_app_config_dir().join("switch_history.jsonl")
+}
+
+/// 切换的发起方
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub enum SwitchInitiator {
+    /// 主界面手动切换
+    #[default]
+    Manual,
+    /// 托盘菜单
+    Tray,
+    /// 自动故障转移
+    Failover,
+    /// 定时切换规则
+    Schedule,
+    /// 工作区自动应用
+    Workspace,
+    /// 事件规则
+    Rule,
+    /// 切回上一个供应商
+    SwitchBack,
+    /// 全局快捷键
+    Hotkey,
+    /// 本地自动化接口
+    Api,
+}
+
+/// 单条切换记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchRecord {
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    pub app_type: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from_name: Option<String>,
+    pub to_id: String,
+    pub to_name: String,
+    pub initiator: SwitchInitiator,
+}
+
+/// 追加一条切换记录；写入失败只记录日志，不影响切换本身
+pub fn record(record: &SwitchRecord) {
+    let line = match serde_json::to_string(record) {
+        Ok(line) => line,
+        Err(e) => {
+            log::warn!("序列化切换记录失败: {}", e);
+            return;
+        }
+    };
+    let _guard = LOG_LOCK.lock();
+    let path = get_history_path();
+    if let Some(parent) = path.parent() {
+        let _ = fs::create_dir_all(parent);
+    }
+    let result = OpenOptions::new()
+        .create(true)
+        .append(true)
+        .open(&path)
+        .and_then(|mut file| writeln!(file, "{}", line));
+    if let Err(e) = result {
+        log::warn!("写入切换历史失败: {}: {}", path.display(), e);
+    }
+}
+
+/// 读取切换历史（最新在前）；app_type 为空时返回全部应用，limit 为空时不限制条数
+pub fn get_history(app_type: Option<&str>, limit: Option<usize>) -> Vec<SwitchRecord> {
+    let content = {
+        let _guard = LOG_LOCK.lock();
+        fs::read_to_string(get_history_path()).unwrap_or_default()
+    };
+    content
+        .lines()
+        .rev()
+        .filter(|line| !line.trim().is_empty())
+        .filter_map(|line| serde_json::from_str::<SwitchRecord>(line).ok())
+        .filter(|r| app_type.is_none_or(|app| r.app_type == app))
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// 最近一次切换前使用的供应商 id（用于“切回上一个”）
+pub fn previous_provider(app_type: &str) -> Option<String> {
+    get_history(Some(app_type), Some(1))
+        .into_iter()
+        .next()
+        .and_then(|r| r.from_id)
+}
@@ -0,0 +1,254 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use std::path::Path;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::codex_config::{get_codex_auth_path, get_codex_config_path};
+use crate::config::{get_claude_settings_path, read_json_file};
+
+/// 单个键的变更
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct KeyChange {
+    /// 以 `.` 连接的键路径，例如 `env.ANTHROPIC_BASE_URL`
+    pub key: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub before: Option<Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after: Option<Value>,
+}
+
+/// 单个配置文件的差异
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FileDiff {
+    pub path: String,
+    /// 切换前文件是否存在
+    pub exists: bool,
+    pub added: Vec<KeyChange>,
+    pub removed: Vec<KeyChange>,
+    pub changed: Vec<KeyChange>,
+}
+
+/// 切换预览结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchPreview {
+    pub app_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_provider_id: Option<String>,
+    pub to_provider_id: String,
+    pub to_provider_name: String,
+    pub files: Vec<FileDiff>,
+}
+
+/// 敏感键（密钥、令牌等）的值只显示首尾几位
+fn is_sensitive_key(key: &str) -> bool {
+    let last = key.rsplit('.').next().unwrap_or(key).to_ascii_lowercase();
+    ["key", "token", "secret", "password"]
+        .iter()
+        .any(|word| last.contains(word))
+}
+
+fn mask_value(key: &str, value: Value) -> Value {
+    match value {
+        Value::String(s) if is_sensitive_key(key) => {
+            let chars: Vec<char> = s.chars().collect();
+            if chars.len() <= 8 {
+                Value::String("****".to_string())
+            } else {
+                let head: String = chars[..4].iter().collect();
+                let tail: String = chars[chars.len() - 4..].iter().collect();
+                Value::String(format!("{}…{}", head, tail))
+            }
+        }
+        other => other,
+    }
+}
+
+/// 将对象展平为 `键路径 -> 值`；数组与标量作为叶子整体比较
+fn flatten(prefix: &str, value: &Value, out: &mut Vec<(String, Value)>) {
+    match value {
+        Value::Object(map) if !map.is_empty() => {
+            for (k, v) in map {
+                let key = if prefix.is_empty() {
+                    k.clone()
+                } else {
+                    format!("{}.{}", prefix, k)
+                };
+                flatten(&key, v, out);
+            }
+        }
+        _ if !prefix.is_empty() => out.push((prefix.to_string(), value.clone())),
+        _ => {}
+    }
+}
+
+/// 计算两个 JSON 值之间的键级差异
+fn diff_values(path: &Path, exists: bool, before: &Value, after: &Value) -> FileDiff {
+    let mut before_flat = Vec::new();
+    let mut after_flat = Vec::new();
+    flatten("", before, &mut before_flat);
+    flatten("", after, &mut after_flat);
+
+    let mut diff = FileDiff {
+        path: path.to_string_lossy().to_string(),
+        exists,
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+    };
+    for (key, old) in &before_flat {
+        match after_flat.iter().find(|(k, _)| k == key) {
+            None => diff.removed.push(KeyChange {
+                key: key.clone(),
+                before: Some(mask_value(key, old.clone())),
+                after: None,
+            }),
+            Some((_, new)) if new != old => diff.changed.push(KeyChange {
+                key: key.clone(),
+                before: Some(mask_value(key, old.clone())),
+                after: Some(mask_value(key, new.clone())),
+            }),
+            Some(_) => {}
+        }
+    }
+    for (key, new) in &after_flat {
+        if !before_flat.iter().any(|(k, _)| k == key) {
+            diff.added.push(KeyChange {
+                key: key.clone(),
+                before: None,
+                after: Some(mask_value(key, new.clone())),
+            });
+        }
+    }
+    diff
+}
+
+fn read_live_json(path: &Path) -> Result<Value, String> {
+    if path.exists() {
+        read_json_file(path).map_err(Into::into)
+    } else {
+        Ok(Value::Object(Map::new()))
+    }
+}
+
+fn toml_text_to_json(text: &str, label: &str) -> Result<Value, String> {
+    if text.trim().is_empty() {
+        return Ok(Value::Object(Map::new()));
+    }
+    let table: toml::Table =
+        toml::from_str(text).map_err(|e| format!("解析 {} 失败: {}", label, e))?;
+    serde_json::to_value(table).map_err(|e| format!("转换 {} 失败: {}", label, e))
+}
+
+/// 预览切换到指定供应商时 live 配置文件的变化（不写入任何文件）
+pub fn preview_switch(
+    config: &MultiAppConfig,
+    app_type: &AppType,
+    provider_id: &str,
+) -> Result<SwitchPreview, String> {
+    let manager = config
+        .get_manager(app_type)
+        .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+    let provider = manager
+        .providers
+        .get(provider_id)
+        .ok_or_else(|| crate::error::provider_not_found(&provider_id))?;
+    // 与实际切换写入相同的内容（附加设置、代理、状态栏）；未解锁时无法解析加密的 API Key，
+    // 按引用原样比较（敏感值本就会被遮蔽）
+    let target = match crate::proxy::live_settings_for(app_type, provider) {
+        Ok(settings) => settings,
+        Err(_) => {
+            crate::proxy::layer_live_settings(app_type, provider, provider.settings_config.clone())?
+        }
+    };
+
+    let files = match app_type {
+        AppType::Claude => {
+            let path = get_claude_settings_path();
+            let live = read_live_json(&path)?;
+            vec![diff_values(&path, path.exists(), &live, &target)]
+        }
+        AppType::Codex => {
+            let auth_path = get_codex_auth_path();
+            let live_auth = read_live_json(&auth_path)?;
+            let target_auth = target
+                .get("auth")
+                .cloned()
+                .ok_or_else(|| "目标供应商缺少 auth 配置".to_string())?;
+
+            let config_path = get_codex_config_path();
+            let live_text = if config_path.exists() {
+                std::fs::read_to_string(&config_path).map_err(|e| {
+                    format!("读取 config.toml 失败: {}: {}", config_path.display(), e)
+                })?
+            } else {
+                String::new()
+            };
+            let target_text = target
+                .get("config")
+                .and_then(|v| v.as_str())
+                .unwrap_or_default();
+            let mut live_config = toml_text_to_json(&live_text, "~/.codex/config.toml")?;
+            let mut target_config = toml_text_to_json(target_text, "目标供应商 config")?;
+            // MCP 服务器在切换后会按 SSOT 重新投影，不属于供应商差异
+            for value in [&mut live_config, &mut target_config] {
+                if let Some(obj) = value.as_object_mut() {
+                    obj.remove("mcp_servers");
+                }
+            }
+
+            vec![
+                diff_values(&auth_path, auth_path.exists(), &live_auth, &target_auth),
+                diff_values(
+                    &config_path,
+                    config_path.exists(),
+                    &live_config,
+                    &target_config,
+                ),
+            ]
+        }
+        AppType::Gemini => {
+            // mcpServers 在切换时保留，read_gemini_live 已将其排除
+            let live = crate::gemini_config::read_gemini_live()?;
+            let empty = Value::Object(Map::new());
+            let env_path = crate::gemini_config::get_gemini_env_path();
+            let settings_path = crate::gemini_config::get_gemini_settings_path();
+            let mut files = vec![diff_values(
+                &env_path,
+                env_path.exists(),
+                live.get("env").unwrap_or(&empty),
+                target.get("env").unwrap_or(&empty),
+            )];
+            // 目标供应商未提供 config 时不改动 settings.json
+            if let Some(target_config) = target.get("config").filter(|v| !v.is_null()) {
+                files.push(diff_values(
+                    &settings_path,
+                    settings_path.exists(),
+                    live.get("config").unwrap_or(&empty),
+                    target_config,
+                ));
+            }
+            files
+        }
+        AppType::OpenCode | AppType::Crush => {
+            // 仅比较适配器管理的字段（供应商、模型），mcp 等其余字段切换时保持不变
+            let adapter = crate::app_adapter::adapter_for(app_type);
+            let live = adapter.read_live()?;
+            adapter
+                .live_paths()
+                .iter()
+                .map(|path| diff_values(path, path.exists(), &live, &target))
+                .collect()
+        }
+    };
+
+    Ok(SwitchPreview {
+        app_type: app_type.as_str().to_string(),
+        from_provider_id: (!manager.current.is_empty()).then(|| manager.current.clone()),
+        to_provider_id: provider.id.clone(),
+        to_provider_name: provider.name.clone(),
+        files,
+    })
+}
@@ -0,0 +1,182 @@
+use chrono::{DateTime, Datelike, Duration as ChronoDuration, Local, NaiveTime};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::events::AppEvent;
+use crate::notification_sinks::{NotificationEvent, NotificationPayload};
+use crate::notifications::{NotificationCategory, NotificationRecord};
+use crate::store::AppState;
+
+/// 按时间表自动切换完成
+pub const EVENT_PROVIDER_SCHEDULED_SWITCH: &str = "provider-scheduled-switch";
+
+/// 调度器检查间隔
+const SCHEDULER_TICK: Duration = Duration::from_secs(60);
+
+fn default_true() -> bool {
+    true
+}
+
+/// 定时切换规则：在指定星期的时间段内使用某个供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SwitchSchedule {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    /// "claude" 或 "codex"
+    pub app_type: String,
+    pub provider_id: String,
+    /// 生效的星期（1 = 周一 … 7 = 周日），为空表示每天
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub days: Vec<u32>,
+    /// 开始时间（本地时间 HH:MM）
+    pub start: String,
+    /// 结束时间（不含）；早于开始时间表示跨越午夜，与开始时间相同表示全天
+    pub end: String,
+}
+
+impl SwitchSchedule {
+    fn runs_on(&self, date: &DateTime<Local>) -> bool {
+        self.days.is_empty() || self.days.contains(&date.weekday().number_from_monday())
+    }
+
+    /// 判断某一时刻是否处于规则时间段内；时间格式无效时视为不匹配
+    pub fn matches(&self, now: &DateTime<Local>) -> bool {
+        let (Ok(start), Ok(end)) = (
+            NaiveTime::parse_from_str(self.start.trim(), "%H:%M"),
+            NaiveTime::parse_from_str(self.end.trim(), "%H:%M"),
+        ) else {
+            return false;
+        };
+        let time = now.time();
+        if start == end {
+            self.runs_on(now)
+        } else if start < end {
+            self.runs_on(now) && time >= start && time < end
+        } else if time >= start {
+            self.runs_on(now)
+        } else {
+            // 跨午夜时段的后半段属于前一天的规则
+            time < end && self.runs_on(&(*now - ChronoDuration::days(1)))
+        }
+    }
+}
+
+/// 当前时刻命中的规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduleMatch {
+    pub app_type: String,
+    pub schedule_id: String,
+    pub provider_id: String,
+}
+
+/// 计算各应用当前应使用的供应商：按列表顺序取第一条命中的已启用规则
+pub fn active_schedules(schedules: &[SwitchSchedule], now: &DateTime<Local>) -> Vec<ScheduleMatch> {
+    let mut matches: Vec<ScheduleMatch> = Vec::new();
+    for schedule in schedules.iter().filter(|s| s.enabled) {
+        let app_type = AppType::from(schedule.app_type.as_str());
+        if matches.iter().any(|m| m.app_type == app_type.as_str()) || !schedule.matches(now) {
+            continue;
+        }
+        matches.push(ScheduleMatch {
+            app_type: app_type.as_str().to_string(),
+            schedule_id: schedule.id.clone(),
+            provider_id: schedule.provider_id.clone(),
+        });
+    }
+    matches
+}
+
+fn notify_scheduled_switch(schedule: &ScheduleMatch, name: &str) {
+    let title = crate::i18n::t("notification.scheduledSwitch.title");
+    let message = crate::i18n::tf(
+        "notification.scheduledSwitch.message",
+        &[("app", &schedule.app_type), ("to", &name)],
+    );
+    crate::notifications::notify(
+        NotificationRecord::new(NotificationCategory::ScheduledSwitch, &title, message.clone())
+            .with_provider(&schedule.app_type, &schedule.provider_id),
+    );
+    crate::notification_sinks::dispatch(
+        NotificationPayload::new(NotificationEvent::Switch, title, message).with_provider(
+            &schedule.app_type,
+            &schedule.provider_id,
+            name,
+        ),
+    );
+}
+
+/// 切换到规则指定的供应商（已是当前供应商则跳过），返回是否发生切换
+async fn apply_schedule(app: &tauri::AppHandle, schedule: &ScheduleMatch) -> Result<bool, String> {
+    let app_type = AppType::from(schedule.app_type.as_str());
+    let name = {
+        let state = app.state::<AppState>();
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let manager = config
+            .get_manager(&app_type)
+            .ok_or_else(|| crate::error::app_type_not_found(&app_type))?;
+        if manager.current == schedule.provider_id {
+            return Ok(false);
+        }
+        manager
+            .providers
+            .get(&schedule.provider_id)
+            .map(|p| p.name.clone())
+            .ok_or_else(|| crate::error::provider_not_found(&schedule.provider_id))?
+    };
+
+    crate::switch_provider_internal(
+        app,
+        app_type,
+        schedule.provider_id.clone(),
+        crate::switch_history::SwitchInitiator::Schedule,
+    )
+    .await?;
+    log::info!(
+        "定时切换: {} 切换到 {}（规则 {}）",
+        schedule.app_type,
+        schedule.provider_id,
+        schedule.schedule_id
+    );
+    notify_scheduled_switch(schedule, &name);
+    crate::events::publish(
+        AppEvent::new(EVENT_PROVIDER_SCHEDULED_SWITCH)
+            .with_provider(&schedule.app_type, &schedule.provider_id)
+            .with_data(serde_json::json!({ "scheduleId": schedule.schedule_id })),
+    );
+    Ok(true)
+}
+
+/// 启动定时切换调度器：仅在命中的规则发生变化时切换，时间段内的手动切换不会被反复覆盖
+pub fn start_switch_scheduler(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        // 应用 → 上一次命中的规则 id
+        let mut applied: HashMap<String, String> = HashMap::new();
+        loop {
+            tokio::time::sleep(SCHEDULER_TICK).await;
+
+            let schedules = crate::settings::get_settings().switch_schedules;
+            let active = active_schedules(&schedules, &Local::now());
+            applied.retain(|app_type, _| active.iter().any(|m| &m.app_type == app_type));
+
+            for schedule in active {
+                if applied.get(&schedule.app_type) == Some(&schedule.schedule_id) {
+                    continue;
+                }
+                match apply_schedule(&app, &schedule).await {
+                    Ok(_) => {
+                        applied.insert(schedule.app_type.clone(), schedule.schedule_id.clone());
+                    }
+                    Err(e) => log::warn!("定时切换规则 {} 执行失败: {}", schedule.schedule_id, e),
+                }
+            }
+        }
+    });
+}
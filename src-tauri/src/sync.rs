@@ -0,0 +1,888 @@
+use base64::engine::general_purpose::STANDARD as BASE64;
+use base64::Engine;
+use chacha20poly1305::aead::rand_core::RngCore;
+use chacha20poly1305::aead::OsRng;
+use hmac::{Hmac, Mac};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::time::Duration;
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::store::AppState;
+
+/// 远端同步文件名
+const SYNC_FILE_NAME: &str = "cc-switch-sync.json";
+const SYNC_FORMAT: &str = "cc-switch-sync";
+const SYNC_VERSION: u32 = 1;
+const SYNC_TIMEOUT_SECS: u64 = 30;
+const GITHUB_API: &str = "https://api.github.com";
+
+/// 仅属于本机的设置项（路径、凭据、本地服务），不参与同步
+const LOCAL_ONLY_SETTINGS: &[&str] = &[
+    "claudeConfigDir",
+    "codexConfigDir",
+    "geminiConfigDir",
+    "claudeConversationDirs",
+    "codexConversationDirs",
+    "geminiConversationDirs",
+    "appHomes",
+    "activeClaudeHome",
+    "activeCodexHome",
+    "envProfiles",
+    "activeEnvProfile",
+    "wsl",
+    "remoteProfiles",
+    "setupReportDir",
+    "workspaces",
+    "proxy",
+    "localApi",
+    "statusline",
+    "sync",
+];
+
+/// 同步后端
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum SyncBackend {
+    /// WebDAV 目录，同步文件保存为 <url>/cc-switch-sync.json
+    #[serde(rename_all = "camelCase")]
+    WebDav {
+        url: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        username: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        password: Option<String>,
+    },
+    /// S3 兼容对象存储（AWS S3、R2、MinIO 等）
+    #[serde(rename_all = "camelCase")]
+    S3 {
+        /// 为空时使用 https://s3.<region>.amazonaws.com
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        endpoint: Option<String>,
+        region: String,
+        bucket: String,
+        access_key_id: String,
+        secret_access_key: String,
+        /// 对象键前缀，例如 backups/
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        prefix: Option<String>,
+        /// 使用路径风格地址（MinIO 等自建服务通常需要开启）
+        #[serde(default)]
+        path_style: bool,
+    },
+    /// GitHub 私有 Gist；gist_id 为空时首次推送自动创建
+    #[serde(rename_all = "camelCase")]
+    Gist {
+        token: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        gist_id: Option<String>,
+    },
+}
+
+impl SyncBackend {
+    fn kind(&self) -> &'static str {
+        match self {
+            SyncBackend::WebDav { .. } => "webDav",
+            SyncBackend::S3 { .. } => "s3",
+            SyncBackend::Gist { .. } => "gist",
+        }
+    }
+}
+
+/// 同步设置（仅保存在本机）
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub backend: Option<SyncBackend>,
+    /// 本机标识，写入远端以显示最后推送的设备
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    /// 上次同步时的远端修订号
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_revision: Option<String>,
+    /// 上次同步时本地数据的摘要，用于判断本地是否有未同步的修改
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_local_hash: Option<String>,
+    /// 上次同步时间（毫秒）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_synced_at: Option<i64>,
+}
+
+/// 同步方向
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncDirection {
+    /// 按两端变化自动推送或拉取，两端都有修改时报告冲突
+    #[default]
+    Auto,
+    Push,
+    Pull,
+}
+
+/// 同步结果类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SyncAction {
+    Pushed,
+    Pulled,
+    UpToDate,
+    /// 两端都有修改（或强制方向会覆盖对方的修改），未做任何改动
+    Conflict,
+}
+
+/// 同步结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncOutcome {
+    pub action: SyncAction,
+    /// 本地自上次同步后有修改
+    pub local_changed: bool,
+    /// 远端自上次同步后被其他设备更新
+    pub remote_changed: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub revision: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_device: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remote_updated_at: Option<i64>,
+}
+
+/// 同步状态（不含凭据）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct SyncStatus {
+    pub configured: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub backend_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_revision: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_synced_at: Option<i64>,
+}
+
+/// 远端文件：元数据明文保存，数据部分使用同步口令端到端加密
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncEnvelope {
+    format: String,
+    version: u32,
+    revision: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    parent_revision: Option<String>,
+    device_id: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    device_name: Option<String>,
+    updated_at: i64,
+    /// Argon2 盐（base64）
+    salt: String,
+    /// XChaCha20-Poly1305 密文（nonce:ciphertext）
+    data: String,
+}
+
+/// Codex 规则文件
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncedCodexRule {
+    name: String,
+    #[serde(default)]
+    tags: Vec<String>,
+    content: String,
+}
+
+/// 同步的数据：供应商与 MCP（config.json）、规则文件、可共享的应用设置
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct SyncSnapshot {
+    config: MultiAppConfig,
+    /// 应用 → 全局规则文件内容（CLAUDE.md、GEMINI.md 等）
+    #[serde(default)]
+    app_rules: BTreeMap<String, String>,
+    #[serde(default)]
+    codex_rules: Vec<SyncedCodexRule>,
+    /// 可共享的设置项（已去除 LOCAL_ONLY_SETTINGS）
+    #[serde(default)]
+    settings: Value,
+}
+
+fn sync_settings() -> SyncSettings {
+    crate::settings::get_settings().sync.unwrap_or_default()
+}
+
+fn save_sync_settings(sync: SyncSettings) -> Result<(), String> {
+    let mut settings = crate::settings::get_settings();
+    settings.sync = Some(sync);
+    crate::settings::update_settings(settings)
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn random_hex(len: usize) -> String {
+    let mut bytes = vec![0u8; len];
+    OsRng.fill_bytes(&mut bytes);
+    hex(&bytes)
+}
+
+fn device_name() -> Option<String> {
+    ["COMPUTERNAME", "HOSTNAME"]
+        .iter()
+        .find_map(|k| std::env::var(k).ok())
+        .filter(|s| !s.trim().is_empty())
+}
+
+/// 查询同步状态
+pub fn status() -> SyncStatus {
+    let sync = sync_settings();
+    SyncStatus {
+        configured: sync.backend.is_some(),
+        backend_type: sync.backend.as_ref().map(|b| b.kind().to_string()),
+        device_id: sync.device_id,
+        last_revision: sync.last_revision,
+        last_synced_at: sync.last_synced_at,
+    }
+}
+
+/// 设置同步后端；更换后端时清空同步记录，下次同步将重新比较两端
+pub fn set_backend(backend: Option<SyncBackend>) -> Result<SyncStatus, String> {
+    let mut sync = sync_settings();
+    if sync.backend != backend {
+        sync.last_revision = None;
+        sync.last_local_hash = None;
+        sync.last_synced_at = None;
+    }
+    sync.backend = backend;
+    save_sync_settings(sync)?;
+    Ok(status())
+}
+
+// ==================== 快照 ====================
+
+fn shareable_settings() -> Result<Value, String> {
+    strip_local_settings(&crate::settings::get_settings())
+}
+
+fn strip_local_settings(settings: &crate::settings::AppSettings) -> Result<Value, String> {
+    let mut value = serde_json::to_value(settings).map_err(|e| format!("序列化设置失败: {}", e))?;
+    if let Some(obj) = value.as_object_mut() {
+        for key in LOCAL_ONLY_SETTINGS {
+            obj.remove(*key);
+        }
+    }
+    Ok(value)
+}
+
+fn build_snapshot(state: &AppState) -> Result<SyncSnapshot, String> {
+    let config = {
+        let config = state.config.lock().map_err(crate::error::lock_failed)?;
+        // 密钥库中的 API Key 解析为明文后再加密上传，其他设备无需共享密钥库
+        crate::secrets::export_with_secrets(&config)?
+    };
+    let mut app_rules = BTreeMap::new();
+    for app_type in AppType::all() {
+        if crate::app_adapter::adapter_for(&app_type)
+            .rules_path()
+            .is_none()
+        {
+            continue;
+        }
+        let content = crate::global_rules::read_app_rules(&app_type)?;
+        if !content.is_empty() {
+            app_rules.insert(app_type.as_str().to_string(), content);
+        }
+    }
+    let codex_rules = crate::global_rules::list_codex_rules()?
+        .into_iter()
+        .map(|rule| SyncedCodexRule {
+            name: rule.name,
+            tags: rule.tags,
+            content: rule.content,
+        })
+        .collect();
+    Ok(SyncSnapshot {
+        config,
+        app_rules,
+        codex_rules,
+        settings: shareable_settings()?,
+    })
+}
+
+/// 快照摘要；先转为 Value 以保证键顺序稳定
+fn snapshot_hash(snapshot: &SyncSnapshot) -> Result<String, String> {
+    let value = serde_json::to_value(snapshot).map_err(|e| format!("序列化同步数据失败: {}", e))?;
+    let bytes = serde_json::to_vec(&value).map_err(|e| format!("序列化同步数据失败: {}", e))?;
+    Ok(hex(&Sha256::digest(&bytes)))
+}
+
+fn seal(snapshot: &SyncSnapshot, passphrase: &str) -> Result<(String, String), String> {
+    let mut salt = [0u8; 16];
+    OsRng.fill_bytes(&mut salt);
+    let key = crate::secrets::derive_key(passphrase, &salt)?;
+    let plaintext =
+        serde_json::to_vec(snapshot).map_err(|e| format!("序列化同步数据失败: {}", e))?;
+    Ok((
+        BASE64.encode(salt),
+        crate::secrets::encrypt(&key, &plaintext)?,
+    ))
+}
+
+fn open(envelope: &SyncEnvelope, passphrase: &str) -> Result<SyncSnapshot, String> {
+    let salt = BASE64
+        .decode(&envelope.salt)
+        .map_err(|e| format!("同步文件格式错误: {}", e))?;
+    let key = crate::secrets::derive_key(passphrase, &salt)?;
+    let plaintext = crate::secrets::decrypt(&key, &envelope.data)
+        .map_err(|_| "解密失败：同步口令错误或数据已损坏".to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| format!("解析同步数据失败: {}", e))
+}
+
+/// 将拉取的快照应用到本机：供应商保留本机当前选中项，规则与设置逐项覆盖
+fn apply_snapshot(state: &AppState, snapshot: SyncSnapshot) -> Result<(), String> {
+    let secrets = crate::secrets::status()?;
+    crate::backups::snapshot_before_write("sync-pull");
+
+    {
+        let mut config = state.config.lock().map_err(crate::error::lock_failed)?;
+        let mut incoming = snapshot.config;
+        for app_type in AppType::all() {
+            let Some(local) = config.get_manager(&app_type) else {
+                continue;
+            };
+            let current = local.current.clone();
+            let current_provider = local.providers.get(&current).cloned();
+            incoming.ensure_app(&app_type);
+            let Some(manager) = incoming.get_manager_mut(&app_type) else {
+                continue;
+            };
+            // 当前供应商对应 live 配置，同步不切换也不删除它
+            if let Some(provider) = current_provider {
+                manager.providers.entry(current.clone()).or_insert(provider);
+                manager.current = current;
+            } else {
+                manager.current = String::new();
+            }
+        }
+        if secrets.initialized && secrets.unlocked {
+            crate::secrets::migrate_to_vault(&mut incoming)?;
+        }
+        for app_type in AppType::all() {
+            if let Err(e) = crate::mcp::sync_enabled_for(&incoming, &app_type) {
+                log::warn!("同步后更新 {} 的 MCP 配置失败: {}", app_type.as_str(), e);
+            }
+        }
+        *config = incoming;
+    }
+    state.save()?;
+
+    for (app, content) in &snapshot.app_rules {
+        crate::global_rules::write_app_rules(&AppType::from(app.as_str()), content)?;
+    }
+    for rule in snapshot.codex_rules {
+        crate::global_rules::validate_rule_filename(&rule.name)?;
+        crate::global_rules::write_codex_rule(&rule.name, &rule.content, rule.tags)?;
+    }
+
+    if let Value::Object(remote) = snapshot.settings {
+        let mut merged = serde_json::to_value(crate::settings::get_settings())
+            .map_err(|e| format!("序列化设置失败: {}", e))?;
+        if let Some(local) = merged.as_object_mut() {
+            for (key, value) in remote {
+                if !LOCAL_ONLY_SETTINGS.contains(&key.as_str()) {
+                    local.insert(key, value);
+                }
+            }
+        }
+        let merged =
+            serde_json::from_value(merged).map_err(|e| format!("解析同步的设置失败: {}", e))?;
+        crate::settings::update_settings(merged)?;
+    }
+    Ok(())
+}
+
+// ==================== 传输 ====================
+
+fn client() -> Result<reqwest::Client, String> {
+    reqwest::Client::builder()
+        .timeout(Duration::from_secs(SYNC_TIMEOUT_SECS))
+        .user_agent("cc-switch-sync/1.0")
+        .build()
+        .map_err(|e| format!("创建 HTTP 客户端失败: {}", e))
+}
+
+fn webdav_url(url: &str) -> String {
+    format!("{}/{}", url.trim().trim_end_matches('/'), SYNC_FILE_NAME)
+}
+
+fn s3_key(prefix: Option<&str>) -> String {
+    let prefix = prefix.map(|p| p.trim().trim_matches('/')).unwrap_or("");
+    if prefix.is_empty() {
+        SYNC_FILE_NAME.to_string()
+    } else {
+        format!("{}/{}", prefix, SYNC_FILE_NAME)
+    }
+}
+
+/// 按 RFC 3986 编码对象键（保留 /）
+fn uri_encode_path(path: &str) -> String {
+    path.bytes()
+        .map(|b| match b {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' | b'/' => {
+                (b as char).to_string()
+            }
+            _ => format!("%{:02X}", b),
+        })
+        .collect()
+}
+
+fn hmac_sha256(key: &[u8], data: &[u8]) -> Result<Vec<u8>, String> {
+    let mut mac =
+        Hmac::<Sha256>::new_from_slice(key).map_err(|e| format!("计算签名失败: {}", e))?;
+    mac.update(data);
+    Ok(mac.finalize().into_bytes().to_vec())
+}
+
+/// 构造带 AWS Signature V4 签名的 S3 请求
+fn s3_request(
+    client: &reqwest::Client,
+    backend: &SyncBackend,
+    method: reqwest::Method,
+    body: Vec<u8>,
+) -> Result<reqwest::RequestBuilder, String> {
+    let SyncBackend::S3 {
+        endpoint,
+        region,
+        bucket,
+        access_key_id,
+        secret_access_key,
+        prefix,
+        path_style,
+    } = backend
+    else {
+        return Err("不是 S3 后端".to_string());
+    };
+    let endpoint = endpoint
+        .as_deref()
+        .map(str::trim)
+        .filter(|e| !e.is_empty())
+        .map(str::to_string)
+        .unwrap_or_else(|| format!("https://s3.{}.amazonaws.com", region));
+    let mut url =
+        reqwest::Url::parse(&endpoint).map_err(|e| format!("S3 地址无效: {} ({})", endpoint, e))?;
+    let key = uri_encode_path(&s3_key(prefix.as_deref()));
+    let path = if *path_style {
+        format!("/{}/{}", bucket, key)
+    } else {
+        let host = url
+            .host_str()
+            .ok_or_else(|| format!("S3 地址缺少主机: {}", endpoint))?;
+        url.set_host(Some(&format!("{}.{}", bucket, host)))
+            .map_err(|e| format!("S3 地址无效: {}", e))?;
+        format!("/{}", key)
+    };
+    url.set_path(&path);
+    let host = match url.port() {
+        Some(port) => format!("{}:{}", url.host_str().unwrap_or_default(), port),
+        None => url.host_str().unwrap_or_default().to_string(),
+    };
+
+    let now = chrono::Utc::now();
+    let amz_date = now.format("%Y%m%dT%H%M%SZ").to_string();
+    let date = now.format("%Y%m%d").to_string();
+    let payload_hash = hex(&Sha256::digest(&body));
+    let signed_headers = "host;x-amz-content-sha256;x-amz-date";
+    let canonical_request = format!(
+        "{}\n{}\n\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\n{}\n{}",
+        method, path, host, payload_hash, amz_date, signed_headers, payload_hash
+    );
+    let scope = format!("{}/{}/s3/aws4_request", date, region);
+    let string_to_sign = format!(
+        "AWS4-HMAC-SHA256\n{}\n{}\n{}",
+        amz_date,
+        scope,
+        hex(&Sha256::digest(canonical_request.as_bytes()))
+    );
+    let mut signing_key = format!("AWS4{}", secret_access_key).into_bytes();
+    for part in [date.as_str(), region.as_str(), "s3", "aws4_request"] {
+        signing_key = hmac_sha256(&signing_key, part.as_bytes())?;
+    }
+    let signature = hex(&hmac_sha256(&signing_key, string_to_sign.as_bytes())?);
+    let authorization = format!(
+        "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders={}, Signature={}",
+        access_key_id, scope, signed_headers, signature
+    );
+
+    Ok(client
+        .request(method, url)
+        .header("x-amz-date", amz_date)
+        .header("x-amz-content-sha256", payload_hash)
+        .header("Authorization", authorization)
+        .body(body))
+}
+
+fn gist_request(
+    client: &reqwest::Client,
+    method: reqwest::Method,
+    url: &str,
+    token: &str,
+) -> reqwest::RequestBuilder {
+    client
+        .request(method, url)
+        .bearer_auth(token.trim())
+        .header("Accept", "application/vnd.github+json")
+}
+
+async fn check(response: reqwest::Response, action: &str) -> Result<reqwest::Response, String> {
+    if response.status().is_success() {
+        return Ok(response);
+    }
+    let status = response.status();
+    let body = response.text().await.unwrap_or_default();
+    let body: String = body.chars().take(200).collect();
+    Err(format!("{}失败: HTTP {} {}", action, status.as_u16(), body))
+}
+
+/// 下载远端同步文件；不存在时返回 None
+async fn download(backend: &SyncBackend) -> Result<Option<String>, String> {
+    let client = client()?;
+    let response = match backend {
+        SyncBackend::WebDav {
+            url,
+            username,
+            password,
+        } => {
+            let mut request = client.get(webdav_url(url));
+            if let Some(username) = username.as_deref() {
+                request = request.basic_auth(username, password.as_deref());
+            }
+            request.send().await
+        }
+        SyncBackend::S3 { .. } => {
+            s3_request(&client, backend, reqwest::Method::GET, Vec::new())?
+                .send()
+                .await
+        }
+        SyncBackend::Gist { token, gist_id } => {
+            let Some(gist_id) = gist_id.as_deref() else {
+                return Ok(None);
+            };
+            let url = format!("{}/gists/{}", GITHUB_API, gist_id);
+            let response = gist_request(&client, reqwest::Method::GET, &url, token)
+                .send()
+                .await
+                .map_err(|e| format!("下载同步文件失败: {}", e))?;
+            let gist: Value = check(response, "下载同步文件")
+                .await?
+                .json()
+                .await
+                .map_err(|e| format!("解析 Gist 失败: {}", e))?;
+            let Some(file) = gist.pointer(&format!("/files/{}", SYNC_FILE_NAME)) else {
+                return Ok(None);
+            };
+            // 超过 1MB 时 content 被截断，需要从 raw_url 读取完整内容
+            if file.get("truncated").and_then(|v| v.as_bool()) == Some(true) {
+                let raw_url = file
+                    .get("raw_url")
+                    .and_then(|v| v.as_str())
+                    .ok_or("Gist 文件缺少 raw_url")?;
+                let response = gist_request(&client, reqwest::Method::GET, raw_url, token)
+                    .send()
+                    .await
+                    .map_err(|e| format!("下载同步文件失败: {}", e))?;
+                return check(response, "下载同步文件")
+                    .await?
+                    .text()
+                    .await
+                    .map(Some)
+                    .map_err(|e| format!("读取同步文件失败: {}", e));
+            }
+            return Ok(file
+                .get("content")
+                .and_then(|v| v.as_str())
+                .map(str::to_string));
+        }
+    }
+    .map_err(|e| format!("下载同步文件失败: {}", e))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    check(response, "下载同步文件")
+        .await?
+        .text()
+        .await
+        .map(Some)
+        .map_err(|e| format!("读取同步文件失败: {}", e))
+}
+
+/// 上传同步文件；新建 Gist 时返回需要保存的新后端配置
+async fn upload(backend: &SyncBackend, body: String) -> Result<Option<SyncBackend>, String> {
+    let client = client()?;
+    let response = match backend {
+        SyncBackend::WebDav {
+            url,
+            username,
+            password,
+        } => {
+            let mut request = client
+                .put(webdav_url(url))
+                .header("Content-Type", "application/json")
+                .body(body);
+            if let Some(username) = username.as_deref() {
+                request = request.basic_auth(username, password.as_deref());
+            }
+            request.send().await
+        }
+        SyncBackend::S3 { .. } => {
+            s3_request(&client, backend, reqwest::Method::PUT, body.into_bytes())?
+                .send()
+                .await
+        }
+        SyncBackend::Gist { token, gist_id } => {
+            let files = json!({ SYNC_FILE_NAME: { "content": body } });
+            match gist_id.as_deref() {
+                Some(gist_id) => {
+                    let url = format!("{}/gists/{}", GITHUB_API, gist_id);
+                    gist_request(&client, reqwest::Method::PATCH, &url, token)
+                        .json(&json!({ "files": files }))
+                        .send()
+                        .await
+                }
+                None => {
+                    let url = format!("{}/gists", GITHUB_API);
+                    let response = gist_request(&client, reqwest::Method::POST, &url, token)
+                        .json(&json!({
+                            "description": "CC Switch sync",
+                            "public": false,
+                            "files": files,
+                        }))
+                        .send()
+                        .await
+                        .map_err(|e| format!("上传同步文件失败: {}", e))?;
+                    let gist: Value = check(response, "创建 Gist")
+                        .await?
+                        .json()
+                        .await
+                        .map_err(|e| format!("解析 Gist 失败: {}", e))?;
+                    let id = gist
+                        .get("id")
+                        .and_then(|v| v.as_str())
+                        .ok_or("创建 Gist 失败：响应缺少 id")?;
+                    return Ok(Some(SyncBackend::Gist {
+                        token: token.clone(),
+                        gist_id: Some(id.to_string()),
+                    }));
+                }
+            }
+        }
+    }
+    .map_err(|e| format!("上传同步文件失败: {}", e))?;
+    check(response, "上传同步文件").await?;
+    Ok(None)
+}
+
+// ==================== 同步 ====================
+
+/// 执行同步：比较远端修订号与本地摘要判断两端变化，
+/// 两端都有修改时返回冲突而不做改动；force 为 true 时按指定方向覆盖对方
+pub async fn run(
+    state: &AppState,
+    passphrase: &str,
+    direction: SyncDirection,
+    force: bool,
+) -> Result<SyncOutcome, String> {
+    if passphrase.is_empty() {
+        return Err("同步口令不能为空".to_string());
+    }
+    let mut sync = sync_settings();
+    let backend = sync
+        .backend
+        .clone()
+        .ok_or_else(|| "尚未配置同步后端".to_string())?;
+
+    let remote = match download(&backend).await? {
+        Some(text) => {
+            let envelope: SyncEnvelope =
+                serde_json::from_str(&text).map_err(|e| format!("同步文件格式错误: {}", e))?;
+            if envelope.format != SYNC_FORMAT || envelope.version > SYNC_VERSION {
+                return Err("不支持的同步文件格式，请升级 CC Switch".to_string());
+            }
+            Some(envelope)
+        }
+        None => None,
+    };
+
+    let snapshot = build_snapshot(state)?;
+    let local_hash = snapshot_hash(&snapshot)?;
+    let local_changed = sync.last_local_hash.as_deref() != Some(local_hash.as_str());
+    let remote_revision = remote.as_ref().map(|r| r.revision.clone());
+    let remote_changed = remote.is_some() && remote_revision != sync.last_revision;
+
+    let mut outcome = SyncOutcome {
+        action: SyncAction::UpToDate,
+        local_changed,
+        remote_changed,
+        revision: remote_revision.clone(),
+        remote_device: remote
+            .as_ref()
+            .map(|r| r.device_name.clone().unwrap_or_else(|| r.device_id.clone())),
+        remote_updated_at: remote.as_ref().map(|r| r.updated_at),
+    };
+
+    let push = match direction {
+        SyncDirection::Auto => match (local_changed || remote.is_none(), remote_changed) {
+            (false, false) => {
+                return Ok(outcome);
+            }
+            (true, true) => {
+                outcome.action = SyncAction::Conflict;
+                return Ok(outcome);
+            }
+            (push, _) => push,
+        },
+        SyncDirection::Push => {
+            if remote_changed && !force {
+                outcome.action = SyncAction::Conflict;
+                return Ok(outcome);
+            }
+            true
+        }
+        SyncDirection::Pull => {
+            if remote.is_none() {
+                return Err("远端还没有同步数据".to_string());
+            }
+            if local_changed && !force {
+                outcome.action = SyncAction::Conflict;
+                return Ok(outcome);
+            }
+            false
+        }
+    };
+
+    let device_id = sync.device_id.get_or_insert_with(|| random_hex(8)).clone();
+    if push {
+        let (salt, data) = seal(&snapshot, passphrase)?;
+        // 远端已有数据时先验证口令一致，避免用不同口令覆盖其他设备的数据
+        if let Some(remote) = remote.as_ref() {
+            if !force {
+                open(remote, passphrase)?;
+            }
+        }
+        let envelope = SyncEnvelope {
+            format: SYNC_FORMAT.to_string(),
+            version: SYNC_VERSION,
+            revision: random_hex(16),
+            parent_revision: remote_revision,
+            device_id,
+            device_name: device_name(),
+            updated_at: chrono::Utc::now().timestamp_millis(),
+            salt,
+            data,
+        };
+        let body = serde_json::to_string_pretty(&envelope)
+            .map_err(|e| format!("序列化同步文件失败: {}", e))?;
+        if let Some(updated) = upload(&backend, body).await? {
+            sync.backend = Some(updated);
+        }
+        sync.last_revision = Some(envelope.revision.clone());
+        sync.last_local_hash = Some(local_hash);
+        outcome.action = SyncAction::Pushed;
+        outcome.revision = Some(envelope.revision);
+        outcome.remote_device = envelope.device_name.or(Some(envelope.device_id));
+        outcome.remote_updated_at = Some(envelope.updated_at);
+    } else {
+        let remote = remote.ok_or_else(|| "远端还没有同步数据".to_string())?;
+        let secrets = crate::secrets::status()?;
+        if secrets.initialized && !secrets.unlocked {
+            return Err("密钥库已锁定，请先解锁后再拉取".to_string());
+        }
+        let incoming = open(&remote, passphrase)?;
+        apply_snapshot(state, incoming)?;
+        // 应用后重新计算本地摘要（保留的当前供应商、本机设置等会使其与远端略有不同）
+        sync.last_local_hash = Some(snapshot_hash(&build_snapshot(state)?)?);
+        sync.last_revision = Some(remote.revision);
+        outcome.action = SyncAction::Pulled;
+    }
+    sync.last_synced_at = Some(chrono::Utc::now().timestamp_millis());
+    save_sync_settings(sync)?;
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::settings::AppSettings;
+
+    const LOCAL_PATH: &str = "/home/alice/local-only";
+
+    /// 所有含本机路径的设置项都填入 LOCAL_PATH
+    fn settings_with_local_paths() -> AppSettings {
+        let path = || LOCAL_PATH.to_string();
+        AppSettings {
+            claude_config_dir: Some(path()),
+            codex_config_dir: Some(path()),
+            gemini_config_dir: Some(path()),
+            claude_conversation_dirs: vec![path()],
+            codex_conversation_dirs: vec![path()],
+            gemini_conversation_dirs: vec![path()],
+            app_homes: vec![crate::app_homes::AppHome {
+                name: "work".to_string(),
+                app_type: "claude".to_string(),
+                dir: path(),
+            }],
+            active_claude_home: Some(path()),
+            active_codex_home: Some(path()),
+            env_profiles: vec![crate::env_profiles::EnvProfile {
+                id: "p1".to_string(),
+                name: "work".to_string(),
+                claude_home: Some(path()),
+                codex_home: Some(path()),
+            }],
+            active_env_profile: Some(path()),
+            wsl: Some(crate::wsl::WslSettings {
+                enabled: true,
+                distro: path(),
+                user: path(),
+            }),
+            remote_profiles: vec![crate::remote::RemoteProfile {
+                id: "r1".to_string(),
+                name: "server".to_string(),
+                host: "example.com".to_string(),
+                port: 22,
+                user: "alice".to_string(),
+                identity_file: Some(path()),
+                claude_dir: Some(path()),
+                codex_dir: Some(path()),
+            }],
+            setup_report_dir: Some(path()),
+            workspaces: vec![crate::workspaces::Workspace {
+                id: "w1".to_string(),
+                name: "project".to_string(),
+                projects: vec![path()],
+                claude_provider_id: None,
+                codex_provider_id: None,
+                gemini_provider_id: None,
+                opencode_provider_id: None,
+                crush_provider_id: None,
+                env_profile_id: None,
+                auto_apply: false,
+            }],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn shared_settings_contain_no_local_paths() {
+        let shared = strip_local_settings(&settings_with_local_paths()).unwrap();
+        let text = serde_json::to_string(&shared).unwrap();
+        assert!(
+            !text.contains(LOCAL_PATH),
+            "本机路径出现在同步设置中: {}",
+            text
+        );
+    }
+}
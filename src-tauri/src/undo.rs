@@ -0,0 +1,211 @@
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+use crate::app_config::AppType;
+use crate::config::{
+    atomic_write, delete_file, get_app_config_dir, read_json_file, write_json_file,
+};
+
+/// 最多保留的撤销记录（按文件计）
+const MAX_ENTRIES: usize = 100;
+
+/// 序列化读写撤销栈
+static UNDO_LOCK: Mutex<()> = Mutex::new(());
+
+/// 撤销栈目录 ~/.cc-switch/undo（index.json + 每条记录一个 .bak 文件）
+fn get_undo_dir() -> PathBuf {
+    get_app_config_dir().join("undo")
+}
+
+/// 单个文件的修改前内容
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct UndoEntry {
+    pub id: String,
+    /// 同一次操作写入的多个文件共享 change_id
+    pub change_id: String,
+    /// 操作名称，例如 switch-provider、write-rules、mcp
+    pub operation: String,
+    pub file: String,
+    /// 修改前文件是否存在；不存在时撤销即删除该文件
+    pub existed: bool,
+    /// 毫秒时间戳
+    pub timestamp: i64,
+    /// 切换供应商前的当前供应商；撤销时需一并恢复 config.json 中的 current，
+    /// 否则下次切换会把旧供应商的 live 配置回填到新供应商
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub previous_current: Option<PreviousProvider>,
+}
+
+/// 修改前的当前供应商
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PreviousProvider {
+    pub app_type: String,
+    /// 为空表示修改前没有当前供应商
+    pub provider_id: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+struct UndoStack {
+    #[serde(default)]
+    entries: Vec<UndoEntry>,
+}
+
+impl UndoStack {
+    fn load() -> Self {
+        let path = get_undo_dir().join("index.json");
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取撤销记录失败: {}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(&get_undo_dir().join("index.json"), self).map_err(Into::into)
+    }
+}
+
+fn blob_path(entry_id: &str) -> PathBuf {
+    get_undo_dir().join(format!("{}.bak", entry_id))
+}
+
+fn capture_inner(
+    operation: &str,
+    paths: &[PathBuf],
+    previous: Option<&PreviousProvider>,
+) -> Result<(), String> {
+    let _guard = UNDO_LOCK.lock();
+    let mut stack = UndoStack::load();
+    let now = chrono::Utc::now();
+    let change_id = now.format("%Y%m%d%H%M%S%9f").to_string();
+
+    for (index, path) in paths.iter().enumerate() {
+        let id = format!("{}-{}", change_id, index);
+        let existed = path.exists();
+        if existed {
+            let content =
+                fs::read(path).map_err(|e| format!("读取文件失败: {}: {}", path.display(), e))?;
+            atomic_write(&blob_path(&id), &content)?;
+        }
+        stack.entries.push(UndoEntry {
+            id,
+            change_id: change_id.clone(),
+            operation: operation.to_string(),
+            file: path.to_string_lossy().to_string(),
+            existed,
+            timestamp: now.timestamp_millis(),
+            previous_current: previous.cloned(),
+        });
+    }
+
+    if stack.entries.len() > MAX_ENTRIES {
+        let overflow = stack.entries.len() - MAX_ENTRIES;
+        for entry in stack.entries.drain(..overflow) {
+            let _ = delete_file(&blob_path(&entry.id));
+        }
+    }
+    stack.save()
+}
+
+/// 切换 / 更新当前供应商时会写入的 live 配置文件
+pub fn live_config_paths(app_type: &AppType) -> Vec<PathBuf> {
+    crate::app_adapter::adapter_for(app_type).live_paths()
+}
+
+/// MCP 同步会写入的客户端配置文件
+pub fn mcp_config_path(app_type: &AppType) -> PathBuf {
+    crate::app_adapter::adapter_for(app_type).mcp_path()
+}
+
+/// 在修改配置文件前记录其当前内容（失败仅记录日志，不阻断写入）
+pub fn capture(operation: &str, paths: &[PathBuf]) {
+    crate::audit::label(operation, paths);
+    if paths.is_empty() {
+        return;
+    }
+    if let Err(e) = capture_inner(operation, paths, None) {
+        log::warn!("记录撤销信息失败: {}", e);
+    }
+}
+
+/// 切换供应商前记录 live 配置与当前供应商 id，撤销时两者一起恢复
+pub fn capture_switch(app_type: &AppType, previous_id: &str) {
+    let paths = live_config_paths(app_type);
+    crate::audit::label("switch-provider", &paths);
+    if paths.is_empty() {
+        return;
+    }
+    let previous = PreviousProvider {
+        app_type: app_type.as_str().to_string(),
+        provider_id: previous_id.to_string(),
+    };
+    if let Err(e) = capture_inner("switch-provider", &paths, Some(&previous)) {
+        log::warn!("记录撤销信息失败: {}", e);
+    }
+}
+
+/// 列出可撤销的修改（最新在前）
+pub fn list_changes() -> Vec<UndoEntry> {
+    let _guard = UNDO_LOCK.lock();
+    let mut entries = UndoStack::load().entries;
+    entries.reverse();
+    entries
+}
+
+fn restore_entry(entry: &UndoEntry) -> Result<(), String> {
+    let path = Path::new(&entry.file);
+    let _file_guard = crate::file_lock::lock_file(path)?;
+    crate::audit::label("rollback", &[path.to_path_buf()]);
+    if entry.existed {
+        let content = fs::read(blob_path(&entry.id))
+            .map_err(|e| format!("读取撤销内容失败: {}: {}", entry.file, e))?;
+        atomic_write(path, &content).map_err(Into::into)
+    } else {
+        delete_file(path).map_err(Into::into)
+    }
+}
+
+/// 撤销最近一次修改：指定 file 时只恢复该文件的最近一次修改（切换供应商的记录恢复整次操作），
+/// 否则恢复最近一次操作涉及的全部文件。返回已恢复的记录；
+/// 记录带有 previous_current 时，调用方需将当前供应商恢复为该 id
+pub fn rollback_last(file: Option<&str>) -> Result<Vec<UndoEntry>, String> {
+    let _guard = UNDO_LOCK.lock();
+    let mut stack = UndoStack::load();
+    let target = match file {
+        Some(file) => stack.entries.iter().rposition(|e| e.file == file),
+        None => stack.entries.len().checked_sub(1),
+    }
+    .ok_or_else(|| "没有可撤销的修改".to_string())?;
+
+    // 切换供应商的记录只能整体撤销：只恢复其中一个文件会让 live 配置与当前供应商不一致
+    let whole_change = file.is_none() || stack.entries[target].previous_current.is_some();
+    let restored: Vec<UndoEntry> = match whole_change {
+        false => vec![stack.entries.remove(target)],
+        true => {
+            let change_id = stack.entries[target].change_id.clone();
+            let (restored, kept) = stack
+                .entries
+                .drain(..)
+                .partition(|e| e.change_id == change_id);
+            stack.entries = kept;
+            restored
+        }
+    };
+
+    for entry in &restored {
+        restore_entry(entry)?;
+        log::info!("已撤销 {} 对 {} 的修改", entry.operation, entry.file);
+    }
+    // 全部恢复成功后再清理，失败时可重试
+    stack.save()?;
+    for entry in &restored {
+        let _ = delete_file(&blob_path(&entry.id));
+    }
+    Ok(restored)
+}
@@ -0,0 +1,334 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use std::fs;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+/// 导出包中的对话文件
+const CONVERSATIONS_ENTRY: &str = "conversations.json";
+
+/// 网页版数据导出的来源
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum WebExportSource {
+    /// chatgpt.com 数据导出
+    Chatgpt,
+    /// claude.ai 数据导出
+    ClaudeAi,
+}
+
+impl WebExportSource {
+    /// 导入后的“项目”目录名，列表中显示为项目名称
+    fn dir_name(self) -> &'static str {
+        match self {
+            WebExportSource::Chatgpt => "chatgpt",
+            WebExportSource::ClaudeAi => "claude-ai",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WebImportResult {
+    pub source: WebExportSource,
+    /// 写入的会话文件
+    pub imported: Vec<String>,
+    /// 没有文本消息而跳过的对话数
+    pub skipped: usize,
+}
+
+/// 导入的对话保存在 ~/.cc-switch/imported/<来源>/<对话 id>.jsonl，
+/// 使用 Claude 会话的 JSONL 格式，作为额外的 Claude 对话根目录参与列表、搜索与导出
+pub fn imported_dir() -> PathBuf {
+    crate::config::get_app_config_dir().join("imported")
+}
+
+/// 一条转换后的消息
+struct ImportedMessage {
+    id: String,
+    role: &'static str,
+    text: String,
+    timestamp: Option<String>,
+}
+
+struct ImportedConversation {
+    id: String,
+    title: Option<String>,
+    updated_at: Option<chrono::DateTime<chrono::Utc>>,
+    messages: Vec<ImportedMessage>,
+}
+
+/// 读取导出包中的 conversations.json；也接受已解压的 conversations.json 文件
+fn read_conversations(path: &Path) -> Result<Value, String> {
+    let is_zip = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .is_some_and(|ext| ext.eq_ignore_ascii_case("zip"));
+    let text = if is_zip {
+        let file = fs::File::open(path).map_err(|e| format!("打开导出包失败: {}", e))?;
+        let mut archive =
+            zip::ZipArchive::new(file).map_err(|e| format!("解析导出包失败: {}", e))?;
+        // 导出包可能多一层目录
+        let name = archive
+            .file_names()
+            .find(|n| *n == CONVERSATIONS_ENTRY || n.ends_with("/conversations.json"))
+            .map(|n| n.to_string())
+            .ok_or_else(|| "导出包中没有 conversations.json".to_string())?;
+        let mut entry = archive
+            .by_name(&name)
+            .map_err(|e| format!("读取导出包失败: {}", e))?;
+        let mut text = String::new();
+        entry
+            .read_to_string(&mut text)
+            .map_err(|e| format!("读取 conversations.json 失败: {}", e))?;
+        text
+    } else {
+        fs::read_to_string(path).map_err(|e| format!("读取文件失败: {}", e))?
+    };
+    serde_json::from_str(&text).map_err(|e| format!("解析 conversations.json 失败: {}", e))
+}
+
+/// 按第一条对话的字段判断来源
+fn detect_source(conversations: &[Value]) -> Result<WebExportSource, String> {
+    let first = conversations
+        .first()
+        .ok_or_else(|| "导出包中没有对话".to_string())?;
+    if first.get("mapping").is_some() {
+        Ok(WebExportSource::Chatgpt)
+    } else if first.get("chat_messages").is_some() {
+        Ok(WebExportSource::ClaudeAi)
+    } else {
+        Err("无法识别的导出格式（仅支持 ChatGPT 与 claude.ai 数据导出）".to_string())
+    }
+}
+
+fn from_unix(value: Option<&Value>) -> Option<chrono::DateTime<chrono::Utc>> {
+    let secs = value?.as_f64()?;
+    chrono::DateTime::from_timestamp(secs as i64, (secs.fract() * 1e9) as u32)
+}
+
+fn from_rfc3339(value: Option<&Value>) -> Option<chrono::DateTime<chrono::Utc>> {
+    chrono::DateTime::parse_from_rfc3339(value?.as_str()?)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
+fn str_field(value: &Value, key: &str) -> Option<String> {
+    value
+        .get(key)
+        .and_then(|v| v.as_str())
+        .filter(|s| !s.is_empty())
+        .map(|s| s.to_string())
+}
+
+/// ChatGPT：mapping 为消息树，从 current_node 沿 parent 回溯得到当前分支；
+/// 只保留用户与助手的文本，跳过系统消息、工具调用与隐藏消息
+fn convert_chatgpt(conversation: &Value) -> Option<ImportedConversation> {
+    let id =
+        str_field(conversation, "conversation_id").or_else(|| str_field(conversation, "id"))?;
+    let mapping = conversation.get("mapping")?.as_object()?;
+
+    let mut chain = Vec::new();
+    let mut cursor = str_field(conversation, "current_node");
+    while let Some(node_id) = cursor {
+        let Some(node) = mapping.get(&node_id) else {
+            break;
+        };
+        chain.push(node);
+        cursor = str_field(node, "parent");
+    }
+    chain.reverse();
+
+    let messages = chain
+        .into_iter()
+        .filter_map(|node| {
+            let message = node.get("message")?;
+            let role = match message.pointer("/author/role")?.as_str()? {
+                "user" => "user",
+                "assistant" => "assistant",
+                _ => return None,
+            };
+            let hidden = message
+                .pointer("/metadata/is_visually_hidden_from_conversation")
+                .and_then(|v| v.as_bool())
+                .unwrap_or(false);
+            let content_type = message.pointer("/content/content_type")?.as_str()?;
+            if hidden || !matches!(content_type, "text" | "multimodal_text") {
+                return None;
+            }
+            let text = message
+                .pointer("/content/parts")?
+                .as_array()?
+                .iter()
+                .filter_map(|part| part.as_str())
+                .filter(|part| !part.trim().is_empty())
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(ImportedMessage {
+                id: str_field(message, "id")
+                    .unwrap_or_else(|| str_field(node, "id").unwrap_or_default()),
+                role,
+                text,
+                timestamp: from_unix(message.get("create_time")).map(|dt| dt.to_rfc3339()),
+            })
+        })
+        .collect();
+
+    Some(ImportedConversation {
+        id,
+        title: str_field(conversation, "title"),
+        updated_at: from_unix(conversation.get("update_time"))
+            .or_else(|| from_unix(conversation.get("create_time"))),
+        messages,
+    })
+}
+
+/// claude.ai：chat_messages 按时间排列；优先取 content 中的文本块，旧格式只有 text 字段
+fn convert_claude_ai(conversation: &Value) -> Option<ImportedConversation> {
+    let id = str_field(conversation, "uuid")?;
+    let messages = conversation
+        .get("chat_messages")?
+        .as_array()?
+        .iter()
+        .filter_map(|message| {
+            let role = match message.get("sender")?.as_str()? {
+                "human" => "user",
+                "assistant" => "assistant",
+                _ => return None,
+            };
+            let blocks: Vec<&str> = message
+                .get("content")
+                .and_then(|c| c.as_array())
+                .map(|blocks| {
+                    blocks
+                        .iter()
+                        .filter(|b| b.get("type").and_then(|t| t.as_str()) == Some("text"))
+                        .filter_map(|b| b.get("text").and_then(|t| t.as_str()))
+                        .filter(|t| !t.trim().is_empty())
+                        .collect()
+                })
+                .unwrap_or_default();
+            let text = if blocks.is_empty() {
+                str_field(message, "text")?
+            } else {
+                blocks.join("\n\n")
+            };
+            if text.trim().is_empty() {
+                return None;
+            }
+            Some(ImportedMessage {
+                id: str_field(message, "uuid").unwrap_or_default(),
+                role,
+                text,
+                timestamp: from_rfc3339(message.get("created_at")).map(|dt| dt.to_rfc3339()),
+            })
+        })
+        .collect();
+
+    Some(ImportedConversation {
+        id,
+        title: str_field(conversation, "name"),
+        updated_at: from_rfc3339(conversation.get("updated_at"))
+            .or_else(|| from_rfc3339(conversation.get("created_at"))),
+        messages,
+    })
+}
+
+/// 转换为 Claude 会话 JSONL：首行为标题摘要，其后每条消息一行并按顺序串联 parentUuid
+fn to_jsonl(conversation: &ImportedConversation) -> Result<String, String> {
+    let mut lines = Vec::with_capacity(conversation.messages.len() + 1);
+    if let Some(title) = &conversation.title {
+        lines.push(json!({ "type": "summary", "summary": title }));
+    }
+    let mut parent: Option<String> = None;
+    for (i, message) in conversation.messages.iter().enumerate() {
+        let uuid = if message.id.is_empty() {
+            format!("{}-{}", conversation.id, i)
+        } else {
+            message.id.clone()
+        };
+        lines.push(json!({
+            "type": message.role,
+            "uuid": uuid,
+            "parentUuid": parent,
+            "sessionId": conversation.id,
+            "timestamp": message.timestamp.clone().unwrap_or_default(),
+            "message": {
+                "role": message.role,
+                "content": [{ "type": "text", "text": message.text }],
+            },
+        }));
+        parent = Some(uuid);
+    }
+    lines
+        .iter()
+        .map(serde_json::to_string)
+        .collect::<Result<Vec<_>, _>>()
+        .map(|lines| lines.join("\n") + "\n")
+        .map_err(|e| format!("序列化消息失败: {}", e))
+}
+
+/// 文件名只保留安全字符
+fn file_name(id: &str) -> String {
+    let name: String = id
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect();
+    format!("{}.jsonl", name)
+}
+
+/// 导入 ChatGPT / claude.ai 数据导出包（zip 或其中的 conversations.json）。
+/// 同一对话再次导入时覆盖原文件；文件修改时间设为对话的最后更新时间，使列表按原始时间排序
+pub fn import_archive(path: &str) -> Result<WebImportResult, String> {
+    let value = read_conversations(Path::new(path))?;
+    let conversations = value
+        .as_array()
+        .ok_or_else(|| "conversations.json 顶层必须是数组".to_string())?;
+    let source = detect_source(conversations)?;
+    let dir = imported_dir().join(source.dir_name());
+
+    let mut result = WebImportResult {
+        source,
+        imported: Vec::new(),
+        skipped: 0,
+    };
+    for conversation in conversations {
+        let converted = match source {
+            WebExportSource::Chatgpt => convert_chatgpt(conversation),
+            WebExportSource::ClaudeAi => convert_claude_ai(conversation),
+        };
+        let Some(converted) = converted.filter(|c| !c.messages.is_empty()) else {
+            result.skipped += 1;
+            continue;
+        };
+        let target = dir.join(file_name(&converted.id));
+        crate::config::write_text_file(&target, &to_jsonl(&converted)?)?;
+        if let Some(updated_at) = converted.updated_at {
+            let modified = std::time::SystemTime::from(updated_at);
+            if let Err(e) = fs::File::options()
+                .write(true)
+                .open(&target)
+                .and_then(|f| f.set_modified(modified))
+            {
+                log::debug!("设置修改时间失败: {}: {}", target.display(), e);
+            }
+        }
+        result.imported.push(target.to_string_lossy().to_string());
+    }
+    log::info!(
+        "已导入 {} 个网页版对话（{}），跳过 {} 个",
+        result.imported.len(),
+        source.dir_name(),
+        result.skipped
+    );
+    Ok(result)
+}
@@ -0,0 +1,607 @@
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Map, Value};
+use std::path::{Path, PathBuf};
+
+use crate::app_config::{AppType, MultiAppConfig};
+use crate::provider::Provider;
+
+/// 预览内容最多保留的字符数
+const MAX_PREVIEW_CHARS: usize = 4000;
+
+/// 迁移方向
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum MigrationDirection {
+    ClaudeToCodex,
+    CodexToClaude,
+}
+
+/// 计划中的文件操作
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum PlannedAction {
+    Create,
+    Update,
+    Skip,
+}
+
+/// 迁移计划中的一项
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PlannedChange {
+    pub path: String,
+    pub action: PlannedAction,
+    pub description: String,
+    /// 将写入的内容（截断）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub preview: Option<String>,
+}
+
+/// 迁移计划（dry-run 结果）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MigrationPlan {
+    pub direction: MigrationDirection,
+    pub project_path: String,
+    /// 绑定到目标应用的供应商
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub provider_id: Option<String>,
+    pub changes: Vec<PlannedChange>,
+    pub warnings: Vec<String>,
+}
+
+/// 计划对应的实际写入操作
+enum MigrationOp {
+    WriteFile {
+        path: PathBuf,
+        content: String,
+    },
+    /// 将 MCP 服务器加入 Codex SSOT 并同步到 ~/.codex/config.toml
+    CodexMcp {
+        servers: Vec<(String, Value)>,
+    },
+    /// 在 ~/.codex/config.toml 中加入 [profiles.<name>]（必要时连同 [model_providers.<key>]）
+    CodexProfile {
+        name: String,
+        profile: toml::Table,
+        model_provider: Option<(String, toml::Value)>,
+    },
+}
+
+struct Planner {
+    plan: MigrationPlan,
+    ops: Vec<MigrationOp>,
+}
+
+impl Planner {
+    fn change(
+        &mut self,
+        path: &Path,
+        action: PlannedAction,
+        description: String,
+        preview: Option<&str>,
+    ) {
+        self.plan.changes.push(PlannedChange {
+            path: path.to_string_lossy().to_string(),
+            action,
+            description,
+            preview: preview.map(|p| p.chars().take(MAX_PREVIEW_CHARS).collect()),
+        });
+    }
+
+    fn warn(&mut self, message: String) {
+        self.plan.warnings.push(message);
+    }
+
+    /// 由源指令文件生成目标指令文件（目标已存在时跳过，不覆盖用户内容）
+    fn plan_instructions(&mut self, project: &Path, from: &str, to: &str) {
+        let source = project.join(from);
+        let target = project.join(to);
+        let Ok(content) = std::fs::read_to_string(&source) else {
+            self.warn(format!("项目中没有 {}，跳过指令文件迁移", from));
+            return;
+        };
+        if target.exists() {
+            self.change(
+                &target,
+                PlannedAction::Skip,
+                format!("{} 已存在，保留现有内容", to),
+                None,
+            );
+            return;
+        }
+        if content.lines().any(|l| l.trim_start().starts_with('@')) {
+            self.warn(format!(
+                "{} 中的 @文件引用 在 {} 中不会被展开，请手动检查",
+                from, to
+            ));
+        }
+        let generated = format!("<!-- 由 cc-switch 从 {} 迁移生成 -->\n\n{}", from, content);
+        self.change(
+            &target,
+            PlannedAction::Create,
+            format!("由 {} 生成 {}", from, to),
+            Some(&generated),
+        );
+        self.ops.push(MigrationOp::WriteFile {
+            path: target,
+            content: generated,
+        });
+    }
+}
+
+fn read_project_mcp(project: &Path) -> Map<String, Value> {
+    std::fs::read_to_string(project.join(".mcp.json"))
+        .ok()
+        .and_then(|text| serde_json::from_str::<Value>(&text).ok())
+        .and_then(|v| v.get("mcpServers").and_then(|m| m.as_object()).cloned())
+        .unwrap_or_default()
+}
+
+fn enabled_servers(config: &MultiAppConfig, app: &AppType) -> Vec<(String, Value)> {
+    let mut servers: Vec<(String, Value)> = config
+        .mcp_for(app)
+        .servers
+        .iter()
+        .filter(|(_, entry)| entry.get("enabled").and_then(|v| v.as_bool()) == Some(true))
+        .filter_map(|(id, entry)| entry.get("server").map(|s| (id.clone(), s.clone())))
+        .collect();
+    servers.sort_by(|a, b| a.0.cmp(&b.0));
+    servers
+}
+
+/// 将项目目录名转换为 Codex profile 名称
+fn profile_name(project: &Path) -> String {
+    let raw = project
+        .file_name()
+        .map(|n| n.to_string_lossy().to_lowercase())
+        .unwrap_or_default();
+    let name: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let name = name.trim_matches('-').to_string();
+    if name.is_empty() {
+        "project".to_string()
+    } else {
+        name
+    }
+}
+
+/// 查找目标应用中与源应用当前供应商对应的供应商：显式指定 > 同名 > 同端点
+fn counterpart_provider(
+    config: &MultiAppConfig,
+    from: &AppType,
+    to: &AppType,
+    provider_id: Option<&str>,
+) -> Result<Option<Provider>, String> {
+    let Some(target_manager) = config.get_manager(to) else {
+        return Ok(None);
+    };
+    if let Some(id) = provider_id {
+        return target_manager
+            .providers
+            .get(id)
+            .cloned()
+            .map(Some)
+            .ok_or_else(|| crate::error::provider_not_found(&id));
+    }
+    let Some(source) = config
+        .get_manager(from)
+        .and_then(|m| m.providers.get(&m.current))
+    else {
+        return Ok(None);
+    };
+    let source_endpoint = source.endpoint(from);
+    let found = target_manager
+        .providers
+        .values()
+        .find(|p| p.name.trim().eq_ignore_ascii_case(source.name.trim()))
+        .or_else(|| {
+            let endpoint = source_endpoint.as_deref()?;
+            target_manager
+                .providers
+                .values()
+                .find(|p| p.endpoint(to).as_deref() == Some(endpoint))
+        });
+    Ok(found.cloned())
+}
+
+fn plan_claude_to_codex(
+    config: &MultiAppConfig,
+    project: &Path,
+    planner: &mut Planner,
+    provider_id: Option<&str>,
+) -> Result<(), String> {
+    planner.plan_instructions(project, "CLAUDE.md", "AGENTS.md");
+
+    // MCP：项目 .mcp.json 与已启用的 Claude MCP → Codex MCP
+    let codex_config_path = crate::codex_config::get_codex_config_path();
+    let existing = &config.mcp_for(&AppType::Codex).servers;
+    let mut servers: Vec<(String, Value)> = read_project_mcp(project).into_iter().collect();
+    for (id, spec) in enabled_servers(config, &AppType::Claude) {
+        if !servers.iter().any(|(existing_id, _)| existing_id == &id) {
+            servers.push((id, spec));
+        }
+    }
+    servers.retain(|(id, _)| !existing.contains_key(id));
+    if servers.is_empty() {
+        planner.change(
+            &codex_config_path,
+            PlannedAction::Skip,
+            "没有需要迁移的 MCP 服务器".to_string(),
+            None,
+        );
+    } else {
+        let ids: Vec<&str> = servers.iter().map(|(id, _)| id.as_str()).collect();
+        let preview = serde_json::to_string_pretty(&servers.iter().cloned().collect::<Map<_, _>>())
+            .unwrap_or_default();
+        planner.change(
+            &codex_config_path,
+            PlannedAction::Update,
+            format!("添加 MCP 服务器: {}", ids.join(", ")),
+            Some(&preview),
+        );
+        planner.ops.push(MigrationOp::CodexMcp { servers });
+    }
+
+    // Profile：绑定到与当前 Claude 供应商对应的 Codex 供应商
+    let Some(provider) =
+        counterpart_provider(config, &AppType::Claude, &AppType::Codex, provider_id)?
+    else {
+        planner
+            .warn("未找到与当前 Claude 供应商对应的 Codex 供应商，跳过 profile 生成".to_string());
+        return Ok(());
+    };
+    planner.plan.provider_id = Some(provider.id.clone());
+
+    let provider_table: toml::Table = provider
+        .settings_config
+        .get("config")
+        .and_then(|v| v.as_str())
+        .and_then(|text| toml::from_str(text).ok())
+        .unwrap_or_default();
+    let mut profile = toml::Table::new();
+    for key in ["model", "model_provider", "model_reasoning_effort"] {
+        if let Some(value) = provider_table.get(key) {
+            profile.insert(key.to_string(), value.clone());
+        }
+    }
+    if profile.is_empty() {
+        planner.warn(format!(
+            "Codex 供应商 {} 未声明 model / model_provider，跳过 profile 生成",
+            provider.name
+        ));
+        return Ok(());
+    }
+
+    let live: toml::Table = std::fs::read_to_string(&codex_config_path)
+        .ok()
+        .and_then(|text| toml::from_str(&text).ok())
+        .unwrap_or_default();
+    let name = profile_name(project);
+    if live
+        .get("profiles")
+        .and_then(|v| v.as_table())
+        .is_some_and(|p| p.contains_key(&name))
+    {
+        planner.change(
+            &codex_config_path,
+            PlannedAction::Skip,
+            format!("profile {} 已存在", name),
+            None,
+        );
+        return Ok(());
+    }
+
+    // live 配置中缺少该 model_provider 定义时一并写入
+    let model_provider = provider_table
+        .get("model_provider")
+        .and_then(|v| v.as_str())
+        .filter(|key| {
+            !live
+                .get("model_providers")
+                .and_then(|v| v.as_table())
+                .is_some_and(|p| p.contains_key(*key))
+        })
+        .and_then(|key| {
+            provider_table
+                .get("model_providers")
+                .and_then(|v| v.as_table())
+                .and_then(|p| p.get(key))
+                .map(|value| (key.to_string(), value.clone()))
+        });
+
+    let mut preview = toml::Table::new();
+    let mut profiles = toml::Table::new();
+    profiles.insert(name.clone(), toml::Value::Table(profile.clone()));
+    preview.insert("profiles".to_string(), toml::Value::Table(profiles));
+    if let Some((key, value)) = &model_provider {
+        let mut providers = toml::Table::new();
+        providers.insert(key.clone(), value.clone());
+        preview.insert("model_providers".to_string(), toml::Value::Table(providers));
+    }
+    planner.change(
+        &codex_config_path,
+        PlannedAction::Update,
+        format!(
+            "添加 profile {}（绑定供应商 {}），使用 codex --profile {} 启动",
+            name, provider.name, name
+        ),
+        toml::to_string(&preview).ok().as_deref(),
+    );
+    planner.warn(
+        "profile 写入当前 live config.toml，切换 Codex 供应商后仅保留在当前供应商的配置中"
+            .to_string(),
+    );
+    planner.ops.push(MigrationOp::CodexProfile {
+        name,
+        profile,
+        model_provider,
+    });
+    Ok(())
+}
+
+fn plan_codex_to_claude(
+    config: &MultiAppConfig,
+    project: &Path,
+    planner: &mut Planner,
+    provider_id: Option<&str>,
+) -> Result<(), String> {
+    planner.plan_instructions(project, "AGENTS.md", "CLAUDE.md");
+
+    // MCP：已启用的 Codex MCP → 项目 .mcp.json（保留已有条目）
+    let mcp_path = project.join(".mcp.json");
+    let mut root: Value = std::fs::read_to_string(&mcp_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .filter(|v: &Value| v.is_object())
+        .unwrap_or_else(|| json!({}));
+    let existing = read_project_mcp(project);
+    let servers: Vec<(String, Value)> = enabled_servers(config, &AppType::Codex)
+        .into_iter()
+        .filter(|(id, _)| !existing.contains_key(id))
+        .collect();
+    if servers.is_empty() {
+        planner.change(
+            &mcp_path,
+            PlannedAction::Skip,
+            "没有需要迁移的 MCP 服务器".to_string(),
+            None,
+        );
+    } else {
+        let ids: Vec<String> = servers.iter().map(|(id, _)| id.clone()).collect();
+        let mut merged = existing;
+        merged.extend(servers);
+        if let Some(obj) = root.as_object_mut() {
+            obj.insert("mcpServers".to_string(), Value::Object(merged));
+        }
+        let content =
+            serde_json::to_string_pretty(&root).map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+        let action = if mcp_path.exists() {
+            PlannedAction::Update
+        } else {
+            PlannedAction::Create
+        };
+        planner.change(
+            &mcp_path,
+            action,
+            format!("添加项目级 MCP 服务器: {}", ids.join(", ")),
+            Some(&content),
+        );
+        planner.ops.push(MigrationOp::WriteFile {
+            path: mcp_path,
+            content,
+        });
+    }
+
+    // 供应商：写入项目 .claude/settings.local.json 的 env
+    let Some(provider) =
+        counterpart_provider(config, &AppType::Codex, &AppType::Claude, provider_id)?
+    else {
+        planner.warn("未找到与当前 Codex 供应商对应的 Claude 供应商，跳过供应商绑定".to_string());
+        return Ok(());
+    };
+    planner.plan.provider_id = Some(provider.id.clone());
+    let resolved = crate::secrets::resolve_settings(&AppType::Claude, &provider.settings_config)?;
+    let Some(env) = resolved
+        .get("env")
+        .filter(|v| v.is_object())
+    else {
+        planner.warn(format!(
+            "Claude 供应商 {} 没有 env 配置（官方登录），无需项目级绑定",
+            provider.name
+        ));
+        return Ok(());
+    };
+
+    let settings_path = project.join(".claude").join("settings.local.json");
+    let mut settings: Value = std::fs::read_to_string(&settings_path)
+        .ok()
+        .and_then(|text| serde_json::from_str(&text).ok())
+        .filter(|v: &Value| v.is_object())
+        .unwrap_or_else(|| json!({}));
+    if settings.get("env").is_some() {
+        planner.change(
+            &settings_path,
+            PlannedAction::Skip,
+            "项目已配置 env，保留现有供应商设置".to_string(),
+            None,
+        );
+        return Ok(());
+    }
+    let action = if settings_path.exists() {
+        PlannedAction::Update
+    } else {
+        PlannedAction::Create
+    };
+    if let Some(obj) = settings.as_object_mut() {
+        obj.insert("env".to_string(), env.clone());
+    }
+    let content =
+        serde_json::to_string_pretty(&settings).map_err(|e| format!("序列化 JSON 失败: {}", e))?;
+    let masked = {
+        let mut masked = settings.clone();
+        if let Some(env) = masked.get_mut("env").and_then(|v| v.as_object_mut()) {
+            for (key, value) in env.iter_mut() {
+                if key.contains("TOKEN") || key.contains("KEY") {
+                    *value = json!("****");
+                }
+            }
+        }
+        serde_json::to_string_pretty(&masked).unwrap_or_default()
+    };
+    planner.change(
+        &settings_path,
+        action,
+        format!("绑定 Claude 供应商 {}（项目级 env）", provider.name),
+        Some(&masked),
+    );
+    planner.warn(".claude/settings.local.json 含有 API Key，请确认其未被提交到版本库".to_string());
+    planner.ops.push(MigrationOp::WriteFile {
+        path: settings_path,
+        content,
+    });
+    Ok(())
+}
+
+fn build_plan(
+    config: &MultiAppConfig,
+    direction: MigrationDirection,
+    project_path: &str,
+    provider_id: Option<&str>,
+) -> Result<Planner, String> {
+    let project = Path::new(project_path.trim());
+    if !project.is_dir() {
+        return Err(format!("项目目录不存在: {}", project.display()));
+    }
+    let mut planner = Planner {
+        plan: MigrationPlan {
+            direction,
+            project_path: project.to_string_lossy().to_string(),
+            provider_id: None,
+            changes: Vec::new(),
+            warnings: Vec::new(),
+        },
+        ops: Vec::new(),
+    };
+    match direction {
+        MigrationDirection::ClaudeToCodex => {
+            plan_claude_to_codex(config, project, &mut planner, provider_id)?
+        }
+        MigrationDirection::CodexToClaude => {
+            plan_codex_to_claude(config, project, &mut planner, provider_id)?
+        }
+    }
+    Ok(planner)
+}
+
+/// 生成迁移计划（dry-run，不写入任何文件）
+pub fn plan_migration(
+    config: &MultiAppConfig,
+    direction: MigrationDirection,
+    project_path: &str,
+    provider_id: Option<&str>,
+) -> Result<MigrationPlan, String> {
+    build_plan(config, direction, project_path, provider_id).map(|planner| planner.plan)
+}
+
+fn write_codex_profile(
+    name: &str,
+    profile: &toml::Table,
+    model_provider: Option<&(String, toml::Value)>,
+) -> Result<(), String> {
+    let path = crate::codex_config::get_codex_config_path();
+    let _lock = crate::file_lock::lock_file(&path)?;
+    let text = crate::codex_config::read_and_validate_codex_config_text()?;
+    let mut root: toml::Table = if text.trim().is_empty() {
+        toml::Table::new()
+    } else {
+        toml::from_str(&text).map_err(|e| format!("解析 config.toml 失败: {}", e))?
+    };
+
+    let profiles = root
+        .entry("profiles")
+        .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+        .as_table_mut()
+        .ok_or_else(|| "config.toml 中的 profiles 不是表".to_string())?;
+    profiles.insert(name.to_string(), toml::Value::Table(profile.clone()));
+    if let Some((key, value)) = model_provider {
+        let providers = root
+            .entry("model_providers")
+            .or_insert_with(|| toml::Value::Table(toml::Table::new()))
+            .as_table_mut()
+            .ok_or_else(|| "config.toml 中的 model_providers 不是表".to_string())?;
+        providers
+            .entry(key.clone())
+            .or_insert_with(|| value.clone());
+    }
+
+    let new_text = toml::to_string(&root).map_err(|e| format!("序列化 config.toml 失败: {}", e))?;
+    crate::config::write_text_file(&path, &new_text).map_err(Into::into)
+}
+
+/// 执行迁移：重新生成计划并按计划写入，返回实际执行的计划
+pub fn apply_migration(
+    config: &mut MultiAppConfig,
+    direction: MigrationDirection,
+    project_path: &str,
+    provider_id: Option<&str>,
+) -> Result<MigrationPlan, String> {
+    let planner = build_plan(config, direction, project_path, provider_id)?;
+    if planner.ops.is_empty() {
+        return Ok(planner.plan);
+    }
+    crate::backups::snapshot_before_write("pre-migration");
+
+    for op in &planner.ops {
+        match op {
+            MigrationOp::WriteFile { path, content } => {
+                crate::config::write_text_file(path, content)?;
+            }
+            MigrationOp::CodexMcp { servers } => {
+                for (id, spec) in servers {
+                    let entry = json!({
+                        "id": id,
+                        "name": id,
+                        "enabled": true,
+                        "server": spec,
+                    });
+                    if let Err(e) =
+                        crate::mcp::upsert_in_config_for(config, &AppType::Codex, id, entry)
+                    {
+                        log::warn!("迁移 MCP 服务器 {} 失败: {}", id, e);
+                    }
+                }
+                crate::mcp::sync_enabled_for(config, &AppType::Codex)?;
+            }
+            MigrationOp::CodexProfile {
+                name,
+                profile,
+                model_provider,
+            } => {
+                write_codex_profile(name, profile, model_provider.as_ref())?;
+            }
+        }
+    }
+
+    // 与切换流程一致：将最新的 live config.toml 回填到当前 Codex 供应商
+    if direction == MigrationDirection::ClaudeToCodex {
+        let text = crate::codex_config::read_and_validate_codex_config_text()?;
+        if let Some(manager) = config.get_manager_mut(&AppType::Codex) {
+            let current = manager.current.clone();
+            if let Some(provider) = manager.providers.get_mut(&current) {
+                if let Some(obj) = provider.settings_config.as_object_mut() {
+                    obj.insert("config".to_string(), Value::String(text));
+                }
+            }
+        }
+    }
+    Ok(planner.plan)
+}
@@ -0,0 +1,207 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tauri::Manager;
+
+use crate::app_config::AppType;
+use crate::events::AppEvent;
+use crate::store::AppState;
+
+/// 检测到新会话位于某个工作区
+pub const EVENT_WORKSPACE_DETECTED: &str = "workspace-detected";
+
+/// 会话监视器的轮询间隔
+const WATCH_INTERVAL: Duration = Duration::from_secs(10);
+/// 超过该时长未再写入的会话移出已处理集合，之后恢复活动时重新检测
+const SEEN_RETENTION: Duration = Duration::from_secs(24 * 3600);
+
+/// 工作区：一组项目目录与其绑定的供应商和规则
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Workspace {
+    pub id: String,
+    #[serde(default)]
+    pub name: String,
+    /// 项目目录（支持 ~ 前缀），会话 cwd 位于其中任一目录内即视为命中
+    #[serde(default)]
+    pub projects: Vec<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub claude_provider_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub codex_provider_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gemini_provider_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub opencode_provider_id: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub crush_provider_id: Option<String>,
+    /// 规则绑定：命中时切换到该环境配置（envProfiles 中的 id），其目录中的规则随之生效
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub env_profile_id: Option<String>,
+    /// 命中时自动切换到绑定的供应商与规则；关闭时仅提示
+    #[serde(default)]
+    pub auto_apply: bool,
+}
+
+impl Workspace {
+    pub fn provider_for(&self, app_type: &AppType) -> Option<&str> {
+        match app_type {
+            AppType::Claude => self.claude_provider_id.as_deref(),
+            AppType::Codex => self.codex_provider_id.as_deref(),
+            AppType::Gemini => self.gemini_provider_id.as_deref(),
+            AppType::OpenCode => self.opencode_provider_id.as_deref(),
+            AppType::Crush => self.crush_provider_id.as_deref(),
+        }
+    }
+}
+
+/// 工作区检测结果
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkspaceDetection {
+    pub workspace_id: String,
+    pub workspace_name: String,
+    pub app_type: String,
+    pub cwd: String,
+    pub session_file: String,
+    /// 是否已自动切换供应商
+    pub applied: bool,
+}
+
+fn expand_home(raw: &str) -> PathBuf {
+    match raw.strip_prefix("~/").or_else(|| raw.strip_prefix("~\\")) {
+        Some(rest) => dirs::home_dir()
+            .map(|home| home.join(rest))
+            .unwrap_or_else(|| PathBuf::from(raw)),
+        None if raw == "~" => dirs::home_dir().unwrap_or_else(|| PathBuf::from(raw)),
+        None => PathBuf::from(raw),
+    }
+}
+
+/// 按 cwd 查找工作区；多个命中时取项目目录最长（最具体）的一个
+pub fn match_workspace(cwd: &str) -> Option<Workspace> {
+    let cwd = Path::new(cwd.trim());
+    let mut best: Option<(usize, Workspace)> = None;
+    for workspace in crate::settings::get_settings().workspaces {
+        for project in workspace.projects.iter().map(|p| p.trim()) {
+            if project.is_empty() {
+                continue;
+            }
+            let project_path = expand_home(project);
+            if !cwd.starts_with(&project_path) {
+                continue;
+            }
+            let depth = project_path.components().count();
+            if best.as_ref().is_none_or(|(d, _)| depth > *d) {
+                best = Some((depth, workspace.clone()));
+            }
+        }
+    }
+    best.map(|(_, workspace)| workspace)
+}
+
+/// 将工作区绑定的规则与供应商应用到对应应用（已是当前的则跳过），返回是否发生切换
+async fn apply_workspace(app: &tauri::AppHandle, workspace: &Workspace) -> Result<bool, String> {
+    let mut switched = false;
+    // 先切换环境配置，随后的供应商切换写入新目录中的 live 配置
+    if let Some(profile_id) = workspace.env_profile_id.as_deref() {
+        let active = crate::settings::get_settings().active_env_profile;
+        if active.as_deref() != Some(profile_id) {
+            crate::env_profiles::switch_profile(Some(profile_id))?;
+            switched = true;
+        }
+    }
+    for app_type in AppType::all() {
+        let Some(target) = workspace.provider_for(&app_type) else {
+            continue;
+        };
+        let is_current = {
+            let state = app.state::<AppState>();
+            let config = state.config.lock().map_err(crate::error::lock_failed)?;
+            config
+                .get_manager(&app_type)
+                .map(|m| m.current == target)
+                .unwrap_or(false)
+        };
+        if !is_current {
+            crate::switch_provider_internal(
+                app,
+                app_type,
+                target.to_string(),
+                crate::switch_history::SwitchInitiator::Workspace,
+            )
+            .await?;
+            switched = true;
+        }
+    }
+    Ok(switched)
+}
+
+async fn handle_new_session(app: &tauri::AppHandle, app_type: &str, path: &Path) {
+    let Some(cwd) = crate::conversation::read_session_cwd(path) else {
+        return;
+    };
+    let Some(workspace) = match_workspace(&cwd) else {
+        return;
+    };
+
+    let applied = if workspace.auto_apply {
+        match apply_workspace(app, &workspace).await {
+            Ok(switched) => switched,
+            Err(e) => {
+                log::warn!("自动应用工作区 {} 失败: {}", workspace.id, e);
+                false
+            }
+        }
+    } else {
+        false
+    };
+
+    let detection = WorkspaceDetection {
+        workspace_id: workspace.id.clone(),
+        workspace_name: workspace.name.clone(),
+        app_type: app_type.to_string(),
+        cwd,
+        session_file: path.to_string_lossy().to_string(),
+        applied,
+    };
+    log::info!(
+        "新会话位于工作区 {}: {}",
+        detection.workspace_id,
+        detection.cwd
+    );
+    crate::events::publish(
+        AppEvent::new(EVENT_WORKSPACE_DETECTED)
+            .with_data(serde_json::to_value(&detection).unwrap_or_default()),
+    );
+}
+
+/// 启动会话监视器：轮询对话目录中新出现（或恢复活动）的会话，按其 cwd 匹配工作区
+pub fn start_session_watcher(app: tauri::AppHandle) {
+    tauri::async_runtime::spawn(async move {
+        let mut seen: HashSet<PathBuf> = HashSet::new();
+        let mut since = SystemTime::now();
+        loop {
+            tokio::time::sleep(WATCH_INTERVAL).await;
+
+            if crate::settings::get_settings().workspaces.is_empty() {
+                since = SystemTime::now();
+                continue;
+            }
+
+            let tick = SystemTime::now();
+            seen.retain(|path| {
+                std::fs::metadata(path)
+                    .and_then(|m| m.modified())
+                    .is_ok_and(|t| t.elapsed().is_ok_and(|age| age < SEEN_RETENTION))
+            });
+            for (app_type, path) in crate::conversation::recent_session_files(since) {
+                if seen.insert(path.clone()) {
+                    handle_new_session(&app, &app_type, &path).await;
+                }
+            }
+            since = tick;
+        }
+    });
+}
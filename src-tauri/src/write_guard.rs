@@ -0,0 +1,332 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sha2::{Digest, Sha256};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, OnceLock};
+use tauri::Emitter;
+
+use crate::app_config::AppType;
+use crate::config::{get_app_config_dir, read_json_file, write_json_file};
+
+/// 检测到外部修改，前端弹出“保留外部修改 / 覆盖 / 合并”选择
+pub const EVENT_WRITE_CONFLICT: &str = "write-conflict";
+
+/// 串行化读写记录文件
+static GUARD_LOCK: Mutex<()> = Mutex::new(());
+/// 尚未处理的冲突
+static CONFLICTS: Mutex<Vec<WriteConflict>> = Mutex::new(Vec::new());
+
+/// 用户选择合并后，下一次写入这些文件时执行三方合并
+fn merge_next() -> &'static Mutex<HashSet<String>> {
+    static MERGE_NEXT: OnceLock<Mutex<HashSet<String>>> = OnceLock::new();
+    MERGE_NEXT.get_or_init(|| Mutex::new(HashSet::new()))
+}
+
+/// 最近一次写入的文本内容，作为三方合并的基准。内容含 API Key，只保存在内存中，
+/// 重启后在下一次写入前无法自动合并
+fn merge_bases() -> &'static Mutex<HashMap<String, String>> {
+    static MERGE_BASES: OnceLock<Mutex<HashMap<String, String>>> = OnceLock::new();
+    MERGE_BASES.get_or_init(|| Mutex::new(HashMap::new()))
+}
+
+/// 记录文件 ~/.cc-switch/write_guard.json
+fn index_path() -> PathBuf {
+    get_app_config_dir().join("write_guard.json")
+}
+
+/// cc-switch 最近一次写入的内容摘要（不保存内容本身）
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct WrittenFile {
+    hash: String,
+    written_at: i64,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct WriteIndex {
+    #[serde(default)]
+    files: HashMap<String, WrittenFile>,
+}
+
+impl WriteIndex {
+    fn load() -> Self {
+        let path = index_path();
+        if !path.exists() {
+            return Self::default();
+        }
+        read_json_file(&path).unwrap_or_else(|e| {
+            log::warn!("读取写入记录失败: {}", e);
+            Self::default()
+        })
+    }
+
+    fn save(&self) -> Result<(), String> {
+        write_json_file(&index_path(), self).map_err(Into::into)
+    }
+}
+
+/// 外部修改冲突
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WriteConflict {
+    pub path: String,
+    pub file_name: String,
+    /// 是否支持三方合并（JSON / TOML 且本次运行中有上次写入的内容）
+    pub can_merge: bool,
+    /// 毫秒时间戳
+    pub detected_at: i64,
+}
+
+/// 冲突处理方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum ConflictResolution {
+    /// 保留外部修改，以磁盘上的内容为新基准，不再重试
+    KeepTheirs,
+    /// 接受外部修改已被覆盖，前端随后重试原操作
+    Overwrite,
+    /// 下一次写入时与外部修改做三方合并，前端随后重试原操作
+    Merge,
+}
+
+fn hash(data: &[u8]) -> String {
+    Sha256::digest(data)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+fn key(path: &Path) -> String {
+    path.to_string_lossy().to_string()
+}
+
+/// 受保护的文件：各应用的 live 配置与全局规则文件
+/// （不含 ~/.claude.json 等由客户端自身频繁改写的 MCP 文件）
+pub(crate) fn is_managed(path: &Path) -> bool {
+    AppType::all().iter().any(|app_type| {
+        let adapter = crate::app_adapter::adapter_for(app_type);
+        adapter.live_paths().iter().any(|p| p == path)
+            || adapter.rules_path().is_some_and(|p| p == path)
+    })
+}
+
+fn mergeable(path: &Path) -> bool {
+    matches!(
+        path.extension().and_then(|e| e.to_str()),
+        Some("json") | Some("toml")
+    )
+}
+
+/// 三方合并：只有一侧修改的键取修改后的值，两侧都修改时以 cc-switch 的写入为准
+fn merge_values(
+    base: Option<&Value>,
+    theirs: Option<&Value>,
+    ours: Option<&Value>,
+) -> Option<Value> {
+    if ours == base || theirs == ours {
+        return theirs.cloned();
+    }
+    if theirs == base {
+        return ours.cloned();
+    }
+    match (theirs, ours) {
+        (Some(Value::Object(theirs)), Some(Value::Object(ours))) => {
+            let base = base.and_then(|b| b.as_object());
+            let mut merged = serde_json::Map::new();
+            let keys: Vec<&String> = ours
+                .keys()
+                .chain(theirs.keys().filter(|k| !ours.contains_key(*k)))
+                .collect();
+            for key in keys {
+                let value = merge_values(
+                    base.and_then(|b| b.get(key)),
+                    theirs.get(key),
+                    ours.get(key),
+                );
+                if let Some(value) = value {
+                    merged.insert(key.clone(), value);
+                }
+            }
+            Some(Value::Object(merged))
+        }
+        _ => ours.cloned(),
+    }
+}
+
+fn parse(path: &Path, text: &str) -> Result<Value, String> {
+    if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+        let table: toml::Table = toml::from_str(text)
+            .map_err(|e| format!("解析 TOML 失败: {}: {}", path.display(), e))?;
+        serde_json::to_value(table).map_err(|e| format!("转换 TOML 失败: {}", e))
+    } else {
+        serde_json::from_str(text).map_err(|e| format!("解析 JSON 失败: {}: {}", path.display(), e))
+    }
+}
+
+fn merge_content(path: &Path, base: &str, theirs: &[u8], ours: &[u8]) -> Result<Vec<u8>, String> {
+    let base = parse(path, base)?;
+    let theirs = parse(path, &String::from_utf8_lossy(theirs))?;
+    let ours = parse(path, &String::from_utf8_lossy(ours))?;
+    let merged = merge_values(Some(&base), Some(&theirs), Some(&ours)).unwrap_or(Value::Null);
+    if path.extension().and_then(|e| e.to_str()) == Some("toml") {
+        let table: toml::Table =
+            serde_json::from_value(merged).map_err(|e| format!("合并结果无法写为 TOML: {}", e))?;
+        toml::to_string(&table)
+            .map(String::into_bytes)
+            .map_err(|e| format!("序列化 TOML 失败: {}", e))
+    } else {
+        serde_json::to_string_pretty(&merged)
+            .map(String::into_bytes)
+            .map_err(|e| format!("序列化 JSON 失败: {}", e))
+    }
+}
+
+fn report_conflict(path: &Path, can_merge: bool) -> String {
+    let conflict = WriteConflict {
+        path: key(path),
+        file_name: path
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default(),
+        can_merge,
+        detected_at: chrono::Utc::now().timestamp_millis(),
+    };
+    if let Ok(mut conflicts) = CONFLICTS.lock() {
+        conflicts.retain(|c| c.path != conflict.path);
+        conflicts.push(conflict.clone());
+    }
+    if let Some(app) = crate::events::handle() {
+        if let Err(e) = app.emit(EVENT_WRITE_CONFLICT, &conflict) {
+            log::warn!("转发写入冲突到前端失败: {}", e);
+        }
+    }
+    format!(
+        "{} 在 cc-switch 上次写入后被其他程序修改，已取消写入以免覆盖，请选择保留外部修改、覆盖或合并",
+        path.display()
+    )
+}
+
+/// 写入前检查：文件在上次写入后被外部修改时返回冲突错误；
+/// 用户已选择合并时返回合并后的内容
+pub fn before_write<'a>(path: &Path, data: &'a [u8]) -> Result<Cow<'a, [u8]>, String> {
+    if !is_managed(path) || !path.exists() {
+        return Ok(Cow::Borrowed(data));
+    }
+    let record = {
+        let _guard = GUARD_LOCK.lock().map_err(crate::error::lock_failed)?;
+        WriteIndex::load().files.remove(&key(path))
+    };
+    let Some(record) = record else {
+        return Ok(Cow::Borrowed(data));
+    };
+    let disk = fs::read(path).map_err(|e| format!("读取文件失败: {}: {}", path.display(), e))?;
+    if hash(&disk) == record.hash || disk == data {
+        return Ok(Cow::Borrowed(data));
+    }
+
+    let merge = merge_next()
+        .lock()
+        .map(|mut pending| pending.remove(&key(path)))
+        .unwrap_or(false);
+    let base = merge_bases()
+        .lock()
+        .ok()
+        .and_then(|bases| bases.get(&key(path)).cloned());
+    match base.as_deref() {
+        Some(base) if merge => {
+            let merged = merge_content(path, base, &disk, data)?;
+            log::info!("已合并外部修改: {}", path.display());
+            Ok(Cow::Owned(merged))
+        }
+        base => Err(report_conflict(path, base.is_some() && mergeable(path))),
+    }
+}
+
+fn record(path: &Path, data: &[u8]) -> Result<(), String> {
+    let _guard = GUARD_LOCK.lock().map_err(crate::error::lock_failed)?;
+    if let Ok(mut bases) = merge_bases().lock() {
+        match String::from_utf8(data.to_vec()) {
+            Ok(text) => bases.insert(key(path), text),
+            Err(_) => bases.remove(&key(path)),
+        };
+    }
+    let mut index = WriteIndex::load();
+    index.files.insert(
+        key(path),
+        WrittenFile {
+            hash: hash(data),
+            written_at: chrono::Utc::now().timestamp_millis(),
+        },
+    );
+    index.save()
+}
+
+/// 写入成功后记录内容摘要（失败仅记录日志）
+pub fn after_write(path: &Path, data: &[u8]) {
+    if !is_managed(path) {
+        return;
+    }
+    if let Err(e) = record(path, data) {
+        log::warn!("记录写入内容失败: {}", e);
+    }
+}
+
+/// 文件当前内容是否与 cc-switch 最近一次写入的内容一致（用于区分自身写入与外部修改）
+pub fn is_own_write(path: &Path) -> bool {
+    let Ok(disk) = fs::read(path) else {
+        return false;
+    };
+    let Ok(_guard) = GUARD_LOCK.lock() else {
+        return false;
+    };
+    WriteIndex::load()
+        .files
+        .get(&key(path))
+        .is_some_and(|record| record.hash == hash(&disk))
+}
+
+/// 列出尚未处理的冲突
+pub fn list_conflicts() -> Vec<WriteConflict> {
+    CONFLICTS.lock().map(|c| c.clone()).unwrap_or_default()
+}
+
+/// 处理冲突：保留或覆盖时以当前磁盘内容为新基准；合并时登记下一次写入执行三方合并
+pub fn resolve(path: &str, resolution: ConflictResolution) -> Result<(), String> {
+    let path_buf = PathBuf::from(path);
+    if !is_managed(&path_buf) {
+        return Err(format!("不是受保护的配置文件: {}", path));
+    }
+    match resolution {
+        ConflictResolution::KeepTheirs | ConflictResolution::Overwrite => {
+            let disk = if path_buf.exists() {
+                fs::read(&path_buf).map_err(|e| format!("读取文件失败: {}: {}", path, e))?
+            } else {
+                Vec::new()
+            };
+            record(&path_buf, &disk)?;
+        }
+        ConflictResolution::Merge => {
+            if !mergeable(&path_buf) {
+                return Err(format!("该文件类型不支持自动合并: {}", path));
+            }
+            let has_base = merge_bases()
+                .lock()
+                .map(|bases| bases.contains_key(path))
+                .unwrap_or(false);
+            if !has_base {
+                return Err(format!("缺少上次写入的内容，无法自动合并: {}", path));
+            }
+            merge_next()
+                .lock()
+                .map_err(crate::error::lock_failed)?
+                .insert(path.to_string());
+        }
+    }
+    if let Ok(mut conflicts) = CONFLICTS.lock() {
+        conflicts.retain(|c| c.path != path);
+    }
+    Ok(())
+}
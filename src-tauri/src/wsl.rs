@@ -0,0 +1,141 @@
+use serde::{Deserialize, Serialize};
+use std::path::PathBuf;
+
+/// WSL 模式：Claude / Codex / Gemini 的配置目录位于指定发行版的用户主目录中
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+#[serde(rename_all = "camelCase")]
+pub struct WslSettings {
+    #[serde(default)]
+    pub enabled: bool,
+    /// 发行版名称，如 Ubuntu-22.04
+    #[serde(default)]
+    pub distro: String,
+    /// 发行版内的用户名
+    #[serde(default)]
+    pub user: String,
+}
+
+/// 发行版中某个用户的配置目录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct WslHome {
+    pub distro: String,
+    pub user: String,
+    pub home_dir: String,
+    pub claude_dir: Option<String>,
+    pub codex_dir: Option<String>,
+    pub gemini_dir: Option<String>,
+}
+
+/// 发行版根目录：优先使用 \\wsl.localhost\<distro>，旧版系统回落到 \\wsl$\<distro>
+fn distro_root(distro: &str) -> PathBuf {
+    let localhost = PathBuf::from(format!(r"\\wsl.localhost\{}", distro));
+    if localhost.is_dir() {
+        return localhost;
+    }
+    PathBuf::from(format!(r"\\wsl$\{}", distro))
+}
+
+/// 发行版中用户的主目录（root 用户为 /root）
+pub fn home_path(distro: &str, user: &str) -> PathBuf {
+    let root = distro_root(distro);
+    if user == "root" {
+        root.join("root")
+    } else {
+        root.join("home").join(user)
+    }
+}
+
+/// wsl.exe 的输出为 UTF-16LE（部分版本设置 WSL_UTF8=1 后为 UTF-8）
+#[cfg_attr(not(windows), allow(dead_code))]
+fn decode_wsl_output(bytes: &[u8]) -> String {
+    let looks_utf16 = bytes.len() >= 2 && bytes.iter().skip(1).step_by(2).all(|b| *b == 0);
+    if looks_utf16 {
+        let units: Vec<u16> = bytes
+            .chunks_exact(2)
+            .map(|c| u16::from_le_bytes([c[0], c[1]]))
+            .collect();
+        String::from_utf16_lossy(&units)
+    } else {
+        String::from_utf8_lossy(bytes).into_owned()
+    }
+}
+
+/// 列出已安装的 WSL 发行版；非 Windows 系统返回空列表
+pub fn list_distros() -> Result<Vec<String>, String> {
+    #[cfg(windows)]
+    {
+        use std::os::windows::process::CommandExt;
+
+        let output = std::process::Command::new("wsl.exe")
+            .args(["--list", "--quiet"])
+            // CREATE_NO_WINDOW：不弹出控制台窗口
+            .creation_flags(0x0800_0000)
+            .output()
+            .map_err(|e| format!("执行 wsl.exe 失败（请确认已安装 WSL）: {}", e))?;
+        if !output.status.success() {
+            return Err(format!(
+                "列出 WSL 发行版失败: {}",
+                decode_wsl_output(&output.stderr).trim()
+            ));
+        }
+        Ok(decode_wsl_output(&output.stdout)
+            .lines()
+            .map(|line| line.trim_matches(|c: char| c.is_whitespace() || c == '\0'))
+            .filter(|line| !line.is_empty())
+            .map(|line| line.to_string())
+            .collect())
+    }
+    #[cfg(not(windows))]
+    {
+        Ok(Vec::new())
+    }
+}
+
+/// 查找发行版中各用户主目录下的 .claude / .codex / .gemini 目录
+pub fn discover_homes(distro: &str) -> Result<Vec<WslHome>, String> {
+    let distro = distro.trim();
+    if distro.is_empty() {
+        return Err("发行版名称不能为空".to_string());
+    }
+    let root = distro_root(distro);
+    if !root.is_dir() {
+        return Err(format!(
+            "无法访问 WSL 发行版 {}（请确认发行版正在运行）",
+            distro
+        ));
+    }
+
+    let mut users: Vec<String> = std::fs::read_dir(root.join("home"))
+        .map(|entries| {
+            entries
+                .flatten()
+                .filter(|e| e.path().is_dir())
+                .map(|e| e.file_name().to_string_lossy().to_string())
+                .collect()
+        })
+        .unwrap_or_default();
+    users.sort();
+    users.push("root".to_string());
+
+    let existing = |dir: PathBuf| dir.is_dir().then(|| dir.to_string_lossy().to_string());
+    Ok(users
+        .into_iter()
+        .filter_map(|user| {
+            let home = home_path(distro, &user);
+            let found = WslHome {
+                distro: distro.to_string(),
+                home_dir: home.to_string_lossy().to_string(),
+                claude_dir: existing(home.join(".claude")),
+                codex_dir: existing(home.join(".codex")),
+                gemini_dir: existing(home.join(".gemini")),
+                user,
+            };
+            // root 目录通常无权访问，只有找到配置目录时才列出
+            let has_config = found.claude_dir.is_some()
+                || found.codex_dir.is_some()
+                || found.gemini_dir.is_some();
+            (found.user != "root" || has_config).then_some(found)
+        })
+        .collect())
+}
@@ -1,6 +1,6 @@
 import { useState, useEffect, useRef } from "react";
 import { useTranslation } from "react-i18next";
-import { Provider } from "./types";
+import { DeepLinkProvider, Provider } from "./types";
 import { AppType } from "./lib/tauri-api";
 import ProviderList from "./components/ProviderList";
 import AddProviderModal from "./components/AddProviderModal";
@@ -42,6 +42,10 @@ function App() {
   const [isMcpOpen, setIsMcpOpen] = useState(false);
   const [isConversationOpen, setIsConversationOpen] = useState(false);
   const [isGlobalRulesOpen, setIsGlobalRulesOpen] = useState(false);
+  // 用户确认后用于预填添加表单的链接草稿
+  const [deepLinkDraft, setDeepLinkDraft] = useState<DeepLinkProvider | null>(
+    null,
+  );
   const timeoutRef = useRef<ReturnType<typeof setTimeout> | null>(null);
 
   // 设置通知的辅助函数
@@ -84,6 +88,115 @@ function App() {
     };
   }, []);
 
+  // 启动自检：配置文件损坏时提示从最新备份恢复
+  const checkIntegrity = async () => {
+    const report = await window.api.getStartupCheck();
+    const issue = report.issues[0];
+    if (!issue) return;
+    if (!issue.backup) {
+      showNotification(
+        t("recovery.noBackup", { file: issue.path, error: issue.error }),
+        "error",
+        10000,
+      );
+      return;
+    }
+    setConfirmDialog({
+      isOpen: true,
+      title: t("recovery.title"),
+      message: t("recovery.message", {
+        file: issue.path,
+        error: issue.error,
+        time: new Date(issue.backup.createdAt * 1000).toLocaleString(),
+      }),
+      onConfirm: async () => {
+        setConfirmDialog(null);
+        try {
+          await window.api.recoverConfigFile(issue.target, "restoreBackup");
+          await loadProviders();
+          await window.api.updateTrayMenu();
+          showNotification(t("recovery.restored"), "success");
+          // 继续处理其余损坏的文件
+          await checkIntegrity();
+        } catch (error) {
+          showNotification(
+            t("recovery.failed", { error: extractErrorMessage(error) }),
+            "error",
+            6000,
+          );
+        }
+      },
+    });
+  };
+
+  useEffect(() => {
+    void checkIntegrity();
+  }, []);
+
+  // 收到 cc-switch:// 添加供应商链接：先确认，再打开预填的添加表单
+  const promptDeepLink = (draft: DeepLinkProvider) => {
+    const appName = t(`apps.${draft.appType}`, {
+      defaultValue: draft.appType,
+    });
+    const notes = [
+      !draft.hasApiKey && t("deepLink.noApiKey"),
+      draft.duplicateOf && t("deepLink.duplicate"),
+    ].filter(Boolean);
+    setConfirmDialog({
+      isOpen: true,
+      title: t("deepLink.confirmTitle"),
+      message: [
+        t("deepLink.confirmMessage", { name: draft.provider.name, appName }),
+        ...notes,
+      ].join(" "),
+      onConfirm: () => {
+        setConfirmDialog(null);
+        setActiveApp(draft.appType as AppType);
+        setDeepLinkDraft(draft);
+      },
+    });
+  };
+
+  useEffect(() => {
+    let cancelled = false;
+    const unlisteners: Array<() => void> = [];
+
+    const setupListener = async () => {
+      try {
+        const unlistenProvider = await window.api.onDeepLinkProvider(
+          (draft) => {
+            // 事件已送达，清除后端暂存的同一草稿
+            void window.api.takePendingDeepLink();
+            promptDeepLink(draft);
+          },
+        );
+        const unlistenError = await window.api.onDeepLinkError((error) => {
+          showNotification(t("deepLink.invalid", { error }), "error", 6000);
+        });
+        unlisteners.push(unlistenProvider, unlistenError);
+        if (cancelled) {
+          unlisteners.forEach((unlisten) => unlisten());
+          return;
+        }
+      } catch (error) {
+        console.error("[App] Failed to listen for deep links", error);
+      }
+
+      // 冷启动时链接先于前端就绪到达，主动拉取
+      const pending = await window.api.takePendingDeepLink();
+      if (pending && !cancelled) {
+        promptDeepLink(pending);
+      }
+    };
+
+    void setupListener();
+
+    return () => {
+      cancelled = true;
+      unlisteners.forEach((unlisten) => unlisten());
+    };
+  }, []);
+
   // 监听托盘切换事件（包括菜单切换）
   useEffect(() => {
     let unlisten: (() => void) | null = null;
@@ -150,6 +263,34 @@ function App() {
     await window.api.updateTrayMenu();
   };
 
+  const handleAddDeepLinkProvider = async (
+    provider: Omit<Provider, "id">,
+  ) => {
+    if (!deepLinkDraft) return;
+    const app = deepLinkDraft.appType as AppType;
+    try {
+      await window.api.addProvider(
+        { ...provider, id: generateId(), createdAt: Date.now() },
+        app,
+      );
+      setDeepLinkDraft(null);
+      if (app === activeApp) {
+        await loadProviders();
+      }
+      showNotification(
+        t("deepLink.imported", { name: provider.name }),
+        "success",
+      );
+      await window.api.updateTrayMenu();
+    } catch (error) {
+      showNotification(
+        t("notifications.saveFailed", { error: extractErrorMessage(error) }),
+        "error",
+        6000,
+      );
+    }
+  };
+
   const handleEditProvider = async (provider: Provider) => {
     try {
       await window.api.updateProvider(provider, activeApp);
@@ -392,6 +533,15 @@ function App() {
         />
       )}
 
+      {deepLinkDraft && (
+        <AddProviderModal
+          appType={deepLinkDraft.appType as AppType}
+          initialData={deepLinkDraft.provider}
+          onAdd={handleAddDeepLinkProvider}
+          onClose={() => setDeepLinkDraft(null)}
+        />
+      )}
+
       {editingProviderId && providers[editingProviderId] && (
         <EditProviderModal
           appType={activeApp}
@@ -6,12 +6,15 @@ import ProviderForm from "./ProviderForm";
 
 interface AddProviderModalProps {
   appType: AppType;
+  // 预填内容（例如来自 cc-switch:// 链接的草稿）
+  initialData?: Provider;
   onAdd: (provider: Omit<Provider, "id">) => void;
   onClose: () => void;
 }
 
 const AddProviderModal: React.FC<AddProviderModalProps> = ({
   appType,
+  initialData,
   onAdd,
   onClose,
 }) => {
@@ -27,7 +30,8 @@ const AddProviderModal: React.FC<AddProviderModalProps> = ({
       appType={appType}
       title={title}
       submitText={t("common.add")}
-      showPresets={true}
+      initialData={initialData}
+      showPresets={!initialData}
       onSubmit={onAdd}
       onClose={onClose}
     />
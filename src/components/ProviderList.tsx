@@ -2,9 +2,10 @@ import React, { useState } from "react";
 import { useTranslation } from "react-i18next";
 import { Provider, UsageScript } from "../types";
 import { AppType } from "../lib/tauri-api";
-import { Play, Edit3, Trash2, CheckCircle2, Users, Check, BarChart3, GripVertical } from "lucide-react";
+import { Play, Edit3, Trash2, CheckCircle2, Users, Check, BarChart3, GripVertical, KeyRound } from "lucide-react";
 import { buttonStyles, badgeStyles, cn } from "../lib/styles";
 import UsageFooter from "./UsageFooter";
+import QuotaBadge from "./QuotaBadge";
 import UsageScriptModal from "./UsageScriptModal";
 import {
   DndContext,
@@ -128,6 +129,15 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
               <CheckCircle2 size={12} />
               {t("provider.currentlyUsing")}
             </div>
+            {provider.meta?.needs_key && (
+              <div
+                className={badgeStyles.warning}
+                title={t("provider.needsKeyHint")}
+              >
+                <KeyRound size={12} />
+                {t("provider.needsKey")}
+              </div>
+            )}
           </div>
 
           <div className="flex items-center gap-2 text-sm">
@@ -152,18 +162,23 @@ const SortableProviderItem: React.FC<SortableProviderItemProps> = ({
                 {apiUrl}
               </span>
             )}
+            <QuotaBadge
+              providerId={provider.id}
+              appType={appType}
+              quotaEnabled={!!provider.meta?.quota_endpoint}
+            />
           </div>
         </div>
 
         <div className="flex items-center gap-2 ml-4">
           <button
             onClick={() => onSwitch(provider.id)}
-            disabled={isCurrent}
+            disabled={isCurrent || !!provider.meta?.needs_key}
             className={cn(
               "inline-flex items-center gap-1.5 px-3 py-1.5 text-sm font-medium rounded-md transition-colors w-[90px] justify-center whitespace-nowrap",
               isCurrent
                 ? "bg-gray-100 text-gray-400 dark:bg-gray-800 dark:text-gray-500 cursor-not-allowed"
-                : "bg-blue-500 text-white hover:bg-blue-600 dark:bg-blue-600 dark:hover:bg-blue-700",
+                : "bg-blue-500 text-white hover:bg-blue-600 dark:bg-blue-600 dark:hover:bg-blue-700 disabled:opacity-50 disabled:cursor-not-allowed",
             )}
           >
             {isCurrent ? <Check size={14} /> : <Play size={14} />}
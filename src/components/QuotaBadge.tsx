@@ -0,0 +1,89 @@
+import React, { useEffect, useState } from "react";
+import { useTranslation } from "react-i18next";
+import { ProviderQuota } from "../types";
+import { AppType } from "../lib/tauri-api";
+import { Wallet, AlertCircle } from "lucide-react";
+
+interface QuotaBadgeProps {
+  providerId: string;
+  appType: AppType;
+  quotaEnabled: boolean; // 是否配置了额度查询接口
+}
+
+const formatAmount = (value: number) =>
+  Number.isInteger(value) ? String(value) : value.toFixed(2);
+
+const QuotaBadge: React.FC<QuotaBadgeProps> = ({
+  providerId,
+  appType,
+  quotaEnabled,
+}) => {
+  const { t } = useTranslation();
+  const [quota, setQuota] = useState<ProviderQuota | null>(null);
+  const [loading, setLoading] = useState(false);
+
+  const fetchQuota = async (refresh = false) => {
+    if (loading) return;
+    setLoading(true);
+    try {
+      setQuota(await window.api.getProviderQuota(providerId, appType, refresh));
+    } catch (error) {
+      console.error("查询额度失败:", error);
+    } finally {
+      setLoading(false);
+    }
+  };
+
+  useEffect(() => {
+    if (quotaEnabled) {
+      // 优先使用缓存，避免每次渲染列表都请求中转站
+      fetchQuota(false);
+    } else {
+      setQuota(null);
+    }
+  }, [providerId, appType, quotaEnabled]);
+
+  if (!quotaEnabled || !quota) return null;
+
+  const updatedAt = new Date(quota.fetchedAt * 1000).toLocaleString();
+
+  if (quota.error) {
+    return (
+      <button
+        onClick={() => fetchQuota(true)}
+        disabled={loading}
+        className="inline-flex items-center gap-1 text-xs text-red-500 dark:text-red-400 disabled:opacity-50"
+        title={t("quota.errorTitle", { error: quota.error, time: updatedAt })}
+      >
+        <AlertCircle size={12} />
+        {t("quota.fetchFailed")}
+      </button>
+    );
+  }
+
+  const remaining = quota.remaining ?? 0;
+  const low =
+    quota.total !== undefined && quota.total > 0
+      ? remaining / quota.total < 0.1
+      : remaining <= 0;
+
+  return (
+    <button
+      onClick={() => fetchQuota(true)}
+      disabled={loading}
+      className={`inline-flex items-center gap-1 text-xs disabled:opacity-50 ${
+        low
+          ? "text-orange-500 dark:text-orange-400"
+          : "text-gray-500 dark:text-gray-400"
+      }`}
+      title={t("quota.updatedAt", { time: updatedAt })}
+    >
+      <Wallet size={12} />
+      {formatAmount(remaining)}
+      {quota.total !== undefined && ` / ${formatAmount(quota.total)}`}
+      {quota.unit && ` ${quota.unit}`}
+    </button>
+  );
+};
+
+export default QuotaBadge;
@@ -257,6 +257,14 @@ export default function SettingsModal({
       } catch (error) {
         console.warn("[Settings] Failed to persist language preference", error);
       }
+      if (initialLanguage !== selectedLanguage) {
+        // 托盘菜单文案由后端按设置语言生成，语言变化后重建
+        void window.api
+          .updateTrayMenu()
+          .catch((error) =>
+            console.error("[SettingsModal] Failed to refresh tray menu", error),
+          );
+      }
       setInitialLanguage(selectedLanguage);
       if (i18n.language !== selectedLanguage) {
         void i18n.changeLanguage(selectedLanguage);
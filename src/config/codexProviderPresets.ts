@@ -81,4 +81,31 @@ export const codexProviderPresets: CodexProviderPreset[] = [
       "https://codex-api-hk-cdn.packycode.com/v1",
     ],
   },
+  {
+    name: "Ollama (Local)",
+    websiteUrl: "https://ollama.com",
+    category: "local",
+    // 本地服务无需鉴权
+    auth: {},
+    config: `model_provider = "ollama"
+model = "gpt-oss:20b"
+
+[model_providers.ollama]
+name = "Ollama"
+base_url = "http://localhost:11434/v1"
+wire_api = "chat"`,
+  },
+  {
+    name: "LM Studio (Local)",
+    websiteUrl: "https://lmstudio.ai",
+    category: "local",
+    auth: {},
+    config: `model_provider = "lmstudio"
+model = "openai/gpt-oss-20b"
+
+[model_providers.lmstudio]
+name = "LM Studio"
+base_url = "http://localhost:1234/v1"
+wire_api = "chat"`,
+  },
 ];
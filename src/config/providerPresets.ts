@@ -165,4 +165,29 @@ export const providerPresets: ProviderPreset[] = [
     ],
     category: "third_party",
   },
+  {
+    name: "Ollama (Local)",
+    websiteUrl: "https://ollama.com",
+    settingsConfig: {
+      env: {
+        ANTHROPIC_BASE_URL: "http://localhost:11434",
+        // 本地服务无需鉴权，占位值仅用于通过 Claude Code 校验
+        ANTHROPIC_AUTH_TOKEN: "ollama",
+        ANTHROPIC_MODEL: "qwen3-coder",
+        ANTHROPIC_SMALL_FAST_MODEL: "qwen3-coder",
+      },
+    },
+    category: "local",
+  },
+  {
+    name: "LM Studio (Local)",
+    websiteUrl: "https://lmstudio.ai",
+    settingsConfig: {
+      env: {
+        ANTHROPIC_BASE_URL: "http://localhost:1234",
+        ANTHROPIC_AUTH_TOKEN: "lm-studio",
+      },
+    },
+    category: "local",
+  },
 ];
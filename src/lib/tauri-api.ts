@@ -1,4 +1,4 @@
-import { invoke } from "@tauri-apps/api/core";
+import { invoke as tauriInvoke, type InvokeArgs } from "@tauri-apps/api/core";
 import { listen, UnlistenFn } from "@tauri-apps/api/event";
 import {
   Provider,
@@ -9,10 +9,54 @@ import {
   McpServerSpec,
   McpConfigResponse,
   ConversationMeta,
+  ConversationTailStart,
+  ConversationSearchHit,
+  ConversationSearchOptions,
+  ConversationExportFormat,
+  DerivedConversation,
+  TrimSpec,
+  MergeStrategy,
+  ConversationIntegrityReport,
+  ConversationTailEvent,
+  ProjectStats,
 } from "../types";
 
+// 后端结构化错误（AppError 序列化为 { code, message, path? }）
+export class BackendError extends Error {
+  code: import("../types").AppErrorCode;
+  path?: string;
+
+  constructor(payload: import("../types").AppErrorPayload) {
+    super(payload.message);
+    this.name = "BackendError";
+    this.code = payload.code;
+    this.path = payload.path;
+  }
+
+  toString() {
+    return this.message;
+  }
+}
+
+const isAppErrorPayload = (
+  error: unknown,
+): error is import("../types").AppErrorPayload =>
+  !!error &&
+  typeof error === "object" &&
+  typeof (error as Record<string, unknown>).code === "string" &&
+  typeof (error as Record<string, unknown>).message === "string";
+
+// 统一调用入口：结构化错误转换为 BackendError，字符串错误保持原样
+const invoke = async <T>(cmd: string, args?: InvokeArgs): Promise<T> => {
+  try {
+    return await tauriInvoke<T>(cmd, args);
+  } catch (error) {
+    throw isAppErrorPayload(error) ? new BackendError(error) : error;
+  }
+};
+
 // 应用类型
-export type AppType = "claude" | "codex";
+export type AppType = "claude" | "codex" | "gemini" | "opencode" | "crush";
 
 // 定义配置状态类型
 interface ConfigStatus {
@@ -93,12 +137,14 @@ export const tauriAPI = {
   switchProvider: async (
     providerId: string,
     app?: AppType,
+    force?: boolean,
   ): Promise<boolean> => {
     try {
       return await invoke("switch_provider", {
         id: providerId,
         app_type: app,
         app,
+        force,
       });
     } catch (error) {
       // 让调用方拿到后端的详细错误信息
@@ -107,6 +153,23 @@ export const tauriAPI = {
     }
   },
 
+  // 换用供应商的下一个 API Key
+  cycleProviderKey: async (
+    providerId: string,
+    app?: AppType,
+  ): Promise<import("../types").ActiveKey> => {
+    try {
+      return await invoke("cycle_provider_key", {
+        providerId,
+        app_type: app,
+        app,
+      });
+    } catch (error) {
+      console.error("切换 API Key 失败:", error);
+      throw error;
+    }
+  },
+
   // 导入当前配置为默认供应商
   importCurrentConfigAsDefault: async (
     app?: AppType,
@@ -210,6 +273,320 @@ export const tauriAPI = {
     }
   },
 
+  // 列出应用的配置目录（默认目录与已登记的多个目录）
+  listAppHomes: async (
+    appType: "claude" | "codex",
+  ): Promise<import("../types").AppHomeStatus[]> => {
+    try {
+      return await invoke("list_app_homes", { appType });
+    } catch (error) {
+      console.error("列出配置目录失败:", error);
+      throw error;
+    }
+  },
+
+  // 选择切换供应商时写入的配置目录，name 为空时回到默认目录
+  selectAppHome: async (
+    appType: "claude" | "codex",
+    name?: string,
+  ): Promise<boolean> => {
+    try {
+      return await invoke("select_app_home", { appType, name });
+    } catch (error) {
+      console.error("选择配置目录失败:", error);
+      throw error;
+    }
+  },
+
+  // 切换环境配置（成组替换 Claude / Codex 配置目录），id 为空时回到默认目录
+  switchEnvProfile: async (id?: string): Promise<boolean> => {
+    try {
+      return await invoke("switch_env_profile", { id });
+    } catch (error) {
+      console.error("切换环境配置失败:", error);
+      throw error;
+    }
+  },
+
+  // 列出已安装的 WSL 发行版（仅 Windows）
+  listWslDistros: async (): Promise<string[]> => {
+    try {
+      return await invoke("list_wsl_distros");
+    } catch (error) {
+      console.error("列出 WSL 发行版失败:", error);
+      throw error;
+    }
+  },
+
+  // 查找 WSL 发行版中各用户的 .claude / .codex / .gemini 目录
+  discoverWslHomes: async (
+    distro: string,
+  ): Promise<import("../types").WslHome[]> => {
+    try {
+      return await invoke("discover_wsl_homes", { distro });
+    } catch (error) {
+      console.error("查找 WSL 配置目录失败:", error);
+      throw error;
+    }
+  },
+
+  // 探测 claude / codex CLI 是否可用
+  probeCliVersions: async (): Promise<import("../types").CliProbe[]> => {
+    try {
+      return await invoke("probe_cli_versions");
+    } catch (error) {
+      console.error("探测 CLI 失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取后台事件日志（最新在前）
+  listNotifications: async (
+    limit?: number,
+  ): Promise<import("../types").NotificationRecord[]> => {
+    try {
+      return await invoke("list_notifications", { limit });
+    } catch (error) {
+      console.error("读取事件日志失败:", error);
+      return [];
+    }
+  },
+
+  // 读取审计日志（最新在前）
+  getAuditLog: async (
+    filter?: import("../types").AuditFilter,
+  ): Promise<import("../types").AuditEntry[]> => {
+    try {
+      return await invoke("get_audit_log", { filter });
+    } catch (error) {
+      console.error("读取审计日志失败:", error);
+      return [];
+    }
+  },
+
+  // 读取启动自检结果
+  getStartupCheck: async (): Promise<import("../types").StartupReport> => {
+    try {
+      return await invoke("get_startup_check");
+    } catch (error) {
+      console.error("读取启动自检结果失败:", error);
+      return { checkedAt: 0, issues: [] };
+    }
+  },
+
+  // 处理损坏的配置文件：从最新备份恢复或重置
+  recoverConfigFile: async (
+    target: import("../types").CheckTarget,
+    action: "restoreBackup" | "reset",
+  ): Promise<import("../types").StartupReport> => {
+    try {
+      return await invoke("recover_config_file", { target, action });
+    } catch (error) {
+      console.error("恢复配置文件失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取最近的应用日志（最新在前）
+  getRecentLogs: async (
+    limit?: number,
+    minLevel?: import("../types").LogLevel,
+  ): Promise<import("../types").LogEntry[]> => {
+    try {
+      return await invoke("get_recent_logs", { limit, minLevel });
+    } catch (error) {
+      console.error("读取应用日志失败:", error);
+      return [];
+    }
+  },
+
+  // 打开日志目录
+  openLogFolder: async (): Promise<void> => {
+    try {
+      await invoke("open_log_folder");
+    } catch (error) {
+      console.error("打开日志目录失败:", error);
+    }
+  },
+
+  // Claude todos / shell-snapshots 缓存汇总
+  listClaudeCaches: async (): Promise<
+    import("../types").ClaudeCacheSummary[]
+  > => {
+    try {
+      return await invoke("list_claude_caches");
+    } catch (error) {
+      console.error("统计缓存失败:", error);
+      return [];
+    }
+  },
+
+  // 列出缓存目录中的文件
+  getClaudeCacheFiles: async (
+    kind: import("../types").ClaudeCacheKind,
+  ): Promise<import("../types").ClaudeCacheFile[]> => {
+    try {
+      return await invoke("get_claude_cache_files", { kind });
+    } catch (error) {
+      console.error("列出缓存文件失败:", error);
+      return [];
+    }
+  },
+
+  // 清理缓存（可保留最近 keepDays 天）
+  purgeClaudeCache: async (
+    kind: import("../types").ClaudeCacheKind,
+    keepDays?: number,
+  ): Promise<import("../types").ClaudeCachePurge> => {
+    try {
+      return await invoke("purge_claude_cache", { kind, keepDays });
+    } catch (error) {
+      console.error("清理缓存失败:", error);
+      throw error;
+    }
+  },
+
+  // Codex 输入历史与日志的大小
+  listCodexCaches: async (): Promise<
+    import("../types").CodexCacheSummary[]
+  > => {
+    try {
+      return await invoke("list_codex_caches");
+    } catch (error) {
+      console.error("统计缓存失败:", error);
+      return [];
+    }
+  },
+
+  // 截断或清空 Codex 输入历史/日志（不可撤销，调用前需经用户确认）
+  purgeCodexCache: async (
+    target: import("../types").CodexCacheTarget,
+    action: import("../types").CodexCacheAction,
+    confirm: boolean,
+  ): Promise<import("../types").CodexCachePurge> => {
+    try {
+      return await invoke("purge_codex_cache", { target, action, confirm });
+    } catch (error) {
+      console.error("清理缓存失败:", error);
+      throw error;
+    }
+  },
+
+  // 清空后台事件日志
+  clearNotifications: async (): Promise<boolean> => {
+    try {
+      return await invoke("clear_notifications");
+    } catch (error) {
+      console.error("清空事件日志失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取 Claude 登录状态
+  getClaudeAuthStatus: async (): Promise<import("../types").ClaudeAuthStatus> => {
+    try {
+      return await invoke("get_claude_auth_status");
+    } catch (error) {
+      console.error("读取 Claude 登录状态失败:", error);
+      throw error;
+    }
+  },
+
+  // 退出 Claude OAuth 登录
+  logoutClaudeOauth: async (): Promise<import("../types").ClaudeAuthStatus> => {
+    try {
+      return await invoke("logout_claude_oauth");
+    } catch (error) {
+      console.error("退出 Claude 登录失败:", error);
+      throw error;
+    }
+  },
+
+  // 测试远程主机连接，返回远程主目录
+  testRemoteConnection: async (profileId: string): Promise<string> => {
+    try {
+      return await invoke("test_remote_connection", { profileId });
+    } catch (error) {
+      console.error("连接远程主机失败:", error);
+      throw error;
+    }
+  },
+
+  // 列出远程主机上的对话
+  listRemoteConversations: async (
+    profileId: string,
+    appType: "claude" | "codex",
+  ): Promise<import("../types").RemoteConversation[]> => {
+    try {
+      return await invoke("list_remote_conversations", { profileId, appType });
+    } catch (error) {
+      console.error("列出远程对话失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取远程对话文件内容
+  readRemoteConversation: async (
+    profileId: string,
+    filePath: string,
+  ): Promise<string> => {
+    try {
+      return await invoke("read_remote_conversation", { profileId, filePath });
+    } catch (error) {
+      console.error("读取远程对话失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取远程全局规则
+  readRemoteRules: async (
+    profileId: string,
+    appType: "claude" | "codex",
+  ): Promise<string> => {
+    try {
+      return await invoke("read_remote_rules", { profileId, appType });
+    } catch (error) {
+      console.error("读取远程规则失败:", error);
+      throw error;
+    }
+  },
+
+  // 写入远程全局规则
+  writeRemoteRules: async (
+    profileId: string,
+    appType: "claude" | "codex",
+    content: string,
+  ): Promise<boolean> => {
+    try {
+      return await invoke("write_remote_rules", {
+        profileId,
+        appType,
+        content,
+      });
+    } catch (error) {
+      console.error("写入远程规则失败:", error);
+      throw error;
+    }
+  },
+
+  // 将本地供应商写入远程主机的 live 配置
+  switchRemoteProvider: async (
+    profileId: string,
+    appType: "claude" | "codex",
+    providerId: string,
+  ): Promise<boolean> => {
+    try {
+      return await invoke("switch_remote_provider", {
+        profileId,
+        appType,
+        providerId,
+      });
+    } catch (error) {
+      console.error("切换远程供应商失败:", error);
+      throw error;
+    }
+  },
+
   // 重启应用程序
   restartApp: async (): Promise<boolean> => {
     try {
@@ -239,6 +616,83 @@ export const tauriAPI = {
     }
   },
 
+  // 获取便携模式状态
+  getPortableInfo: async (): Promise<import("../types").PortableInfo> => {
+    return await invoke("get_portable_info");
+  },
+
+  // 启用便携模式（重启后生效），dataDir 可为相对可执行文件目录的路径
+  enablePortableMode: async (
+    dataDir?: string,
+    copyExisting?: boolean,
+  ): Promise<import("../types").PortableInfo> => {
+    try {
+      return await invoke("enable_portable_mode", { dataDir, copyExisting });
+    } catch (error) {
+      console.error("启用便携模式失败:", error);
+      throw error;
+    }
+  },
+
+  // 关闭便携模式（重启后生效）
+  disablePortableMode: async (): Promise<boolean> => {
+    try {
+      return await invoke("disable_portable_mode");
+    } catch (error) {
+      console.error("关闭便携模式失败:", error);
+      throw error;
+    }
+  },
+
+  // 扫描其他供应商切换工具的默认配置位置
+  scanExternalProviders: async (): Promise<
+    import("../types").ExternalToolSource[]
+  > => {
+    try {
+      return await invoke("scan_external_providers");
+    } catch (error) {
+      console.error("扫描其他工具配置失败:", error);
+      throw error;
+    }
+  },
+
+  // 解析其他工具的配置文件为候选供应商（tool 为空时自动识别格式）
+  parseExternalProviders: async (
+    path: string,
+    tool?: import("../types").ExternalTool,
+  ): Promise<import("../types").ProviderCandidate[]> => {
+    try {
+      return await invoke("parse_external_providers", { path, tool });
+    } catch (error) {
+      console.error("解析其他工具配置失败:", error);
+      throw error;
+    }
+  },
+
+  // 首次运行向导：检测 ~/.claude 与 ~/.codex 的现有配置
+  detectExistingSetup: async (): Promise<
+    import("../types").ExistingSetupReport
+  > => {
+    try {
+      return await invoke("detect_existing_setup");
+    } catch (error) {
+      console.error("检测现有配置失败:", error);
+      throw error;
+    }
+  },
+
+  // 首次运行向导：一键导入现有供应商与 MCP 服务器
+  importExistingSetup: async (
+    includeEnv?: boolean,
+  ): Promise<import("../types").SetupImportResult> => {
+    try {
+      return await invoke("import_existing_setup", { includeEnv });
+    } catch (error) {
+      console.error("导入现有配置失败:", error);
+      throw error;
+    }
+  },
+
   // 获取应用配置文件路径
   getAppConfigPath: async (): Promise<string> => {
     try {
@@ -316,6 +770,25 @@ export const tauriAPI = {
     }
   },
 
+  // 查询供应商余额 / 额度（refresh 为 true 时忽略缓存）
+  getProviderQuota: async (
+    providerId: string,
+    app: AppType,
+    refresh = false
+  ): Promise<import("../types").ProviderQuota> => {
+    try {
+      return await invoke("get_provider_quota", {
+        id: providerId,
+        app_type: app,
+        app: app,
+        appType: app,
+        refresh,
+      });
+    } catch (error) {
+      throw new Error(`查询额度失败: ${String(error)}`);
+    }
+  },
+
   // Claude MCP：获取状态（用户级 ~/.claude.json）
   getClaudeMcpStatus: async (): Promise<McpStatus> => {
     try {
@@ -665,6 +1138,52 @@ export const tauriAPI = {
     return unlisten;
   },
 
+  // 取出冷启动时（前端就绪前）收到的 cc-switch:// 链接草稿
+  takePendingDeepLink: async (): Promise<
+    import("../types").DeepLinkProvider | null
+  > => {
+    try {
+      return await invoke("take_pending_deep_link");
+    } catch (error) {
+      console.error("读取待处理链接失败:", error);
+      return null;
+    }
+  },
+
+  // 解析 cc-switch:// 添加供应商链接（不写入配置）
+  parseProviderLink: async (
+    link: string,
+  ): Promise<import("../types").DeepLinkProvider> => {
+    try {
+      return await invoke("parse_provider_link", { link });
+    } catch (error) {
+      console.error("解析链接失败:", error);
+      throw error;
+    }
+  },
+
+  // 监听收到的添加供应商链接
+  onDeepLinkProvider: async (
+    callback: (draft: import("../types").DeepLinkProvider) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen("deep-link-provider", (event) => {
+      try {
+        callback(event.payload as import("../types").DeepLinkProvider);
+      } catch (e) {
+        console.error("处理 deep-link-provider 事件失败: ", e);
+      }
+    });
+  },
+
+  // 监听无效链接
+  onDeepLinkError: async (
+    callback: (error: string) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen("deep-link-error", (event) => {
+      callback(String(event.payload));
+    });
+  },
+
   // 获取 app_config_dir 覆盖配置(从 Store)
   getAppConfigDirOverride: async (): Promise<string | null> => {
     try {
@@ -722,11 +1241,13 @@ export const tauriAPI = {
   searchConversations: async (
     appType: string | undefined,
     keyword: string,
-  ): Promise<ConversationMeta[]> => {
+    options?: ConversationSearchOptions,
+  ): Promise<ConversationSearchHit[]> => {
     try {
       return await invoke("search_conversations", {
         appType,
         keyword,
+        options,
       });
     } catch (error) {
       console.error("搜索对话记录失败:", error);
@@ -735,9 +1256,12 @@ export const tauriAPI = {
   },
 
   // 删除对话记录
-  deleteConversation: async (filePath: string): Promise<void> => {
+  deleteConversation: async (
+    filePath: string,
+    force?: boolean,
+  ): Promise<void> => {
     try {
-      await invoke("delete_conversation", { filePath });
+      await invoke("delete_conversation", { filePath, force });
     } catch (error) {
       console.error("删除对话记录失败:", error);
       throw error;
@@ -754,6 +1278,328 @@ export const tauriAPI = {
     }
   },
 
+  // 跟随正在进行的会话（Claude / Codex），返回已有的完整行数
+  tailConversation: async (filePath: string): Promise<ConversationTailStart> => {
+    try {
+      return await invoke("tail_conversation", { filePath });
+    } catch (error) {
+      console.error("跟随会话失败:", error);
+      throw error;
+    }
+  },
+
+  // 停止跟随；不传 filePath 时停止全部
+  stopConversationTail: async (filePath?: string): Promise<void> => {
+    try {
+      await invoke("stop_conversation_tail", { filePath });
+    } catch (error) {
+      console.error("停止跟随会话失败:", error);
+      throw error;
+    }
+  },
+
+  // 监听跟随中的会话追加的新消息
+  onConversationTail: async (
+    callback: (data: ConversationTailEvent) => void,
+  ): Promise<UnlistenFn> => {
+    return await listen("conversation-tail", (event) => {
+      try {
+        callback(event.payload as ConversationTailEvent);
+      } catch (e) {
+        console.error("处理 conversation-tail 事件失败: ", e);
+      }
+    });
+  },
+
+  // 只导出对话中选中的消息（条目序号或 uuid），返回导出内容
+  exportConversationMessages: async (
+    filePath: string,
+    messages: Array<number | string>,
+    format?: ConversationExportFormat,
+    destPath?: string,
+    includeNotes?: boolean,
+  ): Promise<string> => {
+    try {
+      return await invoke("export_conversation_messages", {
+        filePath,
+        messages,
+        format,
+        destPath,
+        includeNotes,
+      });
+    } catch (error) {
+      console.error("导出选中消息失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取对话备注
+  getConversationNotes: async (
+    filePath: string,
+  ): Promise<import("../types").ConversationNotes> => {
+    try {
+      return await invoke("get_conversation_notes", { filePath });
+    } catch (error) {
+      console.error("读取对话备注失败:", error);
+      return { updatedAt: 0 };
+    }
+  },
+
+  // 设置对话备注；指定 messageId 时为消息备注，note 为空时删除
+  setConversationNote: async (
+    filePath: string,
+    note: string | null,
+    messageId?: string,
+  ): Promise<import("../types").ConversationNotes> => {
+    try {
+      return await invoke("set_conversation_note", {
+        filePath,
+        messageId,
+        note,
+      });
+    } catch (error) {
+      console.error("保存对话备注失败:", error);
+      throw error;
+    }
+  },
+
+  // 读取模型生成的对话标题与摘要
+  getConversationSummary: async (
+    filePath: string,
+  ): Promise<import("../types").GeneratedSummary | null> => {
+    try {
+      return await invoke("get_conversation_summary", { filePath });
+    } catch (error) {
+      console.error("读取对话摘要失败:", error);
+      return null;
+    }
+  },
+
+  // 全部已生成的对话标题与摘要（以对话文件路径为键）
+  listConversationSummaries: async (): Promise<
+    Record<string, import("../types").GeneratedSummary>
+  > => {
+    try {
+      return await invoke("list_conversation_summaries");
+    } catch (error) {
+      console.error("读取对话摘要失败:", error);
+      return {};
+    }
+  },
+
+  // 使用供应商（默认当前 Claude 供应商）生成对话标题与摘要
+  generateConversationSummary: async (
+    filePath: string,
+    appType?: AppType,
+    providerId?: string,
+  ): Promise<import("../types").GeneratedSummary> => {
+    try {
+      return await invoke("generate_conversation_summary", {
+        filePath,
+        appType,
+        providerId,
+      });
+    } catch (error) {
+      console.error("生成对话摘要失败:", error);
+      throw error;
+    }
+  },
+
+  // 为尚未生成标题的对话批量生成
+  generateMissingConversationSummaries: async (options?: {
+    appType?: AppType;
+    providerId?: string;
+    filePaths?: string[];
+    limit?: number;
+  }): Promise<import("../types").SummaryBatchResult> => {
+    try {
+      return await invoke("generate_missing_conversation_summaries", {
+        appType: options?.appType,
+        providerId: options?.providerId,
+        filePaths: options?.filePaths,
+        limit: options?.limit,
+      });
+    } catch (error) {
+      console.error("批量生成对话摘要失败:", error);
+      throw error;
+    }
+  },
+
+  // 删除已生成的对话标题与摘要
+  deleteConversationSummary: async (filePath: string): Promise<boolean> => {
+    try {
+      return await invoke("delete_conversation_summary", { filePath });
+    } catch (error) {
+      console.error("删除对话摘要失败:", error);
+      throw error;
+    }
+  },
+
+  // 提取对话中的代码块；可按语言筛选，指定 destDir 时写入文件
+  extractCodeBlocks: async (
+    filePath: string,
+    language?: string,
+    destDir?: string,
+  ): Promise<import("../types").CodeBlock[]> => {
+    try {
+      return await invoke("extract_code_blocks", {
+        filePath,
+        language,
+        destDir,
+      });
+    } catch (error) {
+      console.error("提取代码块失败:", error);
+      throw error;
+    }
+  },
+
+  // 列出会话中修改过的文件及改动
+  getSessionFileEdits: async (
+    filePath: string,
+  ): Promise<import("../types").SessionEdits> => {
+    try {
+      return await invoke("get_session_file_edits", { filePath });
+    } catch (error) {
+      console.error("解析文件修改失败:", error);
+      throw error;
+    }
+  },
+
+  // 为消息添加书签（条目序号或 uuid）
+  addMessageBookmark: async (
+    filePath: string,
+    message: number | string,
+    label?: string,
+  ): Promise<import("../types").MessageBookmark> => {
+    try {
+      return await invoke("add_message_bookmark", {
+        filePath,
+        message,
+        label,
+      });
+    } catch (error) {
+      console.error("添加书签失败:", error);
+      throw error;
+    }
+  },
+
+  // 删除消息书签
+  removeMessageBookmark: async (id: string): Promise<boolean> => {
+    try {
+      return await invoke("remove_message_bookmark", { id });
+    } catch (error) {
+      console.error("删除书签失败:", error);
+      throw error;
+    }
+  },
+
+  // 列出消息书签（最新在前），可只列出某个对话的书签
+  listMessageBookmarks: async (
+    filePath?: string,
+  ): Promise<import("../types").MessageBookmark[]> => {
+    try {
+      return await invoke("list_message_bookmarks", { filePath });
+    } catch (error) {
+      console.error("读取书签失败:", error);
+      return [];
+    }
+  },
+
+  // 将对话生成静态分享网站（index.html + 每个对话一个页面）
+  generateShareBundle: async (
+    filePaths: string[],
+    destDir: string,
+    title?: string,
+  ): Promise<import("../types").ShareBundle> => {
+    try {
+      return await invoke("generate_share_bundle", {
+        filePaths,
+        destDir,
+        title,
+      });
+    } catch (error) {
+      console.error("生成分享网站失败:", error);
+      throw error;
+    }
+  },
+
+  // 导入 ChatGPT / claude.ai 网页版数据导出包
+  importWebExport: async (
+    filePath: string,
+  ): Promise<import("../types").WebImportResult> => {
+    try {
+      return await invoke("import_web_export", { filePath });
+    } catch (error) {
+      console.error("导入网页版对话失败:", error);
+      throw error;
+    }
+  },
+
+  // 裁剪对话到新文件，原对话保持不变
+  trimConversation: async (
+    filePath: string,
+    spec: TrimSpec,
+  ): Promise<DerivedConversation> => {
+    try {
+      return await invoke("trim_conversation", { filePath, spec });
+    } catch (error) {
+      console.error("裁剪对话失败:", error);
+      throw error;
+    }
+  },
+
+  // 合并两个对话到新文件，原对话保持不变
+  mergeConversations: async (
+    first: string,
+    second: string,
+    strategy?: MergeStrategy,
+  ): Promise<DerivedConversation> => {
+    try {
+      return await invoke("merge_conversations", { first, second, strategy });
+    } catch (error) {
+      console.error("合并对话失败:", error);
+      throw error;
+    }
+  },
+
+  // 检查对话文件完整性，repair 为 true 时写出修复副本
+  verifyConversation: async (
+    filePath: string,
+    repair?: boolean,
+  ): Promise<ConversationIntegrityReport> => {
+    try {
+      return await invoke("verify_conversation", { filePath, repair });
+    } catch (error) {
+      console.error("检查对话完整性失败:", error);
+      throw error;
+    }
+  },
+
+  // 获取项目概览统计（Claude 项目目录名或 Claude / Codex 工作目录）
+  getProjectStats: async (
+    project: string,
+    appType?: AppType,
+  ): Promise<ProjectStats> => {
+    try {
+      return await invoke("get_project_stats", { project, appType });
+    } catch (error) {
+      console.error("获取项目统计失败:", error);
+      throw error;
+    }
+  },
+
+  // 会话概览：回合数、工具调用统计与时间线
+  summarizeConversation: async (
+    filePath: string,
+  ): Promise<import("../types").ConversationSummary> => {
+    try {
+      return await invoke("summarize_conversation", { filePath });
+    } catch (error) {
+      console.error("生成会话概览失败:", error);
+      throw error;
+    }
+  },
+
   // ==================== 全局规则管理 ====================
 
   // 读取 Claude 全局规则
@@ -786,6 +1632,18 @@ export const tauriAPI = {
     }
   },
 
+  // 列出全部配置目录中的规则文件
+  listRulesAcrossHomes: async (): Promise<
+    import("../types").HomeRuleFile[]
+  > => {
+    try {
+      return await invoke("list_rules_across_homes");
+    } catch (error) {
+      console.error("列出各配置目录规则失败:", error);
+      throw error;
+    }
+  },
+
   // 读取 Codex 规则文件
   readCodexRule: async (filename: string): Promise<string> => {
     try {
@@ -3,6 +3,7 @@ export type ProviderCategory =
   | "cn_official" // 国产官方
   | "aggregator" // 聚合网站
   | "third_party" // 第三方供应商
+  | "local" // 本地推理服务
   | "custom"; // 自定义
 
 export interface Provider {
@@ -63,6 +64,906 @@ export interface ProviderMeta {
   custom_endpoints?: Record<string, CustomEndpoint>;
   // 用量查询脚本配置
   usage_script?: UsageScript;
+  // 备注（用于对比矩阵等展示）
+  notes?: string;
+  // 本地推理服务启动配置（仅 category 为 local 的供应商生效）
+  local_server?: LocalServerConfig;
+  // 余额 / 额度查询接口
+  quota_endpoint?: QuotaEndpoint;
+  // 从分享列表导入且未包含 API Key，补充 Key 前禁止切换
+  needs_key?: boolean;
+  // 所属分组 id
+  group_id?: string;
+  // 供应商专用代理，优先于全局代理；url 为空表示直连
+  proxy?: ProxyConfig;
+  // 最近一次从端点获取的模型列表
+  models?: string[];
+  // 切换时合并进 live 配置的附加请求头与配置片段
+  extras?: ProviderExtras;
+  // 同一供应商的多个 API Key 及轮换策略
+  key_rotation?: KeyRotation;
+}
+
+// 多 Key 轮换策略：手动、每次切换轮询、被限流（429）时换用下一个
+export type RotationStrategy = "manual" | "roundRobin" | "failoverOnRateLimit";
+
+export interface RotationKey {
+  key: string;
+  label?: string;
+}
+
+// 同一供应商的多个 API Key；生效的 Key 写入配置中的 Key 字段
+export interface KeyRotation {
+  keys: RotationKey[];
+  strategy: RotationStrategy;
+  // 当前使用的 Key 序号
+  active: number;
+}
+
+// 当前生效的 Key（已脱敏）
+export interface ActiveKey {
+  providerId: string;
+  index: number;
+  total: number;
+  label?: string;
+  masked: string;
+}
+
+// 供应商附加设置：切换到该供应商时合并，切换离开时移除
+export interface ProviderExtras {
+  // Claude 写入 ANTHROPIC_CUSTOM_HEADERS，Codex 写入当前 model_provider 的 http_headers
+  headers?: Record<string, string>;
+  // 深度合并进 Claude settings.json 的 JSON 对象
+  json?: Record<string, unknown>;
+  // 深度合并进 Codex config.toml 的 TOML 片段
+  toml?: string;
+}
+
+// 代理配置（http://、https://、socks5://、socks5h://）
+export interface ProxyConfig {
+  url: string;
+  // 不走代理的主机（逗号分隔），写入 NO_PROXY
+  noProxy?: string;
+}
+
+export interface ProxyTestResult {
+  success: boolean;
+  target: string;
+  status?: number;
+  latencyMs?: number;
+  error?: string;
+}
+
+// 供应商分组
+export interface ProviderGroup {
+  id: string;
+  name: string;
+  // 停用后组内供应商不能被切换，也不参与故障转移与定时健康检查
+  disabled?: boolean;
+}
+
+// 分组及其供应商（id 为空表示未分组）
+export interface ProviderGroupInfo {
+  id?: string;
+  name: string;
+  disabled: boolean;
+  providerIds: string[];
+}
+
+// 余额 / 额度查询接口配置
+export interface QuotaEndpoint {
+  // 查询地址，支持 {{baseUrl}} 占位符
+  url: string;
+  // 自定义请求头，支持 {{apiKey}} 占位符；为空时使用 Authorization: Bearer <apiKey>
+  headers?: Record<string, string>;
+  // 响应 JSON 中剩余额度的路径，例如 data.balance
+  remainingPath: string;
+  totalPath?: string;
+  unit?: string;
+  // 缓存有效期（分钟，默认 30）
+  cacheMinutes?: number;
+}
+
+// 供应商额度查询结果
+export interface ProviderQuota {
+  appType: string;
+  providerId: string;
+  remaining?: number;
+  total?: number;
+  unit?: string;
+  fetchedAt: number;
+  error?: string;
+  // 是否来自缓存
+  cached: boolean;
+}
+
+// 本地推理服务启动配置
+export interface LocalServerConfig {
+  // 启动命令，例如 `ollama serve`
+  startCommand: string;
+  // 等待服务就绪的超时（秒）
+  readyTimeoutSecs?: number;
+  // 切换到其他供应商时是否停止由 cc-switch 启动的服务
+  stopOnSwitchAway?: boolean;
+}
+
+// 外发通知事件
+export type NotificationEvent = "switch" | "failover" | "budgetAlert" | "rule";
+
+// 外发通知渠道配置
+export interface NotificationSink {
+  id: string;
+  name?: string;
+  kind: "webhook" | "slack" | "ntfy";
+  url: string;
+  enabled?: boolean;
+  // 订阅的事件，为空表示全部事件
+  events?: NotificationEvent[];
+  // 自定义请求体模板，支持 {{event}} {{title}} {{message}} {{app}} {{provider}} {{timestamp}}
+  template?: string;
+  headers?: Record<string, string>;
+  maxRetries?: number;
+}
+
+// 后台事件的通知类别
+export type NotificationCategory =
+  | "failover"
+  | "scheduledSwitch"
+  | "backupCompleted"
+  | "externalChange"
+  | "quotaLow";
+
+// 系统通知设置
+export interface NotificationSettings {
+  // 是否弹出系统通知；关闭后仍写入事件日志
+  osEnabled: boolean;
+  // 不弹出系统通知的类别
+  muted?: NotificationCategory[];
+  // 额度剩余低于该百分比时提醒（默认 10）
+  quotaLowPercent?: number;
+}
+
+// 审计日志记录（cc-switch 对文件的每次修改）
+export interface AuditEntry {
+  timestamp: number;
+  // 触发修改的操作，如 switch-provider、claude-settings；未标记时与 action 相同
+  operation: string;
+  action: "write" | "delete" | "copy";
+  target: string;
+  // 复制时的来源文件
+  source?: string;
+  bytes?: number;
+  outcome: "success" | "failure";
+  error?: string;
+}
+
+// 审计日志筛选条件
+export interface AuditFilter {
+  operation?: string;
+  action?: AuditEntry["action"];
+  // 目标文件路径包含该文本（不区分大小写）
+  target?: string;
+  outcome?: AuditEntry["outcome"];
+  // 时间范围（毫秒）
+  since?: number;
+  until?: number;
+  limit?: number;
+}
+
+// 启动自检覆盖的配置文件
+export type CheckTarget = "appConfig" | "claudeSettings" | "codexConfig";
+
+// 启动自检发现的损坏文件
+export interface IntegrityIssue {
+  target: CheckTarget;
+  path: string;
+  error: string;
+  // 最新的可用备份；为空时只能重置
+  backup?: {
+    source: "snapshot" | "appConfigBak";
+    snapshotId?: string;
+    // 秒级时间戳
+    createdAt: number;
+  };
+}
+
+// 启动自检结果
+export interface StartupReport {
+  checkedAt: number;
+  issues: IntegrityIssue[];
+}
+
+// 日志级别
+export type LogLevel = "error" | "warn" | "info" | "debug" | "trace";
+
+// 应用日志中的一条记录
+export interface LogEntry {
+  // 本地时间 YYYY-MM-DD HH:MM:SS
+  timestamp: string;
+  level: LogLevel;
+  // 模块路径
+  target: string;
+  message: string;
+  // 事件附带的结构化字段
+  fields?: Record<string, unknown>;
+}
+
+// Claude 配置目录下持续累积的缓存（~/.claude/todos、~/.claude/shell-snapshots）
+export type ClaudeCacheKind = "todos" | "shellSnapshots";
+
+export interface ClaudeCacheSummary {
+  kind: ClaudeCacheKind;
+  path: string;
+  files: number;
+  bytes: number;
+  // 秒级时间戳
+  oldestAt?: number;
+  newestAt?: number;
+}
+
+export interface ClaudeCacheFile {
+  name: string;
+  path: string;
+  size: number;
+  // 秒级时间戳
+  modifiedAt: number;
+}
+
+export interface ClaudeCachePurge {
+  kind: ClaudeCacheKind;
+  removed: number;
+  freedBytes: number;
+  // 因可能属于运行中的会话而保留的文件数
+  skippedLive: number;
+}
+
+// Codex 输入历史（~/.codex/history.jsonl）与日志目录（~/.codex/log）
+export type CodexCacheTarget = "history" | "logs";
+
+// 截断为最后 keep 条记录，或全部清空
+export type CodexCacheAction =
+  | { type: "truncate"; keep: number }
+  | { type: "clear" };
+
+export interface CodexCacheSummary {
+  target: CodexCacheTarget;
+  path: string;
+  exists: boolean;
+  bytes: number;
+  // history 为记录条数，日志为文件数
+  entries: number;
+}
+
+export interface CodexCachePurge {
+  target: CodexCacheTarget;
+  removedEntries: number;
+  freedBytes: number;
+}
+
+// 事件日志中的一条通知（事件 "notification"）
+export interface NotificationRecord {
+  category: NotificationCategory;
+  title: string;
+  message: string;
+  appType?: string;
+  providerId?: string;
+  timestamp: number;
+}
+
+// 事件规则动作
+export type RuleAction =
+  | { type: "switchProvider"; appType?: string; providerId: string }
+  | { type: "notify"; message?: string };
+
+// 事件规则
+export interface EventRule {
+  id: string;
+  name?: string;
+  enabled?: boolean;
+  // 触发事件类型，例如 "provider-switched"
+  when: string;
+  // 条件（全部满足）：provider / app 或事件附加数据中的键
+  conditions?: Record<string, string>;
+  actions: RuleAction[];
+  // 规则的文本形式
+  source?: string;
+}
+
+// 工作区：一组项目目录与其绑定的供应商
+export interface Workspace {
+  id: string;
+  name?: string;
+  // 项目目录（支持 ~ 前缀）
+  projects: string[];
+  claudeProviderId?: string;
+  codexProviderId?: string;
+  geminiProviderId?: string;
+  opencodeProviderId?: string;
+  crushProviderId?: string;
+  // 规则绑定：命中时切换到的环境配置 id
+  envProfileId?: string;
+  // 命中时自动切换到绑定的供应商与规则；关闭时仅提示
+  autoApply?: boolean;
+}
+
+// 目录对应的项目环境变量（cc-switch profile env）
+export interface ProjectEnv {
+  workspaceId?: string;
+  workspaceName?: string;
+  vars: Record<string, string>;
+  // 无法注入的原因，例如供应商不存在或 API Key 已加密
+  warnings: string[];
+}
+
+// 供应商趋势数据点（原始采样或降采样后的桶）
+export interface TrendPoint {
+  ts: number;
+  // 桶宽度（秒），0 表示原始采样
+  resolution: number;
+  samples: number;
+  failures: number;
+  avgLatencyMs?: number;
+  maxLatencyMs?: number;
+}
+
+export type TrendRange = "24h" | "7d" | "30d" | "90d";
+
+// 供应商历史趋势
+export interface ProviderTrends {
+  appType: string;
+  providerId: string;
+  points: TrendPoint[];
+  samples: number;
+  failureRate?: number | null;
+  avgLatencyMs?: number | null;
+}
+
+// 健康检查结果
+export interface HealthCheckResult {
+  appType: string;
+  providerId: string;
+  endpoint: string;
+  ok: boolean;
+  latencyMs?: number | null;
+  status?: number | null;
+  error?: string | null;
+}
+
+// 切换预览：单个键的变更
+export interface KeyChange {
+  // 以 "." 连接的键路径，例如 "env.ANTHROPIC_BASE_URL"
+  key: string;
+  before?: unknown;
+  after?: unknown;
+}
+
+// 切换预览：单个配置文件的差异
+export interface FileDiff {
+  path: string;
+  exists: boolean;
+  added: KeyChange[];
+  removed: KeyChange[];
+  changed: KeyChange[];
+}
+
+// 切换预览结果
+export interface SwitchPreview {
+  appType: string;
+  fromProviderId?: string;
+  toProviderId: string;
+  toProviderName: string;
+  files: FileDiff[];
+}
+
+// 导出目标文件已存在时的处理策略
+export type CollisionPolicy = "skip" | "overwrite" | "suffix";
+
+// 对话导出格式：原始 JSONL / Markdown / 阅读模式消息 JSON / OpenAI、Anthropic API 消息数组
+export type ConversationExportFormat =
+  | "jsonl"
+  | "markdown"
+  | "json"
+  | "openai"
+  | "anthropic";
+
+// 对话裁剪方式
+export type TrimSpec =
+  | { mode: "keepLast"; count: number }
+  | { mode: "removeRange"; start: number; end: number };
+
+// 对话合并方式：顺序拼接 / 按时间交错
+export type MergeStrategy = "concat" | "interleave";
+
+// 裁剪 / 合并生成的新对话
+export interface DerivedConversation {
+  filePath: string;
+  sessionId: string;
+  messageCount: number;
+}
+
+// 对话完整性检查
+export interface ConversationIntegrityIssue {
+  // 行号（从 1 开始）
+  line: number;
+  severity: "error" | "warning" | "info";
+  code: string;
+  message: string;
+}
+
+export interface ConversationIntegrityReport {
+  filePath: string;
+  totalLines: number;
+  validLines: number;
+  issues: ConversationIntegrityIssue[];
+  // 请求修复且存在错误时生成的修复副本
+  repaired?: DerivedConversation;
+}
+
+// 批量导出结果
+export interface ExportOutcome {
+  written: string[];
+  skipped: string[];
+}
+
+// 对话分享网站（静态页面）生成结果
+export interface ShareBundle {
+  dir: string;
+  // 入口页 index.html 的路径
+  index: string;
+  pages: string[];
+}
+
+// 网页版数据导出来源
+export type WebExportSource = "chatgpt" | "claudeAi";
+
+// 网页版对话导入结果
+export interface WebImportResult {
+  source: WebExportSource;
+  // 写入的会话文件
+  imported: string[];
+  // 没有文本消息而跳过的对话数
+  skipped: number;
+}
+
+// 供应商校验问题
+export interface ValidationIssue {
+  // 问题所在字段，例如 "env.ANTHROPIC_BASE_URL"、"config.model"
+  field: string;
+  severity: "error" | "warning" | "info";
+  code: string;
+  message: string;
+}
+
+// 供应商校验报告
+export interface ProviderValidation {
+  // 不存在 error 级别问题
+  valid: boolean;
+  issues: ValidationIssue[];
+}
+
+// 供应商连接测试结果
+export interface ConnectionTestResult {
+  ok: boolean;
+  url: string;
+  // 模型列表请求，或其不可用时回退的 1 token 补全
+  probe: "modelsList" | "completion";
+  latencyMs?: number;
+  status?: number;
+  error?: string;
+  // 失败时的响应体（截断）
+  errorBody?: string;
+}
+
+// Claude ↔ Codex 工作流迁移方向
+export type MigrationDirection = "claudeToCodex" | "codexToClaude";
+
+// 迁移计划中的一项
+export interface PlannedChange {
+  path: string;
+  action: "create" | "update" | "skip";
+  description: string;
+  // 将写入的内容（截断）
+  preview?: string;
+}
+
+// 迁移计划（dry-run 结果）
+export interface MigrationPlan {
+  direction: MigrationDirection;
+  projectPath: string;
+  // 绑定到目标应用的供应商
+  providerId?: string;
+  changes: PlannedChange[];
+  warnings: string[];
+}
+
+// 供应商基准测试结果
+export interface ProviderBenchmark {
+  appType: string;
+  providerId: string;
+  providerName: string;
+  // 排名（从 1 开始），无法完成测试的供应商为空
+  rank?: number;
+  baseUrl: string;
+  model?: string;
+  // 首字节时间（请求模型列表至收到响应头）
+  ttfbMs?: number;
+  // 1 token 补全的完整往返时间
+  completionMs?: number;
+  completionStatus?: number;
+  error?: string;
+}
+
+// 自动故障转移设置
+export interface FailoverSettings {
+  enabled: boolean;
+  // 供应商优先级（供应商 id，按顺序尝试）
+  claudePriority?: string[];
+  codexPriority?: string[];
+  geminiPriority?: string[];
+  opencodePriority?: string[];
+  crushPriority?: string[];
+  // 连续失败多少次后触发切换（默认 3）
+  failureThreshold?: number;
+  // 健康检查间隔（秒，默认 60）
+  checkIntervalSecs?: number;
+}
+
+// 供应商切换记录（~/.cc-switch/switch_history.jsonl）
+export interface SwitchRecord {
+  // 毫秒时间戳
+  timestamp: number;
+  appType: string;
+  fromId?: string;
+  fromName?: string;
+  toId: string;
+  toName: string;
+  initiator:
+    | "manual"
+    | "tray"
+    | "failover"
+    | "schedule"
+    | "workspace"
+    | "rule"
+    | "switchBack"
+    | "hotkey"
+    | "api";
+}
+
+// Claude 子代理作用域：global 为 ~/.claude/agents，project 为 <项目>/.claude/agents
+export type AgentScope = "global" | "project";
+
+// Claude 子代理（agents/*.md）
+export interface AgentInfo {
+  fileName: string;
+  path: string;
+  scope: AgentScope;
+  name?: string;
+  description?: string;
+  // 未设置时继承全部工具
+  tools?: string[];
+  model?: string;
+  // 系统提示词（frontmatter 之后的正文）
+  body: string;
+  // frontmatter 校验失败原因
+  error?: string;
+}
+
+// 修改子代理 frontmatter；未传字段保持不变
+export interface AgentFrontmatterUpdate {
+  name?: string;
+  description?: string;
+  // 空数组表示移除 tools
+  tools?: string[];
+  // 空字符串表示移除 model
+  model?: string;
+}
+
+export interface AgentTemplate {
+  id: string;
+  description: string;
+  tools: string[];
+  body: string;
+}
+
+// Claude settings.json 中的 hook 命令
+export interface ClaudeHookCommand {
+  type: "command";
+  command: string;
+  // 超时秒数
+  timeout?: number;
+  [key: string]: unknown;
+}
+
+export interface ClaudeHookMatcher {
+  matcher?: string;
+  hooks: ClaudeHookCommand[];
+  [key: string]: unknown;
+}
+
+export interface ClaudePermissions {
+  allow?: string[];
+  deny?: string[];
+  ask?: string[];
+  additionalDirectories?: string[];
+  defaultMode?: "default" | "acceptEdits" | "plan" | "bypassPermissions";
+  [key: string]: unknown;
+}
+
+// ~/.claude/settings.json；未建模的字段原样保留
+export interface ClaudeSettings {
+  env?: Record<string, string>;
+  permissions?: ClaudePermissions;
+  model?: string;
+  // 事件名（PreToolUse、Stop 等）→ hook 列表
+  hooks?: Record<string, ClaudeHookMatcher[]>;
+  statusLine?: {
+    type: "command";
+    command: string;
+    padding?: number;
+    [key: string]: unknown;
+  };
+  // plugin@marketplace → 是否启用
+  enabledPlugins?: Record<string, boolean>;
+  extraKnownMarketplaces?: Record<
+    string,
+    { source: ClaudeMarketplaceSource; [key: string]: unknown }
+  >;
+  [key: string]: unknown;
+}
+
+export type ClaudeSettingsSection =
+  | "env"
+  | "permissions"
+  | "model"
+  | "hooks"
+  | "statusLine"
+  | "enabledPlugins"
+  | "extraKnownMarketplaces";
+
+// Claude 供应商的模型设置；未设置的字段使用 Claude Code 默认值
+export interface ClaudeModelSettings {
+  // 默认模型：别名（opus / sonnet / haiku）或完整模型 id
+  model?: string;
+  // ANTHROPIC_SMALL_FAST_MODEL
+  smallFastModel?: string;
+  // 别名映射（ANTHROPIC_DEFAULT_*_MODEL）
+  opusModel?: string;
+  sonnetModel?: string;
+  haikuModel?: string;
+  // 最近一次从端点获取的模型列表
+  available: string[];
+}
+
+// 插件市场来源
+export type ClaudeMarketplaceSource =
+  | { source: "github"; repo: string; ref?: string }
+  | { source: "git" | "url"; url: string; ref?: string }
+  | { source: "directory" | "file"; path: string };
+
+// Claude Code 插件市场
+export interface ClaudeMarketplace {
+  name: string;
+  source: ClaudeMarketplaceSource;
+  // 已下载到本地时的目录
+  installLocation?: string;
+  lastUpdated?: string;
+  // 是否在 settings.json 中声明
+  inSettings: boolean;
+}
+
+// Claude Code 插件（id 形如 plugin@marketplace）
+export interface ClaudePlugin {
+  id: string;
+  name: string;
+  marketplace: string;
+  // enabledPlugins 中的值；未声明时为空
+  enabled?: boolean;
+  installed: boolean;
+  version?: string;
+  installPath?: string;
+}
+
+export interface SettingsSchemaError {
+  // JSON Pointer，根对象为空字符串
+  path: string;
+  message: string;
+}
+
+// 配置文本编辑器的诊断（行列号从 1 开始）
+export interface ConfigDiagnostic {
+  severity: "error" | "warning" | "info";
+  code: string;
+  message: string;
+  // 问题所在字段，如 permissions.defaultMode
+  path?: string;
+  line?: number;
+  column?: number;
+}
+
+export interface ConfigTextValidation {
+  format: "json" | "toml";
+  valid: boolean;
+  diagnostics: ConfigDiagnostic[];
+}
+
+// shell 配置文件中的一条环境变量导出
+export interface ShellExport {
+  file: string;
+  line: number;
+  value: string;
+}
+
+export interface EnvVarStatus {
+  name: string;
+  // Claude settings.json env 中的值
+  settingsValue?: string;
+  shellExports: ShellExport[];
+  // 应用进程继承到的值
+  processValue?: string;
+  // settings.json 与 shell 导出取值不一致
+  conflict: boolean;
+}
+
+export interface EnvOverview {
+  shellFiles: string[];
+  variables: EnvVarStatus[];
+}
+
+// Codex config.toml 中的 [profiles.<name>]
+export interface CodexProfile {
+  name: string;
+  // 是否为顶层 profile 指定的默认 profile
+  isDefault: boolean;
+  // profile 表内容，如 model、model_provider、approval_policy、sandbox_mode
+  settings: Record<string, unknown>;
+}
+
+// 可快速切换的 Codex config.toml 顶层键
+export type CodexSettingKey =
+  | "model"
+  | "modelReasoningEffort"
+  | "approvalPolicy"
+  | "sandboxMode";
+
+// config.toml 中的常用顶层设置；未设置时由 Codex 使用默认值
+export interface CodexQuickSettings {
+  model?: string;
+  modelReasoningEffort?: "minimal" | "low" | "medium" | "high";
+  approvalPolicy?: "untrusted" | "on-failure" | "on-request" | "never";
+  sandboxMode?: "read-only" | "workspace-write" | "danger-full-access";
+}
+
+export interface CodexSettingOptions {
+  key: CodexSettingKey;
+  values: string[];
+}
+
+// Claude 斜杠命令（commands/**/*.md，子目录为命名空间）
+export interface SlashCommandInfo {
+  // 调用名，如 /review 或 /frontend:component
+  command: string;
+  // 相对 commands 目录的路径
+  relativePath: string;
+  path: string;
+  scope: AgentScope;
+  description?: string;
+  argumentHint?: string;
+  allowedTools?: string[];
+  model?: string;
+  content: string;
+  error?: string;
+}
+
+// Claude 技能（skills/<目录>/SKILL.md）
+export interface SkillInfo {
+  dirName: string;
+  path: string;
+  scope: AgentScope;
+  name?: string;
+  description?: string;
+  allowedTools?: string[];
+  // SKILL.md 之外的附带文件
+  files: string[];
+  content: string;
+  error?: string;
+}
+
+// 可撤销的配置修改（每个文件一条，同一次操作共享 changeId）
+export interface UndoEntry {
+  id: string;
+  changeId: string;
+  // 例如 switch-provider、write-rules、mcp
+  operation: string;
+  file: string;
+  // 修改前文件是否存在；不存在时撤销即删除该文件
+  existed: boolean;
+  // 毫秒时间戳
+  timestamp: number;
+  // 切换供应商前的当前供应商，撤销时一并恢复
+  previousCurrent?: { appType: string; providerId: string };
+}
+
+// 定时切换规则：在指定星期的时间段内使用某个供应商
+export interface SwitchSchedule {
+  id: string;
+  name?: string;
+  enabled?: boolean;
+  appType: string;
+  providerId: string;
+  // 生效的星期（1 = 周一 … 7 = 周日），为空表示每天
+  days?: number[];
+  // 本地时间 HH:MM；结束早于开始表示跨越午夜，相同表示全天
+  start: string;
+  end: string;
+}
+
+// API Key 加密存储状态
+export interface SecretsStatus {
+  // 是否已设置主密码
+  initialized: boolean;
+  unlocked: boolean;
+  // 密钥库中保存的密钥数量
+  stored: number;
+}
+
+// API Key 加密 / 解密迁移结果
+export interface SecretsMigration {
+  keys: number;
+  providers: number;
+}
+
+// 候选供应商来源
+export type DiscoverySource =
+  | "env"
+  | "claudeSettings"
+  | "codexLive"
+  | "geminiLive"
+  | "adapterLive"
+  | "externalTool";
+
+// 扫描到的候选供应商（导入向导）
+export interface ProviderCandidate {
+  appType: string;
+  source: DiscoverySource;
+  sourcePath?: string;
+  provider: Provider;
+  // 与已有供应商重复时为其 id
+  duplicateOf?: string;
+}
+
+// 可导入配置的其他供应商切换工具
+export type ExternalTool = "ccs" | "claudeCodeRouter" | "generic";
+
+// 在默认位置找到的其他工具配置
+export interface ExternalToolSource {
+  tool: ExternalTool;
+  path: string;
+  candidates: ProviderCandidate[];
+  // 解析失败时的错误信息
+  error?: string;
+}
+
+// cc-switch:// 链接解析出的供应商草稿（确认后再调用 addProvider 写入）
+export interface DeepLinkProvider {
+  appType: string;
+  provider: Provider;
+  hasApiKey: boolean;
+  // 与已有供应商重复时为其 id
+  duplicateOf?: string;
+}
+
+// 导入分享供应商列表的结果
+export interface SharedImportResult {
+  imported: {
+    appType: string;
+    id: string;
+    name: string;
+    needsKey: boolean;
+    // 出于安全原因移除的字段（启动命令、额度查询接口、代理等），需重新配置
+    removedFields?: string[];
+  }[];
+  // 同名且端点相同而跳过的供应商名称
+  skipped: string[];
 }
 
 // 应用设置类型（用于 SettingsModal 与 Tauri API）
@@ -77,12 +978,248 @@ export interface Settings {
   claudeConfigDir?: string;
   // 覆盖 Codex 配置目录（可选）
   codexConfigDir?: string;
+  // 覆盖 Gemini CLI 配置目录（可选）
+  geminiConfigDir?: string;
+  // 自定义对话记录目录（可多个，留空时使用配置目录下的默认位置）
+  claudeConversationDirs?: string[];
+  codexConversationDirs?: string[];
+  geminiConversationDirs?: string[];
+  // 已登记的多个 Claude / Codex 配置目录
+  appHomes?: AppHome[];
+  // 切换供应商时写入的配置目录名称（为空时使用默认目录）
+  activeClaudeHome?: string;
+  activeCodexHome?: string;
+  // 环境配置：成组切换 Claude / Codex 配置目录
+  envProfiles?: EnvProfile[];
+  // 当前环境配置 id
+  activeEnvProfile?: string;
+  // WSL 模式（Windows）：配置目录位于所选发行版中
+  wsl?: WslSettings;
+  // 远程主机（SSH）连接配置
+  remoteProfiles?: RemoteProfile[];
   // 首选语言（可选，默认中文）
   language?: "en" | "zh";
   // Claude 自定义端点列表
   customEndpointsClaude?: Record<string, CustomEndpoint>;
   // Codex 自定义端点列表
   customEndpointsCodex?: Record<string, CustomEndpoint>;
+  // 全局规则 token 预算（可选，默认 8000）
+  rulesTokenBudget?: number;
+  // 外发通知渠道（Webhook / Slack / ntfy）
+  notificationSinks?: NotificationSink[];
+  // 后台事件的系统通知与事件日志
+  notifications?: NotificationSettings;
+  // 事件规则（when <event> and <condition> then <action>）
+  eventRules?: EventRule[];
+  // 定时配置快照间隔（小时），为空或 0 表示关闭
+  backupIntervalHours?: number;
+  // 保留的配置快照数量
+  backupRetain?: number;
+  // 保留的写入前快照数量（与上面的数量分开计数）
+  backupPreWriteRetain?: number;
+  // 工作区：项目目录与供应商绑定
+  workspaces?: Workspace[];
+  // 定时健康检查间隔（分钟），为空或 0 表示关闭
+  healthCheckIntervalMinutes?: number;
+  // 批量导出文件名模板，支持 {project} {date} {title} {id} {app}
+  exportNameTemplate?: string;
+  // 批量导出目标文件已存在时的处理策略
+  exportCollisionPolicy?: CollisionPolicy;
+  // 自动故障转移
+  failover?: FailoverSettings;
+  // 定时状态报告间隔（天），为空或 0 表示关闭
+  setupReportIntervalDays?: number;
+  // 状态报告保存目录，默认 ~/.cc-switch/reports
+  setupReportDir?: string;
+  // 定时切换规则（按顺序取第一条命中的规则）
+  switchSchedules?: SwitchSchedule[];
+  // MCP 目录远程刷新地址
+  mcpCatalogUrl?: string;
+  // 全局代理，写入各应用的 live 配置
+  proxy?: ProxyConfig;
+  // 在当前与上一个供应商之间切换的全局快捷键
+  toggleShortcut?: string;
+  // 本地自动化接口（JSON-RPC，默认关闭）
+  localApi?: LocalApiSettings;
+  // Claude Code 状态栏
+  statusline?: StatuslineSettings;
+  // 云同步（仅保存在本机）
+  sync?: SyncSettings;
+  // 配置版本库（本地 git 仓库）
+  configHistory?: ConfigHistorySettings;
+  // 暂存验证：写入 CLI 配置前先在暂存目录验证
+  stagedApply?: StagedApplySettings;
+  // 日志级别（默认 info）
+  logLevel?: LogLevel;
+}
+
+// live 配置被外部修改（事件 "live-config-changed"）
+export interface LiveConfigChange {
+  appType: string;
+  path: string;
+  exists: boolean;
+  timestamp: number;
+}
+
+// 外部修改冲突（事件 "write-conflict"）
+export interface WriteConflict {
+  path: string;
+  fileName: string;
+  // JSON / TOML 文件可三方合并
+  canMerge: boolean;
+  detectedAt: number;
+}
+
+// 冲突处理方式；overwrite 与 merge 之后需重试原操作
+export type ConflictResolution = "keepTheirs" | "overwrite" | "merge";
+
+// 配置版本库设置
+export interface ConfigHistorySettings {
+  enabled: boolean;
+  // 同时跟踪全局规则文件
+  includeRules: boolean;
+}
+
+// 暂存验证设置
+export interface StagedApplySettings {
+  enabled: boolean;
+  // 只做解析校验，不运行 CLI
+  parseOnly: boolean;
+  // CLI 验证超时（秒），默认 20
+  timeoutSecs?: number;
+}
+
+// CLI 探测结果
+export interface CliProbe {
+  appType: string;
+  found: boolean;
+  version?: string;
+  error?: string;
+}
+
+// Claude 实际使用的认证方式
+export type ClaudeAuthMode = "oAuth" | "apiKey" | "none";
+
+// Claude 登录状态
+export interface ClaudeAuthStatus {
+  mode: ClaudeAuthMode;
+  // 是否存在 OAuth 登录（即使被 API Key 覆盖）
+  oauthLoggedIn: boolean;
+  credentialStore?: "file" | "keychain";
+  email?: string;
+  displayName?: string;
+  organizationName?: string;
+  // 订阅类型（pro / max 等）
+  subscriptionType?: string;
+  // 访问令牌过期时间（毫秒时间戳）
+  expiresAt?: number;
+  apiKeySource?: "settingsEnv" | "apiKeyHelper" | "environment" | "primaryApiKey";
+  // 已登录 OAuth，但 API Key 优先生效
+  oauthOverridden: boolean;
+}
+
+// 配置版本库状态
+export interface ConfigHistoryStatus {
+  enabled: boolean;
+  includeRules: boolean;
+  gitAvailable: boolean;
+  initialized: boolean;
+  repoPath: string;
+  head?: string;
+}
+
+// 配置版本库中的一次提交
+export interface ConfigCommit {
+  id: string;
+  shortId: string;
+  message: string;
+  timestamp: number;
+  files: string[];
+}
+
+// 恢复历史版本的结果
+export interface ConfigCheckoutResult {
+  restored: string[];
+  appConfigRestored: boolean;
+  commit?: string;
+}
+
+// 云同步后端
+export type SyncBackend =
+  | { type: "webDav"; url: string; username?: string; password?: string }
+  | {
+      type: "s3";
+      endpoint?: string;
+      region: string;
+      bucket: string;
+      accessKeyId: string;
+      secretAccessKey: string;
+      prefix?: string;
+      pathStyle?: boolean;
+    }
+  | { type: "gist"; token: string; gistId?: string };
+
+// 云同步设置
+export interface SyncSettings {
+  backend?: SyncBackend;
+  deviceId?: string;
+  lastRevision?: string;
+  lastLocalHash?: string;
+  lastSyncedAt?: number;
+}
+
+export type SyncDirection = "auto" | "push" | "pull";
+
+// 云同步状态（不含凭据）
+export interface SyncStatus {
+  configured: boolean;
+  backendType?: SyncBackend["type"];
+  deviceId?: string;
+  lastRevision?: string;
+  lastSyncedAt?: number;
+}
+
+// 云同步结果；conflict 表示两端都有修改，未做任何改动
+export interface SyncOutcome {
+  action: "pushed" | "pulled" | "upToDate" | "conflict";
+  localChanged: boolean;
+  remoteChanged: boolean;
+  revision?: string;
+  remoteDevice?: string;
+  remoteUpdatedAt?: number;
+}
+
+// Claude Code 状态栏设置
+export interface StatuslineSettings {
+  enabled: boolean;
+  // 支持 {provider}、{model}、{cost}、{dir} 占位符
+  format?: string;
+}
+
+// Claude Code 状态栏安装状态
+export interface StatuslineStatus {
+  enabled: boolean;
+  format: string;
+  scriptPath: string;
+  scriptExists: boolean;
+  // settings.json 当前是否指向 cc-switch 状态栏
+  configured: boolean;
+}
+
+// 本地自动化接口设置
+export interface LocalApiSettings {
+  enabled: boolean;
+  port?: number;
+  token?: string;
+}
+
+// 本地自动化接口运行状态
+export interface LocalApiStatus {
+  enabled: boolean;
+  running: boolean;
+  port: number;
+  url: string;
+  token?: string;
 }
 
 // MCP 服务器连接参数（宽松：允许扩展字段）
@@ -128,6 +1265,56 @@ export interface McpConfigResponse {
   servers: Record<string, McpServer>;
 }
 
+// MCP 目录中的占位符变量（服务器定义中写作 <NAME>）
+export interface McpCatalogVariable {
+  name: string;
+  description?: string;
+  secret?: boolean;
+}
+
+// MCP 目录条目
+export interface McpCatalogEntry {
+  id: string;
+  name: string;
+  description?: string;
+  homepage?: string;
+  docs?: string;
+  tags: string[];
+  server: McpServerSpec;
+  variables: McpCatalogVariable[];
+}
+
+// MCP 目录（内置 + 远程刷新）
+export interface McpCatalog {
+  version: number;
+  // 最近一次远程刷新时间（毫秒时间戳）
+  refreshedAt?: number;
+  servers: McpCatalogEntry[];
+}
+
+// 从目录安装的结果；变量未填写完整时以禁用状态写入
+export interface McpInstallResult {
+  id: string;
+  apps: string[];
+  enabled: boolean;
+  missingVariables: string[];
+}
+
+// MCP 服务器健康检查结果（initialize 握手 + tools/list）
+export interface McpHealthResult {
+  ok: boolean;
+  transport: string;
+  latencyMs?: number;
+  serverName?: string;
+  serverVersion?: string;
+  protocolVersion?: string;
+  tools: { name: string; description?: string }[];
+  // 缺少可执行文件、环境变量未填写、超时等
+  error?: string;
+  // stdio 进程的 stderr 尾部
+  stderr?: string;
+}
+
 // 对话记录元数据
 export interface ConversationMeta {
   id: string;
@@ -139,6 +1326,247 @@ export interface ConversationMeta {
   messageCount: number;
   projectName?: string; // Claude: 项目名称
   sessionId?: string; // 会话ID
+  tags?: string[];
+  // 会话仍被运行中的 CLI 进程使用
+  live?: boolean;
+  // 无法解析的行数（Gemini 会话整体无法解析时为 1）
+  parseErrors?: number;
+  // 所属的已登记配置目录名称（默认目录时为空）
+  home?: string;
+}
+
+// 正在运行的 Claude Code / Codex 进程
+export interface ActiveProcess {
+  pid: number;
+  appType: string;
+  name: string;
+  cwd?: string;
+  startedAt: number;
+}
+
+// 进行中的会话
+export interface ActiveSession {
+  appType: string;
+  filePath: string;
+  pid?: number;
+  cwd?: string;
+  modifiedAt: number;
+}
+
+export interface ActiveSessions {
+  processes: ActiveProcess[];
+  sessions: ActiveSession[];
+}
+
+// 阅读模式消息（index 为原始文件中的行号）
+export interface ReadingMessage {
+  index: number;
+  role: string;
+  timestamp?: string;
+  blocks: Array<{ type: string; [key: string]: any }>;
+  // 条目自身的 uuid / id
+  uuid?: string;
+  // 该消息的备注
+  note?: string;
+  // 该消息的书签 id
+  bookmarkId?: string;
+}
+
+// 从助手消息中提取的代码块
+export interface CodeBlock {
+  // 所在消息的条目序号
+  messageIndex: number;
+  messageUuid?: string;
+  timestamp?: string;
+  language?: string;
+  // 语言标记中附带的文件名
+  fileName?: string;
+  code: string;
+  // 代码块之前最近的一段文字
+  context?: string;
+  // 写入文件时的路径
+  writtenTo?: string;
+}
+
+// 会话中的一次文件修改
+export interface FileEdit {
+  // 工具调用所在消息的条目序号
+  messageIndex: number;
+  timestamp?: string;
+  // 工具名（Edit、Write、apply_patch …）
+  tool: string;
+  kind: "edit" | "write" | "add" | "delete";
+  path: string;
+  movedTo?: string;
+  // 统一 diff 风格的改动内容
+  diff: string;
+  additions: number;
+  deletions: number;
+  // 工具结果为错误
+  failed: boolean;
+}
+
+export interface FileEditSummary {
+  path: string;
+  edits: number;
+  additions: number;
+  deletions: number;
+  failed: number;
+}
+
+// 会话修改过的文件
+export interface SessionEdits {
+  cwd?: string;
+  files: FileEditSummary[];
+  edits: FileEdit[];
+}
+
+// 对话中某条消息的书签
+export interface MessageBookmark {
+  id: string;
+  filePath: string;
+  // 消息在会话中的条目序号（与阅读模式的 index 一致）
+  index: number;
+  uuid?: string;
+  label?: string;
+  role: string;
+  // 消息开头的文本
+  excerpt: string;
+  // 毫秒时间戳
+  createdAt: number;
+  // 对话文件是否仍然存在（仅列表接口）
+  exists?: boolean;
+}
+
+// 对话备注：对话整体的备注与按消息 uuid 记录的备注
+export interface ConversationNotes {
+  note?: string;
+  messages?: Record<string, string>;
+  // 毫秒时间戳
+  updatedAt: number;
+}
+
+// 模型生成的对话标题与摘要
+export interface GeneratedSummary {
+  title: string;
+  summary: string;
+  appType: string;
+  providerId: string;
+  model?: string;
+  // 毫秒时间戳
+  generatedAt: number;
+}
+
+export interface SummaryBatchResult {
+  // 以对话文件路径为键
+  generated: Record<string, GeneratedSummary>;
+  failed: { filePath: string; error: string }[];
+  // 超出数量上限、留待下次处理的对话数
+  remaining: number;
+}
+
+// 开始跟随会话时的位置：startLine 之前的消息按阅读模式加载
+export interface ConversationTailStart {
+  filePath: string;
+  startLine: number;
+}
+
+// 跟随中的会话新增消息（事件 "conversation-tail"）
+export interface ConversationTailEvent {
+  filePath: string;
+  messages: ReadingMessage[];
+  // 文件已被删除，跟随结束
+  ended: boolean;
+}
+
+// 对话搜索选项
+export interface ConversationSearchOptions {
+  // 将关键词整体作为正则表达式
+  regex?: boolean;
+  // 区分大小写（默认不区分）
+  caseSensitive?: boolean;
+  // 全词匹配
+  wholeWord?: boolean;
+  // 只搜索用户输入或助手输出（指定时不匹配 id、项目等元数据）
+  role?: "all" | "user" | "assistant";
+  // 每个对话返回的匹配片段数，默认 3
+  maxMatches?: number;
+}
+
+// 对话搜索结果中的一处匹配
+export interface ConversationSearchMatch {
+  // 会话文件中的条目序号（与阅读模式一致）
+  index: number;
+  role: string;
+  excerpt: string;
+  // 高亮范围在 excerpt 中的偏移
+  highlightStart: number;
+  highlightEnd: number;
+}
+
+export interface ConversationSearchHit extends ConversationMeta {
+  score: number;
+  matchCount: number;
+  matches: ConversationSearchMatch[];
+}
+
+// 项目概览统计
+export interface ProjectTokenTotals {
+  input: number;
+  output: number;
+  cacheCreation: number;
+  cacheRead: number;
+  total: number;
+  // 按内置价格表估算的费用（美元）
+  costUsd: number;
+}
+
+export interface ProjectStats {
+  project: string;
+  sessions: number;
+  messages: number;
+  tokens: ProjectTokenTotals;
+  // 秒级时间戳
+  firstActivity?: number;
+  lastActivity?: number;
+  // 平均会话时长（秒）
+  avgSessionDuration: number;
+  avgMessagesPerSession: number;
+}
+
+// 会话概览
+export type TimelineKind = "user" | "assistant" | "tool";
+
+export interface TimelineEvent {
+  kind: TimelineKind;
+  index: number;
+  timestamp?: string;
+  // 助手回合或工具调用的耗时（毫秒）
+  durationMs?: number;
+  tool?: string;
+  isError: boolean;
+  preview: string;
+}
+
+export interface ToolUsage {
+  name: string;
+  count: number;
+  errors: number;
+  totalDurationMs: number;
+  avgDurationMs?: number;
+}
+
+export interface ConversationSummary {
+  userTurns: number;
+  assistantTurns: number;
+  toolCalls: number;
+  tools: ToolUsage[];
+  timeline: TimelineEvent[];
+  startedAt?: string;
+  endedAt?: string;
+  // 首条到末条消息的时长（秒）
+  wallClockSecs: number;
+  tokens: ProjectTokenTotals;
 }
 
 // 全局规则相关类型
@@ -148,3 +1576,149 @@ export interface CodexRuleFile {
   tags: string[];
   content: string;
 }
+
+// 已登记的应用配置目录（如 ~/.claude-work）
+export interface AppHome {
+  name: string;
+  appType: "claude" | "codex";
+  dir: string;
+}
+
+// 配置目录状态；首项为默认目录，name 为空字符串
+export interface AppHomeStatus {
+  name: string;
+  dir: string;
+  exists: boolean;
+  active: boolean;
+}
+
+// 环境配置（如 work / personal），引用 appHomes 中登记的目录名称，为空时使用默认目录
+export interface EnvProfile {
+  id: string;
+  name: string;
+  claudeHome?: string;
+  codexHome?: string;
+}
+
+// WSL 模式设置
+export interface WslSettings {
+  enabled: boolean;
+  distro: string;
+  user: string;
+}
+
+// WSL 发行版中某个用户的配置目录（不存在时为空）
+export interface WslHome {
+  distro: string;
+  user: string;
+  homeDir: string;
+  claudeDir?: string;
+  codexDir?: string;
+  geminiDir?: string;
+}
+
+// 远程主机连接配置（主机密钥需已登记在 ~/.ssh/known_hosts）
+export interface RemoteProfile {
+  id: string;
+  name: string;
+  host: string;
+  port?: number;
+  user: string;
+  // 私钥路径；为空时使用 ssh-agent
+  identityFile?: string;
+  // 远程配置目录，默认 ~/.claude、~/.codex
+  claudeDir?: string;
+  codexDir?: string;
+}
+
+// 远程主机上的对话记录
+export interface RemoteConversation {
+  id: string;
+  appType: "claude" | "codex";
+  filePath: string;
+  fileSize: number;
+  modifiedAt: number;
+  projectName?: string;
+}
+
+// 便携模式状态
+export interface PortableInfo {
+  enabled: boolean;
+  // 可执行文件旁的 portable.ini
+  markerPath: string;
+  // 当前生效的数据目录（未启用时为空）
+  dataDir?: string;
+}
+
+// 现有配置的认证方式
+export type AuthKind = "apiKey" | "oAuth" | "none";
+
+// 检测到的规则文件
+export interface DetectedRule {
+  name: string;
+  path: string;
+  size: number;
+}
+
+// 单个应用的现有配置（首次运行向导）
+export interface AppSetupReport {
+  appType: "claude" | "codex";
+  configDir: string;
+  installed: boolean;
+  auth: AuthKind;
+  // API Key 仅显示首尾几位
+  maskedKey?: string;
+  baseUrl?: string;
+  models: string[];
+  // 由 live 配置生成的候选供应商
+  provider?: ProviderCandidate;
+  rules: DetectedRule[];
+  mcpServers: string[];
+  // 仅 Claude
+  agents: string[];
+  commands: string[];
+}
+
+// 首次运行时检测到的现有配置
+export interface ExistingSetupReport {
+  firstRun: boolean;
+  apps: AppSetupReport[];
+  envCandidates: ProviderCandidate[];
+}
+
+// 一键导入的结果
+export interface SetupImportResult {
+  providers: { appType: string; id: string }[];
+  currentSet: string[];
+  mcpImported: number;
+  warnings: string[];
+}
+
+// 某个配置目录中的规则文件
+export interface HomeRuleFile {
+  appType: "claude" | "codex";
+  // 所属配置目录名称（默认目录时为空）
+  home?: string;
+  name: string;
+  path: string;
+  content: string;
+}
+
+// 后端错误码（见 src-tauri/src/error.rs）
+export type AppErrorCode =
+  | "fileNotFound"
+  | "permissionDenied"
+  | "io"
+  | "parse"
+  | "serialize"
+  | "lock"
+  | "invalidInput"
+  | "unknown";
+
+// 后端结构化错误
+export interface AppErrorPayload {
+  code: AppErrorCode;
+  message: string;
+  // 涉及的文件（文件类错误）
+  path?: string;
+}
@@ -105,3 +105,33 @@ export const translateMcpBackendError = (
 
   return "";
 };
+
+/**
+ * 获取后端结构化错误的错误码；字符串错误或未知对象返回 undefined
+ */
+export const getErrorCode = (error: unknown): string | undefined => {
+  if (!error || typeof error !== "object") return undefined;
+  const code = (error as Record<string, unknown>).code;
+  return typeof code === "string" ? code : undefined;
+};
+
+/**
+ * 按错误码给出本地化提示（文件缺失 / 权限不足 / 解析失败等），
+ * 未细分的错误回退到后端原始消息
+ */
+export const translateBackendError = (
+  error: unknown,
+  t: (key: string, opts?: any) => string,
+): string => {
+  const code = getErrorCode(error);
+  const detail = extractErrorMessage(error);
+  if (!code || code === "unknown" || code === "invalidInput") {
+    return detail;
+  }
+  const path = (error as Record<string, unknown>).path;
+  return t(`errors.${code}`, {
+    path: typeof path === "string" ? path : "",
+    detail,
+    defaultValue: detail,
+  });
+};
@@ -31,7 +31,15 @@ declare global {
       addProvider: (provider: Provider, app?: AppType) => Promise<boolean>;
       deleteProvider: (id: string, app?: AppType) => Promise<boolean>;
       updateProvider: (provider: Provider, app?: AppType) => Promise<boolean>;
-      switchProvider: (providerId: string, app?: AppType) => Promise<boolean>;
+      switchProvider: (
+        providerId: string,
+        app?: AppType,
+        force?: boolean,
+      ) => Promise<boolean>;
+      cycleProviderKey: (
+        providerId: string,
+        app?: AppType,
+      ) => Promise<import("./types").ActiveKey>;
       importCurrentConfigAsDefault: (app?: AppType) => Promise<ImportResult>;
       getClaudeCodeConfigPath: () => Promise<string>;
       getClaudeConfigStatus: () => Promise<ConfigStatus>;
@@ -56,11 +64,111 @@ declare global {
       onProviderSwitched: (
         callback: (data: { appType: string; providerId: string }) => void,
       ) => Promise<UnlistenFn>;
+      takePendingDeepLink: () => Promise<
+        import("./types").DeepLinkProvider | null
+      >;
+      parseProviderLink: (
+        link: string,
+      ) => Promise<import("./types").DeepLinkProvider>;
+      onDeepLinkProvider: (
+        callback: (draft: import("./types").DeepLinkProvider) => void,
+      ) => Promise<UnlistenFn>;
+      onDeepLinkError: (
+        callback: (error: string) => void,
+      ) => Promise<UnlistenFn>;
       getSettings: () => Promise<Settings>;
       saveSettings: (settings: Settings) => Promise<boolean>;
+      listAppHomes: (
+        appType: "claude" | "codex",
+      ) => Promise<import("./types").AppHomeStatus[]>;
+      selectAppHome: (
+        appType: "claude" | "codex",
+        name?: string,
+      ) => Promise<boolean>;
+      switchEnvProfile: (id?: string) => Promise<boolean>;
+      listWslDistros: () => Promise<string[]>;
+      discoverWslHomes: (
+        distro: string,
+      ) => Promise<import("./types").WslHome[]>;
+      probeCliVersions: () => Promise<import("./types").CliProbe[]>;
+      listNotifications: (
+        limit?: number,
+      ) => Promise<import("./types").NotificationRecord[]>;
+      clearNotifications: () => Promise<boolean>;
+      getAuditLog: (
+        filter?: import("./types").AuditFilter,
+      ) => Promise<import("./types").AuditEntry[]>;
+      getStartupCheck: () => Promise<import("./types").StartupReport>;
+      recoverConfigFile: (
+        target: import("./types").CheckTarget,
+        action: "restoreBackup" | "reset",
+      ) => Promise<import("./types").StartupReport>;
+      getRecentLogs: (
+        limit?: number,
+        minLevel?: import("./types").LogLevel,
+      ) => Promise<import("./types").LogEntry[]>;
+      openLogFolder: () => Promise<void>;
+      listClaudeCaches: () => Promise<import("./types").ClaudeCacheSummary[]>;
+      getClaudeCacheFiles: (
+        kind: import("./types").ClaudeCacheKind,
+      ) => Promise<import("./types").ClaudeCacheFile[]>;
+      purgeClaudeCache: (
+        kind: import("./types").ClaudeCacheKind,
+        keepDays?: number,
+      ) => Promise<import("./types").ClaudeCachePurge>;
+      listCodexCaches: () => Promise<import("./types").CodexCacheSummary[]>;
+      purgeCodexCache: (
+        target: import("./types").CodexCacheTarget,
+        action: import("./types").CodexCacheAction,
+        confirm: boolean,
+      ) => Promise<import("./types").CodexCachePurge>;
+      getClaudeAuthStatus: () => Promise<import("./types").ClaudeAuthStatus>;
+      logoutClaudeOauth: () => Promise<import("./types").ClaudeAuthStatus>;
+      testRemoteConnection: (profileId: string) => Promise<string>;
+      listRemoteConversations: (
+        profileId: string,
+        appType: "claude" | "codex",
+      ) => Promise<import("./types").RemoteConversation[]>;
+      readRemoteConversation: (
+        profileId: string,
+        filePath: string,
+      ) => Promise<string>;
+      readRemoteRules: (
+        profileId: string,
+        appType: "claude" | "codex",
+      ) => Promise<string>;
+      writeRemoteRules: (
+        profileId: string,
+        appType: "claude" | "codex",
+        content: string,
+      ) => Promise<boolean>;
+      switchRemoteProvider: (
+        profileId: string,
+        appType: "claude" | "codex",
+        providerId: string,
+      ) => Promise<boolean>;
       restartApp: () => Promise<boolean>;
       checkForUpdates: () => Promise<void>;
       isPortable: () => Promise<boolean>;
+      getPortableInfo: () => Promise<import("./types").PortableInfo>;
+      enablePortableMode: (
+        dataDir?: string,
+        copyExisting?: boolean,
+      ) => Promise<import("./types").PortableInfo>;
+      disablePortableMode: () => Promise<boolean>;
+      scanExternalProviders: () => Promise<
+        import("./types").ExternalToolSource[]
+      >;
+      parseExternalProviders: (
+        path: string,
+        tool?: import("./types").ExternalTool,
+      ) => Promise<import("./types").ProviderCandidate[]>;
+      detectExistingSetup: () => Promise<
+        import("./types").ExistingSetupReport
+      >;
+      importExistingSetup: (
+        includeEnv?: boolean,
+      ) => Promise<import("./types").SetupImportResult>;
       getAppConfigPath: () => Promise<string>;
       openAppConfigFolder: () => Promise<void>;
       // Claude 插件配置能力
@@ -75,6 +183,11 @@ declare global {
         providerId: string,
         app: AppType
       ) => Promise<import("./types").UsageResult>;
+      getProviderQuota: (
+        providerId: string,
+        app: AppType,
+        refresh?: boolean
+      ) => Promise<import("./types").ProviderQuota>;
       // Claude MCP
       getClaudeMcpStatus: () => Promise<McpStatus>;
       readClaudeMcpConfig: () => Promise<string | null>;
@@ -150,13 +263,101 @@ declare global {
       setAppConfigDirOverride: (path: string | null) => Promise<boolean>;
       // 对话记录管理
       listConversations: (appType?: string) => Promise<import("./types").ConversationMeta[]>;
-      searchConversations: (keyword: string, appType?: string) => Promise<import("./types").ConversationMeta[]>;
-      deleteConversation: (filePath: string) => Promise<void>;
+      searchConversations: (
+        appType: string | undefined,
+        keyword: string,
+        options?: import("./types").ConversationSearchOptions,
+      ) => Promise<import("./types").ConversationSearchHit[]>;
+      deleteConversation: (filePath: string, force?: boolean) => Promise<void>;
       readConversationContent: (filePath: string) => Promise<string>;
+      tailConversation: (filePath: string) => Promise<import("./types").ConversationTailStart>;
+      stopConversationTail: (filePath?: string) => Promise<void>;
+      onConversationTail: (
+        callback: (data: import("./types").ConversationTailEvent) => void,
+      ) => Promise<UnlistenFn>;
+      exportConversationMessages: (
+        filePath: string,
+        messages: Array<number | string>,
+        format?: import("./types").ConversationExportFormat,
+        destPath?: string,
+        includeNotes?: boolean,
+      ) => Promise<string>;
+      getConversationNotes: (
+        filePath: string,
+      ) => Promise<import("./types").ConversationNotes>;
+      setConversationNote: (
+        filePath: string,
+        note: string | null,
+        messageId?: string,
+      ) => Promise<import("./types").ConversationNotes>;
+      getConversationSummary: (
+        filePath: string,
+      ) => Promise<import("./types").GeneratedSummary | null>;
+      listConversationSummaries: () => Promise<
+        Record<string, import("./types").GeneratedSummary>
+      >;
+      generateConversationSummary: (
+        filePath: string,
+        appType?: AppType,
+        providerId?: string,
+      ) => Promise<import("./types").GeneratedSummary>;
+      generateMissingConversationSummaries: (options?: {
+        appType?: AppType;
+        providerId?: string;
+        filePaths?: string[];
+        limit?: number;
+      }) => Promise<import("./types").SummaryBatchResult>;
+      deleteConversationSummary: (filePath: string) => Promise<boolean>;
+      extractCodeBlocks: (
+        filePath: string,
+        language?: string,
+        destDir?: string,
+      ) => Promise<import("./types").CodeBlock[]>;
+      getSessionFileEdits: (
+        filePath: string,
+      ) => Promise<import("./types").SessionEdits>;
+      addMessageBookmark: (
+        filePath: string,
+        message: number | string,
+        label?: string,
+      ) => Promise<import("./types").MessageBookmark>;
+      removeMessageBookmark: (id: string) => Promise<boolean>;
+      listMessageBookmarks: (
+        filePath?: string,
+      ) => Promise<import("./types").MessageBookmark[]>;
+      generateShareBundle: (
+        filePaths: string[],
+        destDir: string,
+        title?: string,
+      ) => Promise<import("./types").ShareBundle>;
+      importWebExport: (
+        filePath: string,
+      ) => Promise<import("./types").WebImportResult>;
+      trimConversation: (
+        filePath: string,
+        spec: import("./types").TrimSpec,
+      ) => Promise<import("./types").DerivedConversation>;
+      mergeConversations: (
+        first: string,
+        second: string,
+        strategy?: import("./types").MergeStrategy,
+      ) => Promise<import("./types").DerivedConversation>;
+      verifyConversation: (
+        filePath: string,
+        repair?: boolean,
+      ) => Promise<import("./types").ConversationIntegrityReport>;
+      getProjectStats: (
+        project: string,
+        appType?: AppType,
+      ) => Promise<import("./types").ProjectStats>;
+      summarizeConversation: (
+        filePath: string,
+      ) => Promise<import("./types").ConversationSummary>;
       // 全局规则管理
       readClaudeRules: () => Promise<string>;
       writeClaudeRules: (content: string) => Promise<void>;
       listCodexRules: () => Promise<import("./types").CodexRuleFile[]>;
+      listRulesAcrossHomes: () => Promise<import("./types").HomeRuleFile[]>;
       readCodexRule: (filename: string) => Promise<string>;
       writeCodexRule: (filename: string, content: string, tags: string[]) => Promise<void>;
       deleteCodexRule: (filename: string) => Promise<void>;